@@ -18,8 +18,8 @@ pub use crate::{
     },
     nonexhaustive_enum::{
         assert_correct_default_storage, assert_correct_storage, AssertCsArgs, EnumInfo,
-        GetEnumInfo, GetVTable as NonExhaustiveMarkerVTable, NonExhaustive, NonExhaustiveMarker,
-        ValidDiscriminant,
+        GetEnumInfo, GetVTable as NonExhaustiveMarkerVTable, HeapFallback, NonExhaustive,
+        NonExhaustiveMarker, ValidDiscriminant,
     },
     pointer_trait::{AsMutPtr, AsPtr, GetPointerKind, PK_Reference},
     prefix_type::{
@@ -52,6 +52,7 @@ pub use std::{
     option::Option,
     primitive::{str, u8, usize},
     ptr::NonNull,
+    result::Result,
     vec,
 };
 
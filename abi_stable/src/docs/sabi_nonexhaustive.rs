@@ -75,6 +75,21 @@ Recommendation:
 Use a type that has a stable layout,generally a concrete type.
 It is a bad idea to use `Enum` since its size is allowed to change.<br>
 
+### Heap fallback (optional parameter)
+
+Makes `NonExhaustive<>` store the enum in the heap if it doesn't fit in Enum_Storage,
+using [`HeapFallback<Enum_Storage>`](crate::nonexhaustive_enum::HeapFallback)
+as the storage of `Enum_NE`.
+
+This allows adding variants larger than the storage in minor versions.
+Enum_Storage must be at least the size and alignment of an `RBox`.
+
+The generated variant constructors use `NonExhaustive::new_or_boxed`,
+and the enum is not implicitly asserted to fit in the storage.
+
+Syntax:`heap_fallback`<br>
+Example:`size = [usize;4], heap_fallback`<br>
+
 ### Traits (optional parameter)
 
 Specifies the traits required when constructing NonExhaustive from this enum and
@@ -149,7 +164,8 @@ The `*Interface` types from the examples come from the
 
 This generates a static assertion that the listed types can be stored within `NonExhaustive`.
 
-Note that this attribute is implicitly added for non-generic enums,
+Note that this attribute is implicitly added for non-generic enums
+that don't use the `heap_fallback` parameter,
 it is only required for generic enums.
 
 Syntax:`assert_nonexhaustive = type`<br>
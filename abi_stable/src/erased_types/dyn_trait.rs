@@ -6,6 +6,7 @@ use std::{
     mem::ManuallyDrop,
     ptr,
     rc::Rc,
+    task::{Context, Poll},
 };
 
 use serde::{de, ser, Deserialize, Deserializer};
@@ -19,7 +20,7 @@ use crate::{
     },
     prefix_type::PrefixRef,
     sabi_types::{MovePtr, RMut, RRef},
-    std_types::{RBox, RIoError, RStr, RVec},
+    std_types::{RBox, RIoError, RStr, RVec, RWaker},
     type_level::{
         downcasting::{TD_CanDowncast, TD_Opaque},
         impl_enum::{Implemented, Unimplemented},
@@ -33,7 +34,7 @@ use crate::std_types::Tuple2;
 use super::{
     c_functions::adapt_std_fmt,
     trait_objects::*,
-    traits::{DeserializeDyn, GetSerializeProxyType},
+    traits::{DeserializeDyn, GetSerializeProxyType, StreamItemOrDefault},
     type_info::TypeInfoFor,
     vtable::{MakeVTable, VTable_Ref},
     IteratorItemOrDefault, *,
@@ -137,6 +138,12 @@ mod priv_ {
     /// - [`serde::Serialize`]:
     ///     first calls the objects' Deserialize impl, then serializes that as a string.
     ///
+    /// - `futures_core::Stream`:
+    ///     requires the "futures" feature to construct the `DynTrait`,
+    ///     and the erased type to be `Unpin`.
+    ///     The stream can be polled without the feature with
+    ///     [`poll_next_unpin`](#method.poll_next_unpin).
+    ///
     /// ###  Deconstruction
     ///
     /// `DynTrait<_>` can then be unwrapped into a concrete type,
@@ -1591,6 +1598,19 @@ mod priv_ {
         }
     }
 
+    impl<'lt, I, EV: Clone> DynTrait<'lt, crate::std_types::RRc<()>, I, EV> {
+        /// Does a shallow clone of the object, just incrementing the reference counter
+        pub fn shallow_clone(&self) -> Self {
+            Self {
+                object: self.object.clone(),
+                vtable: self.vtable,
+                extra_value: self.extra_value.clone(),
+                _marker: self._marker,
+                _marker2: self._marker2,
+            }
+        }
+    }
+
     impl<'borr, P, I, EV> Drop for DynTrait<'borr, P, I, EV>
     where
         P: GetPointerKind,
@@ -2052,6 +2072,153 @@ where
 
 //////////////////////////////////////////////////////////////////
 
+impl<'borr, P, I, Item, EV> DynTrait<'borr, P, I, EV>
+where
+    P: AsMutPtr,
+    I: StreamItemOrDefault<'borr, Item = Item>,
+    I: InterfaceType<Stream = Implemented<trait_marker::Stream>>,
+    Item: 'borr,
+{
+    /// Polls the next element of the `self` Stream.
+    ///
+    /// This is usable regardless of whether the "futures" feature is enabled,
+    /// and because the erased stream is `Unpin`, it does not require pinning `self`.
+    ///
+    /// # Example
+    ///
+    #[cfg_attr(feature = "futures", doc = "```rust")]
+    #[cfg_attr(not(feature = "futures"), doc = "```ignore")]
+    /// use abi_stable::{erased_types::interfaces::StreamInterface, DynTrait};
+    ///
+    /// use std::{
+    ///     pin::Pin,
+    ///     task::{Context, Poll},
+    /// };
+    ///
+    /// // A stream that yields 0 to 2 (inclusive),
+    /// // returning `Pending` the first time it's polled for each element.
+    /// struct Countdown {
+    ///     next: u32,
+    ///     was_pending: bool,
+    /// }
+    ///
+    /// impl futures_core::Stream for Countdown {
+    ///     type Item = u32;
+    ///
+    ///     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<u32>> {
+    ///         if self.next == 3 {
+    ///             Poll::Ready(None)
+    ///         } else if !self.was_pending {
+    ///             self.was_pending = true;
+    ///             cx.waker().wake_by_ref();
+    ///             Poll::Pending
+    ///         } else {
+    ///             self.was_pending = false;
+    ///             self.next += 1;
+    ///             Poll::Ready(Some(self.next - 1))
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let mut wrapped = DynTrait::from_value(Countdown { next: 0, was_pending: false })
+    ///     .interface(StreamInterface::NEW);
+    ///
+    /// # use std::{sync::Arc, task::{Wake, Waker}};
+    /// # struct Noop;
+    /// # impl Wake for Noop { fn wake(self: Arc<Self>) {} }
+    /// # let waker: Waker = Arc::new(Noop).into();
+    /// let mut cx = Context::from_waker(&waker);
+    ///
+    /// let mut list = Vec::new();
+    /// loop {
+    ///     match wrapped.poll_next_unpin(&mut cx) {
+    ///         Poll::Ready(Some(x)) => list.push(x),
+    ///         Poll::Ready(None) => break,
+    ///         Poll::Pending => {}
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(list, [0, 1, 2]);
+    ///
+    /// ```
+    pub fn poll_next_unpin(&mut self, cx: &mut Context<'_>) -> Poll<Option<Item>> {
+        unsafe {
+            let vtable = self.sabi_vtable();
+            let erased = self.sabi_erased_mut();
+            RWaker::with_waker_ref(cx.waker(), |waker| (vtable.stream().poll_next)(erased, waker))
+                .map(|x| x.into_rust())
+                .into_rust()
+        }
+    }
+
+    /// Returns the bounds on the remaining length of the `self` Stream.
+    ///
+    /// This is equivalent to `futures_core::Stream::size_hint`,
+    /// usable regardless of whether the "futures" feature is enabled.
+    ///
+    /// # Example
+    ///
+    #[cfg_attr(feature = "futures", doc = "```rust")]
+    #[cfg_attr(not(feature = "futures"), doc = "```ignore")]
+    /// use abi_stable::{erased_types::interfaces::StreamInterface, DynTrait};
+    ///
+    /// use std::{
+    ///     pin::Pin,
+    ///     task::{Context, Poll},
+    /// };
+    ///
+    /// struct Empty;
+    ///
+    /// impl futures_core::Stream for Empty {
+    ///     type Item = ();
+    ///
+    ///     fn poll_next(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Option<()>> {
+    ///         Poll::Ready(None)
+    ///     }
+    ///
+    ///     fn size_hint(&self) -> (usize, Option<usize>) {
+    ///         (0, Some(0))
+    ///     }
+    /// }
+    ///
+    /// let wrapped = DynTrait::from_value(Empty).interface(StreamInterface::NEW);
+    ///
+    /// assert_eq!(wrapped.stream_size_hint(), (0, Some(0)));
+    ///
+    /// ```
+    pub fn stream_size_hint(&self) -> (usize, Option<usize>) {
+        unsafe {
+            let vtable = self.sabi_vtable();
+            let tuple = (vtable.stream().size_hint)(self.sabi_erased_ref()).into_rust();
+            (tuple.0, tuple.1.into_rust())
+        }
+    }
+}
+
+#[cfg(feature = "futures")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "futures")))]
+impl<'borr, P, I, Item, EV> futures_core::Stream for DynTrait<'borr, P, I, EV>
+where
+    P: AsMutPtr,
+    I: StreamItemOrDefault<'borr, Item = Item>,
+    I: InterfaceType<Stream = Implemented<trait_marker::Stream>>,
+    Item: 'borr,
+{
+    type Item = Item;
+
+    fn poll_next(self: std::pin::Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Item>> {
+        // The erased stream is required to be `Unpin`,
+        // and `DynTrait` is not moved out of the `Pin` here.
+        unsafe { self.get_unchecked_mut().poll_next_unpin(cx) }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.stream_size_hint()
+    }
+}
+
+//////////////////////////////////////////////////////////////////
+
 impl<'borr, P, I, EV> fmtWrite for DynTrait<'borr, P, I, EV>
 where
     P: AsMutPtr,
@@ -4,6 +4,8 @@ macro_rules! declare_enabled_traits {
             $(($auto_trait:ident, $auto_trait_query:ident, $auto_trait_path:path)),* $(,)*
         ]
 
+        regular_traits_bit_order[ $($bit_order_trait:ident),* $(,)* ]
+
         regular_traits[
             $(($regular_trait:ident, $regular_trait_query:ident, $regular_trait_path:path)),* $(,)*
         ]
@@ -32,7 +34,7 @@ macro_rules! declare_enabled_traits {
         mod regular_trait_mask{
             #[repr(u32)]
             enum __Index {
-                $($regular_trait,)*
+                $($bit_order_trait,)*
             }
             $(pub(super) const $regular_trait: u64 = 1u64 << __Index::$regular_trait as u32;)*
         }
@@ -121,3 +121,20 @@ impl<T> DEIteratorInterface<T> {
 impl<'a, T: 'a> IteratorItem<'a> for DEIteratorInterface<T> {
     type Item = T;
 }
+
+//////////////////////////////////////////////
+
+/// Implements `InterfaceType`, requiring `Send + Unpin + futures_core::Stream<Item = T>`
+#[repr(C)]
+#[derive(StableAbi)]
+#[sabi(impl_InterfaceType(Send, Unpin, Stream))]
+pub struct StreamInterface<T>(PhantomData<T>);
+
+impl<T> StreamInterface<T> {
+    ///
+    pub const NEW: Self = Self(PhantomData);
+}
+
+impl<'a, T: 'a> StreamItem<'a> for StreamInterface<T> {
+    type Item = T;
+}
@@ -0,0 +1,95 @@
+use std::pin::Pin;
+
+#[cfg(not(feature = "futures"))]
+use std::task::{Context, Poll};
+
+use crate::{
+    marker_type::{ErasedObject, NonOwningPhantom},
+    sabi_types::{RMut, RRef},
+    std_types::{ROption, RPoll, RWaker, Tuple2},
+    traits::IntoReprC,
+    utils::Transmuter,
+};
+
+#[cfg(feature = "futures")]
+pub use futures_core::Stream;
+
+/// Stand-in for `futures_core::Stream` when the "futures" feature is disabled.
+///
+/// This trait can't be implemented outside of `abi_stable`,
+/// so `DynTrait`s that require `Stream` can't be constructed without the feature,
+/// while keeping the layout of the vtable the same.
+#[cfg(not(feature = "futures"))]
+pub trait Stream {
+    type Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>>;
+
+    fn size_hint(&self) -> (usize, Option<usize>);
+}
+
+///////////////////////////////////////////////////////////////////////////////////
+
+#[repr(C)]
+#[derive(StableAbi)]
+pub struct StreamFns<Item> {
+    pub(super) poll_next:
+        unsafe extern "C" fn(RMut<'_, ErasedObject>, &RWaker) -> RPoll<ROption<Item>>,
+    pub(super) size_hint:
+        unsafe extern "C" fn(RRef<'_, ErasedObject>) -> Tuple2<usize, ROption<usize>>,
+}
+
+impl<Item> Copy for StreamFns<Item> {}
+impl<Item> Clone for StreamFns<Item> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////
+
+pub struct MakeStreamFns<S>(NonOwningPhantom<S>);
+
+impl<S> MakeStreamFns<S>
+where
+    S: Stream + Unpin,
+{
+    const FNS: StreamFns<S::Item> = StreamFns {
+        poll_next: poll_next::<S>,
+        size_hint: size_hint::<S>,
+    };
+
+    pub(super) const NEW: StreamFns<()> = unsafe { Transmuter { from: Self::FNS }.to };
+}
+
+///////////////////////////////////////////////////////////////////////////////////
+
+pub(super) unsafe extern "C" fn poll_next<S>(
+    this: RMut<'_, ErasedObject>,
+    waker: &RWaker,
+) -> RPoll<ROption<S::Item>>
+where
+    S: Stream + Unpin,
+{
+    extern_fn_panic_handling! {no_early_return;
+        let this = unsafe { this.transmute_into_mut::<S>() };
+        waker
+            .with_context(|cx| Pin::new(this).poll_next(cx))
+            .map(IntoReprC::into_c)
+            .into_c()
+    }
+}
+
+pub(super) unsafe extern "C" fn size_hint<S>(
+    this: RRef<'_, ErasedObject>,
+) -> Tuple2<usize, ROption<usize>>
+where
+    S: Stream + Unpin,
+{
+    extern_fn_panic_handling! {no_early_return;
+        let this = unsafe { this.transmute_into_ref::<S>() };
+        let (l,r)=this.size_hint();
+
+        Tuple2(l,r.into_c())
+    }
+}
@@ -121,6 +121,8 @@ declare_InterfaceType! {
     ///     // type IoBufRead = Unimplemented<trait_marker::IoBufRead>;
     ///
     ///     // type Error = Unimplemented<trait_marker::Error>;
+    ///
+    ///     // type Stream = Unimplemented<trait_marker::Stream>;
     /// }
     /// */
     ///
@@ -198,6 +200,9 @@ declare_InterfaceType! {
 
         /// For the `std::error::Error` trait
         type Error;
+
+        /// For the `futures_core::Stream` trait
+        type Stream;
     ]
 
 
@@ -426,6 +431,47 @@ impl<'borr, I> IteratorItemOrDefaultHelper<'borr, Unimplemented<trait_marker::It
     type Item = ();
 }
 
+/// The way to specify the expected `Stream::Item` type for an `InterfaceType`.
+///
+/// This is a separate trait to allow streams that yield borrowed elements.
+pub trait StreamItem<'a>: InterfaceType {
+    /// The stream item type.
+    type Item;
+}
+
+/// Gets the expected `Stream::Item` type for an `InterfaceType`,
+/// defaulting to `()` if it doesn't require `Stream` to be implemented.
+///
+/// Used by `DynTrait`'s vtable to give its stream methods a defaulted return type.
+pub trait StreamItemOrDefault<'borr>: InterfaceType {
+    /// The stream item type.
+    type Item;
+}
+
+impl<'borr, I, Item> StreamItemOrDefault<'borr> for I
+where
+    I: InterfaceType,
+    I: StreamItemOrDefaultHelper<'borr, <I as InterfaceType>::Stream, Item = Item>,
+{
+    type Item = Item;
+}
+
+#[doc(hidden)]
+pub trait StreamItemOrDefaultHelper<'borr, ImplIsRequired> {
+    type Item;
+}
+
+impl<'borr, I, Item> StreamItemOrDefaultHelper<'borr, Implemented<trait_marker::Stream>> for I
+where
+    I: StreamItem<'borr, Item = Item>,
+{
+    type Item = Item;
+}
+
+impl<'borr, I> StreamItemOrDefaultHelper<'borr, Unimplemented<trait_marker::Stream>> for I {
+    type Item = ();
+}
+
 /////////////////////////////////////////////////////////////////////
 
 crate::impl_InterfaceType! {
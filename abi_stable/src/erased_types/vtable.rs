@@ -10,7 +10,8 @@ use std::{
 use super::{
     c_functions::*,
     iterator::{DoubleEndedIteratorFns, IteratorFns, MakeDoubleEndedIteratorFns, MakeIteratorFns},
-    traits::{GetSerializeProxyType, IteratorItemOrDefault, SerializeType},
+    stream::{MakeStreamFns, Stream, StreamFns},
+    traits::{GetSerializeProxyType, IteratorItemOrDefault, SerializeType, StreamItemOrDefault},
     type_info::TypeInfoFor,
     *,
 };
@@ -70,6 +71,8 @@ macro_rules! declare_meta_vtable {
             ])*
         ]
 
+        regular_traits_bit_order[ $($bit_order_trait:ident),* $(,)* ]
+
         $([
             $( #[$field_attr:meta] )*
             $field:ident : $field_ty:ty ;
@@ -103,6 +106,8 @@ macro_rules! declare_meta_vtable {
             prefix_bound(I: InterfaceType),
             bound(I: IteratorItemOrDefault<'borr>),
             bound(<I as IteratorItemOrDefault<'borr>>::Item: StableAbi),
+            bound(I: StreamItemOrDefault<'borr>),
+            bound(<I as StreamItemOrDefault<'borr>>::Item: StableAbi),
             bound(I: GetSerializeProxyType<'borr>),
             bound(<I as GetSerializeProxyType<'borr>>::ProxyType: StableAbi),
             $($(bound=$struct_bound,)*)*
@@ -167,6 +172,21 @@ macro_rules! declare_meta_vtable {
                 }
             }
 
+            pub fn stream(
+                &self
+            )->StreamFns< <I as StreamItemOrDefault<'borr>>::Item >
+            where
+                $interf:InterfaceType<Stream=Implemented<trait_marker::Stream>>,
+                $interf:StreamItemOrDefault<'borr>,
+            {
+                unsafe{
+                    std::mem::transmute::<
+                        StreamFns< () >,
+                        StreamFns< <I as StreamItemOrDefault<'borr>>::Item >
+                    >( self.erased_stream() )
+                }
+            }
+
             pub fn serialize<'s>(&self)->UnerasedSerializeFn<'s,I>
             where
                 I:InterfaceType<Serialize=Implemented<trait_marker::Serialize>>,
@@ -383,6 +403,8 @@ macro_rules! declare_meta_vtable {
                 $(($auto_trait, $auto_trait_query, $auto_trait_path),)*
             ]
 
+            regular_traits_bit_order[ $($bit_order_trait,)* ]
+
             regular_traits[
                 $(($marker_trait, $marker_trait_query, $marker_trait_path),)*
                 $(($selector, $trait_query, $trait_path),)*
@@ -421,6 +443,31 @@ declare_meta_vtable! {
         ]
     ]
 
+    // The position of each trait in this list determines its bit in `RequiredTraits`,
+    // which must stay the same across minor versions,
+    // so newly supported traits must be added at the end.
+    regular_traits_bit_order[
+        Error,
+        Clone,
+        Default,
+        Display,
+        Debug,
+        Serialize,
+        PartialEq,
+        Ord,
+        PartialOrd,
+        Hash,
+        Iterator,
+        DoubleEndedIterator,
+        FmtWrite,
+        IoWrite,
+        IoRead,
+        IoBufRead,
+        IoSeek,
+        Deserialize,
+        Stream,
+    ]
+
     [
         #[sabi(accessible_if= <I as MakeRequiredTraits>::MAKE.contains_clone())]
         clone_ptr:    unsafe extern "C" fn(RRef<'_, ErasedPtr>)->ErasedPtr;
@@ -679,6 +726,26 @@ declare_meta_vtable! {
             io_Seek_seek::<T>
         }
     ]
+    [
+        #[sabi(
+            unsafe_change_type=
+            ROption<StreamFns< <I as StreamItemOrDefault<'borr>>::Item >>
+        )]
+        #[sabi(accessible_if= <I as MakeRequiredTraits>::MAKE.contains_stream())]
+        erased_stream:StreamFns< () >;
+        priv _stream;
+        option=ROption,RSome,RNone;
+        field_index=field_index_for__stream;
+        query_fn = contains_stream;
+
+        impl[] VtableFieldValue<Stream(futures_core::Stream)>
+        where [
+            T:Stream + Unpin,
+            I:StreamItemOrDefault<'borr,Item=<T as Stream>::Item>,
+        ]{
+            MakeStreamFns::<T>::NEW
+        }
+    ]
 }
 
 //////////////
@@ -3,17 +3,37 @@
 //! The modules here are named after the crates whose types are being wrapped.
 //!
 
+#[cfg(feature = "crossbeam-channel")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "channels")))]
+pub mod async_channel;
+
 #[cfg(feature = "crossbeam-channel")]
 #[cfg_attr(feature = "docsrs", doc(cfg(feature = "channels")))]
 pub mod crossbeam_channel;
 
+#[cfg(feature = "bincode")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "bincode")))]
+pub mod bincode;
+
+#[cfg(feature = "log")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "log")))]
+pub mod log;
+
 pub mod parking_lot;
 
 #[cfg(feature = "serde_json")]
 #[cfg_attr(feature = "docsrs", doc(cfg(feature = "serde_json")))]
 pub mod serde_json;
 
-pub use self::parking_lot::{RMutex, ROnce, RRwLock};
+#[cfg(feature = "tokio")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "tokio")))]
+pub mod tokio;
+
+#[cfg(feature = "tracing-core")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "tracing")))]
+pub mod tracing;
+
+pub use self::parking_lot::{RBarrier, RCondvar, RMutex, ROnce, RReentrantMutex, RRwLock};
 
 #[cfg(feature = "serde_json")]
 pub use self::serde_json::{RawValueBox, RawValueRef};
@@ -0,0 +1,838 @@
+//! An ffi-safe multi-producer multi-consumer channel,
+//! which can be used both from async code and from blocking code.
+//!
+//! Every operation has a blocking variant (eg: [`RAsyncReceiver::recv`])
+//! and an async variant (eg: [`RAsyncReceiver::recv_async`]),
+//! which can be freely mixed on the same channel,
+//! so that an async host can communicate with a synchronous dynamic library
+//! (and vice versa) without dedicating a thread to each channel.
+//!
+//! The futures returned by the async methods are ffi-safe,
+//! and can be polled by any executor.
+//!
+//! # Example
+//!
+//! ```rust
+//! use abi_stable::external_types::async_channel;
+//!
+//! let (tx, rx) = async_channel::bounded::<u32>(1);
+//!
+//! // This thread could be inside a dynamic library that doesn't use async.
+//! let handle = std::thread::spawn(move || {
+//!     for i in 0..4 {
+//!         tx.send(i * 2).unwrap();
+//!     }
+//! });
+//!
+//! # fn block_on<F: std::future::Future>(fut: F) -> F::Output {
+//! #     use std::{sync::Arc, task::{Context, Poll, Wake}, thread::{self, Thread}};
+//! #     struct Unpark(Thread);
+//! #     impl Wake for Unpark { fn wake(self: Arc<Self>) { self.0.unpark() } }
+//! #     let waker = Arc::new(Unpark(thread::current())).into();
+//! #     let mut fut = Box::pin(fut);
+//! #     loop {
+//! #         match fut.as_mut().poll(&mut Context::from_waker(&waker)) {
+//! #             Poll::Ready(x) => return x,
+//! #             Poll::Pending => thread::park(),
+//! #         }
+//! #     }
+//! # }
+//! // `block_on` stands in for the executor of the host.
+//! let list = block_on(async move {
+//!     let mut list = Vec::new();
+//!     while let Ok(x) = rx.recv_async().await {
+//!         list.push(x);
+//!     }
+//!     list
+//! });
+//!
+//! assert_eq!(list, [0, 2, 4, 6]);
+//! handle.join().unwrap();
+//!
+//! ```
+//!
+
+use std::{
+    fmt::{self, Debug},
+    future::Future,
+    marker::PhantomData,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use crate::{
+    marker_type::UnsafeIgnoredType,
+    pointer_trait::AsPtr,
+    prefix_type::WithMetadata,
+    sabi_types::RRef,
+    std_types::{RBox, RDuration, ROption, RPoll, RResult, RWaker},
+    traits::{ErasedType, IntoReprRust},
+};
+
+mod channel;
+
+#[cfg(all(test, not(feature = "only_new_tests")))]
+mod tests;
+
+pub use super::crossbeam_channel::{
+    RRecvError, RRecvTimeoutError, RSendError, RSendTimeoutError, RTryRecvError, RTrySendError,
+};
+
+use self::channel::Handle;
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// Creates a channel which can hold up to `capacity` elements in its internal queue.
+///
+/// If `capacity == 0`,this creates a zero-capacity channel,
+/// where a send only completes once a receiver is waiting for the value.
+///
+/// # Example
+///
+/// ```rust
+/// use abi_stable::external_types::async_channel::{self, RTrySendError};
+///
+/// let (tx, rx) = async_channel::bounded::<u32>(2);
+///
+/// tx.send(3).unwrap();
+/// tx.send(5).unwrap();
+/// assert_eq!(tx.try_send(8), Err(RTrySendError::Full(8)));
+///
+/// assert_eq!(rx.recv(), Ok(3));
+/// assert_eq!(rx.recv(), Ok(5));
+///
+/// ```
+pub fn bounded<T>(capacity: usize) -> (RAsyncSender<T>, RAsyncReceiver<T>) {
+    new_channel(Some(capacity))
+}
+
+/// Creates a channel which can hold an unbounded amount of elements in its internal queue.
+///
+/// # Example
+///
+/// ```rust
+/// use abi_stable::external_types::async_channel;
+///
+/// let (tx, rx) = async_channel::unbounded::<&'static str>();
+///
+/// for _ in 0..100 {
+///     tx.send("hello").unwrap();
+/// }
+/// assert_eq!(rx.len(), 100);
+///
+/// ```
+pub fn unbounded<T>() -> (RAsyncSender<T>, RAsyncReceiver<T>) {
+    new_channel(None)
+}
+
+fn new_channel<T>(capacity: Option<usize>) -> (RAsyncSender<T>, RAsyncReceiver<T>) {
+    let (sender, receiver) = Handle::new_pair(capacity);
+    let vtable = MakeVTable::<T>::VTABLE;
+    (
+        RAsyncSender {
+            handle: sender,
+            vtable,
+        },
+        RAsyncReceiver {
+            handle: receiver,
+            vtable,
+        },
+    )
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// The sender end of an async channel,
+/// created by the [`bounded`] and [`unbounded`] functions.
+///
+/// # Example
+///
+/// ```rust
+/// use abi_stable::external_types::async_channel;
+///
+/// # fn block_on<F: std::future::Future>(fut: F) -> F::Output {
+/// #     use std::{sync::Arc, task::{Context, Poll, Wake}, thread::{self, Thread}};
+/// #     struct Unpark(Thread);
+/// #     impl Wake for Unpark { fn wake(self: Arc<Self>) { self.0.unpark() } }
+/// #     let waker = Arc::new(Unpark(thread::current())).into();
+/// #     let mut fut = Box::pin(fut);
+/// #     loop {
+/// #         match fut.as_mut().poll(&mut Context::from_waker(&waker)) {
+/// #             Poll::Ready(x) => return x,
+/// #             Poll::Pending => thread::park(),
+/// #         }
+/// #     }
+/// # }
+/// let (tx, rx) = async_channel::bounded::<u32>(1);
+///
+/// let handle = std::thread::spawn(move || (rx.recv(), rx.recv()));
+///
+/// block_on(async {
+///     tx.send_async(13).await.unwrap();
+///     tx.send_async(21).await.unwrap();
+/// });
+///
+/// assert_eq!(handle.join().unwrap(), (Ok(13), Ok(21)));
+///
+/// ```
+#[repr(C)]
+#[derive(StableAbi)]
+pub struct RAsyncSender<T> {
+    handle: RBox<ErasedHandle<T>>,
+    vtable: VTable_Ref<T>,
+}
+
+impl<T> RAsyncSender<T> {
+    /// Blocks until `value` is either sent,or the the other end is disconnected.
+    ///
+    /// If the channel is disconnected,this will return an error with `value`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use abi_stable::external_types::async_channel::{self, RSendError};
+    ///
+    /// let (tx, rx) = async_channel::unbounded::<u32>();
+    ///
+    /// tx.send(1057).unwrap();
+    ///
+    /// drop(rx);
+    /// assert_eq!(tx.send(0), Err(RSendError(0)));
+    ///
+    /// ```
+    pub fn send(&self, value: T) -> Result<(), RSendError<T>> {
+        self.vtable.send()(self.handle.as_rref(), value).into_rust()
+    }
+
+    /// Immediately sends `value`,
+    /// returning an error if the channel is full or disconnected.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use abi_stable::external_types::async_channel::{self, RTrySendError};
+    ///
+    /// let (tx, rx) = async_channel::bounded::<u32>(1);
+    ///
+    /// tx.try_send(1).unwrap();
+    /// assert_eq!(tx.try_send(2), Err(RTrySendError::Full(2)));
+    ///
+    /// drop(rx);
+    /// assert_eq!(tx.try_send(3), Err(RTrySendError::Disconnected(3)));
+    ///
+    /// ```
+    pub fn try_send(&self, value: T) -> Result<(), RTrySendError<T>> {
+        self.vtable.try_send()(self.handle.as_rref(), value).into_rust()
+    }
+
+    /// Blocks until a timeout to send `value`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use abi_stable::external_types::async_channel::{self, RSendTimeoutError};
+    ///
+    /// use std::time::Duration;
+    ///
+    /// let (tx, rx) = async_channel::bounded::<u32>(1);
+    ///
+    /// tx.send(1).unwrap();
+    ///
+    /// let res = tx.send_timeout(2, Duration::from_millis(1));
+    /// assert_eq!(res, Err(RSendTimeoutError::Timeout(2)));
+    ///
+    /// ```
+    pub fn send_timeout(&self, value: T, timeout: Duration) -> Result<(), RSendTimeoutError<T>> {
+        self.vtable.send_timeout()(self.handle.as_rref(), value, timeout.into()).into_rust()
+    }
+
+    /// Returns a future that sends `value` once the channel has space for it.
+    ///
+    /// If the future is dropped before completing, `value` is dropped without being sent.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use abi_stable::external_types::async_channel::{self, RSendError};
+    ///
+    /// # fn block_on<F: std::future::Future>(fut: F) -> F::Output {
+    /// #     use std::{sync::Arc, task::{Context, Poll, Wake}, thread::{self, Thread}};
+    /// #     struct Unpark(Thread);
+    /// #     impl Wake for Unpark { fn wake(self: Arc<Self>) { self.0.unpark() } }
+    /// #     let waker = Arc::new(Unpark(thread::current())).into();
+    /// #     let mut fut = Box::pin(fut);
+    /// #     loop {
+    /// #         match fut.as_mut().poll(&mut Context::from_waker(&waker)) {
+    /// #             Poll::Ready(x) => return x,
+    /// #             Poll::Pending => thread::park(),
+    /// #         }
+    /// #     }
+    /// # }
+    /// let (tx, rx) = async_channel::bounded::<u32>(4);
+    ///
+    /// block_on(async {
+    ///     tx.send_async(3).await.unwrap();
+    ///     drop(rx);
+    ///     assert_eq!(tx.send_async(5).await, Err(RSendError(5)));
+    /// });
+    ///
+    /// ```
+    pub fn send_async(&self, value: T) -> RSendFuture<'_, T> {
+        RSendFuture {
+            sender: self,
+            value: ROption::RSome(value),
+            key: 0,
+        }
+    }
+
+    /// Returns true if there are no values in the channel queue.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use abi_stable::external_types::async_channel;
+    ///
+    /// let (tx, rx) = async_channel::unbounded::<()>();
+    ///
+    /// assert!(tx.is_empty());
+    /// tx.send(()).unwrap();
+    /// assert!(!tx.is_empty());
+    ///
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.vtable.is_empty()(self.handle.as_rref())
+    }
+
+    /// Returns true if the channel queue is full.
+    ///
+    /// This always returns false for unbounded channels.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use abi_stable::external_types::async_channel;
+    ///
+    /// let (tx, rx) = async_channel::bounded::<()>(1);
+    ///
+    /// assert!(!tx.is_full());
+    /// tx.send(()).unwrap();
+    /// assert!(tx.is_full());
+    ///
+    /// ```
+    pub fn is_full(&self) -> bool {
+        self.vtable.is_full()(self.handle.as_rref())
+    }
+
+    /// Returns the amount of values in the channel queue.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use abi_stable::external_types::async_channel;
+    ///
+    /// let (tx, rx) = async_channel::bounded::<()>(2);
+    ///
+    /// tx.send(()).unwrap();
+    /// assert_eq!(tx.len(), 1);
+    ///
+    /// ```
+    pub fn len(&self) -> usize {
+        self.vtable.len()(self.handle.as_rref())
+    }
+
+    /// Returns the amount of values the channel queue can hold.
+    ///
+    /// This returns None if the channel is unbounded.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use abi_stable::external_types::async_channel;
+    ///
+    /// assert_eq!(async_channel::bounded::<()>(2).0.capacity(), Some(2));
+    /// assert_eq!(async_channel::unbounded::<()>().0.capacity(), None);
+    ///
+    /// ```
+    pub fn capacity(&self) -> Option<usize> {
+        self.vtable.capacity()(self.handle.as_rref()).into_rust()
+    }
+
+    /// Returns true if all the receivers of the channel were dropped.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use abi_stable::external_types::async_channel;
+    ///
+    /// let (tx, rx) = async_channel::unbounded::<()>();
+    ///
+    /// assert!(!tx.is_disconnected());
+    /// drop(rx);
+    /// assert!(tx.is_disconnected());
+    ///
+    /// ```
+    pub fn is_disconnected(&self) -> bool {
+        self.vtable.is_disconnected()(self.handle.as_rref())
+    }
+}
+
+impl<T> Clone for RAsyncSender<T> {
+    /// Clones this channel end,getting another handle into the channel.
+    ///
+    /// Note that this allocates an RBox<_>.
+    fn clone(&self) -> Self {
+        Self {
+            handle: self.vtable.clone_handle()(self.handle.as_rref()),
+            vtable: self.vtable,
+        }
+    }
+}
+
+impl<T> Debug for RAsyncSender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("RAsyncSender{..}")
+    }
+}
+
+unsafe impl<T: Send> Sync for RAsyncSender<T> {}
+
+unsafe impl<T: Send> Send for RAsyncSender<T> {}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// The receiver end of an async channel,
+/// created by the [`bounded`] and [`unbounded`] functions.
+///
+/// # Example
+///
+/// ```rust
+/// use abi_stable::external_types::async_channel;
+///
+/// # fn block_on<F: std::future::Future>(fut: F) -> F::Output {
+/// #     use std::{sync::Arc, task::{Context, Poll, Wake}, thread::{self, Thread}};
+/// #     struct Unpark(Thread);
+/// #     impl Wake for Unpark { fn wake(self: Arc<Self>) { self.0.unpark() } }
+/// #     let waker = Arc::new(Unpark(thread::current())).into();
+/// #     let mut fut = Box::pin(fut);
+/// #     loop {
+/// #         match fut.as_mut().poll(&mut Context::from_waker(&waker)) {
+/// #             Poll::Ready(x) => return x,
+/// #             Poll::Pending => thread::park(),
+/// #         }
+/// #     }
+/// # }
+/// let (tx, rx) = async_channel::unbounded::<&'static str>();
+///
+/// std::thread::spawn(move || {
+///     tx.send("foo").unwrap();
+///     tx.send("bar").unwrap();
+/// });
+///
+/// block_on(async {
+///     assert_eq!(rx.recv_async().await, Ok("foo"));
+///     assert_eq!(rx.recv_async().await, Ok("bar"));
+///     assert!(rx.recv_async().await.is_err());
+/// });
+///
+/// ```
+#[repr(C)]
+#[derive(StableAbi)]
+pub struct RAsyncReceiver<T> {
+    handle: RBox<ErasedHandle<T>>,
+    vtable: VTable_Ref<T>,
+}
+
+impl<T> RAsyncReceiver<T> {
+    /// Blocks until a value is either received,or the the other end is disconnected.
+    ///
+    /// If the channel queue is empty and disconnected,this will return an error.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use abi_stable::external_types::async_channel::{self, RRecvError};
+    ///
+    /// let (tx, rx) = async_channel::unbounded::<u32>();
+    ///
+    /// tx.send(1057).unwrap();
+    /// drop(tx);
+    ///
+    /// assert_eq!(rx.recv(), Ok(1057));
+    /// assert_eq!(rx.recv(), Err(RRecvError));
+    ///
+    /// ```
+    pub fn recv(&self) -> Result<T, RRecvError> {
+        self.vtable.recv()(self.handle.as_rref()).into_rust()
+    }
+
+    /// Immediately receives a value,
+    /// returning an error if the channel is empty.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use abi_stable::external_types::async_channel::{self, RTryRecvError};
+    ///
+    /// let (tx, rx) = async_channel::unbounded::<u32>();
+    ///
+    /// assert_eq!(rx.try_recv(), Err(RTryRecvError::Empty));
+    ///
+    /// tx.send(3).unwrap();
+    /// assert_eq!(rx.try_recv(), Ok(3));
+    ///
+    /// drop(tx);
+    /// assert_eq!(rx.try_recv(), Err(RTryRecvError::Disconnected));
+    ///
+    /// ```
+    pub fn try_recv(&self) -> Result<T, RTryRecvError> {
+        self.vtable.try_recv()(self.handle.as_rref()).into_rust()
+    }
+
+    /// Blocks until a timeout to receive a value.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use abi_stable::external_types::async_channel::{self, RRecvTimeoutError};
+    ///
+    /// use std::time::Duration;
+    ///
+    /// let (tx, rx) = async_channel::unbounded::<u32>();
+    ///
+    /// let res = rx.recv_timeout(Duration::from_millis(1));
+    /// assert_eq!(res, Err(RRecvTimeoutError::Timeout));
+    ///
+    /// ```
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<T, RRecvTimeoutError> {
+        self.vtable.recv_timeout()(self.handle.as_rref(), timeout.into()).into_rust()
+    }
+
+    /// Returns a future that receives a value once the channel has one.
+    ///
+    /// The future resolves to an error if the channel queue is empty and disconnected.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use abi_stable::external_types::async_channel;
+    ///
+    /// # fn block_on<F: std::future::Future>(fut: F) -> F::Output {
+    /// #     use std::{sync::Arc, task::{Context, Poll, Wake}, thread::{self, Thread}};
+    /// #     struct Unpark(Thread);
+    /// #     impl Wake for Unpark { fn wake(self: Arc<Self>) { self.0.unpark() } }
+    /// #     let waker = Arc::new(Unpark(thread::current())).into();
+    /// #     let mut fut = Box::pin(fut);
+    /// #     loop {
+    /// #         match fut.as_mut().poll(&mut Context::from_waker(&waker)) {
+    /// #             Poll::Ready(x) => return x,
+    /// #             Poll::Pending => thread::park(),
+    /// #         }
+    /// #     }
+    /// # }
+    /// let (tx, rx) = async_channel::bounded::<u32>(1);
+    ///
+    /// std::thread::spawn(move || (0..3).for_each(|x| tx.send(x).unwrap()));
+    ///
+    /// block_on(async {
+    ///     assert_eq!(rx.recv_async().await, Ok(0));
+    ///     assert_eq!(rx.recv_async().await, Ok(1));
+    ///     assert_eq!(rx.recv_async().await, Ok(2));
+    ///     assert!(rx.recv_async().await.is_err());
+    /// });
+    ///
+    /// ```
+    pub fn recv_async(&self) -> RRecvFuture<'_, T> {
+        RRecvFuture {
+            receiver: self,
+            key: 0,
+        }
+    }
+
+    /// Returns true if there are no values in the channel queue.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use abi_stable::external_types::async_channel;
+    ///
+    /// let (tx, rx) = async_channel::unbounded::<()>();
+    ///
+    /// assert!(rx.is_empty());
+    /// tx.send(()).unwrap();
+    /// assert!(!rx.is_empty());
+    ///
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.vtable.is_empty()(self.handle.as_rref())
+    }
+
+    /// Returns true if the channel queue is full.
+    ///
+    /// This always returns false for unbounded channels.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use abi_stable::external_types::async_channel;
+    ///
+    /// let (tx, rx) = async_channel::bounded::<()>(1);
+    ///
+    /// assert!(!rx.is_full());
+    /// tx.send(()).unwrap();
+    /// assert!(rx.is_full());
+    ///
+    /// ```
+    pub fn is_full(&self) -> bool {
+        self.vtable.is_full()(self.handle.as_rref())
+    }
+
+    /// Returns the amount of values in the channel queue.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use abi_stable::external_types::async_channel;
+    ///
+    /// let (tx, rx) = async_channel::bounded::<()>(2);
+    ///
+    /// tx.send(()).unwrap();
+    /// assert_eq!(rx.len(), 1);
+    ///
+    /// ```
+    pub fn len(&self) -> usize {
+        self.vtable.len()(self.handle.as_rref())
+    }
+
+    /// Returns the amount of values the channel queue can hold.
+    ///
+    /// This returns None if the channel is unbounded.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use abi_stable::external_types::async_channel;
+    ///
+    /// assert_eq!(async_channel::bounded::<()>(2).1.capacity(), Some(2));
+    /// assert_eq!(async_channel::unbounded::<()>().1.capacity(), None);
+    ///
+    /// ```
+    pub fn capacity(&self) -> Option<usize> {
+        self.vtable.capacity()(self.handle.as_rref()).into_rust()
+    }
+
+    /// Returns true if all the senders of the channel were dropped.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use abi_stable::external_types::async_channel;
+    ///
+    /// let (tx, rx) = async_channel::unbounded::<()>();
+    ///
+    /// assert!(!rx.is_disconnected());
+    /// drop(tx);
+    /// assert!(rx.is_disconnected());
+    ///
+    /// ```
+    pub fn is_disconnected(&self) -> bool {
+        self.vtable.is_disconnected()(self.handle.as_rref())
+    }
+}
+
+impl<T> Clone for RAsyncReceiver<T> {
+    /// Clones this channel end,getting another handle into the channel.
+    ///
+    /// Note that this allocates an RBox<_>.
+    fn clone(&self) -> Self {
+        Self {
+            handle: self.vtable.clone_handle()(self.handle.as_rref()),
+            vtable: self.vtable,
+        }
+    }
+}
+
+impl<T> Debug for RAsyncReceiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("RAsyncReceiver{..}")
+    }
+}
+
+unsafe impl<T: Send> Sync for RAsyncReceiver<T> {}
+
+unsafe impl<T: Send> Send for RAsyncReceiver<T> {}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// The future returned by [`RAsyncSender::send_async`].
+#[repr(C)]
+#[derive(StableAbi)]
+#[must_use = "futures do nothing unless polled"]
+pub struct RSendFuture<'a, T> {
+    sender: &'a RAsyncSender<T>,
+    value: ROption<T>,
+    // The key of the waker that this registered in the channel,0 if it has none.
+    key: u64,
+}
+
+// The value isn't pinned by `RSendFuture`.
+impl<'a, T> Unpin for RSendFuture<'a, T> {}
+
+impl<'a, T> Future for RSendFuture<'a, T> {
+    type Output = Result<(), RSendError<T>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let sender = this.sender;
+        let poll_send = sender.vtable.poll_send();
+        RWaker::with_waker_ref(cx.waker(), |waker| {
+            poll_send(
+                sender.handle.as_rref(),
+                &mut this.value,
+                &mut this.key,
+                waker,
+            )
+        })
+        .into_poll()
+        .map(IntoReprRust::into_rust)
+    }
+}
+
+impl<'a, T> Drop for RSendFuture<'a, T> {
+    fn drop(&mut self) {
+        if self.key != 0 {
+            self.sender.vtable.cancel_send()(self.sender.handle.as_rref(), self.key);
+        }
+    }
+}
+
+impl<'a, T> Debug for RSendFuture<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("RSendFuture{..}")
+    }
+}
+
+/// The future returned by [`RAsyncReceiver::recv_async`].
+#[repr(C)]
+#[derive(StableAbi)]
+#[must_use = "futures do nothing unless polled"]
+pub struct RRecvFuture<'a, T> {
+    receiver: &'a RAsyncReceiver<T>,
+    // The key of the waker that this registered in the channel,0 if it has none.
+    key: u64,
+}
+
+impl<'a, T> Future for RRecvFuture<'a, T> {
+    type Output = Result<T, RRecvError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let receiver = this.receiver;
+        let poll_recv = receiver.vtable.poll_recv();
+        RWaker::with_waker_ref(cx.waker(), |waker| {
+            poll_recv(receiver.handle.as_rref(), &mut this.key, waker)
+        })
+        .into_poll()
+        .map(IntoReprRust::into_rust)
+    }
+}
+
+impl<'a, T> Drop for RRecvFuture<'a, T> {
+    fn drop(&mut self) {
+        if self.key != 0 {
+            self.receiver.vtable.cancel_recv()(self.receiver.handle.as_rref(), self.key);
+        }
+    }
+}
+
+impl<'a, T> Debug for RRecvFuture<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("RRecvFuture{..}")
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+#[repr(C)]
+#[derive(StableAbi)]
+struct ErasedHandle<T>(PhantomData<T>, UnsafeIgnoredType<Handle<T>>);
+
+impl<T> ErasedType<'_> for ErasedHandle<T> {
+    type Unerased = Handle<T>;
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+#[repr(C)]
+#[derive(StableAbi)]
+#[sabi(kind(Prefix))]
+#[sabi(missing_field(panic))]
+struct VTable<T> {
+    send: extern "C" fn(this: RRef<'_, ErasedHandle<T>>, T) -> RResult<(), RSendError<T>>,
+    try_send: extern "C" fn(this: RRef<'_, ErasedHandle<T>>, T) -> RResult<(), RTrySendError<T>>,
+    send_timeout: extern "C" fn(
+        this: RRef<'_, ErasedHandle<T>>,
+        value: T,
+        timeout: RDuration,
+    ) -> RResult<(), RSendTimeoutError<T>>,
+    poll_send: extern "C" fn(
+        this: RRef<'_, ErasedHandle<T>>,
+        value: &mut ROption<T>,
+        key: &mut u64,
+        waker: &RWaker,
+    ) -> RPoll<RResult<(), RSendError<T>>>,
+    cancel_send: extern "C" fn(this: RRef<'_, ErasedHandle<T>>, key: u64),
+
+    recv: extern "C" fn(this: RRef<'_, ErasedHandle<T>>) -> RResult<T, RRecvError>,
+    try_recv: extern "C" fn(this: RRef<'_, ErasedHandle<T>>) -> RResult<T, RTryRecvError>,
+    recv_timeout: extern "C" fn(
+        this: RRef<'_, ErasedHandle<T>>,
+        timeout: RDuration,
+    ) -> RResult<T, RRecvTimeoutError>,
+    poll_recv: extern "C" fn(
+        this: RRef<'_, ErasedHandle<T>>,
+        key: &mut u64,
+        waker: &RWaker,
+    ) -> RPoll<RResult<T, RRecvError>>,
+    cancel_recv: extern "C" fn(this: RRef<'_, ErasedHandle<T>>, key: u64),
+
+    clone_handle: extern "C" fn(this: RRef<'_, ErasedHandle<T>>) -> RBox<ErasedHandle<T>>,
+    is_empty: extern "C" fn(this: RRef<'_, ErasedHandle<T>>) -> bool,
+    is_full: extern "C" fn(this: RRef<'_, ErasedHandle<T>>) -> bool,
+    len: extern "C" fn(this: RRef<'_, ErasedHandle<T>>) -> usize,
+    capacity: extern "C" fn(this: RRef<'_, ErasedHandle<T>>) -> ROption<usize>,
+    #[sabi(last_prefix_field)]
+    is_disconnected: extern "C" fn(this: RRef<'_, ErasedHandle<T>>) -> bool,
+}
+
+struct MakeVTable<'a, T>(PhantomData<&'a T>);
+
+impl<'a, T: 'a> MakeVTable<'a, T> {
+    const VALUE: VTable<T> = VTable {
+        send: channel::send,
+        try_send: channel::try_send,
+        send_timeout: channel::send_timeout,
+        poll_send: channel::poll_send,
+        cancel_send: channel::cancel_send,
+
+        recv: channel::recv,
+        try_recv: channel::try_recv,
+        recv_timeout: channel::recv_timeout,
+        poll_recv: channel::poll_recv,
+        cancel_recv: channel::cancel_recv,
+
+        clone_handle: channel::clone_handle,
+        is_empty: channel::is_empty,
+        is_full: channel::is_full,
+        len: channel::len,
+        capacity: channel::capacity,
+        is_disconnected: channel::is_disconnected,
+    };
+
+    staticref! {
+        const WM_VALUE: WithMetadata<VTable<T>> = WithMetadata::new(Self::VALUE)
+    }
+
+    // The VTABLE for this type in this executable/library
+    const VTABLE: VTable_Ref<T> = VTable_Ref(Self::WM_VALUE.as_prefix());
+}
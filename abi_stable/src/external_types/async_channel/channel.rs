@@ -0,0 +1,484 @@
+//! The channel implementation that's type-erased in the vtable of `RAsyncSender`/`RAsyncReceiver`.
+
+use super::*;
+
+use std::{
+    collections::VecDeque,
+    mem,
+    sync::{Arc, Condvar, Mutex, MutexGuard},
+    task::Poll,
+    time::Instant,
+};
+
+use crate::traits::IntoReprC;
+
+/// The channel that senders and receivers point to.
+struct Channel<T> {
+    state: Mutex<State<T>>,
+    // Notified when a value is sent or all senders disconnect.
+    recv_cond: Condvar,
+    // Notified when a value is received or all receivers disconnect.
+    send_cond: Condvar,
+}
+
+struct State<T> {
+    queue: VecDeque<T>,
+    capacity: Option<usize>,
+    senders: usize,
+    receivers: usize,
+    // The amount of receivers blocked in `recv_deadline`.
+    blocked_receivers: usize,
+    recv_waiters: Waiters,
+    send_waiters: Waiters,
+}
+
+impl<T> State<T> {
+    fn is_full(&self) -> bool {
+        match self.capacity {
+            // A zero-capacity channel only holds the values that waiting receivers will take.
+            Some(0) => self.queue.len() >= self.blocked_receivers + self.recv_waiters.entries.len(),
+            Some(cap) => self.queue.len() >= cap,
+            None => false,
+        }
+    }
+}
+
+/// The wakers of the futures waiting on one side of the channel.
+///
+/// Futures keep the key of their entry,so that they can replace their waker,
+/// and remove it when they're dropped.
+/// `0` is used as the key of futures that have no entry.
+struct Waiters {
+    entries: Vec<(u64, RWaker)>,
+    next_key: u64,
+}
+
+impl Waiters {
+    const fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            next_key: 1,
+        }
+    }
+
+    /// Registers the waker of a future, returning whether it was not already registered.
+    fn register(&mut self, key: &mut u64, waker: &RWaker) -> bool {
+        let k = *key;
+        match self
+            .entries
+            .iter_mut()
+            .find(|(entry_key, _)| *entry_key == k)
+        {
+            Some(entry) => {
+                entry.1 = waker.clone();
+                false
+            }
+            None => {
+                *key = self.next_key;
+                self.next_key += 1;
+                self.entries.push((*key, waker.clone()));
+                true
+            }
+        }
+    }
+
+    fn remove(&mut self, key: &mut u64) {
+        if *key != 0 {
+            let k = *key;
+            self.entries.retain(|(entry_key, _)| *entry_key != k);
+            *key = 0;
+        }
+    }
+
+    // The wakers are woken by `wake_all` after the lock is released.
+    fn take_all(&mut self) -> Vec<(u64, RWaker)> {
+        mem::take(&mut self.entries)
+    }
+}
+
+fn wake_all(waiters: Vec<(u64, RWaker)>) {
+    for (_, waker) in waiters {
+        waker.wake();
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum HandleKind {
+    Sender,
+    Receiver,
+}
+
+/// A sender or receiver of the channel, the unerased type of `ErasedHandle`.
+pub(super) struct Handle<T> {
+    channel: Arc<Channel<T>>,
+    kind: HandleKind,
+}
+
+impl<T> Handle<T> {
+    pub(super) fn new_pair(
+        capacity: Option<usize>,
+    ) -> (RBox<ErasedHandle<T>>, RBox<ErasedHandle<T>>) {
+        let channel = Arc::new(Channel {
+            state: Mutex::new(State {
+                queue: VecDeque::new(),
+                capacity,
+                senders: 1,
+                receivers: 1,
+                blocked_receivers: 0,
+                recv_waiters: Waiters::new(),
+                send_waiters: Waiters::new(),
+            }),
+            recv_cond: Condvar::new(),
+            send_cond: Condvar::new(),
+        });
+        let sender = Handle {
+            channel: channel.clone(),
+            kind: HandleKind::Sender,
+        };
+        let receiver = Handle {
+            channel,
+            kind: HandleKind::Receiver,
+        };
+        (erase(sender), erase(receiver))
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State<T>> {
+        self.channel.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn is_disconnected(&self, state: &State<T>) -> bool {
+        match self.kind {
+            HandleKind::Sender => state.receivers == 0,
+            HandleKind::Receiver => state.senders == 0,
+        }
+    }
+
+    /// Pushes `value` into the queue,waking up receivers.
+    fn push(&self, mut state: MutexGuard<'_, State<T>>, value: T) {
+        state.queue.push_back(value);
+        let waiters = state.recv_waiters.take_all();
+        drop(state);
+        self.channel.recv_cond.notify_all();
+        wake_all(waiters);
+    }
+
+    /// Pops a value from the queue,waking up senders.
+    fn pop(&self, mut state: MutexGuard<'_, State<T>>) -> Option<T> {
+        let value = state.queue.pop_front()?;
+        self.wake_senders(state);
+        Some(value)
+    }
+
+    /// Wakes up senders, releasing the lock.
+    fn wake_senders(&self, mut state: MutexGuard<'_, State<T>>) {
+        let waiters = state.send_waiters.take_all();
+        drop(state);
+        self.channel.send_cond.notify_all();
+        wake_all(waiters);
+    }
+
+    fn try_send(&self, value: T) -> Result<(), RTrySendError<T>> {
+        let state = self.lock();
+        if state.receivers == 0 {
+            Err(RTrySendError::Disconnected(value))
+        } else if state.is_full() {
+            Err(RTrySendError::Full(value))
+        } else {
+            self.push(state, value);
+            Ok(())
+        }
+    }
+
+    fn send_deadline(
+        &self,
+        value: T,
+        deadline: Option<Instant>,
+    ) -> Result<(), RSendTimeoutError<T>> {
+        let mut state = self.lock();
+        loop {
+            if state.receivers == 0 {
+                return Err(RSendTimeoutError::Disconnected(value));
+            } else if !state.is_full() {
+                self.push(state, value);
+                return Ok(());
+            }
+            state = match wait(&self.channel.send_cond, state, deadline) {
+                Ok(x) => x,
+                Err(_) => return Err(RSendTimeoutError::Timeout(value)),
+            };
+        }
+    }
+
+    fn poll_send(
+        &self,
+        value: &mut ROption<T>,
+        key: &mut u64,
+        waker: &RWaker,
+    ) -> Poll<Result<(), RSendError<T>>> {
+        let mut state = self.lock();
+        if state.receivers == 0 {
+            state.send_waiters.remove(key);
+            let value = value.take().expect("polled RSendFuture after completion");
+            Poll::Ready(Err(RSendError(value)))
+        } else if !state.is_full() {
+            state.send_waiters.remove(key);
+            let value = value.take().expect("polled RSendFuture after completion");
+            self.push(state, value);
+            Poll::Ready(Ok(()))
+        } else {
+            state.send_waiters.register(key, waker);
+            Poll::Pending
+        }
+    }
+
+    fn try_recv(&self) -> Result<T, RTryRecvError> {
+        let state = self.lock();
+        let disconnected = state.senders == 0;
+        match self.pop(state) {
+            Some(x) => Ok(x),
+            None if disconnected => Err(RTryRecvError::Disconnected),
+            None => Err(RTryRecvError::Empty),
+        }
+    }
+
+    fn recv_deadline(&self, deadline: Option<Instant>) -> Result<T, RRecvTimeoutError> {
+        let mut state = self.lock();
+        let mut blocked = false;
+        let res = loop {
+            if !state.queue.is_empty() {
+                break Ok(());
+            } else if state.senders == 0 {
+                break Err(RRecvTimeoutError::Disconnected);
+            } else if !blocked {
+                blocked = true;
+                state.blocked_receivers += 1;
+                if state.capacity == Some(0) {
+                    // Senders of zero-capacity channels wait for a receiver.
+                    self.wake_senders(state);
+                    state = self.lock();
+                    continue;
+                }
+            }
+            state = match wait(&self.channel.recv_cond, state, deadline) {
+                Ok(x) => x,
+                Err(x) => {
+                    state = x;
+                    break Err(RRecvTimeoutError::Timeout);
+                }
+            };
+        };
+        if blocked {
+            state.blocked_receivers -= 1;
+        }
+        res.map(|()| self.pop(state).expect("the queue is not empty"))
+    }
+
+    fn poll_recv(&self, key: &mut u64, waker: &RWaker) -> Poll<Result<T, RRecvError>> {
+        let mut state = self.lock();
+        if !state.queue.is_empty() {
+            state.recv_waiters.remove(key);
+            Poll::Ready(Ok(self.pop(state).expect("the queue is not empty")))
+        } else if state.senders == 0 {
+            state.recv_waiters.remove(key);
+            Poll::Ready(Err(RRecvError))
+        } else {
+            let newly_waiting = state.recv_waiters.register(key, waker);
+            if newly_waiting && state.capacity == Some(0) {
+                // Senders of zero-capacity channels wait for a receiver.
+                self.wake_senders(state);
+            }
+            Poll::Pending
+        }
+    }
+}
+
+/// Waits on `cond` until `deadline`, returning `Err` if the deadline has passed.
+fn wait<'a, T>(
+    cond: &Condvar,
+    state: MutexGuard<'a, State<T>>,
+    deadline: Option<Instant>,
+) -> Result<MutexGuard<'a, State<T>>, MutexGuard<'a, State<T>>> {
+    match deadline {
+        Some(deadline) => {
+            let timeout = match deadline.checked_duration_since(Instant::now()) {
+                Some(x) => x,
+                None => return Err(state),
+            };
+            let (state, _) = cond
+                .wait_timeout(state, timeout)
+                .unwrap_or_else(|e| e.into_inner());
+            Ok(state)
+        }
+        None => Ok(cond.wait(state).unwrap_or_else(|e| e.into_inner())),
+    }
+}
+
+impl<T> Clone for Handle<T> {
+    fn clone(&self) -> Self {
+        let mut state = self.lock();
+        match self.kind {
+            HandleKind::Sender => state.senders += 1,
+            HandleKind::Receiver => state.receivers += 1,
+        }
+        Self {
+            channel: self.channel.clone(),
+            kind: self.kind,
+        }
+    }
+}
+
+impl<T> Drop for Handle<T> {
+    fn drop(&mut self) {
+        let mut state = self.lock();
+        let count = match self.kind {
+            HandleKind::Sender => &mut state.senders,
+            HandleKind::Receiver => &mut state.receivers,
+        };
+        *count -= 1;
+        if *count != 0 {
+            return;
+        }
+
+        // Waking up the other side of the channel, since it's now disconnected.
+        let (waiters, cond) = match self.kind {
+            HandleKind::Sender => (state.recv_waiters.take_all(), &self.channel.recv_cond),
+            HandleKind::Receiver => (state.send_waiters.take_all(), &self.channel.send_cond),
+        };
+        drop(state);
+        cond.notify_all();
+        wake_all(waiters);
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+fn erase<T>(handle: Handle<T>) -> RBox<ErasedHandle<T>> {
+    unsafe { ErasedType::from_unerased(RBox::new(handle)) }
+}
+
+fn run<'a, T, F, R>(this: RRef<'a, ErasedHandle<T>>, f: F) -> R
+where
+    F: FnOnce(&'a Handle<T>) -> R,
+{
+    extern_fn_panic_handling! {
+        unsafe{
+            f(ErasedHandle::downcast_into(this).get())
+        }
+    }
+}
+
+fn deadline_from(timeout: RDuration) -> Option<Instant> {
+    Instant::now().checked_add(timeout.into())
+}
+
+pub(super) extern "C" fn clone_handle<T>(this: RRef<'_, ErasedHandle<T>>) -> RBox<ErasedHandle<T>> {
+    run(this, |this| erase(this.clone()))
+}
+
+pub(super) extern "C" fn send<T>(
+    this: RRef<'_, ErasedHandle<T>>,
+    value: T,
+) -> RResult<(), RSendError<T>> {
+    run(this, |this| {
+        this.send_deadline(value, None)
+            .map_err(|e| RSendError(e.into_inner()))
+            .into_c()
+    })
+}
+
+pub(super) extern "C" fn try_send<T>(
+    this: RRef<'_, ErasedHandle<T>>,
+    value: T,
+) -> RResult<(), RTrySendError<T>> {
+    run(this, |this| this.try_send(value).into_c())
+}
+
+pub(super) extern "C" fn send_timeout<T>(
+    this: RRef<'_, ErasedHandle<T>>,
+    value: T,
+    timeout: RDuration,
+) -> RResult<(), RSendTimeoutError<T>> {
+    run(this, |this| match deadline_from(timeout) {
+        Some(deadline) => this.send_deadline(value, Some(deadline)).into_c(),
+        None => this
+            .send_deadline(value, None)
+            .map_err(|e| RSendTimeoutError::Disconnected(e.into_inner()))
+            .into_c(),
+    })
+}
+
+pub(super) extern "C" fn poll_send<T>(
+    this: RRef<'_, ErasedHandle<T>>,
+    value: &mut ROption<T>,
+    key: &mut u64,
+    waker: &RWaker,
+) -> RPoll<RResult<(), RSendError<T>>> {
+    run(this, |this| {
+        this.poll_send(value, key, waker)
+            .map(IntoReprC::into_c)
+            .into_c()
+    })
+}
+
+pub(super) extern "C" fn cancel_send<T>(this: RRef<'_, ErasedHandle<T>>, mut key: u64) {
+    run(this, |this| this.lock().send_waiters.remove(&mut key))
+}
+
+pub(super) extern "C" fn recv<T>(this: RRef<'_, ErasedHandle<T>>) -> RResult<T, RRecvError> {
+    run(this, |this| {
+        this.recv_deadline(None).map_err(|_| RRecvError).into_c()
+    })
+}
+
+pub(super) extern "C" fn try_recv<T>(this: RRef<'_, ErasedHandle<T>>) -> RResult<T, RTryRecvError> {
+    run(this, |this| this.try_recv().into_c())
+}
+
+pub(super) extern "C" fn recv_timeout<T>(
+    this: RRef<'_, ErasedHandle<T>>,
+    timeout: RDuration,
+) -> RResult<T, RRecvTimeoutError> {
+    run(this, |this| {
+        let deadline = deadline_from(timeout);
+        match this.recv_deadline(deadline) {
+            Err(RRecvTimeoutError::Timeout) if deadline.is_none() => {
+                unreachable!("recv without a deadline can't time out")
+            }
+            res => res.into_c(),
+        }
+    })
+}
+
+pub(super) extern "C" fn poll_recv<T>(
+    this: RRef<'_, ErasedHandle<T>>,
+    key: &mut u64,
+    waker: &RWaker,
+) -> RPoll<RResult<T, RRecvError>> {
+    run(this, |this| {
+        this.poll_recv(key, waker).map(IntoReprC::into_c).into_c()
+    })
+}
+
+pub(super) extern "C" fn cancel_recv<T>(this: RRef<'_, ErasedHandle<T>>, mut key: u64) {
+    run(this, |this| this.lock().recv_waiters.remove(&mut key))
+}
+
+pub(super) extern "C" fn is_empty<T>(this: RRef<'_, ErasedHandle<T>>) -> bool {
+    run(this, |this| this.lock().queue.is_empty())
+}
+
+pub(super) extern "C" fn len<T>(this: RRef<'_, ErasedHandle<T>>) -> usize {
+    run(this, |this| this.lock().queue.len())
+}
+
+pub(super) extern "C" fn capacity<T>(this: RRef<'_, ErasedHandle<T>>) -> ROption<usize> {
+    run(this, |this| this.lock().capacity.into_c())
+}
+
+pub(super) extern "C" fn is_full<T>(this: RRef<'_, ErasedHandle<T>>) -> bool {
+    run(this, |this| this.lock().is_full())
+}
+
+pub(super) extern "C" fn is_disconnected<T>(this: RRef<'_, ErasedHandle<T>>) -> bool {
+    run(this, |this| this.is_disconnected(&this.lock()))
+}
@@ -0,0 +1,280 @@
+use super::*;
+
+use std::{sync::Arc, thread, time::Instant};
+
+use crate::test_utils::{block_on, WakeCounter};
+
+fn poll_once<F: Future + Unpin>(fut: &mut F, counter: &Arc<WakeCounter>) -> Poll<F::Output> {
+    let waker = counter.waker();
+    Pin::new(fut).poll(&mut Context::from_waker(&waker))
+}
+
+#[test]
+fn zero_capacity() {
+    let (tx, rx) = bounded::<u32>(0);
+    let counter = WakeCounter::new();
+
+    assert_eq!(tx.capacity(), Some(0));
+    assert!(tx.is_full());
+    assert_eq!(tx.try_send(0), Err(RTrySendError::Full(0)));
+    assert_eq!(
+        tx.send_timeout(1, Duration::from_millis(1)),
+        Err(RSendTimeoutError::Timeout(1))
+    );
+
+    // A waiting async receiver lets a value through.
+    let mut send_fut = tx.send_async(2);
+    assert_eq!(poll_once(&mut send_fut, &counter), Poll::Pending);
+
+    let mut recv_fut = rx.recv_async();
+    assert_eq!(poll_once(&mut recv_fut, &counter), Poll::Pending);
+    assert_eq!(counter.count(), 1);
+
+    assert_eq!(poll_once(&mut send_fut, &counter), Poll::Ready(Ok(())));
+    assert_eq!(counter.count(), 2);
+    assert_eq!(poll_once(&mut recv_fut, &counter), Poll::Ready(Ok(2)));
+    drop((send_fut, recv_fut));
+    assert!(tx.is_full());
+
+    // A blocked receiver lets a value through.
+    let handle = thread::spawn(move || (rx.recv(), rx.recv()));
+    tx.send(3).unwrap();
+    tx.send(5).unwrap();
+    assert_eq!(handle.join().unwrap(), (Ok(3), Ok(5)));
+
+    assert_eq!(tx.send(8), Err(RSendError(8)));
+}
+
+#[test]
+fn zero_capacity_threads() {
+    let (tx, rx) = bounded::<u32>(0);
+
+    let handle = thread::spawn(move || {
+        block_on(async {
+            for i in 0..100 {
+                tx.send_async(i).await.unwrap();
+            }
+        })
+    });
+
+    let list = std::iter::from_fn(|| rx.recv().ok()).collect::<Vec<u32>>();
+    assert_eq!(list, (0..100).collect::<Vec<u32>>());
+    handle.join().unwrap();
+}
+
+#[test]
+fn blocking_sender_async_receiver() {
+    let (tx, rx) = bounded::<u32>(2);
+
+    let handle = thread::spawn(move || {
+        for i in 0..100 {
+            tx.send(i).unwrap();
+        }
+    });
+
+    let list = block_on(async move {
+        let mut list = Vec::new();
+        while let Ok(x) = rx.recv_async().await {
+            list.push(x);
+        }
+        list
+    });
+
+    assert_eq!(list, (0..100).collect::<Vec<u32>>());
+    handle.join().unwrap();
+}
+
+#[test]
+fn async_sender_blocking_receiver() {
+    let (tx, rx) = bounded::<u32>(1);
+
+    let handle = thread::spawn(move || {
+        block_on(async {
+            for i in 0..100 {
+                tx.send_async(i).await.unwrap();
+            }
+        })
+    });
+
+    let list = std::iter::from_fn(|| rx.recv().ok()).collect::<Vec<u32>>();
+    assert_eq!(list, (0..100).collect::<Vec<u32>>());
+    handle.join().unwrap();
+}
+
+#[test]
+fn many_async_senders_and_receivers() {
+    let (tx, rx) = unbounded::<u32>();
+
+    let senders = (0..4)
+        .map(|i| {
+            let tx = tx.clone();
+            thread::spawn(move || {
+                block_on(async {
+                    for j in 0..50 {
+                        tx.send_async(i * 50 + j).await.unwrap();
+                    }
+                })
+            })
+        })
+        .collect::<Vec<_>>();
+    drop(tx);
+
+    let receivers = (0..4)
+        .map(|_| {
+            let rx = rx.clone();
+            thread::spawn(move || {
+                block_on(async {
+                    let mut list = Vec::new();
+                    while let Ok(x) = rx.recv_async().await {
+                        list.push(x);
+                    }
+                    list
+                })
+            })
+        })
+        .collect::<Vec<_>>();
+    drop(rx);
+
+    senders.into_iter().for_each(|h| h.join().unwrap());
+
+    let mut list = receivers
+        .into_iter()
+        .flat_map(|h| h.join().unwrap())
+        .collect::<Vec<u32>>();
+    list.sort_unstable();
+    assert_eq!(list, (0..200).collect::<Vec<u32>>());
+}
+
+#[test]
+fn bounded_backpressure() {
+    let (tx, rx) = bounded::<u32>(1);
+    let counter = WakeCounter::new();
+
+    tx.send(0).unwrap();
+    assert!(tx.is_full());
+
+    let mut fut = tx.send_async(1);
+    assert_eq!(poll_once(&mut fut, &counter), Poll::Pending);
+    assert_eq!(poll_once(&mut fut, &counter), Poll::Pending);
+    assert_eq!(counter.count(), 0);
+
+    assert_eq!(rx.recv(), Ok(0));
+    assert_eq!(counter.count(), 1);
+
+    assert_eq!(poll_once(&mut fut, &counter), Poll::Ready(Ok(())));
+    drop(fut);
+    assert_eq!(rx.try_recv(), Ok(1));
+    assert_eq!(counter.count(), 1);
+}
+
+#[test]
+fn disconnection_wakes_receivers() {
+    let (tx, rx) = unbounded::<u32>();
+    let counter = WakeCounter::new();
+
+    let mut fut = rx.recv_async();
+    assert_eq!(poll_once(&mut fut, &counter), Poll::Pending);
+
+    let tx2 = tx.clone();
+    drop(tx);
+    assert_eq!(counter.count(), 0);
+    tx2.send(3).unwrap();
+    assert_eq!(counter.count(), 1);
+    assert_eq!(poll_once(&mut fut, &counter), Poll::Ready(Ok(3)));
+    drop(fut);
+
+    let mut fut = rx.recv_async();
+    assert_eq!(poll_once(&mut fut, &counter), Poll::Pending);
+    drop(tx2);
+    assert_eq!(counter.count(), 2);
+    assert!(rx.is_disconnected());
+    assert_eq!(poll_once(&mut fut, &counter), Poll::Ready(Err(RRecvError)));
+}
+
+#[test]
+fn disconnection_wakes_senders() {
+    let (tx, rx) = bounded::<u32>(1);
+    let counter = WakeCounter::new();
+
+    tx.send(0).unwrap();
+
+    let mut fut = tx.send_async(1);
+    assert_eq!(poll_once(&mut fut, &counter), Poll::Pending);
+
+    drop(rx);
+    assert_eq!(counter.count(), 1);
+    assert!(tx.is_disconnected());
+    assert_eq!(
+        poll_once(&mut fut, &counter),
+        Poll::Ready(Err(RSendError(1)))
+    );
+}
+
+#[test]
+fn cancelled_futures_are_unregistered() {
+    let (tx, rx) = bounded::<u32>(1);
+    let counter = WakeCounter::new();
+
+    {
+        let mut fut = rx.recv_async();
+        assert_eq!(poll_once(&mut fut, &counter), Poll::Pending);
+    }
+    tx.send(0).unwrap();
+    assert_eq!(counter.count(), 0);
+
+    {
+        let mut fut = tx.send_async(1);
+        assert_eq!(poll_once(&mut fut, &counter), Poll::Pending);
+    }
+    assert_eq!(rx.recv(), Ok(0));
+    assert_eq!(counter.count(), 0);
+    assert!(rx.is_empty());
+}
+
+#[test]
+fn blocking_timeouts() {
+    let (tx, rx) = bounded::<u32>(1);
+
+    let start = Instant::now();
+    assert_eq!(
+        rx.recv_timeout(Duration::from_millis(10)),
+        Err(RRecvTimeoutError::Timeout)
+    );
+    assert!(start.elapsed() >= Duration::from_millis(10));
+
+    tx.send(0).unwrap();
+    assert_eq!(
+        tx.send_timeout(1, Duration::from_millis(10)),
+        Err(RSendTimeoutError::Timeout(1))
+    );
+
+    let handle = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(10));
+        (rx.recv(), rx.recv())
+    });
+    assert_eq!(tx.send_timeout(2, Duration::from_secs(60)), Ok(()));
+    assert_eq!(handle.join().unwrap(), (Ok(0), Ok(2)));
+
+    assert_eq!(
+        tx.send_timeout(3, Duration::from_millis(1)),
+        Err(RSendTimeoutError::Disconnected(3))
+    );
+}
+
+#[test]
+fn queue_info() {
+    let (tx, rx) = bounded::<u32>(2);
+    assert_eq!(tx.capacity(), Some(2));
+    assert_eq!(rx.capacity(), Some(2));
+
+    assert!(tx.is_empty());
+    tx.try_send(0).unwrap();
+    assert_eq!(rx.len(), 1);
+    tx.try_send(1).unwrap();
+    assert!(rx.is_full());
+    assert_eq!(tx.try_send(2), Err(RTrySendError::Full(2)));
+
+    assert_eq!(rx.try_recv(), Ok(0));
+    assert_eq!(tx.len(), 1);
+    assert!(!tx.is_full());
+}
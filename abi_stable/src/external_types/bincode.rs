@@ -0,0 +1,752 @@
+//! Binary serialization proxies, encoding values with `bincode`.
+//!
+//! [`BincodeBox`] and [`BincodeRef`] can be used as the proxy types of
+//! [`DynTrait`] and [`NonExhaustive`],
+//! which allows the value to be serialized without a textual intermediate representation.
+//!
+//! The [`impl_bincode_proxy`] macro implements the serialization traits of
+//! `DynTrait` and `NonExhaustive` to use [`BincodeBox`] as the proxy.
+//!
+//! [`DynTrait`]: crate::DynTrait
+//! [`NonExhaustive`]: crate::nonexhaustive_enum::NonExhaustive
+//! [`impl_bincode_proxy`]: crate::impl_bincode_proxy
+
+use std::fmt;
+
+use bincode::Options;
+
+use serde::{
+    de::{self, SeqAccess, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::std_types::{RBoxError, RSlice, RVec};
+
+fn options() -> impl Options {
+    bincode::DefaultOptions::new()
+}
+
+/// An ffi-safe owned buffer of bytes, holding a value encoded with `bincode`.
+///
+/// This serializes as a byte array,
+/// so it can be embedded in any serde format.
+///
+/// # Example
+///
+/// ```
+/// use abi_stable::{external_types::bincode::BincodeBox, std_types::RString};
+///
+/// let encoded = BincodeBox::encode(&(3u32, RString::from("hello"))).unwrap();
+///
+/// let decoded = encoded.decode::<(u32, RString)>().unwrap();
+/// assert_eq!(decoded, (3, RString::from("hello")));
+///
+/// ```
+#[repr(transparent)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash, StableAbi)]
+pub struct BincodeBox {
+    bytes: RVec<u8>,
+}
+
+impl BincodeBox {
+    /// Encodes `value` with `bincode`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::external_types::bincode::BincodeBox;
+    ///
+    /// let encoded = BincodeBox::encode(&[3u8, 5, 8]).unwrap();
+    ///
+    /// assert_eq!(encoded.as_bytes(), [3, 5, 8]);
+    ///
+    /// ```
+    pub fn encode<T>(value: &T) -> Result<Self, RBoxError>
+    where
+        T: Serialize + ?Sized,
+    {
+        options()
+            .serialize(value)
+            .map(Self::from_bytes)
+            .map_err(RBoxError::new)
+    }
+
+    /// Decodes a `T` from the bytes in this buffer.
+    ///
+    /// # Errors
+    ///
+    /// This returns an error if the bytes are not a valid encoding of a `T`,
+    /// including when there are trailing bytes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::external_types::bincode::BincodeBox;
+    ///
+    /// let encoded = BincodeBox::encode(&1000u16).unwrap();
+    ///
+    /// assert_eq!(encoded.decode::<u16>().unwrap(), 1000);
+    /// assert!(encoded.decode::<bool>().is_err());
+    ///
+    /// ```
+    pub fn decode<'a, T>(&'a self) -> Result<T, RBoxError>
+    where
+        T: Deserialize<'a>,
+    {
+        self.as_bincode_ref().decode()
+    }
+
+    /// Constructs a `BincodeBox` from already encoded bytes.
+    pub fn from_bytes<B>(bytes: B) -> Self
+    where
+        B: Into<RVec<u8>>,
+    {
+        Self {
+            bytes: bytes.into(),
+        }
+    }
+
+    /// Gets the encoded bytes.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Gets the encoded bytes as an `RSlice`.
+    #[inline]
+    pub fn as_rslice(&self) -> RSlice<'_, u8> {
+        self.bytes.as_rslice()
+    }
+
+    /// Converts this into a `BincodeRef` borrowing the encoded bytes.
+    #[inline]
+    pub fn as_bincode_ref(&self) -> BincodeRef<'_> {
+        BincodeRef::from_rslice(self.as_rslice())
+    }
+
+    /// Unwraps this into the encoded bytes.
+    #[inline]
+    pub fn into_bytes(self) -> RVec<u8> {
+        self.bytes
+    }
+}
+
+impl Serialize for BincodeBox {
+    fn serialize<Z>(&self, serializer: Z) -> Result<Z::Ok, Z::Error>
+    where
+        Z: Serializer,
+    {
+        serializer.serialize_bytes(self.as_bytes())
+    }
+}
+
+impl<'de> Deserialize<'de> for BincodeBox {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer
+            .deserialize_byte_buf(BytesVisitor)
+            .map(Self::from_bytes)
+    }
+}
+
+struct BytesVisitor;
+
+impl<'de> Visitor<'de> for BytesVisitor {
+    type Value = RVec<u8>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a byte array")
+    }
+
+    fn visit_bytes<E>(self, bytes: &[u8]) -> Result<RVec<u8>, E>
+    where
+        E: de::Error,
+    {
+        Ok(RVec::from_slice(bytes))
+    }
+
+    fn visit_byte_buf<E>(self, bytes: Vec<u8>) -> Result<RVec<u8>, E>
+    where
+        E: de::Error,
+    {
+        Ok(RVec::from(bytes))
+    }
+
+    fn visit_seq<A>(self, mut access: A) -> Result<RVec<u8>, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut bytes = RVec::with_capacity(access.size_hint().unwrap_or(0));
+        while let Some(byte) = access.next_element::<u8>()? {
+            bytes.push(byte);
+        }
+        Ok(bytes)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// An ffi-safe borrowed slice of bytes, holding a value encoded with `bincode`.
+///
+/// This can only be deserialized from formats that can lend out their bytes,
+/// like `bincode` itself.
+///
+/// # Example
+///
+/// ```
+/// use abi_stable::{
+///     external_types::bincode::{BincodeBox, BincodeRef},
+///     std_types::RStr,
+/// };
+///
+/// let encoded = BincodeBox::encode("hello").unwrap();
+///
+/// // Serializing the proxy inside of another bincode buffer
+/// let outer = BincodeBox::encode(&encoded).unwrap();
+///
+/// let inner = outer.decode::<BincodeRef<'_>>().unwrap();
+/// assert_eq!(inner.decode::<RStr<'_>>().unwrap(), "hello");
+///
+/// ```
+#[repr(transparent)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, StableAbi)]
+pub struct BincodeRef<'a> {
+    bytes: RSlice<'a, u8>,
+}
+
+impl<'a> BincodeRef<'a> {
+    /// Constructs a `BincodeRef` from already encoded bytes.
+    #[inline]
+    pub const fn from_slice(bytes: &'a [u8]) -> Self {
+        Self::from_rslice(RSlice::from_slice(bytes))
+    }
+
+    /// Constructs a `BincodeRef` from already encoded bytes.
+    #[inline]
+    pub const fn from_rslice(bytes: RSlice<'a, u8>) -> Self {
+        Self { bytes }
+    }
+
+    /// Decodes a `T` from the borrowed bytes.
+    ///
+    /// # Errors
+    ///
+    /// This returns an error if the bytes are not a valid encoding of a `T`,
+    /// including when there are trailing bytes.
+    pub fn decode<T>(self) -> Result<T, RBoxError>
+    where
+        T: Deserialize<'a>,
+    {
+        options()
+            .deserialize(self.bytes.as_slice())
+            .map_err(RBoxError::new)
+    }
+
+    /// Gets the encoded bytes.
+    #[inline]
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.bytes.as_slice()
+    }
+
+    /// Gets the encoded bytes as an `RSlice`.
+    #[inline]
+    pub const fn as_rslice(&self) -> RSlice<'a, u8> {
+        self.bytes
+    }
+
+    /// Copies the encoded bytes into a `BincodeBox`.
+    pub fn to_bincode_box(&self) -> BincodeBox {
+        BincodeBox::from_bytes(self.bytes.to_rvec())
+    }
+}
+
+impl<'a> Serialize for BincodeRef<'a> {
+    fn serialize<Z>(&self, serializer: Z) -> Result<Z::Ok, Z::Error>
+    where
+        Z: Serializer,
+    {
+        serializer.serialize_bytes(self.as_bytes())
+    }
+}
+
+impl<'de: 'a, 'a> Deserialize<'de> for BincodeRef<'a> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        <&'de [u8]>::deserialize(deserializer).map(Self::from_slice)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// Implements the serialization traits of [`DynTrait`] and [`NonExhaustive`],
+/// using [`BincodeBox`] as the proxy type.
+///
+/// Multiple impls can be declared in one invocation, each one terminated by a `;`.
+/// Every impl can take generic parameters in the `impl[...]` brackets
+/// (without a trailing comma),
+/// and a where clause in the `where[...]` brackets after the implementing type.
+///
+/// # `DynTrait`
+///
+/// - `impl SerializeProxyType for Interface;`:
+///   implements [`SerializeProxyType`] for the interface.
+///
+/// - `impl SerializeType<Interface> for Type;`:
+///   implements [`SerializeType`] for the erased type,
+///   encoding it with its `serde::Serialize` impl.
+///
+/// - `impl DeserializeDyn<DynTraitType> for Interface = function;`:
+///   implements [`DeserializeDyn`] for the interface,
+///   delegating to a `function` (usually exported by the `implementation crate`)
+///   with the `extern "C" fn(RSlice<'_, u8>) -> RResult<DynTraitType, RBoxError>` signature.
+///
+/// # `NonExhaustive`
+///
+/// - `impl SerializeEnum<Enum> for Interface;`:
+///   implements [`SerializeEnum`] for the interface,
+///   encoding the enum with its `serde::Serialize` impl.
+///
+/// - `impl DeserializeEnum<Enum> for Interface;`:
+///   implements [`DeserializeEnum`] for the interface,
+///   decoding the enum with its `serde::Deserialize` impl,
+///   for any storage and interface of the `NonExhaustive`.
+///
+/// - `impl DeserializeEnum<NonExhaustiveType> for Interface = function;`:
+///   implements [`DeserializeEnum`] for the interface,
+///   delegating to a `function` with the
+///   `extern "C" fn(RSlice<'_, u8>) -> RResult<NonExhaustiveType, RBoxError>` signature,
+///   so that variants added in newer versions of the `implementation crate` can be decoded.
+///
+/// # Example
+///
+/// ### `DynTrait`
+///
+/// ```
+/// use abi_stable::{
+///     external_types::bincode::BincodeBox,
+///     impl_bincode_proxy,
+///     std_types::{RBox, RBoxError, RErr, ROk, RResult, RSlice},
+///     DynTrait, StableAbi,
+/// };
+///
+/// let foo = FooBox::from_value(Foo { field: 10 });
+///
+/// let bytes = BincodeBox::encode(&foo).unwrap();
+/// let decoded = bytes.decode::<FooBox>().unwrap();
+///
+/// assert_eq!(*decoded.downcast_as::<Foo>().unwrap(), Foo { field: 10 });
+///
+/// type FooBox = DynTrait<'static, RBox<()>, FooInterface>;
+///
+/// #[repr(C)]
+/// #[derive(StableAbi)]
+/// #[sabi(impl_InterfaceType(Send, Sync, Debug, Serialize, Deserialize))]
+/// pub struct FooInterface;
+///
+/// impl_bincode_proxy! {
+///     impl SerializeProxyType for FooInterface;
+///     impl DeserializeDyn<FooBox> for FooInterface = deserialize_foo;
+/// }
+///
+/// /////////////
+/// // everything below could be defined in an implementation crate
+///
+/// #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+/// struct Foo {
+///     field: u32,
+/// }
+///
+/// impl_bincode_proxy! {
+///     impl SerializeType<FooInterface> for Foo;
+/// }
+///
+/// extern "C" fn deserialize_foo(s: RSlice<'_, u8>) -> RResult<FooBox, RBoxError> {
+///     abi_stable::extern_fn_panic_handling! {
+///         match BincodeBox::from_bytes(s.to_rvec()).decode::<Foo>() {
+///             Ok(x) => ROk(DynTrait::from_value(x)),
+///             Err(e) => RErr(e),
+///         }
+///     }
+/// }
+/// ```
+///
+/// ### `NonExhaustive`
+///
+/// ```
+/// use abi_stable::{
+///     external_types::bincode::BincodeBox,
+///     impl_bincode_proxy,
+///     nonexhaustive_enum::NonExhaustive,
+///     std_types::RString,
+///     StableAbi,
+/// };
+///
+/// let ne = NonExhaustive::new(Foo::C { name: "world".into() });
+///
+/// let bytes = BincodeBox::encode(&ne).unwrap();
+/// let decoded = bytes.decode::<Foo_NE>().unwrap();
+///
+/// assert_eq!(decoded, ne);
+///
+/// #[repr(u8)]
+/// #[derive(StableAbi, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+/// #[sabi(kind(WithNonExhaustive(
+///     size = 64,
+///     traits(Debug, PartialEq, Eq, Serialize, Deserialize)
+/// )))]
+/// pub enum Foo {
+///     A,
+///     B(i8),
+///     C { name: RString },
+/// }
+///
+/// impl_bincode_proxy! {
+///     impl SerializeEnum<Foo> for Foo_Interface;
+///     impl DeserializeEnum<Foo> for Foo_Interface;
+/// }
+/// ```
+///
+/// [`DynTrait`]: crate::DynTrait
+/// [`NonExhaustive`]: crate::nonexhaustive_enum::NonExhaustive
+/// [`BincodeBox`]: crate::external_types::bincode::BincodeBox
+/// [`SerializeProxyType`]: crate::erased_types::SerializeProxyType
+/// [`SerializeType`]: crate::erased_types::SerializeType
+/// [`DeserializeDyn`]: crate::erased_types::DeserializeDyn
+/// [`SerializeEnum`]: crate::nonexhaustive_enum::SerializeEnum
+/// [`DeserializeEnum`]: crate::nonexhaustive_enum::DeserializeEnum
+#[macro_export]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "bincode")))]
+macro_rules! impl_bincode_proxy {
+    () => {};
+    (
+        impl $([$($impl_params:tt)*])? SerializeProxyType for $interf:ty
+        $(where [$($where_preds:tt)*])?;
+        $($rest:tt)*
+    ) => {
+        impl<'__s, $($($impl_params)*)?> $crate::erased_types::SerializeProxyType<'__s>
+        for $interf
+        where
+            $($($where_preds)*)?
+        {
+            type Proxy = $crate::external_types::bincode::BincodeBox;
+        }
+
+        $crate::impl_bincode_proxy! { $($rest)* }
+    };
+    (
+        impl $([$($impl_params:tt)*])? SerializeType<$interf:ty> for $type:ty
+        $(where [$($where_preds:tt)*])?;
+        $($rest:tt)*
+    ) => {
+        impl<'__s, $($($impl_params)*)?> $crate::erased_types::SerializeType<'__s> for $type
+        where
+            $($($where_preds)*)?
+        {
+            type Interface = $interf;
+
+            fn serialize_impl(
+                &'__s self,
+            ) -> $crate::pmr::Result<
+                $crate::external_types::bincode::BincodeBox,
+                $crate::std_types::RBoxError,
+            > {
+                $crate::external_types::bincode::BincodeBox::encode(self)
+            }
+        }
+
+        $crate::impl_bincode_proxy! { $($rest)* }
+    };
+    (
+        impl $([$($impl_params:tt)*])? DeserializeDyn<$dyn_trait:ty> for $interf:ty
+        $(where [$($where_preds:tt)*])?
+        = $function:expr;
+        $($rest:tt)*
+    ) => {
+        impl<'__borr, $($($impl_params)*)?>
+            $crate::erased_types::DeserializeDyn<'__borr, $dyn_trait>
+        for $interf
+        where
+            $($($where_preds)*)?
+        {
+            type Proxy = $crate::external_types::bincode::BincodeBox;
+
+            fn deserialize_dyn(
+                s: $crate::external_types::bincode::BincodeBox,
+            ) -> $crate::pmr::Result<$dyn_trait, $crate::std_types::RBoxError> {
+                $function(s.as_rslice()).into_result()
+            }
+        }
+
+        $crate::impl_bincode_proxy! { $($rest)* }
+    };
+    (
+        impl $([$($impl_params:tt)*])? SerializeEnum<$enum:ty> for $interf:ty
+        $(where [$($where_preds:tt)*])?;
+        $($rest:tt)*
+    ) => {
+        impl<$($($impl_params)*)?> $crate::nonexhaustive_enum::SerializeEnum<$enum> for $interf
+        where
+            $($($where_preds)*)?
+        {
+            type Proxy = $crate::external_types::bincode::BincodeBox;
+
+            fn serialize_enum(
+                this: &$enum,
+            ) -> $crate::pmr::Result<
+                $crate::external_types::bincode::BincodeBox,
+                $crate::std_types::RBoxError,
+            > {
+                $crate::external_types::bincode::BincodeBox::encode(this)
+            }
+        }
+
+        $crate::impl_bincode_proxy! { $($rest)* }
+    };
+    (
+        impl $([$($impl_params:tt)*])? DeserializeEnum<$nonexhaustive:ty> for $interf:ty
+        $(where [$($where_preds:tt)*])?
+        = $function:expr;
+        $($rest:tt)*
+    ) => {
+        impl<'__borr, $($($impl_params)*)?>
+            $crate::nonexhaustive_enum::DeserializeEnum<'__borr, $nonexhaustive>
+        for $interf
+        where
+            $($($where_preds)*)?
+        {
+            type Proxy = $crate::external_types::bincode::BincodeBox;
+
+            fn deserialize_enum(
+                s: $crate::external_types::bincode::BincodeBox,
+            ) -> $crate::pmr::Result<$nonexhaustive, $crate::std_types::RBoxError> {
+                $function(s.as_rslice()).into_result()
+            }
+        }
+
+        $crate::impl_bincode_proxy! { $($rest)* }
+    };
+    (
+        impl $([$($impl_params:tt)*])? DeserializeEnum<$enum:ty> for $interf:ty
+        $(where [$($where_preds:tt)*])?;
+        $($rest:tt)*
+    ) => {
+        impl<'__borr, $($($impl_params)*,)? __S, __I>
+            $crate::nonexhaustive_enum::DeserializeEnum<
+                '__borr,
+                $crate::nonexhaustive_enum::NonExhaustive<$enum, __S, __I>,
+            >
+        for $interf
+        where
+            $enum: $crate::nonexhaustive_enum::GetVTable<__S, __I>,
+            $($($where_preds)*)?
+        {
+            type Proxy = $crate::external_types::bincode::BincodeBox;
+
+            fn deserialize_enum(
+                s: $crate::external_types::bincode::BincodeBox,
+            ) -> $crate::pmr::Result<
+                $crate::nonexhaustive_enum::NonExhaustive<$enum, __S, __I>,
+                $crate::std_types::RBoxError,
+            > {
+                s.decode::<$enum>()
+                    .map($crate::nonexhaustive_enum::NonExhaustive::with_storage_and_interface)
+            }
+        }
+
+        $crate::impl_bincode_proxy! { $($rest)* }
+    };
+}
+
+#[cfg(all(test, not(feature = "only_new_tests")))]
+mod tests {
+    use super::*;
+
+    use crate::{
+        nonexhaustive_enum::NonExhaustive,
+        std_types::{RBox, RErr, ROk, RResult, RString},
+        DynTrait, StableAbi,
+    };
+
+    #[test]
+    fn encode_decode() {
+        let value = (RString::from("hello"), vec![3u64, 5, 8], -1i8);
+        let encoded = BincodeBox::encode(&value).unwrap();
+
+        assert_eq!(encoded.decode::<(RString, Vec<u64>, i8)>().unwrap(), value);
+        assert_eq!(
+            encoded
+                .as_bincode_ref()
+                .decode::<(&str, Vec<u64>, i8)>()
+                .unwrap(),
+            ("hello", vec![3, 5, 8], -1),
+        );
+
+        let mut trailing = encoded.clone().into_bytes();
+        trailing.push(0);
+        assert!(BincodeBox::from_bytes(trailing)
+            .decode::<(RString, Vec<u64>, i8)>()
+            .is_err());
+
+        assert!(encoded.decode::<(RString, RString)>().is_err());
+    }
+
+    #[test]
+    fn proxies_in_other_formats() {
+        let encoded = BincodeBox::encode(&1000u32).unwrap();
+
+        let json = serde_json::to_string(&encoded).unwrap();
+        assert_eq!(serde_json::from_str::<BincodeBox>(&json).unwrap(), encoded);
+
+        let nested = BincodeBox::encode(&encoded).unwrap();
+        assert_eq!(nested.decode::<BincodeBox>().unwrap(), encoded);
+
+        let inner = nested.decode::<BincodeRef<'_>>().unwrap();
+        assert_eq!(inner, encoded.as_bincode_ref());
+        assert_eq!(inner.to_bincode_box(), encoded);
+        assert_eq!(inner.decode::<u32>().unwrap(), 1000);
+
+        assert!(serde_json::from_str::<BincodeRef<'_>>(&json).is_err());
+    }
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Foo<T> {
+        name: T,
+        value: u32,
+    }
+
+    type FooBox = DynTrait<'static, RBox<()>, FooInterface>;
+
+    #[repr(C)]
+    #[derive(StableAbi)]
+    #[sabi(impl_InterfaceType(Debug, Serialize, Deserialize))]
+    struct FooInterface;
+
+    impl_bincode_proxy! {
+        impl SerializeProxyType for FooInterface;
+        impl[T] SerializeType<FooInterface> for Foo<T> where [T: Serialize];
+        impl DeserializeDyn<FooBox> for FooInterface = deserialize_foo;
+    }
+
+    extern "C" fn deserialize_foo(s: RSlice<'_, u8>) -> RResult<FooBox, RBoxError> {
+        extern_fn_panic_handling! {
+            match BincodeRef::from_rslice(s).decode::<Foo<RString>>() {
+                Ok(x) => ROk(DynTrait::from_value(x)),
+                Err(e) => RErr(e),
+            }
+        }
+    }
+
+    #[test]
+    fn dyn_trait_proxy() {
+        let foo = Foo {
+            name: RString::from("hello"),
+            value: 3,
+        };
+        let boxed = FooBox::from_value(foo.clone());
+
+        let encoded = BincodeBox::encode(&boxed).unwrap();
+        assert_eq!(
+            encoded
+                .decode::<BincodeBox>()
+                .unwrap()
+                .decode::<Foo<RString>>()
+                .unwrap(),
+            foo,
+        );
+
+        let decoded = encoded.decode::<FooBox>().unwrap();
+        assert_eq!(decoded.downcast_as::<Foo<RString>>().unwrap(), &foo);
+
+        let json = serde_json::to_string(&boxed).unwrap();
+        let decoded = serde_json::from_str::<FooBox>(&json).unwrap();
+        assert_eq!(decoded.downcast_as::<Foo<RString>>().unwrap(), &foo);
+    }
+
+    #[repr(u8)]
+    #[derive(StableAbi, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    #[sabi(kind(WithNonExhaustive(
+        size = 64,
+        traits(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)
+    )))]
+    enum Direct {
+        A,
+        B(i8),
+        C { name: RString },
+    }
+
+    impl_bincode_proxy! {
+        impl SerializeEnum<Direct> for Direct_Interface;
+        impl DeserializeEnum<Direct> for Direct_Interface;
+    }
+
+    #[repr(u8)]
+    #[derive(StableAbi, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    #[sabi(kind(WithNonExhaustive(
+        size = 64,
+        traits(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)
+    )))]
+    enum Delegated {
+        A(u64),
+        B { list: RVec<u32> },
+    }
+
+    impl_bincode_proxy! {
+        impl SerializeEnum<Delegated> for Delegated_Interface;
+        impl DeserializeEnum<Delegated_NE> for Delegated_Interface = deserialize_delegated;
+    }
+
+    extern "C" fn deserialize_delegated(s: RSlice<'_, u8>) -> RResult<Delegated_NE, RBoxError> {
+        extern_fn_panic_handling! {
+            BincodeRef::from_rslice(s)
+                .decode::<Delegated>()
+                .map(NonExhaustive::new)
+                .into()
+        }
+    }
+
+    #[test]
+    fn nonexhaustive_proxy() {
+        for variant in [
+            Direct::A,
+            Direct::B(-3),
+            Direct::C {
+                name: "hello".into(),
+            },
+        ] {
+            let ne = NonExhaustive::new(variant.clone());
+
+            let encoded = BincodeBox::encode(&ne).unwrap();
+            assert_eq!(
+                encoded
+                    .decode::<BincodeBox>()
+                    .unwrap()
+                    .decode::<Direct>()
+                    .unwrap(),
+                variant,
+            );
+            assert_eq!(encoded.decode::<Direct_NE>().unwrap(), ne);
+
+            let json = serde_json::to_string(&ne).unwrap();
+            assert_eq!(serde_json::from_str::<Direct_NE>(&json).unwrap(), ne,);
+        }
+
+        for variant in [
+            Delegated::A(u64::MAX),
+            Delegated::B {
+                list: vec![3, 5, 8].into(),
+            },
+        ] {
+            let ne: Delegated_NE = NonExhaustive::new(variant);
+
+            let encoded = BincodeBox::encode(&ne).unwrap();
+            assert_eq!(encoded.decode::<Delegated_NE>().unwrap(), ne);
+        }
+
+        let invalid = BincodeBox::encode(&BincodeBox::encode(&7u8).unwrap()).unwrap();
+        assert!(invalid.decode::<Delegated_NE>().is_err());
+    }
+}
@@ -25,18 +25,31 @@ use crate::{
     traits::{ErasedType, IntoReprRust},
 };
 
+pub mod broadcast;
+
 mod errors;
 mod extern_fns;
 mod iteration;
+mod select;
 
 #[cfg(all(test, not(feature = "test_miri_track_raw")))]
 mod tests;
 
-use self::errors::{
-    RRecvError, RRecvTimeoutError, RSendError, RSendTimeoutError, RTryRecvError, RTrySendError,
+pub use self::{
+    errors::{
+        RReadyTimeoutError, RRecvError, RRecvTimeoutError, RSendError, RSendTimeoutError,
+        RTryReadyError, RTryRecvError, RTrySendError,
+    },
+    iteration::{RIntoIter, RIter},
+    select::RSelect,
+};
+
+#[doc(hidden)]
+pub use self::select::{
+    __rselect_deadline, __rselect_remaining, __rselect_try_recv, __rselect_try_send,
 };
 
-pub use self::iteration::{RIntoIter, RIter};
+use self::select::{MakeSelectVTable, SelectVTable_Ref};
 
 ///////////////////////////////////////////////////////////////////////////////
 
@@ -447,6 +460,11 @@ impl<T> RReceiver<T> {
         self.vtable
     }
 
+    #[cfg(test)]
+    fn set_vtable_for_testing(&mut self) {
+        self.vtable = MakeVTable::<T>::VTABLE_FOR_TESTING;
+    }
+
     /// Blocks until a value is either received,or the the other end is disconnected.
     ///
     /// If the channel queue is empty,this will block to receive a value.
@@ -805,6 +823,7 @@ struct VTable<T> {
     receiver_len: extern "C" fn(this: RRef<'_, ErasedReceiver<T>>) -> usize,
     #[sabi(last_prefix_field)]
     receiver_capacity: extern "C" fn(this: RRef<'_, ErasedReceiver<T>>) -> ROption<usize>,
+    select_vtable: SelectVTable_Ref,
 }
 
 struct MakeVTable<'a, T>(&'a T);
@@ -828,6 +847,7 @@ impl<'a, T: 'a> MakeVTable<'a, T> {
         receiver_is_full: ErasedReceiver::is_full,
         receiver_len: ErasedReceiver::len,
         receiver_capacity: ErasedReceiver::capacity,
+        select_vtable: MakeSelectVTable::<T>::VTABLE,
     };
 
     staticref! {
@@ -836,4 +856,16 @@ impl<'a, T: 'a> MakeVTable<'a, T> {
 
     // The VTABLE for this type in this executable/library
     const VTABLE: VTable_Ref<T> = VTable_Ref(Self::WM_VALUE.as_prefix());
+
+    #[cfg(test)]
+    staticref! {
+        const WM_FOR_TESTING: WithMetadata<VTable<T>> = WithMetadata::new(VTable {
+            select_vtable: MakeSelectVTable::<T>::VTABLE_FOR_TESTING,
+            ..Self::VALUE
+        })
+    }
+
+    // A VTABLE that looks like it comes from a different executable/library
+    #[cfg(test)]
+    const VTABLE_FOR_TESTING: VTable_Ref<T> = VTable_Ref(Self::WM_FOR_TESTING.as_prefix());
 }
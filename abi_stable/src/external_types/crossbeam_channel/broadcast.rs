@@ -0,0 +1,830 @@
+//! An ffi-safe broadcast channel,
+//! where every value sent is received by all the subscribers of the channel.
+//!
+//! Each subscriber has its own queue,
+//! whose oldest values get dropped if it falls behind the sender(s),
+//! as configured by [`RLagPolicy`].
+//!
+//! # Example
+//!
+#![cfg_attr(not(feature = "test_miri_track_raw"), doc = "```rust")]
+#![cfg_attr(feature = "test_miri_track_raw", doc = "```ignore")]
+//! use abi_stable::external_types::crossbeam_channel::broadcast::{self, RLagPolicy};
+//!
+//! let (tx, rx_a) = broadcast::channel::<&'static str>(8, RLagPolicy::Error);
+//! let rx_b = tx.subscribe();
+//!
+//! // Each plugin would receive one of the receivers.
+//! let handles = vec![rx_a, rx_b]
+//!     .into_iter()
+//!     .map(|rx| std::thread::spawn(move || rx.iter().collect::<Vec<_>>()))
+//!     .collect::<Vec<_>>();
+//!
+//! assert_eq!(tx.send("loaded"), 2);
+//! assert_eq!(tx.send("unloading"), 2);
+//! drop(tx);
+//!
+//! for handle in handles {
+//!     assert_eq!(handle.join().unwrap(), ["loaded", "unloading"]);
+//! }
+//!
+//! ```
+//!
+
+use std::{
+    error::Error as ErrorTrait,
+    fmt::{self, Debug, Display},
+    marker::PhantomData,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, Weak,
+    },
+    time::Duration,
+};
+
+use crossbeam_channel::{Receiver, RecvTimeoutError, Sender, TryRecvError, TrySendError};
+
+use crate::{
+    marker_type::UnsafeIgnoredType,
+    pointer_trait::AsPtr,
+    prefix_type::WithMetadata,
+    sabi_types::RRef,
+    std_types::{RBox, RDuration, RErr, ROk, RResult},
+    traits::{ErasedType, IntoReprRust},
+    StableAbi,
+};
+
+#[cfg(all(test, not(feature = "test_miri_track_raw")))]
+mod tests;
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// What happens when a subscriber's queue is full and a value is sent.
+///
+/// In both cases the oldest value in the subscriber's queue is dropped
+/// to make room for the new value.
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, StableAbi)]
+pub enum RLagPolicy {
+    /// Drops the oldest value without notifying the subscriber.
+    DropOldest,
+    /// Drops the oldest value,
+    /// and the next receive of the subscriber returns a `Lagged` error,
+    /// with the amount of values that it missed.
+    Error,
+}
+
+/// Creates a broadcast channel where each subscriber can hold up to
+/// `capacity` values in its queue,
+/// returning the sender and the first subscriber.
+///
+/// More subscribers can be created with [`RBroadcastSender::subscribe`].
+///
+/// Sending a value clones it for every subscriber but the last one,
+/// which is why this requires `T: Clone`.
+///
+/// # Panics
+///
+/// Panics if `capacity == 0`.
+///
+/// # Example
+///
+#[cfg_attr(not(feature = "test_miri_track_raw"), doc = "```rust")]
+#[cfg_attr(feature = "test_miri_track_raw", doc = "```ignore")]
+/// use abi_stable::external_types::crossbeam_channel::broadcast::{
+///     self, RBroadcastTryRecvError, RLagPolicy,
+/// };
+///
+/// let (tx, rx) = broadcast::channel::<u32>(2, RLagPolicy::Error);
+///
+/// for i in 0..5 {
+///     tx.send(i);
+/// }
+///
+/// assert_eq!(rx.try_recv(), Err(RBroadcastTryRecvError::Lagged(3)));
+/// assert_eq!(rx.try_recv(), Ok(3));
+/// assert_eq!(rx.try_recv(), Ok(4));
+/// assert_eq!(rx.try_recv(), Err(RBroadcastTryRecvError::Empty));
+///
+/// ```
+pub fn channel<T>(
+    capacity: usize,
+    policy: RLagPolicy,
+) -> (RBroadcastSender<T>, RBroadcastReceiver<T>)
+where
+    T: Clone,
+{
+    assert!(
+        capacity != 0,
+        "broadcast channels require a non-zero capacity"
+    );
+
+    let shared = Arc::new(Shared {
+        subscribers: Mutex::new(Subscribers {
+            list: Vec::new(),
+            next_id: 0,
+        }),
+        capacity,
+        policy,
+    });
+    let receiver = Shared::subscribe(&shared);
+    let sender = BroadcastSender { shared };
+    let vtable = MakeVTable::<T>::VTABLE;
+
+    (
+        RBroadcastSender {
+            sender: ErasedSender::from_unerased_value(sender),
+            vtable,
+        },
+        RBroadcastReceiver {
+            receiver: ErasedReceiver::from_unerased_value(receiver),
+            vtable,
+        },
+    )
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// The sender end of a broadcast channel,
+/// created by the [`channel`] function.
+///
+/// Sending never blocks,
+/// subscribers that fall behind lose their oldest values instead.
+///
+/// Once all the senders are dropped,
+/// the subscribers are disconnected after receiving the values in their queue.
+#[repr(C)]
+#[derive(StableAbi)]
+pub struct RBroadcastSender<T> {
+    sender: RBox<ErasedSender<T>>,
+    vtable: VTable_Ref<T>,
+}
+
+impl<T> RBroadcastSender<T> {
+    /// Sends `value` to every current subscriber,
+    /// returning how many subscribers it was sent to.
+    ///
+    /// # Example
+    ///
+    #[cfg_attr(not(feature = "test_miri_track_raw"), doc = "```rust")]
+    #[cfg_attr(feature = "test_miri_track_raw", doc = "```ignore")]
+    /// use abi_stable::external_types::crossbeam_channel::broadcast::{self, RLagPolicy};
+    ///
+    /// let (tx, rx) = broadcast::channel::<u32>(4, RLagPolicy::DropOldest);
+    ///
+    /// assert_eq!(tx.send(3), 1);
+    ///
+    /// let rx2 = tx.subscribe();
+    /// assert_eq!(tx.send(5), 2);
+    ///
+    /// drop(rx);
+    /// assert_eq!(tx.send(8), 1);
+    ///
+    /// assert_eq!(rx2.recv(), Ok(5));
+    /// assert_eq!(rx2.recv(), Ok(8));
+    ///
+    /// ```
+    pub fn send(&self, value: T) -> usize {
+        self.vtable.send()(self.sender.as_rref(), value)
+    }
+
+    /// Creates a new subscriber,
+    /// which receives the values sent after this call.
+    ///
+    /// # Example
+    ///
+    #[cfg_attr(not(feature = "test_miri_track_raw"), doc = "```rust")]
+    #[cfg_attr(feature = "test_miri_track_raw", doc = "```ignore")]
+    /// use abi_stable::external_types::crossbeam_channel::broadcast::{self, RLagPolicy};
+    ///
+    /// let (tx, rx) = broadcast::channel::<u32>(4, RLagPolicy::DropOldest);
+    ///
+    /// tx.send(3);
+    /// let rx2 = tx.subscribe();
+    /// tx.send(5);
+    ///
+    /// assert_eq!(rx.iter().take(2).collect::<Vec<_>>(), [3, 5]);
+    /// assert_eq!(rx2.recv(), Ok(5));
+    ///
+    /// ```
+    pub fn subscribe(&self) -> RBroadcastReceiver<T> {
+        RBroadcastReceiver {
+            receiver: self.vtable.subscribe()(self.sender.as_rref()),
+            vtable: self.vtable,
+        }
+    }
+
+    /// Returns the amount of subscribers of the channel.
+    ///
+    /// # Example
+    ///
+    #[cfg_attr(not(feature = "test_miri_track_raw"), doc = "```rust")]
+    #[cfg_attr(feature = "test_miri_track_raw", doc = "```ignore")]
+    /// use abi_stable::external_types::crossbeam_channel::broadcast::{self, RLagPolicy};
+    ///
+    /// let (tx, rx) = broadcast::channel::<u32>(4, RLagPolicy::DropOldest);
+    /// assert_eq!(tx.receiver_count(), 1);
+    ///
+    /// let rx2 = tx.subscribe();
+    /// assert_eq!(tx.receiver_count(), 2);
+    ///
+    /// drop((rx, rx2));
+    /// assert_eq!(tx.receiver_count(), 0);
+    ///
+    /// ```
+    pub fn receiver_count(&self) -> usize {
+        self.vtable.receiver_count()(self.sender.as_rref())
+    }
+
+    /// Returns the amount of values each subscriber can hold in its queue.
+    ///
+    /// # Example
+    ///
+    #[cfg_attr(not(feature = "test_miri_track_raw"), doc = "```rust")]
+    #[cfg_attr(feature = "test_miri_track_raw", doc = "```ignore")]
+    /// use abi_stable::external_types::crossbeam_channel::broadcast::{self, RLagPolicy};
+    ///
+    /// let (tx, _rx) = broadcast::channel::<u32>(16, RLagPolicy::DropOldest);
+    /// assert_eq!(tx.capacity(), 16);
+    ///
+    /// ```
+    pub fn capacity(&self) -> usize {
+        self.vtable.capacity()(self.sender.as_rref())
+    }
+}
+
+impl<T> Clone for RBroadcastSender<T> {
+    /// Clones this channel end,getting another handle into the channel.
+    ///
+    /// Note that this allocates an RBox<_>.
+    fn clone(&self) -> Self {
+        Self {
+            sender: self.vtable.clone_sender()(self.sender.as_rref()),
+            vtable: self.vtable,
+        }
+    }
+}
+
+impl<T> Debug for RBroadcastSender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt("RBroadcastSender{..}", f)
+    }
+}
+
+unsafe impl<T: Send> Sync for RBroadcastSender<T> {}
+
+unsafe impl<T: Send> Send for RBroadcastSender<T> {}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// A subscriber of a broadcast channel,
+/// which receives every value sent after it subscribed.
+#[repr(C)]
+#[derive(StableAbi)]
+pub struct RBroadcastReceiver<T> {
+    receiver: RBox<ErasedReceiver<T>>,
+    vtable: VTable_Ref<T>,
+}
+
+impl<T> RBroadcastReceiver<T> {
+    /// Blocks until a value is either received,or all the senders are dropped.
+    ///
+    /// # Example
+    ///
+    #[cfg_attr(not(feature = "test_miri_track_raw"), doc = "```rust")]
+    #[cfg_attr(feature = "test_miri_track_raw", doc = "```ignore")]
+    /// use abi_stable::external_types::crossbeam_channel::broadcast::{
+    ///     self, RBroadcastRecvError, RLagPolicy,
+    /// };
+    ///
+    /// let (tx, rx) = broadcast::channel::<u32>(4, RLagPolicy::Error);
+    ///
+    /// std::thread::spawn(move || tx.send(13));
+    ///
+    /// assert_eq!(rx.recv(), Ok(13));
+    /// assert_eq!(rx.recv(), Err(RBroadcastRecvError::Disconnected));
+    ///
+    /// ```
+    pub fn recv(&self) -> Result<T, RBroadcastRecvError> {
+        self.vtable.recv()(self.receiver.as_rref()).into_rust()
+    }
+
+    /// Immediately receives a value,
+    /// returning an error if the queue of this subscriber is empty.
+    ///
+    /// # Example
+    ///
+    #[cfg_attr(not(feature = "test_miri_track_raw"), doc = "```rust")]
+    #[cfg_attr(feature = "test_miri_track_raw", doc = "```ignore")]
+    /// use abi_stable::external_types::crossbeam_channel::broadcast::{
+    ///     self, RBroadcastTryRecvError, RLagPolicy,
+    /// };
+    ///
+    /// let (tx, rx) = broadcast::channel::<u32>(4, RLagPolicy::Error);
+    ///
+    /// assert_eq!(rx.try_recv(), Err(RBroadcastTryRecvError::Empty));
+    ///
+    /// tx.send(3);
+    /// assert_eq!(rx.try_recv(), Ok(3));
+    ///
+    /// drop(tx);
+    /// assert_eq!(rx.try_recv(), Err(RBroadcastTryRecvError::Disconnected));
+    ///
+    /// ```
+    pub fn try_recv(&self) -> Result<T, RBroadcastTryRecvError> {
+        self.vtable.try_recv()(self.receiver.as_rref()).into_rust()
+    }
+
+    /// Blocks until a timeout to receive a value.
+    ///
+    /// # Example
+    ///
+    #[cfg_attr(not(feature = "test_miri_track_raw"), doc = "```rust")]
+    #[cfg_attr(feature = "test_miri_track_raw", doc = "```ignore")]
+    /// use abi_stable::external_types::crossbeam_channel::broadcast::{
+    ///     self, RBroadcastRecvTimeoutError, RLagPolicy,
+    /// };
+    ///
+    /// use std::time::Duration;
+    ///
+    /// let (tx, rx) = broadcast::channel::<u32>(4, RLagPolicy::Error);
+    ///
+    /// let res = rx.recv_timeout(Duration::from_millis(1));
+    /// assert_eq!(res, Err(RBroadcastRecvTimeoutError::Timeout));
+    ///
+    /// ```
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<T, RBroadcastRecvTimeoutError> {
+        self.vtable.recv_timeout()(self.receiver.as_rref(), timeout.into()).into_rust()
+    }
+
+    /// Returns the amount of values in the queue of this subscriber.
+    ///
+    /// # Example
+    ///
+    #[cfg_attr(not(feature = "test_miri_track_raw"), doc = "```rust")]
+    #[cfg_attr(feature = "test_miri_track_raw", doc = "```ignore")]
+    /// use abi_stable::external_types::crossbeam_channel::broadcast::{self, RLagPolicy};
+    ///
+    /// let (tx, rx) = broadcast::channel::<u32>(2, RLagPolicy::DropOldest);
+    ///
+    /// tx.send(0);
+    /// assert_eq!(rx.len(), 1);
+    ///
+    /// tx.send(1);
+    /// tx.send(2);
+    /// assert_eq!(rx.len(), 2);
+    ///
+    /// ```
+    pub fn len(&self) -> usize {
+        self.vtable.len()(self.receiver.as_rref())
+    }
+
+    /// Returns true if there are no values in the queue of this subscriber.
+    ///
+    /// # Example
+    ///
+    #[cfg_attr(not(feature = "test_miri_track_raw"), doc = "```rust")]
+    #[cfg_attr(feature = "test_miri_track_raw", doc = "```ignore")]
+    /// use abi_stable::external_types::crossbeam_channel::broadcast::{self, RLagPolicy};
+    ///
+    /// let (tx, rx) = broadcast::channel::<u32>(2, RLagPolicy::DropOldest);
+    ///
+    /// assert!(rx.is_empty());
+    /// tx.send(0);
+    /// assert!(!rx.is_empty());
+    ///
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Iterates over the received values,
+    /// blocking until all the senders are dropped.
+    ///
+    /// `Lagged` errors are skipped.
+    ///
+    /// # Example
+    ///
+    #[cfg_attr(not(feature = "test_miri_track_raw"), doc = "```rust")]
+    #[cfg_attr(feature = "test_miri_track_raw", doc = "```ignore")]
+    /// use abi_stable::external_types::crossbeam_channel::broadcast::{self, RLagPolicy};
+    ///
+    /// let (tx, rx) = broadcast::channel::<u32>(2, RLagPolicy::Error);
+    ///
+    /// (0..4).for_each(|x| { tx.send(x); });
+    /// drop(tx);
+    ///
+    /// assert_eq!(rx.iter().collect::<Vec<u32>>(), [2, 3]);
+    ///
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = T> + '_ {
+        std::iter::from_fn(move || loop {
+            match self.recv() {
+                Ok(x) => return Some(x),
+                Err(RBroadcastRecvError::Lagged(_)) => continue,
+                Err(RBroadcastRecvError::Disconnected) => return None,
+            }
+        })
+    }
+}
+
+impl<T> Debug for RBroadcastReceiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt("RBroadcastReceiver{..}", f)
+    }
+}
+
+unsafe impl<T: Send> Sync for RBroadcastReceiver<T> {}
+
+unsafe impl<T: Send> Send for RBroadcastReceiver<T> {}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// Error returned by [`RBroadcastReceiver::recv`].
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, StableAbi)]
+pub enum RBroadcastRecvError {
+    /// The subscriber fell behind,and this many values were dropped from its queue.
+    ///
+    /// Only returned with the [`RLagPolicy::Error`] policy.
+    Lagged(u64),
+    /// The queue is empty and all the senders were dropped.
+    Disconnected,
+}
+
+/// Error returned by [`RBroadcastReceiver::try_recv`].
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, StableAbi)]
+pub enum RBroadcastTryRecvError {
+    /// The queue of the subscriber is empty.
+    Empty,
+    /// The subscriber fell behind,and this many values were dropped from its queue.
+    ///
+    /// Only returned with the [`RLagPolicy::Error`] policy.
+    Lagged(u64),
+    /// The queue is empty and all the senders were dropped.
+    Disconnected,
+}
+
+/// Error returned by [`RBroadcastReceiver::recv_timeout`].
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, StableAbi)]
+pub enum RBroadcastRecvTimeoutError {
+    /// The queue of the subscriber stayed empty until the timeout.
+    Timeout,
+    /// The subscriber fell behind,and this many values were dropped from its queue.
+    ///
+    /// Only returned with the [`RLagPolicy::Error`] policy.
+    Lagged(u64),
+    /// The queue is empty and all the senders were dropped.
+    Disconnected,
+}
+
+impl Display for RBroadcastRecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Lagged(n) => write!(f, "The subscriber lagged behind,missing {} values", n),
+            Self::Disconnected => f.pad("Attempting to recv on a disconnected channel"),
+        }
+    }
+}
+
+impl Display for RBroadcastTryRecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => f.pad("Attempting to recv on an empty channel"),
+            Self::Lagged(n) => write!(f, "The subscriber lagged behind,missing {} values", n),
+            Self::Disconnected => f.pad("Attempting to recv on a disconnected channel"),
+        }
+    }
+}
+
+impl Display for RBroadcastRecvTimeoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Timeout => f.pad("Timed out while attempting to recv on an empty channel"),
+            Self::Lagged(n) => write!(f, "The subscriber lagged behind,missing {} values", n),
+            Self::Disconnected => f.pad("Attempting to recv on a disconnected channel"),
+        }
+    }
+}
+
+impl ErrorTrait for RBroadcastRecvError {}
+
+impl ErrorTrait for RBroadcastTryRecvError {}
+
+impl ErrorTrait for RBroadcastRecvTimeoutError {}
+
+///////////////////////////////////////////////////////////////////////////////
+
+struct Shared<T> {
+    subscribers: Mutex<Subscribers<T>>,
+    capacity: usize,
+    policy: RLagPolicy,
+}
+
+struct Subscribers<T> {
+    list: Vec<SubscriberQueue<T>>,
+    next_id: u64,
+}
+
+/// The sender's side of the queue of a subscriber.
+struct SubscriberQueue<T> {
+    id: u64,
+    sender: Sender<T>,
+    // Used to drop the oldest value when the queue is full.
+    receiver: Receiver<T>,
+    lagged: Arc<AtomicU64>,
+}
+
+impl<T> Shared<T> {
+    fn subscribe(this: &Arc<Self>) -> Subscription<T> {
+        let (sender, receiver) = crossbeam_channel::bounded(this.capacity);
+        let lagged = Arc::new(AtomicU64::new(0));
+
+        let mut subscribers = this.subscribers.lock().unwrap_or_else(|e| e.into_inner());
+        let id = subscribers.next_id;
+        subscribers.next_id += 1;
+        subscribers.list.push(SubscriberQueue {
+            id,
+            sender,
+            receiver: receiver.clone(),
+            lagged: lagged.clone(),
+        });
+
+        Subscription {
+            id,
+            receiver,
+            lagged,
+            report_lag: this.policy == RLagPolicy::Error,
+            shared: Arc::downgrade(this),
+        }
+    }
+}
+
+/// The unerased type of `ErasedSender`
+struct BroadcastSender<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T: Clone> BroadcastSender<T> {
+    fn send(&self, value: T) -> usize {
+        let subscribers = self
+            .shared
+            .subscribers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+
+        let mut value = Some(value);
+        let len = subscribers.list.len();
+        for (i, queue) in subscribers.list.iter().enumerate() {
+            let mut elem = if i + 1 == len {
+                value
+                    .take()
+                    .expect("the value is only taken by the last subscriber")
+            } else {
+                value
+                    .clone()
+                    .expect("the value is only taken by the last subscriber")
+            };
+
+            // Dropping the oldest values until there's space for `elem`.
+            loop {
+                match queue.sender.try_send(elem) {
+                    Ok(()) => break,
+                    Err(TrySendError::Full(x)) => {
+                        elem = x;
+                        if queue.receiver.try_recv().is_ok() {
+                            queue.lagged.fetch_add(1, Ordering::SeqCst);
+                        }
+                    }
+                    Err(TrySendError::Disconnected(_)) => {
+                        unreachable!("the queue holds a receiver")
+                    }
+                }
+            }
+        }
+        len
+    }
+}
+
+impl<T> Clone for BroadcastSender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+/// The unerased type of `ErasedReceiver`
+struct Subscription<T> {
+    id: u64,
+    receiver: Receiver<T>,
+    lagged: Arc<AtomicU64>,
+    report_lag: bool,
+    // The senders own the `Shared`,
+    // so that subscribers are disconnected once all senders are dropped.
+    shared: Weak<Shared<T>>,
+}
+
+impl<T> Subscription<T> {
+    fn take_lagged(&self) -> Option<u64> {
+        if self.report_lag {
+            match self.lagged.swap(0, Ordering::SeqCst) {
+                0 => None,
+                n => Some(n),
+            }
+        } else {
+            None
+        }
+    }
+}
+
+impl<T> Drop for Subscription<T> {
+    fn drop(&mut self) {
+        if let Some(shared) = self.shared.upgrade() {
+            let mut subscribers = shared.subscribers.lock().unwrap_or_else(|e| e.into_inner());
+            let id = self.id;
+            subscribers.list.retain(|queue| queue.id != id);
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+#[repr(C)]
+#[derive(StableAbi)]
+struct ErasedSender<T>(PhantomData<T>, UnsafeIgnoredType<BroadcastSender<T>>);
+
+impl<T> ErasedType<'_> for ErasedSender<T> {
+    type Unerased = BroadcastSender<T>;
+}
+
+#[repr(C)]
+#[derive(StableAbi)]
+struct ErasedReceiver<T>(PhantomData<T>, UnsafeIgnoredType<Subscription<T>>);
+
+impl<T> ErasedType<'_> for ErasedReceiver<T> {
+    type Unerased = Subscription<T>;
+}
+
+macro_rules! erased_fns {
+    (
+        erased=$erased:ident
+        unerased=$unerased:ident
+    ) => {
+        impl<T> $erased<T> {
+            fn from_unerased_value(value: $unerased<T>) -> RBox<Self> {
+                unsafe {
+                    let boxed = RBox::new(value);
+                    ErasedType::from_unerased(boxed)
+                }
+            }
+
+            fn run<'a, F, R>(this: RRef<'a, Self>, f: F) -> R
+            where
+                F: FnOnce(&'a $unerased<T>) -> R,
+            {
+                extern_fn_panic_handling! {
+                    unsafe{
+                        f(Self::downcast_into(this).get())
+                    }
+                }
+            }
+        }
+    };
+}
+
+erased_fns! {
+    erased=ErasedSender
+    unerased=BroadcastSender
+}
+
+erased_fns! {
+    erased=ErasedReceiver
+    unerased=Subscription
+}
+
+impl<T> ErasedSender<T> {
+    extern "C" fn send(this: RRef<'_, Self>, value: T) -> usize
+    where
+        T: Clone,
+    {
+        Self::run(this, |this| this.send(value))
+    }
+    extern "C" fn subscribe(this: RRef<'_, Self>) -> RBox<ErasedReceiver<T>> {
+        Self::run(this, |this| {
+            ErasedReceiver::from_unerased_value(Shared::subscribe(&this.shared))
+        })
+    }
+    extern "C" fn clone(this: RRef<'_, Self>) -> RBox<Self> {
+        Self::run(this, |this| Self::from_unerased_value(this.clone()))
+    }
+    extern "C" fn receiver_count(this: RRef<'_, Self>) -> usize {
+        Self::run(this, |this| {
+            let subscribers = this
+                .shared
+                .subscribers
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
+            subscribers.list.len()
+        })
+    }
+    extern "C" fn capacity(this: RRef<'_, Self>) -> usize {
+        Self::run(this, |this| this.shared.capacity)
+    }
+}
+
+impl<T> ErasedReceiver<T> {
+    extern "C" fn recv(this: RRef<'_, Self>) -> RResult<T, RBroadcastRecvError> {
+        Self::run(this, |this| {
+            if let Some(n) = this.take_lagged() {
+                return RErr(RBroadcastRecvError::Lagged(n));
+            }
+            match this.receiver.recv() {
+                Ok(x) => ROk(x),
+                Err(_) => RErr(RBroadcastRecvError::Disconnected),
+            }
+        })
+    }
+    extern "C" fn try_recv(this: RRef<'_, Self>) -> RResult<T, RBroadcastTryRecvError> {
+        Self::run(this, |this| {
+            if let Some(n) = this.take_lagged() {
+                return RErr(RBroadcastTryRecvError::Lagged(n));
+            }
+            match this.receiver.try_recv() {
+                Ok(x) => ROk(x),
+                Err(TryRecvError::Empty) => RErr(RBroadcastTryRecvError::Empty),
+                Err(TryRecvError::Disconnected) => RErr(RBroadcastTryRecvError::Disconnected),
+            }
+        })
+    }
+    extern "C" fn recv_timeout(
+        this: RRef<'_, Self>,
+        timeout: RDuration,
+    ) -> RResult<T, RBroadcastRecvTimeoutError> {
+        Self::run(this, |this| {
+            if let Some(n) = this.take_lagged() {
+                return RErr(RBroadcastRecvTimeoutError::Lagged(n));
+            }
+            match this.receiver.recv_timeout(timeout.into()) {
+                Ok(x) => ROk(x),
+                Err(RecvTimeoutError::Timeout) => RErr(RBroadcastRecvTimeoutError::Timeout),
+                Err(RecvTimeoutError::Disconnected) => {
+                    RErr(RBroadcastRecvTimeoutError::Disconnected)
+                }
+            }
+        })
+    }
+    extern "C" fn len(this: RRef<'_, Self>) -> usize {
+        Self::run(this, |this| this.receiver.len())
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+#[repr(C)]
+#[derive(StableAbi)]
+#[sabi(kind(Prefix))]
+#[sabi(missing_field(panic))]
+struct VTable<T> {
+    send: extern "C" fn(this: RRef<'_, ErasedSender<T>>, T) -> usize,
+    subscribe: extern "C" fn(this: RRef<'_, ErasedSender<T>>) -> RBox<ErasedReceiver<T>>,
+    clone_sender: extern "C" fn(this: RRef<'_, ErasedSender<T>>) -> RBox<ErasedSender<T>>,
+    receiver_count: extern "C" fn(this: RRef<'_, ErasedSender<T>>) -> usize,
+    capacity: extern "C" fn(this: RRef<'_, ErasedSender<T>>) -> usize,
+
+    recv: extern "C" fn(this: RRef<'_, ErasedReceiver<T>>) -> RResult<T, RBroadcastRecvError>,
+    try_recv:
+        extern "C" fn(this: RRef<'_, ErasedReceiver<T>>) -> RResult<T, RBroadcastTryRecvError>,
+    recv_timeout: extern "C" fn(
+        this: RRef<'_, ErasedReceiver<T>>,
+        timeout: RDuration,
+    ) -> RResult<T, RBroadcastRecvTimeoutError>,
+    #[sabi(last_prefix_field)]
+    len: extern "C" fn(this: RRef<'_, ErasedReceiver<T>>) -> usize,
+}
+
+struct MakeVTable<'a, T>(PhantomData<&'a T>);
+
+impl<'a, T: Clone + 'a> MakeVTable<'a, T> {
+    const VALUE: VTable<T> = VTable {
+        send: ErasedSender::send,
+        subscribe: ErasedSender::subscribe,
+        clone_sender: ErasedSender::clone,
+        receiver_count: ErasedSender::receiver_count,
+        capacity: ErasedSender::capacity,
+
+        recv: ErasedReceiver::recv,
+        try_recv: ErasedReceiver::try_recv,
+        recv_timeout: ErasedReceiver::recv_timeout,
+        len: ErasedReceiver::len,
+    };
+
+    staticref! {
+        const WM_VALUE: WithMetadata<VTable<T>> = WithMetadata::new(Self::VALUE)
+    }
+
+    // The VTABLE for this type in this executable/library
+    const VTABLE: VTable_Ref<T> = VTable_Ref(Self::WM_VALUE.as_prefix());
+}
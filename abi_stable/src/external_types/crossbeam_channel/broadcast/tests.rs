@@ -0,0 +1,112 @@
+use super::*;
+
+use std::thread;
+
+#[test]
+#[should_panic]
+fn zero_capacity() {
+    let _ = channel::<()>(0, RLagPolicy::Error);
+}
+
+#[test]
+fn every_subscriber_receives() {
+    let (tx, rx0) = channel::<String>(16, RLagPolicy::Error);
+    let receivers = vec![rx0, tx.subscribe(), tx.clone().subscribe()];
+    assert_eq!(tx.receiver_count(), 3);
+
+    let handles = receivers
+        .into_iter()
+        .map(|rx| thread::spawn(move || rx.iter().collect::<Vec<String>>()))
+        .collect::<Vec<_>>();
+
+    let tx2 = tx.clone();
+    for i in 0..10 {
+        assert_eq!(tx2.send(i.to_string()), 3);
+    }
+    drop(tx);
+    drop(tx2);
+
+    let expected = (0..10).map(|i| i.to_string()).collect::<Vec<String>>();
+    for handle in handles {
+        assert_eq!(handle.join().unwrap(), expected);
+    }
+}
+
+#[test]
+fn drop_oldest_policy() {
+    let (tx, rx) = channel::<u32>(3, RLagPolicy::DropOldest);
+    let rx2 = tx.subscribe();
+
+    for i in 0..10 {
+        tx.send(i);
+    }
+    assert_eq!(rx.len(), 3);
+
+    assert_eq!(rx.try_recv(), Ok(7));
+    assert_eq!(rx.recv(), Ok(8));
+    assert_eq!(rx.recv_timeout(Duration::from_millis(1)), Ok(9));
+    assert_eq!(rx.try_recv(), Err(RBroadcastTryRecvError::Empty));
+
+    drop(tx);
+    assert_eq!(rx2.iter().collect::<Vec<u32>>(), [7, 8, 9]);
+    assert_eq!(rx.recv(), Err(RBroadcastRecvError::Disconnected));
+}
+
+#[test]
+fn error_policy() {
+    let (tx, rx) = channel::<u32>(2, RLagPolicy::Error);
+
+    (0..5).for_each(|i| {
+        tx.send(i);
+    });
+    assert_eq!(rx.recv(), Err(RBroadcastRecvError::Lagged(3)));
+    assert_eq!(rx.recv(), Ok(3));
+
+    (5..8).for_each(|i| {
+        tx.send(i);
+    });
+    assert_eq!(
+        rx.recv_timeout(Duration::from_millis(1)),
+        Err(RBroadcastRecvTimeoutError::Lagged(2))
+    );
+    assert_eq!(rx.try_recv(), Ok(6));
+    assert_eq!(rx.try_recv(), Ok(7));
+    assert_eq!(
+        rx.recv_timeout(Duration::from_millis(1)),
+        Err(RBroadcastRecvTimeoutError::Timeout)
+    );
+}
+
+#[test]
+fn dropped_subscribers() {
+    let (tx, rx) = channel::<u32>(2, RLagPolicy::Error);
+    let rx2 = tx.subscribe();
+
+    drop(rx);
+    assert_eq!(tx.receiver_count(), 1);
+    assert_eq!(tx.send(0), 1);
+
+    drop(rx2);
+    assert_eq!(tx.receiver_count(), 0);
+    assert_eq!(tx.send(1), 0);
+
+    let rx3 = tx.subscribe();
+    assert_eq!(tx.send(2), 1);
+    assert_eq!(rx3.try_recv(), Ok(2));
+}
+
+#[test]
+fn subscriber_outlives_senders() {
+    let (tx, rx) = channel::<Arc<()>>(4, RLagPolicy::DropOldest);
+    let arc = Arc::new(());
+
+    tx.send(arc.clone());
+    tx.send(arc.clone());
+    drop(tx);
+    assert_eq!(Arc::strong_count(&arc), 3);
+
+    assert!(rx.recv().is_ok());
+    assert!(rx.recv().is_ok());
+    assert_eq!(rx.recv(), Err(RBroadcastRecvError::Disconnected));
+    assert_eq!(Arc::strong_count(&arc), 1);
+}
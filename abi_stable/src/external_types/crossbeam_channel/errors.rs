@@ -2,6 +2,8 @@ use super::*;
 
 use crate::StableAbi;
 
+use crossbeam_channel::{ReadyTimeoutError, TryReadyError};
+
 use std::{
     error::Error as ErrorTrait,
     fmt::{self, Debug, Display},
@@ -9,11 +11,16 @@ use std::{
 
 ///////////////////////////////////////////////////////////////////////////////
 
+/// The error returned when sending on a disconnected channel,
+/// containing the value that couldn't be sent.
+///
+/// This is the ffi-safe equivalent of `crossbeam_channel::SendError`.
 #[repr(transparent)]
 #[derive(PartialEq, Eq, Clone, Copy, StableAbi)]
 pub struct RSendError<T>(pub T);
 
 impl<T> RSendError<T> {
+    /// Gets the value that couldn't be sent.
     pub fn into_inner(self) -> T {
         self.0
     }
@@ -51,6 +58,9 @@ impl_into_rust_repr! {
 
 ///////////////////////////////////////////////////////////////////////////////
 
+/// The error returned when receiving from an empty, disconnected channel.
+///
+/// This is the ffi-safe equivalent of `crossbeam_channel::RecvError`.
 #[repr(C)]
 #[derive(PartialEq, Eq, Clone, Copy, StableAbi)]
 pub struct RRecvError;
@@ -87,23 +97,32 @@ impl_into_rust_repr! {
 
 ///////////////////////////////////////////////////////////////////////////////
 
+/// The error returned by `try_send`,
+/// containing the value that couldn't be sent.
+///
+/// This is the ffi-safe equivalent of `crossbeam_channel::TrySendError`.
 #[repr(u8)]
 #[derive(PartialEq, Eq, Clone, Copy, StableAbi)]
 pub enum RTrySendError<T> {
+    /// The channel was full.
     Full(T),
+    /// The channel was disconnected.
     Disconnected(T),
 }
 
 impl<T> RTrySendError<T> {
+    /// Gets the value that couldn't be sent.
     pub fn into_inner(self) -> T {
         match self {
             RTrySendError::Full(v) => v,
             RTrySendError::Disconnected(v) => v,
         }
     }
+    /// Whether the channel was full.
     pub fn is_full(&self) -> bool {
         matches!(self, RTrySendError::Full { .. })
     }
+    /// Whether the channel was disconnected.
     pub fn is_disconnected(&self) -> bool {
         matches!(self, RTrySendError::Disconnected { .. })
     }
@@ -157,18 +176,25 @@ impl_into_rust_repr! {
 
 ///////////////////////////////////////////////////////////////////////////////
 
+/// The error returned by `try_recv`.
+///
+/// This is the ffi-safe equivalent of `crossbeam_channel::TryRecvError`.
 #[repr(u8)]
 #[derive(Debug, PartialEq, Eq, Clone, Copy, StableAbi)]
 pub enum RTryRecvError {
+    /// The channel was empty.
     Empty,
+    /// The channel was empty and disconnected.
     Disconnected,
 }
 
 impl RTryRecvError {
+    /// Whether the channel was empty.
     pub fn is_empty(&self) -> bool {
         *self == RTryRecvError::Empty
     }
 
+    /// Whether the channel was disconnected.
     pub fn is_disconnected(&self) -> bool {
         *self == RTryRecvError::Disconnected
     }
@@ -177,8 +203,8 @@ impl RTryRecvError {
 impl Display for RTryRecvError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let msg = match self {
-            RTryRecvError::Empty { .. } => "Attempting to recv on an empty channel",
-            RTryRecvError::Disconnected { .. } => "Attempting to recv on a disconnected channel",
+            RTryRecvError::Empty => "Attempting to recv on an empty channel",
+            RTryRecvError::Disconnected => "Attempting to recv on a disconnected channel",
         };
         f.pad(msg)
     }
@@ -212,23 +238,32 @@ impl_into_rust_repr! {
 
 ///////////////////////////////////////////////////////////////////////////////
 
+/// The error returned by `send_timeout`,
+/// containing the value that couldn't be sent.
+///
+/// This is the ffi-safe equivalent of `crossbeam_channel::SendTimeoutError`.
 #[repr(u8)]
 #[derive(PartialEq, Eq, Clone, Copy, StableAbi)]
 pub enum RSendTimeoutError<T> {
+    /// The channel stayed full until the timeout.
     Timeout(T),
+    /// The channel was disconnected.
     Disconnected(T),
 }
 
 impl<T> RSendTimeoutError<T> {
+    /// Gets the value that couldn't be sent.
     pub fn into_inner(self) -> T {
         match self {
             RSendTimeoutError::Timeout(v) => v,
             RSendTimeoutError::Disconnected(v) => v,
         }
     }
+    /// Whether the channel stayed full until the timeout.
     pub fn is_timeout(&self) -> bool {
         matches!(self, RSendTimeoutError::Timeout { .. })
     }
+    /// Whether the channel was disconnected.
     pub fn is_disconnected(&self) -> bool {
         matches!(self, RSendTimeoutError::Disconnected { .. })
     }
@@ -286,27 +321,34 @@ impl_into_rust_repr! {
 
 ///////////////////////////////////////////////////////////////////////////////
 
+/// The error returned by `recv_timeout`.
+///
+/// This is the ffi-safe equivalent of `crossbeam_channel::RecvTimeoutError`.
 #[repr(u8)]
 #[derive(Debug, PartialEq, Eq, Clone, Copy, StableAbi)]
 pub enum RRecvTimeoutError {
+    /// The channel stayed empty until the timeout.
     Timeout,
+    /// The channel was empty and disconnected.
     Disconnected,
 }
 
 impl RRecvTimeoutError {
+    /// Whether the channel stayed empty until the timeout.
     pub fn is_timeout(&self) -> bool {
-        matches!(self, RRecvTimeoutError::Timeout { .. })
+        matches!(self, RRecvTimeoutError::Timeout)
     }
+    /// Whether the channel was disconnected.
     pub fn is_disconnected(&self) -> bool {
-        matches!(self, RRecvTimeoutError::Disconnected { .. })
+        matches!(self, RRecvTimeoutError::Disconnected)
     }
 }
 
 impl Display for RRecvTimeoutError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let msg = match self {
-            RRecvTimeoutError::Timeout { .. } => "Timed out while attempting to recv on a channel",
-            RRecvTimeoutError::Disconnected { .. } => {
+            RRecvTimeoutError::Timeout => "Timed out while attempting to recv on a channel",
+            RRecvTimeoutError::Disconnected => {
                 "Attempting to recv on a disconnected channel"
             }
         };
@@ -343,3 +385,69 @@ impl_into_rust_repr! {
 }
 
 ///////////////////////////////////////////////////////////////////////////////
+
+/// The error returned by [`RSelect::try_ready`](super::RSelect::try_ready)
+/// when none of the operations are ready.
+///
+/// This is the ffi-safe equivalent of `crossbeam_channel::TryReadyError`.
+#[repr(C)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, StableAbi)]
+pub struct RTryReadyError;
+
+impl Display for RTryReadyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("All operations in the select would block")
+    }
+}
+
+impl ErrorTrait for RTryReadyError {}
+
+impl_from_rust_repr! {
+    impl From<TryReadyError> for RTryReadyError {
+        fn(_this){
+            RTryReadyError
+        }
+    }
+}
+
+impl_into_rust_repr! {
+    impl Into<TryReadyError> for RTryReadyError {
+        fn(_this){
+            TryReadyError
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// The error returned by [`RSelect::ready_timeout`](super::RSelect::ready_timeout)
+/// when none of the operations became ready before the timeout.
+///
+/// This is the ffi-safe equivalent of `crossbeam_channel::ReadyTimeoutError`.
+#[repr(C)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, StableAbi)]
+pub struct RReadyTimeoutError;
+
+impl Display for RReadyTimeoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("Timed out while waiting on a select")
+    }
+}
+
+impl ErrorTrait for RReadyTimeoutError {}
+
+impl_from_rust_repr! {
+    impl From<ReadyTimeoutError> for RReadyTimeoutError {
+        fn(_this){
+            RReadyTimeoutError
+        }
+    }
+}
+
+impl_into_rust_repr! {
+    impl Into<ReadyTimeoutError> for RReadyTimeoutError {
+        fn(_this){
+            ReadyTimeoutError
+        }
+    }
+}
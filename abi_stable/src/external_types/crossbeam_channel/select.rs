@@ -0,0 +1,578 @@
+use super::*;
+
+use std::time::Instant;
+
+use crossbeam_channel::Select;
+
+use crate::{
+    marker_type::ErasedObject,
+    sabi_types::RMut,
+    std_types::{utypeid::new_utypeid, RDuration, RSlice, RVec, UTypeId},
+    traits::IntoReprC,
+};
+
+/// Waits on multiple channel operations,
+/// over [`RReceiver`]s and [`RSender`]s of different element types.
+///
+/// This is the ffi-safe equivalent of the readiness API of `crossbeam_channel::Select`,
+/// it only reports which operation is ready,
+/// the operation must then be completed with
+/// `try_recv`/`try_send` (which can fail if another thread completed it first).
+///
+/// The [`rselect`](crate::rselect) macro is a more convenient way to select over
+/// channels, which also completes the selected operation.
+///
+/// If all the channels were created by the same dynamic library (or executable),
+/// waiting is done by `crossbeam_channel` in that library,
+/// otherwise the operations are polled with an exponential backoff.
+///
+/// # Panics
+///
+/// The methods of this type panic if a channel was created by
+/// a version of `abi_stable` that didn't support `RSelect`.
+///
+/// # Example
+///
+#[cfg_attr(not(feature = "test_miri_track_raw"), doc = "```rust")]
+#[cfg_attr(feature = "test_miri_track_raw", doc = "```ignore")]
+/// use abi_stable::external_types::crossbeam_channel::{self as mpmc, RSelect};
+///
+/// let (tx0, rx0) = mpmc::unbounded::<u32>();
+/// let (tx1, rx1) = mpmc::unbounded::<&str>();
+///
+/// std::thread::spawn(move || tx1.send("hello").unwrap());
+///
+/// let mut select = RSelect::new();
+/// let index0 = select.recv(&rx0);
+/// let index1 = select.recv(&rx1);
+///
+/// let index = select.ready();
+/// assert_eq!(index, index1);
+/// assert_eq!(rx1.try_recv(), Ok("hello"));
+///
+/// # drop(tx0);
+/// ```
+#[repr(C)]
+#[derive(StableAbi)]
+pub struct RSelect<'a> {
+    operations: RVec<SelectOperation<'a>>,
+}
+
+impl<'a> RSelect<'a> {
+    /// Constructs an empty `RSelect`.
+    pub const fn new() -> Self {
+        Self {
+            operations: RVec::new(),
+        }
+    }
+
+    /// Adds a receive operation, returning the index of the operation.
+    pub fn recv<T>(&mut self, receiver: &'a RReceiver<T>) -> usize {
+        let channel = unsafe { receiver.channel.as_rref().transmute::<ErasedObject>() };
+        self.push(channel, OperationKind::Recv, receiver.vtable())
+    }
+
+    /// Adds a send operation, returning the index of the operation.
+    pub fn send<T>(&mut self, sender: &'a RSender<T>) -> usize {
+        let channel = unsafe { sender.channel.as_rref().transmute::<ErasedObject>() };
+        self.push(channel, OperationKind::Send, sender.vtable())
+    }
+
+    fn push<T>(
+        &mut self,
+        channel: RRef<'a, ErasedObject>,
+        kind: OperationKind,
+        vtable: VTable_Ref<T>,
+    ) -> usize {
+        self.operations.push(SelectOperation {
+            channel,
+            kind,
+            vtable: vtable.select_vtable(),
+        });
+        self.operations.len() - 1
+    }
+
+    /// Returns the index of an operation that's ready, without blocking.
+    ///
+    /// # Example
+    ///
+    #[cfg_attr(not(feature = "test_miri_track_raw"), doc = "```rust")]
+    #[cfg_attr(feature = "test_miri_track_raw", doc = "```ignore")]
+    /// use abi_stable::external_types::crossbeam_channel::{self as mpmc, RSelect};
+    ///
+    /// let (tx0, rx0) = mpmc::bounded::<u32>(1);
+    /// let (tx1, rx1) = mpmc::bounded::<u32>(1);
+    ///
+    /// let mut select = RSelect::new();
+    /// select.recv(&rx0);
+    /// let index = select.send(&tx1);
+    ///
+    /// assert_eq!(select.try_ready(), Ok(index));
+    ///
+    /// tx1.send(3).unwrap();
+    /// assert!(select.try_ready().is_err());
+    ///
+    /// # drop((tx0, rx1));
+    /// ```
+    pub fn try_ready(&mut self) -> Result<usize, RTryReadyError> {
+        self.select(SelectMode::Try).ok_or(RTryReadyError)
+    }
+
+    /// Blocks until one of the operations is ready, returning its index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no operations were added to this `RSelect`.
+    ///
+    /// # Example
+    ///
+    #[cfg_attr(not(feature = "test_miri_track_raw"), doc = "```rust")]
+    #[cfg_attr(feature = "test_miri_track_raw", doc = "```ignore")]
+    /// use abi_stable::external_types::crossbeam_channel::{self as mpmc, RSelect};
+    ///
+    /// let (tx, rx) = mpmc::unbounded::<u32>();
+    ///
+    /// std::thread::spawn(move || tx.send(3).unwrap());
+    ///
+    /// let mut select = RSelect::new();
+    /// let index = select.recv(&rx);
+    ///
+    /// assert_eq!(select.ready(), index);
+    /// assert_eq!(rx.try_recv(), Ok(3));
+    ///
+    /// ```
+    pub fn ready(&mut self) -> usize {
+        assert!(
+            !self.operations.is_empty(),
+            "no operations were added to the RSelect"
+        );
+        self.select(SelectMode::Block)
+            .expect("blocking select must return an index")
+    }
+
+    /// Blocks until one of the operations is ready,
+    /// or `timeout` elapses,returning the index of the ready operation.
+    ///
+    /// # Example
+    ///
+    #[cfg_attr(not(feature = "test_miri_track_raw"), doc = "```rust")]
+    #[cfg_attr(feature = "test_miri_track_raw", doc = "```ignore")]
+    /// use abi_stable::{
+    ///     external_types::crossbeam_channel::{self as mpmc, RReadyTimeoutError, RSelect},
+    ///     std_types::RDuration,
+    /// };
+    ///
+    /// let (tx, rx) = mpmc::unbounded::<u32>();
+    ///
+    /// let mut select = RSelect::new();
+    /// select.recv(&rx);
+    ///
+    /// assert_eq!(
+    ///     select.ready_timeout(RDuration::from_millis(1)),
+    ///     Err(RReadyTimeoutError),
+    /// );
+    ///
+    /// # drop(tx);
+    /// ```
+    pub fn ready_timeout(&mut self, timeout: RDuration) -> Result<usize, RReadyTimeoutError> {
+        self.select(SelectMode::Timeout(timeout))
+            .ok_or(RReadyTimeoutError)
+    }
+
+    fn select(&mut self, mode: SelectMode) -> Option<usize> {
+        let ops = &self.operations[..];
+        let first = match ops.first() {
+            Some(x) => x.vtable,
+            None => {
+                if let SelectMode::Timeout(timeout) = mode {
+                    std::thread::sleep(timeout.into());
+                }
+                return None;
+            }
+        };
+
+        let library = first.select_type_id()();
+        if ops.iter().all(|op| op.vtable.select_type_id()() == library) {
+            unsafe { first.select()(RSlice::from_slice(ops), mode).into_rust() }
+        } else {
+            poll_operations(ops, mode)
+        }
+    }
+}
+
+impl<'a> Default for RSelect<'a> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> Debug for RSelect<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RSelect")
+            .field("operations", &self.operations.len())
+            .finish()
+    }
+}
+
+// Polls `ops` until one of them is ready,used when the channels
+// come from different dynamic libraries.
+fn poll_operations(ops: &[SelectOperation<'_>], mode: SelectMode) -> Option<usize> {
+    const MAX_SLEEP: Duration = Duration::from_millis(1);
+
+    let deadline = match mode {
+        SelectMode::Timeout(timeout) => Some(Instant::now() + Duration::from(timeout)),
+        _ => None,
+    };
+    let mut sleep = Duration::from_micros(1);
+
+    loop {
+        for (i, op) in ops.iter().enumerate() {
+            if unsafe { op.vtable.try_ready()(op.channel, op.kind) } {
+                return Some(i);
+            }
+        }
+
+        let sleep_for = match (mode, deadline) {
+            (SelectMode::Try, _) => return None,
+            (_, Some(deadline)) => match deadline.checked_duration_since(Instant::now()) {
+                Some(remaining) if remaining != Duration::ZERO => sleep.min(remaining),
+                _ => return None,
+            },
+            (_, None) => sleep,
+        };
+        std::thread::sleep(sleep_for);
+        sleep = (sleep * 2).min(MAX_SLEEP);
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+#[repr(C)]
+#[derive(StableAbi)]
+struct SelectOperation<'a> {
+    channel: RRef<'a, ErasedObject>,
+    kind: OperationKind,
+    vtable: SelectVTable_Ref,
+}
+
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, StableAbi)]
+enum OperationKind {
+    Recv,
+    Send,
+}
+
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, StableAbi)]
+enum SelectMode {
+    Try,
+    Timeout(RDuration),
+    Block,
+}
+
+#[repr(C)]
+#[derive(StableAbi)]
+#[sabi(kind(Prefix))]
+#[sabi(missing_field(panic))]
+pub(super) struct SelectVTable {
+    // Identifies the `crossbeam_channel::Select` type of the library that created the channel.
+    select_type_id: extern "C" fn() -> UTypeId,
+    select: unsafe extern "C" fn(
+        operations: RSlice<'_, SelectOperation<'_>>,
+        mode: SelectMode,
+    ) -> ROption<usize>,
+    register: unsafe extern "C" fn(
+        channel: RRef<'_, ErasedObject>,
+        kind: OperationKind,
+        select: RMut<'_, ErasedObject>,
+    ),
+    #[sabi(last_prefix_field)]
+    try_ready: unsafe extern "C" fn(channel: RRef<'_, ErasedObject>, kind: OperationKind) -> bool,
+}
+
+pub(super) struct MakeSelectVTable<'a, T>(PhantomData<&'a T>);
+
+impl<'a, T: 'a> MakeSelectVTable<'a, T> {
+    staticref! {
+        const WM_VALUE: WithMetadata<SelectVTable> = WithMetadata::new(SelectVTable {
+            select_type_id: new_utypeid::<Select<'static>>,
+            select,
+            register: register::<T>,
+            try_ready: try_ready::<T>,
+        })
+    }
+
+    // The select vtable for channels of `T` in this executable/library
+    pub(super) const VTABLE: SelectVTable_Ref = SelectVTable_Ref(Self::WM_VALUE.as_prefix());
+
+    #[cfg(test)]
+    staticref! {
+        const WM_FOR_TESTING: WithMetadata<SelectVTable> = WithMetadata::new(SelectVTable {
+            select_type_id: new_utypeid::<()>,
+            select,
+            register: register::<T>,
+            try_ready: try_ready::<T>,
+        })
+    }
+
+    // A select vtable that looks like it comes from a different executable/library
+    #[cfg(test)]
+    pub(super) const VTABLE_FOR_TESTING: SelectVTable_Ref =
+        SelectVTable_Ref(Self::WM_FOR_TESTING.as_prefix());
+}
+
+unsafe extern "C" fn select(
+    operations: RSlice<'_, SelectOperation<'_>>,
+    mode: SelectMode,
+) -> ROption<usize> {
+    extern_fn_panic_handling! {
+        let mut select = Select::new();
+        for op in operations.iter() {
+            unsafe { op.vtable.register()(op.channel, op.kind, RMut::new(&mut select).transmute()) }
+        }
+        match mode {
+            SelectMode::Try => select.try_ready().ok(),
+            SelectMode::Timeout(timeout) => select.ready_timeout(Duration::from(timeout)).ok(),
+            SelectMode::Block => Some(select.ready()),
+        }.into_c()
+    }
+}
+
+/// Adds the `channel` operation to `select`, a `crossbeam_channel::Select`.
+unsafe extern "C" fn register<T>(
+    channel: RRef<'_, ErasedObject>,
+    kind: OperationKind,
+    select: RMut<'_, ErasedObject>,
+) {
+    extern_fn_panic_handling! {
+        // The lifetime of the `Select` is erased,
+        // it's only used while the channels are borrowed by the `RSelect`.
+        let select = unsafe { select.transmute_into_mut::<Select<'_>>() };
+        match kind {
+            OperationKind::Recv => {
+                let channel = unsafe { channel.transmute::<ErasedReceiver<T>>() };
+                let channel = unsafe { &*(ErasedReceiver::downcast_into(channel).get() as *const _) };
+                select.recv(channel);
+            }
+            OperationKind::Send => {
+                let channel = unsafe { channel.transmute::<ErasedSender<T>>() };
+                let channel = unsafe { &*(ErasedSender::downcast_into(channel).get() as *const _) };
+                select.send(channel);
+            }
+        }
+    }
+}
+
+unsafe extern "C" fn try_ready<T>(channel: RRef<'_, ErasedObject>, kind: OperationKind) -> bool {
+    extern_fn_panic_handling! {
+        let mut select = Select::new();
+        unsafe { register::<T>(channel, kind, RMut::new(&mut select).transmute()) };
+        select.try_ready().is_ok()
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+#[doc(hidden)]
+pub fn __rselect_try_recv<T>(receiver: &RReceiver<T>) -> Option<Result<T, RecvError>> {
+    match receiver.try_recv() {
+        Ok(x) => Some(Ok(x)),
+        Err(TryRecvError::Empty) => None,
+        Err(TryRecvError::Disconnected) => Some(Err(RecvError)),
+    }
+}
+
+#[doc(hidden)]
+pub fn __rselect_try_send<T>(
+    sender: &RSender<T>,
+    value: &mut Option<T>,
+) -> Option<Result<(), SendError<T>>> {
+    let to_send = value.take().expect("rselect: value was already sent");
+    match sender.try_send(to_send) {
+        Ok(()) => Some(Ok(())),
+        Err(TrySendError::Full(v)) => {
+            *value = Some(v);
+            None
+        }
+        Err(TrySendError::Disconnected(v)) => Some(Err(SendError(v))),
+    }
+}
+
+#[doc(hidden)]
+pub fn __rselect_deadline(timeout: Duration) -> Instant {
+    Instant::now() + timeout
+}
+
+#[doc(hidden)]
+pub fn __rselect_remaining(deadline: Instant) -> RDuration {
+    deadline.saturating_duration_since(Instant::now()).into()
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// Waits on multiple [`RReceiver`]/[`RSender`] operations,
+/// running the branch of the first one that completes.
+///
+/// This is a limited equivalent of `crossbeam_channel::select`,
+/// implemented on top of [`RSelect`](crate::external_types::crossbeam_channel::RSelect).
+///
+/// # Syntax
+///
+/// These are the branches that can be used,
+/// each channel expression is evaluated once, before waiting:
+///
+/// - `recv(receiver) -> result => expression`:
+/// receives a value from `receiver` (an `&RReceiver<T>` or `RReceiver<T>` place),
+/// `result` is a pattern for a `Result<T, RecvError>`,
+/// which is an error if the channel is disconnected.
+///
+/// - `send(sender, value) -> result => expression`:
+/// sends `value` through `sender`,
+/// `result` is a pattern for a `Result<(), SendError<T>>`,
+/// which is an error if the channel is disconnected.
+///
+/// - `default => expression`:
+/// runs `expression` if no operation is ready, without blocking.
+///
+/// - `default(timeout) => expression`:
+/// runs `expression` if no operation completes within `timeout` (a `Duration`).
+///
+/// The `default` branch can only be used as the last branch.
+///
+/// `break` and `continue` inside the branches refer to a loop inside the macro,
+/// they can't be used to exit a loop around the macro invocation.
+///
+/// # Example
+///
+#[cfg_attr(not(feature = "test_miri_track_raw"), doc = "```rust")]
+#[cfg_attr(feature = "test_miri_track_raw", doc = "```ignore")]
+/// use abi_stable::{external_types::crossbeam_channel as mpmc, rselect};
+///
+/// use std::time::Duration;
+///
+/// let (tx0, rx0) = mpmc::unbounded::<u32>();
+/// let (tx1, rx1) = mpmc::bounded::<&str>(0);
+/// let (tx2, rx2) = mpmc::bounded::<u8>(1);
+///
+/// std::thread::spawn(move || tx0.send(10).unwrap());
+///
+/// let received = rselect! {
+///     recv(rx0) -> res => res.unwrap(),
+///     recv(rx1) -> res => panic!("{:?}", res),
+/// };
+/// assert_eq!(received, 10);
+///
+/// let sent = rselect! {
+///     send(tx2, 100) -> res => res.is_ok(),
+///     default => false,
+/// };
+/// assert!(sent);
+///
+/// let timed_out = rselect! {
+///     send(tx2, 101) -> _ => false,
+///     default(Duration::from_millis(1)) => true,
+/// };
+/// assert!(timed_out);
+///
+/// assert_eq!(rx2.recv(), Ok(100));
+///
+/// # drop((tx1, rx1));
+/// ```
+#[macro_export]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "channels")))]
+macro_rules! rselect {
+    (
+        @bind [$($bound:tt)*]
+        recv($receiver:expr) -> $res:pat => $body:expr
+        $(, $($rest:tt)*)?
+    ) => ({
+        let __receiver = &$receiver;
+        $crate::rselect!{
+            @bind [$($bound)* (recv __receiver, $res, $body)]
+            $($($rest)*)?
+        }
+    });
+    (
+        @bind [$($bound:tt)*]
+        send($sender:expr, $value:expr) -> $res:pat => $body:expr
+        $(, $($rest:tt)*)?
+    ) => ({
+        let __sender = &$sender;
+        #[allow(unused_mut)]
+        let mut __value = ::std::option::Option::Some($value);
+        $crate::rselect!{
+            @bind [$($bound)* (send __sender __value, $res, $body)]
+            $($($rest)*)?
+        }
+    });
+    (@bind [$($bound:tt)*] default => $default:expr $(,)?) => (
+        $crate::rselect!{
+            @select [$($bound)*]
+            |__select| __select.try_ready().ok(),
+            break $default
+        }
+    );
+    (@bind [$($bound:tt)*] default($timeout:expr) => $default:expr $(,)?) => ({
+        let __deadline = $crate::external_types::crossbeam_channel::__rselect_deadline($timeout);
+        $crate::rselect!{
+            @select [$($bound)*]
+            |__select| __select
+                .ready_timeout(
+                    $crate::external_types::crossbeam_channel::__rselect_remaining(__deadline)
+                )
+                .ok(),
+            break $default
+        }
+    });
+    (@bind [$($bound:tt)*]) => (
+        $crate::rselect!{
+            @select [$($bound)*]
+            |__select| ::std::option::Option::Some(__select.ready()),
+            continue
+        }
+    );
+    (
+        @select [$( ($kind:ident $($chan:ident)*, $res:pat, $body:expr) )*]
+        |$select:ident| $wait:expr,
+        $on_none:expr
+    ) => (
+        loop {
+            let mut $select = $crate::external_types::crossbeam_channel::RSelect::new();
+            $( $crate::rselect!(@register $select, $kind $($chan)*); )*
+            #[allow(unreachable_code, clippy::diverging_sub_expression)]
+            let __index = match $wait {
+                ::std::option::Option::Some(x) => x,
+                ::std::option::Option::None => $on_none,
+            };
+            drop($select);
+
+            let mut __current = 0usize;
+            $(
+                #[allow(unreachable_code, clippy::diverging_sub_expression)]
+                if __index == __current {
+                    if let ::std::option::Option::Some(__res) = $crate::rselect!(@complete $kind $($chan)*) {
+                        let $res = __res;
+                        break $body;
+                    }
+                }
+                __current += 1;
+            )*
+            let _ = __current;
+        }
+    );
+    (@register $select:ident, recv $receiver:ident) => (
+        $select.recv($receiver)
+    );
+    (@register $select:ident, send $sender:ident $value:ident) => (
+        $select.send($sender)
+    );
+    (@complete recv $receiver:ident) => (
+        $crate::external_types::crossbeam_channel::__rselect_try_recv($receiver)
+    );
+    (@complete send $sender:ident $value:ident) => (
+        $crate::external_types::crossbeam_channel::__rselect_try_send($sender, &mut $value)
+    );
+    ($($branches:tt)*) => (
+        $crate::rselect!{@bind [] $($branches)*}
+    );
+}
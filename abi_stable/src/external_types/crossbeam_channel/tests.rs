@@ -2,6 +2,8 @@ use super::*;
 
 use crossbeam_utils::thread::scope as scoped_thread;
 
+use crate::rselect;
+
 #[test]
 fn test_size_methods() {
     let cap = 5;
@@ -256,3 +258,153 @@ fn into_iter() {
 
     assert_ne!(rx.try_recv().err(), None);
 }
+
+#[test]
+#[cfg(not(all(miri, target_os = "windows")))]
+fn select_ready() {
+    let (tx0, rx0) = unbounded::<u32>();
+    let (tx1, rx1) = bounded::<&str>(1);
+
+    let mut select = RSelect::new();
+    let recv_index = select.recv(&rx0);
+    let send_index = select.send(&tx1);
+
+    assert_eq!(select.try_ready(), Ok(send_index));
+    tx1.send("foo").unwrap();
+    assert_eq!(select.try_ready(), Err(RTryReadyError));
+    assert_eq!(
+        select.ready_timeout(RDuration::from_millis(1)),
+        Err(RReadyTimeoutError)
+    );
+
+    scoped_thread(|scope| {
+        scope.spawn(|_| tx0.send(3).unwrap());
+        assert_eq!(select.ready(), recv_index);
+    })
+    .unwrap();
+
+    drop(select);
+    assert_eq!(rx0.try_recv(), Ok(3));
+    assert_eq!(rx1.try_recv(), Ok("foo"));
+}
+
+#[test]
+fn select_disconnected_is_ready() {
+    let (tx, rx) = unbounded::<u32>();
+    drop(tx);
+
+    let mut select = RSelect::new();
+    let index = select.recv(&rx);
+    assert_eq!(select.try_ready(), Ok(index));
+}
+
+#[test]
+fn select_empty() {
+    let mut select = RSelect::new();
+    assert_eq!(select.try_ready(), Err(RTryReadyError));
+    assert_eq!(
+        select.ready_timeout(RDuration::from_millis(1)),
+        Err(RReadyTimeoutError)
+    );
+}
+
+#[test]
+#[cfg(not(all(miri, target_os = "windows")))]
+fn select_mixed_libraries() {
+    let (tx0, rx0) = unbounded::<u32>();
+    let (tx1, mut rx1) = unbounded::<u64>();
+    rx1.set_vtable_for_testing();
+
+    let mut select = RSelect::new();
+    select.recv(&rx0);
+    let index1 = select.recv(&rx1);
+
+    assert!(select.try_ready().is_err());
+    assert!(select.ready_timeout(RDuration::from_millis(5)).is_err());
+
+    scoped_thread(|scope| {
+        scope.spawn(|_| {
+            std::thread::sleep(Duration::from_millis(5));
+            tx1.send(8).unwrap();
+        });
+        assert_eq!(select.ready(), index1);
+    })
+    .unwrap();
+
+    drop(select);
+    assert_eq!(rx1.try_recv(), Ok(8));
+    drop(tx0);
+}
+
+#[test]
+fn rselect_macro() {
+    let (tx0, rx0) = unbounded::<u32>();
+    let (tx1, rx1) = bounded::<String>(1);
+
+    tx1.send("full".to_string()).unwrap();
+    tx0.send(5).unwrap();
+    let res = rselect! {
+        recv(rx0) -> x => x.map(|x| x * 2),
+        send(tx1, "hello".to_string()) -> _ => panic!("tx1 is full"),
+    };
+    assert_eq!(res, Ok(10));
+
+    assert_eq!(rx1.recv().unwrap(), "full");
+    let res = rselect! {
+        recv(rx0) -> _ => None,
+        send(tx1, "hello".to_string()) -> res => Some(res),
+    };
+    assert_eq!(res, Some(Ok(())));
+    assert_eq!(rx1.recv().unwrap(), "hello");
+
+    tx1.send("full".to_string()).unwrap();
+    let res = rselect! {
+        recv(rx0) -> _ => "recv",
+        send(tx1, "hello".to_string()) -> _ => "send",
+        default => "default",
+    };
+    assert_eq!(res, "default");
+
+    let res = rselect! {
+        recv(rx0) -> _ => "recv",
+        default(Duration::from_millis(1)) => "default",
+    };
+    assert_eq!(res, "default");
+
+    drop(tx0);
+    let res = rselect! {
+        recv(rx0) -> x => x,
+        default => panic!("disconnected channels are ready"),
+    };
+    assert_eq!(res, Err(RecvError));
+
+    drop(rx1);
+    let res = rselect! {
+        send(tx1, "hello".to_string()) -> res => res,
+    };
+    assert_eq!(res, Err(SendError("hello".to_string())));
+}
+
+#[test]
+#[cfg(not(all(miri, target_os = "windows")))]
+fn rselect_macro_blocking() {
+    let (tx0, rx0) = bounded::<u32>(0);
+    let (tx1, rx1) = bounded::<u32>(0);
+
+    scoped_thread(|scope| {
+        scope.spawn(|_| {
+            assert_eq!(rx1.recv(), Ok(3));
+            tx0.send(8).unwrap();
+        });
+
+        let mut events = Vec::new();
+        for _ in 0..2 {
+            rselect! {
+                recv(rx0) -> x => events.push(x.unwrap()),
+                send(tx1, 3) -> res => events.push(res.map(|_| 0).unwrap()),
+            }
+        }
+        assert_eq!(events, [0, 8]);
+    })
+    .unwrap();
+}
@@ -0,0 +1,621 @@
+//! Ffi-safe bridge for the [`log`](https://docs.rs/log) facade,
+//! which forwards the log records of dynamic libraries to the logger of the host.
+//!
+//! Every dynamic library has its own copy of the `log` crate,
+//! with its own global logger,
+//! so records logged by a library are discarded unless a logger is installed in it.
+//!
+//! The host constructs an [`RLogger`] (usually with [`RLogger::from_global`]),
+//! and passes it to the dynamic libraries it loads,
+//! which install it as their global logger with [`RLogger::install`].
+//!
+//! # Example
+//!
+//! ```rust
+//! use abi_stable::external_types::log::{
+//!     Logger, RLevelFilter, RLogger, RMetadata, RRecord,
+//! };
+//!
+//! use std::sync::{Arc, Mutex};
+//!
+//! // This would be an `extern "C"` function exported by a dynamic library.
+//! extern "C" fn plugin_init(logger: RLogger) {
+//!     logger.install().unwrap();
+//!
+//!     log::info!(target: "plugin", answer = 42; "plugin loaded");
+//! }
+//!
+//! // The host would normally use `RLogger::from_global()`,
+//! // to forward records to its own `log` logger.
+//! let records = Arc::new(Mutex::new(Vec::new()));
+//! plugin_init(RLogger::new(CollectLogger(records.clone())));
+//!
+//! assert_eq!(*records.lock().unwrap(), ["Info plugin: plugin loaded answer=42"]);
+//!
+//!
+//! struct CollectLogger(Arc<Mutex<Vec<String>>>);
+//!
+//! impl Logger for CollectLogger {
+//!     fn enabled(&self, _: RMetadata<'_>) -> bool {
+//!         true
+//!     }
+//!
+//!     fn log(&self, record: &RRecord<'_>) {
+//!         let mut line = format!(
+//!             "{:?} {}: {}",
+//!             record.metadata.level, record.metadata.target, record.message,
+//!         );
+//!         for kv in record.key_values.iter() {
+//!             line.push_str(&format!(" {}={}", kv.key, kv.value));
+//!         }
+//!         self.0.lock().unwrap().push(line);
+//!     }
+//!
+//!     fn flush(&self) {}
+//!
+//!     fn max_level(&self) -> RLevelFilter {
+//!         RLevelFilter::Trace
+//!     }
+//! }
+//!
+//! ```
+//!
+
+use std::fmt::{self, Debug, Write as _};
+
+use log::kv::{self, Key, Value, VisitSource};
+
+use crate::{
+    sabi_trait,
+    sabi_trait::prelude::TD_Opaque,
+    std_types::{RArc, ROption, RSlice, RStr},
+};
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// Ffi-safe equivalent of [`log::Level`].
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, StableAbi)]
+pub enum RLevel {
+    /// Very serious errors.
+    Error = 1,
+    /// Hazardous situations.
+    Warn,
+    /// Useful information.
+    Info,
+    /// Lower priority information.
+    Debug,
+    /// Very low priority, often extremely verbose, information.
+    Trace,
+}
+
+impl_from_rust_repr! {
+    impl From<log::Level> for RLevel {
+        fn(this){
+            match this {
+                log::Level::Error => RLevel::Error,
+                log::Level::Warn => RLevel::Warn,
+                log::Level::Info => RLevel::Info,
+                log::Level::Debug => RLevel::Debug,
+                log::Level::Trace => RLevel::Trace,
+            }
+        }
+    }
+}
+
+impl_into_rust_repr! {
+    impl Into<log::Level> for RLevel {
+        fn(this){
+            match this {
+                RLevel::Error => log::Level::Error,
+                RLevel::Warn => log::Level::Warn,
+                RLevel::Info => log::Level::Info,
+                RLevel::Debug => log::Level::Debug,
+                RLevel::Trace => log::Level::Trace,
+            }
+        }
+    }
+}
+
+/// Ffi-safe equivalent of [`log::LevelFilter`].
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, StableAbi)]
+pub enum RLevelFilter {
+    /// Disables all logging.
+    Off,
+    /// Corresponds to `RLevel::Error`.
+    Error,
+    /// Corresponds to `RLevel::Warn`.
+    Warn,
+    /// Corresponds to `RLevel::Info`.
+    Info,
+    /// Corresponds to `RLevel::Debug`.
+    Debug,
+    /// Corresponds to `RLevel::Trace`.
+    Trace,
+}
+
+impl_from_rust_repr! {
+    impl From<log::LevelFilter> for RLevelFilter {
+        fn(this){
+            match this {
+                log::LevelFilter::Off => RLevelFilter::Off,
+                log::LevelFilter::Error => RLevelFilter::Error,
+                log::LevelFilter::Warn => RLevelFilter::Warn,
+                log::LevelFilter::Info => RLevelFilter::Info,
+                log::LevelFilter::Debug => RLevelFilter::Debug,
+                log::LevelFilter::Trace => RLevelFilter::Trace,
+            }
+        }
+    }
+}
+
+impl_into_rust_repr! {
+    impl Into<log::LevelFilter> for RLevelFilter {
+        fn(this){
+            match this {
+                RLevelFilter::Off => log::LevelFilter::Off,
+                RLevelFilter::Error => log::LevelFilter::Error,
+                RLevelFilter::Warn => log::LevelFilter::Warn,
+                RLevelFilter::Info => log::LevelFilter::Info,
+                RLevelFilter::Debug => log::LevelFilter::Debug,
+                RLevelFilter::Trace => log::LevelFilter::Trace,
+            }
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// Ffi-safe equivalent of [`log::Metadata`].
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, StableAbi)]
+pub struct RMetadata<'a> {
+    /// The verbosity level of the record.
+    pub level: RLevel,
+    /// The name of the target of the record, usually the module path.
+    pub target: RStr<'a>,
+}
+
+/// Ffi-safe equivalent of [`log::Record`],
+/// with the message already formatted.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, StableAbi)]
+pub struct RRecord<'a> {
+    /// The level and target of the record.
+    pub metadata: RMetadata<'a>,
+    /// The formatted message.
+    pub message: RStr<'a>,
+    /// The module path of the code that logged the record.
+    pub module_path: ROption<RStr<'a>>,
+    /// The source file of the code that logged the record.
+    pub file: ROption<RStr<'a>>,
+    /// The line of the code that logged the record.
+    pub line: ROption<u32>,
+    /// The structured key-value pairs of the record.
+    pub key_values: RSlice<'a, RKeyValue<'a>>,
+}
+
+/// A key-value pair of an [`RRecord`], with the value already formatted.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, StableAbi)]
+pub struct RKeyValue<'a> {
+    /// The key.
+    pub key: RStr<'a>,
+    /// The `Display` formatted value.
+    pub value: RStr<'a>,
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// The operations that a logger must provide to be wrapped in an [`RLogger`].
+///
+/// Panics inside of these methods abort the process,
+/// since they can't unwind across the ffi boundary.
+#[sabi_trait]
+pub trait Logger: Send + Sync {
+    /// Whether a record with this metadata would be logged.
+    fn enabled(&self, metadata: RMetadata<'_>) -> bool;
+
+    /// Logs the record.
+    fn log(&self, record: &RRecord<'_>);
+
+    /// Flushes any buffered records.
+    fn flush(&self);
+
+    /// The most verbose level that records are logged at,
+    /// used as the maximum level of the library that installs the logger.
+    #[sabi(last_prefix_field)]
+    fn max_level(&self) -> RLevelFilter;
+}
+
+/// Forwards records to the logger of the `log` crate that this is compiled with.
+struct GlobalLogger;
+
+impl Logger for GlobalLogger {
+    fn enabled(&self, metadata: RMetadata<'_>) -> bool {
+        log::logger().enabled(
+            &log::Metadata::builder()
+                .level(metadata.level.into())
+                .target(metadata.target.as_str())
+                .build(),
+        )
+    }
+
+    fn log(&self, record: &RRecord<'_>) {
+        let key_values = KeyValues(record.key_values);
+        log::logger().log(
+            &log::Record::builder()
+                .args(format_args!("{}", record.message))
+                .level(record.metadata.level.into())
+                .target(record.metadata.target.as_str())
+                .module_path(record.module_path.map(|s| s.as_str()).into_option())
+                .file(record.file.map(|s| s.as_str()).into_option())
+                .line(record.line.into_option())
+                .key_values(&key_values)
+                .build(),
+        );
+    }
+
+    fn flush(&self) {
+        log::logger().flush();
+    }
+
+    fn max_level(&self) -> RLevelFilter {
+        log::max_level().into()
+    }
+}
+
+struct KeyValues<'a>(RSlice<'a, RKeyValue<'a>>);
+
+impl kv::Source for KeyValues<'_> {
+    fn visit<'kvs>(&'kvs self, visitor: &mut dyn VisitSource<'kvs>) -> Result<(), kv::Error> {
+        for kv in self.0.iter() {
+            visitor.visit_pair(
+                Key::from_str(kv.key.as_str()),
+                Value::from(kv.value.as_str()),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+struct CollectKeyValues(Vec<(String, String)>);
+
+impl<'kvs> VisitSource<'kvs> for CollectKeyValues {
+    fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), kv::Error> {
+        self.0.push((key.as_str().to_string(), value.to_string()));
+        Ok(())
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// An ffi-safe handle to a logger,
+/// that can be cheaply cloned and passed to dynamic libraries.
+///
+/// This implements [`log::Log`], forwarding records to the wrapped [`Logger`].
+///
+/// # Example
+///
+/// ```rust
+/// use abi_stable::external_types::log::RLogger;
+///
+/// // The host forwards the records of the dynamic library to its own logger.
+/// let logger = RLogger::from_global();
+///
+/// // The dynamic library installs the logger in its own copy of the `log` crate.
+/// # if false {
+/// logger.install().unwrap();
+/// # }
+///
+/// ```
+#[repr(transparent)]
+#[derive(StableAbi)]
+pub struct RLogger {
+    inner: Logger_TO<'static, RArc<()>>,
+}
+
+impl RLogger {
+    /// Constructs an `RLogger` from a [`Logger`].
+    pub fn new<L>(logger: L) -> Self
+    where
+        L: Logger + 'static,
+    {
+        Self {
+            inner: Logger_TO::from_ptr(RArc::new(logger), TD_Opaque),
+        }
+    }
+
+    /// Constructs an `RLogger` which forwards records to the global logger
+    /// of the `log` crate in the dynamic library/executable that calls this.
+    ///
+    /// This is meant to be called by the host,
+    /// the returned `RLogger` must not be installed with the same copy of the `log` crate,
+    /// since that would make it forward records to itself forever.
+    pub fn from_global() -> Self {
+        Self::new(GlobalLogger)
+    }
+
+    /// The most verbose level that the wrapped logger logs records at.
+    pub fn max_level(&self) -> log::LevelFilter {
+        self.inner.max_level().into()
+    }
+
+    /// Installs this as the global logger of the `log` crate
+    /// in the dynamic library/executable that calls this,
+    /// setting its maximum level to that of the wrapped logger.
+    ///
+    /// This is meant to be called by dynamic libraries,
+    /// with the `RLogger` that the host passed to them.
+    ///
+    /// # Errors
+    ///
+    /// This returns an error if a global logger was already installed.
+    pub fn install(self) -> Result<(), log::SetLoggerError> {
+        let max_level = self.max_level();
+        log::set_boxed_logger(Box::new(self))?;
+        log::set_max_level(max_level);
+        Ok(())
+    }
+}
+
+impl log::Log for RLogger {
+    fn enabled(&self, metadata: &log::Metadata<'_>) -> bool {
+        self.inner.enabled(RMetadata {
+            level: metadata.level().into(),
+            target: metadata.target().into(),
+        })
+    }
+
+    fn log(&self, record: &log::Record<'_>) {
+        let mut buffer = String::new();
+        let message = match record.args().as_str() {
+            Some(message) => message,
+            None => {
+                let _ = write!(buffer, "{}", record.args());
+                &buffer
+            }
+        };
+
+        let mut collected = CollectKeyValues(Vec::new());
+        let _ = record.key_values().visit(&mut collected);
+        let key_values = collected
+            .0
+            .iter()
+            .map(|(key, value)| RKeyValue {
+                key: key.as_str().into(),
+                value: value.as_str().into(),
+            })
+            .collect::<Vec<RKeyValue<'_>>>();
+
+        self.inner.log(&RRecord {
+            metadata: RMetadata {
+                level: record.level().into(),
+                target: record.target().into(),
+            },
+            message: message.into(),
+            module_path: record.module_path().map(RStr::from).into(),
+            file: record.file().map(RStr::from).into(),
+            line: record.line().into(),
+            key_values: key_values.as_slice().into(),
+        });
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
+}
+
+impl Clone for RLogger {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Logger_TO::from_sabi(self.inner.obj.shallow_clone()),
+        }
+    }
+}
+
+impl Debug for RLogger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("RLogger{..}")
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+#[cfg(all(test, not(feature = "only_new_tests")))]
+mod tests {
+    use super::*;
+
+    use std::sync::{Arc, Mutex};
+
+    use log::Log;
+
+    use crate::std_types::{RNone, RSome, RString, RVec};
+
+    #[derive(Debug, PartialEq)]
+    struct OwnedRecord {
+        level: RLevel,
+        target: RString,
+        message: RString,
+        module_path: ROption<RString>,
+        file: ROption<RString>,
+        line: ROption<u32>,
+        key_values: RVec<(RString, RString)>,
+    }
+
+    #[derive(Clone)]
+    struct CollectLogger {
+        records: Arc<Mutex<Vec<OwnedRecord>>>,
+        max_level: RLevelFilter,
+    }
+
+    impl CollectLogger {
+        fn new(max_level: RLevelFilter) -> Self {
+            Self {
+                records: Arc::new(Mutex::new(Vec::new())),
+                max_level,
+            }
+        }
+
+        fn take(&self) -> Vec<OwnedRecord> {
+            std::mem::take(&mut *self.records.lock().unwrap())
+        }
+    }
+
+    impl Logger for CollectLogger {
+        fn enabled(&self, metadata: RMetadata<'_>) -> bool {
+            metadata.level as u8 <= self.max_level as u8
+        }
+
+        fn log(&self, record: &RRecord<'_>) {
+            if !self.enabled(record.metadata) {
+                return;
+            }
+            self.records.lock().unwrap().push(OwnedRecord {
+                level: record.metadata.level,
+                target: record.metadata.target.into(),
+                message: record.message.into(),
+                module_path: record.module_path.map(RString::from),
+                file: record.file.map(RString::from),
+                line: record.line,
+                key_values: record
+                    .key_values
+                    .iter()
+                    .map(|kv| (kv.key.into(), kv.value.into()))
+                    .collect(),
+            });
+        }
+
+        fn flush(&self) {}
+
+        fn max_level(&self) -> RLevelFilter {
+            self.max_level
+        }
+    }
+
+    #[test]
+    fn level_conversions() {
+        for level in log::Level::iter() {
+            let rlevel = RLevel::from(level);
+            assert_eq!(log::Level::from(rlevel), level);
+            assert_eq!(rlevel as usize, level as usize);
+        }
+        for filter in log::LevelFilter::iter() {
+            let rfilter = RLevelFilter::from(filter);
+            assert_eq!(log::LevelFilter::from(rfilter), filter);
+            assert_eq!(rfilter as usize, filter as usize);
+        }
+    }
+
+    #[test]
+    fn forwards_records() {
+        let collector = CollectLogger::new(RLevelFilter::Debug);
+        let logger = RLogger::new(collector.clone()).clone();
+
+        assert_eq!(logger.max_level(), log::LevelFilter::Debug);
+
+        let metadata = |level| log::Metadata::builder().level(level).target("foo").build();
+        assert!(logger.enabled(&metadata(log::Level::Debug)));
+        assert!(!logger.enabled(&metadata(log::Level::Trace)));
+
+        let key_values = [("id", 3), ("count", 5)];
+        logger.log(
+            &log::Record::builder()
+                .args(format_args!("hello {}", "world"))
+                .level(log::Level::Warn)
+                .target("bar")
+                .module_path_static(Some("bar::baz"))
+                .file_static(Some("bar/baz.rs"))
+                .line(Some(13))
+                .key_values(&key_values)
+                .build(),
+        );
+        logger.log(
+            &log::Record::builder()
+                .args(format_args!("hidden"))
+                .level(log::Level::Trace)
+                .build(),
+        );
+        logger.log(
+            &log::Record::builder()
+                .args(format_args!("shown"))
+                .level(log::Level::Error)
+                .build(),
+        );
+
+        assert_eq!(
+            collector.take(),
+            vec![
+                OwnedRecord {
+                    level: RLevel::Warn,
+                    target: "bar".into(),
+                    message: "hello world".into(),
+                    module_path: RSome("bar::baz".into()),
+                    file: RSome("bar/baz.rs".into()),
+                    line: RSome(13),
+                    key_values: rvec![("id".into(), "3".into()), ("count".into(), "5".into())],
+                },
+                OwnedRecord {
+                    level: RLevel::Error,
+                    target: "".into(),
+                    message: "shown".into(),
+                    module_path: RNone,
+                    file: RNone,
+                    line: RNone,
+                    key_values: RVec::new(),
+                },
+            ]
+        );
+    }
+
+    // This is the only test that sets the global logger,
+    // since it can only be set once per process.
+    #[test]
+    fn install_and_forward_global() {
+        let collector = CollectLogger::new(RLevelFilter::Info);
+        RLogger::new(collector.clone()).install().unwrap();
+
+        assert_eq!(log::max_level(), log::LevelFilter::Info);
+        assert!(RLogger::new(collector.clone()).install().is_err());
+
+        log::info!(target: "plugin", key = "value"; "hello {}", 10);
+        log::debug!("hidden");
+
+        // Forwarding records to the logger that was just installed.
+        let global = RLogger::from_global();
+        assert_eq!(global.max_level(), log::LevelFilter::Info);
+        global.log(
+            &log::Record::builder()
+                .args(format_args!("forwarded"))
+                .level(log::Level::Warn)
+                .target("host")
+                .line(Some(3))
+                .key_values(&("a", "b"))
+                .build(),
+        );
+
+        let records = collector.take();
+        assert_eq!(records.len(), 2, "{:#?}", records);
+
+        assert_eq!(records[0].level, RLevel::Info);
+        assert_eq!(records[0].target, "plugin");
+        assert_eq!(records[0].message, "hello 10");
+        assert_eq!(records[0].module_path, RSome(module_path!().into()));
+        assert_eq!(records[0].file, RSome(file!().into()));
+        assert_eq!(records[0].key_values, rvec![("key".into(), "value".into())]);
+
+        assert_eq!(
+            records[1],
+            OwnedRecord {
+                level: RLevel::Warn,
+                target: "host".into(),
+                message: "forwarded".into(),
+                module_path: RNone,
+                file: RNone,
+                line: RSome(3),
+                key_values: rvec![("a".into(), "b".into())],
+            }
+        );
+    }
+}
@@ -1,11 +1,17 @@
 //! Ffi-safe synchronization primitives,most of which are ffi-safe wrappers of
 //! [parking_lot](https://crates.io/crates/parking_lot) types
 
+pub mod barrier;
+pub mod condvar;
 pub mod mutex;
 pub mod once;
+pub mod reentrant_mutex;
 pub mod rw_lock;
 
-pub use self::{mutex::RMutex, once::ROnce, rw_lock::RRwLock};
+pub use self::{
+    barrier::RBarrier, condvar::RCondvar, mutex::RMutex, once::ROnce,
+    reentrant_mutex::RReentrantMutex, rw_lock::RRwLock,
+};
 
 /////////////////////////////////////////////////////////////////////////////////
 
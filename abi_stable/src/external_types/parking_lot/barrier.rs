@@ -0,0 +1,262 @@
+//! Contains an ffi-safe barrier, for synchronizing a fixed number of threads.
+
+use std::{
+    fmt::{self, Debug},
+    mem,
+};
+
+use parking_lot::{Condvar, Mutex};
+
+use super::{UnsafeOveralignedField, RAW_LOCK_SIZE};
+
+use crate::{prefix_type::WithMetadata, StableAbi};
+
+///////////////////////////////////////////////////////////////////////////////
+
+struct BarrierCounts {
+    count: usize,
+    generation: usize,
+}
+
+struct BarrierState {
+    lock: Mutex<BarrierCounts>,
+    condvar: Condvar,
+    num_threads: usize,
+}
+
+type OpaqueBarrier = UnsafeOveralignedField<BarrierState, [u8; OB_PADDING]>;
+
+const OB_PADDING: usize = 5 * RAW_LOCK_SIZE - mem::size_of::<BarrierState>();
+
+const _: () = assert!(5 * RAW_LOCK_SIZE == mem::size_of::<OpaqueBarrier>());
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// A barrier, which blocks threads until a fixed number of them are waiting on it,
+/// at which point all of them are woken up.
+///
+/// An `RBarrier` can be reused after all the threads are woken up.
+///
+/// # Example
+///
+/// ```
+/// use abi_stable::external_types::{RBarrier, RMutex};
+///
+/// static BARRIER: RBarrier = RBarrier::new(4);
+/// static ARRIVED: RMutex<u32> = RMutex::new(0);
+///
+/// let threads = (0..4)
+///     .map(|_| {
+///         std::thread::spawn(|| {
+///             *ARRIVED.lock() += 1;
+///             BARRIER.wait();
+///             // All the threads incremented `ARRIVED` before any of them passed the barrier.
+///             assert_eq!(*ARRIVED.lock(), 4);
+///         })
+///     })
+///     .collect::<Vec<_>>();
+///
+/// for thread in threads {
+///     thread.join().unwrap();
+/// }
+///
+/// ```
+#[repr(C)]
+#[derive(StableAbi)]
+pub struct RBarrier {
+    opaque_barrier: OpaqueBarrier,
+    vtable: VTable_Ref,
+}
+
+impl RBarrier {
+    /// Constructs an `RBarrier` that blocks until `num_threads` threads are waiting on it.
+    ///
+    /// If `num_threads` is 0 or 1, `wait` returns immediately.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::external_types::RBarrier;
+    ///
+    /// static BARRIER: RBarrier = RBarrier::new(2);
+    ///
+    /// let barrier = RBarrier::new(10);
+    ///
+    /// ```
+    pub const fn new(num_threads: usize) -> Self {
+        Self {
+            opaque_barrier: OpaqueBarrier::new(
+                BarrierState {
+                    lock: parking_lot::const_mutex(BarrierCounts {
+                        count: 0,
+                        generation: 0,
+                    }),
+                    condvar: Condvar::new(),
+                    num_threads,
+                },
+                [0u8; OB_PADDING],
+            ),
+            vtable: VTable::VTABLE,
+        }
+    }
+
+    /// Blocks the current thread until all the threads are waiting on this barrier.
+    ///
+    /// Exactly one of the threads that were blocked gets an `RBarrierWaitResult`
+    /// whose `is_leader` method returns true.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::external_types::RBarrier;
+    ///
+    /// static BARRIER: RBarrier = RBarrier::new(3);
+    ///
+    /// let threads = (0..3)
+    ///     .map(|_| std::thread::spawn(|| BARRIER.wait().is_leader()))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// let leaders = threads
+    ///     .into_iter()
+    ///     .map(|thread| thread.join().unwrap())
+    ///     .filter(|&is_leader| is_leader)
+    ///     .count();
+    ///
+    /// assert_eq!(leaders, 1);
+    ///
+    /// ```
+    pub fn wait(&self) -> RBarrierWaitResult {
+        RBarrierWaitResult(self.vtable.wait()(&self.opaque_barrier))
+    }
+}
+
+impl Debug for RBarrier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("RBarrier { .. }")
+    }
+}
+
+unsafe impl Send for RBarrier where Condvar: Send {}
+
+unsafe impl Sync for RBarrier where Condvar: Sync {}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// Returned by [`RBarrier::wait`],
+/// to tell whether the current thread is the leader of the threads that were blocked.
+#[repr(transparent)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, StableAbi)]
+pub struct RBarrierWaitResult(bool);
+
+impl RBarrierWaitResult {
+    /// Returns whether this thread is the leader,
+    /// only one thread is the leader each time that the barrier releases threads.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::external_types::RBarrier;
+    ///
+    /// let barrier = RBarrier::new(1);
+    ///
+    /// assert!(barrier.wait().is_leader());
+    ///
+    /// ```
+    pub const fn is_leader(self) -> bool {
+        self.0
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+#[repr(C)]
+#[derive(StableAbi)]
+#[sabi(kind(Prefix))]
+#[sabi(missing_field(panic))]
+struct VTable {
+    #[sabi(last_prefix_field)]
+    wait: extern "C" fn(this: &OpaqueBarrier) -> bool,
+}
+
+impl VTable {
+    const _TMP0: WithMetadata<VTable> = WithMetadata::new(VTable { wait });
+
+    // The VTABLE for this type in this executable/library
+    const VTABLE: VTable_Ref = { VTable_Ref(Self::_TMP0.static_as_prefix()) };
+}
+
+extern "C" fn wait(this: &OpaqueBarrier) -> bool {
+    extern_fn_panic_handling! {
+        let state = &this.value;
+        let mut counts = state.lock.lock();
+        let generation = counts.generation;
+        counts.count += 1;
+        if counts.count < state.num_threads {
+            while generation == counts.generation {
+                state.condvar.wait(&mut counts);
+            }
+            false
+        } else {
+            counts.count = 0;
+            counts.generation = counts.generation.wrapping_add(1);
+            state.condvar.notify_all();
+            true
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+#[cfg(all(test, not(feature = "only_new_tests")))]
+mod tests {
+    use super::*;
+
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use crossbeam_utils::thread::scope as scoped_thread;
+
+    #[cfg(miri)]
+    const ITERS: usize = 4;
+
+    #[cfg(not(miri))]
+    const ITERS: usize = 0x100;
+
+    const THREADS: usize = 4;
+
+    #[test]
+    #[cfg(not(all(miri, target_os = "windows")))]
+    fn reused_barrier() {
+        let barrier = RBarrier::new(THREADS);
+        let arrived = AtomicUsize::new(0);
+        let leaders = AtomicUsize::new(0);
+
+        scoped_thread(|scope| {
+            for _ in 0..THREADS {
+                scope.spawn(|_| {
+                    for i in 0..ITERS {
+                        arrived.fetch_add(1, Ordering::SeqCst);
+                        if barrier.wait().is_leader() {
+                            leaders.fetch_add(1, Ordering::SeqCst);
+                        }
+                        assert!(arrived.load(Ordering::SeqCst) >= (i + 1) * THREADS);
+                        barrier.wait();
+                    }
+                });
+            }
+        })
+        .unwrap();
+
+        assert_eq!(arrived.load(Ordering::SeqCst), ITERS * THREADS);
+        assert_eq!(leaders.load(Ordering::SeqCst), ITERS);
+    }
+
+    #[test]
+    fn single_thread_barrier() {
+        for num_threads in 0..2 {
+            let barrier = RBarrier::new(num_threads);
+            for _ in 0..4 {
+                assert!(barrier.wait().is_leader());
+            }
+        }
+    }
+}
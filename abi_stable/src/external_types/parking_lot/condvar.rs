@@ -0,0 +1,455 @@
+//! Contains an ffi-safe condition variable, which works with `RMutex`.
+
+use std::{
+    fmt::{self, Debug},
+    mem,
+};
+
+use parking_lot::{Condvar, Mutex};
+
+use super::{
+    mutex::{RMutexGuard, RawMutexRef},
+    UnsafeOveralignedField, RAW_LOCK_SIZE,
+};
+
+use crate::{
+    prefix_type::WithMetadata,
+    std_types::{RDuration, RNone, ROption, RSome},
+    StableAbi,
+};
+
+///////////////////////////////////////////////////////////////////////////////
+
+struct CondvarState {
+    condvar: Condvar,
+    /// Held by waiting threads from before they unlock the `RMutex` until they're asleep,
+    /// and by notifying threads while they notify,
+    /// so that notifications aren't lost.
+    mutex: Mutex<()>,
+}
+
+type OpaqueCondvar = UnsafeOveralignedField<CondvarState, [u8; OC_PADDING]>;
+
+const OC_PADDING: usize = 2 * RAW_LOCK_SIZE - mem::size_of::<CondvarState>();
+
+#[allow(clippy::declare_interior_mutable_const)]
+const OPAQUE_CONDVAR: OpaqueCondvar = OpaqueCondvar::new(
+    CondvarState {
+        condvar: Condvar::new(),
+        mutex: parking_lot::const_mutex(()),
+    },
+    [0u8; OC_PADDING],
+);
+
+const _: () = assert!(2 * RAW_LOCK_SIZE == mem::size_of::<OpaqueCondvar>());
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// A condition variable, for blocking threads until some condition is met,
+/// which is protected by an [`RMutex`](crate::external_types::RMutex).
+///
+/// The threads waiting on an `RCondvar` can be woken up by a different dynamic library
+/// than the one that created the `RCondvar` or the `RMutex`.
+///
+/// Waiting on an `RCondvar` with guards of different `RMutex`es at the same time
+/// is allowed, but discouraged.
+///
+/// # Spurious wakeups
+///
+/// Threads may be woken up without being notified,
+/// so waiting must be done in a loop that checks the condition,
+/// or with [`wait_while`](#method.wait_while).
+///
+/// # Example
+///
+/// ```
+/// use abi_stable::external_types::{RCondvar, RMutex};
+///
+/// static READY: RMutex<bool> = RMutex::new(false);
+/// static CONDVAR: RCondvar = RCondvar::new();
+///
+/// let thread = std::thread::spawn(|| {
+///     *READY.lock() = true;
+///     CONDVAR.notify_one();
+/// });
+///
+/// let mut ready = READY.lock();
+/// while !*ready {
+///     CONDVAR.wait(&mut ready);
+/// }
+/// drop(ready);
+///
+/// thread.join().unwrap();
+///
+/// ```
+#[repr(C)]
+#[derive(StableAbi)]
+pub struct RCondvar {
+    opaque_condvar: OpaqueCondvar,
+    vtable: VTable_Ref,
+}
+
+impl RCondvar {
+    /// Constructs an `RCondvar`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::external_types::RCondvar;
+    ///
+    /// static CONDVAR: RCondvar = RCondvar::new();
+    ///
+    /// let condvar = RCondvar::new();
+    ///
+    /// ```
+    pub const fn new() -> Self {
+        Self {
+            opaque_condvar: OPAQUE_CONDVAR,
+            vtable: VTable::VTABLE,
+        }
+    }
+
+    /// Blocks the current thread until it's notified,
+    /// unlocking the mutex of `guard` while blocked.
+    ///
+    /// The mutex is locked again before this returns.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::external_types::{RCondvar, RMutex};
+    ///
+    /// static QUEUE: RMutex<Vec<u32>> = RMutex::new(Vec::new());
+    /// static CONDVAR: RCondvar = RCondvar::new();
+    ///
+    /// let thread = std::thread::spawn(|| {
+    ///     for i in 0..4 {
+    ///         QUEUE.lock().push(i);
+    ///         CONDVAR.notify_all();
+    ///     }
+    /// });
+    ///
+    /// let mut queue = QUEUE.lock();
+    /// while queue.len() < 4 {
+    ///     CONDVAR.wait(&mut queue);
+    /// }
+    /// assert_eq!(*queue, [0, 1, 2, 3]);
+    /// drop(queue);
+    ///
+    /// thread.join().unwrap();
+    ///
+    /// ```
+    pub fn wait<T>(&self, guard: &mut RMutexGuard<'_, T>) {
+        self.vtable.wait()(&self.opaque_condvar, guard.raw_mutex(), RNone);
+    }
+
+    /// Blocks the current thread while `condition` returns true,
+    /// unlocking the mutex of `guard` while blocked.
+    ///
+    /// `condition` is always called with the mutex locked.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::external_types::{RCondvar, RMutex};
+    ///
+    /// static COUNT: RMutex<u32> = RMutex::new(0);
+    /// static CONDVAR: RCondvar = RCondvar::new();
+    ///
+    /// let threads = (0..4)
+    ///     .map(|_| {
+    ///         std::thread::spawn(|| {
+    ///             *COUNT.lock() += 1;
+    ///             CONDVAR.notify_all();
+    ///         })
+    ///     })
+    ///     .collect::<Vec<_>>();
+    ///
+    /// let mut count = COUNT.lock();
+    /// CONDVAR.wait_while(&mut count, |count| *count < 4);
+    /// assert_eq!(*count, 4);
+    /// drop(count);
+    ///
+    /// for thread in threads {
+    ///     thread.join().unwrap();
+    /// }
+    ///
+    /// ```
+    pub fn wait_while<T, F>(&self, guard: &mut RMutexGuard<'_, T>, mut condition: F)
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        while condition(&mut **guard) {
+            self.wait(guard);
+        }
+    }
+
+    /// Blocks the current thread until it's notified or `timeout` elapses,
+    /// unlocking the mutex of `guard` while blocked.
+    ///
+    /// The mutex is locked again before this returns.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::{
+    ///     external_types::{RCondvar, RMutex},
+    ///     std_types::RDuration,
+    /// };
+    ///
+    /// let mutex = RMutex::new(());
+    /// let condvar = RCondvar::new();
+    ///
+    /// let mut guard = mutex.lock();
+    /// let res = condvar.wait_for(&mut guard, RDuration::from_millis(1));
+    /// assert!(res.timed_out());
+    ///
+    /// ```
+    pub fn wait_for<T>(
+        &self,
+        guard: &mut RMutexGuard<'_, T>,
+        timeout: RDuration,
+    ) -> RWaitTimeoutResult {
+        let timed_out = self.vtable.wait()(&self.opaque_condvar, guard.raw_mutex(), RSome(timeout));
+        RWaitTimeoutResult(timed_out)
+    }
+
+    /// Wakes up one of the threads waiting on this `RCondvar`,
+    /// returning whether a thread was woken up.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::external_types::RCondvar;
+    ///
+    /// let condvar = RCondvar::new();
+    ///
+    /// assert!(!condvar.notify_one());
+    ///
+    /// ```
+    pub fn notify_one(&self) -> bool {
+        self.vtable.notify_one()(&self.opaque_condvar)
+    }
+
+    /// Wakes up all the threads waiting on this `RCondvar`,
+    /// returning how many threads were woken up.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::external_types::RCondvar;
+    ///
+    /// let condvar = RCondvar::new();
+    ///
+    /// assert_eq!(condvar.notify_all(), 0);
+    ///
+    /// ```
+    pub fn notify_all(&self) -> usize {
+        self.vtable.notify_all()(&self.opaque_condvar)
+    }
+}
+
+impl Default for RCondvar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debug for RCondvar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("RCondvar { .. }")
+    }
+}
+
+unsafe impl Send for RCondvar where Condvar: Send {}
+
+unsafe impl Sync for RCondvar where Condvar: Sync {}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// Whether [`RCondvar::wait_for`] returned because the timeout elapsed.
+#[repr(transparent)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, StableAbi)]
+pub struct RWaitTimeoutResult(bool);
+
+impl RWaitTimeoutResult {
+    /// Returns whether the wait timed out.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::{
+    ///     external_types::{RCondvar, RMutex},
+    ///     std_types::RDuration,
+    /// };
+    ///
+    /// let mutex = RMutex::new(());
+    /// let condvar = RCondvar::new();
+    ///
+    /// let res = condvar.wait_for(&mut mutex.lock(), RDuration::from_micros(100));
+    /// assert!(res.timed_out());
+    ///
+    /// ```
+    pub const fn timed_out(self) -> bool {
+        self.0
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+#[repr(C)]
+#[derive(StableAbi)]
+#[sabi(kind(Prefix))]
+#[sabi(missing_field(panic))]
+struct VTable {
+    wait: extern "C" fn(
+        this: &OpaqueCondvar,
+        mutex: RawMutexRef<'_>,
+        timeout: ROption<RDuration>,
+    ) -> bool,
+    notify_one: extern "C" fn(this: &OpaqueCondvar) -> bool,
+    #[sabi(last_prefix_field)]
+    notify_all: extern "C" fn(this: &OpaqueCondvar) -> usize,
+}
+
+impl VTable {
+    const _TMP0: WithMetadata<VTable> = WithMetadata::new(VTable {
+        wait,
+        notify_one,
+        notify_all,
+    });
+
+    // The VTABLE for this type in this executable/library
+    const VTABLE: VTable_Ref = { VTable_Ref(Self::_TMP0.static_as_prefix()) };
+}
+
+extern "C" fn wait(
+    this: &OpaqueCondvar,
+    mutex: RawMutexRef<'_>,
+    timeout: ROption<RDuration>,
+) -> bool {
+    extern_fn_panic_handling! {
+        let state = &this.value;
+        let mut inner_guard = state.mutex.lock();
+        mutex.unlock();
+        let timed_out = match timeout {
+            RSome(timeout) => state.condvar.wait_for(&mut inner_guard, timeout.into()).timed_out(),
+            RNone => {
+                state.condvar.wait(&mut inner_guard);
+                false
+            }
+        };
+        // Unlocking this before locking `mutex`,
+        // because notifying threads can be holding `mutex` while locking this.
+        drop(inner_guard);
+        mutex.lock();
+        timed_out
+    }
+}
+extern "C" fn notify_one(this: &OpaqueCondvar) -> bool {
+    extern_fn_panic_handling! {
+        let _guard = this.value.mutex.lock();
+        this.value.condvar.notify_one()
+    }
+}
+extern "C" fn notify_all(this: &OpaqueCondvar) -> usize {
+    extern_fn_panic_handling! {
+        let _guard = this.value.mutex.lock();
+        this.value.condvar.notify_all()
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+#[cfg(all(test, not(feature = "only_new_tests")))]
+mod tests {
+    use super::*;
+
+    use std::time::{Duration, Instant};
+
+    use crossbeam_utils::thread::scope as scoped_thread;
+
+    use crate::external_types::RMutex;
+
+    #[cfg(miri)]
+    const ITERS: usize = 10;
+
+    #[cfg(not(miri))]
+    const ITERS: usize = 0x400;
+
+    #[test]
+    #[cfg(not(all(miri, target_os = "windows")))]
+    fn ping_pong() {
+        static TURN: RMutex<usize> = RMutex::new(0);
+        static CONDVAR: RCondvar = RCondvar::new();
+
+        scoped_thread(|scope| {
+            for thread in 0..2 {
+                scope.spawn(move |_| {
+                    for _ in 0..ITERS {
+                        let mut turn = TURN.lock();
+                        CONDVAR.wait_while(&mut turn, |turn| *turn % 2 != thread);
+                        *turn += 1;
+                        CONDVAR.notify_all();
+                    }
+                });
+            }
+        })
+        .unwrap();
+
+        assert_eq!(*TURN.lock(), 2 * ITERS);
+    }
+
+    #[test]
+    #[cfg(not(all(miri, target_os = "windows")))]
+    fn notify_one_wakes_waiter() {
+        let mutex = RMutex::new(false);
+        let condvar = RCondvar::new();
+
+        scoped_thread(|scope| {
+            let mut guard = mutex.lock();
+            scope.spawn(|_| {
+                *mutex.lock() = true;
+                while !condvar.notify_one() {
+                    std::thread::yield_now();
+                }
+            });
+            while !*guard {
+                condvar.wait(&mut guard);
+            }
+        })
+        .unwrap();
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn wait_for_timeout() {
+        let mutex = RMutex::new(0);
+        let condvar = RCondvar::new();
+
+        let mut guard = mutex.lock();
+        let start = Instant::now();
+        let res = condvar.wait_for(&mut guard, RDuration::from_millis(20));
+        assert!(res.timed_out());
+        assert!(start.elapsed() >= Duration::from_millis(20));
+
+        // The mutex is locked again after waiting
+        *guard += 1;
+        assert!(mutex.try_lock().is_none());
+        drop(guard);
+        assert_eq!(*mutex.lock(), 1);
+
+        scoped_thread(|scope| {
+            let mut guard = mutex.lock();
+            scope.spawn(|_| {
+                *mutex.lock() = 10;
+                condvar.notify_all();
+            });
+            while *guard != 10 {
+                let res = condvar.wait_for(&mut guard, RDuration::from_secs(10));
+                assert!(!res.timed_out());
+            }
+        })
+        .unwrap();
+    }
+}
@@ -6,6 +6,7 @@ use std::{
     marker::PhantomData,
     mem,
     ops::{Deref, DerefMut},
+    ptr::NonNull,
 };
 
 use lock_api::{RawMutex as RawMutexTrait, RawMutexTimed};
@@ -289,6 +290,82 @@ impl<'a, T> DerefMut for RMutexGuard<'a, T> {
     }
 }
 
+impl<'a, T> RMutexGuard<'a, T> {
+    /// Gets the raw mutex, used by `RCondvar` to unlock the mutex while waiting.
+    pub(super) fn raw_mutex(&self) -> RawMutexRef<'a> {
+        RawMutexRef {
+            raw_mutex: &self.rmutex.raw_mutex,
+            vtable: self.rmutex.vtable(),
+        }
+    }
+
+    /// Makes a guard for a part of the locked data,
+    /// which keeps the mutex locked until it's dropped.
+    ///
+    /// This is an associated function because `RMutexGuard` dereferences to `T`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::external_types::parking_lot::mutex::{RMutex, RMutexGuard};
+    ///
+    /// let mutex = RMutex::new((3, "foo"));
+    ///
+    /// let mut guard = RMutexGuard::map(mutex.lock(), |pair| &mut pair.0);
+    /// *guard += 5;
+    /// assert!(mutex.try_lock().is_none());
+    /// drop(guard);
+    ///
+    /// assert_eq!(*mutex.lock(), (8, "foo"));
+    ///
+    /// ```
+    pub fn map<U, F>(this: Self, f: F) -> RMappedMutexGuard<'a, U>
+    where
+        F: FnOnce(&mut T) -> &mut U,
+    {
+        let raw_mutex = this.raw_mutex();
+        let data = NonNull::from(f(unsafe { &mut *this.rmutex.data.get() }));
+        mem::forget(this);
+        RMappedMutexGuard::new(raw_mutex, data)
+    }
+
+    /// Attempts to make a guard for a part of the locked data,
+    /// returning the original guard if `f` returns None.
+    ///
+    /// This is an associated function because `RMutexGuard` dereferences to `T`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::external_types::parking_lot::mutex::{RMutex, RMutexGuard};
+    ///
+    /// let mutex = RMutex::new(vec![3, 5]);
+    ///
+    /// let guard = RMutexGuard::try_map(mutex.lock(), |list| list.get_mut(2)).unwrap_err();
+    ///
+    /// let mut guard = RMutexGuard::try_map(guard, |list| list.get_mut(1)).unwrap();
+    /// *guard *= 2;
+    /// drop(guard);
+    ///
+    /// assert_eq!(*mutex.lock(), [3, 10]);
+    ///
+    /// ```
+    pub fn try_map<U, F>(this: Self, f: F) -> Result<RMappedMutexGuard<'a, U>, Self>
+    where
+        F: FnOnce(&mut T) -> Option<&mut U>,
+    {
+        let raw_mutex = this.raw_mutex();
+        match f(unsafe { &mut *this.rmutex.data.get() }) {
+            Some(data) => {
+                let data = NonNull::from(data);
+                mem::forget(this);
+                Ok(RMappedMutexGuard::new(raw_mutex, data))
+            }
+            None => Err(this),
+        }
+    }
+}
+
 impl<'a, T> Drop for RMutexGuard<'a, T> {
     fn drop(&mut self) {
         let vtable = self.rmutex.vtable();
@@ -298,6 +375,162 @@ impl<'a, T> Drop for RMutexGuard<'a, T> {
 
 ///////////////////////////////////////////////////////////////////////////////
 
+/// A mutex guard for a part of the data inside an `RMutex`,
+/// constructed with [`RMutexGuard::map`].
+///
+/// When dropped this will unlock the mutex.
+///
+#[repr(C)]
+#[derive(StableAbi)]
+#[sabi(bound(T:'a))]
+#[must_use]
+pub struct RMappedMutexGuard<'a, T> {
+    raw_mutex: RawMutexRef<'a>,
+    data: NonNull<T>,
+    _marker: PhantomData<(&'a mut T, UnsyncUnsend)>,
+}
+
+impl<'a, T> RMappedMutexGuard<'a, T> {
+    const fn new(raw_mutex: RawMutexRef<'a>, data: NonNull<T>) -> Self {
+        Self {
+            raw_mutex,
+            data,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Makes a guard for a part of the locked data,
+    /// which keeps the mutex locked until it's dropped.
+    ///
+    /// This is an associated function because `RMappedMutexGuard` dereferences to `T`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::external_types::parking_lot::mutex::{
+    ///     RMappedMutexGuard, RMutex, RMutexGuard,
+    /// };
+    ///
+    /// let mutex = RMutex::new((3, (5, 8)));
+    ///
+    /// let guard = RMutexGuard::map(mutex.lock(), |pair| &mut pair.1);
+    /// let mut guard = RMappedMutexGuard::map(guard, |pair| &mut pair.0);
+    /// *guard += 8;
+    /// drop(guard);
+    ///
+    /// assert_eq!(*mutex.lock(), (3, (13, 8)));
+    ///
+    /// ```
+    pub fn map<U, F>(this: Self, f: F) -> RMappedMutexGuard<'a, U>
+    where
+        F: FnOnce(&mut T) -> &mut U,
+    {
+        let raw_mutex = this.raw_mutex;
+        let data = NonNull::from(f(unsafe { &mut *this.data.as_ptr() }));
+        mem::forget(this);
+        RMappedMutexGuard::new(raw_mutex, data)
+    }
+
+    /// Attempts to make a guard for a part of the locked data,
+    /// returning the original guard if `f` returns None.
+    ///
+    /// This is an associated function because `RMappedMutexGuard` dereferences to `T`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::external_types::parking_lot::mutex::{
+    ///     RMappedMutexGuard, RMutex, RMutexGuard,
+    /// };
+    ///
+    /// let mutex = RMutex::new((3, vec![5, 8]));
+    ///
+    /// let guard = RMutexGuard::map(mutex.lock(), |pair| &mut pair.1);
+    /// let guard = RMappedMutexGuard::try_map(guard, |list| list.get_mut(2)).unwrap_err();
+    /// let mut guard = RMappedMutexGuard::try_map(guard, |list| list.get_mut(0)).unwrap();
+    /// *guard += 8;
+    /// drop(guard);
+    ///
+    /// assert_eq!(*mutex.lock(), (3, vec![13, 8]));
+    ///
+    /// ```
+    pub fn try_map<U, F>(this: Self, f: F) -> Result<RMappedMutexGuard<'a, U>, Self>
+    where
+        F: FnOnce(&mut T) -> Option<&mut U>,
+    {
+        let raw_mutex = this.raw_mutex;
+        match f(unsafe { &mut *this.data.as_ptr() }) {
+            Some(data) => {
+                let data = NonNull::from(data);
+                mem::forget(this);
+                Ok(RMappedMutexGuard::new(raw_mutex, data))
+            }
+            None => Err(this),
+        }
+    }
+}
+
+impl<'a, T> Display for RMappedMutexGuard<'a, T>
+where
+    T: Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(&**self, f)
+    }
+}
+
+impl<'a, T> Debug for RMappedMutexGuard<'a, T>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&**self, f)
+    }
+}
+
+impl<'a, T> Deref for RMappedMutexGuard<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.data.as_ptr() }
+    }
+}
+
+impl<'a, T> DerefMut for RMappedMutexGuard<'a, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.data.as_ptr() }
+    }
+}
+
+impl<'a, T> Drop for RMappedMutexGuard<'a, T> {
+    fn drop(&mut self) {
+        self.raw_mutex.unlock();
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// A reference to the raw mutex of an `RMutex`,
+/// which locks and unlocks it with the functions of the library that created it.
+#[repr(C)]
+#[derive(StableAbi, Copy, Clone)]
+pub(super) struct RawMutexRef<'a> {
+    raw_mutex: &'a OpaqueMutex,
+    vtable: VTable_Ref,
+}
+
+impl RawMutexRef<'_> {
+    pub(super) fn lock(self) {
+        self.vtable.lock()(self.raw_mutex);
+    }
+
+    pub(super) fn unlock(self) {
+        self.vtable.unlock()(self.raw_mutex);
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
 #[repr(C)]
 #[derive(StableAbi)]
 #[sabi(kind(Prefix))]
@@ -382,6 +615,40 @@ mod tests {
         check_formatting_equivalence(&guard, str_);
     }
 
+    #[test]
+    fn mapped_debug_display() {
+        let str_ = "\nhello\rhello\rhello\n";
+        let mutex = RMutex::new(str_);
+        let guard = RMutexGuard::map(mutex.lock(), |x| x);
+
+        check_formatting_equivalence(&guard, str_);
+    }
+
+    #[test]
+    fn mapped_guard() {
+        let mutex = RMutex::new((0u32, vec![0u32; 4]));
+
+        {
+            let mut guard = RMutexGuard::map(mutex.lock(), |(_, list)| list);
+            assert!(mutex.try_lock().is_none());
+            guard.push(10);
+            let mut guard = RMappedMutexGuard::map(guard, |list| &mut list[2]);
+            *guard += 3;
+        }
+        {
+            let guard = RMutexGuard::try_map(mutex.lock(), |(_, list)| list.get_mut(100));
+            let guard = guard.unwrap_err();
+            let guard = RMutexGuard::map(guard, |(_, list)| list);
+            let mut guard = RMappedMutexGuard::try_map(guard, |list| list.get_mut(4)).unwrap();
+            *guard += 1;
+            let guard = RMappedMutexGuard::try_map(guard, |_| None::<&mut u8>).unwrap_err();
+            assert!(mutex.try_lock().is_none());
+            drop(guard);
+        }
+
+        assert_eq!(*mutex.lock(), (0, vec![0, 0, 3, 0, 11]));
+    }
+
     #[cfg(miri)]
     const ITERS: usize = 10;
 
@@ -0,0 +1,445 @@
+//! Contains an ffi-safe equivalent of `parking_lot::ReentrantMutex`.
+
+use std::{
+    cell::UnsafeCell,
+    fmt::{self, Debug, Display},
+    marker::PhantomData,
+    mem,
+    ops::Deref,
+};
+
+use lock_api::RawReentrantMutex;
+use parking_lot::{RawMutex, RawThreadId};
+
+use super::{UnsafeOveralignedField, RAW_LOCK_SIZE};
+
+use crate::{marker_type::UnsyncUnsend, prefix_type::WithMetadata, std_types::*, StableAbi};
+
+///////////////////////////////////////////////////////////////////////////////
+
+type RawRMutex = RawReentrantMutex<RawMutex, RawThreadId>;
+
+type OpaqueMutex = UnsafeOveralignedField<RawRMutex, [u8; OM_PADDING]>;
+
+const OM_PADDING: usize = 3 * RAW_LOCK_SIZE - mem::size_of::<RawRMutex>();
+
+#[allow(clippy::declare_interior_mutable_const)]
+const OPAQUE_MUTEX: OpaqueMutex = OpaqueMutex::new(RawRMutex::INIT, [0u8; OM_PADDING]);
+
+// assert_mutex_size
+const _: () = assert!(3 * RAW_LOCK_SIZE == mem::size_of::<OpaqueMutex>());
+
+/// A mutual exclusion lock which can be locked multiple times by the same thread,
+/// allowing shared borrows of shared data.
+///
+/// The thread that owns the lock is identified by the dynamic library that
+/// constructed the `RReentrantMutex`,
+/// so a plugin calling back into its host while holding the lock
+/// can lock it again from the host without deadlocking.
+///
+/// Since the lock can be held multiple times,
+/// the guard only gives shared access to the data,
+/// which can be combined with `Cell`/`RefCell` to mutate it.
+///
+/// # Poisoning
+///
+/// As opposed to the standard library version of this type,
+/// this mutex type does not use poisoning,
+/// simply unlocking the lock when a panic happens.
+///
+/// # Example
+///
+/// ```
+/// use abi_stable::external_types::RReentrantMutex;
+///
+/// use std::cell::RefCell;
+///
+/// static MUTEX: RReentrantMutex<RefCell<Vec<u32>>> =
+///     RReentrantMutex::new(RefCell::new(Vec::new()));
+///
+/// fn push(value: u32) {
+///     MUTEX.lock().borrow_mut().push(value);
+/// }
+///
+/// let guard = MUTEX.lock();
+///
+/// // This doesn't deadlock, because the lock is held by the current thread.
+/// push(3);
+/// push(5);
+///
+/// assert_eq!(*guard.borrow(), [3, 5]);
+///
+/// ```
+#[repr(C)]
+#[derive(StableAbi)]
+pub struct RReentrantMutex<T> {
+    raw_mutex: OpaqueMutex,
+    data: UnsafeCell<T>,
+    vtable: VTable_Ref,
+}
+
+/// A reentrant mutex guard,which allows shared access to the data inside an `RReentrantMutex`.
+///
+/// When dropped this will unlock the mutex,
+/// once all the guards held by the current thread are dropped.
+///
+#[repr(transparent)]
+#[derive(StableAbi)]
+#[sabi(bound(T:'a))]
+#[must_use]
+pub struct RReentrantMutexGuard<'a, T> {
+    rmutex: &'a RReentrantMutex<T>,
+    _marker: PhantomData<(&'a T, UnsyncUnsend)>,
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+impl<T> RReentrantMutex<T> {
+    /// Constructs a reentrant mutex,wrapping `value`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::external_types::RReentrantMutex;
+    ///
+    /// static MUTEX: RReentrantMutex<Option<String>> = RReentrantMutex::new(None);
+    ///
+    /// let mutex = RReentrantMutex::new(0);
+    ///
+    /// ```
+    pub const fn new(value: T) -> Self {
+        Self {
+            raw_mutex: OPAQUE_MUTEX,
+            data: UnsafeCell::new(value),
+            vtable: VTable::VTABLE,
+        }
+    }
+
+    #[inline]
+    const fn vtable(&self) -> VTable_Ref {
+        self.vtable
+    }
+
+    #[inline]
+    fn make_guard(&self) -> RReentrantMutexGuard<'_, T> {
+        RReentrantMutexGuard {
+            rmutex: self,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Unwraps this mutex into its wrapped data.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::external_types::RReentrantMutex;
+    ///
+    /// let mutex = RReentrantMutex::new("hello");
+    ///
+    /// assert_eq!(mutex.into_inner(), "hello");
+    ///
+    /// ```
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.data.into_inner()
+    }
+
+    /// Gets a mutable reference to its wrapped data.
+    ///
+    /// This does not require any locking,since it takes `self` mutably.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::external_types::RReentrantMutex;
+    ///
+    /// let mut mutex = RReentrantMutex::new("Hello");
+    ///
+    /// *mutex.get_mut() = "World";
+    ///
+    /// assert_eq!(*mutex.lock(), "World");
+    ///
+    /// ```
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.data.get() }
+    }
+
+    /// Acquires a mutex,blocking the current thread until it can,
+    /// unless the current thread already holds it.
+    ///
+    /// This function returns a guard which releases the mutex when it is dropped,
+    /// once all the other guards held by the current thread are dropped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::external_types::RReentrantMutex;
+    ///
+    /// static MUTEX: RReentrantMutex<usize> = RReentrantMutex::new(0);
+    ///
+    /// let guard = MUTEX.lock();
+    /// let guard2 = MUTEX.lock();
+    ///
+    /// assert_eq!(*guard, 0);
+    /// assert_eq!(*guard2, 0);
+    ///
+    /// ```
+    #[inline]
+    pub fn lock(&self) -> RReentrantMutexGuard<'_, T> {
+        self.vtable().lock()(&self.raw_mutex);
+        self.make_guard()
+    }
+
+    /// Attemps to acquire a mutex,
+    /// failing if another thread holds it.
+    ///
+    /// Returns the mutex guard if the mutex can be immediately acquired,
+    /// otherwise returns `RNone`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::external_types::RReentrantMutex;
+    ///
+    /// static MUTEX: RReentrantMutex<usize> = RReentrantMutex::new(0);
+    ///
+    /// let guard = MUTEX.try_lock().unwrap();
+    ///
+    /// assert!(MUTEX.try_lock().is_some());
+    ///
+    /// std::thread::spawn(|| {
+    ///     assert!(MUTEX.try_lock().is_none());
+    /// })
+    /// .join()
+    /// .unwrap();
+    ///
+    /// assert_eq!(*guard, 0);
+    ///
+    /// ```
+    #[inline]
+    pub fn try_lock(&self) -> ROption<RReentrantMutexGuard<'_, T>> {
+        if self.vtable().try_lock()(&self.raw_mutex) {
+            RSome(self.make_guard())
+        } else {
+            RNone
+        }
+    }
+
+    /// Attempts to acquire a mutex for the timeout duration,
+    /// failing if another thread holds it for that long.
+    ///
+    /// Once the timeout is reached,this will return `RNone`,
+    /// otherwise it will return the mutex guard.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::{external_types::RReentrantMutex, std_types::RDuration};
+    ///
+    /// static MUTEX: RReentrantMutex<usize> = RReentrantMutex::new(0);
+    ///
+    /// static DUR: RDuration = RDuration::from_millis(4);
+    ///
+    /// let guard = MUTEX.try_lock_for(DUR).unwrap();
+    ///
+    /// std::thread::spawn(|| {
+    ///     assert!(MUTEX.try_lock_for(DUR).is_none());
+    /// })
+    /// .join()
+    /// .unwrap();
+    ///
+    /// assert_eq!(*guard, 0);
+    ///
+    /// ```
+    #[inline]
+    pub fn try_lock_for(&self, timeout: RDuration) -> ROption<RReentrantMutexGuard<'_, T>> {
+        if self.vtable().try_lock_for()(&self.raw_mutex, timeout) {
+            RSome(self.make_guard())
+        } else {
+            RNone
+        }
+    }
+}
+
+unsafe impl<T: Send> Send for RReentrantMutex<T> where RawRMutex: Send {}
+
+unsafe impl<T: Send> Sync for RReentrantMutex<T> where RawRMutex: Sync {}
+
+///////////////////////////////////////////////////////////////////////////////
+
+impl<T: Default> Default for RReentrantMutex<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+impl<'a, T> Display for RReentrantMutexGuard<'a, T>
+where
+    T: Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(&**self, f)
+    }
+}
+
+impl<'a, T> Debug for RReentrantMutexGuard<'a, T>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&**self, f)
+    }
+}
+
+impl<'a, T> Deref for RReentrantMutexGuard<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.rmutex.data.get() }
+    }
+}
+
+impl<'a, T> Drop for RReentrantMutexGuard<'a, T> {
+    fn drop(&mut self) {
+        let vtable = self.rmutex.vtable();
+        vtable.unlock()(&self.rmutex.raw_mutex);
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+#[repr(C)]
+#[derive(StableAbi)]
+#[sabi(kind(Prefix))]
+#[sabi(missing_field(panic))]
+struct VTable {
+    lock: extern "C" fn(this: &OpaqueMutex),
+    try_lock: extern "C" fn(this: &OpaqueMutex) -> bool,
+    unlock: extern "C" fn(this: &OpaqueMutex),
+    #[sabi(last_prefix_field)]
+    try_lock_for: extern "C" fn(this: &OpaqueMutex, timeout: RDuration) -> bool,
+}
+
+impl VTable {
+    const _TMP0: WithMetadata<VTable> = WithMetadata::new(VTable {
+        lock,
+        try_lock,
+        unlock,
+        try_lock_for,
+    });
+
+    // The VTABLE for this type in this executable/library
+    const VTABLE: VTable_Ref = { VTable_Ref(Self::_TMP0.static_as_prefix()) };
+}
+
+extern "C" fn lock(this: &OpaqueMutex) {
+    extern_fn_panic_handling! {
+        this.value.lock();
+    }
+}
+extern "C" fn try_lock(this: &OpaqueMutex) -> bool {
+    extern_fn_panic_handling! {
+        this.value.try_lock()
+    }
+}
+extern "C" fn unlock(this: &OpaqueMutex) {
+    extern_fn_panic_handling! {
+        unsafe{
+            this.value.unlock();
+        }
+    }
+}
+extern "C" fn try_lock_for(this: &OpaqueMutex, timeout: RDuration) -> bool {
+    extern_fn_panic_handling! {
+        this.value.try_lock_for(timeout.into())
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+#[cfg(all(test, not(feature = "only_new_tests")))]
+mod tests {
+    use super::*;
+
+    use std::{cell::Cell, thread, time::Duration};
+
+    use crossbeam_utils::thread::scope as scoped_thread;
+
+    use crate::test_utils::check_formatting_equivalence;
+
+    #[test]
+    fn get_mut() {
+        let mut mutex: RReentrantMutex<usize> = RReentrantMutex::new(0);
+        *mutex.get_mut() += 100;
+        assert_eq!(*mutex.lock(), 100);
+        assert_eq!(mutex.into_inner(), 100);
+    }
+
+    #[test]
+    fn debug_display() {
+        let str_ = "\nhello\rhello\rhello\n";
+        let mutex = RReentrantMutex::new(str_);
+        let guard = mutex.lock();
+
+        check_formatting_equivalence(&guard, str_);
+    }
+
+    #[test]
+    #[cfg(not(all(miri, target_os = "windows")))]
+    fn reentrant_locking() {
+        static MUTEX: RReentrantMutex<Cell<usize>> = RReentrantMutex::new(Cell::new(0));
+
+        fn recurse(depth: usize) {
+            let guard = MUTEX.lock();
+            guard.set(guard.get() + 1);
+            if depth != 0 {
+                recurse(depth - 1);
+            }
+        }
+
+        scoped_thread(|scope| {
+            for _ in 0..8 {
+                scope.spawn(move |_| {
+                    for _ in 0..16 {
+                        recurse(3);
+                    }
+                });
+            }
+        })
+        .unwrap();
+
+        assert_eq!(MUTEX.lock().get(), 8 * 16 * 4);
+    }
+
+    #[test]
+    #[cfg(not(all(miri, target_os = "windows")))]
+    fn try_lock__() {
+        static MUTEX: RReentrantMutex<usize> = RReentrantMutex::new(0);
+
+        scoped_thread(|scope| {
+            let guard = MUTEX.lock();
+            let guard2 = MUTEX.try_lock().unwrap();
+            let guard3 = MUTEX.try_lock_for(RDuration::from_millis(1)).unwrap();
+            drop(guard);
+            drop(guard2);
+            scope.spawn(move |_| {
+                assert!(MUTEX.try_lock().is_none());
+                assert!(MUTEX.try_lock_for(RDuration::new(0, 100_000)).is_none());
+            });
+            thread::sleep(Duration::from_millis(100));
+            drop(guard3);
+        })
+        .unwrap();
+
+        scoped_thread(|scope| {
+            scope.spawn(move |_| {
+                assert!(MUTEX.try_lock().is_some());
+            });
+        })
+        .unwrap();
+    }
+}
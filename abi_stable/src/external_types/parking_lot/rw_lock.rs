@@ -6,9 +6,13 @@ use std::{
     marker::PhantomData,
     mem,
     ops::{Deref, DerefMut},
+    ptr::NonNull,
 };
 
-use lock_api::{RawRwLock as RawRwLockTrait, RawRwLockTimed};
+use lock_api::{
+    RawRwLock as RawRwLockTrait, RawRwLockDowngrade, RawRwLockTimed, RawRwLockUpgrade,
+    RawRwLockUpgradeDowngrade, RawRwLockUpgradeTimed,
+};
 use parking_lot::RawRwLock;
 
 use super::{UnsafeOveralignedField, RAW_LOCK_SIZE};
@@ -97,6 +101,22 @@ pub struct RWriteGuard<'a, T> {
     _marker: PhantomData<(&'a mut T, UnsyncUnsend)>,
 }
 
+/// An upgradable read guard,which allows shared access to the data inside the `RRwLock`,
+/// and can be atomically upgraded into an `RWriteGuard`.
+///
+/// There can be only one of these for the same RRwLock at any given time,
+/// alongside any amount of `RReadGuard`s.
+///
+/// When dropped this will unlock the rwlock.
+#[repr(transparent)]
+#[derive(StableAbi)]
+#[sabi(bound(T:'a))]
+#[must_use]
+pub struct RUpgradableReadGuard<'a, T> {
+    rlock: &'a RRwLock<T>,
+    _marker: PhantomData<(&'a T, UnsyncUnsend)>,
+}
+
 ///////////////////////////////////////////////////////////////////////////////
 
 impl<T> RRwLock<T> {
@@ -144,6 +164,22 @@ impl<T> RRwLock<T> {
         }
     }
 
+    #[inline]
+    fn upgradable_guard(&self) -> RUpgradableReadGuard<'_, T> {
+        RUpgradableReadGuard {
+            rlock: self,
+            _marker: PhantomData,
+        }
+    }
+
+    #[inline]
+    fn raw_lock(&self) -> RawRwLockRef<'_> {
+        RawRwLockRef {
+            raw_lock: &self.raw_lock,
+            vtable: self.vtable(),
+        }
+    }
+
     /// Unwraps this lock into its wrapped data.
     ///
     /// # Example
@@ -366,6 +402,121 @@ impl<T> RRwLock<T> {
             RNone
         }
     }
+
+    /// Acquires a lock for reading which can be upgraded to a write lock,
+    /// blocking the current thread until it can.
+    ///
+    /// This function returns an upgradable read guard,
+    /// which releases the lock when it is dropped.
+    ///
+    /// Only one upgradable read lock can be held at a time,
+    /// alongside any number of read locks.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `RRwLock<T>` was created by a dynamic library/executable
+    /// built with a version of `abi_stable` that doesn't support upgradable read locks.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::external_types::{
+    ///     parking_lot::rw_lock::RUpgradableReadGuard, RRwLock,
+    /// };
+    ///
+    /// static LOCK: RRwLock<usize> = RRwLock::new(0);
+    ///
+    /// let guard = LOCK.upgradable_read();
+    /// assert_eq!(*LOCK.read(), 0);
+    ///
+    /// if *guard == 0 {
+    ///     let mut guard = RUpgradableReadGuard::upgrade(guard);
+    ///     *guard += 10;
+    /// }
+    ///
+    /// assert_eq!(*LOCK.read(), 10);
+    ///
+    /// ```
+    #[inline]
+    pub fn upgradable_read(&self) -> RUpgradableReadGuard<'_, T> {
+        self.vtable().lock_upgradable()(&self.raw_lock);
+        self.upgradable_guard()
+    }
+
+    /// Attempts to acquire a lock for reading which can be upgraded to a write lock,
+    /// failing if it is locked for writing or another upgradable read lock is held.
+    ///
+    /// Returns the upgradable read guard if the lock was acquired,
+    /// which releases the lock when it is dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `RRwLock<T>` was created by a dynamic library/executable
+    /// built with a version of `abi_stable` that doesn't support upgradable read locks.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::external_types::RRwLock;
+    ///
+    /// let lock = RRwLock::new(0);
+    ///
+    /// let guard = lock.try_upgradable_read().unwrap();
+    ///
+    /// assert!(lock.try_upgradable_read().is_none());
+    /// assert!(lock.try_read().is_some());
+    ///
+    /// assert_eq!(*guard, 0);
+    ///
+    /// ```
+    #[inline]
+    pub fn try_upgradable_read(&self) -> ROption<RUpgradableReadGuard<'_, T>> {
+        if self.vtable().try_lock_upgradable()(&self.raw_lock) {
+            RSome(self.upgradable_guard())
+        } else {
+            RNone
+        }
+    }
+
+    /// Attempts to acquire a lock for reading which can be upgraded to a write lock,
+    /// for the timeout duration.
+    ///
+    /// Once the timeout is reached,this will return None,
+    /// otherwise it will return the upgradable read guard.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `RRwLock<T>` was created by a dynamic library/executable
+    /// built with a version of `abi_stable` that doesn't support upgradable read locks.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::{external_types::RRwLock, std_types::RDuration};
+    ///
+    /// static DUR: RDuration = RDuration::from_millis(1);
+    ///
+    /// let lock = RRwLock::new(0);
+    ///
+    /// let guard = lock.try_upgradable_read_for(DUR).unwrap();
+    ///
+    /// assert!(lock.try_upgradable_read_for(DUR).is_none());
+    /// assert!(lock.try_read_for(DUR).is_some());
+    ///
+    /// assert_eq!(*guard, 0);
+    ///
+    /// ```
+    #[inline]
+    pub fn try_upgradable_read_for(
+        &self,
+        timeout: RDuration,
+    ) -> ROption<RUpgradableReadGuard<'_, T>> {
+        if self.vtable().try_lock_upgradable_for()(&self.raw_lock, timeout) {
+            RSome(self.upgradable_guard())
+        } else {
+            RNone
+        }
+    }
 }
 
 unsafe impl<T: Send> Send for RRwLock<T> where RawRwLock: Send {}
@@ -412,10 +563,102 @@ macro_rules! impl_lock_guard {
     };
 }
 
+macro_rules! impl_mapped_guard {
+    ($guard:ident) => {
+        impl<'a, T> Display for $guard<'a, T>
+        where
+            T: Display,
+        {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                Display::fmt(&**self, f)
+            }
+        }
+
+        impl<'a, T> Debug for $guard<'a, T>
+        where
+            T: Debug,
+        {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                Debug::fmt(&**self, f)
+            }
+        }
+
+        impl<'a, T> Deref for $guard<'a, T> {
+            type Target = T;
+
+            fn deref(&self) -> &T {
+                unsafe { &*self.data.as_ptr() }
+            }
+        }
+    };
+}
+
 //////////////////////////////////////
 
 impl_lock_guard! { RReadGuard  }
 
+impl<'a, T> RReadGuard<'a, T> {
+    /// Makes a guard for a part of the locked data,
+    /// which keeps the rwlock locked for reading until it's dropped.
+    ///
+    /// This is an associated function because `RReadGuard` dereferences to `T`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::external_types::{parking_lot::rw_lock::RReadGuard, RRwLock};
+    ///
+    /// let lock = RRwLock::new((3, "foo"));
+    ///
+    /// let guard = RReadGuard::map(lock.read(), |pair| &pair.1);
+    /// assert_eq!(*guard, "foo");
+    /// assert!(lock.try_write().is_none());
+    ///
+    /// ```
+    pub fn map<U, F>(this: Self, f: F) -> RMappedRwLockReadGuard<'a, U>
+    where
+        F: FnOnce(&T) -> &U,
+    {
+        let raw_lock = this.rlock.raw_lock();
+        let data = NonNull::from(f(unsafe { &*this.rlock.data.get() }));
+        mem::forget(this);
+        RMappedRwLockReadGuard::new(raw_lock, data)
+    }
+
+    /// Attempts to make a guard for a part of the locked data,
+    /// returning the original guard if `f` returns None.
+    ///
+    /// This is an associated function because `RReadGuard` dereferences to `T`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::external_types::{parking_lot::rw_lock::RReadGuard, RRwLock};
+    ///
+    /// let lock = RRwLock::new(vec![3, 5]);
+    ///
+    /// let guard = RReadGuard::try_map(lock.read(), |list| list.get(2)).unwrap_err();
+    /// let guard = RReadGuard::try_map(guard, |list| list.get(1)).unwrap();
+    ///
+    /// assert_eq!(*guard, 5);
+    ///
+    /// ```
+    pub fn try_map<U, F>(this: Self, f: F) -> Result<RMappedRwLockReadGuard<'a, U>, Self>
+    where
+        F: FnOnce(&T) -> Option<&U>,
+    {
+        let raw_lock = this.rlock.raw_lock();
+        match f(unsafe { &*this.rlock.data.get() }) {
+            Some(data) => {
+                let data = NonNull::from(data);
+                mem::forget(this);
+                Ok(RMappedRwLockReadGuard::new(raw_lock, data))
+            }
+            None => Err(this),
+        }
+    }
+}
+
 impl<'a, T> Drop for RReadGuard<'a, T> {
     fn drop(&mut self) {
         let vtable = self.rlock.vtable();
@@ -432,6 +675,141 @@ impl<'a, T> DerefMut for RWriteGuard<'a, T> {
     }
 }
 
+impl<'a, T> RWriteGuard<'a, T> {
+    /// Atomically downgrades this into a read guard,
+    /// without allowing any writers to take the lock in between.
+    ///
+    /// This is an associated function because `RWriteGuard` dereferences to `T`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `RRwLock<T>` was created by a dynamic library/executable
+    /// built with a version of `abi_stable` that doesn't support downgrading write locks.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::external_types::{parking_lot::rw_lock::RWriteGuard, RRwLock};
+    ///
+    /// let lock = RRwLock::new(0);
+    ///
+    /// let mut guard = lock.write();
+    /// *guard += 3;
+    ///
+    /// let guard = RWriteGuard::downgrade(guard);
+    /// assert_eq!(*lock.read(), 3);
+    /// assert!(lock.try_write().is_none());
+    ///
+    /// assert_eq!(*guard, 3);
+    ///
+    /// ```
+    pub fn downgrade(this: Self) -> RReadGuard<'a, T> {
+        let rlock = this.rlock;
+        mem::forget(this);
+        rlock.vtable().downgrade()(&rlock.raw_lock);
+        rlock.read_guard()
+    }
+
+    /// Atomically downgrades this into an upgradable read guard,
+    /// without allowing any writers to take the lock in between.
+    ///
+    /// This is an associated function because `RWriteGuard` dereferences to `T`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `RRwLock<T>` was created by a dynamic library/executable
+    /// built with a version of `abi_stable` that doesn't support downgrading write locks.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::external_types::{parking_lot::rw_lock::RWriteGuard, RRwLock};
+    ///
+    /// let lock = RRwLock::new(0);
+    ///
+    /// let mut guard = lock.write();
+    /// *guard += 3;
+    ///
+    /// let guard = RWriteGuard::downgrade_to_upgradable(guard);
+    /// assert_eq!(*lock.read(), 3);
+    /// assert!(lock.try_upgradable_read().is_none());
+    ///
+    /// assert_eq!(*guard, 3);
+    ///
+    /// ```
+    pub fn downgrade_to_upgradable(this: Self) -> RUpgradableReadGuard<'a, T> {
+        let rlock = this.rlock;
+        mem::forget(this);
+        rlock.vtable().downgrade_to_upgradable()(&rlock.raw_lock);
+        rlock.upgradable_guard()
+    }
+
+    /// Makes a guard for a part of the locked data,
+    /// which keeps the rwlock locked for writing until it's dropped.
+    ///
+    /// This is an associated function because `RWriteGuard` dereferences to `T`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::external_types::{parking_lot::rw_lock::RWriteGuard, RRwLock};
+    ///
+    /// let lock = RRwLock::new((3, "foo"));
+    ///
+    /// let mut guard = RWriteGuard::map(lock.write(), |pair| &mut pair.0);
+    /// *guard += 5;
+    /// assert!(lock.try_read().is_none());
+    /// drop(guard);
+    ///
+    /// assert_eq!(*lock.read(), (8, "foo"));
+    ///
+    /// ```
+    pub fn map<U, F>(this: Self, f: F) -> RMappedRwLockWriteGuard<'a, U>
+    where
+        F: FnOnce(&mut T) -> &mut U,
+    {
+        let raw_lock = this.rlock.raw_lock();
+        let data = NonNull::from(f(unsafe { &mut *this.rlock.data.get() }));
+        mem::forget(this);
+        RMappedRwLockWriteGuard::new(raw_lock, data)
+    }
+
+    /// Attempts to make a guard for a part of the locked data,
+    /// returning the original guard if `f` returns None.
+    ///
+    /// This is an associated function because `RWriteGuard` dereferences to `T`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::external_types::{parking_lot::rw_lock::RWriteGuard, RRwLock};
+    ///
+    /// let lock = RRwLock::new(vec![3, 5]);
+    ///
+    /// let guard = RWriteGuard::try_map(lock.write(), |list| list.get_mut(2)).unwrap_err();
+    /// let mut guard = RWriteGuard::try_map(guard, |list| list.get_mut(1)).unwrap();
+    /// *guard *= 2;
+    /// drop(guard);
+    ///
+    /// assert_eq!(*lock.read(), [3, 10]);
+    ///
+    /// ```
+    pub fn try_map<U, F>(this: Self, f: F) -> Result<RMappedRwLockWriteGuard<'a, U>, Self>
+    where
+        F: FnOnce(&mut T) -> Option<&mut U>,
+    {
+        let raw_lock = this.rlock.raw_lock();
+        match f(unsafe { &mut *this.rlock.data.get() }) {
+            Some(data) => {
+                let data = NonNull::from(data);
+                mem::forget(this);
+                Ok(RMappedRwLockWriteGuard::new(raw_lock, data))
+            }
+            None => Err(this),
+        }
+    }
+}
+
 impl<'a, T> Drop for RWriteGuard<'a, T> {
     fn drop(&mut self) {
         let vtable = self.rlock.vtable();
@@ -439,6 +817,418 @@ impl<'a, T> Drop for RWriteGuard<'a, T> {
     }
 }
 
+//////////////////////////////////////
+
+impl_lock_guard! { RUpgradableReadGuard }
+
+impl<'a, T> RUpgradableReadGuard<'a, T> {
+    /// Atomically upgrades this into a write guard,
+    /// blocking the current thread until all the read guards are dropped.
+    ///
+    /// This is an associated function because `RUpgradableReadGuard` dereferences to `T`.
+    ///
+    /// # Panics
+    ///
+    /// This doesn't panic because of an older `abi_stable` version,
+    /// since acquiring the upgradable read guard already checked
+    /// that the `RRwLock<T>` supports upgradable read locks.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::external_types::{
+    ///     parking_lot::rw_lock::RUpgradableReadGuard, RRwLock,
+    /// };
+    ///
+    /// let lock = RRwLock::new(0);
+    ///
+    /// let guard = lock.upgradable_read();
+    ///
+    /// let mut guard = RUpgradableReadGuard::upgrade(guard);
+    /// *guard += 1;
+    /// assert!(lock.try_read().is_none());
+    /// drop(guard);
+    ///
+    /// assert_eq!(*lock.read(), 1);
+    ///
+    /// ```
+    pub fn upgrade(this: Self) -> RWriteGuard<'a, T> {
+        let rlock = this.rlock;
+        mem::forget(this);
+        rlock.vtable().upgrade()(&rlock.raw_lock);
+        rlock.write_guard()
+    }
+
+    /// Attempts to atomically upgrade this into a write guard,
+    /// returning the upgradable guard back if there are any read guards.
+    ///
+    /// This is an associated function because `RUpgradableReadGuard` dereferences to `T`.
+    ///
+    /// # Panics
+    ///
+    /// This doesn't panic because of an older `abi_stable` version,
+    /// since acquiring the upgradable read guard already checked
+    /// that the `RRwLock<T>` supports upgradable read locks.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::external_types::{
+    ///     parking_lot::rw_lock::RUpgradableReadGuard, RRwLock,
+    /// };
+    ///
+    /// let lock = RRwLock::new(0);
+    ///
+    /// let guard = lock.upgradable_read();
+    ///
+    /// let read_guard = lock.read();
+    /// let guard = RUpgradableReadGuard::try_upgrade(guard).unwrap_err();
+    /// drop(read_guard);
+    ///
+    /// let mut guard = RUpgradableReadGuard::try_upgrade(guard).unwrap();
+    /// *guard += 1;
+    /// drop(guard);
+    ///
+    /// assert_eq!(*lock.read(), 1);
+    ///
+    /// ```
+    pub fn try_upgrade(this: Self) -> Result<RWriteGuard<'a, T>, Self> {
+        if this.rlock.vtable().try_upgrade()(&this.rlock.raw_lock) {
+            let rlock = this.rlock;
+            mem::forget(this);
+            Ok(rlock.write_guard())
+        } else {
+            Err(this)
+        }
+    }
+
+    /// Attempts to atomically upgrade this into a write guard,for the timeout duration.
+    ///
+    /// Once the timeout is reached,this will return the upgradable guard back,
+    /// otherwise it will return the write guard.
+    ///
+    /// This is an associated function because `RUpgradableReadGuard` dereferences to `T`.
+    ///
+    /// # Panics
+    ///
+    /// This doesn't panic because of an older `abi_stable` version,
+    /// since acquiring the upgradable read guard already checked
+    /// that the `RRwLock<T>` supports upgradable read locks.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::{
+    ///     external_types::{parking_lot::rw_lock::RUpgradableReadGuard, RRwLock},
+    ///     std_types::RDuration,
+    /// };
+    ///
+    /// static DUR: RDuration = RDuration::from_millis(1);
+    ///
+    /// let lock = RRwLock::new(0);
+    ///
+    /// let guard = lock.upgradable_read();
+    ///
+    /// let read_guard = lock.read();
+    /// let guard = RUpgradableReadGuard::try_upgrade_for(guard, DUR).unwrap_err();
+    /// drop(read_guard);
+    ///
+    /// let mut guard = RUpgradableReadGuard::try_upgrade_for(guard, DUR).unwrap();
+    /// *guard += 1;
+    /// drop(guard);
+    ///
+    /// assert_eq!(*lock.read(), 1);
+    ///
+    /// ```
+    pub fn try_upgrade_for(this: Self, timeout: RDuration) -> Result<RWriteGuard<'a, T>, Self> {
+        if this.rlock.vtable().try_upgrade_for()(&this.rlock.raw_lock, timeout) {
+            let rlock = this.rlock;
+            mem::forget(this);
+            Ok(rlock.write_guard())
+        } else {
+            Err(this)
+        }
+    }
+
+    /// Atomically downgrades this into a read guard,
+    /// allowing another upgradable read lock to be acquired.
+    ///
+    /// This is an associated function because `RUpgradableReadGuard` dereferences to `T`.
+    ///
+    /// # Panics
+    ///
+    /// This doesn't panic because of an older `abi_stable` version,
+    /// since acquiring the upgradable read guard already checked
+    /// that the `RRwLock<T>` supports upgradable read locks.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::external_types::{
+    ///     parking_lot::rw_lock::RUpgradableReadGuard, RRwLock,
+    /// };
+    ///
+    /// let lock = RRwLock::new(0);
+    ///
+    /// let guard = lock.upgradable_read();
+    /// assert!(lock.try_upgradable_read().is_none());
+    ///
+    /// let guard = RUpgradableReadGuard::downgrade(guard);
+    /// assert!(lock.try_upgradable_read().is_some());
+    ///
+    /// assert_eq!(*guard, 0);
+    ///
+    /// ```
+    pub fn downgrade(this: Self) -> RReadGuard<'a, T> {
+        let rlock = this.rlock;
+        mem::forget(this);
+        rlock.vtable().downgrade_upgradable()(&rlock.raw_lock);
+        rlock.read_guard()
+    }
+}
+
+impl<'a, T> Drop for RUpgradableReadGuard<'a, T> {
+    fn drop(&mut self) {
+        let vtable = self.rlock.vtable();
+        vtable.unlock_upgradable()(&self.rlock.raw_lock);
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// A read guard for a part of the data inside an `RRwLock`,
+/// constructed with [`RReadGuard::map`].
+///
+/// When dropped this will unlock the rwlock.
+#[repr(C)]
+#[derive(StableAbi)]
+#[sabi(bound(T:'a))]
+#[must_use]
+pub struct RMappedRwLockReadGuard<'a, T> {
+    raw_lock: RawRwLockRef<'a>,
+    data: NonNull<T>,
+    _marker: PhantomData<(&'a T, UnsyncUnsend)>,
+}
+
+/// A write guard for a part of the data inside an `RRwLock`,
+/// constructed with [`RWriteGuard::map`].
+///
+/// When dropped this will unlock the rwlock.
+#[repr(C)]
+#[derive(StableAbi)]
+#[sabi(bound(T:'a))]
+#[must_use]
+pub struct RMappedRwLockWriteGuard<'a, T> {
+    raw_lock: RawRwLockRef<'a>,
+    data: NonNull<T>,
+    _marker: PhantomData<(&'a mut T, UnsyncUnsend)>,
+}
+
+impl_mapped_guard! { RMappedRwLockReadGuard }
+
+impl<'a, T> RMappedRwLockReadGuard<'a, T> {
+    const fn new(raw_lock: RawRwLockRef<'a>, data: NonNull<T>) -> Self {
+        Self {
+            raw_lock,
+            data,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Makes a guard for a part of the locked data,
+    /// which keeps the rwlock locked for reading until it's dropped.
+    ///
+    /// This is an associated function because `RMappedRwLockReadGuard` dereferences to `T`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::external_types::{
+    ///     parking_lot::rw_lock::{RMappedRwLockReadGuard, RReadGuard},
+    ///     RRwLock,
+    /// };
+    ///
+    /// let lock = RRwLock::new((3, (5, 8)));
+    ///
+    /// let guard = RReadGuard::map(lock.read(), |pair| &pair.1);
+    /// let guard = RMappedRwLockReadGuard::map(guard, |pair| &pair.0);
+    ///
+    /// assert_eq!(*guard, 5);
+    ///
+    /// ```
+    pub fn map<U, F>(this: Self, f: F) -> RMappedRwLockReadGuard<'a, U>
+    where
+        F: FnOnce(&T) -> &U,
+    {
+        let raw_lock = this.raw_lock;
+        let data = NonNull::from(f(unsafe { &*this.data.as_ptr() }));
+        mem::forget(this);
+        RMappedRwLockReadGuard::new(raw_lock, data)
+    }
+
+    /// Attempts to make a guard for a part of the locked data,
+    /// returning the original guard if `f` returns None.
+    ///
+    /// This is an associated function because `RMappedRwLockReadGuard` dereferences to `T`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::external_types::{
+    ///     parking_lot::rw_lock::{RMappedRwLockReadGuard, RReadGuard},
+    ///     RRwLock,
+    /// };
+    ///
+    /// let lock = RRwLock::new((3, vec![5, 8]));
+    ///
+    /// let guard = RReadGuard::map(lock.read(), |pair| &pair.1);
+    /// let guard = RMappedRwLockReadGuard::try_map(guard, |list| list.get(2)).unwrap_err();
+    /// let guard = RMappedRwLockReadGuard::try_map(guard, |list| list.get(1)).unwrap();
+    ///
+    /// assert_eq!(*guard, 8);
+    ///
+    /// ```
+    pub fn try_map<U, F>(this: Self, f: F) -> Result<RMappedRwLockReadGuard<'a, U>, Self>
+    where
+        F: FnOnce(&T) -> Option<&U>,
+    {
+        let raw_lock = this.raw_lock;
+        match f(unsafe { &*this.data.as_ptr() }) {
+            Some(data) => {
+                let data = NonNull::from(data);
+                mem::forget(this);
+                Ok(RMappedRwLockReadGuard::new(raw_lock, data))
+            }
+            None => Err(this),
+        }
+    }
+}
+
+impl<'a, T> Drop for RMappedRwLockReadGuard<'a, T> {
+    fn drop(&mut self) {
+        self.raw_lock.unlock_shared();
+    }
+}
+
+//////////////////////////////////////
+
+impl_mapped_guard! { RMappedRwLockWriteGuard }
+
+impl<'a, T> DerefMut for RMappedRwLockWriteGuard<'a, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.data.as_ptr() }
+    }
+}
+
+impl<'a, T> RMappedRwLockWriteGuard<'a, T> {
+    const fn new(raw_lock: RawRwLockRef<'a>, data: NonNull<T>) -> Self {
+        Self {
+            raw_lock,
+            data,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Makes a guard for a part of the locked data,
+    /// which keeps the rwlock locked for writing until it's dropped.
+    ///
+    /// This is an associated function because `RMappedRwLockWriteGuard` dereferences to `T`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::external_types::{
+    ///     parking_lot::rw_lock::{RMappedRwLockWriteGuard, RWriteGuard},
+    ///     RRwLock,
+    /// };
+    ///
+    /// let lock = RRwLock::new((3, (5, 8)));
+    ///
+    /// let guard = RWriteGuard::map(lock.write(), |pair| &mut pair.1);
+    /// let mut guard = RMappedRwLockWriteGuard::map(guard, |pair| &mut pair.0);
+    /// *guard += 8;
+    /// drop(guard);
+    ///
+    /// assert_eq!(*lock.read(), (3, (13, 8)));
+    ///
+    /// ```
+    pub fn map<U, F>(this: Self, f: F) -> RMappedRwLockWriteGuard<'a, U>
+    where
+        F: FnOnce(&mut T) -> &mut U,
+    {
+        let raw_lock = this.raw_lock;
+        let data = NonNull::from(f(unsafe { &mut *this.data.as_ptr() }));
+        mem::forget(this);
+        RMappedRwLockWriteGuard::new(raw_lock, data)
+    }
+
+    /// Attempts to make a guard for a part of the locked data,
+    /// returning the original guard if `f` returns None.
+    ///
+    /// This is an associated function because `RMappedRwLockWriteGuard` dereferences to `T`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::external_types::{
+    ///     parking_lot::rw_lock::{RMappedRwLockWriteGuard, RWriteGuard},
+    ///     RRwLock,
+    /// };
+    ///
+    /// let lock = RRwLock::new((3, vec![5, 8]));
+    ///
+    /// let guard = RWriteGuard::map(lock.write(), |pair| &mut pair.1);
+    /// let guard = RMappedRwLockWriteGuard::try_map(guard, |list| list.get_mut(2)).unwrap_err();
+    /// let mut guard = RMappedRwLockWriteGuard::try_map(guard, |list| list.get_mut(0)).unwrap();
+    /// *guard += 8;
+    /// drop(guard);
+    ///
+    /// assert_eq!(*lock.read(), (3, vec![13, 8]));
+    ///
+    /// ```
+    pub fn try_map<U, F>(this: Self, f: F) -> Result<RMappedRwLockWriteGuard<'a, U>, Self>
+    where
+        F: FnOnce(&mut T) -> Option<&mut U>,
+    {
+        let raw_lock = this.raw_lock;
+        match f(unsafe { &mut *this.data.as_ptr() }) {
+            Some(data) => {
+                let data = NonNull::from(data);
+                mem::forget(this);
+                Ok(RMappedRwLockWriteGuard::new(raw_lock, data))
+            }
+            None => Err(this),
+        }
+    }
+}
+
+impl<'a, T> Drop for RMappedRwLockWriteGuard<'a, T> {
+    fn drop(&mut self) {
+        self.raw_lock.unlock_exclusive();
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// A reference to the raw lock of an `RRwLock`,
+/// which unlocks it with the functions of the library that created it.
+#[repr(C)]
+#[derive(StableAbi, Copy, Clone)]
+struct RawRwLockRef<'a> {
+    raw_lock: &'a OpaqueRwLock,
+    vtable: VTable_Ref,
+}
+
+impl RawRwLockRef<'_> {
+    fn unlock_shared(self) {
+        self.vtable.unlock_shared()(self.raw_lock);
+    }
+
+    fn unlock_exclusive(self) {
+        self.vtable.unlock_exclusive()(self.raw_lock);
+    }
+}
+
 ///////////////////////////////////////////////////////////////////////////////
 
 #[repr(C)]
@@ -456,6 +1246,19 @@ struct VTable {
     try_lock_exclusive_for: extern "C" fn(this: &OpaqueRwLock, timeout: RDuration) -> bool,
     #[sabi(last_prefix_field)]
     unlock_exclusive: extern "C" fn(this: &OpaqueRwLock),
+
+    lock_upgradable: extern "C" fn(this: &OpaqueRwLock),
+    try_lock_upgradable: extern "C" fn(this: &OpaqueRwLock) -> bool,
+    try_lock_upgradable_for: extern "C" fn(this: &OpaqueRwLock, timeout: RDuration) -> bool,
+    unlock_upgradable: extern "C" fn(this: &OpaqueRwLock),
+
+    upgrade: extern "C" fn(this: &OpaqueRwLock),
+    try_upgrade: extern "C" fn(this: &OpaqueRwLock) -> bool,
+    try_upgrade_for: extern "C" fn(this: &OpaqueRwLock, timeout: RDuration) -> bool,
+
+    downgrade: extern "C" fn(this: &OpaqueRwLock),
+    downgrade_upgradable: extern "C" fn(this: &OpaqueRwLock),
+    downgrade_to_upgradable: extern "C" fn(this: &OpaqueRwLock),
 }
 
 impl VTable {
@@ -469,6 +1272,16 @@ impl VTable {
             try_lock_exclusive,
             try_lock_exclusive_for,
             unlock_exclusive,
+            lock_upgradable,
+            try_lock_upgradable,
+            try_lock_upgradable_for,
+            unlock_upgradable,
+            upgrade,
+            try_upgrade,
+            try_upgrade_for,
+            downgrade,
+            downgrade_upgradable,
+            downgrade_to_upgradable,
         };
         WithMetadata::new(vtable)
     };
@@ -523,6 +1336,73 @@ extern "C" fn unlock_exclusive(this: &OpaqueRwLock) {
     }
 }
 
+extern "C" fn lock_upgradable(this: &OpaqueRwLock) {
+    extern_fn_panic_handling! {
+        this.value.lock_upgradable();
+    }
+}
+extern "C" fn try_lock_upgradable(this: &OpaqueRwLock) -> bool {
+    extern_fn_panic_handling! {
+        this.value.try_lock_upgradable()
+    }
+}
+extern "C" fn try_lock_upgradable_for(this: &OpaqueRwLock, timeout: RDuration) -> bool {
+    extern_fn_panic_handling! {
+        this.value.try_lock_upgradable_for(timeout.into())
+    }
+}
+extern "C" fn unlock_upgradable(this: &OpaqueRwLock) {
+    extern_fn_panic_handling! {
+        unsafe{
+            this.value.unlock_upgradable();
+        }
+    }
+}
+
+extern "C" fn upgrade(this: &OpaqueRwLock) {
+    extern_fn_panic_handling! {
+        unsafe{
+            this.value.upgrade();
+        }
+    }
+}
+extern "C" fn try_upgrade(this: &OpaqueRwLock) -> bool {
+    extern_fn_panic_handling! {
+        unsafe{
+            this.value.try_upgrade()
+        }
+    }
+}
+extern "C" fn try_upgrade_for(this: &OpaqueRwLock, timeout: RDuration) -> bool {
+    extern_fn_panic_handling! {
+        unsafe{
+            this.value.try_upgrade_for(timeout.into())
+        }
+    }
+}
+
+extern "C" fn downgrade(this: &OpaqueRwLock) {
+    extern_fn_panic_handling! {
+        unsafe{
+            this.value.downgrade();
+        }
+    }
+}
+extern "C" fn downgrade_upgradable(this: &OpaqueRwLock) {
+    extern_fn_panic_handling! {
+        unsafe{
+            this.value.downgrade_upgradable();
+        }
+    }
+}
+extern "C" fn downgrade_to_upgradable(this: &OpaqueRwLock) {
+    extern_fn_panic_handling! {
+        unsafe{
+            this.value.downgrade_to_upgradable();
+        }
+    }
+}
+
 ///////////////////////////////////////////////////////////////////////////////
 
 #[cfg(all(test, not(feature = "only_new_tests")))]
@@ -562,6 +1442,109 @@ mod tests {
         check_formatting_equivalence(&lock.write(), str_);
     }
 
+    #[test]
+    fn upgradable_and_mapped_debug_display() {
+        let str_ = "\nhello\rhello\rhello\n";
+        let lock = RRwLock::new(str_);
+        check_formatting_equivalence(&lock.upgradable_read(), str_);
+        check_formatting_equivalence(&RReadGuard::map(lock.read(), |x| x), str_);
+        check_formatting_equivalence(&RWriteGuard::map(lock.write(), |x| x), str_);
+    }
+
+    #[test]
+    fn mapped_guards() {
+        let lock = RRwLock::new((0u32, vec![0u32; 4]));
+
+        {
+            let guard = RReadGuard::map(lock.read(), |(_, list)| list);
+            assert!(lock.try_write().is_none());
+            let guard = RMappedRwLockReadGuard::map(guard, |list| &list[1]);
+            assert_eq!(*guard, 0);
+            let guard = RMappedRwLockReadGuard::try_map(guard, |_| None::<&u8>).unwrap_err();
+            assert!(lock.try_read().is_some());
+            drop(guard);
+        }
+        {
+            let mut guard = RWriteGuard::map(lock.write(), |(_, list)| list);
+            assert!(lock.try_read().is_none());
+            guard.push(10);
+            let guard = RMappedRwLockWriteGuard::try_map(guard, |list| list.get_mut(9));
+            let guard = guard.unwrap_err();
+            let mut guard = RMappedRwLockWriteGuard::map(guard, |list| &mut list[2]);
+            *guard += 3;
+        }
+        {
+            let guard = RWriteGuard::try_map(lock.write(), |(x, _)| Some(x)).unwrap();
+            drop(guard);
+            let guard = RReadGuard::try_map(lock.read(), |(_, list)| list.get(100));
+            drop(guard.unwrap_err());
+        }
+
+        assert!(lock.try_write().is_some());
+        assert_eq!(*lock.read(), (0, vec![0, 0, 3, 0, 10]));
+    }
+
+    #[test]
+    fn upgradable_read() {
+        let lock = RRwLock::new(0);
+
+        let guard = lock.upgradable_read();
+        assert!(lock.try_upgradable_read().is_none());
+        assert!(lock.try_write().is_none());
+        let read_guard = lock.read();
+        let guard = RUpgradableReadGuard::try_upgrade(guard).unwrap_err();
+        drop(read_guard);
+        let mut guard = RUpgradableReadGuard::upgrade(guard);
+        *guard += 1;
+        assert!(lock.try_read().is_none());
+
+        let guard = RWriteGuard::downgrade_to_upgradable(guard);
+        assert_eq!(*lock.read(), 1);
+        assert!(lock.try_upgradable_read().is_none());
+
+        let guard = RUpgradableReadGuard::downgrade(guard);
+        assert!(lock.try_write().is_none());
+        assert!(lock.try_upgradable_read().is_some());
+        drop(guard);
+
+        let mut guard = RUpgradableReadGuard::try_upgrade(lock.upgradable_read()).unwrap();
+        *guard += 1;
+        let guard = RWriteGuard::downgrade(guard);
+        assert_eq!(*guard, 2);
+        assert!(lock.try_upgradable_read().is_some());
+        drop(guard);
+
+        assert!(lock.try_write().is_some());
+    }
+
+    #[test]
+    #[cfg(not(all(miri, target_os = "windows")))]
+    fn upgradable_read_threads() {
+        static LOCK: RRwLock<usize> = RRwLock::new(0);
+
+        scoped_thread(|scope| {
+            for _ in 0..8 {
+                scope.spawn(move |_| {
+                    for _ in 0..8 {
+                        let guard = LOCK.upgradable_read();
+                        let value = *guard;
+                        let mut guard = RUpgradableReadGuard::upgrade(guard);
+                        assert_eq!(*guard, value);
+                        *guard += 1;
+                    }
+                });
+                scope.spawn(move |_| {
+                    for _ in 0..8 {
+                        assert!(*LOCK.read() <= EXPECTED);
+                    }
+                });
+            }
+        })
+        .unwrap();
+
+        assert_eq!(*LOCK.read(), EXPECTED);
+    }
+
     const EXPECTED: usize = 64;
 
     #[test]
@@ -10,6 +10,10 @@ use serde_json::{error::Error as JsonError, value::RawValue};
 
 use crate::std_types::{RStr, RString};
 
+mod value;
+
+pub use self::value::{RMap, RNumber, RValue, RValueIndex};
+
 /// An ffi-safe equivalent of `&serde_json::value::RawValue`
 ///
 /// # Example
@@ -0,0 +1,1075 @@
+use std::{
+    fmt::{self, Debug, Display},
+    ops::{Index, IndexMut},
+};
+
+use serde::{
+    de::{self, MapAccess, SeqAccess, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use serde_json::{Number, Value};
+
+use crate::std_types::{RIndexMap, RString, RVec, Tuple2};
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// An ffi-safe equivalent of `serde_json::Value`, any valid JSON value.
+///
+/// This allows passing structured data between dynamic libraries
+/// without reparsing it on each side,
+/// converting it from/to a `serde_json::Value` is lossless.
+///
+/// # Example
+///
+/// ```
+/// use abi_stable::{
+///     external_types::serde_json::RValue,
+///     sabi_extern_fn,
+///     std_types::RString,
+/// };
+///
+/// #[sabi_extern_fn]
+/// fn describe_pet(pet: &RValue) -> RString {
+///     format!("{} is {} years old", pet["name"], pet["age"]).into()
+/// }
+///
+/// let pet = serde_json::json!({"name": "Bob", "age": 7, "tags": ["dog", "good"]});
+/// let rpet = RValue::from(pet.clone());
+///
+/// assert_eq!(describe_pet(&rpet), r#""Bob" is 7 years old"#);
+/// assert_eq!(rpet["tags"][1], "good");
+/// assert_eq!(serde_json::from_str::<RValue>(&pet.to_string()).unwrap(), rpet);
+/// assert_eq!(serde_json::Value::from(rpet), pet);
+///
+/// ```
+#[repr(u8)]
+#[derive(Debug, Clone, PartialEq, Eq, StableAbi)]
+pub enum RValue {
+    /// The JSON `null` value.
+    Null,
+    /// A JSON boolean.
+    Bool(bool),
+    /// A JSON number.
+    Number(RNumber),
+    /// A JSON string.
+    String(RString),
+    /// A JSON array.
+    Array(RVec<RValue>),
+    /// A JSON object.
+    Object(RMap),
+}
+
+/// An ffi-safe map from strings to [`RValue`]s, the contents of a JSON object.
+///
+/// The entries are kept in insertion order,
+/// like in the `serde_json::Map` used when its `preserve_order` feature is enabled.
+///
+/// # Example
+///
+/// ```
+/// use abi_stable::external_types::serde_json::{RMap, RValue};
+///
+/// let mut map = RMap::new();
+/// map.insert("foo".into(), RValue::from(3));
+/// map.insert("bar".into(), RValue::from("hello"));
+///
+/// assert_eq!(map.len(), 2);
+/// assert_eq!(map["foo"], 3);
+/// assert_eq!(map.get("bar"), Some(&RValue::from("hello")));
+/// assert_eq!(map.keys().collect::<Vec<_>>(), ["foo", "bar"]);
+///
+/// ```
+pub type RMap = RIndexMap<RString, RValue>;
+
+impl Default for RValue {
+    fn default() -> Self {
+        RValue::Null
+    }
+}
+
+impl RValue {
+    /// Indexes into an array or object,
+    /// returning `None` if the index/key isn't in it, or this is neither.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::external_types::serde_json::RValue;
+    ///
+    /// let value = RValue::from(serde_json::json!({"list": [3, 5, 8]}));
+    ///
+    /// assert_eq!(value.get("list").and_then(|list| list.get(1)), Some(&RValue::from(5)));
+    /// assert_eq!(value.get("nope"), None);
+    /// assert_eq!(value.get(0), None);
+    ///
+    /// ```
+    pub fn get<I: RValueIndex>(&self, index: I) -> Option<&RValue> {
+        index.index_into(self)
+    }
+
+    /// Mutably indexes into an array or object,
+    /// returning `None` if the index/key isn't in it, or this is neither.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::external_types::serde_json::RValue;
+    ///
+    /// let mut value = RValue::from(serde_json::json!({"list": [3, 5, 8]}));
+    ///
+    /// *value.get_mut("list").unwrap().get_mut(0).unwrap() = RValue::from(13);
+    ///
+    /// assert_eq!(value["list"][0], 13);
+    ///
+    /// ```
+    pub fn get_mut<I: RValueIndex>(&mut self, index: I) -> Option<&mut RValue> {
+        index.index_into_mut(self)
+    }
+
+    /// Whether this is `RValue::Null`.
+    pub const fn is_null(&self) -> bool {
+        matches!(self, RValue::Null)
+    }
+
+    /// Whether this is an `RValue::Bool`.
+    pub const fn is_boolean(&self) -> bool {
+        matches!(self, RValue::Bool(_))
+    }
+
+    /// Whether this is an `RValue::Number`.
+    pub const fn is_number(&self) -> bool {
+        matches!(self, RValue::Number(_))
+    }
+
+    /// Whether this is an `RValue::String`.
+    pub const fn is_string(&self) -> bool {
+        matches!(self, RValue::String(_))
+    }
+
+    /// Whether this is an `RValue::Array`.
+    pub const fn is_array(&self) -> bool {
+        matches!(self, RValue::Array(_))
+    }
+
+    /// Whether this is an `RValue::Object`.
+    pub const fn is_object(&self) -> bool {
+        matches!(self, RValue::Object(_))
+    }
+
+    /// Gets the boolean, if this is an `RValue::Bool`.
+    pub const fn as_bool(&self) -> Option<bool> {
+        match self {
+            RValue::Bool(x) => Some(*x),
+            _ => None,
+        }
+    }
+
+    /// Gets the number, if this is an `RValue::Number`.
+    pub const fn as_number(&self) -> Option<&RNumber> {
+        match self {
+            RValue::Number(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    /// Gets the number as a `u64`, if this is a number representable as one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::external_types::serde_json::RValue;
+    ///
+    /// assert_eq!(RValue::from(3).as_u64(), Some(3));
+    /// assert_eq!(RValue::from(-3).as_u64(), None);
+    /// assert_eq!(RValue::from("3").as_u64(), None);
+    ///
+    /// ```
+    pub fn as_u64(&self) -> Option<u64> {
+        self.as_number().and_then(RNumber::as_u64)
+    }
+
+    /// Gets the number as an `i64`, if this is a number representable as one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::external_types::serde_json::RValue;
+    ///
+    /// assert_eq!(RValue::from(-3).as_i64(), Some(-3));
+    /// assert_eq!(RValue::from(u64::MAX).as_i64(), None);
+    ///
+    /// ```
+    pub fn as_i64(&self) -> Option<i64> {
+        self.as_number().and_then(RNumber::as_i64)
+    }
+
+    /// Gets the number as an `f64`, if this is a number.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::external_types::serde_json::RValue;
+    ///
+    /// assert_eq!(RValue::from(2).as_f64(), Some(2.0));
+    /// assert_eq!(RValue::from(0.5).as_f64(), Some(0.5));
+    ///
+    /// ```
+    pub fn as_f64(&self) -> Option<f64> {
+        self.as_number().map(RNumber::as_f64)
+    }
+
+    /// Gets the string, if this is an `RValue::String`.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            RValue::String(x) => Some(x.as_str()),
+            _ => None,
+        }
+    }
+
+    /// Gets the array, if this is an `RValue::Array`.
+    pub const fn as_array(&self) -> Option<&RVec<RValue>> {
+        match self {
+            RValue::Array(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    /// Gets the array mutably, if this is an `RValue::Array`.
+    pub fn as_array_mut(&mut self) -> Option<&mut RVec<RValue>> {
+        match self {
+            RValue::Array(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    /// Gets the object, if this is an `RValue::Object`.
+    pub const fn as_object(&self) -> Option<&RMap> {
+        match self {
+            RValue::Object(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    /// Gets the object mutably, if this is an `RValue::Object`.
+    pub fn as_object_mut(&mut self) -> Option<&mut RMap> {
+        match self {
+            RValue::Object(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    /// Takes the value out, leaving `RValue::Null` in its place.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::external_types::serde_json::RValue;
+    ///
+    /// let mut value = RValue::from(serde_json::json!({"x": "hello"}));
+    ///
+    /// assert_eq!(value["x"].take(), "hello");
+    /// assert_eq!(value["x"], RValue::Null);
+    ///
+    /// ```
+    pub fn take(&mut self) -> RValue {
+        std::mem::take(self)
+    }
+}
+
+/// Formats the value as JSON, pretty printed with the `{:#}` format.
+impl Display for RValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let json = if f.alternate() {
+            serde_json::to_string_pretty(self)
+        } else {
+            serde_json::to_string(self)
+        };
+        f.write_str(&json.map_err(|_| fmt::Error)?)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// A type that can index into an [`RValue`],
+/// either a `usize` for arrays or a string for objects.
+///
+/// This trait is sealed, it can't be implemented outside of `abi_stable`.
+pub trait RValueIndex: private::Sealed {
+    #[doc(hidden)]
+    fn index_into<'v>(&self, value: &'v RValue) -> Option<&'v RValue>;
+
+    #[doc(hidden)]
+    fn index_into_mut<'v>(&self, value: &'v mut RValue) -> Option<&'v mut RValue>;
+
+    #[doc(hidden)]
+    fn index_or_insert<'v>(&self, value: &'v mut RValue) -> &'v mut RValue;
+}
+
+mod private {
+    pub trait Sealed {}
+    impl Sealed for usize {}
+    impl Sealed for str {}
+    impl Sealed for String {}
+    impl Sealed for crate::std_types::RString {}
+    impl<T: ?Sized + Sealed> Sealed for &T {}
+}
+
+impl RValueIndex for usize {
+    fn index_into<'v>(&self, value: &'v RValue) -> Option<&'v RValue> {
+        value.as_array().and_then(|array| array.get(*self))
+    }
+
+    fn index_into_mut<'v>(&self, value: &'v mut RValue) -> Option<&'v mut RValue> {
+        value.as_array_mut().and_then(|array| array.get_mut(*self))
+    }
+
+    fn index_or_insert<'v>(&self, value: &'v mut RValue) -> &'v mut RValue {
+        match value {
+            RValue::Array(array) => {
+                let len = array.len();
+                array.get_mut(*self).unwrap_or_else(|| {
+                    panic!(
+                        "cannot access index {} of JSON array of length {}",
+                        self, len
+                    )
+                })
+            }
+            _ => panic!("cannot access index {} of JSON {}", self, kind_name(value)),
+        }
+    }
+}
+
+impl RValueIndex for str {
+    fn index_into<'v>(&self, value: &'v RValue) -> Option<&'v RValue> {
+        value.as_object().and_then(|map| map.get(self))
+    }
+
+    fn index_into_mut<'v>(&self, value: &'v mut RValue) -> Option<&'v mut RValue> {
+        value.as_object_mut().and_then(|map| map.get_mut(self))
+    }
+
+    fn index_or_insert<'v>(&self, value: &'v mut RValue) -> &'v mut RValue {
+        if value.is_null() {
+            *value = RValue::Object(RMap::new());
+        }
+        match value {
+            RValue::Object(map) => {
+                if !map.contains_key(self) {
+                    map.insert(self.into(), RValue::Null);
+                }
+                &mut map[self]
+            }
+            _ => panic!("cannot access key {:?} in JSON {}", self, kind_name(value)),
+        }
+    }
+}
+
+impl RValueIndex for String {
+    fn index_into<'v>(&self, value: &'v RValue) -> Option<&'v RValue> {
+        self.as_str().index_into(value)
+    }
+
+    fn index_into_mut<'v>(&self, value: &'v mut RValue) -> Option<&'v mut RValue> {
+        self.as_str().index_into_mut(value)
+    }
+
+    fn index_or_insert<'v>(&self, value: &'v mut RValue) -> &'v mut RValue {
+        self.as_str().index_or_insert(value)
+    }
+}
+
+impl RValueIndex for RString {
+    fn index_into<'v>(&self, value: &'v RValue) -> Option<&'v RValue> {
+        self.as_str().index_into(value)
+    }
+
+    fn index_into_mut<'v>(&self, value: &'v mut RValue) -> Option<&'v mut RValue> {
+        self.as_str().index_into_mut(value)
+    }
+
+    fn index_or_insert<'v>(&self, value: &'v mut RValue) -> &'v mut RValue {
+        self.as_str().index_or_insert(value)
+    }
+}
+
+impl<T> RValueIndex for &T
+where
+    T: ?Sized + RValueIndex,
+{
+    fn index_into<'v>(&self, value: &'v RValue) -> Option<&'v RValue> {
+        (**self).index_into(value)
+    }
+
+    fn index_into_mut<'v>(&self, value: &'v mut RValue) -> Option<&'v mut RValue> {
+        (**self).index_into_mut(value)
+    }
+
+    fn index_or_insert<'v>(&self, value: &'v mut RValue) -> &'v mut RValue {
+        (**self).index_or_insert(value)
+    }
+}
+
+fn kind_name(value: &RValue) -> &'static str {
+    match value {
+        RValue::Null => "null",
+        RValue::Bool(_) => "boolean",
+        RValue::Number(_) => "number",
+        RValue::String(_) => "string",
+        RValue::Array(_) => "array",
+        RValue::Object(_) => "object",
+    }
+}
+
+static NULL: RValue = RValue::Null;
+
+/// Indexes into an array or object,
+/// returning `RValue::Null` if the index/key isn't in it, or this is neither.
+impl<I> Index<I> for RValue
+where
+    I: RValueIndex,
+{
+    type Output = RValue;
+
+    fn index(&self, index: I) -> &RValue {
+        index.index_into(self).unwrap_or(&NULL)
+    }
+}
+
+/// Mutably indexes into an array or object.
+///
+/// Indexing into an object with a missing key inserts `RValue::Null` for that key,
+/// and indexing into `RValue::Null` with a key turns it into an empty object first.
+///
+/// # Panics
+///
+/// Panics if the index is out of bounds of the array,
+/// or the value is not the kind of value that the index is for.
+impl<I> IndexMut<I> for RValue
+where
+    I: RValueIndex,
+{
+    fn index_mut(&mut self, index: I) -> &mut RValue {
+        index.index_or_insert(self)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// An ffi-safe equivalent of `serde_json::Number`.
+///
+/// This is stored as a `u64` if it's a non-negative integer,
+/// as an `i64` if it's a negative integer,
+/// as a finite `f64` if it's exactly representable as one,
+/// otherwise as its decimal string
+/// (only possible with the `arbitrary_precision` feature of `serde_json`).
+///
+/// # Example
+///
+/// ```
+/// use abi_stable::external_types::serde_json::RNumber;
+///
+/// assert_eq!(RNumber::from(3u8).as_u64(), Some(3));
+/// assert_eq!(RNumber::from(-3).as_i64(), Some(-3));
+/// assert_eq!(RNumber::from_f64(0.5).unwrap().as_f64(), 0.5);
+/// assert_eq!(RNumber::from_f64(f64::NAN), None);
+///
+/// ```
+#[repr(transparent)]
+#[derive(Clone, PartialEq, StableAbi)]
+pub struct RNumber {
+    n: N,
+}
+
+mod repr {
+    use crate::std_types::RString;
+
+    #[repr(u8)]
+    #[derive(Clone, PartialEq, StableAbi)]
+    pub enum N {
+        PosInt(u64),
+        NegInt(i64),
+        Float(f64),
+        Arbitrary(RString),
+    }
+}
+
+use self::repr::N;
+
+// Floats are never NaN.
+impl Eq for RNumber {}
+
+impl RNumber {
+    /// Constructs an `RNumber` from a float, returning `None` if it's not finite.
+    pub fn from_f64(n: f64) -> Option<Self> {
+        if n.is_finite() {
+            Some(Self { n: N::Float(n) })
+        } else {
+            None
+        }
+    }
+
+    /// Whether this is an integer representable as a `u64`.
+    pub const fn is_u64(&self) -> bool {
+        matches!(self.n, N::PosInt(_))
+    }
+
+    /// Whether this is an integer representable as an `i64`.
+    pub const fn is_i64(&self) -> bool {
+        match self.n {
+            N::PosInt(x) => x <= i64::MAX as u64,
+            N::NegInt(_) => true,
+            N::Float(_) | N::Arbitrary(_) => false,
+        }
+    }
+
+    /// Whether this is a number with a fractional part or an exponent.
+    pub const fn is_f64(&self) -> bool {
+        matches!(self.n, N::Float(_))
+    }
+
+    /// Gets this as a `u64`, if it's an integer representable as one.
+    pub const fn as_u64(&self) -> Option<u64> {
+        match self.n {
+            N::PosInt(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    /// Gets this as an `i64`, if it's an integer representable as one.
+    pub const fn as_i64(&self) -> Option<i64> {
+        match self.n {
+            N::PosInt(x) if x <= i64::MAX as u64 => Some(x as i64),
+            N::NegInt(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    /// Gets this as an `f64`, which may lose precision for large integers.
+    ///
+    /// Numbers outside the range of `f64` are converted to an infinity.
+    pub fn as_f64(&self) -> f64 {
+        match self.n {
+            N::PosInt(x) => x as f64,
+            N::NegInt(x) => x as f64,
+            N::Float(x) => x,
+            N::Arbitrary(ref x) => x.parse().expect("RNumber strings are valid numbers"),
+        }
+    }
+}
+
+impl Debug for RNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "RNumber({})", self)
+    }
+}
+
+impl Display for RNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.n {
+            N::Arbitrary(ref x) => Display::fmt(x, f),
+            _ => Display::fmt(&Number::from(self.clone()), f),
+        }
+    }
+}
+
+macro_rules! impl_from_unsigned {
+    ($($ty:ty),*) => {$(
+        impl From<$ty> for RNumber {
+            fn from(n: $ty) -> Self {
+                Self { n: N::PosInt(n as u64) }
+            }
+        }
+    )*};
+}
+
+macro_rules! impl_from_signed {
+    ($($ty:ty),*) => {$(
+        impl From<$ty> for RNumber {
+            fn from(n: $ty) -> Self {
+                let n = if n < 0 {
+                    N::NegInt(n as i64)
+                } else {
+                    N::PosInt(n as u64)
+                };
+                Self { n }
+            }
+        }
+    )*};
+}
+
+impl_from_unsigned! {u8, u16, u32, u64, usize}
+impl_from_signed! {i8, i16, i32, i64, isize}
+
+/// Numbers that aren't exactly representable as a `u64`, an `i64`, or an `f64`
+/// (only possible with the `arbitrary_precision` feature of `serde_json`)
+/// are stored as their decimal string.
+impl From<Number> for RNumber {
+    fn from(n: Number) -> Self {
+        let n = if let Some(x) = n.as_u64() {
+            N::PosInt(x)
+        } else if let Some(x) = n.as_i64() {
+            N::NegInt(x)
+        } else if let Some(x) = n
+            .as_f64()
+            .filter(|&x| Number::from_f64(x).as_ref() == Some(&n))
+        {
+            N::Float(x)
+        } else {
+            N::Arbitrary(n.to_string().into())
+        };
+        Self { n }
+    }
+}
+
+/// Numbers stored as a decimal string are parsed back into a `Number`.
+/// If the `arbitrary_precision` feature of `serde_json` is disabled this loses precision,
+/// and numbers outside the range of `f64` saturate to the largest finite `f64` of the same sign.
+impl From<RNumber> for Number {
+    fn from(n: RNumber) -> Self {
+        match n.n {
+            N::PosInt(x) => Number::from(x),
+            N::NegInt(x) => Number::from(x),
+            N::Float(x) => Number::from_f64(x).expect("RNumber floats are always finite"),
+            N::Arbitrary(x) => x.parse().unwrap_or_else(|_| {
+                let x = if x.starts_with('-') {
+                    f64::MIN
+                } else {
+                    f64::MAX
+                };
+                Number::from_f64(x).unwrap()
+            }),
+        }
+    }
+}
+
+impl Serialize for RNumber {
+    fn serialize<Z>(&self, serializer: Z) -> Result<Z::Ok, Z::Error>
+    where
+        Z: Serializer,
+    {
+        match self.n {
+            N::PosInt(x) => serializer.serialize_u64(x),
+            N::NegInt(x) => serializer.serialize_i64(x),
+            N::Float(x) => serializer.serialize_f64(x),
+            N::Arbitrary(_) => Number::from(self.clone()).serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for RNumber {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match RValue::deserialize(deserializer)? {
+            RValue::Number(n) => Ok(n),
+            other => Err(de::Error::invalid_type(
+                de::Unexpected::Other(kind_name(&other)),
+                &"a JSON number",
+            )),
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+impl From<Value> for RValue {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::Null => RValue::Null,
+            Value::Bool(x) => RValue::Bool(x),
+            Value::Number(x) => RValue::Number(x.into()),
+            Value::String(x) => RValue::String(x.into()),
+            Value::Array(x) => RValue::Array(x.into_iter().map(RValue::from).collect()),
+            Value::Object(x) => RValue::Object(
+                x.into_iter()
+                    .map(|(key, value)| (RString::from(key), RValue::from(value)))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+impl From<RValue> for Value {
+    fn from(value: RValue) -> Self {
+        match value {
+            RValue::Null => Value::Null,
+            RValue::Bool(x) => Value::Bool(x),
+            RValue::Number(x) => Value::Number(x.into()),
+            RValue::String(x) => Value::String(x.into()),
+            RValue::Array(x) => Value::Array(x.into_iter().map(Value::from).collect()),
+            RValue::Object(x) => Value::Object(
+                x.into_iter()
+                    .map(|Tuple2(key, value)| (key.into(), Value::from(value)))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+impl From<()> for RValue {
+    fn from(_: ()) -> Self {
+        RValue::Null
+    }
+}
+
+impl From<bool> for RValue {
+    fn from(x: bool) -> Self {
+        RValue::Bool(x)
+    }
+}
+
+macro_rules! impl_from_integer {
+    ($($ty:ty),*) => {$(
+        impl From<$ty> for RValue {
+            fn from(x: $ty) -> Self {
+                RValue::Number(x.into())
+            }
+        }
+    )*};
+}
+
+impl_from_integer! {u8, u16, u32, u64, usize, i8, i16, i32, i64, isize}
+
+/// Non-finite floats are converted to `RValue::Null`, like `serde_json` does.
+impl From<f64> for RValue {
+    fn from(x: f64) -> Self {
+        RNumber::from_f64(x).map_or(RValue::Null, RValue::Number)
+    }
+}
+
+/// Non-finite floats are converted to `RValue::Null`, like `serde_json` does.
+impl From<f32> for RValue {
+    fn from(x: f32) -> Self {
+        RValue::from(x as f64)
+    }
+}
+
+impl From<RNumber> for RValue {
+    fn from(x: RNumber) -> Self {
+        RValue::Number(x)
+    }
+}
+
+impl From<&str> for RValue {
+    fn from(x: &str) -> Self {
+        RValue::String(x.into())
+    }
+}
+
+impl From<String> for RValue {
+    fn from(x: String) -> Self {
+        RValue::String(x.into())
+    }
+}
+
+impl From<RString> for RValue {
+    fn from(x: RString) -> Self {
+        RValue::String(x)
+    }
+}
+
+impl From<RVec<RValue>> for RValue {
+    fn from(x: RVec<RValue>) -> Self {
+        RValue::Array(x)
+    }
+}
+
+impl From<Vec<RValue>> for RValue {
+    fn from(x: Vec<RValue>) -> Self {
+        RValue::Array(x.into())
+    }
+}
+
+impl From<RMap> for RValue {
+    fn from(x: RMap) -> Self {
+        RValue::Object(x)
+    }
+}
+
+impl std::iter::FromIterator<RValue> for RValue {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = RValue>,
+    {
+        RValue::Array(iter.into_iter().collect())
+    }
+}
+
+macro_rules! impl_partial_eq_number {
+    ($($ty:ty),*) => {$(
+        impl PartialEq<$ty> for RValue {
+            fn eq(&self, other: &$ty) -> bool {
+                let other = RValue::from(*other);
+                *self == other
+            }
+        }
+    )*};
+}
+
+impl_partial_eq_number! {u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, f64}
+
+impl PartialEq<bool> for RValue {
+    fn eq(&self, other: &bool) -> bool {
+        self.as_bool() == Some(*other)
+    }
+}
+
+impl PartialEq<str> for RValue {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == Some(other)
+    }
+}
+
+impl PartialEq<&str> for RValue {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == Some(*other)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+impl Serialize for RValue {
+    fn serialize<Z>(&self, serializer: Z) -> Result<Z::Ok, Z::Error>
+    where
+        Z: Serializer,
+    {
+        match self {
+            RValue::Null => serializer.serialize_unit(),
+            RValue::Bool(x) => serializer.serialize_bool(*x),
+            RValue::Number(x) => x.serialize(serializer),
+            RValue::String(x) => serializer.serialize_str(x),
+            RValue::Array(x) => x.serialize(serializer),
+            RValue::Object(x) => x.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for RValue {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(RValueVisitor)
+    }
+}
+
+struct RValueVisitor;
+
+impl<'de> Visitor<'de> for RValueVisitor {
+    type Value = RValue;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("any valid JSON value")
+    }
+
+    fn visit_bool<E>(self, x: bool) -> Result<RValue, E> {
+        Ok(RValue::Bool(x))
+    }
+
+    fn visit_i64<E>(self, x: i64) -> Result<RValue, E> {
+        Ok(RValue::from(x))
+    }
+
+    fn visit_u64<E>(self, x: u64) -> Result<RValue, E> {
+        Ok(RValue::from(x))
+    }
+
+    fn visit_f64<E>(self, x: f64) -> Result<RValue, E> {
+        Ok(RValue::from(x))
+    }
+
+    fn visit_str<E>(self, x: &str) -> Result<RValue, E> {
+        Ok(RValue::from(x))
+    }
+
+    fn visit_string<E>(self, x: String) -> Result<RValue, E> {
+        Ok(RValue::from(x))
+    }
+
+    fn visit_none<E>(self) -> Result<RValue, E> {
+        Ok(RValue::Null)
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<RValue, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        RValue::deserialize(deserializer)
+    }
+
+    fn visit_unit<E>(self) -> Result<RValue, E> {
+        Ok(RValue::Null)
+    }
+
+    fn visit_seq<A>(self, mut access: A) -> Result<RValue, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut array = RVec::with_capacity(access.size_hint().unwrap_or(0));
+        while let Some(elem) = access.next_element()? {
+            array.push(elem);
+        }
+        Ok(RValue::Array(array))
+    }
+
+    fn visit_map<A>(self, mut access: A) -> Result<RValue, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut map = RMap::with_capacity(access.size_hint().unwrap_or(0));
+
+        match access.next_key::<RString>()? {
+            Some(key) if key.as_str() == NUMBER_TOKEN => {
+                let number = access.next_value::<String>()?;
+                let number = number.parse::<Number>().map_err(de::Error::custom)?;
+                return Ok(RValue::Number(number.into()));
+            }
+            Some(key) => {
+                map.insert(key, access.next_value()?);
+            }
+            None => {}
+        }
+
+        while let Some((key, value)) = access.next_entry()? {
+            map.insert(key, value);
+        }
+        Ok(RValue::Object(map))
+    }
+}
+
+/// The key of the single-entry map that the `arbitrary_precision` feature of `serde_json`
+/// deserializes numbers as, with the number's decimal string as the value.
+const NUMBER_TOKEN: &str = "$serde_json::private::Number";
+
+///////////////////////////////////////////////////////////////////////////////
+
+#[cfg(all(test, not(feature = "only_new_tests")))]
+mod tests {
+    use super::*;
+
+    use crate::std_types::RSome;
+
+    use serde_json::json;
+
+    fn sample() -> Value {
+        json!({
+            "null": null,
+            "bools": [true, false],
+            "numbers": [0, 1, u64::MAX, -1, i64::MIN, 0.5, -1.5e300, 1e-10],
+            "string": "hello\n\"world\"",
+            "nested": {"empty_array": [], "empty_object": {}, "z": {"a": [{"b": 1}]}},
+        })
+    }
+
+    #[test]
+    fn json_value_conversions() {
+        let value = sample();
+        let rvalue = RValue::from(value.clone());
+
+        assert_eq!(Value::from(rvalue.clone()), value);
+
+        let numbers = rvalue["numbers"].as_array().unwrap();
+        assert_eq!(numbers[2].as_u64(), Some(u64::MAX));
+        assert_eq!(numbers[4].as_i64(), Some(i64::MIN));
+        assert_eq!(numbers[6].as_f64(), Some(-1.5e300));
+        assert!(numbers[1].as_number().unwrap().is_u64());
+        assert!(numbers[3].as_number().unwrap().is_i64());
+        assert!(numbers[5].as_number().unwrap().is_f64());
+    }
+
+    #[test]
+    fn serde_roundtrip() {
+        let value = sample();
+        let rvalue = RValue::from(value.clone());
+
+        let json = serde_json::to_string(&rvalue).unwrap();
+        assert_eq!(serde_json::from_str::<Value>(&json).unwrap(), value);
+        assert_eq!(serde_json::from_str::<RValue>(&json).unwrap(), rvalue);
+        assert_eq!(rvalue.to_string(), json);
+
+        let pretty = format!("{:#}", rvalue);
+        assert_eq!(serde_json::from_str::<RValue>(&pretty).unwrap(), rvalue);
+
+        let number = serde_json::from_str::<RNumber>("-7").unwrap();
+        assert_eq!(number, RNumber::from(-7));
+        assert_eq!(number.to_string(), "-7");
+        assert!(serde_json::from_str::<RNumber>("\"7\"").is_err());
+    }
+
+    #[test]
+    fn indexing() {
+        let mut value = RValue::from(sample());
+
+        assert_eq!(value["nested"]["z"]["a"][0]["b"], 1);
+        assert_eq!(value["bools"][1], false);
+        assert_eq!(value["string"], "hello\n\"world\"");
+        assert_eq!(value["missing"]["deeper"][3], RValue::Null);
+        assert_eq!(value[RString::from("null")], RValue::Null);
+        assert_eq!(value[&"null".to_string()], RValue::Null);
+
+        value["null"]["created"] = RValue::from("yes");
+        assert_eq!(value["null"], RValue::from(json!({"created": "yes"})));
+
+        value["new"] = RValue::from(vec![RValue::from(1)]);
+        value["new"][0] = RValue::from(2.5);
+        assert_eq!(value["new"], RValue::from(json!([2.5])));
+
+        let object = value.as_object_mut().unwrap();
+        assert_eq!(
+            object.keys().map(|k| k.as_str()).collect::<Vec<_>>(),
+            ["bools", "nested", "null", "numbers", "string", "new"],
+        );
+        assert_eq!(
+            object.shift_remove("new"),
+            RSome(RValue::from(json!([2.5])))
+        );
+        assert_eq!(object.len(), 5);
+    }
+
+    #[test]
+    #[should_panic]
+    fn index_out_of_bounds() {
+        let mut value = RValue::from(json!([1]));
+        value[1] = RValue::Null;
+    }
+
+    #[test]
+    fn arbitrary_precision_numbers() {
+        let value = serde_json::from_str::<RValue>("1e400").unwrap();
+        let huge = value.as_number().unwrap().clone();
+        // `serde_json` normalizes the exponent of arbitrary precision numbers
+        assert_eq!(huge.to_string(), "1e+400");
+        assert_eq!(huge.as_f64(), f64::INFINITY);
+        assert_eq!(huge.as_i64(), None);
+        assert!(!huge.is_f64());
+        assert_eq!(Number::from(huge).to_string(), "1e+400");
+        assert_eq!(serde_json::to_string(&value).unwrap(), "1e+400");
+        assert_eq!(
+            serde_json::from_str::<RValue>(r#"{"a": [-1e400]}"#).unwrap()["a"][0].to_string(),
+            "-1e+400"
+        );
+
+        let big = serde_json::from_str::<RNumber>("123456789012345678901234567890").unwrap();
+        assert_eq!(big.as_f64(), 1.2345678901234568e29);
+        assert_eq!(
+            serde_json::to_string(&big).unwrap(),
+            Number::from(big).to_string()
+        );
+    }
+
+    #[test]
+    fn non_finite_floats() {
+        assert_eq!(RValue::from(f64::NAN), RValue::Null);
+        assert_eq!(RValue::from(f32::INFINITY), RValue::Null);
+        assert_eq!(RNumber::from_f64(f64::NEG_INFINITY), None);
+    }
+
+    #[test]
+    fn recursive_layout() {
+        use crate::{abi_stability::abi_checking::check_layout_compatibility, StableAbi};
+
+        let layout = <RValue as StableAbi>::LAYOUT;
+        assert!(check_layout_compatibility(layout, layout).is_ok());
+    }
+}
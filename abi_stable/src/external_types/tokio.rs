@@ -0,0 +1,151 @@
+//! Adapter for using a tokio runtime as an [`RExecutor`](crate::sabi_types::executor::RExecutor).
+
+use tokio::runtime::Handle;
+
+use crate::{
+    sabi_types::{
+        executor::{BlockingTask_TO, Executor},
+        RFuture,
+    },
+    std_types::{RBox, RDuration},
+};
+
+/// An [`Executor`] that spawns tasks on a tokio runtime.
+///
+/// The runtime must have the time driver enabled for [`Executor::sleep`] to work.
+///
+/// # Example
+///
+/// ```rust
+/// use abi_stable::{external_types::tokio::TokioExecutor, sabi_types::executor::RExecutor};
+///
+/// use std::time::Duration;
+///
+/// let runtime = tokio::runtime::Builder::new_current_thread()
+///     .enable_time()
+///     .build()
+///     .unwrap();
+///
+/// let executor = RExecutor::new(TokioExecutor::new(runtime.handle().clone()));
+///
+/// let value = runtime.block_on(async {
+///     executor.sleep(Duration::from_millis(1)).await;
+///     executor.spawn_blocking(|| 3 + 5).await
+/// });
+///
+/// assert_eq!(value, 8);
+///
+/// ```
+#[derive(Debug, Clone)]
+pub struct TokioExecutor {
+    handle: Handle,
+}
+
+impl TokioExecutor {
+    /// Constructs a `TokioExecutor` that spawns tasks with `handle`.
+    pub fn new(handle: Handle) -> Self {
+        Self { handle }
+    }
+
+    /// Constructs a `TokioExecutor` for the runtime that this is called inside of.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is not called from inside a tokio runtime.
+    pub fn current() -> Self {
+        Self::new(Handle::current())
+    }
+
+    /// Gets the handle to the runtime that tasks are spawned on.
+    pub fn handle(&self) -> &Handle {
+        &self.handle
+    }
+}
+
+impl Executor for TokioExecutor {
+    fn spawn(&self, future: RFuture<'static, ()>) {
+        drop(self.handle.spawn(future));
+    }
+
+    fn spawn_blocking(&self, task: BlockingTask_TO<'static, RBox<()>>) {
+        drop(self.handle.spawn_blocking(move || task.run()));
+    }
+
+    fn sleep(&self, duration: RDuration) -> RFuture<'static, ()> {
+        // `tokio::time::sleep` requires being inside of the runtime's context.
+        let _guard = self.handle.enter();
+        RFuture::new(tokio::time::sleep(duration.into()))
+    }
+}
+
+#[cfg(all(test, not(feature = "only_new_tests")))]
+mod tests {
+    use super::*;
+
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::{Duration, Instant},
+    };
+
+    use crate::sabi_types::executor::RExecutor;
+
+    fn runtime() -> tokio::runtime::Runtime {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn spawn_and_sleep() {
+        let runtime = runtime();
+        let executor = RExecutor::new(TokioExecutor::new(runtime.handle().clone()));
+
+        let counter = Arc::new(AtomicUsize::new(0));
+        let start = Instant::now();
+
+        runtime.block_on(async {
+            for _ in 0..4 {
+                let counter = counter.clone();
+                let executor2 = executor.clone();
+                executor.spawn(async move {
+                    executor2.sleep(Duration::from_millis(5)).await;
+                    counter.fetch_add(1, Ordering::SeqCst);
+                });
+            }
+            executor.sleep(Duration::from_millis(50)).await;
+        });
+
+        assert_eq!(counter.load(Ordering::SeqCst), 4);
+        assert!(start.elapsed() >= Duration::from_millis(50));
+    }
+
+    #[test]
+    fn spawn_blocking() {
+        let runtime = runtime();
+        let executor = RExecutor::new(TokioExecutor::new(runtime.handle().clone()));
+
+        let value = runtime.block_on(async {
+            let fut = executor.spawn_blocking(|| {
+                std::thread::sleep(Duration::from_millis(1));
+                "hello"
+            });
+            fut.await
+        });
+        assert_eq!(value, "hello");
+    }
+
+    #[test]
+    fn current() {
+        let runtime = runtime();
+        let executor = runtime.block_on(async { TokioExecutor::current() });
+        let executor = RExecutor::new(executor);
+
+        // Sleeping outside of the runtime's context
+        let sleep = executor.sleep(Duration::from_millis(1));
+        runtime.block_on(sleep);
+    }
+}
@@ -0,0 +1,1364 @@
+//! Ffi-safe proxy for [`tracing`](https://docs.rs/tracing) subscribers,
+//! which forwards the spans and events of dynamic libraries to the subscriber of the host.
+//!
+//! Every dynamic library has its own copy of the `tracing-core` crate,
+//! with its own global (and thread-local) subscriber,
+//! so spans and events created by a library are discarded unless
+//! a subscriber is set in it,
+//! and spans opened by the host aren't seen as the parents of the spans/events of the library.
+//!
+//! The host constructs an [`RSubscriber`] (usually with [`RSubscriber::from_current`]),
+//! and passes it to the dynamic libraries it loads,
+//! which install it as their global subscriber with [`RSubscriber::install`].
+//!
+//! Spans are identified by the ids that the subscriber of the host assigned to them,
+//! so the spans that a library creates are children of the span that the host entered
+//! (and vice versa), unless the parent is explicitly specified.
+//!
+//! # Example
+//!
+//! ```rust
+//! use abi_stable::external_types::tracing::RSubscriber;
+//!
+//! // This would be an `extern "C"` function exported by a dynamic library.
+//! extern "C" fn plugin_init(subscriber: RSubscriber) {
+//!     // Dynamic libraries would usually call `subscriber.install()` instead,
+//!     // to use the subscriber of the host everywhere.
+//!     tracing::subscriber::with_default(subscriber, || {
+//!         let span = tracing::info_span!("loading", plugin = "foo");
+//!         let _guard = span.enter();
+//!
+//!         tracing::info!(answer = 42, "plugin loaded");
+//!     });
+//! }
+//!
+//! // The host forwards the spans and events of the dynamic library to its own subscriber.
+//! plugin_init(RSubscriber::from_current());
+//!
+//! ```
+//!
+
+use std::{
+    collections::HashMap,
+    fmt::{self, Debug},
+    num::NonZeroU64,
+};
+
+use parking_lot::RwLock;
+
+use tracing_core::{
+    callsite::{Callsite, Identifier},
+    dispatcher::{self, Dispatch, SetGlobalDefaultError},
+    field::{self, Field, FieldSet, Value, ValueSet, Visit},
+    identify_callsite,
+    metadata::{Kind, Level, LevelFilter, Metadata},
+    span::{self, Attributes, Id, Record},
+    subscriber::Interest,
+    Event,
+};
+
+use crate::{
+    sabi_trait,
+    sabi_trait::prelude::TD_Opaque,
+    sabi_types::{RLazy, ROnceCell},
+    std_types::{RArc, RNone, ROption, RSlice, RSome, RStr},
+};
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// Ffi-safe equivalent of [`tracing_core::Level`].
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, StableAbi)]
+pub enum RLevel {
+    /// Very serious errors.
+    Error = 1,
+    /// Hazardous situations.
+    Warn,
+    /// Useful information.
+    Info,
+    /// Lower priority information.
+    Debug,
+    /// Very low priority, often extremely verbose, information.
+    Trace,
+}
+
+impl_from_rust_repr! {
+    impl From<Level> for RLevel {
+        fn(this){
+            if this == Level::ERROR {
+                RLevel::Error
+            } else if this == Level::WARN {
+                RLevel::Warn
+            } else if this == Level::INFO {
+                RLevel::Info
+            } else if this == Level::DEBUG {
+                RLevel::Debug
+            } else {
+                RLevel::Trace
+            }
+        }
+    }
+}
+
+impl_into_rust_repr! {
+    impl Into<Level> for RLevel {
+        fn(this){
+            match this {
+                RLevel::Error => Level::ERROR,
+                RLevel::Warn => Level::WARN,
+                RLevel::Info => Level::INFO,
+                RLevel::Debug => Level::DEBUG,
+                RLevel::Trace => Level::TRACE,
+            }
+        }
+    }
+}
+
+/// Ffi-safe equivalent of [`tracing_core::LevelFilter`].
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, StableAbi)]
+pub enum RLevelFilter {
+    /// Disables all spans and events.
+    Off,
+    /// Corresponds to `RLevel::Error`.
+    Error,
+    /// Corresponds to `RLevel::Warn`.
+    Warn,
+    /// Corresponds to `RLevel::Info`.
+    Info,
+    /// Corresponds to `RLevel::Debug`.
+    Debug,
+    /// Corresponds to `RLevel::Trace`.
+    Trace,
+}
+
+impl_from_rust_repr! {
+    impl From<LevelFilter> for RLevelFilter {
+        fn(this){
+            match this.into_level() {
+                Some(level) => match RLevel::from(level) {
+                    RLevel::Error => RLevelFilter::Error,
+                    RLevel::Warn => RLevelFilter::Warn,
+                    RLevel::Info => RLevelFilter::Info,
+                    RLevel::Debug => RLevelFilter::Debug,
+                    RLevel::Trace => RLevelFilter::Trace,
+                },
+                None => RLevelFilter::Off,
+            }
+        }
+    }
+}
+
+impl_into_rust_repr! {
+    impl Into<LevelFilter> for RLevelFilter {
+        fn(this){
+            match this {
+                RLevelFilter::Off => LevelFilter::OFF,
+                RLevelFilter::Error => LevelFilter::ERROR,
+                RLevelFilter::Warn => LevelFilter::WARN,
+                RLevelFilter::Info => LevelFilter::INFO,
+                RLevelFilter::Debug => LevelFilter::DEBUG,
+                RLevelFilter::Trace => LevelFilter::TRACE,
+            }
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// Ffi-safe equivalent of [`tracing_core::metadata::Kind`],
+/// whether a callsite creates spans or emits events.
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, StableAbi)]
+pub enum RKind {
+    /// The callsite emits events.
+    Event,
+    /// The callsite creates spans.
+    Span,
+}
+
+impl RKind {
+    fn of(metadata: &Metadata<'_>) -> Self {
+        if metadata.is_span() {
+            RKind::Span
+        } else {
+            RKind::Event
+        }
+    }
+}
+
+impl_into_rust_repr! {
+    impl Into<Kind> for RKind {
+        fn(this){
+            match this {
+                RKind::Event => Kind::EVENT,
+                RKind::Span => Kind::SPAN,
+            }
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// Ffi-safe equivalent of [`tracing_core::subscriber::Interest`],
+/// whether a subscriber is interested in the spans/events of a callsite.
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, StableAbi)]
+pub enum RInterest {
+    /// The subscriber is never interested in the callsite.
+    Never,
+    /// The subscriber must be asked whether it's interested every time.
+    Sometimes,
+    /// The subscriber is always interested in the callsite.
+    Always,
+}
+
+impl_from_rust_repr! {
+    impl From<Interest> for RInterest {
+        fn(this){
+            if this.is_never() {
+                RInterest::Never
+            } else if this.is_always() {
+                RInterest::Always
+            } else {
+                RInterest::Sometimes
+            }
+        }
+    }
+}
+
+impl_into_rust_repr! {
+    impl Into<Interest> for RInterest {
+        fn(this){
+            match this {
+                RInterest::Never => Interest::never(),
+                RInterest::Sometimes => Interest::sometimes(),
+                RInterest::Always => Interest::always(),
+            }
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// Identifies a callsite (a place in the code that creates spans or emits events),
+/// unique across all the dynamic libraries loaded in the process.
+#[repr(transparent)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, StableAbi)]
+pub struct RCallsiteId(usize);
+
+impl RCallsiteId {
+    fn of(metadata: &'static Metadata<'static>) -> Self {
+        Self(metadata as *const Metadata<'static> as usize)
+    }
+}
+
+/// Ffi-safe equivalent of [`tracing_core::Metadata`],
+/// describing a callsite.
+///
+/// The strings are `'static` because dynamic libraries loaded by `abi_stable`
+/// are never unloaded.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, StableAbi)]
+pub struct RMetadata {
+    /// The callsite that this describes.
+    pub callsite: RCallsiteId,
+    /// The name of the span, or the description of the event.
+    pub name: RStr<'static>,
+    /// The target of the callsite, usually the module path.
+    pub target: RStr<'static>,
+    /// The verbosity level of the callsite.
+    pub level: RLevel,
+    /// The module path of the callsite.
+    pub module_path: ROption<RStr<'static>>,
+    /// The source file of the callsite.
+    pub file: ROption<RStr<'static>>,
+    /// The line of the callsite.
+    pub line: ROption<u32>,
+    /// The names of the fields of the spans/events of the callsite.
+    pub field_names: RSlice<'static, RStr<'static>>,
+    /// Whether this is the callsite of a span or an event.
+    pub kind: RKind,
+}
+
+impl RMetadata {
+    fn new(callsite: RCallsiteId, metadata: &'static Metadata<'static>) -> Self {
+        let field_names = metadata
+            .fields()
+            .iter()
+            .map(|field| RStr::from_str(field.name()))
+            .collect::<Vec<RStr<'static>>>();
+
+        Self {
+            callsite,
+            name: metadata.name().into(),
+            target: metadata.target().into(),
+            level: (*metadata.level()).into(),
+            module_path: metadata.module_path().map(RStr::from).into(),
+            file: metadata.file().map(RStr::from).into(),
+            line: metadata.line().into(),
+            field_names: RSlice::from_slice(Box::leak(field_names.into_boxed_slice())),
+            kind: RKind::of(metadata),
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// Ffi-safe equivalent of [`tracing_core::span::Id`],
+/// the id that the subscriber of the host assigned to a span.
+#[repr(transparent)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, StableAbi)]
+pub struct RSpanId(NonZeroU64);
+
+impl RSpanId {
+    /// Constructs an `RSpanId` from its numeric value.
+    pub const fn new(id: NonZeroU64) -> Self {
+        Self(id)
+    }
+
+    /// Gets the numeric value of this id.
+    pub const fn get(self) -> NonZeroU64 {
+        self.0
+    }
+}
+
+impl From<&Id> for RSpanId {
+    fn from(this: &Id) -> Self {
+        RSpanId(this.into_non_zero_u64())
+    }
+}
+
+impl_into_rust_repr! {
+    impl Into<Id> for RSpanId {
+        fn(this){
+            Id::from_non_zero_u64(this.0)
+        }
+    }
+}
+
+/// The parent of a new span or event.
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, StableAbi)]
+pub enum RParent {
+    /// The parent is the span that the subscriber considers the current one.
+    Current,
+    /// The span/event has no parent.
+    Root,
+    /// The parent is this span.
+    Explicit(RSpanId),
+}
+
+/// The span that a subscriber considers the current one in this thread.
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, StableAbi)]
+pub enum RCurrentSpan {
+    /// The current span.
+    Entered {
+        /// The id of the span.
+        id: RSpanId,
+        /// The metadata of the callsite that created the span.
+        metadata: RMetadata,
+    },
+    /// The thread isn't inside of a span.
+    NotEntered,
+    /// The subscriber doesn't track the current span.
+    Unknown,
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// The value of a field of a span or event,
+/// passed from the [`Visit`] implementation of the library to the subscriber of the host.
+///
+/// Values of types other than the primitive ones are `Debug` formatted.
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, PartialEq, StableAbi)]
+pub enum RFieldValue<'a> {
+    /// A signed integer.
+    I64(i64),
+    /// An unsigned integer.
+    U64(u64),
+    /// A floating point number.
+    F64(f64),
+    /// A boolean.
+    Bool(bool),
+    /// A string.
+    Str(RStr<'a>),
+    /// The `Debug` formatted representation of a value.
+    Debug(RStr<'a>),
+}
+
+/// A field of a span or event.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, StableAbi)]
+pub struct RField<'a> {
+    /// The name of the field.
+    pub name: RStr<'static>,
+    /// The value of the field.
+    pub value: RFieldValue<'a>,
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// The operations that a subscriber must provide to be wrapped in an [`RSubscriber`].
+///
+/// Callsites are registered with `register_callsite` before
+/// any span/event of theirs is passed to the other methods.
+///
+/// Panics inside of these methods abort the process,
+/// since they can't unwind across the ffi boundary.
+#[sabi_trait]
+pub trait Subscriber: Send + Sync {
+    /// Registers a callsite, returning whether the subscriber is interested in it.
+    fn register_callsite(&self, metadata: &RMetadata) -> RInterest;
+
+    /// Whether a span/event from the callsite would be recorded.
+    fn enabled(&self, callsite: RCallsiteId) -> bool;
+
+    /// The most verbose level that spans/events are recorded at,
+    /// `RNone` if the subscriber doesn't know.
+    fn max_level_hint(&self) -> ROption<RLevelFilter>;
+
+    /// Creates a span,
+    /// returning `RNone` if the subscriber doesn't know the callsite.
+    fn new_span(
+        &self,
+        callsite: RCallsiteId,
+        parent: RParent,
+        fields: RSlice<'_, RField<'_>>,
+    ) -> ROption<RSpanId>;
+
+    /// Records the values of fields of the span,
+    /// which was created by the `callsite` callsite.
+    fn record(&self, span: RSpanId, callsite: RCallsiteId, fields: RSlice<'_, RField<'_>>);
+
+    /// Records that the `span` span follows from the `follows` span.
+    fn record_follows_from(&self, span: RSpanId, follows: RSpanId);
+
+    /// Records an event.
+    fn event(&self, callsite: RCallsiteId, parent: RParent, fields: RSlice<'_, RField<'_>>);
+
+    /// Records that the current thread entered the span.
+    fn enter(&self, span: RSpanId);
+
+    /// Records that the current thread exited the span.
+    fn exit(&self, span: RSpanId);
+
+    /// Records that a new handle to the span was created,
+    /// returning the id of the new handle.
+    fn clone_span(&self, span: RSpanId) -> RSpanId;
+
+    /// Records that a handle to the span was dropped,
+    /// returning whether the span was closed.
+    fn try_close(&self, span: RSpanId) -> bool;
+
+    /// Gets the span that the current thread is inside of.
+    #[sabi(last_prefix_field)]
+    fn current_span(&self) -> RCurrentSpan;
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// The callsites that have been passed through the ffi boundary,
+/// shared by all the `RSubscriber`s in this dynamic library/executable.
+#[derive(Default)]
+struct Callsites {
+    by_id: HashMap<RCallsiteId, Registered>,
+    // Maps the identifiers of the callsites of this copy of `tracing-core`
+    // (including proxy callsites) to the ids of the callsites they stand for.
+    ids: HashMap<Identifier, RCallsiteId>,
+}
+
+struct Registered {
+    metadata: &'static Metadata<'static>,
+    ffi: RMetadata,
+}
+
+static CALLSITES: RLazy<RwLock<Callsites>> = RLazy::new(Default::default);
+
+/// Stands in for a callsite from another copy of `tracing-core`,
+/// since subscribers require metadata that is `'static` and
+/// identifies a `Callsite` of the same copy of `tracing-core`.
+struct ProxyCallsite {
+    metadata: ROnceCell<Metadata<'static>>,
+}
+
+impl Callsite for ProxyCallsite {
+    fn set_interest(&self, _: Interest) {}
+
+    fn metadata(&self) -> &Metadata<'_> {
+        self.metadata
+            .get()
+            .expect("the metadata is set right after the ProxyCallsite is constructed")
+    }
+}
+
+/// Gets the ffi-safe metadata of a callsite of this copy of `tracing-core`.
+fn ffi_metadata(metadata: &'static Metadata<'static>) -> RMetadata {
+    let identifier = metadata.callsite();
+    {
+        let callsites = CALLSITES.read();
+        if let Some(id) = callsites.ids.get(&identifier) {
+            return callsites.by_id[id].ffi;
+        }
+    }
+
+    let mut callsites = CALLSITES.write();
+    if let Some(id) = callsites.ids.get(&identifier) {
+        return callsites.by_id[id].ffi;
+    }
+    let id = RCallsiteId::of(metadata);
+    let ffi = RMetadata::new(id, metadata);
+    callsites.ids.insert(identifier, id);
+    callsites.by_id.insert(id, Registered { metadata, ffi });
+    ffi
+}
+
+/// Gets the metadata in this copy of `tracing-core` for a callsite,
+/// creating a proxy callsite if it comes from another copy of `tracing-core`.
+fn local_metadata(ffi: &RMetadata) -> &'static Metadata<'static> {
+    if let Some(registered) = CALLSITES.read().by_id.get(&ffi.callsite) {
+        return registered.metadata;
+    }
+
+    let mut callsites = CALLSITES.write();
+    if let Some(registered) = callsites.by_id.get(&ffi.callsite) {
+        return registered.metadata;
+    }
+
+    let callsite: &'static ProxyCallsite = Box::leak(Box::new(ProxyCallsite {
+        metadata: ROnceCell::new(),
+    }));
+    let field_names = ffi
+        .field_names
+        .iter()
+        .map(|name| name.as_str())
+        .collect::<Vec<&'static str>>();
+    let _ = callsite.metadata.set(Metadata::new(
+        ffi.name.as_str(),
+        ffi.target.as_str(),
+        ffi.level.into(),
+        ffi.file.map(|s| s.as_str()).into_option(),
+        ffi.line.into_option(),
+        ffi.module_path.map(|s| s.as_str()).into_option(),
+        FieldSet::new(
+            Box::leak(field_names.into_boxed_slice()),
+            identify_callsite!(callsite),
+        ),
+        ffi.kind.into(),
+    ));
+    let metadata = callsite.metadata.get().unwrap();
+
+    callsites.ids.insert(metadata.callsite(), ffi.callsite);
+    callsites.by_id.insert(
+        ffi.callsite,
+        Registered {
+            metadata,
+            ffi: *ffi,
+        },
+    );
+    metadata
+}
+
+fn lookup_metadata(callsite: RCallsiteId) -> Option<&'static Metadata<'static>> {
+    CALLSITES.read().by_id.get(&callsite).map(|r| r.metadata)
+}
+
+fn lookup_id(identifier: &Identifier) -> Option<RCallsiteId> {
+    CALLSITES.read().ids.get(identifier).copied()
+}
+
+fn callsite_id(metadata: &'static Metadata<'static>) -> RCallsiteId {
+    lookup_id(&metadata.callsite()).unwrap_or_else(|| RCallsiteId::of(metadata))
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// The ffi-safe visitor, collects the fields of a span/event
+/// so that they can be passed to the subscriber of the host.
+#[derive(Default)]
+struct CollectFields {
+    callsite: Option<Identifier>,
+    fields: Vec<(&'static str, CollectedValue)>,
+}
+
+enum CollectedValue {
+    I64(i64),
+    U64(u64),
+    F64(f64),
+    Bool(bool),
+    Str(String),
+    Debug(String),
+}
+
+impl CollectFields {
+    fn push(&mut self, field: &Field, value: CollectedValue) {
+        if self.callsite.is_none() {
+            self.callsite = Some(field.callsite());
+        }
+        self.fields.push((field.name(), value));
+    }
+
+    fn ffi_fields(&self) -> Vec<RField<'_>> {
+        self.fields
+            .iter()
+            .map(|(name, value)| RField {
+                name: RStr::from_str(name),
+                value: match value {
+                    CollectedValue::I64(x) => RFieldValue::I64(*x),
+                    CollectedValue::U64(x) => RFieldValue::U64(*x),
+                    CollectedValue::F64(x) => RFieldValue::F64(*x),
+                    CollectedValue::Bool(x) => RFieldValue::Bool(*x),
+                    CollectedValue::Str(x) => RFieldValue::Str(x.as_str().into()),
+                    CollectedValue::Debug(x) => RFieldValue::Debug(x.as_str().into()),
+                },
+            })
+            .collect()
+    }
+}
+
+impl Visit for CollectFields {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.push(field, CollectedValue::F64(value));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.push(field, CollectedValue::I64(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.push(field, CollectedValue::U64(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.push(field, CollectedValue::Bool(value));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.push(field, CollectedValue::Str(value.to_string()));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.push(field, CollectedValue::Debug(format!("{:?}", value)));
+    }
+}
+
+/// Writes a string that was already `Debug` formatted as is.
+struct Formatted<'a>(&'a str);
+
+impl Debug for Formatted<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+enum HostValue<'a> {
+    I64(i64),
+    U64(u64),
+    F64(f64),
+    Bool(bool),
+    Str(&'a str),
+    Debug(field::DebugValue<Formatted<'a>>),
+}
+
+impl<'a> HostValue<'a> {
+    fn new(value: RFieldValue<'a>) -> Self {
+        match value {
+            RFieldValue::I64(x) => HostValue::I64(x),
+            RFieldValue::U64(x) => HostValue::U64(x),
+            RFieldValue::F64(x) => HostValue::F64(x),
+            RFieldValue::Bool(x) => HostValue::Bool(x),
+            RFieldValue::Str(x) => HostValue::Str(x.as_str()),
+            RFieldValue::Debug(x) => HostValue::Debug(field::debug(Formatted(x.as_str()))),
+        }
+    }
+
+    fn as_value(&self) -> &dyn Value {
+        match self {
+            HostValue::I64(x) => x,
+            HostValue::U64(x) => x,
+            HostValue::F64(x) => x,
+            HostValue::Bool(x) => x,
+            HostValue::Str(x) => x,
+            HostValue::Debug(x) => x,
+        }
+    }
+}
+
+/// Passes the `ValueSet` for `fields` to `f`,
+/// ignoring the fields that `metadata` doesn't declare.
+fn with_value_set<R>(
+    metadata: &'static Metadata<'static>,
+    fields: RSlice<'_, RField<'_>>,
+    f: impl FnOnce(&ValueSet<'_>) -> R,
+) -> R {
+    let field_set = metadata.fields();
+
+    let host_values = fields
+        .iter()
+        .filter_map(|field| {
+            let declared = field_set.field(field.name.as_str())?;
+            Some((declared, HostValue::new(field.value)))
+        })
+        // `FieldSet::value_set` only takes arrays of up to 32 values
+        .take(32)
+        .collect::<Vec<_>>();
+
+    let values = host_values
+        .iter()
+        .map(|(field, value)| (field, Some(value.as_value())))
+        .collect::<Vec<_>>();
+
+    // `FieldSet::value_set_all` takes a slice,
+    // but it was added in a `tracing-core` version that requires Rust 1.65.
+    macro_rules! with_array {
+        ($($len:literal)*) => {
+            match values.len() {
+                $(
+                    $len => {
+                        let values: [_; $len] = match values.try_into() {
+                            Ok(x) => x,
+                            Err(_) => unreachable!(),
+                        };
+                        f(&field_set.value_set(&values))
+                    }
+                )*
+                _ => unreachable!(),
+            }
+        };
+    }
+
+    with_array! {
+        0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16
+        17 18 19 20 21 22 23 24 25 26 27 28 29 30 31 32
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// Forwards spans and events to a `Dispatch` of the `tracing-core` crate
+/// that this is compiled with.
+struct DispatchSubscriber {
+    dispatch: Dispatch,
+}
+
+impl Subscriber for DispatchSubscriber {
+    fn register_callsite(&self, metadata: &RMetadata) -> RInterest {
+        self.dispatch
+            .register_callsite(local_metadata(metadata))
+            .into()
+    }
+
+    fn enabled(&self, callsite: RCallsiteId) -> bool {
+        lookup_metadata(callsite).map_or(false, |metadata| self.dispatch.enabled(metadata))
+    }
+
+    fn max_level_hint(&self) -> ROption<RLevelFilter> {
+        // `Dispatch` doesn't expose the hint of its subscriber,
+        // this is the most verbose level of all the subscribers in this executable.
+        RSome(LevelFilter::current().into())
+    }
+
+    fn new_span(
+        &self,
+        callsite: RCallsiteId,
+        parent: RParent,
+        fields: RSlice<'_, RField<'_>>,
+    ) -> ROption<RSpanId> {
+        let metadata = match lookup_metadata(callsite) {
+            Some(metadata) => metadata,
+            None => return RNone,
+        };
+
+        with_value_set(metadata, fields, |values| {
+            let attributes = match parent {
+                RParent::Current => Attributes::new(metadata, values),
+                RParent::Root => Attributes::new_root(metadata, values),
+                RParent::Explicit(parent) => Attributes::child_of(parent.into(), metadata, values),
+            };
+            RSome(RSpanId::from(&self.dispatch.new_span(&attributes)))
+        })
+    }
+
+    fn record(&self, span: RSpanId, callsite: RCallsiteId, fields: RSlice<'_, RField<'_>>) {
+        if let Some(metadata) = lookup_metadata(callsite) {
+            with_value_set(metadata, fields, |values| {
+                self.dispatch.record(&span.into(), &Record::new(values));
+            });
+        }
+    }
+
+    fn record_follows_from(&self, span: RSpanId, follows: RSpanId) {
+        self.dispatch
+            .record_follows_from(&span.into(), &follows.into());
+    }
+
+    fn event(&self, callsite: RCallsiteId, parent: RParent, fields: RSlice<'_, RField<'_>>) {
+        let metadata = match lookup_metadata(callsite) {
+            Some(metadata) => metadata,
+            None => return,
+        };
+
+        with_value_set(metadata, fields, |values| {
+            let event = match parent {
+                RParent::Current => Event::new(metadata, values),
+                RParent::Root => Event::new_child_of(None, metadata, values),
+                RParent::Explicit(parent) => {
+                    Event::new_child_of(Id::from(parent), metadata, values)
+                }
+            };
+            self.dispatch.event(&event);
+        });
+    }
+
+    fn enter(&self, span: RSpanId) {
+        self.dispatch.enter(&span.into());
+    }
+
+    fn exit(&self, span: RSpanId) {
+        self.dispatch.exit(&span.into());
+    }
+
+    fn clone_span(&self, span: RSpanId) -> RSpanId {
+        RSpanId::from(&self.dispatch.clone_span(&span.into()))
+    }
+
+    fn try_close(&self, span: RSpanId) -> bool {
+        self.dispatch.try_close(span.into())
+    }
+
+    fn current_span(&self) -> RCurrentSpan {
+        let current = self.dispatch.current_span();
+        if !current.is_known() {
+            return RCurrentSpan::Unknown;
+        }
+        match current.into_inner() {
+            Some((id, metadata)) => RCurrentSpan::Entered {
+                id: RSpanId::from(&id),
+                metadata: ffi_metadata(metadata),
+            },
+            None => RCurrentSpan::NotEntered,
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// The id that `RSubscriber` gives to spans that the wrapped subscriber didn't create,
+/// it's ignored by all the other methods.
+const DISABLED_SPAN: u64 = u64::MAX;
+
+fn ffi_span_id(id: &Id) -> Option<RSpanId> {
+    if id.into_u64() == DISABLED_SPAN {
+        None
+    } else {
+        Some(RSpanId::from(id))
+    }
+}
+
+fn ffi_parent(is_root: bool, parent: Option<&Id>) -> RParent {
+    match parent.and_then(ffi_span_id) {
+        Some(parent) => RParent::Explicit(parent),
+        None if is_root => RParent::Root,
+        None => RParent::Current,
+    }
+}
+
+/// An ffi-safe handle to a `tracing` subscriber,
+/// that can be cheaply cloned and passed to dynamic libraries.
+///
+/// This implements [`tracing_core::Subscriber`],
+/// forwarding spans and events to the wrapped [`Subscriber`].
+///
+/// # Example
+///
+/// ```rust
+/// use abi_stable::external_types::tracing::RSubscriber;
+///
+/// // The host forwards the spans and events of the dynamic library to its own subscriber.
+/// let subscriber = RSubscriber::from_current();
+///
+/// // The dynamic library installs the subscriber in its own copy of `tracing-core`.
+/// # if false {
+/// subscriber.install().unwrap();
+/// # }
+///
+/// ```
+#[repr(transparent)]
+#[derive(StableAbi)]
+pub struct RSubscriber {
+    inner: Subscriber_TO<'static, RArc<()>>,
+}
+
+impl RSubscriber {
+    /// Constructs an `RSubscriber` from a [`Subscriber`].
+    pub fn new<S>(subscriber: S) -> Self
+    where
+        S: Subscriber + 'static,
+    {
+        Self {
+            inner: Subscriber_TO::from_ptr(RArc::new(subscriber), TD_Opaque),
+        }
+    }
+
+    /// Constructs an `RSubscriber` which forwards spans and events to `dispatch`.
+    ///
+    /// The returned `RSubscriber` must not be used as a subscriber of
+    /// the same copy of `tracing-core` that `dispatch` comes from,
+    /// since that would make it forward spans and events to itself forever.
+    pub fn from_dispatch(dispatch: Dispatch) -> Self {
+        Self::new(DispatchSubscriber { dispatch })
+    }
+
+    /// Constructs an `RSubscriber` which forwards spans and events to the subscriber
+    /// that is the default for the current thread
+    /// in the dynamic library/executable that calls this.
+    ///
+    /// This is meant to be called by the host,
+    /// with the same caveats as [`from_dispatch`](Self::from_dispatch).
+    pub fn from_current() -> Self {
+        dispatcher::get_default(|dispatch| Self::from_dispatch(dispatch.clone()))
+    }
+
+    /// Installs this as the global default subscriber of the `tracing-core` crate
+    /// in the dynamic library/executable that calls this.
+    ///
+    /// This is meant to be called by dynamic libraries,
+    /// with the `RSubscriber` that the host passed to them.
+    ///
+    /// # Errors
+    ///
+    /// This returns an error if a global default subscriber was already installed.
+    pub fn install(self) -> Result<(), SetGlobalDefaultError> {
+        dispatcher::set_global_default(Dispatch::new(self))
+    }
+}
+
+impl tracing_core::Subscriber for RSubscriber {
+    fn register_callsite(&self, metadata: &'static Metadata<'static>) -> Interest {
+        self.inner.register_callsite(&ffi_metadata(metadata)).into()
+    }
+
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        match lookup_id(&metadata.callsite()) {
+            Some(callsite) => self.inner.enabled(callsite),
+            None => false,
+        }
+    }
+
+    fn max_level_hint(&self) -> Option<LevelFilter> {
+        self.inner
+            .max_level_hint()
+            .map(LevelFilter::from)
+            .into_option()
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let mut fields = CollectFields::default();
+        span.record(&mut fields);
+
+        self.inner
+            .new_span(
+                callsite_id(span.metadata()),
+                ffi_parent(span.is_root(), span.parent()),
+                fields.ffi_fields().as_slice().into(),
+            )
+            .map_or(Id::from_u64(DISABLED_SPAN), Id::from)
+    }
+
+    fn record(&self, span: &Id, values: &Record<'_>) {
+        let span = match ffi_span_id(span) {
+            Some(span) => span,
+            None => return,
+        };
+        let mut fields = CollectFields::default();
+        values.record(&mut fields);
+
+        if let Some(callsite) = fields.callsite.as_ref().and_then(lookup_id) {
+            self.inner
+                .record(span, callsite, fields.ffi_fields().as_slice().into());
+        }
+    }
+
+    fn record_follows_from(&self, span: &Id, follows: &Id) {
+        if let (Some(span), Some(follows)) = (ffi_span_id(span), ffi_span_id(follows)) {
+            self.inner.record_follows_from(span, follows);
+        }
+    }
+
+    fn event(&self, event: &Event<'_>) {
+        let mut fields = CollectFields::default();
+        event.record(&mut fields);
+
+        self.inner.event(
+            callsite_id(event.metadata()),
+            ffi_parent(event.is_root(), event.parent()),
+            fields.ffi_fields().as_slice().into(),
+        );
+    }
+
+    fn enter(&self, span: &Id) {
+        if let Some(span) = ffi_span_id(span) {
+            self.inner.enter(span);
+        }
+    }
+
+    fn exit(&self, span: &Id) {
+        if let Some(span) = ffi_span_id(span) {
+            self.inner.exit(span);
+        }
+    }
+
+    fn clone_span(&self, span: &Id) -> Id {
+        match ffi_span_id(span) {
+            Some(span) => self.inner.clone_span(span).into(),
+            None => span.clone(),
+        }
+    }
+
+    fn try_close(&self, span: Id) -> bool {
+        match ffi_span_id(&span) {
+            Some(span) => self.inner.try_close(span),
+            None => false,
+        }
+    }
+
+    fn current_span(&self) -> span::Current {
+        match self.inner.current_span() {
+            RCurrentSpan::Entered { id, metadata } => {
+                span::Current::new(id.into(), local_metadata(&metadata))
+            }
+            // `tracing-core` only allows constructing unknown `Current`s
+            // through the default implementation of this method.
+            RCurrentSpan::NotEntered | RCurrentSpan::Unknown => span::Current::none(),
+        }
+    }
+}
+
+impl Clone for RSubscriber {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Subscriber_TO::from_sabi(self.inner.obj.shallow_clone()),
+        }
+    }
+}
+
+impl Debug for RSubscriber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("RSubscriber{..}")
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+#[cfg(all(test, not(feature = "only_new_tests")))]
+mod tests {
+    use super::*;
+
+    use std::{
+        fmt::Write as _,
+        sync::{
+            atomic::{AtomicU64, Ordering},
+            Arc, Mutex,
+        },
+    };
+
+    use crate::std_types::RStr;
+
+    #[derive(Default)]
+    struct RecorderState {
+        next_id: AtomicU64,
+        stack: Mutex<Vec<(Id, &'static Metadata<'static>)>>,
+        spans: Mutex<HashMap<u64, &'static Metadata<'static>>>,
+        log: Mutex<Vec<String>>,
+    }
+
+    /// The subscriber of the host.
+    #[derive(Clone, Default)]
+    struct Recorder(Arc<RecorderState>);
+
+    struct FormatFields(String);
+
+    impl Visit for FormatFields {
+        fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+            let _ = write!(self.0, " {}={:?}", field.name(), value);
+        }
+    }
+
+    impl Recorder {
+        fn take(&self) -> Vec<String> {
+            std::mem::take(&mut *self.0.log.lock().unwrap())
+        }
+
+        fn push(&self, line: String) {
+            self.0.log.lock().unwrap().push(line);
+        }
+
+        fn parent(&self, is_root: bool, parent: Option<&Id>) -> String {
+            let parent = if is_root {
+                None
+            } else if let Some(parent) = parent {
+                Some(parent.clone())
+            } else {
+                self.0
+                    .stack
+                    .lock()
+                    .unwrap()
+                    .last()
+                    .map(|(id, _)| id.clone())
+            };
+            match parent {
+                Some(id) => format!("#{}", id.into_u64()),
+                None => "none".to_string(),
+            }
+        }
+    }
+
+    impl tracing_core::Subscriber for Recorder {
+        fn register_callsite(&self, _: &'static Metadata<'static>) -> Interest {
+            Interest::always()
+        }
+
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            let id = Id::from_u64(self.0.next_id.fetch_add(1, Ordering::SeqCst) + 1);
+            self.0
+                .spans
+                .lock()
+                .unwrap()
+                .insert(id.into_u64(), span.metadata());
+
+            let mut fields = FormatFields(String::new());
+            span.record(&mut fields);
+            self.push(format!(
+                "new_span {} #{} parent={}{}",
+                span.metadata().name(),
+                id.into_u64(),
+                self.parent(span.is_root(), span.parent()),
+                fields.0,
+            ));
+            id
+        }
+
+        fn record(&self, span: &Id, values: &Record<'_>) {
+            let mut fields = FormatFields(String::new());
+            values.record(&mut fields);
+            self.push(format!("record #{}{}", span.into_u64(), fields.0));
+        }
+
+        fn record_follows_from(&self, span: &Id, follows: &Id) {
+            self.push(format!(
+                "follows #{} #{}",
+                span.into_u64(),
+                follows.into_u64()
+            ));
+        }
+
+        fn event(&self, event: &Event<'_>) {
+            let mut fields = FormatFields(String::new());
+            event.record(&mut fields);
+            self.push(format!(
+                "event {} parent={}{}",
+                event.metadata().level(),
+                self.parent(event.is_root(), event.parent()),
+                fields.0,
+            ));
+        }
+
+        fn enter(&self, span: &Id) {
+            let metadata = self.0.spans.lock().unwrap()[&span.into_u64()];
+            self.0.stack.lock().unwrap().push((span.clone(), metadata));
+            self.push(format!("enter #{}", span.into_u64()));
+        }
+
+        fn exit(&self, span: &Id) {
+            self.0.stack.lock().unwrap().pop();
+            self.push(format!("exit #{}", span.into_u64()));
+        }
+
+        fn current_span(&self) -> span::Current {
+            match self.0.stack.lock().unwrap().last() {
+                Some((id, metadata)) => span::Current::new(id.clone(), metadata),
+                None => span::Current::none(),
+            }
+        }
+    }
+
+    #[test]
+    fn conversions() {
+        for level in [
+            Level::ERROR,
+            Level::WARN,
+            Level::INFO,
+            Level::DEBUG,
+            Level::TRACE,
+        ] {
+            assert_eq!(Level::from(RLevel::from(level)), level);
+
+            let filter = LevelFilter::from_level(level);
+            assert_eq!(LevelFilter::from(RLevelFilter::from(filter)), filter);
+        }
+        assert_eq!(RLevelFilter::from(LevelFilter::OFF), RLevelFilter::Off);
+
+        for interest in [RInterest::Never, RInterest::Sometimes, RInterest::Always] {
+            assert_eq!(RInterest::from(Interest::from(interest)), interest);
+        }
+    }
+
+    #[test]
+    fn forwards_spans_and_events() {
+        let recorder = Recorder::default();
+        let subscriber = RSubscriber::from_dispatch(Dispatch::new(recorder.clone())).clone();
+
+        tracing::subscriber::with_default(subscriber, || {
+            let outer = tracing::info_span!("outer", answer = 42);
+            let _guard = outer.enter();
+
+            tracing::info!(flag = true, text = "hi", "hello");
+
+            let inner = tracing::debug_span!(parent: None, "inner", value = tracing::field::Empty);
+            inner.record("value", 1.5);
+            inner.follows_from(&outer);
+            tracing::warn!(parent: &inner, list = ?[3, 5], "explicit");
+
+            let current = tracing::Span::current();
+            assert_eq!(current.id(), outer.id());
+            assert!(std::ptr::eq(
+                current.metadata().unwrap(),
+                outer.metadata().unwrap()
+            ));
+        });
+
+        assert_eq!(
+            recorder.take(),
+            [
+                "new_span outer #1 parent=none answer=42",
+                "enter #1",
+                "event INFO parent=#1 message=hello flag=true text=\"hi\"",
+                "new_span inner #2 parent=none",
+                "record #2 value=1.5",
+                "follows #2 #1",
+                "event WARN parent=#2 message=explicit list=[3, 5]",
+                "exit #1",
+            ],
+        );
+    }
+
+    static SPAN_CALLSITE: u8 = 0;
+    static EVENT_CALLSITE: u8 = 0;
+
+    fn foreign_metadata(callsite: &'static u8, name: &'static str, kind: RKind) -> RMetadata {
+        const FIELD_NAMES: &[RStr<'static>] = &[
+            RStr::from_str("message"),
+            RStr::from_str("number"),
+            RStr::from_str("text"),
+        ];
+        RMetadata {
+            callsite: RCallsiteId(callsite as *const u8 as usize),
+            name: RStr::from_str(name),
+            target: RStr::from_str("plugin"),
+            level: RLevel::Info,
+            module_path: RSome(RStr::from_str("plugin::module")),
+            file: RSome(RStr::from_str("plugin/module.rs")),
+            line: RSome(10),
+            field_names: RSlice::from_slice(FIELD_NAMES),
+            kind,
+        }
+    }
+
+    // Simulates a dynamic library with its own copy of `tracing-core`,
+    // whose callsites are unknown to this one.
+    #[test]
+    fn proxies_foreign_callsites() {
+        let recorder = Recorder::default();
+        let subscriber = RSubscriber::from_dispatch(Dispatch::new(recorder.clone()));
+        let ffi = &subscriber.inner;
+
+        let span_meta = foreign_metadata(&SPAN_CALLSITE, "foreign_span", RKind::Span);
+        let event_meta = foreign_metadata(&EVENT_CALLSITE, "foreign_event", RKind::Event);
+
+        assert_eq!(
+            ffi.new_span(span_meta.callsite, RParent::Root, RSlice::EMPTY),
+            RNone
+        );
+        assert!(!ffi.enabled(span_meta.callsite));
+
+        assert_eq!(ffi.register_callsite(&span_meta), RInterest::Always);
+        assert_eq!(ffi.register_callsite(&event_meta), RInterest::Always);
+        assert!(ffi.enabled(span_meta.callsite));
+
+        let proxy = lookup_metadata(span_meta.callsite).unwrap();
+        assert_eq!(proxy.name(), "foreign_span");
+        assert_eq!(proxy.target(), "plugin");
+        assert_eq!(*proxy.level(), Level::INFO);
+        assert_eq!(proxy.module_path(), Some("plugin::module"));
+        assert_eq!(proxy.file(), Some("plugin/module.rs"));
+        assert_eq!(proxy.line(), Some(10));
+        assert!(proxy.is_span());
+        assert_eq!(proxy.fields().len(), 3);
+        assert_eq!(ffi_metadata(proxy), span_meta);
+        assert!(std::ptr::eq(local_metadata(&span_meta), proxy));
+
+        let fields = [
+            RField {
+                name: RStr::from_str("number"),
+                value: RFieldValue::U64(3),
+            },
+            RField {
+                name: RStr::from_str("undeclared"),
+                value: RFieldValue::Bool(false),
+            },
+        ];
+        let span = ffi
+            .new_span(span_meta.callsite, RParent::Root, fields[..].into())
+            .unwrap();
+
+        ffi.enter(span);
+        assert_eq!(
+            ffi.current_span(),
+            RCurrentSpan::Entered {
+                id: span,
+                metadata: span_meta,
+            }
+        );
+
+        let fields = [
+            RField {
+                name: RStr::from_str("message"),
+                value: RFieldValue::Debug(RStr::from_str("some message")),
+            },
+            RField {
+                name: RStr::from_str("text"),
+                value: RFieldValue::Str(RStr::from_str("hello")),
+            },
+        ];
+        ffi.event(event_meta.callsite, RParent::Current, fields[..].into());
+        ffi.event(event_meta.callsite, RParent::Root, RSlice::EMPTY);
+        ffi.exit(span);
+        assert_eq!(ffi.current_span(), RCurrentSpan::NotEntered);
+
+        let fields = [RField {
+            name: RStr::from_str("number"),
+            value: RFieldValue::I64(-5),
+        }];
+        ffi.record(span, span_meta.callsite, fields[..].into());
+        ffi.event(event_meta.callsite, RParent::Explicit(span), RSlice::EMPTY);
+
+        assert_eq!(
+            recorder.take(),
+            [
+                "new_span foreign_span #1 parent=none number=3",
+                "enter #1",
+                "event INFO parent=#1 message=some message text=\"hello\"",
+                "event INFO parent=none",
+                "exit #1",
+                "record #1 number=-5",
+                "event INFO parent=#1",
+            ],
+        );
+    }
+}
@@ -12,7 +12,12 @@ use std::{marker::PhantomData, mem::ManuallyDrop};
 ///
 /// - Not implement Drop,and have no drop glue.
 ///
-pub unsafe trait InlineStorage {}
+pub unsafe trait InlineStorage {
+    // Whether `NonExhaustive` stores enums that don't fit in this storage in the heap,
+    // only overriden by `HeapFallback`.
+    #[doc(hidden)]
+    const __HEAP_FALLBACK: bool = false;
+}
 
 macro_rules! impl_for_arrays {
     ( ty=$ty:ty , len[ $($len:expr),* $(,)* ] ) => (
@@ -66,6 +66,9 @@ These are default cargo features that enable optional crates :
 - "channels":
     Depends on `crossbeam-channel`,
     wrapping channels from it for ffi in `abi_stable::external_types::crossbeam_channel` .
+    This also enables the broadcast channel in
+    `abi_stable::external_types::crossbeam_channel::broadcast`,
+    and the async channel in `abi_stable::external_types::async_channel`.
 
 - "serde_json":
     Depends on `serde_json`,
@@ -89,9 +92,37 @@ These are crate features to manually enable support for newer language features:
 
 - "rust_1_64": Turns many functions for converting types to slices into const fns.
 
+- "rust_1_65": Makes `RBoxError_::new` and `RBoxError_::from_box` capture a backtrace
+(when enabled by environment variables), returned by `RBoxError_::backtrace`.
+
 - "rust_latest_stable":
 Enables the "rust_1_*" features for all the stable releases.
 
+- "futures":
+Depends on `futures-core`,
+allowing `DynTrait` and `RStream` to be constructed from/used as `futures_core::Stream`s.
+
+- "tokio":
+Depends on `tokio`,
+providing an adapter for using a tokio runtime as an `abi_stable::sabi_types::executor::RExecutor`,
+in `abi_stable::external_types::tokio`.
+
+- "log":
+Depends on `log`,
+providing an ffi-safe logger that forwards the `log` records of dynamic libraries to the host,
+in `abi_stable::external_types::log`.
+
+- "tracing":
+Depends on `tracing-core`,
+providing an ffi-safe subscriber that forwards the spans and events of dynamic libraries
+to the `tracing` subscriber of the host,
+in `abi_stable::external_types::tracing`.
+
+- "bincode":
+Depends on `bincode`,
+providing binary proxy types for serializing `DynTrait` and `NonExhaustive`,
+in `abi_stable::external_types::bincode`.
+
 # Glossary
 
 `interface crate`: the crate that declares the public functions, types, and traits that
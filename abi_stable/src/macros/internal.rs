@@ -37,6 +37,121 @@ macro_rules! deref_coerced_impl_cmp_traits {
     };
 }
 
+// Used instead of `shared_impls` and `deref_coerced_impl_cmp_traits`
+// for types that can't be borrowed as `$coerce_to` on some platforms,
+// converting them with the `$convert` method instead.
+#[cfg(windows)]
+macro_rules! converted_impl_cmp_traits {
+    (
+        $Self:ty;
+        convert_with = $convert:ident,
+        coerce_to = $coerce_to:ty,
+        std = [$($Rhs:ty),* $(,)?],
+        ffi = [$($FfiRhs:ty),* $(,)?] $(,)?
+    ) => {
+        const _: () = {
+            use std::{
+                cmp::{Ord, Ordering, PartialEq, PartialOrd},
+                fmt::{self, Debug},
+                hash::{Hash, Hasher},
+            };
+
+            impl Debug for $Self {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    Debug::fmt(&self.$convert(), f)
+                }
+            }
+
+            impl Eq for $Self {}
+
+            impl PartialEq for $Self {
+                fn eq(&self, other: &Self) -> bool {
+                    self.$convert() == other.$convert()
+                }
+            }
+
+            impl Ord for $Self {
+                fn cmp(&self, other: &Self) -> Ordering {
+                    self.$convert().cmp(&other.$convert())
+                }
+            }
+
+            impl PartialOrd for $Self {
+                fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                    Some(self.cmp(other))
+                }
+            }
+
+            impl Hash for $Self {
+                fn hash<H>(&self, state: &mut H)
+                where
+                    H: Hasher,
+                {
+                    self.$convert().hash(state)
+                }
+            }
+
+            $(
+                impl PartialEq<$Rhs> for $Self {
+                    fn eq(&self, other: &$Rhs) -> bool {
+                        <$coerce_to as PartialEq>::eq(self.$convert().as_ref(), other.as_ref())
+                    }
+                }
+
+                impl PartialOrd<$Rhs> for $Self {
+                    fn partial_cmp(&self, other: &$Rhs) -> Option<Ordering> {
+                        <$coerce_to as PartialOrd>::partial_cmp(
+                            self.$convert().as_ref(),
+                            other.as_ref(),
+                        )
+                    }
+                }
+
+                impl PartialEq<$Self> for $Rhs {
+                    fn eq(&self, other: &$Self) -> bool {
+                        <$coerce_to as PartialEq>::eq(self.as_ref(), other.$convert().as_ref())
+                    }
+                }
+
+                impl PartialOrd<$Self> for $Rhs {
+                    fn partial_cmp(&self, other: &$Self) -> Option<Ordering> {
+                        <$coerce_to as PartialOrd>::partial_cmp(
+                            self.as_ref(),
+                            other.$convert().as_ref(),
+                        )
+                    }
+                }
+            )*
+
+            $(
+                impl PartialEq<$FfiRhs> for $Self {
+                    fn eq(&self, other: &$FfiRhs) -> bool {
+                        self.$convert() == other.$convert()
+                    }
+                }
+
+                impl PartialOrd<$FfiRhs> for $Self {
+                    fn partial_cmp(&self, other: &$FfiRhs) -> Option<Ordering> {
+                        self.$convert().partial_cmp(&other.$convert())
+                    }
+                }
+
+                impl PartialEq<$Self> for $FfiRhs {
+                    fn eq(&self, other: &$Self) -> bool {
+                        self.$convert() == other.$convert()
+                    }
+                }
+
+                impl PartialOrd<$Self> for $FfiRhs {
+                    fn partial_cmp(&self, other: &$Self) -> Option<Ordering> {
+                        self.$convert().partial_cmp(&other.$convert())
+                    }
+                }
+            )*
+        };
+    };
+}
+
 macro_rules! slice_like_impl_cmp_traits {
     (
         impl $impl_params:tt $Self:ty,
@@ -13,15 +13,19 @@ pub mod doc_enums;
 pub mod examples;
 
 pub(crate) mod alt_c_functions;
+pub(crate) mod heap_fallback;
 pub(crate) mod nonexhaustive;
+pub(crate) mod preserve_unknown;
 pub(crate) mod traits;
 pub(crate) mod vtable;
 
 pub use self::{
+    heap_fallback::HeapFallback,
     nonexhaustive::{
         NonExhaustive, NonExhaustiveFor, NonExhaustiveSharedOps, NonExhaustiveWI, NonExhaustiveWS,
         UnwrapEnumError,
     },
+    preserve_unknown::PreserveUnknown,
     traits::{
         DeserializeEnum, EnumInfo, GetEnumInfo, NonExhaustiveMarker, SerializeEnum,
         ValidDiscriminant,
@@ -3,7 +3,8 @@ use std::ptr;
 use crate::{
     marker_type::ErasedObject,
     nonexhaustive_enum::{
-        vtable::NonExhaustiveVtable_Ref, GetEnumInfo, NonExhaustive, SerializeEnum,
+        heap_fallback::BoxedEnum, vtable::NonExhaustiveVtable_Ref, GetEnumInfo, NonExhaustive,
+        SerializeEnum,
     },
     sabi_types::{RMut, RRef},
     std_types::{RBoxError, RCmpOrdering, ROption, RResult, RSome},
@@ -17,6 +18,13 @@ pub(crate) unsafe extern "C" fn drop_impl<E>(this: RMut<'_, ErasedObject>) {
     }}
 }
 
+pub(crate) unsafe extern "C" fn drop_boxed_impl<E>(this: RMut<'_, ErasedObject>) {
+    extern_fn_panic_handling! {no_early_return; unsafe {
+        let this = this.transmute_into_mut::<BoxedEnum<E>>();
+        ptr::drop_in_place(&mut this.value);
+    }}
+}
+
 pub(crate) unsafe extern "C" fn clone_impl<E, F, I>(
     this: RRef<'_, ErasedObject>,
     vtable: NonExhaustiveVtable_Ref<E, F, I>,
@@ -27,7 +35,7 @@ where
 {
     extern_fn_panic_handling! {no_early_return; unsafe {
         let this = this.transmute_into_ref::<E>();
-        NonExhaustive::with_vtable(this.clone(), vtable)
+        NonExhaustive::with_vtable_or_boxed(this.clone(), vtable)
     }}
 }
 
@@ -58,3 +58,17 @@ pub mod example_3 {
 
     declare_constructors! {Foo}
 }
+
+pub mod example_4 {
+    #[repr(u8)]
+    #[derive(StableAbi, Debug, Clone, PartialEq, Eq)]
+    #[sabi(kind(WithNonExhaustive(
+        size = [usize;4],
+        heap_fallback,
+        traits(Debug, Clone, PartialEq),
+    )))]
+    pub enum Foo {
+        A,
+        B([u64; 8]),
+    }
+}
@@ -260,6 +260,51 @@ pub mod generic_b {
     }
 }
 
+pub mod heap_fallback_a {
+    #[repr(u8)]
+    #[derive(StableAbi, Hash, Debug, PartialOrd, Ord, PartialEq, Eq, Clone)]
+    #[sabi(kind(WithNonExhaustive(
+        size = [usize; 4],
+        heap_fallback,
+        traits(Debug, PartialEq, Eq, Ord, PartialOrd, Clone, Hash)
+    )))]
+    pub enum Foo<T> {
+        A,
+        B(T),
+    }
+}
+
+pub mod heap_fallback_b {
+    #[repr(u8)]
+    #[derive(StableAbi, Hash, Debug, PartialOrd, Ord, PartialEq, Eq, Clone)]
+    #[sabi(kind(WithNonExhaustive(
+        size = [usize; 4],
+        heap_fallback,
+        traits(Debug, PartialEq, Eq, Ord, PartialOrd, Clone, Hash)
+    )))]
+    pub enum Foo<T> {
+        A,
+        B(T),
+        C([u64; 8]),
+    }
+}
+
+pub mod heap_fallback_c {
+    #[repr(u8)]
+    #[derive(StableAbi, Hash, Debug, PartialOrd, Ord, PartialEq, Eq, Clone)]
+    #[sabi(kind(WithNonExhaustive(
+        size = [usize; 4],
+        heap_fallback,
+        traits(Debug, PartialEq, Eq, Ord, PartialOrd, Clone, Hash)
+    )))]
+    pub enum Foo<T> {
+        A,
+        B(T),
+        C([u64; 8]),
+        D([u64; 32]),
+    }
+}
+
 pub mod many_ranges_a {
     #[repr(u8)]
     #[derive(StableAbi, Debug, PartialEq, Eq, Clone)]
@@ -0,0 +1,108 @@
+//! Contains `HeapFallback`, and the helpers for storing enums in the heap.
+
+use std::mem;
+
+use crate::{inline_storage::InlineStorage, std_types::RBox};
+
+/// A storage for [`NonExhaustive`] which stores the enums that don't fit in `S` in the heap.
+///
+/// Enums whose size or alignment are larger than those of `S` are stored in an [`RBox`],
+/// which allows adding large variants to an enum in semver compatible versions,
+/// without changing its storage.
+/// Since [`NonExhaustive::as_enum`] returns a reference to the whole enum,
+/// this depends on the size of the enum, not on the size of the variant being stored.
+///
+/// Whether the enum is stored in the heap is recorded in each `NonExhaustive`,
+/// so `NonExhaustive`s constructed by versions of the library
+/// where the enum did and didn't fit in `S` can be used together.
+///
+/// `S` must have the size and alignment to store an `RBox`,
+/// this is checked when an enum is stored in the heap.
+///
+/// The `heap_fallback` argument of the
+/// [`#[sabi(kind(WithNonExhaustive(...)))]`](crate::docs::sabi_nonexhaustive)
+/// attribute makes this the default storage of an enum.
+///
+/// # Construction
+///
+/// Only the non-`const` `NonExhaustive` constructors
+/// (eg: [`NonExhaustive::new_or_boxed`]) can store an enum in the heap,
+/// the `const` ones panic if the enum doesn't fit in `S`.
+///
+/// # Unwrapping
+///
+/// If the `NonExhaustive` was constructed by a version of the library where the enum
+/// was smaller, and the memory it's stored in is too small for the enum in this version,
+/// [`NonExhaustive::as_enum`], [`NonExhaustive::as_enum_mut`],
+/// and [`NonExhaustive::into_enum`] return an error,
+/// the same as with variants that this version of the library doesn't know about.
+///
+/// # Example
+///
+/// ```
+/// use abi_stable::{
+///     nonexhaustive_enum::{HeapFallback, NonExhaustive},
+///     std_types::RString,
+///     StableAbi,
+/// };
+///
+/// let small = Foo_NE::new_or_boxed(Foo::A(3));
+/// let large = Foo_NE::new_or_boxed(Foo::B([5; 16]));
+///
+/// // `Foo` doesn't fit in its 32 byte storage, so it's stored in the heap.
+/// assert!(small.is_boxed());
+/// assert!(large.is_boxed());
+///
+/// assert_eq!(small, Foo::A(3));
+/// assert_eq!(large.into_enum().ok(), Some(Foo::B([5; 16])));
+///
+/// assert_eq!(
+///     std::mem::size_of::<Foo_NE>(),
+///     std::mem::size_of::<HeapFallback<[usize; 4]>>() + std::mem::size_of::<usize>(),
+/// );
+///
+/// #[repr(u8)]
+/// #[derive(StableAbi, Debug, Clone, PartialEq)]
+/// #[sabi(kind(WithNonExhaustive(
+///     size = [usize; 4],
+///     heap_fallback,
+///     traits(Debug, Clone, PartialEq),
+/// )))]
+/// pub enum Foo {
+///     A(u32),
+///     // This variant could be added in a minor version.
+///     B([u64; 16]),
+/// }
+///
+/// ```
+///
+/// [`NonExhaustive`]: crate::nonexhaustive_enum::NonExhaustive
+/// [`NonExhaustive::new_or_boxed`]: crate::nonexhaustive_enum::NonExhaustive::new_or_boxed
+/// [`NonExhaustive::as_enum`]: crate::nonexhaustive_enum::NonExhaustive::as_enum
+/// [`NonExhaustive::as_enum_mut`]: crate::nonexhaustive_enum::NonExhaustive::as_enum_mut
+/// [`NonExhaustive::into_enum`]: crate::nonexhaustive_enum::NonExhaustive::into_enum
+/// [`RBox`]: crate::std_types::RBox
+#[repr(transparent)]
+#[derive(StableAbi)]
+pub struct HeapFallback<S> {
+    #[sabi(unsafe_opaque_field)]
+    _inline: S,
+}
+
+unsafe impl<S> InlineStorage for HeapFallback<S>
+where
+    S: InlineStorage,
+{
+    const __HEAP_FALLBACK: bool = true;
+}
+
+/// How an enum is laid out in the storage of a `NonExhaustive` when it's in the heap.
+#[repr(C)]
+pub(crate) struct BoxedEnum<E> {
+    pub(crate) value: RBox<E>,
+}
+
+/// Whether `E` has the size and alignment to be stored inline in `S`.
+pub(crate) const fn fits_in_storage<E, S>() -> bool {
+    mem::size_of::<E>() <= mem::size_of::<S>() && mem::align_of::<E>() <= mem::align_of::<S>()
+}
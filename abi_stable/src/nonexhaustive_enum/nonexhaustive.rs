@@ -5,22 +5,26 @@ use std::{
     fmt::{self, Debug, Display},
     hash::{Hash, Hasher},
     marker::PhantomData,
-    mem::ManuallyDrop,
+    mem::{self, ManuallyDrop},
     ops::Deref,
+    ptr,
 };
 
 use crate::{
     abi_stability::StableAbi,
     erased_types::{c_functions, trait_objects::HasherObject, InterfaceType, MakeRequiredTraits},
-    inline_storage::ScratchSpace,
+    inline_storage::{InlineStorage, ScratchSpace},
     marker_type::ErasedObject,
     nonexhaustive_enum::{
-        assert_correct_storage, vtable::NonExhaustiveVtable_Ref, AssertCsArgs, DeserializeEnum,
-        EnumInfo, GetEnumInfo, GetVTable, NonExhaustiveMarker, SerializeEnum, ValidDiscriminant,
+        assert_correct_storage,
+        heap_fallback::{fits_in_storage, BoxedEnum},
+        vtable::NonExhaustiveVtable_Ref,
+        AssertCsArgs, DeserializeEnum, EnumInfo, GetEnumInfo, GetVTable, NonExhaustiveMarker,
+        SerializeEnum, ValidDiscriminant,
     },
     pointer_trait::{CanTransmuteElement, TransmuteElement},
     sabi_types::{RMut, RRef},
-    std_types::RBoxError,
+    std_types::{RBox, RBoxError},
     traits::IntoReprRust,
     type_level::{impl_enum::Implemented, trait_marker},
 };
@@ -46,7 +50,9 @@ mod tests;
 ///
 /// The storage type,used to store the enum opaquely.
 ///
-/// This has to be at least the size and alignment of the wrapped enum.
+/// This has to be at least the size and alignment of the wrapped enum,
+/// unless it's a [`HeapFallback`](crate::nonexhaustive_enum::HeapFallback),
+/// which stores the enums that don't fit in it in the heap.
 ///
 /// This is necessary because:
 ///
@@ -195,9 +201,12 @@ mod tests;
     phantom_type_param = <E as NonExhaustiveMarker<S>>::Marker,
 )]
 pub struct NonExhaustive<E, S, I> {
-    // This is an opaque field since we only care about its size and alignment
+    // This is an opaque field since we only care about its size and alignment.
+    //
+    // This doesn't use `ScratchSpace<E, S>` so that the size of `NonExhaustive`
+    // doesn't depend on `E` when it's stored in the heap.
     #[sabi(unsafe_opaque_field)]
-    fill: ScratchSpace<E, S>,
+    fill: ScratchSpace<(), S>,
     vtable: NonExhaustiveVtable_Ref<E, S, I>,
     _marker: PhantomData<()>,
 }
@@ -277,12 +286,96 @@ impl<E, S, I> NonExhaustive<E, S, I> {
     ) -> Self {
         // `ScratchSpace::new` is what asserts that the enum is
         // the correct size and alignment
+        let fill = ScratchSpace::<E, S>::new(value);
         Self {
-            fill: ScratchSpace::<E, S>::new(value),
+            fill: unsafe { const_transmute!(ScratchSpace<E, S>, ScratchSpace<(), S>, fill) },
             vtable,
             _marker: PhantomData,
         }
     }
+
+    /// Constructs a `NonExhaustive<>` from `value` using its default interface and storage,
+    /// storing `value` in the heap if it doesn't fit in a
+    /// [`HeapFallback`](crate::nonexhaustive_enum::HeapFallback) storage.
+    ///
+    /// # Panic
+    ///
+    /// This panics if the storage has an alignment or size smaller than that of `E`,
+    /// and isn't a `HeapFallback`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::nonexhaustive_enum::doc_enums::example_4::{Foo, Foo_NE};
+    ///
+    /// let a = Foo_NE::new_or_boxed(Foo::A);
+    /// let b = Foo_NE::new_or_boxed(Foo::B([3; 8]));
+    ///
+    /// // `Foo` is larger than its `[usize; 4]` storage, so it's stored in the heap.
+    /// assert!(a.is_boxed());
+    /// assert!(b.is_boxed());
+    ///
+    /// assert_eq!(a, Foo::A);
+    /// assert_eq!(b, Foo::B([3; 8]));
+    ///
+    /// ```
+    #[inline]
+    pub fn new_or_boxed(value: E) -> Self
+    where
+        E: GetVTable<S, I> + GetEnumInfo<DefaultStorage = S, DefaultInterface = I>,
+        S: InlineStorage,
+    {
+        NonExhaustive::with_storage_and_interface_or_boxed(value)
+    }
+
+    /// Constructs a `NonExhaustive<>` from `value` using both a custom interface and storage,
+    /// storing `value` in the heap if it doesn't fit in a
+    /// [`HeapFallback`](crate::nonexhaustive_enum::HeapFallback) storage.
+    ///
+    /// # Panic
+    ///
+    /// This panics if the storage has an alignment or size smaller than that of `E`,
+    /// and isn't a `HeapFallback`.
+    #[inline]
+    pub fn with_storage_and_interface_or_boxed(value: E) -> Self
+    where
+        E: GetVTable<S, I>,
+        S: InlineStorage,
+    {
+        let vtable = if S::__HEAP_FALLBACK && !fits_in_storage::<E, S>() {
+            E::BOXED_VTABLE
+        } else {
+            E::VTABLE
+        };
+        unsafe { NonExhaustive::with_vtable_or_boxed(value, vtable) }
+    }
+
+    /// Constructs a `NonExhaustive<>` with `vtable`,
+    /// storing `value` in the heap if it's the vtable for boxed enums.
+    #[track_caller]
+    pub(super) unsafe fn with_vtable_or_boxed(
+        value: E,
+        vtable: NonExhaustiveVtable_Ref<E, S, I>,
+    ) -> Self {
+        if vtable.is_boxed() {
+            assert!(
+                fits_in_storage::<BoxedEnum<E>, S>(),
+                "the HeapFallback storage is too small to store an RBox",
+            );
+            let fill = ScratchSpace::<BoxedEnum<E>, S>::new(BoxedEnum {
+                value: RBox::new(value),
+            });
+            Self {
+                fill: unsafe {
+                    const_transmute!(ScratchSpace<BoxedEnum<E>, S>, ScratchSpace<(), S>, fill)
+                },
+                vtable,
+                _marker: PhantomData,
+            }
+        } else {
+            unsafe { NonExhaustive::with_vtable(value, vtable) }
+        }
+    }
 }
 
 impl<E, S, I> NonExhaustive<E, S, I>
@@ -296,6 +389,11 @@ where
     /// This returns an error if the wrapped enum is of a variant that is
     /// not valid in this context.
     ///
+    /// This also returns an error if the enum was constructed by a version of the library
+    /// where `E` was smaller,
+    /// and the memory it's stored in is too small for the `E` in this version,
+    /// since a reference to it would point past the end of that memory.
+    ///
     /// # Example
     ///
     /// This shows how some `NonExhaustive<enum>` can be unwrapped, and others cannot.<br>
@@ -313,11 +411,10 @@ where
     ///
     ///
     /// ```
-    ///
     pub fn as_enum(&self) -> Result<&E, UnwrapEnumError<&Self>> {
         let discriminant = self.get_discriminant();
-        if E::is_valid_discriminant(discriminant) {
-            unsafe { Ok(&*(&self.fill as *const ScratchSpace<E, S> as *const E)) }
+        if E::is_valid_discriminant(discriminant) && self.fits_in_place() {
+            unsafe { Ok(&*self.enum_ptr()) }
         } else {
             Err(UnwrapEnumError::new(self))
         }
@@ -331,6 +428,9 @@ where
     /// This returns an error if the wrapped enum is of a variant that is
     /// not valid in this context.
     ///
+    /// This also returns an error in the same cases as [`as_enum`](Self::as_enum),
+    /// when the memory the enum is stored in is too small for the `E` in this version.
+    ///
     /// # Example
     ///
     /// This shows how some `NonExhaustive<enum>` can be unwrapped, and others cannot.<br>
@@ -352,13 +452,17 @@ where
         E: GetVTable<S, I>,
     {
         let discriminant = self.get_discriminant();
-        if E::is_valid_discriminant(discriminant) {
+        if E::is_valid_discriminant(discriminant) && self.fits_in_place() {
             // Must update the vtable every time as_enum_mut is called,
             // because if the enum is replaced with a variant with a discriminant
             // outside the valid range for the functions in the vtable,
             // it would be undefined behavior to call those functions.
-            self.vtable = E::VTABLE;
-            unsafe { Ok(&mut *(&mut self.fill as *mut ScratchSpace<E, S> as *mut E)) }
+            self.vtable = if self.is_boxed() {
+                E::BOXED_VTABLE
+            } else {
+                E::VTABLE
+            };
+            unsafe { Ok(&mut *self.enum_ptr_mut()) }
         } else {
             Err(UnwrapEnumError::new(self))
         }
@@ -371,6 +475,9 @@ where
     /// This returns an error if the wrapped enum is of a variant that is
     /// not valid in this context.
     ///
+    /// This also returns an error in the same cases as [`as_enum`](Self::as_enum),
+    /// when the memory the enum is stored in is too small for the `E` in this version.
+    ///
     /// # Example
     ///
     /// This shows how some `NonExhaustive<enum>` can be unwrapped, and others cannot.<br>
@@ -389,9 +496,15 @@ where
     /// ```
     pub fn into_enum(self) -> Result<E, UnwrapEnumError<Self>> {
         let discriminant = self.get_discriminant();
-        if E::is_valid_discriminant(discriminant) {
+        if E::is_valid_discriminant(discriminant) && self.fits_in_place() {
             let this = ManuallyDrop::new(self);
-            unsafe { Ok((&this.fill as *const ScratchSpace<E, S> as *const E).read()) }
+            unsafe {
+                if this.is_boxed() {
+                    Ok(RBox::into_inner(ptr::read(&(*this.boxed_ptr()).value)))
+                } else {
+                    Ok(ptr::read(this.enum_ptr()))
+                }
+            }
         } else {
             Err(UnwrapEnumError::new(self))
         }
@@ -409,7 +522,22 @@ where
     /// Gets the value of the discriminant of the enum.
     #[inline]
     pub const fn get_discriminant(&self) -> E::Discriminant {
-        unsafe { *(&self.fill as *const ScratchSpace<E, S> as *const E::Discriminant) }
+        unsafe { *(self.enum_ptr() as *const E::Discriminant) }
+    }
+
+    /// Whether the memory that the enum is stored in has the size and alignment
+    /// of the `E` in this version of the library.
+    ///
+    /// This is only false when this was constructed by a version of the library
+    /// where `E` was smaller.
+    fn fits_in_place(&self) -> bool {
+        if self.is_boxed() {
+            let vtable = self.vtable();
+            mem::size_of::<E>() <= vtable._sabi_enum_size()
+                && mem::align_of::<E>() <= vtable._sabi_enum_align()
+        } else {
+            fits_in_storage::<E, S>()
+        }
     }
 }
 
@@ -488,16 +616,51 @@ impl<E, S, I> NonExhaustive<E, S, I> {
         self.vtable
     }
 
+    /// Whether the wrapped enum is stored in the heap,
+    /// which only happens when it doesn't fit in a
+    /// [`HeapFallback`](crate::nonexhaustive_enum::HeapFallback) storage.
+    ///
+    /// This is determined by the version of the library that constructed
+    /// this `NonExhaustive`.
+    #[inline]
+    pub const fn is_boxed(&self) -> bool {
+        self.vtable().is_boxed()
+    }
+
+    const fn boxed_ptr(&self) -> *const BoxedEnum<E> {
+        &self.fill as *const ScratchSpace<(), S> as *const BoxedEnum<E>
+    }
+
+    const fn enum_ptr(&self) -> *const E {
+        if self.is_boxed() {
+            let boxed = unsafe { &*self.boxed_ptr() };
+            boxed.value.data()
+        } else {
+            &self.fill as *const ScratchSpace<(), S> as *const E
+        }
+    }
+
+    fn enum_ptr_mut(&mut self) -> *mut E {
+        if self.is_boxed() {
+            let boxed = &mut self.fill as *mut ScratchSpace<(), S> as *mut BoxedEnum<E>;
+            unsafe { (*boxed).value.data_mut() }
+        } else {
+            &mut self.fill as *mut ScratchSpace<(), S> as *mut E
+        }
+    }
+
     const fn sabi_erased_ref(&self) -> RRef<'_, ErasedObject> {
-        unsafe { RRef::from_raw(&self.fill as *const ScratchSpace<E, S> as *const ErasedObject) }
+        unsafe { RRef::from_raw(self.enum_ptr() as *const ErasedObject) }
     }
 
     const fn as_erased_ref(&self) -> RRef<'_, ErasedObject> {
         unsafe { RRef::from_raw(self as *const Self as *const ErasedObject) }
     }
 
+    // This points to the storage, since the function that drops
+    // the enum is different when it's boxed.
     fn sabi_erased_mut(&mut self) -> RMut<'_, ErasedObject> {
-        unsafe { RMut::from_raw(&mut self.fill as *mut ScratchSpace<E, S> as *mut ErasedObject) }
+        unsafe { RMut::from_raw(&mut self.fill as *mut ScratchSpace<(), S> as *mut ErasedObject) }
     }
 }
 
@@ -621,7 +784,7 @@ where
         I: InterfaceType<Serialize = Implemented<trait_marker::Serialize>>,
         I: SerializeEnum<E>,
     {
-        unsafe { self.vtable().serialize()(self.as_erased_ref()).into_result() }
+        unsafe { self.vtable().serialize()(self.sabi_erased_ref()).into_result() }
     }
 
     /// Deserializes a `NonExhaustive<_>` from a proxy.
@@ -647,7 +810,7 @@ where
         Z: Serializer,
     {
         unsafe {
-            self.vtable().serialize()(self.as_erased_ref())
+            self.vtable().serialize()(self.sabi_erased_ref())
                 .into_result()
                 .map_err(ser::Error::custom)?
                 .serialize(serializer)
@@ -5,7 +5,8 @@ use crate::{
     nonexhaustive_enum::{
         examples::{
             command_a, command_b, command_c, command_h_mismatched_discriminant, command_serde,
-            const_expr_size_align, generic_a, generic_b, many_ranges_a, many_ranges_b,
+            const_expr_size_align, generic_a, generic_b, heap_fallback_a, heap_fallback_b,
+            heap_fallback_c, many_ranges_a, many_ranges_b,
         },
         GetEnumInfo,
     },
@@ -289,6 +290,195 @@ fn clone_test() {
     assert_eq!(Arc::strong_count(&arc), 1);
 }
 
+#[test]
+fn heap_fallback_test() {
+    use self::heap_fallback_b::{Foo, Foo_NE};
+
+    let arc = Arc::new(100);
+
+    let mut variant_a = Foo_NE::new_or_boxed(Foo::<Arc<i32>>::A);
+    let mut variant_b = Foo_NE::new_or_boxed(Foo::B(arc.clone()));
+    let variant_c = Foo_NE::new_or_boxed(Foo::C([3; 8]));
+
+    assert_eq!(
+        std::mem::size_of::<Foo_NE<Arc<i32>>>(),
+        std::mem::size_of::<[usize; 5]>(),
+    );
+    assert!(variant_a.is_boxed());
+    assert!(variant_b.is_boxed());
+    assert!(variant_c.is_boxed());
+    assert_eq!(variant_a.get_discriminant(), 0);
+    assert_eq!(variant_b.get_discriminant(), 1);
+    assert_eq!(variant_c.get_discriminant(), 2);
+    assert_eq!(Arc::strong_count(&arc), 2);
+
+    assert_eq!(variant_a.as_enum(), Ok(&Foo::A));
+    assert_eq!(variant_b.as_enum(), Ok(&Foo::B(arc.clone())));
+    assert_eq!(variant_c, Foo::C([3; 8]));
+    assert_eq!(
+        format!("{:?}", variant_c),
+        format!("{:?}", Foo::<()>::C([3; 8]))
+    );
+    assert!(variant_a < variant_b);
+    assert!(variant_c > variant_b);
+
+    {
+        let clone_b = variant_b.clone();
+        assert!(clone_b.is_boxed());
+        assert_eq!(Arc::strong_count(&arc), 3);
+        assert_eq!(clone_b, variant_b);
+    }
+    assert_eq!(Arc::strong_count(&arc), 2);
+
+    *variant_a.as_enum_mut().unwrap() = Foo::B(arc.clone());
+    assert_eq!(variant_a, variant_b);
+    assert_eq!(Arc::strong_count(&arc), 3);
+
+    *variant_b.as_enum_mut().unwrap() = Foo::C([5; 8]);
+    assert_eq!(variant_b.get_discriminant(), 2);
+    assert_eq!(Arc::strong_count(&arc), 2);
+
+    assert_eq!(variant_a.into_enum(), Ok(Foo::B(arc.clone())));
+    assert_eq!(variant_b.into_enum(), Ok(Foo::C([5; 8])));
+    assert_eq!(Arc::strong_count(&arc), 1);
+
+    drop(variant_c);
+
+    {
+        use self::heap_fallback_a::{Foo as FooA, Foo_NE as FooA_NE};
+
+        let inline = FooA_NE::new_or_boxed(FooA::B(arc.clone()));
+        assert!(!inline.is_boxed());
+        assert_eq!(inline, NonExhaustive::new(FooA::B(arc.clone())));
+        assert_eq!(Arc::strong_count(&arc), 2);
+    }
+    assert_eq!(Arc::strong_count(&arc), 1);
+
+    must_panic(|| Foo_NE::<Arc<i32>>::new(Foo::A)).unwrap();
+}
+
+#[test]
+fn heap_fallback_versions_test() {
+    use self::{
+        heap_fallback_a::{Foo as FooA, Foo_NE as FooA_NE},
+        heap_fallback_b::{Foo as FooB, Foo_NE as FooB_NE},
+        heap_fallback_c::{Foo as FooC, Foo_NE as FooC_NE},
+    };
+
+    type NeA = FooA_NE<Arc<i32>>;
+    type NeB = FooB_NE<Arc<i32>>;
+    type NeC = FooC_NE<Arc<i32>>;
+
+    let arc = Arc::new(100);
+
+    // Constructed by the version where the enum fits inline,
+    // unwrapped by the version where it doesn't.
+    unsafe {
+        let mut variant_b =
+            std::mem::transmute::<NeA, NeB>(FooA_NE::new_or_boxed(FooA::B(arc.clone())));
+        assert!(!variant_b.is_boxed());
+        assert_eq!(Arc::strong_count(&arc), 2);
+
+        // The storage is too small for the enum in this version,
+        // so it can only be read through the vtable of the version that constructed it.
+        assert!(variant_b.is_valid_discriminant());
+        assert_eq!(variant_b.as_enum().ok(), None);
+        assert_eq!(variant_b.as_enum_mut().ok(), None);
+        assert_ne!(variant_b, FooB::B(arc.clone()));
+        assert_eq!(variant_b, FooB_NE::new_or_boxed(FooB::B(arc.clone())));
+        assert_eq!(format!("{:?}", variant_b), "B(100)");
+
+        {
+            let clone_b = variant_b.clone();
+            assert!(!clone_b.is_boxed());
+            assert_eq!(Arc::strong_count(&arc), 3);
+        }
+        assert_eq!(Arc::strong_count(&arc), 2);
+
+        let variant_a = std::mem::transmute::<NeA, NeB>(FooA_NE::new_or_boxed(FooA::A));
+        assert_eq!(variant_a.as_enum().ok(), None);
+        let variant_a = variant_a.into_enum().unwrap_err().into_inner();
+        assert_eq!(
+            std::mem::transmute::<NeB, NeA>(variant_a).into_enum(),
+            Ok(FooA::A)
+        );
+
+        let variant_b = variant_b.into_enum().unwrap_err().into_inner();
+        assert_eq!(Arc::strong_count(&arc), 2);
+        assert_eq!(
+            std::mem::transmute::<NeB, NeA>(variant_b).into_enum(),
+            Ok(FooA::B(arc.clone()))
+        );
+        assert_eq!(Arc::strong_count(&arc), 1);
+    }
+
+    // Constructed by the version where the enum doesn't fit inline,
+    // unwrapped by the version where it does.
+    unsafe {
+        let mut variant_b =
+            std::mem::transmute::<NeB, NeA>(FooB_NE::new_or_boxed(FooB::B(arc.clone())));
+        let variant_c = std::mem::transmute::<NeB, NeA>(FooB_NE::new_or_boxed(FooB::C([3; 8])));
+        assert!(variant_b.is_boxed());
+        assert!(variant_c.is_boxed());
+        assert_eq!(Arc::strong_count(&arc), 2);
+
+        assert_eq!(variant_b.as_enum(), Ok(&FooA::B(arc.clone())));
+        assert_eq!(variant_c.as_enum().ok(), None);
+        assert_ne!(variant_c, FooA::A);
+        assert_ne!(variant_b, variant_c);
+
+        assert_eq!(variant_b.as_enum_mut(), Ok(&mut FooA::B(arc.clone())));
+        assert!(variant_b.is_boxed());
+        assert_eq!(variant_b, FooA::B(arc.clone()));
+
+        let variant_a = std::mem::transmute::<NeB, NeA>(FooB_NE::new_or_boxed(FooB::A));
+        assert_eq!(variant_a.into_enum(), Ok(FooA::A));
+        assert_eq!(variant_c.into_enum().ok(), None);
+
+        drop(variant_b);
+        assert_eq!(Arc::strong_count(&arc), 1);
+    }
+
+    // Boxed by a version where the enum is smaller,
+    // unwrapped by a version where it's larger.
+    unsafe {
+        let mut variant_b =
+            std::mem::transmute::<NeB, NeC>(FooB_NE::new_or_boxed(FooB::B(arc.clone())));
+        let variant_c = std::mem::transmute::<NeB, NeC>(FooB_NE::new_or_boxed(FooB::C([3; 8])));
+        assert!(variant_b.is_boxed());
+        assert_eq!(Arc::strong_count(&arc), 2);
+
+        // The heap allocation is too small for the enum in this version.
+        assert!(variant_b.is_valid_discriminant());
+        assert_eq!(variant_b.as_enum().ok(), None);
+        assert_eq!(variant_b.as_enum_mut().ok(), None);
+        assert_ne!(variant_b, FooC::B(arc.clone()));
+        assert_eq!(format!("{:?}", variant_b), "B(100)");
+        assert_eq!(format!("{:?}", variant_c), "C([3, 3, 3, 3, 3, 3, 3, 3])");
+
+        {
+            let clone_b = variant_b.clone();
+            assert!(clone_b.is_boxed());
+            assert_eq!(clone_b, variant_b);
+            assert_eq!(Arc::strong_count(&arc), 3);
+        }
+        assert_eq!(Arc::strong_count(&arc), 2);
+
+        let variant_c = variant_c.into_enum().unwrap_err().into_inner();
+        assert_eq!(
+            std::mem::transmute::<NeC, NeB>(variant_c).into_enum(),
+            Ok(FooB::C([3; 8]))
+        );
+
+        let variant_b = variant_b.into_enum().unwrap_err().into_inner();
+        assert_eq!(
+            std::mem::transmute::<NeC, NeB>(variant_b).as_enum(),
+            Ok(&FooB::B(arc.clone()))
+        );
+        assert_eq!(Arc::strong_count(&arc), 1);
+    }
+}
+
 #[test]
 fn fmt_test() {
     use self::command_serde::Foo as FooC;
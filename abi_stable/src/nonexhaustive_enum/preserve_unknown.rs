@@ -0,0 +1,338 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::nonexhaustive_enum::{DeserializeEnum, NonExhaustive};
+
+/// A [`NonExhaustive`] that keeps the serialized representation of
+/// the values it couldn't deserialize.
+///
+/// When a library deserializes a `NonExhaustive` with a variant that
+/// it doesn't know about (because it was added in a newer version of the library),
+/// deserialization fails.
+/// Deserializing a `PreserveUnknown<NE, P>` instead stores the proxy `P`
+/// (the type that [`DeserializeEnum`] deserializes from) in the `Unknown` variant,
+/// and serializes it back unchanged,
+/// so that libraries that don't know about a variant can pass it along without loss.
+///
+/// Any proxy that [`DeserializeEnum`] errors on is preserved,
+/// including ones that could not be deserialized by any version of the library.
+///
+/// # Example
+///
+/// This example simulates an older library that receives
+/// a variant added by a newer library,
+/// and passes it along to the newer one.
+///
+/// ```
+/// use abi_stable::{
+///     external_types::RawValueBox,
+///     nonexhaustive_enum::{DeserializeEnum, NonExhaustive, PreserveUnknown, SerializeEnum},
+///     std_types::{RBoxError, RString},
+///     StableAbi,
+/// };
+///
+/// # fn main() {
+/// // serialized by the newer library
+/// let json = serde_json::to_string(&v2::Foo_NE::new(v2::Foo::C("hello".into()))).unwrap();
+///
+/// // deserialized and serialized back by the older library
+/// let preserved = serde_json::from_str::<PreserveUnknown<v1::Foo_NE, RawValueBox>>(&json)
+///     .unwrap();
+/// assert!(preserved.is_unknown());
+/// let json = serde_json::to_string(&preserved).unwrap();
+///
+/// // deserialized by the newer library
+/// let ne = serde_json::from_str::<v2::Foo_NE>(&json).unwrap();
+/// assert_eq!(ne, v2::Foo::C("hello".into()));
+/// # }
+///
+/// mod v1 {
+///     use super::*;
+///
+///     #[repr(u8)]
+///     #[derive(StableAbi, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+///     #[sabi(kind(WithNonExhaustive(
+///         size = 64,
+///         traits(Debug, PartialEq, Eq, Serialize, Deserialize)
+///     )))]
+///     pub enum Foo {
+///         A,
+///         B(u32),
+///     }
+///
+///     impl SerializeEnum<Foo> for Foo_Interface {
+///         type Proxy = RawValueBox;
+///
+///         fn serialize_enum(this: &Foo) -> Result<RawValueBox, RBoxError> {
+///             serde_json::value::to_raw_value(this)
+///                 .map(RawValueBox::from)
+///                 .map_err(RBoxError::new)
+///         }
+///     }
+///
+///     impl<'a> DeserializeEnum<'a, Foo_NE> for Foo_Interface {
+///         type Proxy = RawValueBox;
+///
+///         fn deserialize_enum(s: RawValueBox) -> Result<Foo_NE, RBoxError> {
+///             serde_json::from_str::<Foo>(s.get())
+///                 .map(NonExhaustive::new)
+///                 .map_err(RBoxError::new)
+///         }
+///     }
+/// }
+///
+/// mod v2 {
+///     use super::*;
+///
+///     #[repr(u8)]
+///     #[derive(StableAbi, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+///     #[sabi(kind(WithNonExhaustive(
+///         size = 64,
+///         traits(Debug, PartialEq, Eq, Serialize, Deserialize)
+///     )))]
+///     pub enum Foo {
+///         A,
+///         B(u32),
+///         C(RString),
+///     }
+///
+///     impl SerializeEnum<Foo> for Foo_Interface {
+///         type Proxy = RawValueBox;
+///
+///         fn serialize_enum(this: &Foo) -> Result<RawValueBox, RBoxError> {
+///             serde_json::value::to_raw_value(this)
+///                 .map(RawValueBox::from)
+///                 .map_err(RBoxError::new)
+///         }
+///     }
+///
+///     impl<'a> DeserializeEnum<'a, Foo_NE> for Foo_Interface {
+///         type Proxy = RawValueBox;
+///
+///         fn deserialize_enum(s: RawValueBox) -> Result<Foo_NE, RBoxError> {
+///             serde_json::from_str::<Foo>(s.get())
+///                 .map(NonExhaustive::new)
+///                 .map_err(RBoxError::new)
+///         }
+///     }
+/// }
+///
+/// ```
+///
+/// [`NonExhaustive`]: crate::nonexhaustive_enum::NonExhaustive
+/// [`DeserializeEnum`]: crate::nonexhaustive_enum::DeserializeEnum
+#[repr(u8)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, StableAbi)]
+pub enum PreserveUnknown<NE, P> {
+    /// A successfully deserialized value.
+    Known(NE),
+    /// The serialized representation of a value that couldn't be deserialized.
+    Unknown(P),
+}
+
+impl<NE, P> PreserveUnknown<NE, P> {
+    /// Whether this is a successfully deserialized value.
+    #[inline]
+    pub const fn is_known(&self) -> bool {
+        matches!(self, PreserveUnknown::Known(_))
+    }
+
+    /// Whether this is the serialized representation of an unknown value.
+    #[inline]
+    pub const fn is_unknown(&self) -> bool {
+        matches!(self, PreserveUnknown::Unknown(_))
+    }
+
+    /// Gets a reference to the known value.
+    #[inline]
+    pub const fn as_known(&self) -> Option<&NE> {
+        match self {
+            PreserveUnknown::Known(x) => Some(x),
+            PreserveUnknown::Unknown(_) => None,
+        }
+    }
+
+    /// Gets a mutable reference to the known value.
+    #[inline]
+    pub fn as_known_mut(&mut self) -> Option<&mut NE> {
+        match self {
+            PreserveUnknown::Known(x) => Some(x),
+            PreserveUnknown::Unknown(_) => None,
+        }
+    }
+
+    /// Gets a reference to the serialized representation of the unknown value.
+    #[inline]
+    pub const fn as_unknown(&self) -> Option<&P> {
+        match self {
+            PreserveUnknown::Known(_) => None,
+            PreserveUnknown::Unknown(x) => Some(x),
+        }
+    }
+
+    /// Unwraps the known value,
+    /// returning the serialized representation if it's unknown.
+    #[inline]
+    pub fn into_known(self) -> Result<NE, P> {
+        match self {
+            PreserveUnknown::Known(x) => Ok(x),
+            PreserveUnknown::Unknown(x) => Err(x),
+        }
+    }
+}
+
+impl<E, S, I, P> PreserveUnknown<NonExhaustive<E, S, I>, P> {
+    /// Deserializes a `NonExhaustive` from `proxy`,
+    /// keeping the proxy in the `Unknown` variant if that fails.
+    pub fn from_proxy<'borr>(proxy: P) -> Self
+    where
+        I: DeserializeEnum<'borr, NonExhaustive<E, S, I>, Proxy = P>,
+        P: Clone,
+    {
+        match I::deserialize_enum(proxy.clone()) {
+            Ok(x) => PreserveUnknown::Known(x),
+            Err(_) => PreserveUnknown::Unknown(proxy),
+        }
+    }
+
+    /// Attempts to deserialize the unknown value again,
+    /// for when this is passed to a library that may know about it.
+    pub fn resolve<'borr>(self) -> Self
+    where
+        I: DeserializeEnum<'borr, NonExhaustive<E, S, I>, Proxy = P>,
+        P: Clone,
+    {
+        match self {
+            PreserveUnknown::Known(x) => PreserveUnknown::Known(x),
+            PreserveUnknown::Unknown(proxy) => Self::from_proxy(proxy),
+        }
+    }
+}
+
+impl<NE, P> From<NE> for PreserveUnknown<NE, P> {
+    #[inline]
+    fn from(x: NE) -> Self {
+        PreserveUnknown::Known(x)
+    }
+}
+
+/// Serializes the known value,
+/// or the serialized representation of the unknown value.
+impl<NE, P> Serialize for PreserveUnknown<NE, P>
+where
+    NE: Serialize,
+    P: Serialize,
+{
+    fn serialize<Z>(&self, serializer: Z) -> Result<Z::Ok, Z::Error>
+    where
+        Z: Serializer,
+    {
+        match self {
+            PreserveUnknown::Known(x) => x.serialize(serializer),
+            PreserveUnknown::Unknown(x) => x.serialize(serializer),
+        }
+    }
+}
+
+/// Deserializes the proxy,then deserializes it into a `NonExhaustive<_>`
+/// with `<I as DeserializeEnum>::deserialize_enum`,
+/// keeping the proxy if that fails.
+impl<'de, E, S, I, P> Deserialize<'de> for PreserveUnknown<NonExhaustive<E, S, I>, P>
+where
+    I: DeserializeEnum<'de, NonExhaustive<E, S, I>, Proxy = P>,
+    P: Deserialize<'de> + Clone,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        P::deserialize(deserializer).map(Self::from_proxy)
+    }
+}
+
+#[cfg(all(test, not(feature = "only_new_tests")))]
+mod tests {
+    use super::*;
+
+    use crate::{
+        nonexhaustive_enum::examples::{command_a, command_b},
+        std_types::{RBoxError, RString},
+    };
+
+    type OldFoo = command_a::Foo_NE;
+    type NewFoo = command_b::Foo_NE;
+
+    impl DeserializeEnum<'_, OldFoo> for command_a::Foo_Interface {
+        type Proxy = RString;
+
+        fn deserialize_enum(s: RString) -> Result<OldFoo, RBoxError> {
+            let variant = match (s.as_str(), s.strip_prefix('B')) {
+                ("A", _) => command_a::Foo::A,
+                (_, Some(x)) => command_a::Foo::B(x.parse().map_err(RBoxError::new)?),
+                _ => return Err(RBoxError::from_fmt(&s)),
+            };
+            Ok(NonExhaustive::new(variant))
+        }
+    }
+
+    impl DeserializeEnum<'_, NewFoo> for command_b::Foo_Interface {
+        type Proxy = RString;
+
+        fn deserialize_enum(s: RString) -> Result<NewFoo, RBoxError> {
+            match s.as_str() {
+                "C" => Ok(NonExhaustive::new(command_b::Foo::C)),
+                _ => Err(RBoxError::from_fmt(&s)),
+            }
+        }
+    }
+
+    #[test]
+    fn known_and_unknown() {
+        let known = PreserveUnknown::<OldFoo, RString>::from_proxy("B10".into());
+        assert!(known.is_known());
+        assert!(!known.is_unknown());
+        assert_eq!(known.as_known().unwrap(), &command_a::Foo::B(10));
+        assert_eq!(known.as_unknown(), None);
+
+        let unknown = PreserveUnknown::<OldFoo, RString>::from_proxy("C".into());
+        assert!(!unknown.is_known());
+        assert!(unknown.is_unknown());
+        assert_eq!(unknown.as_known(), None);
+        assert_eq!(unknown.as_unknown().unwrap(), "C");
+        assert_eq!(unknown.clone().into_known().unwrap_err(), "C");
+
+        let unknown = PreserveUnknown::<NewFoo, RString>::Unknown(RString::from("C"));
+        assert_eq!(unknown.resolve().into_known().unwrap(), command_b::Foo::C,);
+
+        let mut known =
+            PreserveUnknown::<OldFoo, RString>::from(NonExhaustive::new(command_a::Foo::A));
+        *known.as_known_mut().unwrap() = NonExhaustive::new(command_a::Foo::B(3));
+        assert_eq!(known.into_known().unwrap(), command_a::Foo::B(3));
+    }
+
+    #[test]
+    fn serde_round_trip() {
+        use crate::nonexhaustive_enum::examples::command_serde::{Foo, Foo_NE};
+
+        let json = serde_json::to_string(&Foo_NE::new(Foo::D {
+            name: "hello".into(),
+        }))
+        .unwrap();
+
+        let known = serde_json::from_str::<PreserveUnknown<Foo_NE, RString>>(&json).unwrap();
+        assert_eq!(
+            known.as_known().unwrap(),
+            &Foo::D {
+                name: "hello".into()
+            },
+        );
+        assert_eq!(serde_json::to_string(&known).unwrap(), json);
+
+        let json = r#""{\"E\":{\"value\":[3,5,8]}}""#;
+
+        let unknown = serde_json::from_str::<PreserveUnknown<Foo_NE, RString>>(json).unwrap();
+        assert_eq!(unknown.as_unknown().unwrap(), r#"{"E":{"value":[3,5,8]}}"#);
+        assert_eq!(serde_json::to_string(&unknown).unwrap(), json);
+
+        assert!(serde_json::from_str::<Foo_NE>(json).is_err());
+    }
+}
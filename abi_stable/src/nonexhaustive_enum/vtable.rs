@@ -49,6 +49,23 @@ pub trait GetVTable<S, I>: Sized {
     /// The vtable
     const VTABLE: NonExhaustiveVtable_Ref<Self, S, I> =
         NonExhaustiveVtable_Ref(Self::VTABLE_WM.as_prefix());
+
+    #[doc(hidden)]
+    const BOXED_VTABLE_VAL: NonExhaustiveVtable<Self, S, I> = NonExhaustiveVtable {
+        _sabi_drop: alt_c_functions::drop_boxed_impl::<Self>,
+        _sabi_is_boxed: true,
+        ..Self::VTABLE_VAL
+    };
+
+    staticref! {
+        #[doc(hidden)]
+        const BOXED_VTABLE_WM: WithMetadata<NonExhaustiveVtable<Self,S,I>> =
+            WithMetadata::new(Self::BOXED_VTABLE_VAL)
+    }
+
+    /// The vtable for when the enum is stored in the heap.
+    const BOXED_VTABLE: NonExhaustiveVtable_Ref<Self, S, I> =
+        NonExhaustiveVtable_Ref(Self::BOXED_VTABLE_WM.as_prefix());
 }
 
 /// The vtable for NonExhaustive.
@@ -116,6 +133,20 @@ pub struct NonExhaustiveVtable<E, S, I> {
     #[sabi(last_prefix_field)]
     pub(crate) _sabi_hash:
         Option<unsafe extern "C" fn(RRef<'_, ErasedObject>, trait_objects::HasherObject<'_>)>,
+
+    /// Whether the enum is stored in the heap,
+    /// because it doesn't fit in a [`HeapFallback`](crate::nonexhaustive_enum::HeapFallback)
+    /// storage.
+    ///
+    /// Its accessor returns an `Option` so that it can be a `const fn`.
+    #[sabi(missing_field(option))]
+    pub(crate) _sabi_is_boxed: bool,
+
+    /// The size of the enum in the version of the library that created this vtable.
+    pub(crate) _sabi_enum_size: usize,
+
+    /// The alignment of the enum in the version of the library that created this vtable.
+    pub(crate) _sabi_enum_align: usize,
 }
 
 unsafe impl<E, S, I> Sync for NonExhaustiveVtable<E, S, I> {}
@@ -152,6 +183,9 @@ where
         _sabi_partial_cmp: <I::PartialOrd as InitPartialOrdField<E, S, I>>::VALUE,
         _sabi_cmp: <I::Ord as InitOrdField<E, S, I>>::VALUE,
         _sabi_hash: <I::Hash as InitHashField<E, S, I>>::VALUE,
+        _sabi_is_boxed: false,
+        _sabi_enum_size: std::mem::size_of::<E>(),
+        _sabi_enum_align: std::mem::align_of::<E>(),
     };
 }
 
@@ -161,6 +195,12 @@ type UnerasedSerializeFn<E, I> =
     ) -> RResult<<I as GetSerializeEnumProxy<E>>::ProxyType, RBoxError>;
 
 impl<E, S, I> NonExhaustiveVtable_Ref<E, S, I> {
+    /// Whether the enum is stored in the heap,
+    /// false for vtables from versions that don't have the field.
+    pub(crate) const fn is_boxed(self) -> bool {
+        matches!(self._sabi_is_boxed(), Some(true))
+    }
+
     pub(crate) fn serialize(self) -> UnerasedSerializeFn<E, I>
     where
         I: InterfaceType<Serialize = Implemented<trait_marker::Serialize>>,
@@ -467,7 +467,7 @@ assert!(!IS_CARD.contains(&'B'));
 ### Cloning an `RArc`-using trait object.
 
 Because of a quirk of how `#[sabi_trait]` trait objects work,
-trait objects that use [`RArc`] can only be `.clone()`d if they 
+trait objects that use [`RArc`] (or [`RRc`]) can only be `.clone()`d if they 
 have a `Clone` supertrait.
 To work around this, you can use the 
 [`RObject::shallow_clone`] /[`DynTrait::shallow_clone`] methods.
@@ -511,6 +511,7 @@ impl Foo for SomeDay {}
 [`RBox<()>`]: ./std_types/struct.RBox.html
 [`RArc<()>`]: ./std_types/struct.RArc.html
 [`RArc`]: ./std_types/struct.RArc.html
+[`RRc`]: ./std_types/struct.RRc.html
 [`RRef<'_, ()>`]: ./sabi_types/struct.RRef.html
 [`RMut<'_, ()>`]: ./sabi_types/struct.RMut.html
 
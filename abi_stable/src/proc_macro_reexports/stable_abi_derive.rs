@@ -222,6 +222,9 @@ and won't be usable afterwards.
 
 - `Error`: corresponds to `std::error::Error` .
 
+- `Stream`: corresponds to `futures_core::Stream`,
+    this type will also have to implement [`abi_stable::erased_types::StreamItem`].
+
 <br>
 Examples:
 
@@ -369,6 +372,7 @@ Panics if the field doesn't exist, with an informative error message.
 `#[sabi(missing_field(option))]`<br>
 Returns None if the field doesn't exist, Some(fieldvalue) if it does.
 This is the default.
+The accessors of fields with this setting are `const fn`s.
 
 `#[sabi(missing_field(with = somefunction))]`<br>
 Returns `somefunction()` if the field doesn't exist.
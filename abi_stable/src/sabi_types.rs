@@ -2,13 +2,17 @@
 
 pub mod bitarray;
 mod constructor;
+pub mod executor;
 mod ignored_wrapper;
 mod late_static_ref;
 mod maybe_cmp;
 mod move_ptr;
 mod nul_str;
+mod once_cell;
 mod rmut;
+mod rfuture;
 mod rref;
+mod rstream;
 pub mod rsmallbox;
 mod static_ref;
 pub mod version;
@@ -16,13 +20,17 @@ pub mod version;
 pub use self::{
     bitarray::BitArray64,
     constructor::{Constructor, ConstructorOrValue},
+    executor::RExecutor,
     ignored_wrapper::CmpIgnored,
     late_static_ref::LateStaticRef,
     maybe_cmp::MaybeCmp,
     move_ptr::MovePtr,
     nul_str::{NulStr, NulStrError},
+    once_cell::{RLazy, ROnceCell},
     rmut::RMut,
+    rfuture::RFuture,
     rref::RRef,
+    rstream::RStream,
     rsmallbox::RSmallBox,
     static_ref::StaticRef,
     version::{ParseVersionError, VersionNumber, VersionStrings},
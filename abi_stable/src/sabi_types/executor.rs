@@ -0,0 +1,291 @@
+//! Ffi-safe handles to async executors,
+//! which allow dynamic libraries to spawn tasks on the executor of the host.
+//!
+//! The host constructs an [`RExecutor`] from its executor
+//! (eg: [`TokioExecutor`](crate::external_types::tokio::TokioExecutor),
+//! with the "tokio" feature),
+//! and passes it to the dynamic libraries it loads,
+//! which don't need to depend on the same runtime crate (or version of it) as the host.
+//!
+//! # Example
+//!
+//! ```rust
+//! use abi_stable::sabi_types::{
+//!     executor::{RExecutor, ThreadPoolExecutor},
+//!     RFuture,
+//! };
+//!
+//! use std::time::Duration;
+//!
+//! // This would be an `extern "C"` function exported by a dynamic library.
+//! extern "C" fn plugin_entry(executor: &RExecutor) -> RFuture<'static, u64> {
+//!     let executor = executor.clone();
+//!     RFuture::new(async move {
+//!         executor.sleep(Duration::from_millis(1)).await;
+//!         executor.spawn_blocking(|| (1..=10).product::<u64>()).await
+//!     })
+//! }
+//!
+//! let executor = RExecutor::new(ThreadPoolExecutor::new(2));
+//!
+//! let (sender, receiver) = std::sync::mpsc::channel();
+//! let fut = plugin_entry(&executor);
+//! executor.spawn(async move { sender.send(fut.await).unwrap() });
+//!
+//! assert_eq!(receiver.recv().unwrap(), 3628800);
+//!
+//! ```
+//!
+
+use std::{
+    fmt::{self, Debug},
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+    time::Duration,
+};
+
+use crate::{
+    sabi_trait,
+    sabi_trait::prelude::TD_Opaque,
+    sabi_types::RFuture,
+    std_types::{RArc, RBox, RDuration},
+};
+
+mod thread_pool;
+
+#[cfg(all(test, not(feature = "only_new_tests")))]
+mod tests;
+
+pub use self::thread_pool::ThreadPoolExecutor;
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// The operations that an executor must provide to be wrapped in an [`RExecutor`].
+///
+/// Panics inside of these methods abort the process,
+/// since they can't unwind across the ffi boundary.
+#[sabi_trait]
+pub trait Executor: Send + Sync {
+    /// Spawns a task that drives `future` to completion.
+    fn spawn(&self, future: RFuture<'static, ()>);
+
+    /// Runs `task` in a thread where blocking is acceptable.
+    fn spawn_blocking(&self, task: BlockingTask_TO<'static, RBox<()>>);
+
+    /// Returns a future that completes once `duration` has elapsed.
+    #[sabi(last_prefix_field)]
+    fn sleep(&self, duration: RDuration) -> RFuture<'static, ()>;
+}
+
+/// A type-erased closure that's passed to [`Executor::spawn_blocking`].
+#[sabi_trait]
+pub trait BlockingTask: Send {
+    /// Runs the closure.
+    #[sabi(last_prefix_field)]
+    fn run(self);
+}
+
+struct BlockingFn<F>(F);
+
+impl<F> BlockingTask for BlockingFn<F>
+where
+    F: FnOnce() + Send,
+{
+    fn run(self) {
+        (self.0)()
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// An ffi-safe handle to an executor,
+/// that can be cheaply cloned and passed to dynamic libraries.
+///
+/// # Example
+///
+/// ```rust
+/// use abi_stable::sabi_types::executor::{RExecutor, ThreadPoolExecutor};
+///
+/// use std::sync::mpsc;
+///
+/// let executor = RExecutor::new(ThreadPoolExecutor::new(1));
+///
+/// let (sender, receiver) = mpsc::channel();
+///
+/// for i in 0..3 {
+///     let sender = sender.clone();
+///     executor.spawn(async move { sender.send(i * 2).unwrap() });
+/// }
+///
+/// let mut list = receiver.iter().take(3).collect::<Vec<u32>>();
+/// list.sort();
+/// assert_eq!(list, [0, 2, 4]);
+///
+/// ```
+#[repr(transparent)]
+#[derive(StableAbi)]
+pub struct RExecutor {
+    inner: Executor_TO<'static, RArc<()>>,
+}
+
+impl RExecutor {
+    /// Constructs an `RExecutor` from an [`Executor`].
+    pub fn new<E>(executor: E) -> Self
+    where
+        E: Executor + 'static,
+    {
+        Self {
+            inner: Executor_TO::from_ptr(RArc::new(executor), TD_Opaque),
+        }
+    }
+
+    /// Spawns a task that drives `future` to completion.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use abi_stable::sabi_types::executor::{RExecutor, ThreadPoolExecutor};
+    ///
+    /// let executor = RExecutor::new(ThreadPoolExecutor::new(1));
+    ///
+    /// let (sender, receiver) = std::sync::mpsc::channel();
+    /// executor.spawn(async move { sender.send("hello").unwrap() });
+    ///
+    /// assert_eq!(receiver.recv().unwrap(), "hello");
+    ///
+    /// ```
+    pub fn spawn<F>(&self, future: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        self.inner.spawn(RFuture::new(future));
+    }
+
+    /// Runs `f` in a thread where blocking is acceptable,
+    /// returning a future that resolves to the return value of `f`.
+    ///
+    /// # Panics
+    ///
+    /// The returned future panics if the executor drops `f` without calling it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use abi_stable::sabi_types::executor::{RExecutor, ThreadPoolExecutor};
+    ///
+    /// let executor = RExecutor::new(ThreadPoolExecutor::new(1));
+    ///
+    /// let fut = executor.spawn_blocking(|| std::env::var("NOT_A_REAL_VAR").is_ok());
+    ///
+    /// let (sender, receiver) = std::sync::mpsc::channel();
+    /// executor.spawn(async move { sender.send(fut.await).unwrap() });
+    ///
+    /// assert_eq!(receiver.recv().unwrap(), false);
+    ///
+    /// ```
+    pub fn spawn_blocking<F, R>(&self, f: F) -> impl Future<Output = R> + Send + 'static
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        let (sender, receiver) = oneshot();
+        let task = BlockingFn(move || sender.send(f()));
+        self.inner
+            .spawn_blocking(BlockingTask_TO::from_value(task, TD_Opaque));
+        receiver
+    }
+
+    /// Returns a future that completes once `duration` has elapsed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use abi_stable::sabi_types::executor::{RExecutor, ThreadPoolExecutor};
+    ///
+    /// use std::time::{Duration, Instant};
+    ///
+    /// let executor = RExecutor::new(ThreadPoolExecutor::new(1));
+    ///
+    /// let start = Instant::now();
+    /// let sleep = executor.sleep(Duration::from_millis(10));
+    ///
+    /// let (sender, receiver) = std::sync::mpsc::channel();
+    /// executor.spawn(async move { sender.send(sleep.await).unwrap() });
+    ///
+    /// receiver.recv().unwrap();
+    /// assert!(start.elapsed() >= Duration::from_millis(10));
+    ///
+    /// ```
+    pub fn sleep(&self, duration: Duration) -> RFuture<'static, ()> {
+        self.inner.sleep(duration.into())
+    }
+}
+
+impl Clone for RExecutor {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Executor_TO::from_sabi(self.inner.obj.shallow_clone()),
+        }
+    }
+}
+
+impl Debug for RExecutor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("RExecutor{..}")
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+struct OneshotState<T> {
+    value: Option<T>,
+    waker: Option<Waker>,
+    sender_dropped: bool,
+}
+
+struct OneshotSender<T>(Arc<Mutex<OneshotState<T>>>);
+
+struct OneshotReceiver<T>(Arc<Mutex<OneshotState<T>>>);
+
+fn oneshot<T>() -> (OneshotSender<T>, OneshotReceiver<T>) {
+    let state = Arc::new(Mutex::new(OneshotState {
+        value: None,
+        waker: None,
+        sender_dropped: false,
+    }));
+    (OneshotSender(state.clone()), OneshotReceiver(state))
+}
+
+impl<T> OneshotSender<T> {
+    fn send(self, value: T) {
+        self.0.lock().unwrap().value = Some(value);
+    }
+}
+
+impl<T> Drop for OneshotSender<T> {
+    fn drop(&mut self) {
+        let mut state = self.0.lock().unwrap();
+        state.sender_dropped = true;
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> Future for OneshotReceiver<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        let mut state = self.0.lock().unwrap();
+        if let Some(value) = state.value.take() {
+            Poll::Ready(value)
+        } else if state.sender_dropped {
+            panic!("the blocking task was dropped without being run");
+        } else {
+            state.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use super::*;
+
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc,
+    },
+    time::Instant,
+};
+
+use crate::test_utils::block_on;
+
+fn pool(threads: usize) -> RExecutor {
+    RExecutor::new(ThreadPoolExecutor::new(threads))
+}
+
+#[test]
+fn spawn_many() {
+    let executor = pool(4);
+    let counter = Arc::new(AtomicUsize::new(0));
+    let (sender, receiver) = mpsc::channel();
+
+    for _ in 0..100 {
+        let counter = counter.clone();
+        let sender = sender.clone();
+        executor.spawn(async move {
+            counter.fetch_add(1, Ordering::SeqCst);
+            sender.send(()).unwrap();
+        });
+    }
+
+    receiver.iter().take(100).for_each(drop);
+    assert_eq!(counter.load(Ordering::SeqCst), 100);
+}
+
+#[test]
+fn spawned_task_is_woken() {
+    let executor = pool(1);
+    let (sender, receiver) = mpsc::channel();
+
+    let blocking = executor.spawn_blocking(|| 10);
+    let sleep = executor.sleep(Duration::from_millis(5));
+    executor.spawn(async move {
+        sleep.await;
+        sender.send(blocking.await * 2).unwrap();
+    });
+
+    assert_eq!(receiver.recv().unwrap(), 20);
+}
+
+#[test]
+fn spawn_blocking_outside_executor() {
+    let executor = pool(1);
+
+    let fut = executor.spawn_blocking(|| {
+        std::thread::sleep(Duration::from_millis(5));
+        "done"
+    });
+    assert_eq!(block_on(fut), "done");
+}
+
+#[test]
+fn sleep_duration() {
+    let executor = pool(1);
+
+    let start = Instant::now();
+    block_on(executor.sleep(Duration::from_millis(20)));
+    assert!(start.elapsed() >= Duration::from_millis(20));
+}
+
+#[test]
+fn cloned_executor() {
+    let executor = pool(2);
+    let (sender, receiver) = mpsc::channel();
+
+    let executor2 = executor.clone();
+    drop(executor);
+
+    let executor3 = executor2.clone();
+    executor2.spawn(async move {
+        let value = executor3.spawn_blocking(|| 3).await;
+        sender.send(value).unwrap();
+    });
+
+    assert_eq!(receiver.recv().unwrap(), 3);
+}
+
+#[test]
+fn dropping_pool_drops_pending_tasks() {
+    let arc = Arc::new(());
+    {
+        let executor = pool(2);
+        let arc = arc.clone();
+        executor.spawn(async move {
+            let _arc = arc;
+            std::future::pending::<()>().await;
+        });
+    }
+
+    let start = Instant::now();
+    while Arc::strong_count(&arc) != 1 {
+        assert!(start.elapsed() < Duration::from_secs(10));
+        std::thread::sleep(Duration::from_millis(1));
+    }
+}
+
+#[test]
+#[should_panic]
+fn dropped_blocking_task() {
+    struct DropsTasks;
+
+    impl Executor for DropsTasks {
+        fn spawn(&self, _: RFuture<'static, ()>) {}
+
+        fn spawn_blocking(&self, _: BlockingTask_TO<'static, RBox<()>>) {}
+
+        fn sleep(&self, _: RDuration) -> RFuture<'static, ()> {
+            RFuture::new(async {})
+        }
+    }
+
+    let executor = RExecutor::new(DropsTasks);
+    block_on(executor.spawn_blocking(|| ()));
+}
@@ -0,0 +1,232 @@
+use std::{
+    collections::VecDeque,
+    fmt::{self, Debug},
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Condvar, Mutex, Weak},
+    task::{Context, Poll, Wake, Waker},
+    thread,
+    time::Duration,
+};
+
+use super::{BlockingTask_TO, Executor};
+
+use crate::{
+    sabi_types::RFuture,
+    std_types::{RBox, RDuration},
+};
+
+/// A simple thread pool executor,
+/// intended as a reference implementation of [`Executor`] for tests and examples.
+///
+/// Tasks are polled by a fixed number of worker threads,
+/// while blocking closures and timers each get a thread of their own.
+///
+/// Dropping the `ThreadPoolExecutor` stops the worker threads,
+/// dropping all the tasks that haven't completed.
+///
+/// Tasks only hold a weak reference to the thread pool,
+/// but a task that owns an [`RExecutor`](super::RExecutor) clone
+/// keeps the executor alive until the task is dropped,
+/// so the tasks that never complete must not own a clone of their executor.
+///
+/// # Example
+///
+/// ```rust
+/// use abi_stable::sabi_types::executor::{RExecutor, ThreadPoolExecutor};
+///
+/// use std::time::Duration;
+///
+/// let executor = RExecutor::new(ThreadPoolExecutor::new(4));
+///
+/// let (sender, receiver) = std::sync::mpsc::channel();
+///
+/// for i in 0..4u64 {
+///     let sender = sender.clone();
+///     let executor2 = executor.clone();
+///     executor.spawn(async move {
+///         executor2.sleep(Duration::from_millis(i * 10)).await;
+///         sender.send(i).unwrap();
+///     });
+/// }
+///
+/// assert_eq!(receiver.iter().take(4).collect::<Vec<u64>>(), [0, 1, 2, 3]);
+///
+/// ```
+pub struct ThreadPoolExecutor {
+    shared: Arc<Shared>,
+}
+
+struct Shared {
+    queue: Mutex<Queue>,
+    condvar: Condvar,
+}
+
+struct Queue {
+    tasks: VecDeque<Arc<Task>>,
+    shutdown: bool,
+}
+
+struct Task {
+    future: Mutex<Option<RFuture<'static, ()>>>,
+    // This is a weak reference so that queued tasks don't keep
+    // the worker threads alive after the executor is dropped.
+    shared: Weak<Shared>,
+}
+
+impl ThreadPoolExecutor {
+    /// Constructs a `ThreadPoolExecutor` that polls tasks with `threads` worker threads.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `threads` is zero.
+    pub fn new(threads: usize) -> Self {
+        assert_ne!(threads, 0, "a ThreadPoolExecutor needs at least one thread");
+
+        let shared = Arc::new(Shared {
+            queue: Mutex::new(Queue {
+                tasks: VecDeque::new(),
+                shutdown: false,
+            }),
+            condvar: Condvar::new(),
+        });
+
+        for _ in 0..threads {
+            let shared = shared.clone();
+            thread::spawn(move || shared.run_worker());
+        }
+
+        Self { shared }
+    }
+}
+
+impl Shared {
+    fn push(&self, task: Arc<Task>) {
+        let mut queue = self.queue.lock().unwrap();
+        if !queue.shutdown {
+            queue.tasks.push_back(task);
+            self.condvar.notify_one();
+        }
+    }
+
+    fn run_worker(&self) {
+        loop {
+            let task = {
+                let mut queue = self.queue.lock().unwrap();
+                loop {
+                    if queue.shutdown {
+                        // Dropping the tasks outside the lock,
+                        // since dropping them could wake up other tasks.
+                        let tasks = std::mem::take(&mut queue.tasks);
+                        drop(queue);
+                        drop(tasks);
+                        return;
+                    } else if let Some(task) = queue.tasks.pop_front() {
+                        break task;
+                    }
+                    queue = self.condvar.wait(queue).unwrap();
+                }
+            };
+            task.poll();
+        }
+    }
+}
+
+impl Task {
+    fn poll(self: Arc<Self>) {
+        let mut slot = self.future.lock().unwrap();
+        if let Some(future) = &mut *slot {
+            let waker = Waker::from(self.clone());
+            if future
+                .poll_unpin(&mut Context::from_waker(&waker))
+                .is_ready()
+            {
+                *slot = None;
+            }
+        }
+    }
+}
+
+impl Wake for Task {
+    fn wake(self: Arc<Self>) {
+        if let Some(shared) = self.shared.upgrade() {
+            shared.push(self);
+        }
+    }
+}
+
+impl Executor for ThreadPoolExecutor {
+    fn spawn(&self, future: RFuture<'static, ()>) {
+        self.shared.push(Arc::new(Task {
+            future: Mutex::new(Some(future)),
+            shared: Arc::downgrade(&self.shared),
+        }));
+    }
+
+    fn spawn_blocking(&self, task: BlockingTask_TO<'static, RBox<()>>) {
+        thread::spawn(move || task.run());
+    }
+
+    fn sleep(&self, duration: RDuration) -> RFuture<'static, ()> {
+        RFuture::new(Sleep::new(duration.into()))
+    }
+}
+
+impl Drop for ThreadPoolExecutor {
+    fn drop(&mut self) {
+        self.shared.queue.lock().unwrap().shutdown = true;
+        self.shared.condvar.notify_all();
+    }
+}
+
+impl Debug for ThreadPoolExecutor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("ThreadPoolExecutor{..}")
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+struct Sleep {
+    state: Arc<Mutex<SleepState>>,
+}
+
+struct SleepState {
+    elapsed: bool,
+    waker: Option<Waker>,
+}
+
+impl Sleep {
+    fn new(duration: Duration) -> Self {
+        let state = Arc::new(Mutex::new(SleepState {
+            elapsed: false,
+            waker: None,
+        }));
+
+        let thread_state = state.clone();
+        thread::spawn(move || {
+            thread::sleep(duration);
+            let mut state = thread_state.lock().unwrap();
+            state.elapsed = true;
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+        });
+
+        Self { state }
+    }
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let mut state = self.state.lock().unwrap();
+        if state.elapsed {
+            Poll::Ready(())
+        } else {
+            state.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
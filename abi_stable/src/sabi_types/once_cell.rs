@@ -0,0 +1,628 @@
+//! Lazily initialized cells, which own their value.
+
+use std::{
+    cell::{Cell, UnsafeCell},
+    fmt::{self, Debug},
+    mem::MaybeUninit,
+    ops::Deref,
+    panic::{self, AssertUnwindSafe},
+    ptr,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use crate::{
+    external_types::RMutex,
+    std_types::{RErr, ROk, RResult},
+};
+
+/// A cell which can be written to only once,
+/// the ffi-safe equivalent of `once_cell::sync::OnceCell`.
+///
+/// As opposed to [`LateStaticRef`](crate::sabi_types::LateStaticRef),
+/// this owns its value, and does not require it to be a `'static` reference.
+///
+/// Initialization uses the lock of the `ROnceCell`,
+/// which is locked with the functions of the dynamic library that constructed it,
+/// so that the value is initialized exactly once,
+/// even if the `ROnceCell` is shared between a library and the executable that loaded it.
+///
+/// # Example
+///
+/// This lazily computes a value stored in a prefix-type module.
+///
+/// ```
+/// use abi_stable::{
+///     prefix_type::WithMetadata,
+///     sabi_types::ROnceCell,
+///     std_types::RString,
+///     StableAbi,
+/// };
+///
+/// #[repr(C)]
+/// #[derive(StableAbi)]
+/// #[sabi(kind(Prefix))]
+/// pub struct Module {
+///     #[sabi(last_prefix_field)]
+///     pub greeting: &'static ROnceCell<RString>,
+/// }
+///
+/// static GREETING: ROnceCell<RString> = ROnceCell::new();
+///
+/// static MODULE: WithMetadata<Module> = WithMetadata::new(Module {
+///     greeting: &GREETING,
+/// });
+///
+/// let module = Module_Ref(MODULE.static_as_prefix());
+///
+/// assert_eq!(module.greeting().get(), None);
+///
+/// let greeting = module.greeting().get_or_init(|| RString::from("hello"));
+/// assert_eq!(greeting, "hello");
+///
+/// // The closure isn't called, because the cell was already initialized.
+/// let greeting = module.greeting().get_or_init(|| unreachable!());
+/// assert_eq!(greeting, "hello");
+///
+/// ```
+#[repr(C)]
+#[derive(StableAbi)]
+pub struct ROnceCell<T> {
+    initialized: AtomicBool,
+    lock: RMutex<()>,
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+#[allow(clippy::declare_interior_mutable_const)]
+const LOCK: RMutex<()> = RMutex::new(());
+
+unsafe impl<T: Send + Sync> Sync for ROnceCell<T> {}
+unsafe impl<T: Send> Send for ROnceCell<T> {}
+
+impl<T> ROnceCell<T> {
+    /// Constructs an uninitialized `ROnceCell`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::{sabi_types::ROnceCell, std_types::RVec};
+    ///
+    /// static CELL: ROnceCell<RVec<u32>> = ROnceCell::new();
+    ///
+    /// let cell = ROnceCell::<u32>::new();
+    ///
+    /// ```
+    pub const fn new() -> Self {
+        Self {
+            initialized: AtomicBool::new(false),
+            lock: LOCK,
+            value: UnsafeCell::new(MaybeUninit::uninit()),
+        }
+    }
+
+    /// Gets a reference to the value,
+    /// returning None if the cell is not initialized.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::sabi_types::ROnceCell;
+    ///
+    /// let cell = ROnceCell::new();
+    ///
+    /// assert_eq!(cell.get(), None);
+    ///
+    /// cell.get_or_init(|| 100);
+    /// assert_eq!(cell.get(), Some(&100));
+    ///
+    /// ```
+    pub fn get(&self) -> Option<&T> {
+        if self.initialized.load(Ordering::Acquire) {
+            unsafe { Some(&*(*self.value.get()).as_ptr()) }
+        } else {
+            None
+        }
+    }
+
+    /// Gets a mutable reference to the value,
+    /// returning None if the cell is not initialized.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::sabi_types::ROnceCell;
+    ///
+    /// let mut cell = ROnceCell::new();
+    ///
+    /// assert_eq!(cell.get_mut(), None);
+    ///
+    /// cell.get_or_init(|| 100);
+    /// *cell.get_mut().unwrap() += 1;
+    /// assert_eq!(cell.get(), Some(&101));
+    ///
+    /// ```
+    pub fn get_mut(&mut self) -> Option<&mut T> {
+        if *self.initialized.get_mut() {
+            unsafe { Some(&mut *self.value.get_mut().as_mut_ptr()) }
+        } else {
+            None
+        }
+    }
+
+    /// Initializes the cell with `value`,
+    /// returning it back in an `Err` if the cell was already initialized.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::sabi_types::ROnceCell;
+    ///
+    /// let cell = ROnceCell::new();
+    ///
+    /// assert_eq!(cell.set(3), Ok(()));
+    /// assert_eq!(cell.set(5), Err(5));
+    /// assert_eq!(cell.get(), Some(&3));
+    ///
+    /// ```
+    pub fn set(&self, value: T) -> Result<(), T> {
+        let mut value = Some(value);
+        self.get_or_init(|| value.take().unwrap());
+        match value {
+            Some(value) => Err(value),
+            None => Ok(()),
+        }
+    }
+
+    /// Gets a reference to the value,
+    /// initializing the cell with `initializer` if it wasn't already initialized.
+    ///
+    /// If `initializer` panics, the panic is propagated,
+    /// and the cell can be initialized later.
+    ///
+    /// Calling this recursively on the same cell from inside `initializer` deadlocks.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::{sabi_types::ROnceCell, std_types::RString};
+    ///
+    /// static CELL: ROnceCell<RString> = ROnceCell::new();
+    ///
+    /// assert_eq!(CELL.get_or_init(|| RString::from("foo")), "foo");
+    /// assert_eq!(CELL.get_or_init(|| RString::from("bar")), "foo");
+    ///
+    /// ```
+    pub fn get_or_init<F>(&self, initializer: F) -> &T
+    where
+        F: FnOnce() -> T,
+    {
+        match self.get_or_try_init(|| Ok::<T, std::convert::Infallible>(initializer())) {
+            Ok(value) => value,
+            Err(e) => match e {},
+        }
+    }
+
+    /// Gets a reference to the value,
+    /// initializing the cell with `initializer` if it wasn't already initialized.
+    ///
+    /// If `initializer` returns an `Err(...)` this returns the error and
+    /// allows the cell to be initialized later.
+    ///
+    /// If `initializer` panics, the panic is propagated,
+    /// and the cell can be initialized later.
+    ///
+    /// Calling this recursively on the same cell from inside `initializer` deadlocks.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::sabi_types::ROnceCell;
+    ///
+    /// let cell = ROnceCell::<u32>::new();
+    ///
+    /// assert_eq!(cell.get_or_try_init(|| "10a".parse::<u32>()).ok(), None);
+    /// assert_eq!(cell.get(), None);
+    ///
+    /// assert_eq!(cell.get_or_try_init(|| "10".parse::<u32>()), Ok(&10));
+    /// assert_eq!(cell.get_or_try_init(|| "20".parse::<u32>()), Ok(&10));
+    ///
+    /// ```
+    pub fn get_or_try_init<F, E>(&self, initializer: F) -> Result<&T, E>
+    where
+        F: FnOnce() -> Result<T, E>,
+    {
+        if let Some(value) = self.get() {
+            return Ok(value);
+        }
+
+        let guard_ = self.lock.lock();
+
+        if let Some(value) = self.get() {
+            return Ok(value);
+        }
+
+        let value = initializer()?;
+
+        unsafe {
+            (*self.value.get()).as_mut_ptr().write(value);
+        }
+        self.initialized.store(true, Ordering::Release);
+
+        drop(guard_);
+
+        unsafe { Ok(&*(*self.value.get()).as_ptr()) }
+    }
+
+    /// Takes the value out of the cell, leaving it uninitialized.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::sabi_types::ROnceCell;
+    ///
+    /// let mut cell = ROnceCell::new();
+    ///
+    /// assert_eq!(cell.take(), None);
+    ///
+    /// cell.get_or_init(|| 8);
+    /// assert_eq!(cell.take(), Some(8));
+    /// assert_eq!(cell.get(), None);
+    ///
+    /// ```
+    pub fn take(&mut self) -> Option<T> {
+        if *self.initialized.get_mut() {
+            *self.initialized.get_mut() = false;
+            unsafe { Some(ptr::read(self.value.get_mut().as_ptr())) }
+        } else {
+            None
+        }
+    }
+
+    /// Unwraps the cell, returning the value if it was initialized.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::sabi_types::ROnceCell;
+    ///
+    /// let cell = ROnceCell::new();
+    /// cell.get_or_init(|| "hello");
+    ///
+    /// assert_eq!(cell.into_inner(), Some("hello"));
+    ///
+    /// ```
+    pub fn into_inner(mut self) -> Option<T> {
+        self.take()
+    }
+}
+
+impl<T> Default for ROnceCell<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> From<T> for ROnceCell<T> {
+    fn from(value: T) -> Self {
+        Self {
+            initialized: AtomicBool::new(true),
+            lock: LOCK,
+            value: UnsafeCell::new(MaybeUninit::new(value)),
+        }
+    }
+}
+
+impl<T: Clone> Clone for ROnceCell<T> {
+    fn clone(&self) -> Self {
+        match self.get() {
+            Some(value) => Self::from(value.clone()),
+            None => Self::new(),
+        }
+    }
+}
+
+impl<T: Debug> Debug for ROnceCell<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.get() {
+            Some(value) => f.debug_tuple("ROnceCell").field(value).finish(),
+            None => f.write_str("ROnceCell(<uninit>)"),
+        }
+    }
+}
+
+impl<T> Drop for ROnceCell<T> {
+    fn drop(&mut self) {
+        if *self.initialized.get_mut() {
+            unsafe { ptr::drop_in_place(self.value.get_mut().as_mut_ptr()) }
+        }
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////
+
+/// A value which is initialized on the first access,
+/// the ffi-safe equivalent of `once_cell::sync::Lazy`.
+///
+/// This is an [`ROnceCell`] with the function used to initialize it.
+///
+/// # Initializer
+///
+/// The initializer is called through a function pointer stored in the `RLazy`
+/// by the dynamic library that constructed it,
+/// so that accessing the value from another dynamic library
+/// uses the code of the one that constructed the `RLazy`.
+///
+/// The layout of `F` is not checked when loading a library.
+///
+/// # Panics
+///
+/// If the initializer panics, the `RLazy` is poisoned,
+/// and this and any future access panics.
+///
+/// # Example
+///
+/// ```
+/// use abi_stable::{
+///     sabi_types::RLazy,
+///     std_types::{RString, RVec},
+/// };
+///
+/// static NAMES: RLazy<RVec<RString>> = RLazy::new(|| {
+///     ["foo", "bar", "baz"].iter().map(|&s| RString::from(s)).collect()
+/// });
+///
+/// assert_eq!(NAMES.len(), 3);
+/// assert_eq!(NAMES[1], "bar");
+///
+/// ```
+#[repr(C)]
+#[derive(StableAbi)]
+#[sabi(unsafe_unconstrained(F))]
+pub struct RLazy<T, F = fn() -> T> {
+    cell: ROnceCell<T>,
+    #[sabi(unsafe_opaque_field)]
+    init: Cell<Option<F>>,
+    initialize: unsafe extern "C" fn(&RLazy<T, F>) -> RResult<(), ()>,
+}
+
+unsafe impl<T, F: Send> Sync for RLazy<T, F> where ROnceCell<T>: Sync {}
+
+impl<T, F> RLazy<T, F> {
+    /// Constructs an `RLazy` which is initialized with `init` on the first access.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::{sabi_types::RLazy, std_types::RString};
+    ///
+    /// static GREETING: RLazy<RString> = RLazy::new(|| RString::from("hello"));
+    ///
+    /// let lazy = RLazy::new(|| 10);
+    ///
+    /// assert_eq!(*GREETING, "hello");
+    /// assert_eq!(*lazy, 10);
+    ///
+    /// ```
+    pub const fn new(init: F) -> Self
+    where
+        F: FnOnce() -> T,
+    {
+        Self {
+            cell: ROnceCell::new(),
+            init: Cell::new(Some(init)),
+            initialize: initialize::<T, F>,
+        }
+    }
+
+    /// Gets a reference to the value, initializing it if it wasn't already.
+    ///
+    /// This is equivalent to dereferencing the `RLazy`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the initializer panics, or if it previously panicked.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::sabi_types::RLazy;
+    ///
+    /// let lazy = RLazy::new(|| vec![3, 5, 8]);
+    ///
+    /// assert_eq!(RLazy::force(&lazy), &[3, 5, 8]);
+    ///
+    /// ```
+    pub fn force(this: &Self) -> &T {
+        if let Some(value) = this.cell.get() {
+            return value;
+        }
+        if let RErr(()) = unsafe { (this.initialize)(this) } {
+            panic!("RLazy instance has previously been poisoned");
+        }
+        this.cell.get().expect("RLazy was not initialized")
+    }
+
+    /// Gets a reference to the value, returning None if it wasn't initialized yet.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::sabi_types::RLazy;
+    ///
+    /// let lazy = RLazy::new(|| 3);
+    ///
+    /// assert_eq!(RLazy::get(&lazy), None);
+    ///
+    /// RLazy::force(&lazy);
+    /// assert_eq!(RLazy::get(&lazy), Some(&3));
+    ///
+    /// ```
+    pub fn get(this: &Self) -> Option<&T> {
+        this.cell.get()
+    }
+
+    /// Unwraps the `RLazy`,
+    /// returning the value if it was initialized, otherwise the initializer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the initializer previously panicked.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::sabi_types::RLazy;
+    ///
+    /// let lazy = RLazy::new(|| 3);
+    /// assert_eq!(RLazy::into_value(lazy).unwrap_err()(), 3);
+    ///
+    /// let lazy = RLazy::new(|| 5);
+    /// RLazy::force(&lazy);
+    /// assert_eq!(RLazy::into_value(lazy).ok(), Some(5));
+    ///
+    /// ```
+    pub fn into_value(this: Self) -> Result<T, F> {
+        let Self { cell, init, .. } = this;
+        match cell.into_inner() {
+            Some(value) => Ok(value),
+            None => Err(init
+                .into_inner()
+                .expect("RLazy instance has previously been poisoned")),
+        }
+    }
+}
+
+impl<T, F> Deref for RLazy<T, F> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        Self::force(self)
+    }
+}
+
+impl<T: Default> Default for RLazy<T> {
+    fn default() -> Self {
+        Self::new(T::default)
+    }
+}
+
+impl<T: Debug, F> Debug for RLazy<T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RLazy")
+            .field("cell", &self.cell)
+            .finish_non_exhaustive()
+    }
+}
+
+unsafe extern "C" fn initialize<T, F>(this: &RLazy<T, F>) -> RResult<(), ()>
+where
+    F: FnOnce() -> T,
+{
+    let res = panic::catch_unwind(AssertUnwindSafe(|| {
+        this.cell.get_or_init(|| match this.init.take() {
+            Some(init) => init(),
+            None => panic!("RLazy instance has previously been poisoned"),
+        });
+    }));
+    match res {
+        Ok { .. } => ROk(()),
+        Err { .. } => RErr(()),
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////
+
+#[cfg(all(test, not(feature = "only_new_tests")))]
+mod tests {
+    use super::*;
+
+    use std::{panic::catch_unwind, sync::atomic::AtomicUsize};
+
+    use crossbeam_utils::thread::scope as scoped_thread;
+
+    use crate::std_types::RString;
+
+    #[test]
+    fn init_after_panic_or_error() {
+        let cell = ROnceCell::<RString>::new();
+
+        let caught = catch_unwind(AssertUnwindSafe(|| {
+            cell.get_or_init(|| panic!());
+        }));
+        assert!(caught.is_err());
+        assert_eq!(cell.get(), None);
+
+        assert_eq!(cell.get_or_try_init(|| Err(10)), Err(10));
+        assert_eq!(cell.get(), None);
+
+        assert_eq!(
+            cell.get_or_try_init(|| Ok::<_, i32>(RString::from("foo"))),
+            Ok(&RString::from("foo"))
+        );
+        assert_eq!(
+            cell.get_or_try_init(|| -> Result<_, i32> { panic!("this should not run") }),
+            Ok(&RString::from("foo"))
+        );
+        assert_eq!(cell.set(RString::from("bar")), Err(RString::from("bar")));
+    }
+
+    #[test]
+    fn drops_value() {
+        let counter = AtomicUsize::new(0);
+
+        struct OnDrop<'a>(&'a AtomicUsize);
+
+        impl Drop for OnDrop<'_> {
+            fn drop(&mut self) {
+                self.0.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        drop(ROnceCell::<OnDrop<'_>>::new());
+        assert_eq!(counter.load(Ordering::SeqCst), 0);
+
+        let cell = ROnceCell::new();
+        assert!(cell.set(OnDrop(&counter)).is_ok());
+        assert!(cell.set(OnDrop(&counter)).is_err());
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+        drop(cell);
+        assert_eq!(counter.load(Ordering::SeqCst), 2);
+
+        let mut cell = ROnceCell::from(OnDrop(&counter));
+        let value = cell.take();
+        drop(cell);
+        assert_eq!(counter.load(Ordering::SeqCst), 2);
+        drop(value);
+        assert_eq!(counter.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    #[cfg(not(all(miri, target_os = "windows")))]
+    fn initialized_once_across_threads() {
+        let calls = AtomicUsize::new(0);
+
+        let lazy = RLazy::new(|| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            RString::from("hello")
+        });
+
+        scoped_thread(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|_| assert_eq!(*lazy, "hello"));
+            }
+        })
+        .unwrap();
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn poisoned_lazy() {
+        let lazy = RLazy::<u32, _>::new(|| panic!());
+
+        for _ in 0..2 {
+            let caught = catch_unwind(AssertUnwindSafe(|| *lazy));
+            assert!(caught.is_err());
+        }
+        assert_eq!(RLazy::get(&lazy), None);
+    }
+}
@@ -0,0 +1,258 @@
+//! Contains the `RFuture<'a, T>` type.
+
+use std::{
+    fmt::{self, Debug},
+    future::Future,
+    marker::PhantomData,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use crate::{
+    marker_type::ErasedObject,
+    prefix_type::WithMetadata,
+    sabi_types::RMut,
+    std_types::{RPoll, RWaker},
+};
+
+/// An ffi-safe, type-erased, `Send` future,
+/// equivalent to `Pin<Box<dyn Future<Output = T> + Send + 'a>>`.
+///
+/// This can be polled across the ffi boundary,
+/// passing an [`RWaker`](crate::std_types::RWaker) to the erased future.
+///
+/// # Example
+///
+/// ```rust
+/// use abi_stable::sabi_types::RFuture;
+///
+/// use std::{
+///     future::Future,
+///     pin::Pin,
+///     task::{Context, Poll},
+/// };
+///
+/// let mut fut: RFuture<'static, u32> = RFuture::new(async { 3 + 5 });
+///
+/// # use std::{sync::Arc, task::{Wake, Waker}};
+/// # struct Noop;
+/// # impl Wake for Noop { fn wake(self: Arc<Self>) {} }
+/// # let waker: Waker = Arc::new(Noop).into();
+/// let mut cx = Context::from_waker(&waker);
+///
+/// assert_eq!(Pin::new(&mut fut).poll(&mut cx), Poll::Ready(8));
+///
+/// ```
+#[repr(C)]
+#[derive(StableAbi)]
+pub struct RFuture<'a, T> {
+    data: *mut ErasedObject,
+    vtable: FutureVTable_Ref<T>,
+    _marker: PhantomData<&'a ()>,
+}
+
+unsafe impl<'a, T> Send for RFuture<'a, T> {}
+
+impl<'a, T> RFuture<'a, T> {
+    /// Constructs an `RFuture` from a future,
+    /// which is boxed and pinned in place.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use abi_stable::sabi_types::RFuture;
+    ///
+    /// let list = vec![3, 5, 8];
+    ///
+    /// let fut: RFuture<'_, usize> = RFuture::new(async { list.len() });
+    ///
+    /// ```
+    pub fn new<F>(future: F) -> Self
+    where
+        F: Future<Output = T> + Send + 'a,
+    {
+        Self {
+            data: Box::into_raw(Box::new(future)) as *mut ErasedObject,
+            vtable: VTableGetter::<F>::LIB_VTABLE,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Polls this future, without requiring it to be pinned.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use abi_stable::sabi_types::RFuture;
+    ///
+    /// use std::task::{Context, Poll};
+    ///
+    /// let mut fut = RFuture::new(async { "hello" });
+    ///
+    /// # use std::{sync::Arc, task::{Wake, Waker}};
+    /// # struct Noop;
+    /// # impl Wake for Noop { fn wake(self: Arc<Self>) {} }
+    /// # let waker: Waker = Arc::new(Noop).into();
+    /// let mut cx = Context::from_waker(&waker);
+    ///
+    /// assert_eq!(fut.poll_unpin(&mut cx), Poll::Ready("hello"));
+    ///
+    /// ```
+    pub fn poll_unpin(&mut self, cx: &mut Context<'_>) -> Poll<T> {
+        let poll = self.vtable.poll();
+        let data = unsafe { RMut::from_raw(self.data) };
+        RWaker::with_waker_ref(cx.waker(), |waker| unsafe { poll(data, waker) }).into_poll()
+    }
+}
+
+impl<'a, T> Future for RFuture<'a, T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        self.get_mut().poll_unpin(cx)
+    }
+}
+
+impl<'a, T> Drop for RFuture<'a, T> {
+    fn drop(&mut self) {
+        unsafe { self.vtable.drop_()(self.data) }
+    }
+}
+
+impl<'a, T> Debug for RFuture<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RFuture").field("data", &self.data).finish()
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+#[repr(C)]
+#[derive(StableAbi)]
+#[sabi(kind(Prefix))]
+#[sabi(missing_field(panic))]
+struct FutureVTable<T> {
+    poll: unsafe extern "C" fn(RMut<'_, ErasedObject>, &RWaker) -> RPoll<T>,
+    #[sabi(last_prefix_field)]
+    drop_: unsafe extern "C" fn(*mut ErasedObject),
+}
+
+struct VTableGetter<'a, F>(PhantomData<&'a F>);
+
+impl<'a, F> VTableGetter<'a, F>
+where
+    F: Future + 'a,
+{
+    staticref! {
+        const WM_DEFAULT: WithMetadata<FutureVTable<F::Output>> =
+            WithMetadata::new(FutureVTable {
+                poll: poll_future::<F>,
+                drop_: drop_future::<F>,
+            })
+    }
+
+    // The vtable for `RFuture`s that wrap an `F`, in this executable/library
+    const LIB_VTABLE: FutureVTable_Ref<F::Output> = FutureVTable_Ref(Self::WM_DEFAULT.as_prefix());
+}
+
+unsafe extern "C" fn poll_future<F>(
+    this: RMut<'_, ErasedObject>,
+    waker: &RWaker,
+) -> RPoll<F::Output>
+where
+    F: Future,
+{
+    extern_fn_panic_handling! {no_early_return;
+        // The future is boxed, and is never moved out of its allocation.
+        let this = unsafe { Pin::new_unchecked(this.transmute_into_mut::<F>()) };
+        waker.with_context(|cx| this.poll(cx)).into()
+    }
+}
+
+unsafe extern "C" fn drop_future<F>(this: *mut ErasedObject) {
+    extern_fn_panic_handling! {no_early_return;
+        drop(unsafe { Box::from_raw(this as *mut F) });
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+#[cfg(all(test, not(feature = "only_new_tests")))]
+mod tests {
+    use super::*;
+
+    use std::sync::Arc;
+
+    use crate::test_utils::{block_on, WakeCounter};
+
+    /// Returns `Pending` (and wakes up the task) `count` times before completing.
+    struct YieldTimes {
+        count: u32,
+    }
+
+    impl Future for YieldTimes {
+        type Output = u32;
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<u32> {
+            if self.count == 0 {
+                Poll::Ready(100)
+            } else {
+                self.count -= 1;
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        }
+    }
+
+    #[test]
+    fn polling() {
+        let counter = WakeCounter::new();
+        let waker = counter.waker();
+        let mut cx = Context::from_waker(&waker);
+
+        let mut fut = RFuture::new(YieldTimes { count: 2 });
+
+        assert_eq!(fut.poll_unpin(&mut cx), Poll::Pending);
+        assert_eq!(Pin::new(&mut fut).poll(&mut cx), Poll::Pending);
+        assert_eq!(counter.count(), 2);
+        assert_eq!(fut.poll_unpin(&mut cx), Poll::Ready(100));
+        assert_eq!(counter.count(), 2);
+
+        drop(fut);
+        drop(waker);
+        assert_eq!(Arc::strong_count(&counter), 1);
+    }
+
+    #[test]
+    fn async_blocks() {
+        let inner = RFuture::new(YieldTimes { count: 3 });
+        let fut = RFuture::new(async move { inner.await + 5 });
+        assert_eq!(block_on(fut), 105);
+    }
+
+    #[test]
+    fn borrowing() {
+        let list = vec![3, 5, 8];
+        let fut: RFuture<'_, u32> = RFuture::new(async { list.iter().sum() });
+        assert_eq!(block_on(fut), 16);
+    }
+
+    #[test]
+    fn drops_future() {
+        let arc = Arc::new(0);
+        let fut = RFuture::new({
+            let arc = arc.clone();
+            async move { *arc }
+        });
+        assert_eq!(Arc::strong_count(&arc), 2);
+        drop(fut);
+        assert_eq!(Arc::strong_count(&arc), 1);
+
+        let fut = RFuture::new({
+            let arc = arc.clone();
+            async move { *arc }
+        });
+        assert_eq!(block_on(fut), 0);
+        assert_eq!(Arc::strong_count(&arc), 1);
+    }
+}
@@ -0,0 +1,327 @@
+//! Contains the `RStream<'a, T>` type.
+
+use std::{
+    fmt::{self, Debug},
+    task::{Context, Poll},
+};
+
+use crate::{erased_types::interfaces::StreamInterface, std_types::RBox, DynTrait};
+
+/// An ffi-safe, type-erased, `Send` stream,
+/// equivalent to `Pin<Box<dyn futures_core::Stream<Item = T> + Send + 'a>>`.
+///
+/// This can be polled across the ffi boundary,
+/// passing an [`RWaker`](crate::std_types::RWaker) to the erased stream.
+///
+/// Constructing an `RStream` from a `futures_core::Stream`,
+/// and using it as one, requires the "futures" feature.
+/// Without the feature, an `RStream` received from another library can still be polled
+/// with the [`poll_next_unpin`](#method.poll_next_unpin) method.
+///
+/// # Example
+///
+#[cfg_attr(feature = "futures", doc = "```rust")]
+#[cfg_attr(not(feature = "futures"), doc = "```ignore")]
+/// use abi_stable::sabi_types::RStream;
+///
+/// use futures_core::Stream;
+///
+/// use std::{
+///     pin::Pin,
+///     task::{Context, Poll},
+/// };
+///
+/// struct Counter(u32);
+///
+/// impl Stream for Counter {
+///     type Item = u32;
+///
+///     fn poll_next(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Option<u32>> {
+///         self.0 += 1;
+///         Poll::Ready(Some(self.0).filter(|&x| x <= 3))
+///     }
+/// }
+///
+/// let mut stream = RStream::new(Counter(0));
+///
+/// # use std::{sync::Arc, task::{Wake, Waker}};
+/// # struct Noop;
+/// # impl Wake for Noop { fn wake(self: Arc<Self>) {} }
+/// # let waker: Waker = Arc::new(Noop).into();
+/// let mut cx = Context::from_waker(&waker);
+///
+/// assert_eq!(Pin::new(&mut stream).poll_next(&mut cx), Poll::Ready(Some(1)));
+/// assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Ready(Some(2)));
+/// assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Ready(Some(3)));
+/// assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Ready(None));
+///
+/// ```
+#[repr(C)]
+#[derive(StableAbi)]
+pub struct RStream<'a, T: 'a> {
+    inner: DynTrait<'a, RBox<()>, StreamInterface<T>>,
+}
+
+impl<'a, T> RStream<'a, T> {
+    /// Constructs an `RStream` from a `futures_core::Stream`.
+    ///
+    /// `!Unpin` streams can be passed by pinning them first, with `Box::pin`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use abi_stable::sabi_types::RStream;
+    ///
+    /// use futures_core::Stream;
+    ///
+    /// use std::{
+    ///     pin::Pin,
+    ///     task::{Context, Poll},
+    /// };
+    ///
+    /// struct Once<T>(Option<T>);
+    ///
+    /// impl<T: Unpin> Stream for Once<T> {
+    ///     type Item = T;
+    ///
+    ///     fn poll_next(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Option<T>> {
+    ///         Poll::Ready(self.0.take())
+    ///     }
+    /// }
+    ///
+    /// let stream: RStream<'static, u8> = RStream::new(Once(Some(3)));
+    ///
+    /// let pinned: Pin<Box<dyn Stream<Item = u8> + Send>> = Box::pin(Once(Some(5)));
+    /// let stream: RStream<'static, u8> = RStream::new(pinned);
+    ///
+    /// ```
+    #[cfg(feature = "futures")]
+    #[cfg_attr(feature = "docsrs", doc(cfg(feature = "futures")))]
+    pub fn new<S>(stream: S) -> Self
+    where
+        S: futures_core::Stream<Item = T> + Unpin + Send + 'a,
+    {
+        Self {
+            inner: DynTrait::from_borrowing_value(stream),
+        }
+    }
+
+    /// Constructs an `RStream` from a `DynTrait` that implements `Stream`.
+    pub fn from_dyn(inner: DynTrait<'a, RBox<()>, StreamInterface<T>>) -> Self {
+        Self { inner }
+    }
+
+    /// Unwraps this `RStream` into the `DynTrait` that it wraps.
+    pub fn into_dyn(self) -> DynTrait<'a, RBox<()>, StreamInterface<T>> {
+        self.inner
+    }
+
+    /// Polls the next element of this stream.
+    ///
+    /// This is usable regardless of whether the "futures" feature is enabled.
+    ///
+    /// # Example
+    ///
+    #[cfg_attr(feature = "futures", doc = "```rust")]
+    #[cfg_attr(not(feature = "futures"), doc = "```ignore")]
+    /// use abi_stable::sabi_types::RStream;
+    ///
+    /// use std::{
+    ///     pin::Pin,
+    ///     task::{Context, Poll},
+    /// };
+    ///
+    /// struct Repeat(&'static str);
+    ///
+    /// impl futures_core::Stream for Repeat {
+    ///     type Item = &'static str;
+    ///
+    ///     fn poll_next(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+    ///         Poll::Ready(Some(self.0))
+    ///     }
+    /// }
+    ///
+    /// let mut stream = RStream::new(Repeat("hello"));
+    ///
+    /// # use std::{sync::Arc, task::{Wake, Waker}};
+    /// # struct Noop;
+    /// # impl Wake for Noop { fn wake(self: Arc<Self>) {} }
+    /// # let waker: Waker = Arc::new(Noop).into();
+    /// let mut cx = Context::from_waker(&waker);
+    ///
+    /// assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Ready(Some("hello")));
+    /// assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Ready(Some("hello")));
+    ///
+    /// ```
+    pub fn poll_next_unpin(&mut self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        self.inner.poll_next_unpin(cx)
+    }
+
+    /// Returns the bounds on the remaining length of this stream.
+    pub fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.stream_size_hint()
+    }
+}
+
+#[cfg(feature = "futures")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "futures")))]
+impl<'a, T> futures_core::Stream for RStream<'a, T> {
+    type Item = T;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        self.poll_next_unpin(cx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        RStream::size_hint(self)
+    }
+}
+
+impl<'a, T> Debug for RStream<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("RStream{..}")
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+#[cfg(all(test, not(feature = "only_new_tests")))]
+mod tests {
+    use super::*;
+
+    use std::{pin::Pin, sync::Arc};
+
+    use futures_core::Stream;
+
+    use crate::test_utils::WakeCounter;
+
+    /// Yields `0..len`, returning `Pending` (and waking up the task)
+    /// before every element.
+    struct Alternating {
+        next: u32,
+        len: u32,
+        was_pending: bool,
+    }
+
+    impl Alternating {
+        fn new(len: u32) -> Self {
+            Self {
+                next: 0,
+                len,
+                was_pending: false,
+            }
+        }
+    }
+
+    impl Stream for Alternating {
+        type Item = u32;
+
+        fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<u32>> {
+            if self.next == self.len {
+                Poll::Ready(None)
+            } else if !self.was_pending {
+                self.was_pending = true;
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            } else {
+                self.was_pending = false;
+                self.next += 1;
+                Poll::Ready(Some(self.next - 1))
+            }
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            let rem = (self.len - self.next) as usize;
+            (rem, Some(rem))
+        }
+    }
+
+    fn collect<S>(mut stream: S) -> Vec<S::Item>
+    where
+        S: Stream + Unpin,
+    {
+        let counter = WakeCounter::new();
+        let waker = counter.waker();
+        let mut cx = Context::from_waker(&waker);
+
+        let mut list = Vec::new();
+        loop {
+            match Pin::new(&mut stream).poll_next(&mut cx) {
+                Poll::Ready(Some(x)) => list.push(x),
+                Poll::Ready(None) => return list,
+                Poll::Pending => {}
+            }
+        }
+    }
+
+    #[test]
+    fn poll_next_wakes() {
+        let counter = WakeCounter::new();
+        let waker = counter.waker();
+        let mut cx = Context::from_waker(&waker);
+
+        let mut stream = RStream::new(Alternating::new(2));
+
+        assert_eq!(stream.size_hint(), (2, Some(2)));
+        assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Pending);
+        assert_eq!(counter.count(), 1);
+        assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Ready(Some(0)));
+        assert_eq!(stream.size_hint(), (1, Some(1)));
+        assert_eq!(Pin::new(&mut stream).poll_next(&mut cx), Poll::Pending);
+        assert_eq!(counter.count(), 2);
+        assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Ready(Some(1)));
+        assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Ready(None));
+        assert_eq!(stream.size_hint(), (0, Some(0)));
+
+        drop(stream);
+        drop(waker);
+        assert_eq!(Arc::strong_count(&counter), 1);
+    }
+
+    #[test]
+    fn collecting() {
+        assert_eq!(
+            collect(RStream::new(Alternating::new(0))),
+            Vec::<u32>::new()
+        );
+        assert_eq!(collect(RStream::new(Alternating::new(4))), vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn pinned_stream() {
+        let boxed: Pin<Box<dyn Stream<Item = u32> + Send>> = Box::pin(Alternating::new(3));
+        assert_eq!(collect(RStream::new(boxed)), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn borrowing_stream() {
+        let list = [3, 5, 8];
+
+        struct Iter<'a>(std::slice::Iter<'a, u32>);
+
+        impl<'a> Stream for Iter<'a> {
+            type Item = &'a u32;
+
+            fn poll_next(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Option<&'a u32>> {
+                Poll::Ready(self.0.next())
+            }
+        }
+
+        let stream: RStream<'_, &u32> = RStream::new(Iter(list.iter()));
+        assert_eq!(collect(stream), vec![&3, &5, &8]);
+    }
+
+    #[test]
+    fn dyn_trait_conversion() {
+        let stream = RStream::new(Alternating::new(3));
+        let dyn_ = stream.into_dyn();
+        assert_eq!(dyn_.stream_size_hint(), (3, Some(3)));
+        assert_eq!(collect(dyn_), vec![0, 1, 2]);
+
+        let stream = RStream::from_dyn(DynTrait::from_value(Alternating::new(2)));
+        assert_eq!(collect(stream), vec![0, 1]);
+    }
+}
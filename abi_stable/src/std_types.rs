@@ -5,23 +5,36 @@
 //! the [external_types module](../external_types/index.html)
 
 pub(crate) mod arc;
+pub(crate) mod arc_slice;
+pub mod array_vec;
 pub(crate) mod boxed;
+pub(crate) mod boxed_slice;
+pub mod btree_map;
+pub mod btree_set;
 pub(crate) mod cmp_ordering;
 pub mod cow;
+pub mod index_map;
 pub mod map;
 pub(crate) mod option;
+pub(crate) mod os_str;
+pub(crate) mod path;
 pub(crate) mod range;
+pub(crate) mod rc;
 pub(crate) mod result;
+pub mod set;
 pub(crate) mod slice_mut;
 pub(crate) mod slices;
+pub mod small_vec;
 pub(crate) mod std_error;
 pub(crate) mod std_io;
 pub(crate) mod str;
 pub mod string;
+pub(crate) mod task;
 pub(crate) mod time;
 pub(crate) mod tuple;
 pub mod utypeid;
 pub mod vec;
+pub mod vec_deque;
 
 /// Some types from the `std::sync` module have ffi-safe equivalents in
 /// `abi_stable::external_types`.
@@ -36,21 +49,34 @@ pub mod sync {}
 
 #[doc(inline)]
 pub use self::{
-    arc::RArc,
+    arc::{RArc, RWeak},
+    arc_slice::{RArcSlice, RArcStr},
+    array_vec::RArrayVec,
     boxed::RBox,
+    boxed_slice::{RBoxSlice, RBoxStr},
+    btree_map::RBTreeMap,
+    btree_set::RBTreeSet,
     cmp_ordering::RCmpOrdering,
     cow::{RCow, RCowSlice, RCowStr, RCowVal},
+    index_map::RIndexMap,
     map::RHashMap,
     option::{RNone, ROption, RSome},
+    os_str::{ROsStr, ROsString},
+    path::{RPath, RPathBuf},
+    rc::{RRc, RRcWeak},
     result::{RErr, ROk, RResult},
+    set::RHashSet,
     slice_mut::RSliceMut,
     slices::RSlice,
+    small_vec::RSmallVec,
     std_error::{RBoxError, RBoxError_, SendRBoxError, UnsyncRBoxError},
     std_io::{RIoError, RIoErrorKind, RSeekFrom},
     str::RStr,
     string::RString,
-    time::RDuration,
+    task::{RPoll, RWaker},
+    time::{RDuration, RInstant, RSystemTime},
     tuple::{Tuple1, Tuple2, Tuple3, Tuple4},
     utypeid::UTypeId,
     vec::RVec,
+    vec_deque::RVecDeque,
 };
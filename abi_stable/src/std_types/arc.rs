@@ -1,6 +1,12 @@
 //! Contains the ffi-safe equivalent of `std::sync::Arc`.
 
-use std::{borrow::Borrow, marker::PhantomData, mem::ManuallyDrop, sync::Arc};
+use std::{
+    borrow::Borrow,
+    fmt::{self, Debug},
+    marker::PhantomData,
+    mem::ManuallyDrop,
+    sync::{Arc, Weak},
+};
 
 use core_extensions::SelfOps;
 
@@ -13,7 +19,7 @@ use crate::{
     prefix_type::{PrefixRef, WithMetadata},
     std_types::{
         utypeid::{new_utypeid, UTypeId},
-        RResult,
+        ROption, RResult,
     },
 };
 
@@ -139,9 +145,64 @@ mod private {
             self.vtable = unsafe { VTableGetter::<T>::LIB_VTABLE_FOR_TESTING.0.cast() };
         }
     }
+
+    /// Ffi-safe version of `std::sync::Weak`,
+    /// a non-owning reference to the value of an [`RArc`].
+    ///
+    /// An `RWeak` does not keep the value alive,
+    /// it has to be upgraded to an `RArc` to access the value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RArc, RNone, RSome, RWeak};
+    ///
+    /// let arc = RArc::new(100);
+    /// let weak: RWeak<u32> = RArc::downgrade(&arc);
+    ///
+    /// assert_eq!(weak.upgrade().map(|x| *x), RSome(100));
+    ///
+    /// drop(arc);
+    ///
+    /// assert_eq!(weak.upgrade(), RNone);
+    ///
+    /// ```
+    ///
+    #[derive(StableAbi)]
+    #[repr(C)]
+    pub struct RWeak<T> {
+        data: *const T,
+        #[sabi(unsafe_change_type = ArcVtable_Ref<T>)]
+        vtable: PrefixRef<ErasedPrefix>,
+        _marker: PhantomData<T>,
+    }
+
+    impl_from_rust_repr! {
+        impl[T] From<Weak<T>> for RWeak<T> {
+            fn(this){
+                RWeak {
+                    data: Weak::into_raw(this),
+                    vtable: unsafe{ VTableGetter::<T>::LIB_VTABLE.0.cast() },
+                    _marker: Default::default(),
+                }
+            }
+        }
+    }
+
+    impl<T> RWeak<T> {
+        #[inline(always)]
+        pub(super) const fn data(&self) -> *const T {
+            self.data
+        }
+
+        #[inline(always)]
+        pub(crate) const fn vtable(&self) -> ArcVtable_Ref<T> {
+            unsafe { ArcVtable_Ref::<T>(self.vtable.cast()) }
+        }
+    }
 }
 
-pub use self::private::RArc;
+pub use self::private::{RArc, RWeak};
 
 impl<T> RArc<T> {
     /// Constructs an `RArc` from a value.
@@ -310,7 +371,7 @@ impl<T> RArc<T> {
         unsafe { vtable.strong_count()(this) }
     }
 
-    /// Gets the number of `std::sync::Weak` that point to the value.
+    /// Gets the number of `RWeak` and `std::sync::Weak` that point to the value.
     ///
     /// # Example
     ///
@@ -333,6 +394,47 @@ impl<T> RArc<T> {
         let vtable = this.vtable();
         unsafe { vtable.weak_count()(this) }
     }
+
+    /// Creates an [`RWeak`] pointing to the same value as this `RArc<T>`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `RArc<T>` was created by a dynamic library/executable
+    /// built with a version of `abi_stable` that doesn't support weak pointers.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RArc;
+    ///
+    /// let arc = RArc::new(0);
+    /// let weak = RArc::downgrade(&arc);
+    ///
+    /// assert_eq!(RArc::weak_count(&arc), 1);
+    /// assert!(RArc::ptr_eq(&arc, &weak.upgrade().unwrap()));
+    ///
+    /// ```
+    pub fn downgrade(this: &Self) -> RWeak<T> {
+        let vtable = this.vtable();
+        unsafe { vtable.downgrade()(this) }
+    }
+
+    /// Returns whether both `RArc`s point to the same allocation.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RArc;
+    ///
+    /// let arc = RArc::new(0);
+    ///
+    /// assert!(RArc::ptr_eq(&arc, &arc.clone()));
+    /// assert!(!RArc::ptr_eq(&arc, &RArc::new(0)));
+    ///
+    /// ```
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        std::ptr::eq(this.data(), other.data())
+    }
 }
 
 ////////////////////////////////////////////////////////////////////
@@ -402,6 +504,141 @@ impl<T> Unpin for RArc<T> {}
 
 /////////////////////////////////////////////////////////
 
+impl<T> RWeak<T> {
+    /// Constructs an `RWeak<T>` that doesn't point to any value,
+    /// calling `upgrade` on it always returns `RNone`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RNone, RWeak};
+    ///
+    /// let weak = RWeak::<u32>::new();
+    ///
+    /// assert_eq!(weak.upgrade(), RNone);
+    ///
+    /// ```
+    pub fn new() -> Self {
+        Weak::new().into()
+    }
+
+    /// Attempts to get an `RArc<T>` to the value,
+    /// returning `RNone` if the value was already dropped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RArc, RNone};
+    ///
+    /// let arc = RArc::new("hello");
+    /// let weak = RArc::downgrade(&arc);
+    ///
+    /// assert_eq!(*weak.upgrade().unwrap(), "hello");
+    ///
+    /// drop(arc);
+    /// assert_eq!(weak.upgrade(), RNone);
+    ///
+    /// ```
+    pub fn upgrade(&self) -> ROption<RArc<T>> {
+        let vtable = self.vtable();
+        unsafe { vtable.upgrade()(self) }
+    }
+
+    /// Gets the number of `RArc` that point to the value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RArc;
+    ///
+    /// let arc = RArc::new(0);
+    /// let weak = RArc::downgrade(&arc);
+    /// assert_eq!(weak.strong_count(), 1);
+    ///
+    /// drop(arc);
+    /// assert_eq!(weak.strong_count(), 0);
+    ///
+    /// ```
+    pub fn strong_count(&self) -> usize {
+        let vtable = self.vtable();
+        unsafe { vtable.weak_strong_count()(self) }
+    }
+
+    /// Gets the number of weak pointers that point to the value,
+    /// returning 0 if there are no `RArc`s left.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RArc;
+    ///
+    /// let arc = RArc::new(0);
+    /// let weak = RArc::downgrade(&arc);
+    /// let _weak2 = weak.clone();
+    /// assert_eq!(weak.weak_count(), 2);
+    ///
+    /// drop(arc);
+    /// assert_eq!(weak.weak_count(), 0);
+    ///
+    /// ```
+    pub fn weak_count(&self) -> usize {
+        let vtable = self.vtable();
+        unsafe { vtable.weak_weak_count()(self) }
+    }
+
+    /// Returns whether both `RWeak`s point to the same allocation,
+    /// or were both created with `RWeak::new`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RArc;
+    ///
+    /// let arc = RArc::new(0);
+    /// let weak = RArc::downgrade(&arc);
+    ///
+    /// assert!(weak.ptr_eq(&RArc::downgrade(&arc)));
+    /// assert!(!weak.ptr_eq(&RArc::downgrade(&RArc::new(0))));
+    ///
+    /// ```
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self.data(), other.data())
+    }
+}
+
+impl<T> Default for RWeak<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for RWeak<T> {
+    fn clone(&self) -> Self {
+        unsafe { (self.vtable().clone_weak())(self) }
+    }
+}
+
+impl<T> Debug for RWeak<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("(RWeak)")
+    }
+}
+
+impl<T> Drop for RWeak<T> {
+    fn drop(&mut self) {
+        unsafe {
+            let vtable = self.vtable();
+            (vtable.destructor_weak())(self.data());
+        }
+    }
+}
+
+unsafe impl<T> Sync for RWeak<T> where T: Send + Sync {}
+
+unsafe impl<T> Send for RWeak<T> where T: Send + Sync {}
+
+/////////////////////////////////////////////////////////
+
 mod vtable_mod {
     use super::*;
 
@@ -416,6 +653,12 @@ mod vtable_mod {
             try_unwrap: try_unwrap_arc::<T>,
             strong_count: strong_count_arc::<T>,
             weak_count: weak_count_arc::<T>,
+            downgrade: downgrade_arc::<T>,
+            upgrade: upgrade_weak::<T>,
+            clone_weak: clone_weak::<T>,
+            destructor_weak: destructor_weak::<T>,
+            weak_strong_count: strong_count_weak::<T>,
+            weak_weak_count: weak_count_weak::<T>,
         };
 
         staticref! {
@@ -455,6 +698,12 @@ mod vtable_mod {
         pub(super) strong_count: unsafe extern "C" fn(&RArc<T>) -> usize,
         #[sabi(last_prefix_field)]
         pub(super) weak_count: unsafe extern "C" fn(&RArc<T>) -> usize,
+        pub(super) downgrade: unsafe extern "C" fn(&RArc<T>) -> RWeak<T>,
+        pub(super) upgrade: unsafe extern "C" fn(&RWeak<T>) -> ROption<RArc<T>>,
+        pub(super) clone_weak: unsafe extern "C" fn(&RWeak<T>) -> RWeak<T>,
+        pub(super) destructor_weak: unsafe extern "C" fn(*const T),
+        pub(super) weak_strong_count: unsafe extern "C" fn(&RWeak<T>) -> usize,
+        pub(super) weak_weak_count: unsafe extern "C" fn(&RWeak<T>) -> usize,
     }
 
     unsafe extern "C" fn destructor_arc<T>(this: *const T, call_drop: CallReferentDrop) {
@@ -505,5 +754,41 @@ mod vtable_mod {
     unsafe extern "C" fn weak_count_arc<T>(this: &RArc<T>) -> usize {
         unsafe { with_arc_ref(this, |x| Arc::weak_count(x)) }
     }
+
+    unsafe extern "C" fn downgrade_arc<T>(this: &RArc<T>) -> RWeak<T> {
+        unsafe { with_arc_ref(this, |x| Arc::downgrade(x).into()) }
+    }
+
+    unsafe fn with_weak_ref<T, F, R>(this: &RWeak<T>, f: F) -> R
+    where
+        F: FnOnce(&Weak<T>) -> R,
+    {
+        let x = this.data();
+        let x = unsafe { Weak::from_raw(x) };
+        let x = ManuallyDrop::new(x);
+        f(&x)
+    }
+
+    unsafe extern "C" fn upgrade_weak<T>(this: &RWeak<T>) -> ROption<RArc<T>> {
+        unsafe { with_weak_ref(this, |x| x.upgrade().map(RArc::from).into()) }
+    }
+
+    unsafe extern "C" fn clone_weak<T>(this: &RWeak<T>) -> RWeak<T> {
+        unsafe { with_weak_ref(this, |x| Weak::clone(x).into()) }
+    }
+
+    unsafe extern "C" fn destructor_weak<T>(this: *const T) {
+        extern_fn_panic_handling! {no_early_return; unsafe {
+            drop(Weak::from_raw(this));
+        }}
+    }
+
+    unsafe extern "C" fn strong_count_weak<T>(this: &RWeak<T>) -> usize {
+        unsafe { with_weak_ref(this, Weak::strong_count) }
+    }
+
+    unsafe extern "C" fn weak_count_weak<T>(this: &RWeak<T>) -> usize {
+        unsafe { with_weak_ref(this, Weak::weak_count) }
+    }
 }
 use self::vtable_mod::{ArcVtable_Ref, VTableGetter};
@@ -2,6 +2,8 @@ use super::*;
 
 use std::cell::Cell;
 
+use crate::std_types::RNone;
+
 #[allow(clippy::redundant_allocation)]
 fn _covariant_arc<'a: 'b, 'b, T>(foo: Arc<&'a T>) -> Arc<&'b T> {
     foo
@@ -130,6 +132,57 @@ fn make_mut() {
     assert_eq!(arc.value, 'c');
 }
 
+#[test]
+fn downgrade_upgrade() {
+    let count = Cell::new(1);
+    let dod = DecrementOnDrop(&count);
+
+    let arc = RArc::new(dod.clone());
+    let weak = RArc::downgrade(&arc);
+    assert_eq!(dod.count(), 2);
+    assert_eq!(RArc::weak_count(&arc), 1);
+    assert_eq!(weak.strong_count(), 1);
+
+    {
+        let upgraded = weak.upgrade().unwrap();
+        assert!(RArc::ptr_eq(&arc, &upgraded));
+        assert_eq!(RArc::strong_count(&arc), 2);
+    }
+
+    let weak_clone = weak.clone();
+    assert!(weak.ptr_eq(&weak_clone));
+    assert_eq!(weak.weak_count(), 2);
+
+    drop(arc);
+    assert_eq!(dod.count(), 1);
+    assert_eq!(weak.upgrade().map(drop), RNone);
+    assert_eq!(weak.strong_count(), 0);
+    assert_eq!(weak_clone.weak_count(), 0);
+}
+
+#[test]
+fn weak_with_other_vtable() {
+    let mut arc = RArc::new(1000);
+    arc.set_vtable_for_testing();
+
+    let weak = RArc::downgrade(&arc);
+    assert_eq!(*weak.upgrade().unwrap(), 1000);
+
+    let from_std = RWeak::from(Arc::downgrade(&RArc::into_arc(arc)));
+    assert_eq!(from_std.upgrade(), RNone);
+    assert_eq!(weak.upgrade(), RNone);
+}
+
+#[test]
+fn new_weak() {
+    let weak = RWeak::<u32>::new();
+    assert_eq!(weak.upgrade(), RNone);
+    assert_eq!(weak.strong_count(), 0);
+    assert_eq!(weak.weak_count(), 0);
+    assert!(weak.ptr_eq(&RWeak::default()));
+    drop(weak.clone());
+}
+
 /////////////////////////////////////////
 
 #[derive(Clone)]
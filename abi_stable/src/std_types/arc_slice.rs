@@ -0,0 +1,792 @@
+//! Contains the ffi-safe equivalents of `Arc<[T]>` and `Arc<str>`.
+
+use std::{
+    borrow::Borrow,
+    fmt::{self, Display},
+    iter::FromIterator,
+    marker::PhantomData,
+    mem::{self, ManuallyDrop},
+    ops::Deref,
+    ptr, slice, str,
+    sync::Arc,
+};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{
+    marker_type::NonOwningPhantom,
+    prefix_type::WithMetadata,
+    std_types::{
+        utypeid::{new_utypeid, UTypeId},
+        RSlice, RStr, RString, RVec,
+    },
+};
+
+#[cfg(all(test, not(feature = "only_new_tests")))]
+mod test;
+
+/// Ffi-safe equivalent of `Arc<[T]>`,
+/// an atomically reference-counted slice stored as a pointer and a length.
+///
+/// This avoids the double indirection of an `RArc<RVec<T>>`.
+///
+/// # Example
+///
+/// ```
+/// use abi_stable::std_types::RArcSlice;
+///
+/// use std::sync::Arc;
+///
+/// let arc: Arc<[u32]> = Arc::from(vec![3, 5, 8]);
+///
+/// // This reuses the allocation of the `Arc<[u32]>`
+/// let slice = RArcSlice::from(arc);
+/// let clone = slice.clone();
+///
+/// assert_eq!(&slice[..], &[3, 5, 8]);
+/// assert!(RArcSlice::ptr_eq(&slice, &clone));
+/// assert_eq!(RArcSlice::strong_count(&slice), 2);
+///
+/// ```
+///
+#[repr(C)]
+#[derive(StableAbi)]
+pub struct RArcSlice<T> {
+    data: *const T,
+    length: usize,
+    vtable: ArcSliceVtable_Ref<T>,
+    _marker: PhantomData<T>,
+}
+
+impl<T> RArcSlice<T> {
+    /// Converts an `Arc<[T]>` to an `RArcSlice<T>`, reusing its heap allocation.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RArcSlice;
+    ///
+    /// use std::sync::Arc;
+    ///
+    /// let slice = RArcSlice::from_arc(Arc::from(&[13, 21][..]));
+    ///
+    /// assert_eq!(slice.as_slice(), &[13, 21]);
+    ///
+    /// ```
+    pub fn from_arc(this: Arc<[T]>) -> Self {
+        let length = this.len();
+        RArcSlice {
+            data: Arc::into_raw(this) as *const T,
+            length,
+            vtable: VTableGetter::<T>::LIB_VTABLE,
+            _marker: PhantomData,
+        }
+    }
+
+    #[inline(always)]
+    const fn vtable(&self) -> ArcSliceVtable_Ref<T> {
+        self.vtable
+    }
+
+    #[inline(always)]
+    fn erased_data(&self) -> *const () {
+        self.data as *const ()
+    }
+
+    #[allow(dead_code)]
+    #[cfg(test)]
+    pub(super) fn set_vtable_for_testing(&mut self) {
+        self.vtable = VTableGetter::<T>::LIB_VTABLE_FOR_TESTING;
+    }
+
+    /// Returns the length of the slice.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RArcSlice;
+    ///
+    /// assert_eq!(RArcSlice::<u32>::default().len(), 0);
+    /// assert_eq!(RArcSlice::from(vec![3, 5]).len(), 2);
+    ///
+    /// ```
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Returns whether the slice is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RArcSlice;
+    ///
+    /// assert!(RArcSlice::<u32>::default().is_empty());
+    /// assert!(!RArcSlice::from(vec![3, 5]).is_empty());
+    ///
+    /// ```
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Gets a slice of the contents.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RArcSlice;
+    ///
+    /// let slice = RArcSlice::from(vec![3, 5]);
+    ///
+    /// assert_eq!(slice.as_slice(), &[3, 5]);
+    ///
+    /// ```
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.data, self.length) }
+    }
+
+    /// Gets an `RSlice<'_, T>` of the contents.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RArcSlice, RSlice};
+    ///
+    /// let slice = RArcSlice::from(vec![3, 5]);
+    ///
+    /// assert_eq!(slice.as_rslice(), RSlice::from_slice(&[3, 5]));
+    ///
+    /// ```
+    #[inline]
+    pub fn as_rslice(&self) -> RSlice<'_, T> {
+        self.as_slice().into()
+    }
+
+    /// Attempts to get a mutable slice of the contents,
+    /// failing if the `RArcSlice<T>`'s strong count is greater than 1.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RArcSlice;
+    ///
+    /// let mut slice = RArcSlice::from(vec![3, 5]);
+    /// RArcSlice::get_mut(&mut slice).unwrap()[0] = 8;
+    /// assert_eq!(slice.as_slice(), &[8, 5]);
+    ///
+    /// let _clone = slice.clone();
+    /// assert_eq!(RArcSlice::get_mut(&mut slice), None);
+    ///
+    /// ```
+    pub fn get_mut(this: &mut Self) -> Option<&mut [T]> {
+        let vtable = this.vtable();
+        if unsafe { vtable.is_unique()(this.erased_data(), this.length) } {
+            unsafe { Some(slice::from_raw_parts_mut(this.data as *mut T, this.length)) }
+        } else {
+            None
+        }
+    }
+
+    /// Gets the number of `RArcSlice` that point to the slice.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RArcSlice;
+    ///
+    /// let slice = RArcSlice::from(vec![3, 5]);
+    /// assert_eq!(RArcSlice::strong_count(&slice), 1);
+    ///
+    /// let clone = slice.clone();
+    /// assert_eq!(RArcSlice::strong_count(&slice), 2);
+    ///
+    /// ```
+    pub fn strong_count(this: &Self) -> usize {
+        let vtable = this.vtable();
+        unsafe { vtable.strong_count()(this.erased_data(), this.length) }
+    }
+
+    /// Returns whether both `RArcSlice`s point to the same allocation.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RArcSlice;
+    ///
+    /// let slice = RArcSlice::from(vec![3, 5]);
+    ///
+    /// assert!(RArcSlice::ptr_eq(&slice, &slice.clone()));
+    /// assert!(!RArcSlice::ptr_eq(&slice, &RArcSlice::from(vec![3, 5])));
+    ///
+    /// ```
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        ptr::eq(this.data, other.data) && this.length == other.length
+    }
+
+    /// Converts this `RArcSlice<T>` into an `Arc<[T]>`
+    ///
+    /// # Allocation
+    ///
+    /// If this is invoked outside of the dynamic library/binary that created it,
+    /// it will allocate a new `Arc<[T]>` and clone the elements into it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RArcSlice;
+    ///
+    /// use std::sync::Arc;
+    ///
+    /// let slice = RArcSlice::from(vec![3, 5]);
+    ///
+    /// assert_eq!(RArcSlice::into_arc(slice), Arc::from(vec![3, 5]));
+    ///
+    /// ```
+    pub fn into_arc(this: Self) -> Arc<[T]>
+    where
+        T: Clone,
+    {
+        let this_vtable = this.vtable();
+        let other_vtable = VTableGetter::<T>::LIB_VTABLE;
+        if ptr::eq(this_vtable.0.to_raw_ptr(), other_vtable.0.to_raw_ptr())
+            || this_vtable.type_id()() == other_vtable.type_id()()
+        {
+            let this = ManuallyDrop::new(this);
+            unsafe { Arc::from_raw(ptr::slice_from_raw_parts(this.data, this.length)) }
+        } else {
+            Arc::from(this.as_slice())
+        }
+    }
+}
+
+impl<T> Default for RArcSlice<T> {
+    fn default() -> Self {
+        Self::from_arc(Arc::from(Vec::new()))
+    }
+}
+
+impl<T> Clone for RArcSlice<T> {
+    fn clone(&self) -> Self {
+        let vtable = self.vtable();
+        unsafe { vtable.increment_count()(self.erased_data(), self.length) };
+        RArcSlice {
+            data: self.data,
+            length: self.length,
+            vtable: self.vtable,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> Deref for RArcSlice<T> {
+    type Target = [T];
+
+    #[inline]
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T> AsRef<[T]> for RArcSlice<T> {
+    fn as_ref(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T> Borrow<[T]> for RArcSlice<T> {
+    fn borrow(&self) -> &[T] {
+        self
+    }
+}
+
+impl_from_rust_repr! {
+    impl[T] From<Arc<[T]>> for RArcSlice<T> {
+        fn(this){
+            RArcSlice::from_arc(this)
+        }
+    }
+}
+
+impl_into_rust_repr! {
+    impl[T] Into<Arc<[T]>> for RArcSlice<T>
+    where[
+        T: Clone,
+    ]{
+        fn(this){
+            RArcSlice::into_arc(this)
+        }
+    }
+}
+
+impl<T> From<Vec<T>> for RArcSlice<T> {
+    fn from(this: Vec<T>) -> Self {
+        Self::from_arc(this.into())
+    }
+}
+
+impl<T> From<RVec<T>> for RArcSlice<T> {
+    fn from(this: RVec<T>) -> Self {
+        Self::from_arc(this.into_vec().into())
+    }
+}
+
+impl<T> From<&[T]> for RArcSlice<T>
+where
+    T: Clone,
+{
+    fn from(this: &[T]) -> Self {
+        Self::from_arc(this.into())
+    }
+}
+
+impl<T> FromIterator<T> for RArcSlice<T> {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        Self::from_arc(iter.into_iter().collect())
+    }
+}
+
+impl<'a, T> IntoIterator for &'a RArcSlice<T> {
+    type Item = &'a T;
+
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> slice::Iter<'a, T> {
+        self.as_slice().iter()
+    }
+}
+
+slice_like_impl_cmp_traits! {
+    impl[] RArcSlice<T>,
+    where[];
+    Vec<U>,
+    [U],
+    &[U],
+    RSlice<'_, U>,
+    RVec<U>,
+}
+
+slice_like_impl_cmp_traits! {
+    impl[const N: usize] RArcSlice<T>,
+    where[];
+    [U; N],
+}
+
+shared_impls! {
+    mod = arc_slice_impls
+    new_type = RArcSlice[][T],
+    original_type = Arc,
+}
+
+impl<'de, T> Deserialize<'de> for RArcSlice<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        <Vec<T>>::deserialize(deserializer).map(Self::from)
+    }
+}
+
+impl<T> Serialize for RArcSlice<T>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.as_slice().serialize(serializer)
+    }
+}
+
+unsafe impl<T> Sync for RArcSlice<T> where T: Send + Sync {}
+
+unsafe impl<T> Send for RArcSlice<T> where T: Send + Sync {}
+
+impl<T> Drop for RArcSlice<T> {
+    fn drop(&mut self) {
+        let vtable = self.vtable();
+        unsafe { vtable.destructor()(self.erased_data(), self.length) }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// Ffi-safe equivalent of `Arc<str>`,
+/// an atomically reference-counted string slice stored as a pointer and a length.
+///
+/// This avoids the double indirection of an `RArc<RString>`.
+///
+/// # Example
+///
+/// ```
+/// use abi_stable::std_types::RArcStr;
+///
+/// use std::sync::Arc;
+///
+/// let arc: Arc<str> = Arc::from("hello");
+///
+/// // This reuses the allocation of the `Arc<str>`
+/// let string = RArcStr::from(arc);
+/// let clone = string.clone();
+///
+/// assert_eq!(&*string, "hello");
+/// assert!(RArcStr::ptr_eq(&string, &clone));
+///
+/// ```
+///
+#[repr(transparent)]
+#[derive(StableAbi)]
+pub struct RArcStr {
+    inner: RArcSlice<u8>,
+}
+
+impl RArcStr {
+    /// Converts an `Arc<str>` to an `RArcStr`, reusing its heap allocation.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RArcStr;
+    ///
+    /// use std::sync::Arc;
+    ///
+    /// let string = RArcStr::from_arc(Arc::from("world"));
+    ///
+    /// assert_eq!(string.as_str(), "world");
+    ///
+    /// ```
+    pub fn from_arc(this: Arc<str>) -> Self {
+        let bytes = unsafe { Arc::from_raw(Arc::into_raw(this) as *const [u8]) };
+        Self {
+            inner: RArcSlice::from_arc(bytes),
+        }
+    }
+
+    /// Returns the length of the string, in bytes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RArcStr;
+    ///
+    /// assert_eq!(RArcStr::from("foo").len(), 3);
+    ///
+    /// ```
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns whether the string is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RArcStr;
+    ///
+    /// assert!(RArcStr::default().is_empty());
+    /// assert!(!RArcStr::from("foo").is_empty());
+    ///
+    /// ```
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Gets a `&str` of the contents.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RArcStr;
+    ///
+    /// assert_eq!(RArcStr::from("foo").as_str(), "foo");
+    ///
+    /// ```
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        unsafe { str::from_utf8_unchecked(self.inner.as_slice()) }
+    }
+
+    /// Gets an `RStr<'_>` of the contents.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RArcStr, RStr};
+    ///
+    /// assert_eq!(RArcStr::from("foo").as_rstr(), RStr::from("foo"));
+    ///
+    /// ```
+    #[inline]
+    pub fn as_rstr(&self) -> RStr<'_> {
+        self.as_str().into()
+    }
+
+    /// Gets the number of `RArcStr` that point to the string.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RArcStr;
+    ///
+    /// let string = RArcStr::from("foo");
+    /// assert_eq!(RArcStr::strong_count(&string), 1);
+    ///
+    /// let clone = string.clone();
+    /// assert_eq!(RArcStr::strong_count(&string), 2);
+    ///
+    /// ```
+    pub fn strong_count(this: &Self) -> usize {
+        RArcSlice::strong_count(&this.inner)
+    }
+
+    /// Returns whether both `RArcStr`s point to the same allocation.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RArcStr;
+    ///
+    /// let string = RArcStr::from("foo");
+    ///
+    /// assert!(RArcStr::ptr_eq(&string, &string.clone()));
+    /// assert!(!RArcStr::ptr_eq(&string, &RArcStr::from("foo")));
+    ///
+    /// ```
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        RArcSlice::ptr_eq(&this.inner, &other.inner)
+    }
+
+    /// Converts this `RArcStr` into an `Arc<str>`
+    ///
+    /// # Allocation
+    ///
+    /// If this is invoked outside of the dynamic library/binary that created it,
+    /// it will allocate a new `Arc<str>` and copy the string into it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RArcStr;
+    ///
+    /// use std::sync::Arc;
+    ///
+    /// let string = RArcStr::from("foo");
+    ///
+    /// assert_eq!(RArcStr::into_arc(string), Arc::from("foo"));
+    ///
+    /// ```
+    pub fn into_arc(this: Self) -> Arc<str> {
+        let bytes = RArcSlice::into_arc(this.inner);
+        unsafe { Arc::from_raw(Arc::into_raw(bytes) as *const str) }
+    }
+}
+
+impl Default for RArcStr {
+    fn default() -> Self {
+        Self::from_arc(Arc::from(""))
+    }
+}
+
+impl Clone for RArcStr {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl Deref for RArcStr {
+    type Target = str;
+
+    #[inline]
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for RArcStr {
+    fn as_ref(&self) -> &str {
+        self
+    }
+}
+
+impl AsRef<[u8]> for RArcStr {
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl Borrow<str> for RArcStr {
+    fn borrow(&self) -> &str {
+        self
+    }
+}
+
+impl_from_rust_repr! {
+    impl[] From<Arc<str>> for RArcStr {
+        fn(this){
+            RArcStr::from_arc(this)
+        }
+    }
+}
+
+impl_into_rust_repr! {
+    impl[] Into<Arc<str>> for RArcStr {
+        fn(this){
+            RArcStr::into_arc(this)
+        }
+    }
+}
+
+impl From<String> for RArcStr {
+    fn from(this: String) -> Self {
+        Self::from_arc(this.into())
+    }
+}
+
+impl From<RString> for RArcStr {
+    fn from(this: RString) -> Self {
+        Self::from_arc(this.as_str().into())
+    }
+}
+
+impl From<&str> for RArcStr {
+    fn from(this: &str) -> Self {
+        Self::from_arc(this.into())
+    }
+}
+
+impl Display for RArcStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(self.as_str(), f)
+    }
+}
+
+deref_coerced_impl_cmp_traits! {
+    RArcStr;
+    coerce_to = str,
+    [
+        String,
+        str,
+        &str,
+        RStr<'_>,
+        RString,
+    ]
+}
+
+shared_impls! {
+    mod = arc_str_impls
+    new_type = RArcStr[][],
+    original_type = str,
+}
+
+impl<'de> Deserialize<'de> for RArcStr {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer).map(Self::from)
+    }
+}
+
+impl Serialize for RArcStr {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.as_str().serialize(serializer)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+#[derive(StableAbi)]
+#[repr(C)]
+#[sabi(kind(Prefix))]
+#[sabi(missing_field(panic))]
+pub(crate) struct ArcSliceVtable<T> {
+    type_id: extern "C" fn() -> UTypeId,
+    destructor: unsafe extern "C" fn(*const (), usize),
+    increment_count: unsafe extern "C" fn(*const (), usize),
+    strong_count: unsafe extern "C" fn(*const (), usize) -> usize,
+    #[sabi(last_prefix_field)]
+    is_unique: unsafe extern "C" fn(*const (), usize) -> bool,
+    _marker: NonOwningPhantom<T>,
+}
+
+/// Dummy type used to create a statically allocated constant that can only be
+/// accessed of the lifetime of T.
+struct VTableGetter<'a, T>(PhantomData<&'a T>);
+
+impl<'a, T: 'a> VTableGetter<'a, T> {
+    const DEFAULT_VTABLE: ArcSliceVtable<T> = ArcSliceVtable {
+        type_id: new_utypeid::<RArcSlice<()>>,
+        destructor: destructor_arc_slice::<T>,
+        increment_count: increment_count_arc_slice::<T>,
+        strong_count: strong_count_arc_slice::<T>,
+        is_unique: is_unique_arc_slice::<T>,
+        _marker: NonOwningPhantom::NEW,
+    };
+
+    staticref! {
+        const WM_DEFAULT: WithMetadata<ArcSliceVtable<T>> =
+            WithMetadata::new(Self::DEFAULT_VTABLE);
+    }
+
+    // The VTABLE for this type in this executable/library
+    const LIB_VTABLE: ArcSliceVtable_Ref<T> = ArcSliceVtable_Ref(Self::WM_DEFAULT.as_prefix());
+
+    #[cfg(test)]
+    staticref! {
+        const WM_FOR_TESTING: WithMetadata<ArcSliceVtable<T>> =
+            WithMetadata::new(
+                ArcSliceVtable {
+                    type_id: new_utypeid::<RArcSlice<i32>>,
+                    ..Self::DEFAULT_VTABLE
+                },
+            )
+    }
+
+    #[allow(dead_code)]
+    #[cfg(test)]
+    const LIB_VTABLE_FOR_TESTING: ArcSliceVtable_Ref<T> =
+        ArcSliceVtable_Ref(Self::WM_FOR_TESTING.as_prefix());
+}
+
+unsafe fn with_arc_ref<T, F, R>(ptr: *const (), len: usize, f: F) -> R
+where
+    F: FnOnce(&mut Arc<[T]>) -> R,
+{
+    let slice = ptr::slice_from_raw_parts(ptr as *const T, len);
+    let mut arc = ManuallyDrop::new(unsafe { Arc::from_raw(slice) });
+    f(&mut arc)
+}
+
+unsafe extern "C" fn destructor_arc_slice<T>(ptr: *const (), len: usize) {
+    extern_fn_panic_handling! {no_early_return; unsafe {
+        drop(Arc::from_raw(ptr::slice_from_raw_parts(ptr as *const T, len)));
+    }}
+}
+
+unsafe extern "C" fn increment_count_arc_slice<T>(ptr: *const (), len: usize) {
+    unsafe { with_arc_ref::<T, _, _>(ptr, len, |x| mem::forget(Arc::clone(x))) }
+}
+
+unsafe extern "C" fn strong_count_arc_slice<T>(ptr: *const (), len: usize) -> usize {
+    unsafe { with_arc_ref::<T, _, _>(ptr, len, |x| Arc::strong_count(x)) }
+}
+
+unsafe extern "C" fn is_unique_arc_slice<T>(ptr: *const (), len: usize) -> bool {
+    unsafe { with_arc_ref::<T, _, _>(ptr, len, |x| Arc::get_mut(x).is_some()) }
+}
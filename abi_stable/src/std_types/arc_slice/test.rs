@@ -0,0 +1,95 @@
+use super::*;
+
+fn _covariant_rarcslice<'a: 'b, 'b, T>(slice: RArcSlice<&'a T>) -> RArcSlice<&'b T> {
+    slice
+}
+
+#[test]
+fn from_arc_reuses_allocation() {
+    let arc: Arc<[u32]> = Arc::from(vec![3, 5, 8]);
+    let addr = arc.as_ptr();
+
+    let slice = RArcSlice::from(arc.clone());
+    assert_eq!(slice.as_ptr(), addr);
+    assert_eq!(slice, [3, 5, 8]);
+    assert_eq!(RArcSlice::strong_count(&slice), 2);
+
+    let arc2 = RArcSlice::into_arc(slice);
+    assert!(Arc::ptr_eq(&arc, &arc2));
+
+    let string: Arc<str> = Arc::from("hello");
+    let rstring = RArcStr::from(string.clone());
+    assert_eq!(rstring.as_ptr(), string.as_ptr());
+    assert_eq!(rstring, "hello");
+    assert!(Arc::ptr_eq(&RArcStr::into_arc(rstring), &string));
+}
+
+#[test]
+fn into_arc_with_other_vtable() {
+    let mut slice = RArcSlice::from(vec![RString::from("foo"), RString::from("bar")]);
+    let clone = slice.clone();
+    slice.set_vtable_for_testing();
+
+    let arc = RArcSlice::into_arc(slice);
+    assert_ne!(arc.as_ptr(), clone.as_ptr());
+    assert_eq!(&*arc, &["foo", "bar"]);
+    assert_eq!(RArcSlice::strong_count(&clone), 1);
+
+    let mut string = RArcStr::from("hello");
+    string.inner.set_vtable_for_testing();
+    assert_eq!(&*RArcStr::into_arc(string), "hello");
+}
+
+#[test]
+fn reference_counting() {
+    let mut slice = RArcSlice::from(vec![3, 5]);
+    assert_eq!(RArcSlice::strong_count(&slice), 1);
+
+    RArcSlice::get_mut(&mut slice).unwrap()[1] = 8;
+    {
+        let clone = slice.clone();
+        assert!(RArcSlice::ptr_eq(&slice, &clone));
+        assert_eq!(RArcSlice::strong_count(&slice), 2);
+        assert_eq!(RArcSlice::get_mut(&mut slice), None);
+    }
+    assert_eq!(RArcSlice::strong_count(&slice), 1);
+    assert_eq!(slice, [3, 8]);
+
+    let string = RArcStr::from("foo");
+    let clone = string.clone();
+    assert!(RArcStr::ptr_eq(&string, &clone));
+    assert_eq!(RArcStr::strong_count(&string), 2);
+}
+
+#[test]
+fn traits() {
+    let slice = RArcSlice::from(&[3, 5, 8][..]);
+    assert_eq!(slice, vec![3, 5, 8]);
+    assert_eq!(slice, RArcSlice::from(vec![3, 5, 8]));
+    let greater = RArcSlice::from(vec![4]);
+    assert!(slice < greater);
+    assert_eq!(format!("{:?}", slice), "[3, 5, 8]");
+
+    let string = RArcStr::from(String::from("hello"));
+    assert_eq!(format!("{} {:?}", string, string), r#"hello "hello""#);
+    assert_eq!(string, RString::from("hello"));
+
+    assert!(RArcSlice::<u8>::default().is_empty());
+    assert!(RArcStr::default().is_empty());
+}
+
+#[test]
+fn serde() {
+    let slice = RArcSlice::from(vec![3, 5]);
+    let json = serde_json::to_string(&slice).unwrap();
+    assert_eq!(json, "[3,5]");
+    assert_eq!(
+        serde_json::from_str::<RArcSlice<u32>>(&json).unwrap(),
+        slice
+    );
+
+    let string = RArcStr::from("foo");
+    let json = serde_json::to_string(&string).unwrap();
+    assert_eq!(json, r#""foo""#);
+    assert_eq!(serde_json::from_str::<RArcStr>(&json).unwrap(), string);
+}
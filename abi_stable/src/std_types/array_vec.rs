@@ -0,0 +1,915 @@
+//! Contains an ffi-safe vector with a fixed capacity, stored inline, and related items.
+
+use std::{
+    borrow::{Borrow, BorrowMut},
+    cmp::Ordering,
+    convert::TryFrom,
+    fmt::{self, Debug},
+    hash::{Hash, Hasher},
+    iter::{FromIterator, FusedIterator},
+    mem::{self, ManuallyDrop, MaybeUninit},
+    ops::{Deref, DerefMut},
+    ptr, slice,
+};
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::std_types::{RSlice, RSliceMut, RVec};
+
+#[cfg(all(test, not(feature = "only_new_tests")))]
+mod test;
+
+/// An ffi-safe vector which stores up to `N` elements inline,
+/// never allocating.
+///
+/// Operations that would make the length exceed the capacity panic,
+/// while the `try_*` methods return the elements that didn't fit.
+///
+/// # Example
+///
+/// ```
+/// use abi_stable::std_types::{RArrayVec, RVec};
+///
+/// let mut vec = RArrayVec::<u32, 4>::new();
+///
+/// vec.push(3);
+/// vec.push(5);
+/// vec.extend_from_slice(&[8, 13]);
+///
+/// assert!(vec.is_full());
+/// assert_eq!(vec.try_push(21), Err(21));
+///
+/// assert_eq!(vec.iter().sum::<u32>(), 29);
+/// assert_eq!(vec.into_rvec(), RVec::from(vec![3, 5, 8, 13]));
+///
+/// ```
+///
+#[repr(C)]
+#[derive(StableAbi)]
+pub struct RArrayVec<T, const N: usize> {
+    length: usize,
+    buffer: [MaybeUninit<T>; N],
+}
+
+impl<T, const N: usize> RArrayVec<T, N> {
+    /// Creates an empty `RArrayVec`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RArrayVec;
+    ///
+    /// let vec = RArrayVec::<u32, 8>::new();
+    ///
+    /// assert!(vec.is_empty());
+    /// assert_eq!(vec.capacity(), 8);
+    ///
+    /// ```
+    pub const fn new() -> Self {
+        Self {
+            length: 0,
+            // SAFETY: an array of `MaybeUninit` doesn't need to be initialized
+            buffer: unsafe { MaybeUninit::<[MaybeUninit<T>; N]>::uninit().assume_init() },
+        }
+    }
+
+    /// Returns the amount of elements in the vector.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RArrayVec;
+    ///
+    /// let vec = RArrayVec::from([3, 5, 8]);
+    ///
+    /// assert_eq!(vec.len(), 3);
+    ///
+    /// ```
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Returns whether the vector is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RArrayVec;
+    ///
+    /// let mut vec = RArrayVec::<u32, 2>::new();
+    /// assert!(vec.is_empty());
+    ///
+    /// vec.push(0);
+    /// assert!(!vec.is_empty());
+    ///
+    /// ```
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Returns whether the vector is at its capacity.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RArrayVec;
+    ///
+    /// let mut vec = RArrayVec::<u32, 2>::new();
+    /// vec.push(0);
+    /// assert!(!vec.is_full());
+    ///
+    /// vec.push(1);
+    /// assert!(vec.is_full());
+    ///
+    /// ```
+    #[inline]
+    pub const fn is_full(&self) -> bool {
+        self.length == N
+    }
+
+    /// Returns the maximum amount of elements the vector can store, which is `N`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RArrayVec;
+    ///
+    /// assert_eq!(RArrayVec::<u32, 5>::new().capacity(), 5);
+    ///
+    /// ```
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Returns the amount of elements that can be added before the vector is full.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RArrayVec;
+    ///
+    /// let mut vec = RArrayVec::<u32, 5>::new();
+    /// vec.push(0);
+    ///
+    /// assert_eq!(vec.remaining_capacity(), 4);
+    ///
+    /// ```
+    #[inline]
+    pub const fn remaining_capacity(&self) -> usize {
+        N - self.length
+    }
+
+    /// Returns a raw pointer to the start of the buffer.
+    #[inline]
+    pub const fn as_ptr(&self) -> *const T {
+        self.buffer.as_ptr() as *const T
+    }
+
+    /// Returns a mutable raw pointer to the start of the buffer.
+    #[inline]
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        self.buffer.as_mut_ptr() as *mut T
+    }
+
+    /// Creates a `&[T]` with access to all the elements of the vector.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RArrayVec;
+    ///
+    /// let vec = RArrayVec::from([3, 5, 8]);
+    ///
+    /// assert_eq!(vec.as_slice(), &[3, 5, 8]);
+    ///
+    /// ```
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.as_ptr(), self.length) }
+    }
+
+    /// Creates a `&mut [T]` with access to all the elements of the vector.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RArrayVec;
+    ///
+    /// let mut vec = RArrayVec::from([3, 5, 8]);
+    /// vec.as_mut_slice()[0] = 13;
+    ///
+    /// assert_eq!(vec.as_slice(), &[13, 5, 8]);
+    ///
+    /// ```
+    #[inline]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        let len = self.length;
+        unsafe { slice::from_raw_parts_mut(self.as_mut_ptr(), len) }
+    }
+
+    /// Creates an `RSlice<'_, T>` with access to all the elements of the vector.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RArrayVec, RSlice};
+    ///
+    /// let vec = RArrayVec::from([3, 5, 8]);
+    ///
+    /// assert_eq!(vec.as_rslice(), RSlice::from_slice(&[3, 5, 8]));
+    ///
+    /// ```
+    #[inline]
+    pub fn as_rslice(&self) -> RSlice<'_, T> {
+        self.as_slice().into()
+    }
+
+    /// Creates an `RSliceMut<'_, T>` with access to all the elements of the vector.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RArrayVec, RSliceMut};
+    ///
+    /// let mut vec = RArrayVec::from([3, 5, 8]);
+    ///
+    /// assert_eq!(vec.as_mut_rslice(), RSliceMut::from_mut_slice(&mut [3, 5, 8]));
+    ///
+    /// ```
+    #[inline]
+    pub fn as_mut_rslice(&mut self) -> RSliceMut<'_, T> {
+        self.as_mut_slice().into()
+    }
+
+    /// Sets the length of the vector.
+    ///
+    /// # Safety
+    ///
+    /// `new_len` must be less than or equal to `N`,
+    /// and the elements in `old_len..new_len` must be initialized.
+    #[inline]
+    pub unsafe fn set_len(&mut self, new_len: usize) {
+        self.length = new_len;
+    }
+
+    /// Appends `value` at the end of the vector.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the vector is full.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RArrayVec;
+    ///
+    /// let mut vec = RArrayVec::<u32, 4>::new();
+    ///
+    /// vec.push(3);
+    /// vec.push(5);
+    ///
+    /// assert_eq!(vec.as_slice(), &[3, 5]);
+    ///
+    /// ```
+    pub fn push(&mut self, value: T) {
+        if self.try_push(value).is_err() {
+            panic!("cannot push into a full RArrayVec<_, {}>", N);
+        }
+    }
+
+    /// Appends `value` at the end of the vector,
+    /// returning it back if the vector is full.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RArrayVec;
+    ///
+    /// let mut vec = RArrayVec::<u32, 1>::new();
+    ///
+    /// assert_eq!(vec.try_push(3), Ok(()));
+    /// assert_eq!(vec.try_push(5), Err(5));
+    ///
+    /// assert_eq!(vec.as_slice(), &[3]);
+    ///
+    /// ```
+    pub fn try_push(&mut self, value: T) -> Result<(), T> {
+        match self.buffer.get_mut(self.length) {
+            Some(slot) => {
+                *slot = MaybeUninit::new(value);
+                self.length += 1;
+                Ok(())
+            }
+            None => Err(value),
+        }
+    }
+
+    /// Removes the last element of the vector, returning it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RArrayVec;
+    ///
+    /// let mut vec = RArrayVec::from([3, 5]);
+    ///
+    /// assert_eq!(vec.pop(), Some(5));
+    /// assert_eq!(vec.pop(), Some(3));
+    /// assert_eq!(vec.pop(), None);
+    ///
+    /// ```
+    pub fn pop(&mut self) -> Option<T> {
+        if self.length == 0 {
+            None
+        } else {
+            self.length -= 1;
+            unsafe { Some(ptr::read(self.as_ptr().add(self.length))) }
+        }
+    }
+
+    /// Inserts `value` at the `index` position,
+    /// shifting all the elements after it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > self.len()`, or the vector is full.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RArrayVec;
+    ///
+    /// let mut vec = RArrayVec::<u32, 4>::from_iter([3, 5]);
+    ///
+    /// vec.insert(1, 8);
+    /// vec.insert(0, 13);
+    ///
+    /// assert_eq!(vec.as_slice(), &[13, 3, 8, 5]);
+    ///
+    /// ```
+    pub fn insert(&mut self, index: usize, value: T) {
+        let len = self.length;
+        assert!(
+            index <= len,
+            "index out of bounds: the len is {} but the index is {}",
+            len,
+            index,
+        );
+        assert!(len < N, "cannot insert into a full RArrayVec<_, {}>", N);
+        unsafe {
+            let p = self.as_mut_ptr().add(index);
+            ptr::copy(p, p.add(1), len - index);
+            ptr::write(p, value);
+            self.length = len + 1;
+        }
+    }
+
+    /// Removes the element at the `index` position,
+    /// shifting all the elements after it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index >= self.len()`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RArrayVec;
+    ///
+    /// let mut vec = RArrayVec::from([3, 5, 8, 13]);
+    ///
+    /// assert_eq!(vec.remove(1), 5);
+    /// assert_eq!(vec.as_slice(), &[3, 8, 13]);
+    ///
+    /// ```
+    pub fn remove(&mut self, index: usize) -> T {
+        let len = self.length;
+        assert!(
+            index < len,
+            "index out of bounds: the len is {} but the index is {}",
+            len,
+            index,
+        );
+        unsafe {
+            self.length = len - 1;
+            let p = self.as_mut_ptr().add(index);
+            let ret = ptr::read(p);
+            ptr::copy(p.add(1), p, len - index - 1);
+            ret
+        }
+    }
+
+    /// Removes the element at the `index` position,
+    /// replacing it with the last element.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index >= self.len()`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RArrayVec;
+    ///
+    /// let mut vec = RArrayVec::from([3, 5, 8, 13]);
+    ///
+    /// assert_eq!(vec.swap_remove(0), 3);
+    /// assert_eq!(vec.as_slice(), &[13, 5, 8]);
+    ///
+    /// ```
+    pub fn swap_remove(&mut self, index: usize) -> T {
+        let len = self.length;
+        assert!(
+            index < len,
+            "index out of bounds: the len is {} but the index is {}",
+            len,
+            index,
+        );
+        self.as_mut_slice().swap(index, len - 1);
+        self.pop().unwrap()
+    }
+
+    /// Shortens the vector to `len` elements, dropping the rest.
+    ///
+    /// This does nothing if `len >= self.len()`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RArrayVec;
+    ///
+    /// let mut vec = RArrayVec::from([3, 5, 8, 13]);
+    ///
+    /// vec.truncate(2);
+    /// assert_eq!(vec.as_slice(), &[3, 5]);
+    ///
+    /// ```
+    pub fn truncate(&mut self, len: usize) {
+        let old_len = self.length;
+        if len < old_len {
+            unsafe {
+                // Setting the length first in case that dropping an element panics.
+                self.length = len;
+                let tail = ptr::slice_from_raw_parts_mut(self.as_mut_ptr().add(len), old_len - len);
+                ptr::drop_in_place(tail);
+            }
+        }
+    }
+
+    /// Removes all the elements of the vector.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RArrayVec;
+    ///
+    /// let mut vec = RArrayVec::from([3, 5, 8, 13]);
+    ///
+    /// vec.clear();
+    /// assert!(vec.is_empty());
+    ///
+    /// ```
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    /// Retains only the elements for which `pred` returns true.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RArrayVec;
+    ///
+    /// let mut vec = RArrayVec::from([3, 5, 8, 13]);
+    ///
+    /// vec.retain(|x| x % 2 == 1);
+    /// assert_eq!(vec.as_slice(), &[3, 5, 13]);
+    ///
+    /// ```
+    pub fn retain<F>(&mut self, mut pred: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let mut kept = 0;
+        for i in 0..self.length {
+            if pred(&self.as_slice()[i]) {
+                self.as_mut_slice().swap(kept, i);
+                kept += 1;
+            }
+        }
+        self.truncate(kept);
+    }
+
+    /// Appends clones of all the elements of `slice` at the end of the vector.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the elements don't fit in the vector,
+    /// in which case it is left unmodified.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RArrayVec;
+    ///
+    /// let mut vec = RArrayVec::<u32, 4>::new();
+    ///
+    /// vec.extend_from_slice(&[3, 5]);
+    /// vec.extend_from_slice(&[8]);
+    ///
+    /// assert_eq!(vec.as_slice(), &[3, 5, 8]);
+    ///
+    /// ```
+    pub fn extend_from_slice(&mut self, slice: &[T])
+    where
+        T: Clone,
+    {
+        assert!(
+            slice.len() <= self.remaining_capacity(),
+            "cannot extend RArrayVec<_, {}> of length {} with {} elements",
+            N,
+            self.length,
+            slice.len(),
+        );
+        for elem in slice {
+            self.push(elem.clone());
+        }
+    }
+
+    /// Converts this `RArrayVec` into an `RVec`, moving the elements to the heap.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RArrayVec, RVec};
+    ///
+    /// let vec = RArrayVec::from([3, 5, 8]);
+    ///
+    /// assert_eq!(vec.into_rvec(), RVec::from(vec![3, 5, 8]));
+    ///
+    /// ```
+    pub fn into_rvec(self) -> RVec<T> {
+        let mut rvec = RVec::with_capacity(self.length);
+        rvec.extend(self);
+        rvec
+    }
+}
+
+impl<T, const N: usize> Drop for RArrayVec<T, N> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T, const N: usize> Default for RArrayVec<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> Clone for RArrayVec<T, N>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T, const N: usize> Deref for RArrayVec<T, N> {
+    type Target = [T];
+
+    #[inline]
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T, const N: usize> DerefMut for RArrayVec<T, N> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<T, const N: usize> AsRef<[T]> for RArrayVec<T, N> {
+    fn as_ref(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T, const N: usize> AsMut<[T]> for RArrayVec<T, N> {
+    fn as_mut(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+impl<T, const N: usize> Borrow<[T]> for RArrayVec<T, N> {
+    fn borrow(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T, const N: usize> BorrowMut<[T]> for RArrayVec<T, N> {
+    fn borrow_mut(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+impl<T, const N: usize> Debug for RArrayVec<T, N>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt(self.as_slice(), f)
+    }
+}
+
+impl<T, const N: usize> Eq for RArrayVec<T, N> where T: Eq {}
+
+impl<T, const N: usize> PartialEq for RArrayVec<T, N>
+where
+    T: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<T, const N: usize> Ord for RArrayVec<T, N>
+where
+    T: Ord,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_slice().cmp(other.as_slice())
+    }
+}
+
+impl<T, const N: usize> PartialOrd for RArrayVec<T, N>
+where
+    T: PartialOrd,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.as_slice().partial_cmp(other.as_slice())
+    }
+}
+
+impl<T, const N: usize> Hash for RArrayVec<T, N>
+where
+    T: Hash,
+{
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        self.as_slice().hash(state)
+    }
+}
+
+slice_like_impl_cmp_traits! {
+    impl[const N: usize] RArrayVec<T, N>,
+    where[];
+    Vec<U>,
+    [U],
+    &[U],
+    &mut [U],
+    RVec<U>,
+    RSlice<'_, U>,
+    RSliceMut<'_, U>,
+}
+
+slice_like_impl_cmp_traits! {
+    impl[const N: usize, const M: usize] RArrayVec<T, N>,
+    where[];
+    [U; M],
+}
+
+impl<T, const N: usize> From<[T; N]> for RArrayVec<T, N> {
+    fn from(array: [T; N]) -> Self {
+        let array = ManuallyDrop::new(array);
+        Self {
+            length: N,
+            buffer: unsafe { mem::transmute_copy::<[T; N], [MaybeUninit<T>; N]>(&array) },
+        }
+    }
+}
+
+/// Fails if the `RVec` is longer than `N`, returning it back.
+impl<T, const N: usize> TryFrom<RVec<T>> for RArrayVec<T, N> {
+    type Error = RVec<T>;
+
+    fn try_from(vec: RVec<T>) -> Result<Self, RVec<T>> {
+        if vec.len() <= N {
+            Ok(vec.into_iter().collect())
+        } else {
+            Err(vec)
+        }
+    }
+}
+
+impl<T, const N: usize> From<RArrayVec<T, N>> for RVec<T> {
+    fn from(this: RArrayVec<T, N>) -> RVec<T> {
+        this.into_rvec()
+    }
+}
+
+impl<T, const N: usize> From<RArrayVec<T, N>> for Vec<T> {
+    fn from(this: RArrayVec<T, N>) -> Vec<T> {
+        this.into_iter().collect()
+    }
+}
+
+/// # Panics
+///
+/// Panics if the iterator yields more than `N` elements.
+impl<T, const N: usize> FromIterator<T> for RArrayVec<T, N> {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut this = Self::new();
+        this.extend(iter);
+        this
+    }
+}
+
+/// # Panics
+///
+/// Panics if the vector can't fit all the elements of the iterator.
+impl<T, const N: usize> Extend<T> for RArrayVec<T, N> {
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        iter.into_iter().for_each(|x| self.push(x));
+    }
+}
+
+impl<'a, T, const N: usize> Extend<&'a T> for RArrayVec<T, N>
+where
+    T: 'a + Copy,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = &'a T>,
+    {
+        iter.into_iter().for_each(|x| self.push(*x));
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a RArrayVec<T, N> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a mut RArrayVec<T, N> {
+    type Item = &'a mut T;
+    type IntoIter = slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T, const N: usize> IntoIterator for RArrayVec<T, N> {
+    type Item = T;
+    type IntoIter = IntoIter<T, N>;
+
+    fn into_iter(self) -> IntoIter<T, N> {
+        let this = ManuallyDrop::new(self);
+        IntoIter {
+            start: 0,
+            end: this.length,
+            buffer: unsafe { ptr::read(&this.buffer) },
+        }
+    }
+}
+
+impl<T, const N: usize> Serialize for RArrayVec<T, N>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.as_slice().serialize(serializer)
+    }
+}
+
+impl<'de, T, const N: usize> Deserialize<'de> for RArrayVec<T, N>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let vec = Vec::<T>::deserialize(deserializer)?;
+        if vec.len() <= N {
+            Ok(vec.into_iter().collect())
+        } else {
+            Err(de::Error::invalid_length(
+                vec.len(),
+                &&*format!("at most {} elements", N),
+            ))
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// An iterator that yields the elements of an [`RArrayVec`] by value.
+pub struct IntoIter<T, const N: usize> {
+    start: usize,
+    end: usize,
+    buffer: [MaybeUninit<T>; N],
+}
+
+impl<T, const N: usize> IntoIter<T, N> {
+    /// Returns a slice over the elements that haven't been yielded yet.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RArrayVec;
+    ///
+    /// let mut iter = RArrayVec::from([0, 1, 2, 3]).into_iter();
+    ///
+    /// assert_eq!(iter.next(), Some(0));
+    /// assert_eq!(iter.next_back(), Some(3));
+    /// assert_eq!(iter.as_slice(), &[1, 2]);
+    ///
+    /// ```
+    pub fn as_slice(&self) -> &[T] {
+        unsafe {
+            let start = (self.buffer.as_ptr() as *const T).add(self.start);
+            slice::from_raw_parts(start, self.end - self.start)
+        }
+    }
+
+    /// Returns a mutable slice over the elements that haven't been yielded yet.
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe {
+            let start = (self.buffer.as_mut_ptr() as *mut T).add(self.start);
+            slice::from_raw_parts_mut(start, self.end - self.start)
+        }
+    }
+}
+
+impl<T, const N: usize> Iterator for IntoIter<T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.start == self.end {
+            None
+        } else {
+            self.start += 1;
+            unsafe { Some(self.buffer[self.start - 1].as_ptr().read()) }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.start;
+        (len, Some(len))
+    }
+}
+
+impl<T, const N: usize> DoubleEndedIterator for IntoIter<T, N> {
+    fn next_back(&mut self) -> Option<T> {
+        if self.start == self.end {
+            None
+        } else {
+            self.end -= 1;
+            unsafe { Some(self.buffer[self.end].as_ptr().read()) }
+        }
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for IntoIter<T, N> {}
+
+impl<T, const N: usize> FusedIterator for IntoIter<T, N> {}
+
+impl<T, const N: usize> Debug for IntoIter<T, N>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("IntoIter").field(&self.as_slice()).finish()
+    }
+}
+
+impl<T, const N: usize> Drop for IntoIter<T, N> {
+    fn drop(&mut self) {
+        let remaining: *mut [T] = self.as_mut_slice();
+        self.start = self.end;
+        unsafe { ptr::drop_in_place(remaining) }
+    }
+}
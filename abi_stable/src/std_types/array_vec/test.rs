@@ -0,0 +1,124 @@
+use super::*;
+
+use std::{cell::Cell, rc::Rc};
+
+use crate::std_types::RString;
+
+#[test]
+fn push_pop_and_capacity() {
+    let mut vec = RArrayVec::<RString, 3>::new();
+    assert_eq!(vec.capacity(), 3);
+
+    for word in ["foo", "bar", "baz"] {
+        vec.push(word.into());
+    }
+    assert!(vec.is_full());
+    assert_eq!(vec.remaining_capacity(), 0);
+    assert_eq!(vec.try_push("qux".into()), Err(RString::from("qux")));
+
+    assert_eq!(vec.pop().unwrap(), "baz");
+    assert_eq!(vec, ["foo", "bar"]);
+}
+
+#[test]
+#[should_panic]
+fn push_when_full() {
+    let mut vec = RArrayVec::from([3, 5]);
+    vec.push(8);
+}
+
+#[test]
+fn insert_remove() {
+    let mut vec = RArrayVec::<u32, 6>::new();
+    vec.insert(0, 5);
+    vec.insert(0, 3);
+    vec.insert(2, 13);
+    vec.insert(2, 8);
+    assert_eq!(vec, [3, 5, 8, 13]);
+
+    assert_eq!(vec.remove(1), 5);
+    assert_eq!(vec, [3, 8, 13]);
+
+    assert_eq!(vec.swap_remove(0), 3);
+    assert_eq!(vec, [13, 8]);
+
+    vec.extend_from_slice(&[21, 34, 55]);
+    vec.retain(|x| x % 2 == 1);
+    assert_eq!(vec, [13, 21, 55]);
+}
+
+#[test]
+fn drops_elements() {
+    let count = Rc::new(());
+    let vec = (0..4).map(|_| count.clone()).collect::<RArrayVec<_, 4>>();
+    assert_eq!(Rc::strong_count(&count), 5);
+
+    let mut vec2 = vec.clone();
+    assert_eq!(Rc::strong_count(&count), 9);
+    vec2.truncate(1);
+    assert_eq!(Rc::strong_count(&count), 6);
+    drop(vec2);
+    assert_eq!(Rc::strong_count(&count), 5);
+
+    let mut iter = vec.into_iter();
+    iter.next();
+    iter.next_back();
+    assert_eq!(Rc::strong_count(&count), 3);
+    drop(iter);
+    assert_eq!(Rc::strong_count(&count), 1);
+}
+
+#[test]
+fn truncate_with_panicking_drop() {
+    struct PanicOnDrop<'a>(&'a Cell<u32>, bool);
+
+    impl Drop for PanicOnDrop<'_> {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+            if self.1 {
+                panic!()
+            }
+        }
+    }
+
+    let count = Cell::new(0);
+    let mut vec = RArrayVec::<_, 3>::new();
+    vec.push(PanicOnDrop(&count, false));
+    vec.push(PanicOnDrop(&count, true));
+    vec.push(PanicOnDrop(&count, false));
+
+    let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| vec.truncate(1)));
+    assert!(res.is_err());
+    assert_eq!(vec.len(), 1);
+    assert_eq!(count.get(), 2);
+
+    drop(vec);
+    assert_eq!(count.get(), 3);
+}
+
+#[test]
+fn rvec_conversions() {
+    let vec = RArrayVec::<u32, 4>::try_from(RVec::from(vec![3, 5, 8])).unwrap();
+    assert_eq!(vec, [3, 5, 8]);
+
+    let too_long = RVec::from(vec![0, 1, 2, 3, 4]);
+    assert_eq!(
+        RArrayVec::<u32, 4>::try_from(too_long.clone()),
+        Err(too_long)
+    );
+
+    assert_eq!(vec.clone().into_rvec(), RVec::from(vec![3, 5, 8]));
+    assert_eq!(Vec::from(vec), vec![3, 5, 8]);
+}
+
+#[test]
+fn serde_roundtrip() {
+    let vec = RArrayVec::from([3, 5, 8]);
+    let json = serde_json::to_string(&vec).unwrap();
+    assert_eq!(json, "[3,5,8]");
+
+    let deserialized: RArrayVec<u32, 4> = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized, [3, 5, 8]);
+
+    serde_json::from_str::<RArrayVec<u32, 2>>(&json).unwrap_err();
+}
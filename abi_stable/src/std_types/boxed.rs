@@ -145,11 +145,11 @@ mod private {
         }
 
         #[inline(always)]
-        pub(super) const fn data(&self) -> *mut T {
+        pub(crate) const fn data(&self) -> *mut T {
             self.data.as_ptr()
         }
         #[inline(always)]
-        pub(super) fn data_mut(&mut self) -> *mut T {
+        pub(crate) fn data_mut(&mut self) -> *mut T {
             self.data.as_ptr()
         }
 
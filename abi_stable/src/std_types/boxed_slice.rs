@@ -0,0 +1,836 @@
+//! Contains the ffi-safe equivalents of `Box<[T]>` and `Box<str>`.
+
+use std::{
+    borrow::{Borrow, BorrowMut},
+    fmt::{self, Display},
+    iter::FromIterator,
+    marker::PhantomData,
+    mem::ManuallyDrop,
+    ops::{Deref, DerefMut},
+    ptr::{self, NonNull},
+    slice, str,
+};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{
+    marker_type::NonOwningPhantom,
+    pointer_trait::CallReferentDrop,
+    prefix_type::WithMetadata,
+    std_types::{
+        utypeid::{new_utypeid, UTypeId},
+        RSlice, RSliceMut, RStr, RString, RVec,
+    },
+};
+
+#[cfg(all(test, not(feature = "only_new_tests")))]
+mod test;
+
+/// Ffi-safe equivalent of `Box<[T]>`,
+/// a heap allocated slice stored as a pointer and a length.
+///
+/// Unlike `RVec<T>`, this does not store a capacity,
+/// and its length can't change after construction.
+///
+/// # Example
+///
+/// ```
+/// use abi_stable::std_types::RBoxSlice;
+///
+/// let boxed: Box<[u32]> = vec![3, 5, 8].into_boxed_slice();
+///
+/// // This reuses the allocation of the `Box<[u32]>`
+/// let mut slice = RBoxSlice::from(boxed);
+///
+/// slice[0] = 1;
+///
+/// assert_eq!(slice.len(), 3);
+/// assert_eq!(&slice[..], &[1, 5, 8]);
+///
+/// ```
+///
+#[repr(C)]
+#[derive(StableAbi)]
+pub struct RBoxSlice<T> {
+    data: NonNull<T>,
+    length: usize,
+    vtable: BoxSliceVtable_Ref<T>,
+    _marker: PhantomData<T>,
+}
+
+impl<T> RBoxSlice<T> {
+    /// Converts a `Box<[T]>` to an `RBoxSlice<T>`, reusing its heap allocation.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBoxSlice;
+    ///
+    /// let boxed: Box<[u8]> = Box::new([13, 21]);
+    /// let slice = RBoxSlice::from_box(boxed);
+    ///
+    /// assert_eq!(slice.as_slice(), &[13, 21]);
+    ///
+    /// ```
+    pub fn from_box(this: Box<[T]>) -> Self {
+        let length = this.len();
+        let data = Box::into_raw(this) as *mut T;
+        RBoxSlice {
+            data: unsafe { NonNull::new_unchecked(data) },
+            length,
+            vtable: VTableGetter::<T>::LIB_VTABLE,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Converts a `Vec<T>` to an `RBoxSlice<T>`,
+    /// this only reallocates if the `Vec<T>` has excess capacity.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBoxSlice;
+    ///
+    /// let slice = RBoxSlice::from_vec(vec!["foo", "bar"]);
+    ///
+    /// assert_eq!(slice.as_slice(), &["foo", "bar"]);
+    ///
+    /// ```
+    pub fn from_vec(this: Vec<T>) -> Self {
+        Self::from_box(this.into_boxed_slice())
+    }
+
+    #[allow(dead_code)]
+    #[cfg(test)]
+    pub(super) fn set_vtable_for_testing(&mut self) {
+        self.vtable = VTableGetter::<T>::LIB_VTABLE_FOR_TESTING;
+    }
+
+    /// Returns the length of the slice.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBoxSlice;
+    ///
+    /// assert_eq!(RBoxSlice::<u32>::default().len(), 0);
+    /// assert_eq!(RBoxSlice::from(vec![3, 5]).len(), 2);
+    ///
+    /// ```
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Returns whether the slice is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBoxSlice;
+    ///
+    /// assert!(RBoxSlice::<u32>::default().is_empty());
+    /// assert!(!RBoxSlice::from(vec![3, 5]).is_empty());
+    ///
+    /// ```
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Gets a slice of the contents.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBoxSlice;
+    ///
+    /// let slice = RBoxSlice::from(vec![3, 5]);
+    ///
+    /// assert_eq!(slice.as_slice(), &[3, 5]);
+    ///
+    /// ```
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.data.as_ptr(), self.length) }
+    }
+
+    /// Gets a mutable slice of the contents.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBoxSlice;
+    ///
+    /// let mut slice = RBoxSlice::from(vec![3, 5]);
+    /// slice.as_mut_slice().reverse();
+    ///
+    /// assert_eq!(slice.as_slice(), &[5, 3]);
+    ///
+    /// ```
+    #[inline]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.data.as_ptr(), self.length) }
+    }
+
+    /// Gets an `RSlice<'_, T>` of the contents.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RBoxSlice, RSlice};
+    ///
+    /// let slice = RBoxSlice::from(vec![3, 5]);
+    ///
+    /// assert_eq!(slice.as_rslice(), RSlice::from_slice(&[3, 5]));
+    ///
+    /// ```
+    #[inline]
+    pub fn as_rslice(&self) -> RSlice<'_, T> {
+        self.as_slice().into()
+    }
+
+    /// Gets an `RSliceMut<'_, T>` of the contents.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RBoxSlice, RSliceMut};
+    ///
+    /// let mut slice = RBoxSlice::from(vec![3, 5]);
+    ///
+    /// assert_eq!(slice.as_mut_rslice(), RSliceMut::from_mut_slice(&mut [3, 5]));
+    ///
+    /// ```
+    #[inline]
+    pub fn as_mut_rslice(&mut self) -> RSliceMut<'_, T> {
+        self.as_mut_slice().into()
+    }
+
+    /// Converts this `RBoxSlice<T>` into a `Box<[T]>`
+    ///
+    /// # Allocation
+    ///
+    /// If this is invoked outside of the dynamic library/binary that created it,
+    /// it will allocate a new `Box<[T]>` and move the elements into it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBoxSlice;
+    ///
+    /// let slice = RBoxSlice::from(vec![3, 5]);
+    ///
+    /// assert_eq!(slice.into_boxed_slice(), vec![3, 5].into_boxed_slice());
+    ///
+    /// ```
+    pub fn into_boxed_slice(self) -> Box<[T]> {
+        let this = ManuallyDrop::new(self);
+        let this_vtable = this.vtable;
+        let other_vtable = VTableGetter::<T>::LIB_VTABLE;
+        unsafe {
+            if ptr::eq(this_vtable.0.to_raw_ptr(), other_vtable.0.to_raw_ptr())
+                || this_vtable.type_id()() == other_vtable.type_id()()
+            {
+                let slice = ptr::slice_from_raw_parts_mut(this.data.as_ptr(), this.length);
+                Box::from_raw(slice)
+            } else {
+                let mut vec = Vec::<T>::with_capacity(this.length);
+                ptr::copy_nonoverlapping(this.data.as_ptr(), vec.as_mut_ptr(), this.length);
+                vec.set_len(this.length);
+                // The elements were moved into `vec`,so this only deallocates the slice.
+                (this_vtable.destructor())(
+                    this.data.as_ptr() as *mut (),
+                    this.length,
+                    CallReferentDrop::No,
+                );
+                vec.into_boxed_slice()
+            }
+        }
+    }
+
+    /// Converts this `RBoxSlice<T>` into a `Vec<T>`
+    ///
+    /// # Allocation
+    ///
+    /// If this is invoked outside of the dynamic library/binary that created it,
+    /// it will allocate a new `Vec<T>` and move the elements into it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBoxSlice;
+    ///
+    /// let slice = RBoxSlice::from(vec![3, 5]);
+    ///
+    /// assert_eq!(slice.into_vec(), vec![3, 5]);
+    ///
+    /// ```
+    pub fn into_vec(self) -> Vec<T> {
+        self.into_boxed_slice().into_vec()
+    }
+
+    /// Converts this `RBoxSlice<T>` into an `RVec<T>`
+    ///
+    /// # Allocation
+    ///
+    /// If this is invoked outside of the dynamic library/binary that created it,
+    /// it will allocate a new `RVec<T>` and move the elements into it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RBoxSlice, RVec};
+    ///
+    /// let slice = RBoxSlice::from(vec![3, 5]);
+    ///
+    /// assert_eq!(slice.into_rvec(), RVec::from(vec![3, 5]));
+    ///
+    /// ```
+    pub fn into_rvec(self) -> RVec<T> {
+        self.into_vec().into()
+    }
+}
+
+impl<T> Default for RBoxSlice<T> {
+    fn default() -> Self {
+        Self::from_vec(Vec::new())
+    }
+}
+
+impl<T> Clone for RBoxSlice<T>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        self.as_slice().into()
+    }
+}
+
+impl<T> Deref for RBoxSlice<T> {
+    type Target = [T];
+
+    #[inline]
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T> DerefMut for RBoxSlice<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<T> AsRef<[T]> for RBoxSlice<T> {
+    fn as_ref(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T> AsMut<[T]> for RBoxSlice<T> {
+    fn as_mut(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+impl<T> Borrow<[T]> for RBoxSlice<T> {
+    fn borrow(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T> BorrowMut<[T]> for RBoxSlice<T> {
+    fn borrow_mut(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+impl_from_rust_repr! {
+    impl[T] From<Box<[T]>> for RBoxSlice<T> {
+        fn(this){
+            RBoxSlice::from_box(this)
+        }
+    }
+}
+
+impl_into_rust_repr! {
+    impl[T] Into<Box<[T]>> for RBoxSlice<T> {
+        fn(this){
+            this.into_boxed_slice()
+        }
+    }
+}
+
+impl<T> From<Vec<T>> for RBoxSlice<T> {
+    fn from(this: Vec<T>) -> Self {
+        Self::from_vec(this)
+    }
+}
+
+impl<T> From<RVec<T>> for RBoxSlice<T> {
+    fn from(this: RVec<T>) -> Self {
+        Self::from_vec(this.into_vec())
+    }
+}
+
+impl<T> From<&[T]> for RBoxSlice<T>
+where
+    T: Clone,
+{
+    fn from(this: &[T]) -> Self {
+        Self::from_vec(this.to_vec())
+    }
+}
+
+impl<T> From<RBoxSlice<T>> for RVec<T> {
+    fn from(this: RBoxSlice<T>) -> Self {
+        this.into_rvec()
+    }
+}
+
+impl<T> FromIterator<T> for RBoxSlice<T> {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        Self::from_vec(iter.into_iter().collect())
+    }
+}
+
+impl<'a, T> IntoIterator for &'a RBoxSlice<T> {
+    type Item = &'a T;
+
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> slice::Iter<'a, T> {
+        self.as_slice().iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut RBoxSlice<T> {
+    type Item = &'a mut T;
+
+    type IntoIter = slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> slice::IterMut<'a, T> {
+        self.as_mut_slice().iter_mut()
+    }
+}
+
+slice_like_impl_cmp_traits! {
+    impl[] RBoxSlice<T>,
+    where[];
+    Vec<U>,
+    [U],
+    &[U],
+    RSlice<'_, U>,
+    RVec<U>,
+}
+
+slice_like_impl_cmp_traits! {
+    impl[const N: usize] RBoxSlice<T>,
+    where[];
+    [U; N],
+}
+
+shared_impls! {
+    mod = boxed_slice_impls
+    new_type = RBoxSlice[][T],
+    original_type = Box,
+}
+
+impl<'de, T> Deserialize<'de> for RBoxSlice<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        <Vec<T>>::deserialize(deserializer).map(Self::from_vec)
+    }
+}
+
+impl<T> Serialize for RBoxSlice<T>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.as_slice().serialize(serializer)
+    }
+}
+
+unsafe impl<T: Send> Send for RBoxSlice<T> {}
+unsafe impl<T: Sync> Sync for RBoxSlice<T> {}
+
+impl<T> Drop for RBoxSlice<T> {
+    fn drop(&mut self) {
+        unsafe {
+            let destructor = self.vtable.destructor();
+            destructor(
+                self.data.as_ptr() as *mut (),
+                self.length,
+                CallReferentDrop::Yes,
+            );
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// Ffi-safe equivalent of `Box<str>`,
+/// a heap allocated string slice stored as a pointer and a length.
+///
+/// # Example
+///
+/// ```
+/// use abi_stable::std_types::RBoxStr;
+///
+/// let boxed: Box<str> = "hello".into();
+///
+/// // This reuses the allocation of the `Box<str>`
+/// let string = RBoxStr::from(boxed);
+///
+/// assert_eq!(&*string, "hello");
+/// assert_eq!(string.to_uppercase(), "HELLO");
+///
+/// ```
+///
+#[repr(transparent)]
+#[derive(StableAbi)]
+pub struct RBoxStr {
+    inner: RBoxSlice<u8>,
+}
+
+impl RBoxStr {
+    /// Converts a `Box<str>` to an `RBoxStr`, reusing its heap allocation.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBoxStr;
+    ///
+    /// let string = RBoxStr::from_box(Box::from("world"));
+    ///
+    /// assert_eq!(string.as_str(), "world");
+    ///
+    /// ```
+    pub fn from_box(this: Box<str>) -> Self {
+        Self {
+            inner: RBoxSlice::from_box(this.into_boxed_bytes()),
+        }
+    }
+
+    /// Returns the length of the string, in bytes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBoxStr;
+    ///
+    /// assert_eq!(RBoxStr::from("foo").len(), 3);
+    ///
+    /// ```
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns whether the string is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBoxStr;
+    ///
+    /// assert!(RBoxStr::default().is_empty());
+    /// assert!(!RBoxStr::from("foo").is_empty());
+    ///
+    /// ```
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Gets a `&str` of the contents.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBoxStr;
+    ///
+    /// assert_eq!(RBoxStr::from("foo").as_str(), "foo");
+    ///
+    /// ```
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        unsafe { str::from_utf8_unchecked(self.inner.as_slice()) }
+    }
+
+    /// Gets a `&mut str` of the contents.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBoxStr;
+    ///
+    /// let mut string = RBoxStr::from("foo");
+    /// string.as_mut_str().make_ascii_uppercase();
+    ///
+    /// assert_eq!(string.as_str(), "FOO");
+    ///
+    /// ```
+    #[inline]
+    pub fn as_mut_str(&mut self) -> &mut str {
+        unsafe { str::from_utf8_unchecked_mut(self.inner.as_mut_slice()) }
+    }
+
+    /// Gets an `RStr<'_>` of the contents.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RBoxStr, RStr};
+    ///
+    /// assert_eq!(RBoxStr::from("foo").as_rstr(), RStr::from("foo"));
+    ///
+    /// ```
+    #[inline]
+    pub fn as_rstr(&self) -> RStr<'_> {
+        self.as_str().into()
+    }
+
+    /// Converts this `RBoxStr` into a `Box<str>`
+    ///
+    /// # Allocation
+    ///
+    /// If this is invoked outside of the dynamic library/binary that created it,
+    /// it will allocate a new `Box<str>` and copy the string into it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBoxStr;
+    ///
+    /// let string = RBoxStr::from("foo");
+    ///
+    /// assert_eq!(string.into_boxed_str(), Box::from("foo"));
+    ///
+    /// ```
+    pub fn into_boxed_str(self) -> Box<str> {
+        unsafe { str::from_boxed_utf8_unchecked(self.inner.into_boxed_slice()) }
+    }
+
+    /// Converts this `RBoxStr` into a `String`
+    ///
+    /// # Allocation
+    ///
+    /// If this is invoked outside of the dynamic library/binary that created it,
+    /// it will allocate a new `String` and copy the string into it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBoxStr;
+    ///
+    /// let string = RBoxStr::from("foo");
+    ///
+    /// assert_eq!(string.into_string(), "foo");
+    ///
+    /// ```
+    pub fn into_string(self) -> String {
+        self.into_boxed_str().into_string()
+    }
+}
+
+impl Default for RBoxStr {
+    fn default() -> Self {
+        Self::from_box(Box::default())
+    }
+}
+
+impl Clone for RBoxStr {
+    fn clone(&self) -> Self {
+        self.as_str().into()
+    }
+}
+
+impl Deref for RBoxStr {
+    type Target = str;
+
+    #[inline]
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl DerefMut for RBoxStr {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut str {
+        self.as_mut_str()
+    }
+}
+
+impl AsRef<str> for RBoxStr {
+    fn as_ref(&self) -> &str {
+        self
+    }
+}
+
+impl AsRef<[u8]> for RBoxStr {
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl Borrow<str> for RBoxStr {
+    fn borrow(&self) -> &str {
+        self
+    }
+}
+
+impl_from_rust_repr! {
+    impl[] From<Box<str>> for RBoxStr {
+        fn(this){
+            RBoxStr::from_box(this)
+        }
+    }
+}
+
+impl_into_rust_repr! {
+    impl[] Into<Box<str>> for RBoxStr {
+        fn(this){
+            this.into_boxed_str()
+        }
+    }
+}
+
+impl From<String> for RBoxStr {
+    fn from(this: String) -> Self {
+        Self::from_box(this.into_boxed_str())
+    }
+}
+
+impl From<RString> for RBoxStr {
+    fn from(this: RString) -> Self {
+        Self::from_box(this.into_string().into_boxed_str())
+    }
+}
+
+impl From<&str> for RBoxStr {
+    fn from(this: &str) -> Self {
+        Self::from_box(this.into())
+    }
+}
+
+impl From<RBoxStr> for RString {
+    fn from(this: RBoxStr) -> Self {
+        this.into_string().into()
+    }
+}
+
+impl Display for RBoxStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(self.as_str(), f)
+    }
+}
+
+deref_coerced_impl_cmp_traits! {
+    RBoxStr;
+    coerce_to = str,
+    [
+        String,
+        str,
+        &str,
+        RStr<'_>,
+        RString,
+    ]
+}
+
+shared_impls! {
+    mod = boxed_str_impls
+    new_type = RBoxStr[][],
+    original_type = str,
+}
+
+impl<'de> Deserialize<'de> for RBoxStr {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer).map(Self::from)
+    }
+}
+
+impl Serialize for RBoxStr {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.as_str().serialize(serializer)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+#[derive(StableAbi)]
+#[repr(C)]
+#[sabi(kind(Prefix))]
+#[sabi(missing_field(panic))]
+pub(crate) struct BoxSliceVtable<T> {
+    type_id: extern "C" fn() -> UTypeId,
+    #[sabi(last_prefix_field)]
+    destructor: unsafe extern "C" fn(*mut (), usize, CallReferentDrop),
+    _marker: NonOwningPhantom<T>,
+}
+
+/// Dummy type used to create a statically allocated constant that can only be
+/// accessed of the lifetime of T.
+struct VTableGetter<'a, T>(PhantomData<&'a T>);
+
+impl<'a, T: 'a> VTableGetter<'a, T> {
+    const DEFAULT_VTABLE: BoxSliceVtable<T> = BoxSliceVtable {
+        type_id: new_utypeid::<RBoxSlice<()>>,
+        destructor: destroy_box_slice::<T>,
+        _marker: NonOwningPhantom::NEW,
+    };
+
+    staticref! {
+        const WM_DEFAULT: WithMetadata<BoxSliceVtable<T>> =
+            WithMetadata::new(Self::DEFAULT_VTABLE);
+    }
+
+    // The VTABLE for this type in this executable/library
+    const LIB_VTABLE: BoxSliceVtable_Ref<T> = BoxSliceVtable_Ref(Self::WM_DEFAULT.as_prefix());
+
+    #[cfg(test)]
+    staticref! {
+        const WM_FOR_TESTING: WithMetadata<BoxSliceVtable<T>> =
+            WithMetadata::new(
+                BoxSliceVtable {
+                    type_id: new_utypeid::<RBoxSlice<i32>>,
+                    ..Self::DEFAULT_VTABLE
+                },
+            )
+    }
+
+    #[allow(dead_code)]
+    #[cfg(test)]
+    const LIB_VTABLE_FOR_TESTING: BoxSliceVtable_Ref<T> =
+        BoxSliceVtable_Ref(Self::WM_FOR_TESTING.as_prefix());
+}
+
+unsafe extern "C" fn destroy_box_slice<T>(ptr: *mut (), len: usize, call_drop: CallReferentDrop) {
+    extern_fn_panic_handling! {no_early_return;
+        let ptr = ptr::slice_from_raw_parts_mut(ptr as *mut T, len);
+        if let CallReferentDrop::Yes = call_drop {
+            unsafe { ptr::drop_in_place(ptr); }
+        }
+        unsafe { drop(Box::from_raw(ptr as *mut [ManuallyDrop<T>])); }
+    }
+}
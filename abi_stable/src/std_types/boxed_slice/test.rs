@@ -0,0 +1,111 @@
+use super::*;
+
+use std::cell::Cell;
+
+fn _covariant_rboxslice<'a: 'b, 'b, T>(slice: RBoxSlice<&'a T>) -> RBoxSlice<&'b T> {
+    slice
+}
+
+#[test]
+fn from_box_reuses_allocation() {
+    let boxed: Box<[u32]> = vec![3, 5, 8].into_boxed_slice();
+    let addr = boxed.as_ptr();
+
+    let slice = RBoxSlice::from(boxed);
+    assert_eq!(slice.as_ptr(), addr);
+    assert_eq!(slice, [3, 5, 8]);
+
+    let boxed = slice.into_boxed_slice();
+    assert_eq!(boxed.as_ptr(), addr);
+
+    let string: Box<str> = "hello".into();
+    let addr = string.as_ptr();
+
+    let rstring = RBoxStr::from(string);
+    assert_eq!(rstring.as_ptr(), addr);
+    assert_eq!(rstring, "hello");
+    let string = rstring.into_boxed_str();
+    assert_eq!(string.as_ptr(), addr);
+}
+
+#[test]
+fn into_box_with_other_vtable() {
+    let mut slice = RBoxSlice::from(vec![RString::from("foo"), RString::from("bar")]);
+    let addr = slice.as_ptr();
+    slice.set_vtable_for_testing();
+
+    let boxed = slice.into_boxed_slice();
+    assert_ne!(boxed.as_ptr(), addr);
+    assert_eq!(&*boxed, &["foo", "bar"]);
+
+    let mut string = RBoxStr::from("hello");
+    string.inner.set_vtable_for_testing();
+    assert_eq!(string.into_string(), "hello");
+}
+
+#[test]
+fn drops_elements() {
+    struct OnDrop<'a>(&'a Cell<u32>);
+
+    impl Drop for OnDrop<'_> {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    let count = Cell::new(0);
+    let new_slice = || (0..3).map(|_| OnDrop(&count)).collect::<RBoxSlice<_>>();
+
+    drop(new_slice());
+    assert_eq!(count.get(), 3);
+
+    let mut slice = new_slice();
+    slice.set_vtable_for_testing();
+    let vec = slice.into_vec();
+    assert_eq!(count.get(), 3);
+    drop(vec);
+    assert_eq!(count.get(), 6);
+}
+
+#[test]
+fn mutation_and_traits() {
+    let mut slice = RBoxSlice::from(&[5, 3, 8][..]);
+    slice.sort();
+    assert_eq!(slice, vec![3, 5, 8]);
+    assert_eq!(slice.clone(), slice);
+    let greater = RBoxSlice::from(vec![4]);
+    assert!(slice < greater);
+    assert_eq!(format!("{:?}", slice), "[3, 5, 8]");
+
+    let mut string = RBoxStr::from(String::from("hello"));
+    string.make_ascii_uppercase();
+    assert_eq!(string, "HELLO");
+    assert_eq!(format!("{} {:?}", string, string), r#"HELLO "HELLO""#);
+    assert_eq!(RString::from(string.clone()), "HELLO");
+
+    assert!(RBoxSlice::<u8>::default().is_empty());
+    assert!(RBoxStr::default().is_empty());
+}
+
+#[test]
+fn zero_sized() {
+    let slice = RBoxSlice::from(vec![(); 10]);
+    assert_eq!(slice.len(), 10);
+    assert_eq!(slice.into_vec().len(), 10);
+}
+
+#[test]
+fn serde() {
+    let slice = RBoxSlice::from(vec![3, 5]);
+    let json = serde_json::to_string(&slice).unwrap();
+    assert_eq!(json, "[3,5]");
+    assert_eq!(
+        serde_json::from_str::<RBoxSlice<u32>>(&json).unwrap(),
+        slice
+    );
+
+    let string = RBoxStr::from("foo");
+    let json = serde_json::to_string(&string).unwrap();
+    assert_eq!(json, r#""foo""#);
+    assert_eq!(serde_json::from_str::<RBoxStr>(&json).unwrap(), string);
+}
@@ -0,0 +1,1269 @@
+//! Contains the ffi-safe equivalent of `std::collections::BTreeMap`, and related items.
+#![allow(clippy::missing_const_for_fn)]
+
+use std::{
+    borrow::Borrow,
+    cmp::{Ord, Ordering},
+    collections::BTreeMap,
+    fmt::{self, Debug},
+    hash::{Hash, Hasher},
+    iter::FromIterator,
+    marker::PhantomData,
+    mem,
+    ops::{Bound, Index, IndexMut, RangeBounds},
+    ptr::NonNull,
+};
+
+#[allow(unused_imports)]
+use core_extensions::SelfOps;
+
+use crate::{
+    marker_type::{
+        ErasedObject, ErasedPrefix, NonOwningPhantom, NotCopyNotClone, UnsafeIgnoredType,
+    },
+    pointer_trait::{AsMutPtr, AsPtr},
+    prefix_type::{PrefixRef, WithMetadata},
+    sabi_types::{RMut, RRef},
+    std_types::*,
+    traits::{ErasedType, IntoReprRust},
+    DynTrait, StableAbi,
+};
+
+mod entry;
+mod extern_fns;
+mod iterator_stuff;
+mod map_key;
+mod map_query;
+
+#[cfg(all(test, not(feature = "only_new_tests")))]
+mod test;
+
+use self::{
+    entry::BoxedREntry,
+    map_key::MapKey,
+    map_query::{assert_valid_range, MapQuery, QueryBound},
+};
+
+pub use self::{
+    entry::{REntry, ROccupiedEntry, RVacantEntry},
+    iterator_stuff::{IntoIter, MutIterInterface, RefIterInterface, ValIterInterface},
+};
+
+/// An ffi-safe ordered map, which wraps `std::collections::BTreeMap<K, V>`,
+/// only requiring the `K: Ord` bound when constructing it.
+///
+/// Unlike [`RHashMap`](crate::std_types::RHashMap),
+/// this iterates over its entries in ascending key order,
+/// and supports querying ranges of keys.
+///
+/// Most of the API in `BTreeMap` is implemented here, including the Entry API.
+///
+/// # Example
+///
+/// ```
+/// use abi_stable::std_types::{RBTreeMap, RSome, Tuple2};
+///
+/// let mut map = RBTreeMap::new();
+///
+/// map.insert(1997, "Deep Blue beats Kasparov");
+/// map.insert(1969, "Moon landing");
+/// map.insert(1989, "Fall of the Berlin wall");
+/// map.insert(2004, "Facebook launches");
+///
+/// assert_eq!(map[&1969], "Moon landing");
+///
+/// assert_eq!(map.remove(&2004), RSome("Facebook launches"));
+///
+/// assert_eq!(
+///     map.range(1980..).collect::<Vec<_>>(),
+///     [
+///         Tuple2(&1989, &"Fall of the Berlin wall"),
+///         Tuple2(&1997, &"Deep Blue beats Kasparov"),
+///     ],
+/// );
+///
+/// assert_eq!(map.keys().collect::<Vec<_>>(), [&1969, &1989, &1997]);
+///
+/// ```
+///
+#[derive(StableAbi)]
+#[repr(C)]
+pub struct RBTreeMap<K, V> {
+    map: RBox<ErasedMap<K, V>>,
+    #[sabi(unsafe_change_type = VTable_Ref<K, V>)]
+    vtable: PrefixRef<ErasedPrefix>,
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+struct BoxedBTreeMap<'a, K, V> {
+    map: BTreeMap<MapKey<K>, V>,
+    entry: Option<BoxedREntry<'a, K, V>>,
+}
+
+/// An RBTreeMap iterator,
+/// implementing `DoubleEndedIterator<Item= Tuple2< &K, &V > > + !Send + !Sync + Clone`
+pub type Iter<'a, K, V> = DynTrait<'a, RBox<()>, RefIterInterface<K, V>>;
+
+/// An RBTreeMap iterator,
+/// implementing `DoubleEndedIterator<Item= Tuple2< &K, &mut V > > + !Send + !Sync`
+pub type IterMut<'a, K, V> = DynTrait<'a, RBox<()>, MutIterInterface<K, V>>;
+
+/// An iterator over a range of entries of an RBTreeMap,
+/// implementing `DoubleEndedIterator<Item= Tuple2< &K, &V > > + !Send + !Sync + Clone`
+pub type Range<'a, K, V> = DynTrait<'a, RBox<()>, RefIterInterface<K, V>>;
+
+/// An iterator over a range of entries of an RBTreeMap,
+/// implementing `DoubleEndedIterator<Item= Tuple2< &K, &mut V > > + !Send + !Sync`
+pub type RangeMut<'a, K, V> = DynTrait<'a, RBox<()>, MutIterInterface<K, V>>;
+
+/// Used as the erased type of the RBTreeMap type.
+#[repr(C)]
+#[derive(StableAbi)]
+struct ErasedMap<K, V>(PhantomData<(K, V)>);
+
+impl<'a, K: 'a, V: 'a> ErasedType<'a> for ErasedMap<K, V> {
+    type Unerased = BoxedBTreeMap<'a, K, V>;
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+impl<K, V> RBTreeMap<K, V> {
+    /// Constructs an empty RBTreeMap.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RBTreeMap, RString};
+    ///
+    /// let mut map = RBTreeMap::<RString, u32>::new();
+    /// assert!(map.is_empty());
+    /// map.insert("Hello".into(), 10);
+    /// assert_eq!(map.is_empty(), false);
+    ///
+    /// ```
+    pub fn new() -> RBTreeMap<K, V>
+    where
+        K: Ord,
+    {
+        unsafe {
+            RBTreeMap {
+                map: VTable::<K, V>::erased_map(),
+                vtable: VTable::<K, V>::VTABLE_REF.0.cast(),
+            }
+        }
+    }
+
+    fn vtable(&self) -> VTable_Ref<K, V> {
+        unsafe { VTable_Ref::<K, V>(self.vtable.cast()) }
+    }
+}
+
+impl<K, V> RBTreeMap<K, V> {
+    /// Returns whether the map associates a value with the key.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RBTreeMap, RString};
+    ///
+    /// let mut map = RBTreeMap::<RString, u32>::new();
+    /// assert_eq!(map.contains_key("boo"), false);
+    /// map.insert("boo".into(), 0);
+    /// assert_eq!(map.contains_key("boo"), true);
+    ///
+    /// ```
+    pub fn contains_key<Q>(&self, query: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.get(query).is_some()
+    }
+
+    /// Returns a reference to the value associated with the key.
+    ///
+    /// Returns a `None` if there is no entry for the key.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RBTreeMap, RString};
+    ///
+    /// let mut map = RBTreeMap::<RString, u32>::new();
+    /// assert_eq!(map.get("boo"), None);
+    /// map.insert("boo".into(), 0);
+    /// assert_eq!(map.get("boo"), Some(&0));
+    ///
+    /// ```
+    pub fn get<Q>(&self, query: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let vtable = self.vtable();
+        unsafe { vtable.get_elem()(self.map.as_rref(), MapQuery::new(&query)) }
+    }
+
+    /// Returns a mutable reference to the value associated with the key.
+    ///
+    /// Returns a `None` if there is no entry for the key.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RBTreeMap, RString};
+    ///
+    /// let mut map = RBTreeMap::<RString, u32>::new();
+    /// assert_eq!(map.get_mut("boo"), None);
+    /// map.insert("boo".into(), 0);
+    /// assert_eq!(map.get_mut("boo"), Some(&mut 0));
+    ///
+    /// ```
+    pub fn get_mut<Q>(&mut self, query: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let vtable = self.vtable();
+        unsafe { vtable.get_mut_elem()(self.map.as_rmut(), MapQuery::new(&query)) }
+    }
+
+    /// Removes the value associated with the key.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RBTreeMap, RNone, RSome};
+    ///
+    /// let mut map = vec![(0, 1), (3, 4)].into_iter().collect::<RBTreeMap<u32, u32>>();
+    ///
+    /// assert_eq!(map.remove(&0), RSome(1));
+    /// assert_eq!(map.remove(&0), RNone);
+    ///
+    /// assert_eq!(map.remove(&3), RSome(4));
+    /// assert_eq!(map.remove(&3), RNone);
+    ///
+    /// ```
+    pub fn remove<Q>(&mut self, query: &Q) -> ROption<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.remove_entry(query).map(|x| x.1)
+    }
+
+    /// Removes the entry for the key.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RBTreeMap, RNone, RSome, Tuple2};
+    ///
+    /// let mut map = vec![(0, 1), (3, 4)].into_iter().collect::<RBTreeMap<u32, u32>>();
+    ///
+    /// assert_eq!(map.remove_entry(&0), RSome(Tuple2(0, 1)));
+    /// assert_eq!(map.remove_entry(&0), RNone);
+    ///
+    /// assert_eq!(map.remove_entry(&3), RSome(Tuple2(3, 4)));
+    /// assert_eq!(map.remove_entry(&3), RNone);
+    ///
+    /// ```
+    pub fn remove_entry<Q>(&mut self, query: &Q) -> ROption<Tuple2<K, V>>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let vtable = self.vtable();
+        unsafe { vtable.remove_entry()(self.map.as_rmut(), MapQuery::new(&query)) }
+    }
+
+    /// Iterates over the entries with keys in `range`, in ascending key order.
+    ///
+    /// This returns a type that implements
+    /// `DoubleEndedIterator<Item= Tuple2< &K, &V > > + !Send + !Sync + Clone`
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of the range is greater than the end,
+    /// or if the start and end are equal and both excluded.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RBTreeMap, RString, Tuple2};
+    ///
+    /// use std::ops::Bound;
+    ///
+    /// let map = vec![(3, "foo"), (5, "bar"), (8, "baz"), (13, "qux")]
+    ///     .into_iter()
+    ///     .collect::<RBTreeMap<u32, &str>>();
+    ///
+    /// assert_eq!(
+    ///     map.range(4..=8).collect::<Vec<_>>(),
+    ///     [Tuple2(&5, &"bar"), Tuple2(&8, &"baz")],
+    /// );
+    ///
+    /// assert_eq!(
+    ///     map.range(..5).rev().collect::<Vec<_>>(),
+    ///     [Tuple2(&3, &"foo")],
+    /// );
+    ///
+    /// // Querying a map of `RString` keys with `str` bounds.
+    /// let map = vec!["apple", "banana", "cherry"]
+    ///     .into_iter()
+    ///     .map(|s| (RString::from(s), s.len()))
+    ///     .collect::<RBTreeMap<RString, usize>>();
+    ///
+    /// let range = map.range::<str, _>((Bound::Included("b"), Bound::Unbounded));
+    /// assert_eq!(range.map(|x| x.1).collect::<Vec<_>>(), [&6, &6]);
+    ///
+    /// ```
+    pub fn range<Q, R>(&self, range: R) -> Range<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let (start, end) = range_bounds(&range);
+        assert_valid_range(start, end);
+
+        let vtable = self.vtable();
+        unsafe {
+            vtable.range()(
+                self.map.as_rref(),
+                QueryBound::new(&start),
+                QueryBound::new(&end),
+            )
+        }
+    }
+
+    /// Iterates over the entries with keys in `range`, in ascending key order,
+    /// with mutable references to the values.
+    ///
+    /// This returns a type that implements
+    /// `DoubleEndedIterator<Item= Tuple2< &K, &mut V > > + !Send + !Sync`
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of the range is greater than the end,
+    /// or if the start and end are equal and both excluded.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBTreeMap;
+    ///
+    /// let mut map = (0..10).map(|x| (x, x)).collect::<RBTreeMap<u32, u32>>();
+    ///
+    /// for x in map.range_mut(5..) {
+    ///     *x.1 *= 100;
+    /// }
+    ///
+    /// assert_eq!(
+    ///     map.values().copied().collect::<Vec<_>>(),
+    ///     [0, 1, 2, 3, 4, 500, 600, 700, 800, 900],
+    /// );
+    ///
+    /// ```
+    pub fn range_mut<Q, R>(&mut self, range: R) -> RangeMut<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let (start, end) = range_bounds(&range);
+        assert_valid_range(start, end);
+
+        let vtable = self.vtable();
+        unsafe {
+            vtable.range_mut()(
+                self.map.as_rmut(),
+                QueryBound::new(&start),
+                QueryBound::new(&end),
+            )
+        }
+    }
+}
+
+impl<K, V> RBTreeMap<K, V> {
+    /// Returns whether the map associates a value with the key.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBTreeMap;
+    ///
+    /// let mut map = RBTreeMap::<u32, u32>::new();
+    /// assert_eq!(map.contains_key_p(&11), false);
+    /// map.insert(11, 0);
+    /// assert_eq!(map.contains_key_p(&11), true);
+    ///
+    /// ```
+    pub fn contains_key_p(&self, key: &K) -> bool {
+        self.get_p(key).is_some()
+    }
+
+    /// Returns a reference to the value associated with the key.
+    ///
+    /// Returns a `None` if there is no entry for the key.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBTreeMap;
+    ///
+    /// let mut map = RBTreeMap::<u32, u32>::new();
+    /// assert_eq!(map.get_p(&12), None);
+    /// map.insert(12, 0);
+    /// assert_eq!(map.get_p(&12), Some(&0));
+    ///
+    /// ```
+    pub fn get_p(&self, key: &K) -> Option<&V> {
+        let vtable = self.vtable();
+        unsafe { vtable.get_elem_p()(self.map.as_rref(), key) }
+    }
+
+    /// Returns a mutable reference to the value associated with the key.
+    ///
+    /// Returns a `None` if there is no entry for the key.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBTreeMap;
+    ///
+    /// let mut map = RBTreeMap::<u32, u32>::new();
+    /// assert_eq!(map.get_mut_p(&12), None);
+    /// map.insert(12, 0);
+    /// assert_eq!(map.get_mut_p(&12), Some(&mut 0));
+    ///
+    /// ```
+    pub fn get_mut_p(&mut self, key: &K) -> Option<&mut V> {
+        let vtable = self.vtable();
+        unsafe { vtable.get_mut_elem_p()(self.map.as_rmut(), key) }
+    }
+
+    /// Removes the value associated with the key.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RBTreeMap, RNone, RSome};
+    ///
+    /// let mut map = vec![(0, 1), (3, 4)].into_iter().collect::<RBTreeMap<u32, u32>>();
+    ///
+    /// assert_eq!(map.remove_p(&0), RSome(1));
+    /// assert_eq!(map.remove_p(&0), RNone);
+    ///
+    /// ```
+    pub fn remove_p(&mut self, key: &K) -> ROption<V> {
+        self.remove_entry_p(key).map(|x| x.1)
+    }
+
+    /// Removes the entry for the key.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RBTreeMap, RNone, RSome, Tuple2};
+    ///
+    /// let mut map = vec![(0, 1), (3, 4)].into_iter().collect::<RBTreeMap<u32, u32>>();
+    ///
+    /// assert_eq!(map.remove_entry_p(&0), RSome(Tuple2(0, 1)));
+    /// assert_eq!(map.remove_entry_p(&0), RNone);
+    ///
+    /// ```
+    pub fn remove_entry_p(&mut self, key: &K) -> ROption<Tuple2<K, V>> {
+        let vtable = self.vtable();
+        unsafe { vtable.remove_entry_p()(self.map.as_rmut(), key) }
+    }
+
+    /// Returns a reference to the value associated with the key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key is not associated with a value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBTreeMap;
+    ///
+    /// let map = vec![(0, 1), (3, 4)].into_iter().collect::<RBTreeMap<u32, u32>>();
+    ///
+    /// assert_eq!(map.index_p(&0), &1);
+    /// assert_eq!(map.index_p(&3), &4);
+    ///
+    /// ```
+    pub fn index_p(&self, key: &K) -> &V {
+        self.get_p(key)
+            .expect("no entry in RBTreeMap<_, _> found for key")
+    }
+
+    /// Returns a mutable reference to the value associated with the key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key is not associated with a value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBTreeMap;
+    ///
+    /// let mut map = vec![(0, 1), (3, 4)].into_iter().collect::<RBTreeMap<u32, u32>>();
+    ///
+    /// assert_eq!(map.index_mut_p(&0), &mut 1);
+    /// assert_eq!(map.index_mut_p(&3), &mut 4);
+    ///
+    /// ```
+    pub fn index_mut_p(&mut self, key: &K) -> &mut V {
+        self.get_mut_p(key)
+            .expect("no entry in RBTreeMap<_, _> found for key")
+    }
+
+    //////////////////////////////////
+
+    /// Inserts a value into the map, associating it with a key, returning the previous value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RBTreeMap, RNone, RSome};
+    ///
+    /// let mut map = RBTreeMap::<u32, u32>::new();
+    ///
+    /// assert_eq!(map.insert(0, 1), RNone);
+    /// assert_eq!(map.insert(0, 2), RSome(1));
+    ///
+    /// assert_eq!(map[&0], 2);
+    ///
+    /// ```
+    pub fn insert(&mut self, key: K, value: V) -> ROption<V> {
+        let vtable = self.vtable();
+        unsafe { vtable.insert_elem()(self.map.as_rmut(), key, value) }
+    }
+
+    /// Removes all the entries in the map.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBTreeMap;
+    ///
+    /// let mut map = vec![(0, 1), (3, 4)].into_iter().collect::<RBTreeMap<u32, u32>>();
+    ///
+    /// map.clear();
+    ///
+    /// assert_eq!(map.contains_key(&0), false);
+    /// assert_eq!(map.contains_key(&3), false);
+    ///
+    /// ```
+    pub fn clear(&mut self) {
+        let vtable = self.vtable();
+        unsafe {
+            vtable.clear_map()(self.map.as_rmut());
+        }
+    }
+
+    /// Returns the amount of entries in the map.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBTreeMap;
+    ///
+    /// let mut map = RBTreeMap::<u32, u32>::new();
+    ///
+    /// assert_eq!(map.len(), 0);
+    /// map.insert(0, 1);
+    /// assert_eq!(map.len(), 1);
+    /// map.insert(2, 3);
+    /// assert_eq!(map.len(), 2);
+    ///
+    /// ```
+    pub fn len(&self) -> usize {
+        let vtable = self.vtable();
+        unsafe { vtable.len()(self.map.as_rref()) }
+    }
+
+    /// Returns whether the map contains any entries.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBTreeMap;
+    ///
+    /// let mut map = RBTreeMap::<u32, u32>::new();
+    ///
+    /// assert_eq!(map.is_empty(), true);
+    /// map.insert(0, 1);
+    /// assert_eq!(map.is_empty(), false);
+    ///
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the entry with the smallest key in the map.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RBTreeMap, Tuple2};
+    ///
+    /// let mut map = RBTreeMap::<u32, char>::new();
+    /// assert_eq!(map.first_key_value(), None);
+    ///
+    /// map.insert(5, 'b');
+    /// map.insert(3, 'a');
+    /// assert_eq!(map.first_key_value(), Some(Tuple2(&3, &'a')));
+    ///
+    /// ```
+    pub fn first_key_value(&self) -> Option<Tuple2<&K, &V>> {
+        self.iter().next()
+    }
+
+    /// Returns the entry with the largest key in the map.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RBTreeMap, Tuple2};
+    ///
+    /// let mut map = RBTreeMap::<u32, char>::new();
+    /// assert_eq!(map.last_key_value(), None);
+    ///
+    /// map.insert(5, 'b');
+    /// map.insert(3, 'a');
+    /// assert_eq!(map.last_key_value(), Some(Tuple2(&5, &'b')));
+    ///
+    /// ```
+    pub fn last_key_value(&self) -> Option<Tuple2<&K, &V>> {
+        self.iter().next_back()
+    }
+
+    /// Iterates over the entries in the map in ascending key order,
+    /// with references to the values in the map.
+    ///
+    /// This returns a type that implements
+    /// `DoubleEndedIterator<Item= Tuple2< &K, &V > > + !Send + !Sync + Clone`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RBTreeMap, Tuple2};
+    ///
+    /// let mut map = RBTreeMap::<u32, u32>::new();
+    ///
+    /// map.insert(3, 4);
+    /// map.insert(0, 1);
+    ///
+    /// assert_eq!(
+    ///     map.iter().collect::<Vec<_>>(),
+    ///     vec![Tuple2(&0, &1), Tuple2(&3, &4)],
+    /// );
+    ///
+    /// ```
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        let vtable = self.vtable();
+
+        unsafe { vtable.iter()(self.map.as_rref()) }
+    }
+
+    /// Iterates over the entries in the map in ascending key order,
+    /// with mutable references to the values in the map.
+    ///
+    /// This returns a type that implements
+    /// `DoubleEndedIterator<Item= Tuple2< &K, &mut V > > + !Send + !Sync`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RBTreeMap, Tuple2};
+    ///
+    /// let mut map = RBTreeMap::<u32, u32>::new();
+    ///
+    /// map.insert(3, 4);
+    /// map.insert(0, 1);
+    ///
+    /// assert_eq!(
+    ///     map.iter_mut().collect::<Vec<_>>(),
+    ///     vec![Tuple2(&0, &mut 1), Tuple2(&3, &mut 4)],
+    /// );
+    ///
+    /// ```
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        let vtable = self.vtable();
+
+        unsafe { vtable.iter_mut()(self.map.as_rmut()) }
+    }
+
+    /// Gets a handle into the entry in the map for the key,
+    /// that allows operating directly on the entry.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBTreeMap;
+    ///
+    /// let mut map = RBTreeMap::<u32, u32>::new();
+    ///
+    /// // Inserting an entry that wasn't there before.
+    /// {
+    ///     let mut entry = map.entry(0);
+    ///     assert_eq!(entry.get(), None);
+    ///     assert_eq!(entry.or_insert(3), &mut 3);
+    ///     assert_eq!(map.get(&0), Some(&3));
+    /// }
+    ///
+    /// // Modifying an entry that was already there.
+    /// *map.entry(0).or_insert(100) += 10;
+    /// assert_eq!(map.get(&0), Some(&13));
+    ///
+    /// ```
+    ///
+    pub fn entry(&mut self, key: K) -> REntry<'_, K, V> {
+        let vtable = self.vtable();
+
+        unsafe { vtable.entry()(self.map.as_rmut(), key) }
+    }
+
+    /// An iterator visiting all keys in ascending order.
+    /// The iterator element type is `&'a K`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBTreeMap;
+    ///
+    /// let mut map = RBTreeMap::new();
+    /// map.insert("b", 2);
+    /// map.insert("a", 1);
+    /// map.insert("c", 3);
+    ///
+    /// assert_eq!(map.keys().collect::<Vec<_>>(), [&"a", &"b", &"c"]);
+    /// ```
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys { inner: self.iter() }
+    }
+
+    /// An iterator visiting all values in ascending order of their keys.
+    /// The iterator element type is `&'a V`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBTreeMap;
+    ///
+    /// let mut map = RBTreeMap::new();
+    /// map.insert("b", 2);
+    /// map.insert("a", 1);
+    /// map.insert("c", 3);
+    ///
+    /// assert_eq!(map.values().collect::<Vec<_>>(), [&1, &2, &3]);
+    /// ```
+    pub fn values(&self) -> Values<'_, K, V> {
+        Values { inner: self.iter() }
+    }
+}
+
+fn range_bounds<Q, R>(range: &R) -> (Bound<&Q>, Bound<&Q>)
+where
+    Q: ?Sized,
+    R: RangeBounds<Q>,
+{
+    (range.start_bound(), range.end_bound())
+}
+
+/// An iterator over the keys of a `RBTreeMap`, in ascending order.
+///
+/// This `struct` is created by the [`keys`] method on [`RBTreeMap`]. See its
+/// documentation for more.
+///
+/// [`keys`]: RBTreeMap::keys
+///
+/// # Example
+///
+/// ```
+/// use abi_stable::std_types::RBTreeMap;
+///
+/// let mut map = RBTreeMap::new();
+/// map.insert("a", 1);
+/// let iter_keys = map.keys();
+/// ```
+#[repr(C)]
+#[derive(StableAbi)]
+pub struct Keys<'a, K: 'a, V: 'a> {
+    inner: Iter<'a, K, V>,
+}
+
+impl<K, V> Clone for Keys<'_, K, V> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Keys {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<K: Debug, V> fmt::Debug for Keys<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<'a, K, V> Iterator for Keys<'a, K, V> {
+    type Item = &'a K;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a K> {
+        self.inner.next().map(|tuple| tuple.0)
+    }
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Keys<'a, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a K> {
+        self.inner.next_back().map(|tuple| tuple.0)
+    }
+}
+
+/// An iterator over the values of a `RBTreeMap`, in ascending order of their keys.
+///
+/// This `struct` is created by the [`values`] method on [`RBTreeMap`]. See its
+/// documentation for more.
+///
+/// [`values`]: RBTreeMap::values
+///
+/// # Example
+///
+/// ```
+/// use abi_stable::std_types::RBTreeMap;
+///
+/// let mut map = RBTreeMap::new();
+/// map.insert("a", 1);
+/// let iter_values = map.values();
+/// ```
+#[repr(C)]
+#[derive(StableAbi)]
+pub struct Values<'a, K: 'a, V: 'a> {
+    inner: Iter<'a, K, V>,
+}
+
+impl<K, V> Clone for Values<'_, K, V> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Values {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<K, V: Debug> fmt::Debug for Values<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<'a, K, V> Iterator for Values<'a, K, V> {
+    type Item = &'a V;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a V> {
+        self.inner.next().map(|tuple| tuple.1)
+    }
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Values<'a, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a V> {
+        self.inner.next_back().map(|tuple| tuple.1)
+    }
+}
+
+/// This returns an `DoubleEndedIterator<Item= Tuple2< K, V > >+!Send+!Sync`
+impl<K, V> IntoIterator for RBTreeMap<K, V> {
+    type Item = Tuple2<K, V>;
+    type IntoIter = IntoIter<K, V>;
+
+    fn into_iter(self) -> IntoIter<K, V> {
+        let vtable = self.vtable();
+
+        unsafe { vtable.iter_val()(self.map) }
+    }
+}
+
+/// This returns a
+/// `DoubleEndedIterator<Item= Tuple2< &K, &V > > + !Send + !Sync + Clone`
+impl<'a, K, V> IntoIterator for &'a RBTreeMap<K, V> {
+    type Item = Tuple2<&'a K, &'a V>;
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// This returns a type that implements
+/// `DoubleEndedIterator<Item= Tuple2< &K, &mut V > > + !Send + !Sync`
+impl<'a, K, V> IntoIterator for &'a mut RBTreeMap<K, V> {
+    type Item = Tuple2<&'a K, &'a mut V>;
+    type IntoIter = IterMut<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<K, V> From<BTreeMap<K, V>> for RBTreeMap<K, V>
+where
+    K: Ord,
+{
+    fn from(map: BTreeMap<K, V>) -> Self {
+        map.into_iter().collect()
+    }
+}
+
+impl<K, V> From<RBTreeMap<K, V>> for BTreeMap<K, V>
+where
+    K: Ord,
+{
+    fn from(this: RBTreeMap<K, V>) -> BTreeMap<K, V> {
+        this.into_iter().map(|x| x.into_tuple()).collect()
+    }
+}
+
+impl<K, V> FromIterator<(K, V)> for RBTreeMap<K, V>
+where
+    K: Ord,
+{
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let mut map = Self::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K, V> FromIterator<Tuple2<K, V>> for RBTreeMap<K, V>
+where
+    K: Ord,
+{
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = Tuple2<K, V>>,
+    {
+        let mut map = Self::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K, V> Extend<(K, V)> for RBTreeMap<K, V> {
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        for (k, v) in iter {
+            self.insert(k, v);
+        }
+    }
+}
+
+impl<K, V> Extend<Tuple2<K, V>> for RBTreeMap<K, V> {
+    #[inline]
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = Tuple2<K, V>>,
+    {
+        self.extend(iter.into_iter().map(Tuple2::into_rust));
+    }
+}
+
+impl<K, V> Default for RBTreeMap<K, V>
+where
+    K: Ord,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> Clone for RBTreeMap<K, V>
+where
+    K: Ord + Clone,
+    V: Clone,
+{
+    fn clone(&self) -> Self {
+        self.iter()
+            .map(|Tuple2(k, v)| (k.clone(), v.clone()))
+            .collect()
+    }
+}
+
+impl<K, V> Debug for RBTreeMap<K, V>
+where
+    K: Debug,
+    V: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.iter().map(Tuple2::into_rust))
+            .finish()
+    }
+}
+
+impl<K, V> Eq for RBTreeMap<K, V>
+where
+    K: Eq,
+    V: Eq,
+{
+}
+
+impl<K, V> PartialEq for RBTreeMap<K, V>
+where
+    K: PartialEq,
+    V: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<K, V> PartialOrd for RBTreeMap<K, V>
+where
+    K: PartialOrd,
+    V: PartialOrd,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<K, V> Ord for RBTreeMap<K, V>
+where
+    K: Ord,
+    V: Ord,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<K, V> Hash for RBTreeMap<K, V>
+where
+    K: Hash,
+    V: Hash,
+{
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        state.write_usize(self.len());
+        for elem in self.iter() {
+            elem.hash(state);
+        }
+    }
+}
+
+unsafe impl<K, V> Send for RBTreeMap<K, V> where BTreeMap<K, V>: Send {}
+
+unsafe impl<K, V> Sync for RBTreeMap<K, V> where BTreeMap<K, V>: Sync {}
+
+impl<K, Q, V> Index<&Q> for RBTreeMap<K, V>
+where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    type Output = V;
+
+    fn index(&self, query: &Q) -> &V {
+        self.get(query)
+            .expect("no entry in RBTreeMap<_, _> found for key")
+    }
+}
+
+impl<K, Q, V> IndexMut<&Q> for RBTreeMap<K, V>
+where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    fn index_mut(&mut self, query: &Q) -> &mut V {
+        self.get_mut(query)
+            .expect("no entry in RBTreeMap<_, _> found for key")
+    }
+}
+
+mod serde {
+    use super::*;
+
+    use ::serde::{
+        de::{MapAccess, Visitor},
+        ser::SerializeMap,
+        Deserialize, Deserializer, Serialize, Serializer,
+    };
+
+    struct RBTreeMapVisitor<K, V> {
+        _marker: NonOwningPhantom<RBTreeMap<K, V>>,
+    }
+
+    impl<K, V> RBTreeMapVisitor<K, V> {
+        fn new() -> Self {
+            RBTreeMapVisitor {
+                _marker: NonOwningPhantom::NEW,
+            }
+        }
+    }
+
+    impl<'de, K, V> Visitor<'de> for RBTreeMapVisitor<K, V>
+    where
+        K: Deserialize<'de> + Ord,
+        V: Deserialize<'de>,
+    {
+        type Value = RBTreeMap<K, V>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("an RBTreeMap")
+        }
+
+        fn visit_map<M>(self, mut map_access: M) -> Result<Self::Value, M::Error>
+        where
+            M: MapAccess<'de>,
+        {
+            let mut map = RBTreeMap::new();
+
+            while let Some((k, v)) = map_access.next_entry()? {
+                map.insert(k, v);
+            }
+
+            Ok(map)
+        }
+    }
+
+    impl<'de, K, V> Deserialize<'de> for RBTreeMap<K, V>
+    where
+        K: Deserialize<'de> + Ord,
+        V: Deserialize<'de>,
+    {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserializer.deserialize_map(RBTreeMapVisitor::new())
+        }
+    }
+
+    impl<K, V> Serialize for RBTreeMap<K, V>
+    where
+        K: Serialize,
+        V: Serialize,
+    {
+        fn serialize<Z>(&self, serializer: Z) -> Result<Z::Ok, Z::Error>
+        where
+            Z: Serializer,
+        {
+            let mut map = serializer.serialize_map(Some(self.len()))?;
+            for Tuple2(k, v) in self.iter() {
+                map.serialize_entry(k, v)?;
+            }
+            map.end()
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+#[derive(StableAbi)]
+#[repr(C)]
+#[sabi(kind(Prefix), missing_field(panic))]
+struct VTable<K, V> {
+    ///
+    insert_elem: unsafe extern "C" fn(RMut<'_, ErasedMap<K, V>>, K, V) -> ROption<V>,
+
+    get_elem:
+        for<'a> unsafe extern "C" fn(RRef<'a, ErasedMap<K, V>>, MapQuery<'_, K>) -> Option<&'a V>,
+    get_mut_elem: for<'a> unsafe extern "C" fn(
+        RMut<'a, ErasedMap<K, V>>,
+        MapQuery<'_, K>,
+    ) -> Option<&'a mut V>,
+    remove_entry:
+        unsafe extern "C" fn(RMut<'_, ErasedMap<K, V>>, MapQuery<'_, K>) -> ROption<Tuple2<K, V>>,
+
+    get_elem_p: for<'a> unsafe extern "C" fn(RRef<'a, ErasedMap<K, V>>, &K) -> Option<&'a V>,
+    get_mut_elem_p:
+        for<'a> unsafe extern "C" fn(RMut<'a, ErasedMap<K, V>>, &K) -> Option<&'a mut V>,
+    remove_entry_p: unsafe extern "C" fn(RMut<'_, ErasedMap<K, V>>, &K) -> ROption<Tuple2<K, V>>,
+
+    clear_map: unsafe extern "C" fn(RMut<'_, ErasedMap<K, V>>),
+    len: unsafe extern "C" fn(RRef<'_, ErasedMap<K, V>>) -> usize,
+    iter: unsafe extern "C" fn(RRef<'_, ErasedMap<K, V>>) -> Iter<'_, K, V>,
+    iter_mut: unsafe extern "C" fn(RMut<'_, ErasedMap<K, V>>) -> IterMut<'_, K, V>,
+    range: for<'a> unsafe extern "C" fn(
+        RRef<'a, ErasedMap<K, V>>,
+        QueryBound<'_, K>,
+        QueryBound<'_, K>,
+    ) -> Range<'a, K, V>,
+    range_mut: for<'a> unsafe extern "C" fn(
+        RMut<'a, ErasedMap<K, V>>,
+        QueryBound<'_, K>,
+        QueryBound<'_, K>,
+    ) -> RangeMut<'a, K, V>,
+    iter_val: unsafe extern "C" fn(RBox<ErasedMap<K, V>>) -> IntoIter<K, V>,
+    #[sabi(last_prefix_field)]
+    entry: unsafe extern "C" fn(RMut<'_, ErasedMap<K, V>>, K) -> REntry<'_, K, V>,
+}
+
+impl<K, V> VTable<K, V>
+where
+    K: Ord,
+{
+    staticref! {
+        const VTABLE_VAL: WithMetadata<VTable<K, V>> = WithMetadata::new(Self::VTABLE)
+    }
+
+    const VTABLE_REF: VTable_Ref<K, V> = VTable_Ref(Self::VTABLE_VAL.as_prefix());
+
+    fn erased_map() -> RBox<ErasedMap<K, V>> {
+        unsafe {
+            let map = BTreeMap::<MapKey<K>, V>::new();
+            let boxed = BoxedBTreeMap { map, entry: None };
+            let boxed = RBox::new(boxed);
+            mem::transmute::<RBox<_>, RBox<ErasedMap<K, V>>>(boxed)
+        }
+    }
+
+    const VTABLE: VTable<K, V> = VTable {
+        insert_elem: ErasedMap::insert_elem,
+
+        get_elem: ErasedMap::get_elem,
+        get_mut_elem: ErasedMap::get_mut_elem,
+        remove_entry: ErasedMap::remove_entry,
+
+        get_elem_p: ErasedMap::get_elem_p,
+        get_mut_elem_p: ErasedMap::get_mut_elem_p,
+        remove_entry_p: ErasedMap::remove_entry_p,
+
+        clear_map: ErasedMap::clear_map,
+        len: ErasedMap::len,
+        iter: ErasedMap::iter,
+        iter_mut: ErasedMap::iter_mut,
+        range: ErasedMap::range,
+        range_mut: ErasedMap::range_mut,
+        iter_val: ErasedMap::iter_val,
+        entry: ErasedMap::entry,
+    };
+}
+
+///////////////////////////////////////////////////////////////////////////////
@@ -0,0 +1,131 @@
+use super::*;
+
+/// A trait object used in method that access map entries without replacing them.
+#[derive(StableAbi)]
+#[repr(C)]
+pub struct MapQuery<'a, K> {
+    _marker: NotCopyNotClone,
+    cmp_key: extern "C" fn(&K, RRef<'_, ErasedObject>) -> RCmpOrdering,
+    // Used by `BTreeMap::range` to compare the start and end bounds.
+    cmp_query: extern "C" fn(RRef<'_, ErasedObject>, RRef<'_, ErasedObject>) -> RCmpOrdering,
+    query: RRef<'a, ErasedObject>,
+}
+
+impl<'a, K> MapQuery<'a, K> {
+    #[inline]
+    pub(super) fn new<Q>(query: &'a &'a Q) -> Self
+    where
+        K: Borrow<Q>,
+        Q: Ord + 'a + ?Sized,
+    {
+        MapQuery {
+            _marker: NotCopyNotClone,
+            cmp_key: cmp_key::<K, Q>,
+            cmp_query: cmp_query::<Q>,
+            query: unsafe { RRef::new(query).transmute() },
+        }
+    }
+
+    #[inline]
+    pub(super) unsafe fn as_static(&self) -> &MapQuery<'static, K> {
+        unsafe { crate::utils::transmute_reference(self) }
+    }
+}
+
+impl<'a, K> MapQuery<'a, K> {
+    /// Compares `key` with the query, `key.borrow().cmp(query)`.
+    #[inline]
+    pub(super) fn cmp_key(&self, key: &K) -> Ordering {
+        (self.cmp_key)(key, self.query).into()
+    }
+
+    /// Compares this query with another one,
+    /// both of which must have been constructed with the same `Q` type.
+    #[inline]
+    pub(super) fn cmp_query(&self, other: &Self) -> Ordering {
+        (self.cmp_query)(self.query, other.query).into()
+    }
+
+    #[inline]
+    pub(super) unsafe fn as_mapkey(&self) -> MapKey<K> {
+        MapKey::Query(NonNull::from(unsafe { self.as_static() }))
+    }
+}
+
+extern "C" fn cmp_key<K, Q>(key: &K, query: RRef<'_, ErasedObject>) -> RCmpOrdering
+where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    extern_fn_panic_handling! {
+        let query = unsafe{ query.transmute_into_ref::<&Q>() };
+        key.borrow().cmp(*query).into()
+    }
+}
+
+extern "C" fn cmp_query<Q>(lhs: RRef<'_, ErasedObject>, rhs: RRef<'_, ErasedObject>) -> RCmpOrdering
+where
+    Q: Ord + ?Sized,
+{
+    extern_fn_panic_handling! {
+        let lhs = unsafe{ lhs.transmute_into_ref::<&Q>() };
+        let rhs = unsafe{ rhs.transmute_into_ref::<&Q>() };
+        (*lhs).cmp(*rhs).into()
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// An ffi-safe equivalent of `Bound<&Q>`,used to pass range bounds to the map.
+#[derive(StableAbi)]
+#[repr(u8)]
+pub enum QueryBound<'a, K> {
+    Included(MapQuery<'a, K>),
+    Excluded(MapQuery<'a, K>),
+    Unbounded,
+}
+
+impl<'a, K> QueryBound<'a, K> {
+    #[inline]
+    pub(super) fn new<Q>(bound: &'a Bound<&'a Q>) -> Self
+    where
+        K: Borrow<Q>,
+        Q: Ord + 'a + ?Sized,
+    {
+        match bound {
+            Bound::Included(x) => QueryBound::Included(MapQuery::new(x)),
+            Bound::Excluded(x) => QueryBound::Excluded(MapQuery::new(x)),
+            Bound::Unbounded => QueryBound::Unbounded,
+        }
+    }
+
+    #[inline]
+    pub(super) unsafe fn as_mapkey_bound(&self) -> Bound<MapKey<K>> {
+        unsafe {
+            match self {
+                QueryBound::Included(x) => Bound::Included(x.as_mapkey()),
+                QueryBound::Excluded(x) => Bound::Excluded(x.as_mapkey()),
+                QueryBound::Unbounded => Bound::Unbounded,
+            }
+        }
+    }
+}
+
+/// Panics in the same situations that `BTreeMap::range` does,
+/// since panicking inside the map's extern functions aborts the process.
+pub(super) fn assert_valid_range<Q>(start: Bound<&Q>, end: Bound<&Q>)
+where
+    Q: Ord + ?Sized,
+{
+    match (start, end) {
+        (Bound::Excluded(s), Bound::Excluded(e)) if s == e => {
+            panic!("range start and end are equal and excluded in RBTreeMap")
+        }
+        (Bound::Included(s) | Bound::Excluded(s), Bound::Included(e) | Bound::Excluded(e))
+            if s > e =>
+        {
+            panic!("range start is greater than range end in RBTreeMap")
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,213 @@
+use super::*;
+
+use crate::std_types::RString;
+
+fn _covariant_btreemap<'a: 'b, 'b, T>(foo: BTreeMap<&'a T, &'a T>) -> BTreeMap<&'b T, &'b T> {
+    foo
+}
+
+fn _covariant_rbtreemap<'a: 'b, 'b, T>(foo: RBTreeMap<&'a T, &'a T>) -> RBTreeMap<&'b T, &'b T> {
+    foo
+}
+
+fn new_stdmap() -> BTreeMap<u32, u32> {
+    vec![(90, 40), (10, 20), (88, 30), (77, 22)]
+        .into_iter()
+        .collect()
+}
+
+fn new_map() -> RBTreeMap<u32, u32> {
+    new_stdmap().into()
+}
+
+#[test]
+fn test_new_map() {
+    let mut map = RBTreeMap::new();
+    map.insert(10, 100);
+    assert_eq!(map.get(&10), Some(&100));
+
+    assert_eq!(RBTreeMap::<u32, u32>::default(), RBTreeMap::new());
+}
+
+#[test]
+fn test_insert_remove() {
+    let mut map = RBTreeMap::<RString, u32>::new();
+
+    assert_eq!(map.insert("foo".into(), 3), RNone);
+    assert_eq!(map.insert("bar".into(), 5), RNone);
+    assert_eq!(map.insert("foo".into(), 8), RSome(3));
+    assert_eq!(map.len(), 2);
+
+    assert_eq!(map.get("foo"), Some(&8));
+    assert_eq!(map.get_p(&RString::from("bar")), Some(&5));
+    assert_eq!(map.get("baz"), None);
+
+    *map.get_mut("bar").unwrap() += 100;
+    assert_eq!(map["bar"], 105);
+
+    assert_eq!(map.remove_entry("bar"), RSome(Tuple2("bar".into(), 105)));
+    assert_eq!(map.remove("bar"), RNone);
+    assert_eq!(map.remove_p(&"foo".into()), RSome(8));
+    assert!(map.is_empty());
+}
+
+#[test]
+fn test_ordered_iteration() {
+    let mut map = new_map();
+    let std = new_stdmap();
+
+    let list = map.iter().map(Tuple2::into_rust).collect::<Vec<_>>();
+    assert_eq!(list, std.iter().collect::<Vec<_>>());
+
+    let rev = map.iter().rev().map(|x| *x.0).collect::<Vec<_>>();
+    assert_eq!(rev, std.keys().rev().copied().collect::<Vec<_>>());
+
+    for Tuple2(_, v) in map.iter_mut() {
+        *v += 1;
+    }
+    assert_eq!(
+        map.values().copied().collect::<Vec<_>>(),
+        std.values().map(|v| v + 1).collect::<Vec<_>>(),
+    );
+
+    assert_eq!(map.first_key_value(), Some(Tuple2(&10, &21)));
+    assert_eq!(map.last_key_value(), Some(Tuple2(&90, &41)));
+
+    let owned = map.into_iter().rev().map(|x| x.0).collect::<Vec<_>>();
+    assert_eq!(owned, vec![90, 88, 77, 10]);
+}
+
+#[test]
+fn test_range() {
+    let map = (0..20)
+        .map(|x| (x, x * 10))
+        .collect::<RBTreeMap<u32, u32>>();
+    let std = (0..20).map(|x| (x, x * 10)).collect::<BTreeMap<u32, u32>>();
+
+    macro_rules! assert_range {
+        ($range:expr) => {{
+            let list = map.range($range).map(Tuple2::into_rust).collect::<Vec<_>>();
+            let expected = std.range($range).collect::<Vec<_>>();
+            assert_eq!(list, expected);
+
+            let rev = map.range($range).rev().map(|x| *x.0).collect::<Vec<_>>();
+            let expected = std.range($range).rev().map(|x| *x.0).collect::<Vec<_>>();
+            assert_eq!(rev, expected);
+        }};
+    }
+
+    assert_range!(..);
+    assert_range!(5..);
+    assert_range!(..5);
+    assert_range!(..=5);
+    assert_range!(3..9);
+    assert_range!(3..=9);
+    assert_range!(4..4);
+    assert_range!(15..100);
+    assert_range!((Bound::Excluded(3), Bound::Included(7)));
+    assert_range!((Bound::Excluded(3), Bound::Excluded(4)));
+
+    let iter = map.range(5..8);
+    assert_eq!(iter.clone().count(), 3);
+    assert_eq!(iter.count(), 3);
+}
+
+#[test]
+fn test_range_borrowed() {
+    let map = vec!["apple", "banana", "cherry", "date"]
+        .into_iter()
+        .map(|s| (RString::from(s), s.len()))
+        .collect::<RBTreeMap<RString, usize>>();
+
+    let keys = map
+        .range::<str, _>((Bound::Included("b"), Bound::Excluded("d")))
+        .map(|x| x.0.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(keys, vec!["banana", "cherry"]);
+}
+
+#[test]
+fn test_range_mut() {
+    let mut map = (0..10).map(|x| (x, x)).collect::<RBTreeMap<u32, u32>>();
+
+    for Tuple2(_, v) in map.range_mut(3..6).rev() {
+        *v = 0;
+    }
+
+    assert_eq!(
+        map.values().copied().collect::<Vec<_>>(),
+        vec![0, 1, 2, 0, 0, 0, 6, 7, 8, 9],
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_range_start_greater() {
+    let map = new_map();
+    #[allow(clippy::reversed_empty_ranges)]
+    let _ = map.range(10..5);
+}
+
+#[test]
+#[should_panic]
+fn test_range_equal_excluded() {
+    let map = new_map();
+    let _ = map.range((Bound::Excluded(5), Bound::Excluded(5)));
+}
+
+#[test]
+fn test_entry() {
+    let mut map = new_map();
+
+    *map.entry(10).or_insert(0) += 1;
+    *map.entry(11).or_insert(0) += 1;
+
+    match map.entry(77) {
+        REntry::Occupied(entry) => assert_eq!(entry.remove(), 22),
+        REntry::Vacant(_) => unreachable!(),
+    }
+
+    assert_eq!(
+        map.iter().map(Tuple2::into_rust).collect::<Vec<_>>(),
+        vec![(&10, &21), (&11, &1), (&88, &30), (&90, &40)],
+    );
+}
+
+#[test]
+fn test_conversions_and_cmp() {
+    let std = new_stdmap();
+    let map = RBTreeMap::from(std.clone());
+
+    assert_eq!(BTreeMap::from(map.clone()), std);
+    assert_eq!(map.clone(), map);
+
+    let mut bigger = map.clone();
+    bigger.insert(0, 100);
+    assert!(bigger < map);
+    assert_ne!(bigger, map);
+
+    assert_eq!(format!("{:?}", map), format!("{:?}", std));
+}
+
+#[test]
+fn test_serde() {
+    let json = r##"
+        {
+            "c": "3",
+            "a": "1",
+            "b": "2"
+        }
+    "##;
+
+    let map = serde_json::from_str::<RBTreeMap<RString, RString>>(json).unwrap();
+    assert_eq!(
+        map.keys().map(RString::as_str).collect::<Vec<_>>(),
+        vec!["a", "b", "c"],
+    );
+
+    let serialized = serde_json::to_string(&map).unwrap();
+    assert_eq!(serialized, r#"{"a":"1","b":"2","c":"3"}"#);
+
+    let redeserialized = serde_json::from_str::<RBTreeMap<RString, RString>>(&serialized).unwrap();
+    assert_eq!(redeserialized, map);
+}
@@ -0,0 +1,1027 @@
+//! Contains the ffi-safe equivalent of `std::collections::BTreeSet`, and related items.
+#![allow(clippy::missing_const_for_fn)]
+
+use std::{
+    borrow::Borrow,
+    cmp::Ordering,
+    collections::BTreeSet,
+    fmt::{self, Debug},
+    hash::{Hash, Hasher},
+    iter::FromIterator,
+    mem,
+    ops::RangeBounds,
+};
+
+use crate::{
+    std_types::{
+        btree_map::{self, RBTreeMap},
+        ROption, Tuple2,
+    },
+    StableAbi,
+};
+
+#[cfg(all(test, not(feature = "only_new_tests")))]
+mod test;
+
+/// An ffi-safe ordered set, which wraps `std::collections::BTreeSet<T>`,
+/// only requiring the `T: Ord` bound when constructing it.
+///
+/// This is implemented on top of [`RBTreeMap<T, ()>`](crate::std_types::RBTreeMap),
+/// sharing its vtable.
+///
+/// # Example
+///
+/// ```
+/// use abi_stable::std_types::RBTreeSet;
+///
+/// let mut set = RBTreeSet::new();
+///
+/// assert!(set.insert(8));
+/// assert!(set.insert(3));
+/// assert!(set.insert(5));
+/// assert!(!set.insert(3));
+///
+/// assert_eq!(set.iter().collect::<Vec<_>>(), [&3, &5, &8]);
+///
+/// let other = vec![5, 8, 13].into_iter().collect::<RBTreeSet<u32>>();
+///
+/// assert_eq!(set.union(&other).collect::<Vec<_>>(), [&3, &5, &8, &13]);
+/// assert_eq!(set.intersection(&other).collect::<Vec<_>>(), [&5, &8]);
+/// assert_eq!(set.difference(&other).collect::<Vec<_>>(), [&3]);
+///
+/// ```
+///
+#[derive(StableAbi)]
+#[repr(C)]
+pub struct RBTreeSet<T> {
+    map: RBTreeMap<T, ()>,
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+impl<T> RBTreeSet<T> {
+    /// Constructs an empty RBTreeSet.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RBTreeSet, RString};
+    ///
+    /// let mut set = RBTreeSet::<RString>::new();
+    /// assert!(set.is_empty());
+    /// set.insert("Hello".into());
+    /// assert_eq!(set.is_empty(), false);
+    ///
+    /// ```
+    #[inline]
+    pub fn new() -> RBTreeSet<T>
+    where
+        T: Ord,
+    {
+        RBTreeSet {
+            map: RBTreeMap::new(),
+        }
+    }
+
+    /// Returns whether the set contains the value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RBTreeSet, RString};
+    ///
+    /// let mut set = RBTreeSet::<RString>::new();
+    /// assert_eq!(set.contains("boo"), false);
+    /// set.insert("boo".into());
+    /// assert_eq!(set.contains("boo"), true);
+    ///
+    /// ```
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.map.contains_key(value)
+    }
+
+    /// Returns whether the set contains the value.
+    ///
+    /// This is a variant of `contains` that takes a `&T` instead of a borrowed query.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBTreeSet;
+    ///
+    /// let mut set = RBTreeSet::<u32>::new();
+    /// assert_eq!(set.contains_p(&11), false);
+    /// set.insert(11);
+    /// assert_eq!(set.contains_p(&11), true);
+    ///
+    /// ```
+    pub fn contains_p(&self, value: &T) -> bool {
+        self.map.contains_key_p(value)
+    }
+
+    /// Adds a value to the set, returning whether it wasn't already present.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBTreeSet;
+    ///
+    /// let mut set = RBTreeSet::<u32>::new();
+    ///
+    /// assert_eq!(set.insert(3), true);
+    /// assert_eq!(set.insert(3), false);
+    /// assert_eq!(set.len(), 1);
+    ///
+    /// ```
+    pub fn insert(&mut self, value: T) -> bool {
+        self.map.insert(value, ()).is_none()
+    }
+
+    /// Removes a value from the set, returning whether it was present.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBTreeSet;
+    ///
+    /// let mut set = vec![0, 3].into_iter().collect::<RBTreeSet<u32>>();
+    ///
+    /// assert_eq!(set.remove(&0), true);
+    /// assert_eq!(set.remove(&0), false);
+    ///
+    /// ```
+    pub fn remove<Q>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.map.remove(value).is_some()
+    }
+
+    /// Removes a value from the set, returning it if it was present.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RBTreeSet, RNone, RSome, RString};
+    ///
+    /// let mut set = vec![RString::from("foo")].into_iter().collect::<RBTreeSet<_>>();
+    ///
+    /// assert_eq!(set.take("foo"), RSome(RString::from("foo")));
+    /// assert_eq!(set.take("foo"), RNone);
+    ///
+    /// ```
+    pub fn take<Q>(&mut self, value: &Q) -> ROption<T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.map.remove_entry(value).map(|x| x.0)
+    }
+
+    /// Removes a value from the set, returning whether it was present.
+    ///
+    /// This is a variant of `remove` that takes a `&T` instead of a borrowed query.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBTreeSet;
+    ///
+    /// let mut set = vec![0, 3].into_iter().collect::<RBTreeSet<u32>>();
+    ///
+    /// assert_eq!(set.remove_p(&0), true);
+    /// assert_eq!(set.remove_p(&0), false);
+    ///
+    /// ```
+    pub fn remove_p(&mut self, value: &T) -> bool {
+        self.map.remove_p(value).is_some()
+    }
+
+    /// Removes all the values in the set.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBTreeSet;
+    ///
+    /// let mut set = vec![0, 3].into_iter().collect::<RBTreeSet<u32>>();
+    ///
+    /// set.clear();
+    /// assert!(set.is_empty());
+    ///
+    /// ```
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+
+    /// Returns the amount of values in the set.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBTreeSet;
+    ///
+    /// let mut set = RBTreeSet::<u32>::new();
+    ///
+    /// assert_eq!(set.len(), 0);
+    /// set.insert(0);
+    /// assert_eq!(set.len(), 1);
+    ///
+    /// ```
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns whether the set contains any values.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBTreeSet;
+    ///
+    /// let mut set = RBTreeSet::<u32>::new();
+    ///
+    /// assert_eq!(set.is_empty(), true);
+    /// set.insert(0);
+    /// assert_eq!(set.is_empty(), false);
+    ///
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Returns the smallest value in the set.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBTreeSet;
+    ///
+    /// let set = vec![5, 3, 8].into_iter().collect::<RBTreeSet<u32>>();
+    /// assert_eq!(set.first(), Some(&3));
+    ///
+    /// ```
+    pub fn first(&self) -> Option<&T> {
+        self.map.first_key_value().map(|x| x.0)
+    }
+
+    /// Returns the largest value in the set.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBTreeSet;
+    ///
+    /// let set = vec![5, 3, 8].into_iter().collect::<RBTreeSet<u32>>();
+    /// assert_eq!(set.last(), Some(&8));
+    ///
+    /// ```
+    pub fn last(&self) -> Option<&T> {
+        self.map.last_key_value().map(|x| x.0)
+    }
+
+    /// Iterates over the values in the set in ascending order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBTreeSet;
+    ///
+    /// let set = vec![3, 0].into_iter().collect::<RBTreeSet<u32>>();
+    ///
+    /// assert_eq!(set.iter().collect::<Vec<_>>(), [&0, &3]);
+    /// assert_eq!(set.iter().rev().collect::<Vec<_>>(), [&3, &0]);
+    ///
+    /// ```
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            inner: self.map.keys(),
+        }
+    }
+
+    /// Iterates over the values in `range`, in ascending order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of the range is greater than the end,
+    /// or if the start and end are equal and both excluded.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBTreeSet;
+    ///
+    /// let set = (0..10).collect::<RBTreeSet<u32>>();
+    ///
+    /// assert_eq!(set.range(3..6).collect::<Vec<_>>(), [&3, &4, &5]);
+    /// assert_eq!(set.range(8..).rev().collect::<Vec<_>>(), [&9, &8]);
+    ///
+    /// ```
+    pub fn range<Q, R>(&self, range: R) -> Range<'_, T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        Range {
+            inner: self.map.range(range),
+        }
+    }
+}
+
+impl<T> RBTreeSet<T>
+where
+    T: Ord,
+{
+    /// Iterates over the values that are in `self` but not in `other`, in ascending order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBTreeSet;
+    ///
+    /// let a = vec![1, 2, 3].into_iter().collect::<RBTreeSet<u32>>();
+    /// let b = vec![2, 3, 4].into_iter().collect::<RBTreeSet<u32>>();
+    ///
+    /// assert_eq!(a.difference(&b).collect::<Vec<_>>(), [&1]);
+    /// assert_eq!(b.difference(&a).collect::<Vec<_>>(), [&4]);
+    ///
+    /// ```
+    pub fn difference<'a>(&'a self, other: &'a RBTreeSet<T>) -> Difference<'a, T> {
+        Difference {
+            iter: MergeIter::new(self.iter(), other.iter()),
+        }
+    }
+
+    /// Iterates over the values that are in either `self` or `other`, but not in both,
+    /// in ascending order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBTreeSet;
+    ///
+    /// let a = vec![1, 2, 3].into_iter().collect::<RBTreeSet<u32>>();
+    /// let b = vec![2, 3, 4].into_iter().collect::<RBTreeSet<u32>>();
+    ///
+    /// assert_eq!(a.symmetric_difference(&b).collect::<Vec<_>>(), [&1, &4]);
+    ///
+    /// ```
+    pub fn symmetric_difference<'a>(
+        &'a self,
+        other: &'a RBTreeSet<T>,
+    ) -> SymmetricDifference<'a, T> {
+        SymmetricDifference {
+            iter: MergeIter::new(self.iter(), other.iter()),
+        }
+    }
+
+    /// Iterates over the values that are in both `self` and `other`, in ascending order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBTreeSet;
+    ///
+    /// let a = vec![1, 2, 3].into_iter().collect::<RBTreeSet<u32>>();
+    /// let b = vec![2, 3, 4].into_iter().collect::<RBTreeSet<u32>>();
+    ///
+    /// assert_eq!(a.intersection(&b).collect::<Vec<_>>(), [&2, &3]);
+    ///
+    /// ```
+    pub fn intersection<'a>(&'a self, other: &'a RBTreeSet<T>) -> Intersection<'a, T> {
+        Intersection {
+            iter: MergeIter::new(self.iter(), other.iter()),
+        }
+    }
+
+    /// Iterates over the values that are in `self` or `other`, without duplicates,
+    /// in ascending order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBTreeSet;
+    ///
+    /// let a = vec![1, 2, 3].into_iter().collect::<RBTreeSet<u32>>();
+    /// let b = vec![2, 3, 4].into_iter().collect::<RBTreeSet<u32>>();
+    ///
+    /// assert_eq!(a.union(&b).collect::<Vec<_>>(), [&1, &2, &3, &4]);
+    ///
+    /// ```
+    pub fn union<'a>(&'a self, other: &'a RBTreeSet<T>) -> Union<'a, T> {
+        Union {
+            iter: MergeIter::new(self.iter(), other.iter()),
+        }
+    }
+
+    /// Returns whether `self` and `other` have no values in common.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBTreeSet;
+    ///
+    /// let a = vec![1, 2].into_iter().collect::<RBTreeSet<u32>>();
+    /// let b = vec![3, 4].into_iter().collect::<RBTreeSet<u32>>();
+    /// let c = vec![2, 3].into_iter().collect::<RBTreeSet<u32>>();
+    ///
+    /// assert!(a.is_disjoint(&b));
+    /// assert!(!a.is_disjoint(&c));
+    ///
+    /// ```
+    pub fn is_disjoint(&self, other: &RBTreeSet<T>) -> bool {
+        self.intersection(other).next().is_none()
+    }
+
+    /// Returns whether all the values in `self` are also in `other`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBTreeSet;
+    ///
+    /// let a = vec![1, 2].into_iter().collect::<RBTreeSet<u32>>();
+    /// let b = vec![1, 2, 3].into_iter().collect::<RBTreeSet<u32>>();
+    ///
+    /// assert!(a.is_subset(&b));
+    /// assert!(!b.is_subset(&a));
+    ///
+    /// ```
+    pub fn is_subset(&self, other: &RBTreeSet<T>) -> bool {
+        self.len() <= other.len() && self.difference(other).next().is_none()
+    }
+
+    /// Returns whether all the values in `other` are also in `self`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBTreeSet;
+    ///
+    /// let a = vec![1, 2].into_iter().collect::<RBTreeSet<u32>>();
+    /// let b = vec![1, 2, 3].into_iter().collect::<RBTreeSet<u32>>();
+    ///
+    /// assert!(b.is_superset(&a));
+    /// assert!(!a.is_superset(&b));
+    ///
+    /// ```
+    pub fn is_superset(&self, other: &RBTreeSet<T>) -> bool {
+        other.is_subset(self)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// An iterator over the values of an `RBTreeSet`, in ascending order.
+///
+/// This `struct` is created by the [`iter`] method on [`RBTreeSet`].
+///
+/// [`iter`]: RBTreeSet::iter
+#[repr(C)]
+#[derive(StableAbi)]
+pub struct Iter<'a, T> {
+    inner: btree_map::Keys<'a, T, ()>,
+}
+
+impl<T> Clone for Iter<'_, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Iter {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: Debug> Debug for Iter<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a T> {
+        self.inner.next()
+    }
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a T> {
+        self.inner.next_back()
+    }
+}
+
+/// An iterator over a range of values of an `RBTreeSet`, in ascending order.
+///
+/// This `struct` is created by the [`range`] method on [`RBTreeSet`].
+///
+/// [`range`]: RBTreeSet::range
+#[repr(C)]
+#[derive(StableAbi)]
+pub struct Range<'a, T: 'a> {
+    inner: btree_map::Range<'a, T, ()>,
+}
+
+impl<T> Clone for Range<'_, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Range {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: Debug> Debug for Range<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<'a, T> Iterator for Range<'a, T> {
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a T> {
+        self.inner.next().map(|x| x.0)
+    }
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Range<'a, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a T> {
+        self.inner.next_back().map(|x| x.0)
+    }
+}
+
+/// An iterator that yields all the values of an `RBTreeSet` in ascending order,
+/// deallocating the set afterwards.
+///
+/// This implements `DoubleEndedIterator<Item= T > + !Send + !Sync`
+#[repr(C)]
+#[derive(StableAbi)]
+pub struct IntoIter<T> {
+    inner: btree_map::IntoIter<T, ()>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        self.inner.next().map(|x| x.0)
+    }
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<T> {
+        self.inner.next_back().map(|x| x.0)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// Iterates over two sorted iterators in lockstep,
+/// returning the next value of either, or of both if they're equal.
+struct MergeIter<'a, T> {
+    a: Iter<'a, T>,
+    b: Iter<'a, T>,
+    peeked: Peeked<'a, T>,
+}
+
+enum Peeked<'a, T> {
+    Nothing,
+    A(&'a T),
+    B(&'a T),
+}
+
+impl<T> Clone for Peeked<'_, T> {
+    fn clone(&self) -> Self {
+        match *self {
+            Peeked::Nothing => Peeked::Nothing,
+            Peeked::A(x) => Peeked::A(x),
+            Peeked::B(x) => Peeked::B(x),
+        }
+    }
+}
+
+impl<T> Clone for MergeIter<'_, T> {
+    fn clone(&self) -> Self {
+        MergeIter {
+            a: self.a.clone(),
+            b: self.b.clone(),
+            peeked: self.peeked.clone(),
+        }
+    }
+}
+
+impl<'a, T: Ord> MergeIter<'a, T> {
+    fn new(a: Iter<'a, T>, b: Iter<'a, T>) -> Self {
+        MergeIter {
+            a,
+            b,
+            peeked: Peeked::Nothing,
+        }
+    }
+
+    fn nexts(&mut self) -> (Option<&'a T>, Option<&'a T>) {
+        let (a_next, b_next) = match mem::replace(&mut self.peeked, Peeked::Nothing) {
+            Peeked::A(a) => (Some(a), self.b.next()),
+            Peeked::B(b) => (self.a.next(), Some(b)),
+            Peeked::Nothing => (self.a.next(), self.b.next()),
+        };
+
+        if let (Some(a), Some(b)) = (a_next, b_next) {
+            match a.cmp(b) {
+                Ordering::Less => {
+                    self.peeked = Peeked::B(b);
+                    return (Some(a), None);
+                }
+                Ordering::Greater => {
+                    self.peeked = Peeked::A(a);
+                    return (None, Some(b));
+                }
+                Ordering::Equal => {}
+            }
+        }
+        (a_next, b_next)
+    }
+
+    fn lens(&self) -> (usize, usize) {
+        let peeked = match self.peeked {
+            Peeked::Nothing => (0, 0),
+            Peeked::A(_) => (1, 0),
+            Peeked::B(_) => (0, 1),
+        };
+        (
+            self.a.size_hint().0 + peeked.0,
+            self.b.size_hint().0 + peeked.1,
+        )
+    }
+}
+
+macro_rules! declare_set_op_iter {
+    (
+        $(#[$attr:meta])*
+        struct $name:ident;
+        size_hint($a_len:ident, $b_len:ident) = $size_hint:expr;
+        fn next($iter:ident) $next:block
+    ) => {
+        $(#[$attr])*
+        pub struct $name<'a, T> {
+            iter: MergeIter<'a, T>,
+        }
+
+        impl<T> Clone for $name<'_, T> {
+            fn clone(&self) -> Self {
+                $name {
+                    iter: self.iter.clone(),
+                }
+            }
+        }
+
+        impl<T> Debug for $name<'_, T>
+        where
+            T: Debug + Ord,
+        {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.debug_list().entries(self.clone()).finish()
+            }
+        }
+
+        impl<'a, T> Iterator for $name<'a, T>
+        where
+            T: Ord,
+        {
+            type Item = &'a T;
+
+            fn next(&mut self) -> Option<&'a T> {
+                let $iter = &mut self.iter;
+                $next
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                let ($a_len, $b_len) = self.iter.lens();
+                $size_hint
+            }
+        }
+    };
+}
+
+declare_set_op_iter! {
+    /// An iterator over the values that are in one `RBTreeSet` but not in another,
+    /// in ascending order.
+    ///
+    /// This `struct` is created by the [`difference`] method on [`RBTreeSet`].
+    ///
+    /// [`difference`]: RBTreeSet::difference
+    struct Difference;
+    size_hint(a_len, b_len) = (a_len.saturating_sub(b_len), None);
+    fn next(iter) {
+        loop {
+            match iter.nexts() {
+                (Some(a), None) => return Some(a),
+                (None, None) => return None,
+                _ => {}
+            }
+        }
+    }
+}
+
+declare_set_op_iter! {
+    /// An iterator over the values that are in exactly one of two `RBTreeSet`s,
+    /// in ascending order.
+    ///
+    /// This `struct` is created by the [`symmetric_difference`] method on [`RBTreeSet`].
+    ///
+    /// [`symmetric_difference`]: RBTreeSet::symmetric_difference
+    struct SymmetricDifference;
+    size_hint(a_len, b_len) = (0, Some(a_len + b_len));
+    fn next(iter) {
+        loop {
+            match iter.nexts() {
+                (Some(_), Some(_)) => {}
+                (a, b) => return a.or(b),
+            }
+        }
+    }
+}
+
+declare_set_op_iter! {
+    /// An iterator over the values that are in both of two `RBTreeSet`s,
+    /// in ascending order.
+    ///
+    /// This `struct` is created by the [`intersection`] method on [`RBTreeSet`].
+    ///
+    /// [`intersection`]: RBTreeSet::intersection
+    struct Intersection;
+    size_hint(a_len, b_len) = (0, Some(a_len.min(b_len)));
+    fn next(iter) {
+        loop {
+            match iter.nexts() {
+                (Some(a), Some(_)) => return Some(a),
+                (None, None) => return None,
+                _ => {}
+            }
+        }
+    }
+}
+
+declare_set_op_iter! {
+    /// An iterator over the values that are in either of two `RBTreeSet`s,
+    /// without duplicates, in ascending order.
+    ///
+    /// This `struct` is created by the [`union`] method on [`RBTreeSet`].
+    ///
+    /// [`union`]: RBTreeSet::union
+    struct Union;
+    size_hint(a_len, b_len) = (a_len.max(b_len), Some(a_len + b_len));
+    fn next(iter) {
+        let (a, b) = iter.nexts();
+        a.or(b)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// This returns a `DoubleEndedIterator<Item= T >+!Send+!Sync`
+impl<T> IntoIterator for RBTreeSet<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter {
+            inner: self.map.into_iter(),
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a RBTreeSet<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T> From<BTreeSet<T>> for RBTreeSet<T>
+where
+    T: Ord,
+{
+    fn from(set: BTreeSet<T>) -> Self {
+        set.into_iter().collect()
+    }
+}
+
+impl<T> From<RBTreeSet<T>> for BTreeSet<T>
+where
+    T: Ord,
+{
+    fn from(this: RBTreeSet<T>) -> BTreeSet<T> {
+        this.into_iter().collect()
+    }
+}
+
+impl<T> FromIterator<T> for RBTreeSet<T>
+where
+    T: Ord,
+{
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<T> Extend<T> for RBTreeSet<T> {
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        self.map.extend(iter.into_iter().map(|x| Tuple2(x, ())));
+    }
+}
+
+impl<'a, T> Extend<&'a T> for RBTreeSet<T>
+where
+    T: Copy + 'a,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = &'a T>,
+    {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+impl<T> Default for RBTreeSet<T>
+where
+    T: Ord,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for RBTreeSet<T>
+where
+    T: Ord + Clone,
+{
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T> Debug for RBTreeSet<T>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<T> Eq for RBTreeSet<T> where T: Eq {}
+
+impl<T> PartialEq for RBTreeSet<T>
+where
+    T: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<T> PartialOrd for RBTreeSet<T>
+where
+    T: PartialOrd,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T> Ord for RBTreeSet<T>
+where
+    T: Ord,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T> Hash for RBTreeSet<T>
+where
+    T: Hash,
+{
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        state.write_usize(self.len());
+        for elem in self.iter() {
+            elem.hash(state);
+        }
+    }
+}
+
+mod serde {
+    use super::*;
+
+    use crate::marker_type::NonOwningPhantom;
+
+    use ::serde::{
+        de::{SeqAccess, Visitor},
+        ser::SerializeSeq,
+        Deserialize, Deserializer, Serialize, Serializer,
+    };
+
+    struct RBTreeSetVisitor<T> {
+        _marker: NonOwningPhantom<RBTreeSet<T>>,
+    }
+
+    impl<T> RBTreeSetVisitor<T> {
+        fn new() -> Self {
+            RBTreeSetVisitor {
+                _marker: NonOwningPhantom::NEW,
+            }
+        }
+    }
+
+    impl<'de, T> Visitor<'de> for RBTreeSetVisitor<T>
+    where
+        T: Deserialize<'de> + Ord,
+    {
+        type Value = RBTreeSet<T>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("an RBTreeSet")
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let mut set = RBTreeSet::new();
+
+            while let Some(v) = seq.next_element()? {
+                set.insert(v);
+            }
+
+            Ok(set)
+        }
+    }
+
+    impl<'de, T> Deserialize<'de> for RBTreeSet<T>
+    where
+        T: Deserialize<'de> + Ord,
+    {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserializer.deserialize_seq(RBTreeSetVisitor::new())
+        }
+    }
+
+    impl<T> Serialize for RBTreeSet<T>
+    where
+        T: Serialize,
+    {
+        fn serialize<Z>(&self, serializer: Z) -> Result<Z::Ok, Z::Error>
+        where
+            Z: Serializer,
+        {
+            let mut seq = serializer.serialize_seq(Some(self.len()))?;
+            for v in self.iter() {
+                seq.serialize_element(v)?;
+            }
+            seq.end()
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use super::*;
+
+use crate::std_types::{RSome, RString};
+
+fn _covariant_rbtreeset<'a: 'b, 'b, T>(foo: RBTreeSet<&'a T>) -> RBTreeSet<&'b T> {
+    foo
+}
+
+fn set_of(list: &[u32]) -> RBTreeSet<u32> {
+    list.iter().copied().collect()
+}
+
+fn std_set_of(list: &[u32]) -> BTreeSet<u32> {
+    list.iter().copied().collect()
+}
+
+#[test]
+fn test_insert_remove() {
+    let mut set = RBTreeSet::<RString>::new();
+
+    assert!(set.insert("foo".into()));
+    assert!(set.insert("bar".into()));
+    assert!(!set.insert("foo".into()));
+    assert_eq!(set.len(), 2);
+
+    assert!(set.contains("foo"));
+    assert!(set.contains_p(&RString::from("bar")));
+    assert!(!set.contains("baz"));
+
+    assert_eq!(set.first().map(RString::as_str), Some("bar"));
+    assert_eq!(set.last().map(RString::as_str), Some("foo"));
+
+    assert!(set.remove("bar"));
+    assert!(!set.remove("bar"));
+    assert_eq!(set.take("foo"), RSome(RString::from("foo")));
+    assert!(set.is_empty());
+}
+
+#[test]
+fn test_ordered_iteration() {
+    let set = set_of(&[8, 3, 5, 1]);
+
+    assert_eq!(set.iter().copied().collect::<Vec<_>>(), vec![1, 3, 5, 8]);
+    assert_eq!(
+        set.iter().rev().copied().collect::<Vec<_>>(),
+        vec![8, 5, 3, 1]
+    );
+    assert_eq!(set.range(2..=5).copied().collect::<Vec<_>>(), vec![3, 5]);
+    assert_eq!(
+        set.range(..4).rev().copied().collect::<Vec<_>>(),
+        vec![3, 1]
+    );
+    assert_eq!(
+        set.clone().into_iter().rev().collect::<Vec<_>>(),
+        vec![8, 5, 3, 1]
+    );
+}
+
+#[test]
+fn test_set_operations() {
+    let lists: &[&[u32]] = &[
+        &[],
+        &[1, 2, 3, 4],
+        &[3, 4, 5],
+        &[0, 2, 4, 6, 8],
+        &[10, 11],
+        &[4],
+    ];
+
+    for a in lists {
+        for b in lists {
+            let (ra, rb) = (set_of(a), set_of(b));
+            let (sa, sb) = (std_set_of(a), std_set_of(b));
+
+            macro_rules! assert_op {
+                ($method:ident) => {
+                    assert_eq!(
+                        ra.$method(&rb).collect::<Vec<_>>(),
+                        sa.$method(&sb).collect::<Vec<_>>(),
+                        "{}({:?}, {:?})",
+                        stringify!($method),
+                        a,
+                        b,
+                    );
+                };
+            }
+
+            assert_op!(union);
+            assert_op!(intersection);
+            assert_op!(difference);
+            assert_op!(symmetric_difference);
+
+            assert_eq!(ra.is_disjoint(&rb), sa.is_disjoint(&sb));
+            assert_eq!(ra.is_subset(&rb), sa.is_subset(&sb));
+            assert_eq!(ra.is_superset(&rb), sa.is_superset(&sb));
+        }
+    }
+}
+
+#[test]
+fn test_conversions_and_cmp() {
+    let std = std_set_of(&[1, 2, 3]);
+    let set = RBTreeSet::from(std.clone());
+
+    assert_eq!(BTreeSet::from(set.clone()), std);
+    assert_eq!(set.clone(), set);
+    assert!(set_of(&[0, 5]) < set);
+
+    let mut extended = set_of(&[1]);
+    extended.extend(&[2, 3]);
+    assert_eq!(extended, set);
+
+    assert_eq!(format!("{:?}", set), format!("{:?}", std));
+}
+
+#[test]
+fn test_serde() {
+    let set = serde_json::from_str::<RBTreeSet<RString>>(r#"["c", "a", "b", "a"]"#).unwrap();
+
+    let serialized = serde_json::to_string(&set).unwrap();
+    assert_eq!(serialized, r#"["a","b","c"]"#);
+
+    let redeserialized = serde_json::from_str::<RBTreeSet<RString>>(&serialized).unwrap();
+    assert_eq!(redeserialized, set);
+}
@@ -0,0 +1,1229 @@
+//! Contains an ffi-safe hash map that preserves the insertion order of its entries,
+//! and related items.
+
+use std::{
+    borrow::Borrow,
+    collections::{hash_map::RandomState, HashMap},
+    fmt::{self, Debug},
+    hash::{BuildHasher, BuildHasherDefault, Hash, Hasher},
+    iter::FromIterator,
+    marker::PhantomData,
+    mem,
+    ops::{Index, IndexMut},
+};
+
+use crate::{
+    marker_type::{ErasedPrefix, NonOwningPhantom, UnsafeIgnoredType},
+    pointer_trait::{AsMutPtr, AsPtr},
+    prefix_type::{PrefixRef, WithMetadata},
+    sabi_types::{RMut, RRef},
+    std_types::{map::map_query::MapQuery, RBox, RNone, ROption, RSlice, RSome, RVec, Tuple2},
+    traits::{ErasedType, IntoReprRust},
+    StableAbi,
+};
+
+mod extern_fns;
+mod iterators;
+
+#[cfg(all(test, not(feature = "only_new_tests")))]
+mod test;
+
+pub use self::iterators::{IntoIter, Iter, IterMut, Keys, Values, ValuesMut};
+
+/// An ffi-safe hash map which iterates over its entries in insertion order,
+/// and allows accessing them by index,
+/// only requiring the `K: Eq + Hash` bounds when constructing it.
+///
+/// The entries are stored contiguously in an [`RVec`],
+/// with a hash table of their indices used to look up keys.
+///
+/// Inserting a key that is already in the map replaces its value,
+/// without changing its position.
+///
+/// # Removal
+///
+/// There are two ways to remove entries,
+/// which differ in how they affect the order of the remaining entries:
+///
+/// - [`swap_remove`](Self::swap_remove): replaces the removed entry with the last entry,
+/// this takes `O(1)` time.
+///
+/// - [`shift_remove`](Self::shift_remove): shifts all the entries after the removed one,
+/// preserving their order, this takes `O(n)` time.
+///
+/// # Example
+///
+/// ```
+/// use abi_stable::std_types::{RIndexMap, RSome, Tuple2};
+///
+/// let mut map = RIndexMap::new();
+///
+/// map.insert("name", "plugin");
+/// map.insert("version", "1.0");
+/// map.insert("author", "someone");
+///
+/// assert_eq!(
+///     map.keys().copied().collect::<Vec<_>>(),
+///     ["name", "version", "author"],
+/// );
+///
+/// assert_eq!(map["version"], "1.0");
+/// assert_eq!(map.get_index(2), Some(Tuple2(&"author", &"someone")));
+///
+/// assert_eq!(map.shift_remove("name"), RSome("plugin"));
+/// assert_eq!(
+///     map.keys().copied().collect::<Vec<_>>(),
+///     ["version", "author"],
+/// );
+///
+/// ```
+///
+#[derive(StableAbi)]
+#[repr(C)]
+#[sabi(
+    // The hasher doesn't matter
+    unsafe_unconstrained(S),
+)]
+pub struct RIndexMap<K, V, S = RandomState> {
+    entries: RVec<Bucket<K, V>>,
+    indices: RBox<ErasedIndices<K, V, S>>,
+    #[sabi(unsafe_change_type = VTable_Ref<K, V, S>)]
+    vtable: PrefixRef<ErasedPrefix>,
+}
+
+/// An entry of the map, along with the hash of its key.
+#[derive(StableAbi)]
+#[repr(C)]
+struct Bucket<K, V> {
+    hash: u64,
+    key: K,
+    value: V,
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// The hash table of the indices of the entries in an `RIndexMap`.
+struct IndexTable<S> {
+    indices: HashMap<u64, Slots, BuildHasherDefault<HashValueHasher>>,
+    hash_builder: S,
+}
+
+/// The indices of the entries whose keys have the same hash.
+enum Slots {
+    One(usize),
+    Many(Vec<usize>),
+}
+
+/// A hasher for keys that are already hashes.
+#[derive(Default)]
+struct HashValueHasher(u64);
+
+impl Hasher for HashValueHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = self.0.rotate_left(8) ^ u64::from(byte);
+        }
+    }
+
+    fn write_u64(&mut self, hash: u64) {
+        self.0 = hash;
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// Used as the erased type of the `IndexTable` of an `RIndexMap`.
+#[repr(C)]
+#[derive(StableAbi)]
+#[sabi(
+    // The hasher doesn't matter
+    unsafe_unconstrained(S),
+)]
+struct ErasedIndices<K, V, S>(PhantomData<(K, V)>, UnsafeIgnoredType<S>);
+
+impl<'a, K: 'a, V: 'a, S: 'a> ErasedType<'a> for ErasedIndices<K, V, S> {
+    type Unerased = IndexTable<S>;
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+impl<K, V> RIndexMap<K, V, RandomState> {
+    /// Constructs an empty RIndexMap.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RIndexMap, RString};
+    ///
+    /// let mut map = RIndexMap::<RString, u32>::new();
+    /// assert!(map.is_empty());
+    /// map.insert("Hello".into(), 10);
+    /// assert_eq!(map.is_empty(), false);
+    ///
+    /// ```
+    #[inline]
+    pub fn new() -> RIndexMap<K, V>
+    where
+        Self: Default,
+    {
+        Self::default()
+    }
+
+    /// Constructs an empty RIndexMap with at least the passed capacity.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RIndexMap, RString};
+    ///
+    /// let mut map = RIndexMap::<RString, u32>::with_capacity(10);
+    /// assert!(map.capacity() >= 10);
+    ///
+    /// ```
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> RIndexMap<K, V>
+    where
+        Self: Default,
+    {
+        let mut this = Self::default();
+        this.reserve(capacity);
+        this
+    }
+}
+
+impl<K, V, S> RIndexMap<K, V, S> {
+    /// Constructs an empty RIndexMap with the passed `hash_builder` to hash the keys.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RIndexMap, RString};
+    /// use std::collections::hash_map::RandomState;
+    ///
+    /// let s = RandomState::new();
+    /// let mut map = RIndexMap::<RString, u32, _>::with_hasher(s);
+    /// assert!(map.is_empty());
+    /// map.insert("Hello".into(), 10);
+    /// assert_eq!(map.is_empty(), false);
+    ///
+    /// ```
+    #[inline]
+    pub fn with_hasher(hash_builder: S) -> RIndexMap<K, V, S>
+    where
+        K: Eq + Hash,
+        S: BuildHasher,
+    {
+        Self::with_capacity_and_hasher(0, hash_builder)
+    }
+
+    /// Constructs an empty RIndexMap with at least the passed capacity,
+    /// and the passed `hash_builder` to hash the keys.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RIndexMap, RString};
+    /// use std::collections::hash_map::RandomState;
+    ///
+    /// let s = RandomState::new();
+    /// let mut map = RIndexMap::<RString, u32, _>::with_capacity_and_hasher(10, s);
+    /// assert!(map.capacity() >= 10);
+    ///
+    /// ```
+    pub fn with_capacity_and_hasher(capacity: usize, hash_builder: S) -> RIndexMap<K, V, S>
+    where
+        K: Eq + Hash,
+        S: BuildHasher,
+    {
+        let mut this = RIndexMap {
+            entries: RVec::new(),
+            indices: VTable::<K, V, S>::erased_indices(hash_builder),
+            vtable: unsafe { VTable::<K, V, S>::VTABLE_REF.0.cast() },
+        };
+        this.reserve(capacity);
+        this
+    }
+
+    fn vtable(&self) -> VTable_Ref<K, V, S> {
+        unsafe { VTable_Ref::<K, V, S>(self.vtable.cast()) }
+    }
+}
+
+impl<K, V, S> RIndexMap<K, V, S> {
+    /// Returns the index of the entry for the key.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RIndexMap, RString};
+    ///
+    /// let mut map = RIndexMap::<RString, u32>::new();
+    /// map.insert("foo".into(), 0);
+    /// map.insert("bar".into(), 1);
+    ///
+    /// assert_eq!(map.get_index_of("foo"), Some(0));
+    /// assert_eq!(map.get_index_of("bar"), Some(1));
+    /// assert_eq!(map.get_index_of("baz"), None);
+    ///
+    /// ```
+    pub fn get_index_of<Q>(&self, query: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let vtable = self.vtable();
+        unsafe {
+            vtable.get_index_of()(
+                self.indices.as_rref(),
+                self.entries.as_rslice(),
+                MapQuery::new(&query),
+            )
+            .into_option()
+        }
+    }
+
+    fn get_index_of_p(&self, key: &K) -> Option<usize> {
+        let vtable = self.vtable();
+        unsafe {
+            vtable.get_index_of_p()(self.indices.as_rref(), self.entries.as_rslice(), key)
+                .into_option()
+        }
+    }
+
+    /// Returns whether the map associates a value with the key.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RIndexMap, RString};
+    ///
+    /// let mut map = RIndexMap::<RString, u32>::new();
+    /// assert_eq!(map.contains_key("boo"), false);
+    /// map.insert("boo".into(), 0);
+    /// assert_eq!(map.contains_key("boo"), true);
+    ///
+    /// ```
+    pub fn contains_key<Q>(&self, query: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get_index_of(query).is_some()
+    }
+
+    /// Returns a reference to the value associated with the key.
+    ///
+    /// Returns a `None` if there is no entry for the key.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RIndexMap, RString};
+    ///
+    /// let mut map = RIndexMap::<RString, u32>::new();
+    /// assert_eq!(map.get("boo"), None);
+    /// map.insert("boo".into(), 0);
+    /// assert_eq!(map.get("boo"), Some(&0));
+    ///
+    /// ```
+    pub fn get<Q>(&self, query: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let index = self.get_index_of(query)?;
+        Some(&self.entries[index].value)
+    }
+
+    /// Returns a mutable reference to the value associated with the key.
+    ///
+    /// Returns a `None` if there is no entry for the key.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RIndexMap, RString};
+    ///
+    /// let mut map = RIndexMap::<RString, u32>::new();
+    /// assert_eq!(map.get_mut("boo"), None);
+    /// map.insert("boo".into(), 0);
+    /// assert_eq!(map.get_mut("boo"), Some(&mut 0));
+    ///
+    /// ```
+    pub fn get_mut<Q>(&mut self, query: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let index = self.get_index_of(query)?;
+        Some(&mut self.entries[index].value)
+    }
+
+    /// Returns references to the key and value of the entry at `index`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RIndexMap, Tuple2};
+    ///
+    /// let map = vec![(3, 4), (0, 1)].into_iter().collect::<RIndexMap<u32, u32>>();
+    ///
+    /// assert_eq!(map.get_index(0), Some(Tuple2(&3, &4)));
+    /// assert_eq!(map.get_index(1), Some(Tuple2(&0, &1)));
+    /// assert_eq!(map.get_index(2), None);
+    ///
+    /// ```
+    pub fn get_index(&self, index: usize) -> Option<Tuple2<&K, &V>> {
+        self.entries
+            .get(index)
+            .map(|bucket| Tuple2(&bucket.key, &bucket.value))
+    }
+
+    /// Returns a reference to the key and a mutable reference to the value
+    /// of the entry at `index`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RIndexMap, Tuple2};
+    ///
+    /// let mut map = vec![(3, 4), (0, 1)].into_iter().collect::<RIndexMap<u32, u32>>();
+    ///
+    /// if let Some(Tuple2(_, value)) = map.get_index_mut(1) {
+    ///     *value += 10;
+    /// }
+    ///
+    /// assert_eq!(map[&0], 11);
+    /// assert_eq!(map.get_index_mut(2), None);
+    ///
+    /// ```
+    pub fn get_index_mut(&mut self, index: usize) -> Option<Tuple2<&K, &mut V>> {
+        self.entries
+            .get_mut(index)
+            .map(|bucket| Tuple2(&bucket.key, &mut bucket.value))
+    }
+
+    /// Returns references to the key and value of the first entry.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RIndexMap, Tuple2};
+    ///
+    /// let mut map = RIndexMap::<u32, u32>::new();
+    /// assert_eq!(map.first(), None);
+    ///
+    /// map.insert(5, 6);
+    /// map.insert(1, 2);
+    /// assert_eq!(map.first(), Some(Tuple2(&5, &6)));
+    ///
+    /// ```
+    pub fn first(&self) -> Option<Tuple2<&K, &V>> {
+        self.get_index(0)
+    }
+
+    /// Returns references to the key and value of the last entry.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RIndexMap, Tuple2};
+    ///
+    /// let mut map = RIndexMap::<u32, u32>::new();
+    /// assert_eq!(map.last(), None);
+    ///
+    /// map.insert(5, 6);
+    /// map.insert(1, 2);
+    /// assert_eq!(map.last(), Some(Tuple2(&1, &2)));
+    ///
+    /// ```
+    pub fn last(&self) -> Option<Tuple2<&K, &V>> {
+        self.len()
+            .checked_sub(1)
+            .and_then(|index| self.get_index(index))
+    }
+
+    //////////////////////////////////
+
+    /// Inserts a value into the map, associating it with a key, returning the previous value.
+    ///
+    /// If the key was already in the map, its entry keeps its position,
+    /// otherwise the entry is added at the end.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RIndexMap, RNone, RSome};
+    ///
+    /// let mut map = RIndexMap::<u32, u32>::new();
+    ///
+    /// assert_eq!(map.insert(0, 1), RNone);
+    /// assert_eq!(map.insert(2, 3), RNone);
+    /// assert_eq!(map.insert(0, 5), RSome(1));
+    ///
+    /// assert_eq!(map.keys().copied().collect::<Vec<_>>(), [0, 2]);
+    /// assert_eq!(map[&0], 5);
+    ///
+    /// ```
+    pub fn insert(&mut self, key: K, value: V) -> ROption<V> {
+        self.insert_full(key, value).1
+    }
+
+    /// Inserts a value into the map, associating it with a key,
+    /// returning the index of the entry and the previous value.
+    ///
+    /// If the key was already in the map, its entry keeps its position,
+    /// otherwise the entry is added at the end.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RIndexMap, RNone, RSome, Tuple2};
+    ///
+    /// let mut map = RIndexMap::<u32, u32>::new();
+    ///
+    /// assert_eq!(map.insert_full(0, 1), Tuple2(0, RNone));
+    /// assert_eq!(map.insert_full(2, 3), Tuple2(1, RNone));
+    /// assert_eq!(map.insert_full(0, 5), Tuple2(0, RSome(1)));
+    ///
+    /// ```
+    pub fn insert_full(&mut self, key: K, value: V) -> Tuple2<usize, ROption<V>> {
+        let vtable = self.vtable();
+        unsafe { vtable.insert_full()(self.indices.as_rmut(), &mut self.entries, key, value) }
+    }
+
+    /// Removes the entry for the key, returning its value,
+    /// by replacing it with the last entry.
+    ///
+    /// This takes `O(1)` time, but changes the order of the entries.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RIndexMap, RNone, RSome};
+    ///
+    /// let mut map = vec![(0, 1), (3, 4), (5, 6)].into_iter().collect::<RIndexMap<u32, u32>>();
+    ///
+    /// assert_eq!(map.swap_remove(&0), RSome(1));
+    /// assert_eq!(map.swap_remove(&0), RNone);
+    ///
+    /// assert_eq!(map.keys().copied().collect::<Vec<_>>(), [5, 3]);
+    ///
+    /// ```
+    pub fn swap_remove<Q>(&mut self, query: &Q) -> ROption<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.swap_remove_entry(query).map(|x| x.1)
+    }
+
+    /// Removes the entry for the key, by replacing it with the last entry.
+    ///
+    /// This takes `O(1)` time, but changes the order of the entries.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RIndexMap, RNone, RSome, Tuple2};
+    ///
+    /// let mut map = vec![(0, 1), (3, 4), (5, 6)].into_iter().collect::<RIndexMap<u32, u32>>();
+    ///
+    /// assert_eq!(map.swap_remove_entry(&0), RSome(Tuple2(0, 1)));
+    /// assert_eq!(map.swap_remove_entry(&0), RNone);
+    ///
+    /// assert_eq!(map.keys().copied().collect::<Vec<_>>(), [5, 3]);
+    ///
+    /// ```
+    pub fn swap_remove_entry<Q>(&mut self, query: &Q) -> ROption<Tuple2<K, V>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        match self.get_index_of(query) {
+            Some(index) => self.swap_remove_index(index),
+            None => RNone,
+        }
+    }
+
+    /// Removes the entry at `index`, by replacing it with the last entry.
+    ///
+    /// This takes `O(1)` time, but changes the order of the entries.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RIndexMap, RNone, RSome, Tuple2};
+    ///
+    /// let mut map = vec![(0, 1), (3, 4), (5, 6)].into_iter().collect::<RIndexMap<u32, u32>>();
+    ///
+    /// assert_eq!(map.swap_remove_index(0), RSome(Tuple2(0, 1)));
+    /// assert_eq!(map.swap_remove_index(2), RNone);
+    ///
+    /// assert_eq!(map.keys().copied().collect::<Vec<_>>(), [5, 3]);
+    ///
+    /// ```
+    pub fn swap_remove_index(&mut self, index: usize) -> ROption<Tuple2<K, V>> {
+        let vtable = self.vtable();
+        unsafe { vtable.swap_remove_index()(self.indices.as_rmut(), &mut self.entries, index) }
+    }
+
+    /// Removes the entry for the key, returning its value,
+    /// by shifting all the entries after it.
+    ///
+    /// This takes `O(n)` time, preserving the order of the remaining entries.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RIndexMap, RNone, RSome};
+    ///
+    /// let mut map = vec![(0, 1), (3, 4), (5, 6)].into_iter().collect::<RIndexMap<u32, u32>>();
+    ///
+    /// assert_eq!(map.shift_remove(&0), RSome(1));
+    /// assert_eq!(map.shift_remove(&0), RNone);
+    ///
+    /// assert_eq!(map.keys().copied().collect::<Vec<_>>(), [3, 5]);
+    ///
+    /// ```
+    pub fn shift_remove<Q>(&mut self, query: &Q) -> ROption<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.shift_remove_entry(query).map(|x| x.1)
+    }
+
+    /// Removes the entry for the key, by shifting all the entries after it.
+    ///
+    /// This takes `O(n)` time, preserving the order of the remaining entries.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RIndexMap, RNone, RSome, Tuple2};
+    ///
+    /// let mut map = vec![(0, 1), (3, 4), (5, 6)].into_iter().collect::<RIndexMap<u32, u32>>();
+    ///
+    /// assert_eq!(map.shift_remove_entry(&0), RSome(Tuple2(0, 1)));
+    /// assert_eq!(map.shift_remove_entry(&0), RNone);
+    ///
+    /// assert_eq!(map.keys().copied().collect::<Vec<_>>(), [3, 5]);
+    ///
+    /// ```
+    pub fn shift_remove_entry<Q>(&mut self, query: &Q) -> ROption<Tuple2<K, V>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        match self.get_index_of(query) {
+            Some(index) => self.shift_remove_index(index),
+            None => RNone,
+        }
+    }
+
+    /// Removes the entry at `index`, by shifting all the entries after it.
+    ///
+    /// This takes `O(n)` time, preserving the order of the remaining entries.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RIndexMap, RNone, RSome, Tuple2};
+    ///
+    /// let mut map = vec![(0, 1), (3, 4), (5, 6)].into_iter().collect::<RIndexMap<u32, u32>>();
+    ///
+    /// assert_eq!(map.shift_remove_index(0), RSome(Tuple2(0, 1)));
+    /// assert_eq!(map.shift_remove_index(2), RNone);
+    ///
+    /// assert_eq!(map.keys().copied().collect::<Vec<_>>(), [3, 5]);
+    ///
+    /// ```
+    pub fn shift_remove_index(&mut self, index: usize) -> ROption<Tuple2<K, V>> {
+        let vtable = self.vtable();
+        unsafe { vtable.shift_remove_index()(self.indices.as_rmut(), &mut self.entries, index) }
+    }
+
+    /// Removes the last entry of the map.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RIndexMap, RNone, RSome, Tuple2};
+    ///
+    /// let mut map = vec![(0, 1), (3, 4)].into_iter().collect::<RIndexMap<u32, u32>>();
+    ///
+    /// assert_eq!(map.pop(), RSome(Tuple2(3, 4)));
+    /// assert_eq!(map.pop(), RSome(Tuple2(0, 1)));
+    /// assert_eq!(map.pop(), RNone);
+    ///
+    /// ```
+    pub fn pop(&mut self) -> ROption<Tuple2<K, V>> {
+        match self.len().checked_sub(1) {
+            Some(index) => self.swap_remove_index(index),
+            None => RNone,
+        }
+    }
+
+    /// Reserves enough space to insert `reserved` extra elements without reallocating.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RIndexMap;
+    ///
+    /// let mut map = RIndexMap::<u32, u32>::new();
+    /// map.reserve(10);
+    /// assert!(map.capacity() >= 10);
+    ///
+    /// ```
+    pub fn reserve(&mut self, reserved: usize) {
+        let vtable = self.vtable();
+        self.entries.reserve(reserved);
+        unsafe {
+            vtable.reserve()(self.indices.as_rmut(), reserved);
+        }
+    }
+
+    /// Removes all the entries in the map.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RIndexMap;
+    ///
+    /// let mut map = vec![(0, 1), (3, 4)].into_iter().collect::<RIndexMap<u32, u32>>();
+    ///
+    /// assert_eq!(map.contains_key(&0), true);
+    /// assert_eq!(map.contains_key(&3), true);
+    ///
+    /// map.clear();
+    ///
+    /// assert_eq!(map.contains_key(&0), false);
+    /// assert_eq!(map.contains_key(&3), false);
+    ///
+    /// ```
+    pub fn clear(&mut self) {
+        let vtable = self.vtable();
+        unsafe {
+            vtable.clear_map()(self.indices.as_rmut());
+        }
+        self.entries.clear();
+    }
+
+    /// Returns the amount of entries in the map.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RIndexMap;
+    ///
+    /// let mut map = RIndexMap::<u32, u32>::new();
+    ///
+    /// assert_eq!(map.len(), 0);
+    /// map.insert(0, 1);
+    /// assert_eq!(map.len(), 1);
+    /// map.insert(2, 3);
+    /// assert_eq!(map.len(), 2);
+    ///
+    /// ```
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns the capacity of the map, the amount of elements it can store without reallocating.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RIndexMap;
+    ///
+    /// let mut map = RIndexMap::<u32, u32>::with_capacity(4);
+    ///
+    /// assert!(map.capacity() >= 4);
+    ///
+    /// ```
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.entries.capacity()
+    }
+
+    /// Returns whether the map contains any entries.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RIndexMap;
+    ///
+    /// let mut map = RIndexMap::<u32, u32>::new();
+    ///
+    /// assert_eq!(map.is_empty(), true);
+    /// map.insert(0, 1);
+    /// assert_eq!(map.is_empty(), false);
+    ///
+    /// ```
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterates over the entries in the map in order,
+    /// with references to the values in the map.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RIndexMap, Tuple2};
+    ///
+    /// let mut map = RIndexMap::<u32, u32>::new();
+    ///
+    /// map.insert(3, 4);
+    /// map.insert(0, 1);
+    ///
+    /// let list = map.iter().collect::<Vec<_>>();
+    /// assert_eq!(list, vec![Tuple2(&3, &4), Tuple2(&0, &1)]);
+    ///
+    /// ```
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter::new(&self.entries)
+    }
+
+    /// Iterates over the entries in the map in order,
+    /// with mutable references to the values in the map.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RIndexMap, Tuple2};
+    ///
+    /// let mut map = RIndexMap::<u32, u32>::new();
+    ///
+    /// map.insert(3, 4);
+    /// map.insert(0, 1);
+    ///
+    /// let list = map.iter_mut().collect::<Vec<_>>();
+    /// assert_eq!(list, vec![Tuple2(&3, &mut 4), Tuple2(&0, &mut 1)]);
+    ///
+    /// ```
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        IterMut::new(&mut self.entries)
+    }
+
+    /// Iterates over the keys in the map in order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RIndexMap;
+    ///
+    /// let mut map = RIndexMap::new();
+    /// map.insert("c", 3);
+    /// map.insert("a", 1);
+    /// map.insert("b", 2);
+    ///
+    /// assert_eq!(map.keys().copied().collect::<Vec<_>>(), ["c", "a", "b"]);
+    ///
+    /// ```
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys::new(&self.entries)
+    }
+
+    /// Iterates over the values in the map in order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RIndexMap;
+    ///
+    /// let mut map = RIndexMap::new();
+    /// map.insert("c", 3);
+    /// map.insert("a", 1);
+    /// map.insert("b", 2);
+    ///
+    /// assert_eq!(map.values().copied().collect::<Vec<_>>(), [3, 1, 2]);
+    ///
+    /// ```
+    pub fn values(&self) -> Values<'_, K, V> {
+        Values::new(&self.entries)
+    }
+
+    /// Iterates over mutable references to the values in the map in order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RIndexMap;
+    ///
+    /// let mut map = RIndexMap::new();
+    /// map.insert("c", 3);
+    /// map.insert("a", 1);
+    ///
+    /// for value in map.values_mut() {
+    ///     *value *= 10;
+    /// }
+    ///
+    /// assert_eq!(map.values().copied().collect::<Vec<_>>(), [30, 10]);
+    ///
+    /// ```
+    pub fn values_mut(&mut self) -> ValuesMut<'_, K, V> {
+        ValuesMut::new(&mut self.entries)
+    }
+}
+
+/// This returns an `Iterator<Item= Tuple2< K, V > >`
+impl<K, V, S> IntoIterator for RIndexMap<K, V, S> {
+    type Item = Tuple2<K, V>;
+    type IntoIter = IntoIter<K, V>;
+
+    fn into_iter(self) -> IntoIter<K, V> {
+        IntoIter::new(self.entries)
+    }
+}
+
+/// This returns an `Iterator<Item= Tuple2< &K, &V > >`
+impl<'a, K, V, S> IntoIterator for &'a RIndexMap<K, V, S> {
+    type Item = Tuple2<&'a K, &'a V>;
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// This returns an `Iterator<Item= Tuple2< &K, &mut V > >`
+impl<'a, K, V, S> IntoIterator for &'a mut RIndexMap<K, V, S> {
+    type Item = Tuple2<&'a K, &'a mut V>;
+    type IntoIter = IterMut<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<K, V, S> FromIterator<(K, V)> for RIndexMap<K, V, S>
+where
+    Self: Default,
+{
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let mut map = Self::default();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K, V, S> FromIterator<Tuple2<K, V>> for RIndexMap<K, V, S>
+where
+    Self: Default,
+{
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = Tuple2<K, V>>,
+    {
+        let mut map = Self::default();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K, V, S> Extend<(K, V)> for RIndexMap<K, V, S> {
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for (k, v) in iter {
+            self.insert(k, v);
+        }
+    }
+}
+
+impl<K, V, S> Extend<Tuple2<K, V>> for RIndexMap<K, V, S> {
+    #[inline]
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = Tuple2<K, V>>,
+    {
+        self.extend(iter.into_iter().map(Tuple2::into_rust));
+    }
+}
+
+impl<K, V, S> Default for RIndexMap<K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher + Default,
+{
+    fn default() -> Self {
+        Self::with_hasher(S::default())
+    }
+}
+
+impl<K, V, S> Clone for RIndexMap<K, V, S>
+where
+    K: Clone,
+    V: Clone,
+    Self: Default,
+{
+    fn clone(&self) -> Self {
+        self.iter()
+            .map(|Tuple2(k, v)| (k.clone(), v.clone()))
+            .collect()
+    }
+}
+
+impl<K, V, S> Debug for RIndexMap<K, V, S>
+where
+    K: Debug,
+    V: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.iter().map(Tuple2::into_rust))
+            .finish()
+    }
+}
+
+impl<K, V, S> Eq for RIndexMap<K, V, S>
+where
+    K: Eq,
+    V: Eq,
+{
+}
+
+/// Maps are compared as sets of entries, ignoring their order.
+impl<K, V, S> PartialEq for RIndexMap<K, V, S>
+where
+    K: PartialEq,
+    V: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        if self.len() != other.len() {
+            return false;
+        }
+
+        self.iter().all(|Tuple2(k, vl)| {
+            other
+                .get_index_of_p(k)
+                .map_or(false, |i| other.entries[i].value == *vl)
+        })
+    }
+}
+
+unsafe impl<K, V, S> Send for RIndexMap<K, V, S>
+where
+    K: Send,
+    V: Send,
+    S: Send,
+{
+}
+
+unsafe impl<K, V, S> Sync for RIndexMap<K, V, S>
+where
+    K: Sync,
+    V: Sync,
+    S: Sync,
+{
+}
+
+impl<K, Q, V, S> Index<&Q> for RIndexMap<K, V, S>
+where
+    K: Borrow<Q>,
+    Q: Eq + Hash + ?Sized,
+{
+    type Output = V;
+
+    fn index(&self, query: &Q) -> &V {
+        self.get(query)
+            .expect("no entry in RIndexMap<_, _> found for key")
+    }
+}
+
+impl<K, Q, V, S> IndexMut<&Q> for RIndexMap<K, V, S>
+where
+    K: Borrow<Q>,
+    Q: Eq + Hash + ?Sized,
+{
+    fn index_mut(&mut self, query: &Q) -> &mut V {
+        self.get_mut(query)
+            .expect("no entry in RIndexMap<_, _> found for key")
+    }
+}
+
+/// Gets the value of the entry at the index.
+impl<K, V, S> Index<usize> for RIndexMap<K, V, S> {
+    type Output = V;
+
+    fn index(&self, index: usize) -> &V {
+        &self.entries[index].value
+    }
+}
+
+/// Gets the value of the entry at the index.
+impl<K, V, S> IndexMut<usize> for RIndexMap<K, V, S> {
+    fn index_mut(&mut self, index: usize) -> &mut V {
+        &mut self.entries[index].value
+    }
+}
+
+mod serde {
+    use super::*;
+
+    use ::serde::{
+        de::{MapAccess, Visitor},
+        ser::SerializeMap,
+        Deserialize, Deserializer, Serialize, Serializer,
+    };
+
+    struct RIndexMapVisitor<K, V, S> {
+        _marker: NonOwningPhantom<RIndexMap<K, V, S>>,
+    }
+
+    impl<K, V, S> RIndexMapVisitor<K, V, S> {
+        fn new() -> Self {
+            RIndexMapVisitor {
+                _marker: NonOwningPhantom::NEW,
+            }
+        }
+    }
+
+    impl<'de, K, V, S> Visitor<'de> for RIndexMapVisitor<K, V, S>
+    where
+        K: Deserialize<'de>,
+        V: Deserialize<'de>,
+        RIndexMap<K, V, S>: Default,
+    {
+        type Value = RIndexMap<K, V, S>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("an RIndexMap")
+        }
+
+        fn visit_map<M>(self, mut map_access: M) -> Result<Self::Value, M::Error>
+        where
+            M: MapAccess<'de>,
+        {
+            let capacity = map_access.size_hint().unwrap_or(0);
+            let mut map = RIndexMap::default();
+            map.reserve(capacity);
+
+            while let Some((k, v)) = map_access.next_entry()? {
+                map.insert(k, v);
+            }
+
+            Ok(map)
+        }
+    }
+
+    impl<'de, K, V, S> Deserialize<'de> for RIndexMap<K, V, S>
+    where
+        K: Deserialize<'de>,
+        V: Deserialize<'de>,
+        Self: Default,
+    {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserializer.deserialize_map(RIndexMapVisitor::new())
+        }
+    }
+
+    impl<K, V, S> Serialize for RIndexMap<K, V, S>
+    where
+        K: Serialize,
+        V: Serialize,
+    {
+        fn serialize<Z>(&self, serializer: Z) -> Result<Z::Ok, Z::Error>
+        where
+            Z: Serializer,
+        {
+            let mut map = serializer.serialize_map(Some(self.len()))?;
+            for Tuple2(k, v) in self.iter() {
+                map.serialize_entry(k, v)?;
+            }
+            map.end()
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+#[derive(StableAbi)]
+#[repr(C)]
+#[sabi(
+    kind(Prefix),
+    missing_field(panic),
+    // The hasher doesn't matter
+    unsafe_unconstrained(S),
+)]
+struct VTable<K, V, S> {
+    insert_full: unsafe extern "C" fn(
+        RMut<'_, ErasedIndices<K, V, S>>,
+        &mut RVec<Bucket<K, V>>,
+        K,
+        V,
+    ) -> Tuple2<usize, ROption<V>>,
+
+    get_index_of: unsafe extern "C" fn(
+        RRef<'_, ErasedIndices<K, V, S>>,
+        RSlice<'_, Bucket<K, V>>,
+        MapQuery<'_, K>,
+    ) -> ROption<usize>,
+    get_index_of_p: unsafe extern "C" fn(
+        RRef<'_, ErasedIndices<K, V, S>>,
+        RSlice<'_, Bucket<K, V>>,
+        &K,
+    ) -> ROption<usize>,
+
+    swap_remove_index: unsafe extern "C" fn(
+        RMut<'_, ErasedIndices<K, V, S>>,
+        &mut RVec<Bucket<K, V>>,
+        usize,
+    ) -> ROption<Tuple2<K, V>>,
+    shift_remove_index: unsafe extern "C" fn(
+        RMut<'_, ErasedIndices<K, V, S>>,
+        &mut RVec<Bucket<K, V>>,
+        usize,
+    ) -> ROption<Tuple2<K, V>>,
+
+    reserve: unsafe extern "C" fn(RMut<'_, ErasedIndices<K, V, S>>, usize),
+    #[sabi(last_prefix_field)]
+    clear_map: unsafe extern "C" fn(RMut<'_, ErasedIndices<K, V, S>>),
+}
+
+impl<K, V, S> VTable<K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    const VTABLE_VAL: WithMetadata<VTable<K, V, S>> = WithMetadata::new(Self::VTABLE);
+
+    const VTABLE_REF: VTable_Ref<K, V, S> = unsafe { VTable_Ref(Self::VTABLE_VAL.as_prefix()) };
+
+    fn erased_indices(hash_builder: S) -> RBox<ErasedIndices<K, V, S>> {
+        unsafe {
+            let table = IndexTable {
+                indices: HashMap::default(),
+                hash_builder,
+            };
+            let boxed = RBox::new(table);
+            mem::transmute::<RBox<_>, RBox<ErasedIndices<K, V, S>>>(boxed)
+        }
+    }
+
+    const VTABLE: VTable<K, V, S> = VTable {
+        insert_full: ErasedIndices::insert_full,
+        get_index_of: ErasedIndices::get_index_of,
+        get_index_of_p: ErasedIndices::get_index_of_p,
+        swap_remove_index: ErasedIndices::swap_remove_index,
+        shift_remove_index: ErasedIndices::shift_remove_index,
+        reserve: ErasedIndices::reserve,
+        clear_map: ErasedIndices::clear_map,
+    };
+}
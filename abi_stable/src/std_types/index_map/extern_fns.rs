@@ -0,0 +1,211 @@
+use super::*;
+
+use std::collections::hash_map::Entry;
+
+impl<S> IndexTable<S>
+where
+    S: BuildHasher,
+{
+    fn hash<Q>(&self, key: &Q) -> u64
+    where
+        Q: Hash + ?Sized,
+    {
+        let mut hasher = self.hash_builder.build_hasher();
+        key.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+impl<S> IndexTable<S> {
+    fn find<F>(&self, hash: u64, mut is_match: F) -> Option<usize>
+    where
+        F: FnMut(usize) -> bool,
+    {
+        match self.indices.get(&hash)? {
+            Slots::One(index) => Some(*index).filter(|&i| is_match(i)),
+            Slots::Many(indices) => indices.iter().copied().find(|&i| is_match(i)),
+        }
+    }
+
+    fn insert(&mut self, hash: u64, index: usize) {
+        match self.indices.entry(hash) {
+            Entry::Vacant(entry) => {
+                entry.insert(Slots::One(index));
+            }
+            Entry::Occupied(mut entry) => {
+                let slots = entry.get_mut();
+                match slots {
+                    Slots::One(prev) => *slots = Slots::Many(vec![*prev, index]),
+                    Slots::Many(indices) => indices.push(index),
+                }
+            }
+        }
+    }
+
+    fn remove(&mut self, hash: u64, index: usize) {
+        if let Entry::Occupied(mut entry) = self.indices.entry(hash) {
+            match entry.get_mut() {
+                Slots::One(_) => {
+                    entry.remove();
+                }
+                Slots::Many(indices) => {
+                    indices.retain(|&i| i != index);
+                    if let [remaining] = **indices {
+                        entry.insert(Slots::One(remaining));
+                    }
+                }
+            }
+        }
+    }
+
+    fn replace(&mut self, hash: u64, old: usize, new: usize) {
+        match self.indices.get_mut(&hash) {
+            Some(Slots::One(index)) => *index = new,
+            Some(Slots::Many(indices)) => {
+                for index in indices.iter_mut().filter(|i| **i == old) {
+                    *index = new;
+                }
+            }
+            None => {}
+        }
+    }
+
+    /// Decrements all the indices after `removed`.
+    fn shift_after(&mut self, removed: usize) {
+        let mut decrement = |index: &mut usize| {
+            if *index > removed {
+                *index -= 1;
+            }
+        };
+        for slots in self.indices.values_mut() {
+            match slots {
+                Slots::One(index) => decrement(index),
+                Slots::Many(indices) => indices.iter_mut().for_each(&mut decrement),
+            }
+        }
+    }
+}
+
+impl<K, V, S> ErasedIndices<K, V, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    unsafe fn run<'a, F, R>(this: RRef<'a, Self>, f: F) -> R
+    where
+        F: FnOnce(&'a IndexTable<S>) -> R,
+    {
+        extern_fn_panic_handling! {no_early_return;
+            let table = unsafe { this.transmute_into_ref::<IndexTable<S>>() };
+            f(table)
+        }
+    }
+
+    unsafe fn run_mut<'a, F, R>(this: RMut<'a, Self>, f: F) -> R
+    where
+        F: FnOnce(&'a mut IndexTable<S>) -> R,
+    {
+        extern_fn_panic_handling! {no_early_return;
+            let table = unsafe { this.transmute_into_mut::<IndexTable<S>>() };
+            f(table)
+        }
+    }
+
+    pub(super) unsafe extern "C" fn insert_full(
+        this: RMut<'_, Self>,
+        entries: &mut RVec<Bucket<K, V>>,
+        key: K,
+        value: V,
+    ) -> Tuple2<usize, ROption<V>> {
+        unsafe {
+            Self::run_mut(this, |table| {
+                let hash = table.hash(&key);
+                match table.find(hash, |i| entries[i].key == key) {
+                    Some(index) => {
+                        let prev = mem::replace(&mut entries[index].value, value);
+                        Tuple2(index, RSome(prev))
+                    }
+                    None => {
+                        let index = entries.len();
+                        table.insert(hash, index);
+                        entries.push(Bucket { hash, key, value });
+                        Tuple2(index, RNone)
+                    }
+                }
+            })
+        }
+    }
+
+    pub(super) unsafe extern "C" fn get_index_of(
+        this: RRef<'_, Self>,
+        entries: RSlice<'_, Bucket<K, V>>,
+        query: MapQuery<'_, K>,
+    ) -> ROption<usize> {
+        unsafe {
+            Self::run(this, |table| {
+                let hash = table.hash(&query);
+                table.find(hash, |i| query.is_equal(&entries[i].key)).into()
+            })
+        }
+    }
+
+    pub(super) unsafe extern "C" fn get_index_of_p(
+        this: RRef<'_, Self>,
+        entries: RSlice<'_, Bucket<K, V>>,
+        key: &K,
+    ) -> ROption<usize> {
+        unsafe {
+            Self::run(this, |table| {
+                let hash = table.hash(key);
+                table.find(hash, |i| entries[i].key == *key).into()
+            })
+        }
+    }
+
+    pub(super) unsafe extern "C" fn swap_remove_index(
+        this: RMut<'_, Self>,
+        entries: &mut RVec<Bucket<K, V>>,
+        index: usize,
+    ) -> ROption<Tuple2<K, V>> {
+        unsafe {
+            Self::run_mut(this, |table| {
+                let last = match entries.len().checked_sub(1) {
+                    Some(last) if index <= last => last,
+                    _ => return RNone,
+                };
+                table.remove(entries[index].hash, index);
+                if index != last {
+                    table.replace(entries[last].hash, last, index);
+                }
+                let Bucket { key, value, .. } = entries.swap_remove(index);
+                RSome(Tuple2(key, value))
+            })
+        }
+    }
+
+    pub(super) unsafe extern "C" fn shift_remove_index(
+        this: RMut<'_, Self>,
+        entries: &mut RVec<Bucket<K, V>>,
+        index: usize,
+    ) -> ROption<Tuple2<K, V>> {
+        unsafe {
+            Self::run_mut(this, |table| {
+                if index >= entries.len() {
+                    return RNone;
+                }
+                table.remove(entries[index].hash, index);
+                table.shift_after(index);
+                let Bucket { key, value, .. } = entries.remove(index);
+                RSome(Tuple2(key, value))
+            })
+        }
+    }
+
+    pub(super) unsafe extern "C" fn reserve(this: RMut<'_, Self>, reserved: usize) {
+        unsafe { Self::run_mut(this, |table| table.indices.reserve(reserved)) }
+    }
+
+    pub(super) unsafe extern "C" fn clear_map(this: RMut<'_, Self>) {
+        unsafe { Self::run_mut(this, |table| table.indices.clear()) }
+    }
+}
@@ -0,0 +1,142 @@
+use super::*;
+
+use std::{iter::FusedIterator, slice};
+
+use crate::std_types::vec::IntoIter as RVecIntoIter;
+
+macro_rules! declare_iterator {
+    (
+        $(#[$attr:meta])*
+        struct $name:ident[$($lt:lifetime)?] {
+            iter: $iter:ty,
+            item: $item:ty,
+            map: |$bucket:ident| $map:expr,
+        }
+    ) => {
+        $(#[$attr])*
+        pub struct $name<$($lt,)? K, V> {
+            iter: $iter,
+        }
+
+        impl<$($lt,)? K, V> $name<$($lt,)? K, V> {
+            pub(super) fn new(iter: impl IntoIterator<IntoIter = $iter>) -> Self {
+                Self {
+                    iter: iter.into_iter(),
+                }
+            }
+        }
+
+        impl<$($lt,)? K, V> Iterator for $name<$($lt,)? K, V> {
+            type Item = $item;
+
+            #[inline]
+            fn next(&mut self) -> Option<$item> {
+                self.iter.next().map(|$bucket| $map)
+            }
+
+            #[inline]
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                self.iter.size_hint()
+            }
+        }
+
+        impl<$($lt,)? K, V> DoubleEndedIterator for $name<$($lt,)? K, V> {
+            #[inline]
+            fn next_back(&mut self) -> Option<$item> {
+                self.iter.next_back().map(|$bucket| $map)
+            }
+        }
+
+        impl<$($lt,)? K, V> ExactSizeIterator for $name<$($lt,)? K, V> {}
+
+        impl<$($lt,)? K, V> FusedIterator for $name<$($lt,)? K, V> {}
+    };
+}
+
+declare_iterator! {
+    /// An iterator over the entries of an `RIndexMap`,
+    /// implementing `Iterator<Item= Tuple2< &K, &V > >`.
+    struct Iter['a] {
+        iter: slice::Iter<'a, Bucket<K, V>>,
+        item: Tuple2<&'a K, &'a V>,
+        map: |bucket| Tuple2(&bucket.key, &bucket.value),
+    }
+}
+
+declare_iterator! {
+    /// An iterator over the entries of an `RIndexMap`,
+    /// implementing `Iterator<Item= Tuple2< &K, &mut V > >`.
+    struct IterMut['a] {
+        iter: slice::IterMut<'a, Bucket<K, V>>,
+        item: Tuple2<&'a K, &'a mut V>,
+        map: |bucket| Tuple2(&bucket.key, &mut bucket.value),
+    }
+}
+
+declare_iterator! {
+    /// An iterator over the keys of an `RIndexMap`,
+    /// implementing `Iterator<Item= &K >`.
+    struct Keys['a] {
+        iter: slice::Iter<'a, Bucket<K, V>>,
+        item: &'a K,
+        map: |bucket| &bucket.key,
+    }
+}
+
+declare_iterator! {
+    /// An iterator over the values of an `RIndexMap`,
+    /// implementing `Iterator<Item= &V >`.
+    struct Values['a] {
+        iter: slice::Iter<'a, Bucket<K, V>>,
+        item: &'a V,
+        map: |bucket| &bucket.value,
+    }
+}
+
+declare_iterator! {
+    /// An iterator over the values of an `RIndexMap`,
+    /// implementing `Iterator<Item= &mut V >`.
+    struct ValuesMut['a] {
+        iter: slice::IterMut<'a, Bucket<K, V>>,
+        item: &'a mut V,
+        map: |bucket| &mut bucket.value,
+    }
+}
+
+declare_iterator! {
+    /// An iterator that yields all the entries of an `RIndexMap`,
+    /// deallocating the map afterwards.
+    ///
+    /// This implements `Iterator<Item= Tuple2< K, V > >`
+    struct IntoIter[] {
+        iter: RVecIntoIter<Bucket<K, V>>,
+        item: Tuple2<K, V>,
+        map: |bucket| Tuple2(bucket.key, bucket.value),
+    }
+}
+
+macro_rules! impl_clone_debug {
+    ($($name:ident),*) => {
+        $(
+            impl<K, V> Clone for $name<'_, K, V> {
+                fn clone(&self) -> Self {
+                    Self {
+                        iter: self.iter.clone(),
+                    }
+                }
+            }
+
+            impl<K, V> Debug for $name<'_, K, V>
+            where
+                Self: Iterator,
+                <Self as Iterator>::Item: Debug,
+            {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    f.debug_list().entries(self.clone()).finish()
+                }
+            }
+        )*
+    };
+}
+
+impl_clone_debug! {Iter, Keys, Values}
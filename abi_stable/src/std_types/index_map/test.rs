@@ -0,0 +1,209 @@
+use super::*;
+
+use crate::std_types::RString;
+
+use std::collections::HashMap;
+
+fn keys<K: Clone, V, S>(map: &RIndexMap<K, V, S>) -> Vec<K> {
+    map.keys().cloned().collect()
+}
+
+/// Checks that every key can be looked up at the index it's iterated at.
+fn assert_consistent<V>(map: &RIndexMap<u32, V>) {
+    for (i, key) in map.keys().enumerate() {
+        assert_eq!(map.get_index_of(key), Some(i));
+    }
+}
+
+#[test]
+fn insertion_order() {
+    let mut map = RIndexMap::<u32, u32>::new();
+    let order = [7, 3, 9, 1, 5, 0, 8];
+    for (i, &k) in order.iter().enumerate() {
+        assert_eq!(map.insert_full(k, k * 10), Tuple2(i, RNone));
+    }
+    assert_eq!(keys(&map), order);
+
+    assert_eq!(map.insert_full(9, 0), Tuple2(2, RSome(90)));
+    assert_eq!(keys(&map), order);
+    assert_eq!(map[&9], 0);
+    assert_eq!(map[2], 0);
+
+    assert_eq!(
+        map.iter().rev().map(|x| *x.0).collect::<Vec<_>>(),
+        order.iter().rev().copied().collect::<Vec<_>>(),
+    );
+    assert_eq!(map.iter().len(), order.len());
+    assert_consistent(&map);
+}
+
+#[test]
+fn borrowed_queries() {
+    let mut map = RIndexMap::<RString, u32>::new();
+    map.insert("foo".into(), 0);
+    map.insert("bar".into(), 1);
+
+    assert_eq!(map.get("bar"), Some(&1));
+    assert_eq!(map.get_index_of("foo"), Some(0));
+    assert!(!map.contains_key("baz"));
+
+    *map.get_mut("foo").unwrap() += 5;
+    map["bar"] += 5;
+    assert_eq!(map.values().copied().collect::<Vec<_>>(), [5, 6]);
+
+    assert_eq!(
+        map.shift_remove_entry("foo"),
+        RSome(Tuple2("foo".into(), 5))
+    );
+    assert_eq!(map.get_index_of("bar"), Some(0));
+}
+
+#[test]
+fn swap_remove() {
+    let mut map = (0..10u32).map(|x| (x, x)).collect::<RIndexMap<u32, u32>>();
+
+    assert_eq!(map.swap_remove(&2), RSome(2));
+    assert_eq!(keys(&map), [0, 1, 9, 3, 4, 5, 6, 7, 8]);
+    assert_consistent(&map);
+
+    assert_eq!(map.swap_remove_index(8), RSome(Tuple2(8, 8)));
+    assert_eq!(map.swap_remove_index(8), RNone);
+    assert_eq!(map.swap_remove(&2), RNone);
+    assert_eq!(keys(&map), [0, 1, 9, 3, 4, 5, 6, 7]);
+    assert_consistent(&map);
+
+    assert_eq!(map.pop(), RSome(Tuple2(7, 7)));
+    assert_consistent(&map);
+}
+
+#[test]
+fn shift_remove() {
+    let mut map = (0..10u32).map(|x| (x, x)).collect::<RIndexMap<u32, u32>>();
+
+    assert_eq!(map.shift_remove(&2), RSome(2));
+    assert_eq!(keys(&map), [0, 1, 3, 4, 5, 6, 7, 8, 9]);
+    assert_consistent(&map);
+
+    assert_eq!(map.shift_remove_index(0), RSome(Tuple2(0, 0)));
+    assert_eq!(map.shift_remove_index(8), RNone);
+    assert_eq!(map.shift_remove(&2), RNone);
+    assert_eq!(keys(&map), [1, 3, 4, 5, 6, 7, 8, 9]);
+    assert_consistent(&map);
+
+    map.insert(2, 2);
+    assert_eq!(map.last(), Some(Tuple2(&2, &2)));
+    assert_consistent(&map);
+}
+
+/// A hasher that makes every key collide.
+#[derive(Default)]
+struct ConstantHasher;
+
+impl Hasher for ConstantHasher {
+    fn write(&mut self, _: &[u8]) {}
+
+    fn finish(&self) -> u64 {
+        0
+    }
+}
+
+#[test]
+fn colliding_hashes() {
+    type Map = RIndexMap<u32, u32, BuildHasherDefault<ConstantHasher>>;
+
+    let mut map = (0..8u32).map(|x| (x, x)).collect::<Map>();
+    assert_eq!(map.get(&5), Some(&5));
+
+    assert_eq!(map.swap_remove(&1), RSome(1));
+    assert_eq!(map.shift_remove(&3), RSome(3));
+    assert_eq!(keys(&map), [0, 7, 2, 4, 5, 6]);
+    for (i, key) in keys(&map).iter().enumerate() {
+        assert_eq!(map.get_index_of(key), Some(i));
+    }
+
+    while map.len() > 1 {
+        map.shift_remove_index(0);
+    }
+    assert_eq!(map.get(&6), Some(&6));
+    map.insert(1, 1);
+    assert_eq!(map.get_index_of(&1), Some(1));
+}
+
+#[test]
+fn compared_with_hashmap() {
+    let mut map = RIndexMap::<u32, u32>::new();
+    let mut std = HashMap::<u32, u32>::new();
+
+    for x in 0..1000u32 {
+        let key = x.wrapping_mul(2_654_435_761) % 97;
+        match x % 5 {
+            0 => assert_eq!(map.swap_remove(&key).into_option(), std.remove(&key)),
+            1 => assert_eq!(map.shift_remove(&key).into_option(), std.remove(&key)),
+            _ => assert_eq!(map.insert(key, x).into_option(), std.insert(key, x)),
+        }
+        assert_eq!(map.len(), std.len());
+    }
+
+    for (k, v) in &std {
+        assert_eq!(map.get(k), Some(v));
+    }
+    assert_consistent(&map);
+}
+
+#[test]
+fn iterators() {
+    let mut map = vec![(3, 'a'), (1, 'b'), (2, 'c')]
+        .into_iter()
+        .collect::<RIndexMap<u32, char>>();
+
+    for Tuple2(k, v) in &mut map {
+        *v = char::from_digit(*k, 10).unwrap();
+    }
+    for v in map.values_mut() {
+        *v = v.to_ascii_uppercase();
+    }
+    assert_eq!(map.values().copied().collect::<String>(), "312");
+    assert_eq!(format!("{:?}", map.keys()), "[3, 1, 2]");
+    assert_eq!(format!("{:?}", map), "{3: '3', 1: '1', 2: '2'}");
+
+    let mut iter = map.into_iter();
+    assert_eq!(iter.next_back(), Some(Tuple2(2, '2')));
+    assert_eq!(iter.len(), 2);
+    assert_eq!(iter.collect::<Vec<_>>(), [Tuple2(3, '3'), Tuple2(1, '1')]);
+}
+
+#[test]
+fn clear_and_equality() {
+    let map = vec![(3, 4), (1, 2)]
+        .into_iter()
+        .collect::<RIndexMap<u32, u32>>();
+    let reversed = vec![(1, 2), (3, 4)]
+        .into_iter()
+        .collect::<RIndexMap<u32, u32>>();
+    assert_eq!(map, reversed);
+    assert_eq!(map.clone(), map);
+
+    let mut cleared = map.clone();
+    cleared.clear();
+    assert!(cleared.is_empty());
+    assert_eq!(cleared.get(&3), None);
+    assert_ne!(cleared, map);
+
+    cleared.insert(5, 6);
+    assert_eq!(cleared.get_index_of(&5), Some(0));
+}
+
+#[test]
+fn serde() {
+    let map = vec![("zeta", 0), ("alpha", 1), ("mu", 2)]
+        .into_iter()
+        .map(|(k, v)| (RString::from(k), v))
+        .collect::<RIndexMap<RString, u32>>();
+
+    let serialized = serde_json::to_string(&map).unwrap();
+    assert_eq!(serialized, r#"{"zeta":0,"alpha":1,"mu":2}"#);
+
+    let deserialized = serde_json::from_str::<RIndexMap<RString, u32>>(&serialized).unwrap();
+    assert_eq!(keys(&deserialized), keys(&map));
+    assert_eq!(deserialized, map);
+}
@@ -34,7 +34,7 @@ mod entry;
 mod extern_fns;
 mod iterator_stuff;
 mod map_key;
-mod map_query;
+pub(super) mod map_query;
 
 #[cfg(all(test, not(feature = "only_new_tests")))]
 mod test;
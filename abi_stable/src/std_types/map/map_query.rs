@@ -12,7 +12,7 @@ pub struct MapQuery<'a, K> {
 
 impl<'a, K> MapQuery<'a, K> {
     #[inline]
-    pub(super) fn new<Q>(query: &'a &'a Q) -> Self
+    pub(crate) fn new<Q>(query: &'a &'a Q) -> Self
     where
         K: Borrow<Q>,
         Q: Hash + Eq + 'a + ?Sized,
@@ -33,7 +33,7 @@ impl<'a, K> MapQuery<'a, K> {
 
 impl<'a, K> MapQuery<'a, K> {
     #[inline]
-    pub(super) fn is_equal(&self, other: &K) -> bool {
+    pub(crate) fn is_equal(&self, other: &K) -> bool {
         (self.is_equal)(other, self.query)
     }
 
@@ -0,0 +1,890 @@
+//! Contains ffi-safe equivalents of `std::ffi::{OsString, OsStr}`.
+//!
+//! On Unix platforms the bytes of the string are stored as-is,
+//! so strings that aren't valid UTF-8 round-trip without loss.
+//!
+//! On Windows the string is stored as [WTF-8](https://simonsapin.github.io/wtf-8/),
+//! which also round-trips unpaired surrogates without loss,
+//! but means that the string can't be borrowed as an `&OsStr`.
+//! Because of that, the methods and impls that borrow an `OsStr`
+//! out of these types aren't available on Windows,
+//! those are `as_os_str`, and the `Deref`, `AsRef`, and `Borrow` impls.
+//!
+//! On other platforms the string is converted to UTF-8 lossily,
+//! because the standard library doesn't expose their encoding in the MSRV.
+//!
+//! Borrowing these types out of an `&OsStr`,
+//! with `ROsStr::new` or `From<&OsStr> for ROsStr`,
+//! is only possible on Unix.
+
+use std::{
+    borrow::Cow,
+    ffi::{OsStr, OsString},
+    str,
+};
+
+#[cfg(not(windows))]
+use std::{borrow::Borrow, ops::Deref};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::std_types::{RSlice, RString, RVec};
+
+#[cfg(all(test, not(feature = "only_new_tests")))]
+mod test;
+
+/// Ffi-safe equivalent of `std::ffi::OsString`.
+///
+/// # Example
+///
+/// ```
+/// use abi_stable::std_types::ROsString;
+///
+/// use std::ffi::{OsStr, OsString};
+///
+/// let mut string = ROsString::from(OsString::from("foo"));
+/// string.push("bar");
+///
+/// assert_eq!(string, OsStr::new("foobar"));
+/// assert_eq!(string.to_str(), Some("foobar"));
+///
+/// ```
+///
+#[repr(C)]
+#[derive(Default, Clone, StableAbi)]
+pub struct ROsString {
+    bytes: RVec<u8>,
+}
+
+/// Ffi-safe equivalent of `&'a std::ffi::OsStr`.
+///
+/// # Example
+///
+/// ```
+/// use abi_stable::std_types::{ROsStr, ROsString};
+///
+/// use std::ffi::OsStr;
+///
+/// let string = ROsString::from("hello");
+/// let borrowed: ROsStr<'_> = string.as_ros_str();
+///
+/// assert_eq!(borrowed, OsStr::new("hello"));
+/// assert_eq!(borrowed.to_os_string(), string);
+///
+/// ```
+///
+#[repr(C)]
+#[derive(Copy, Clone, StableAbi)]
+pub struct ROsStr<'a> {
+    bytes: RSlice<'a, u8>,
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+#[cfg(unix)]
+mod platform {
+    use super::*;
+
+    use std::os::unix::ffi::{OsStrExt, OsStringExt};
+
+    pub(super) fn os_str_to_bytes(this: &OsStr) -> Cow<'_, [u8]> {
+        Cow::Borrowed(this.as_bytes())
+    }
+
+    pub(super) fn os_string_into_vec(this: OsString) -> Vec<u8> {
+        this.into_vec()
+    }
+
+    pub(super) fn push_os_str(bytes: &mut RVec<u8>, other: &OsStr) {
+        bytes.extend_from_slice(other.as_bytes());
+    }
+
+    pub(super) fn bytes_to_string_lossy(bytes: &[u8]) -> Cow<'_, str> {
+        String::from_utf8_lossy(bytes)
+    }
+
+    /// # Safety
+    ///
+    /// `bytes` must come from `os_str_to_bytes` or `os_string_into_vec`.
+    pub(super) unsafe fn bytes_to_os_str(bytes: &[u8]) -> &OsStr {
+        OsStr::from_bytes(bytes)
+    }
+
+    /// # Safety
+    ///
+    /// `bytes` must come from `os_str_to_bytes` or `os_string_into_vec`.
+    pub(super) unsafe fn vec_into_os_string(bytes: Vec<u8>) -> OsString {
+        OsString::from_vec(bytes)
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::*;
+
+    use std::{
+        char, iter,
+        os::windows::ffi::{OsStrExt, OsStringExt},
+        str,
+    };
+
+    /// Encodes potentially ill-formed UTF-16 as WTF-8,
+    /// unpaired surrogates are encoded like any other code point below `0x10000`.
+    fn encode_wide<I>(wide: I, out: &mut Vec<u8>)
+    where
+        I: IntoIterator<Item = u16>,
+    {
+        let mut buffer = [0u8; 4];
+        for elem in char::decode_utf16(wide) {
+            match elem {
+                Ok(c) => out.extend_from_slice(c.encode_utf8(&mut buffer).as_bytes()),
+                Err(e) => {
+                    let surrogate = u32::from(e.unpaired_surrogate());
+                    out.extend_from_slice(&[
+                        0xE0 | (surrogate >> 12) as u8,
+                        0x80 | ((surrogate >> 6) & 0x3F) as u8,
+                        0x80 | (surrogate & 0x3F) as u8,
+                    ]);
+                }
+            }
+        }
+    }
+
+    /// Decodes WTF-8 into code points, which includes unpaired surrogates.
+    fn decode_code_points(bytes: &[u8]) -> impl Iterator<Item = u32> + '_ {
+        let mut bytes = bytes.iter().map(|&b| u32::from(b));
+        iter::from_fn(move || {
+            let first = bytes.next()?;
+            let (continuation, init) = match first {
+                0..=0x7F => return Some(first),
+                0xC0..=0xDF => (1, first & 0x1F),
+                0xE0..=0xEF => (2, first & 0x0F),
+                _ => (3, first & 0x07),
+            };
+            Some((0..continuation).fold(init, |cp, _| {
+                (cp << 6) | (bytes.next().unwrap_or(0x80) & 0x3F)
+            }))
+        })
+    }
+
+    fn decode_wide(bytes: &[u8]) -> Vec<u16> {
+        let mut out = Vec::with_capacity(bytes.len());
+        let mut buffer = [0u16; 2];
+        for cp in decode_code_points(bytes) {
+            match char::from_u32(cp) {
+                Some(c) => out.extend_from_slice(c.encode_utf16(&mut buffer)),
+                // an unpaired surrogate
+                None => out.push(cp as u16),
+            }
+        }
+        out
+    }
+
+    pub(super) fn os_str_to_bytes(this: &OsStr) -> Cow<'_, [u8]> {
+        match this.to_str() {
+            Some(x) => Cow::Borrowed(x.as_bytes()),
+            None => {
+                let mut out = Vec::with_capacity(this.len());
+                encode_wide(this.encode_wide(), &mut out);
+                Cow::Owned(out)
+            }
+        }
+    }
+
+    pub(super) fn os_string_into_vec(this: OsString) -> Vec<u8> {
+        match this.into_string() {
+            Ok(x) => x.into_bytes(),
+            Err(x) => os_str_to_bytes(&x).into_owned(),
+        }
+    }
+
+    pub(super) fn push_os_str(bytes: &mut RVec<u8>, other: &OsStr) {
+        let mut wide = other.encode_wide().peekable();
+        let mut out = Vec::new();
+
+        // A trailing lead surrogate followed by a trail surrogate
+        // has to be joined into a single code point to stay well-formed WTF-8.
+        match (&**bytes, wide.peek()) {
+            (&[.., 0xED, b1 @ 0xA0..=0xAF, b2], Some(&(0xDC00..=0xDFFF))) => {
+                let lead = 0xD000 | (u16::from(b1 & 0x3F) << 6) | u16::from(b2 & 0x3F);
+                bytes.truncate(bytes.len() - 3);
+                encode_wide(iter::once(lead).chain(wide), &mut out);
+            }
+            _ => encode_wide(wide, &mut out),
+        }
+
+        bytes.extend_from_slice(&out);
+    }
+
+    pub(super) fn bytes_to_string_lossy(bytes: &[u8]) -> Cow<'_, str> {
+        match str::from_utf8(bytes) {
+            Ok(x) => Cow::Borrowed(x),
+            Err(_) => Cow::Owned(
+                decode_code_points(bytes)
+                    .map(|cp| char::from_u32(cp).unwrap_or(char::REPLACEMENT_CHARACTER))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// # Safety
+    ///
+    /// `bytes` must come from `os_str_to_bytes` or `os_string_into_vec`.
+    pub(super) unsafe fn vec_into_os_string(bytes: Vec<u8>) -> OsString {
+        match String::from_utf8(bytes) {
+            Ok(x) => OsString::from(x),
+            Err(e) => OsString::from_wide(&decode_wide(e.as_bytes())),
+        }
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+mod platform {
+    use super::*;
+
+    use std::str;
+
+    pub(super) fn os_str_to_bytes(this: &OsStr) -> Cow<'_, [u8]> {
+        match this.to_string_lossy() {
+            Cow::Borrowed(x) => Cow::Borrowed(x.as_bytes()),
+            Cow::Owned(x) => Cow::Owned(x.into_bytes()),
+        }
+    }
+
+    pub(super) fn os_string_into_vec(this: OsString) -> Vec<u8> {
+        match this.into_string() {
+            Ok(x) => x.into_bytes(),
+            Err(x) => x.to_string_lossy().into_owned().into_bytes(),
+        }
+    }
+
+    pub(super) fn push_os_str(bytes: &mut RVec<u8>, other: &OsStr) {
+        bytes.extend_from_slice(&os_str_to_bytes(other));
+    }
+
+    pub(super) fn bytes_to_string_lossy(bytes: &[u8]) -> Cow<'_, str> {
+        Cow::Borrowed(unsafe { str::from_utf8_unchecked(bytes) })
+    }
+
+    /// # Safety
+    ///
+    /// `bytes` must come from `os_str_to_bytes` or `os_string_into_vec`.
+    pub(super) unsafe fn bytes_to_os_str(bytes: &[u8]) -> &OsStr {
+        OsStr::new(unsafe { str::from_utf8_unchecked(bytes) })
+    }
+
+    /// # Safety
+    ///
+    /// `bytes` must come from `os_str_to_bytes` or `os_string_into_vec`.
+    pub(super) unsafe fn vec_into_os_string(bytes: Vec<u8>) -> OsString {
+        OsString::from(unsafe { String::from_utf8_unchecked(bytes) })
+    }
+}
+
+#[cfg(not(windows))]
+use self::platform::bytes_to_os_str;
+
+use self::platform::{
+    bytes_to_string_lossy, os_str_to_bytes, os_string_into_vec, push_os_str, vec_into_os_string,
+};
+
+///////////////////////////////////////////////////////////////////////////////
+
+impl ROsString {
+    /// Creates a new, empty `ROsString`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::ROsString;
+    ///
+    /// let string = ROsString::new();
+    ///
+    /// assert!(string.is_empty());
+    ///
+    /// ```
+    pub const fn new() -> Self {
+        Self { bytes: RVec::new() }
+    }
+
+    /// Converts an `OsString` to an `ROsString`,
+    /// reusing its heap allocation on Unix.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::ROsString;
+    ///
+    /// use std::ffi::{OsStr, OsString};
+    ///
+    /// let string = ROsString::from_os_string(OsString::from("foo"));
+    ///
+    /// assert_eq!(string, OsStr::new("foo"));
+    ///
+    /// ```
+    pub fn from_os_string(this: OsString) -> Self {
+        Self {
+            bytes: os_string_into_vec(this).into(),
+        }
+    }
+
+    /// Gets an `&OsStr` of the contents.
+    ///
+    /// This is not available on Windows,
+    /// read the [module docs](crate::std_types::os_str) for why.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::ROsString;
+    ///
+    /// use std::ffi::OsStr;
+    ///
+    /// let string = ROsString::from("foo");
+    ///
+    /// assert_eq!(string.as_os_str(), OsStr::new("foo"));
+    ///
+    /// ```
+    #[cfg(not(windows))]
+    #[inline]
+    pub fn as_os_str(&self) -> &OsStr {
+        unsafe { bytes_to_os_str(self.bytes.as_slice()) }
+    }
+
+    /// Gets an `ROsStr<'_>` of the contents.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{ROsStr, ROsString};
+    ///
+    /// let string = ROsString::from("foo");
+    ///
+    /// assert_eq!(string.as_ros_str(), ROsStr::from_str("foo"));
+    ///
+    /// ```
+    #[inline]
+    pub fn as_ros_str(&self) -> ROsStr<'_> {
+        ROsStr {
+            bytes: self.bytes.as_rslice(),
+        }
+    }
+
+    /// Returns the length of the string,
+    /// in the platform-specific encoding used by this type.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::ROsString;
+    ///
+    /// assert_eq!(ROsString::from("foo").len(), 3);
+    ///
+    /// ```
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Returns whether the string is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::ROsString;
+    ///
+    /// assert!(ROsString::new().is_empty());
+    /// assert!(!ROsString::from("foo").is_empty());
+    ///
+    /// ```
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Converts the string to a `&str`,
+    /// returning `None` if it isn't valid UTF-8.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::ROsString;
+    ///
+    /// assert_eq!(ROsString::from("foo").to_str(), Some("foo"));
+    ///
+    /// ```
+    pub fn to_str(&self) -> Option<&str> {
+        str::from_utf8(self.bytes.as_slice()).ok()
+    }
+
+    /// Converts the string to a `Cow<'_, str>`,
+    /// replacing invalid UTF-8 with `U+FFFD REPLACEMENT CHARACTER`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::ROsString;
+    ///
+    /// assert_eq!(ROsString::from("foo").to_string_lossy(), "foo");
+    ///
+    /// ```
+    pub fn to_string_lossy(&self) -> Cow<'_, str> {
+        bytes_to_string_lossy(self.bytes.as_slice())
+    }
+
+    /// Appends `other` to the end of the string.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::ROsString;
+    ///
+    /// let mut string = ROsString::from("foo");
+    /// string.push("bar");
+    ///
+    /// assert_eq!(string, ROsString::from("foobar"));
+    ///
+    /// ```
+    pub fn push<S>(&mut self, other: S)
+    where
+        S: AsRef<OsStr>,
+    {
+        push_os_str(&mut self.bytes, other.as_ref());
+    }
+
+    /// Removes all the contents of the string,
+    /// this has no effect on its capacity.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::ROsString;
+    ///
+    /// let mut string = ROsString::from("foo");
+    /// string.clear();
+    ///
+    /// assert!(string.is_empty());
+    ///
+    /// ```
+    pub fn clear(&mut self) {
+        self.bytes.clear();
+    }
+
+    /// Converts this `ROsString` into an `OsString`.
+    ///
+    /// # Allocation
+    ///
+    /// If this is invoked outside of the dynamic library/binary that created it,
+    /// it will allocate a new `OsString` and copy the string into it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::ROsString;
+    ///
+    /// use std::ffi::OsString;
+    ///
+    /// let string = ROsString::from("foo");
+    ///
+    /// assert_eq!(string.into_os_string(), OsString::from("foo"));
+    ///
+    /// ```
+    pub fn into_os_string(self) -> OsString {
+        unsafe { vec_into_os_string(self.bytes.into_vec()) }
+    }
+
+    /// Converts this `ROsString` into an `RString`,
+    /// returning it back if it isn't valid UTF-8.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{ROsString, RString};
+    ///
+    /// let string = ROsString::from("foo");
+    ///
+    /// assert_eq!(string.into_string(), Ok(RString::from("foo")));
+    ///
+    /// ```
+    pub fn into_string(self) -> Result<RString, ROsString> {
+        if self.to_str().is_some() {
+            Ok(unsafe { RString::from_utf8_unchecked(self.bytes) })
+        } else {
+            Err(self)
+        }
+    }
+
+    #[cfg(windows)]
+    pub(crate) fn to_std_os_string(&self) -> OsString {
+        unsafe { vec_into_os_string(self.bytes.to_vec()) }
+    }
+}
+
+#[cfg(not(windows))]
+impl Deref for ROsString {
+    type Target = OsStr;
+
+    #[inline]
+    fn deref(&self) -> &OsStr {
+        self.as_os_str()
+    }
+}
+
+#[cfg(not(windows))]
+impl AsRef<OsStr> for ROsString {
+    fn as_ref(&self) -> &OsStr {
+        self
+    }
+}
+
+#[cfg(not(windows))]
+impl Borrow<OsStr> for ROsString {
+    fn borrow(&self) -> &OsStr {
+        self
+    }
+}
+
+impl_from_rust_repr! {
+    impl[] From<OsString> for ROsString {
+        fn(this){
+            ROsString::from_os_string(this)
+        }
+    }
+}
+
+impl_into_rust_repr! {
+    impl[] Into<OsString> for ROsString {
+        fn(this){
+            this.into_os_string()
+        }
+    }
+}
+
+impl From<&OsStr> for ROsString {
+    fn from(this: &OsStr) -> Self {
+        Self {
+            bytes: os_str_to_bytes(this).into_owned().into(),
+        }
+    }
+}
+
+impl From<ROsStr<'_>> for ROsString {
+    fn from(this: ROsStr<'_>) -> Self {
+        this.to_os_string()
+    }
+}
+
+impl From<&str> for ROsString {
+    fn from(this: &str) -> Self {
+        Self {
+            bytes: this.as_bytes().into(),
+        }
+    }
+}
+
+impl From<String> for ROsString {
+    fn from(this: String) -> Self {
+        Self {
+            bytes: this.into_bytes().into(),
+        }
+    }
+}
+
+impl From<RString> for ROsString {
+    fn from(this: RString) -> Self {
+        Self {
+            bytes: this.into_bytes(),
+        }
+    }
+}
+
+#[cfg(not(windows))]
+deref_coerced_impl_cmp_traits! {
+    ROsString;
+    coerce_to = OsStr,
+    [
+        OsString,
+        OsStr,
+        &OsStr,
+        ROsStr<'_>,
+    ]
+}
+
+#[cfg(not(windows))]
+shared_impls! {
+    mod = os_string_impls
+    new_type = ROsString[][],
+    original_type = OsString,
+}
+
+#[cfg(windows)]
+converted_impl_cmp_traits! {
+    ROsString;
+    convert_with = to_std_os_string,
+    coerce_to = OsStr,
+    std = [OsString, OsStr, &OsStr],
+    ffi = [ROsStr<'_>],
+}
+
+impl<'de> Deserialize<'de> for ROsString {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        OsString::deserialize(deserializer).map(Self::from)
+    }
+}
+
+impl Serialize for ROsString {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        #[cfg(not(windows))]
+        let string = self.as_os_str();
+        #[cfg(windows)]
+        let string = &self.to_std_os_string();
+
+        string.serialize(serializer)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+impl<'a> ROsStr<'a> {
+    /// Converts an `&OsStr` to an `ROsStr<'_>`.
+    ///
+    /// This is only available on Unix,
+    /// read the [module docs](crate::std_types::os_str) for why.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::ROsStr;
+    ///
+    /// use std::ffi::OsStr;
+    ///
+    /// let string = ROsStr::new(OsStr::new("foo"));
+    ///
+    /// assert_eq!(string.as_os_str(), OsStr::new("foo"));
+    ///
+    /// ```
+    #[cfg(unix)]
+    pub fn new(this: &'a OsStr) -> Self {
+        use std::os::unix::ffi::OsStrExt;
+
+        Self {
+            bytes: this.as_bytes().into(),
+        }
+    }
+
+    /// Constructs an `ROsStr<'a>` from a `&'a str`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::ROsStr;
+    ///
+    /// const FOO: ROsStr<'static> = ROsStr::from_str("foo");
+    ///
+    /// assert_eq!(FOO.to_str(), Some("foo"));
+    ///
+    /// ```
+    pub const fn from_str(this: &'a str) -> Self {
+        Self {
+            bytes: RSlice::from_slice(this.as_bytes()),
+        }
+    }
+
+    /// Gets an `&OsStr` of the contents.
+    ///
+    /// This is not available on Windows,
+    /// read the [module docs](crate::std_types::os_str) for why.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::ROsStr;
+    ///
+    /// use std::ffi::OsStr;
+    ///
+    /// assert_eq!(ROsStr::from_str("foo").as_os_str(), OsStr::new("foo"));
+    ///
+    /// ```
+    #[cfg(not(windows))]
+    #[inline]
+    pub fn as_os_str(self) -> &'a OsStr {
+        unsafe { bytes_to_os_str(self.bytes.as_slice()) }
+    }
+
+    /// Returns the length of the string,
+    /// in the platform-specific encoding used by this type.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::ROsStr;
+    ///
+    /// assert_eq!(ROsStr::from_str("foo").len(), 3);
+    ///
+    /// ```
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Returns whether the string is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::ROsStr;
+    ///
+    /// assert!(ROsStr::from_str("").is_empty());
+    /// assert!(!ROsStr::from_str("foo").is_empty());
+    ///
+    /// ```
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Converts the string to a `&str`,
+    /// returning `None` if it isn't valid UTF-8.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::ROsStr;
+    ///
+    /// assert_eq!(ROsStr::from_str("foo").to_str(), Some("foo"));
+    ///
+    /// ```
+    pub fn to_str(self) -> Option<&'a str> {
+        str::from_utf8(self.bytes.as_slice()).ok()
+    }
+
+    /// Converts the string to a `Cow<'a, str>`,
+    /// replacing invalid UTF-8 with `U+FFFD REPLACEMENT CHARACTER`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::ROsStr;
+    ///
+    /// assert_eq!(ROsStr::from_str("foo").to_string_lossy(), "foo");
+    ///
+    /// ```
+    pub fn to_string_lossy(self) -> Cow<'a, str> {
+        bytes_to_string_lossy(self.bytes.as_slice())
+    }
+
+    /// Copies the string into an `ROsString`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{ROsStr, ROsString};
+    ///
+    /// assert_eq!(ROsStr::from_str("foo").to_os_string(), ROsString::from("foo"));
+    ///
+    /// ```
+    pub fn to_os_string(self) -> ROsString {
+        ROsString {
+            bytes: self.bytes.to_rvec(),
+        }
+    }
+
+    #[cfg(windows)]
+    pub(crate) fn to_std_os_string(self) -> OsString {
+        unsafe { vec_into_os_string(self.bytes.to_vec()) }
+    }
+}
+
+#[cfg(not(windows))]
+impl<'a> Deref for ROsStr<'a> {
+    type Target = OsStr;
+
+    #[inline]
+    fn deref(&self) -> &OsStr {
+        self.as_os_str()
+    }
+}
+
+#[cfg(not(windows))]
+impl AsRef<OsStr> for ROsStr<'_> {
+    fn as_ref(&self) -> &OsStr {
+        self
+    }
+}
+
+#[cfg(unix)]
+impl_from_rust_repr! {
+    impl['a] From<&'a OsStr> for ROsStr<'a> {
+        fn(this){
+            ROsStr::new(this)
+        }
+    }
+}
+
+#[cfg(not(windows))]
+impl_into_rust_repr! {
+    impl['a] Into<&'a OsStr> for ROsStr<'a> {
+        fn(this){
+            this.as_os_str()
+        }
+    }
+}
+
+impl<'a> From<&'a str> for ROsStr<'a> {
+    fn from(this: &'a str) -> Self {
+        Self::from_str(this)
+    }
+}
+
+impl<'a> From<&'a ROsString> for ROsStr<'a> {
+    fn from(this: &'a ROsString) -> Self {
+        this.as_ros_str()
+    }
+}
+
+#[cfg(not(windows))]
+deref_coerced_impl_cmp_traits! {
+    ROsStr<'_>;
+    coerce_to = OsStr,
+    [
+        OsString,
+        OsStr,
+        &OsStr,
+    ]
+}
+
+#[cfg(not(windows))]
+shared_impls! {
+    mod = os_str_impls
+    new_type = ROsStr['a][],
+    original_type = OsStr,
+}
+
+#[cfg(windows)]
+converted_impl_cmp_traits! {
+    ROsStr<'_>;
+    convert_with = to_std_os_string,
+    coerce_to = OsStr,
+    std = [OsString, OsStr, &OsStr],
+    ffi = [],
+}
+
+impl<'a> Serialize for ROsStr<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        #[cfg(not(windows))]
+        let string = self.as_os_str();
+        #[cfg(windows)]
+        let string = &self.to_std_os_string();
+
+        string.serialize(serializer)
+    }
+}
@@ -0,0 +1,120 @@
+use super::*;
+
+#[cfg(unix)]
+use std::os::unix::ffi::OsStrExt;
+
+#[test]
+fn conversions() {
+    let std = OsString::from("hello");
+    let string = ROsString::from(std.clone());
+
+    assert_eq!(string, std);
+    #[cfg(not(windows))]
+    assert_eq!(string.as_os_str(), &*std);
+    assert_eq!(string.as_ros_str(), ROsStr::from_str("hello"));
+    assert_eq!(ROsString::from(string.as_ros_str()), string);
+    assert_eq!(ROsString::from(RString::from("hello")), string);
+    assert_eq!(string.clone().into_os_string(), std);
+    assert_eq!(string.into_string(), Ok(RString::from("hello")));
+
+    #[cfg(unix)]
+    let borrowed = ROsStr::from(std.as_os_str());
+    #[cfg(not(unix))]
+    let borrowed = ROsStr::from_str("hello");
+    assert_eq!(borrowed, std);
+    assert_eq!(borrowed.to_str(), Some("hello"));
+    assert_eq!(borrowed.to_os_string(), std);
+}
+
+#[test]
+fn push_and_clear() {
+    let mut string = ROsString::new();
+    assert!(string.is_empty());
+
+    string.push("foo");
+    string.push(OsStr::new("bar"));
+    assert_eq!(string, OsStr::new("foobar"));
+    assert_eq!(string.len(), 6);
+
+    string.clear();
+    assert!(string.is_empty());
+}
+
+#[cfg(unix)]
+#[test]
+fn non_utf8_round_trip() {
+    let std = OsStr::from_bytes(b"foo\xffbar");
+
+    let string = ROsString::from(std);
+    assert_eq!(string.as_os_str().as_bytes(), std.as_bytes());
+    assert_eq!(string.to_str(), None);
+    assert_eq!(string.to_string_lossy(), "foo\u{FFFD}bar");
+    assert_eq!(string.clone().into_os_string(), std);
+    assert_eq!(string.clone().into_string(), Err(string.clone()));
+
+    let borrowed = ROsStr::new(std);
+    assert_eq!(borrowed.as_os_str().as_bytes(), std.as_bytes());
+    assert_eq!(borrowed.to_os_string(), string);
+}
+
+#[cfg(windows)]
+#[test]
+fn unpaired_surrogate_round_trip() {
+    use std::os::windows::ffi::{OsStrExt, OsStringExt};
+
+    let wide = [0x66, 0xD800, 0x6F, 0xDC00];
+    let std = OsString::from_wide(&wide);
+
+    let string = ROsString::from(std.clone());
+    assert_eq!(string, std);
+    assert_eq!(ROsString::from(&*std), string);
+    assert_eq!(string.to_str(), None);
+    assert_eq!(string.to_string_lossy(), "f\u{FFFD}o\u{FFFD}");
+    assert_eq!(
+        string
+            .clone()
+            .into_os_string()
+            .encode_wide()
+            .collect::<Vec<u16>>(),
+        wide
+    );
+    assert_eq!(string.clone().into_string(), Err(string.clone()));
+    assert_eq!(string.as_ros_str().to_os_string(), string);
+
+    // a lead surrogate followed by a trail surrogate is joined into one code point
+    let mut joined = ROsString::from(OsString::from_wide(&[0xD83D]));
+    joined.push(OsString::from_wide(&[0xDE00]));
+    assert_eq!(joined, OsStr::new("\u{1F600}"));
+    assert_eq!(joined.to_str(), Some("\u{1F600}"));
+}
+
+#[test]
+fn cmp_and_debug() {
+    let foo = ROsString::from("foo");
+    let bar = ROsString::from("bar");
+
+    assert!(bar < foo);
+    assert_ne!(bar, foo);
+    assert_eq!(format!("{:?}", foo), format!("{:?}", OsStr::new("foo")));
+    assert_eq!(
+        format!("{:?}", foo.as_ros_str()),
+        format!("{:?}", OsStr::new("foo"))
+    );
+}
+
+#[test]
+fn serde() {
+    let string = ROsString::from("foo");
+    let serialized = serde_json::to_string(&string).unwrap();
+    assert_eq!(
+        serialized,
+        serde_json::to_string(OsStr::new("foo")).unwrap()
+    );
+    assert_eq!(
+        serde_json::to_string(&string.as_ros_str()).unwrap(),
+        serialized
+    );
+
+    let deserialized = serde_json::from_str::<ROsString>(&serialized).unwrap();
+    assert_eq!(deserialized, string);
+}
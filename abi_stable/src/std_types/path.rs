@@ -0,0 +1,715 @@
+//! Contains ffi-safe equivalents of `std::path::{PathBuf, Path}`.
+//!
+//! These are wrappers around [`ROsString`] and [`ROsStr`],
+//! so they have the same platform-specific encoding,
+//! and the same restrictions on borrowing a `Path` out of them,
+//! or borrowing them out of a `Path`,
+//! described in the [`os_str` module docs](crate::std_types::os_str).
+
+use std::{
+    borrow::Cow,
+    ffi::{OsStr, OsString},
+    path::{Path, PathBuf},
+};
+
+#[cfg(not(windows))]
+use std::{borrow::Borrow, ops::Deref, path::Display};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::std_types::{ROsStr, ROsString, RString};
+
+#[cfg(all(test, not(feature = "only_new_tests")))]
+mod test;
+
+/// Ffi-safe equivalent of `std::path::PathBuf`.
+///
+/// # Example
+///
+/// ```
+/// use abi_stable::std_types::RPathBuf;
+///
+/// use std::path::Path;
+///
+/// let mut path = RPathBuf::from("/usr");
+/// path.push("lib");
+///
+/// assert_eq!(path, Path::new("/usr/lib"));
+///
+/// ```
+///
+#[repr(transparent)]
+#[derive(Default, Clone, StableAbi)]
+pub struct RPathBuf {
+    inner: ROsString,
+}
+
+/// Ffi-safe equivalent of `&'a std::path::Path`.
+///
+/// # Example
+///
+/// ```
+/// use abi_stable::std_types::{RPath, RPathBuf};
+///
+/// use std::path::PathBuf;
+///
+/// fn file_name(path: RPath<'_>) -> Option<String> {
+///     let path: PathBuf = path.to_path_buf().into();
+///     Some(path.file_name()?.to_str()?.to_owned())
+/// }
+///
+/// let path = RPathBuf::from("plugins/foo.so");
+///
+/// assert_eq!(file_name(path.as_rpath()).as_deref(), Some("foo.so"));
+/// assert_eq!(file_name(RPath::from_str("bar.so")).as_deref(), Some("bar.so"));
+///
+/// ```
+///
+#[repr(transparent)]
+#[derive(Copy, Clone, StableAbi)]
+pub struct RPath<'a> {
+    inner: ROsStr<'a>,
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+impl RPathBuf {
+    /// Creates a new, empty `RPathBuf`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RPathBuf;
+    ///
+    /// let path = RPathBuf::new();
+    ///
+    /// assert!(path.as_ros_string().is_empty());
+    ///
+    /// ```
+    pub const fn new() -> Self {
+        Self {
+            inner: ROsString::new(),
+        }
+    }
+
+    /// Converts a `PathBuf` to an `RPathBuf`,
+    /// reusing its heap allocation on Unix.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RPathBuf;
+    ///
+    /// use std::path::{Path, PathBuf};
+    ///
+    /// let path = RPathBuf::from_path_buf(PathBuf::from("foo/bar"));
+    ///
+    /// assert_eq!(path, Path::new("foo/bar"));
+    ///
+    /// ```
+    pub fn from_path_buf(this: PathBuf) -> Self {
+        Self {
+            inner: ROsString::from_os_string(this.into_os_string()),
+        }
+    }
+
+    /// Gets a `&Path` of the contents.
+    ///
+    /// This is not available on Windows,
+    /// read the [`os_str` module docs](crate::std_types::os_str) for why.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RPathBuf;
+    ///
+    /// use std::path::Path;
+    ///
+    /// assert_eq!(RPathBuf::from("foo").as_path(), Path::new("foo"));
+    ///
+    /// ```
+    #[cfg(not(windows))]
+    #[inline]
+    pub fn as_path(&self) -> &Path {
+        Path::new(self.inner.as_os_str())
+    }
+
+    /// Gets an `RPath<'_>` of the contents.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RPath, RPathBuf};
+    ///
+    /// let path = RPathBuf::from("foo");
+    ///
+    /// assert_eq!(path.as_rpath(), RPath::from_str("foo"));
+    ///
+    /// ```
+    #[inline]
+    pub fn as_rpath(&self) -> RPath<'_> {
+        RPath {
+            inner: self.inner.as_ros_str(),
+        }
+    }
+
+    /// Gets an `&ROsString` of the contents.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{ROsString, RPathBuf};
+    ///
+    /// let path = RPathBuf::from("foo");
+    ///
+    /// assert_eq!(path.as_ros_string(), &ROsString::from("foo"));
+    ///
+    /// ```
+    #[inline]
+    pub fn as_ros_string(&self) -> &ROsString {
+        &self.inner
+    }
+
+    /// Returns an object that implements `Display`,
+    /// replacing invalid UTF-8 with `U+FFFD REPLACEMENT CHARACTER`.
+    ///
+    /// This is not available on Windows,
+    /// read the [`os_str` module docs](crate::std_types::os_str) for why.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RPathBuf;
+    ///
+    /// assert_eq!(RPathBuf::from("foo/bar").display().to_string(), "foo/bar");
+    ///
+    /// ```
+    #[cfg(not(windows))]
+    pub fn display(&self) -> Display<'_> {
+        self.as_path().display()
+    }
+
+    /// Extends the path with `path`,
+    /// with the same semantics as `PathBuf::push`.
+    ///
+    /// # Allocation
+    ///
+    /// If this is invoked outside of the dynamic library/binary that created it,
+    /// it will allocate a new buffer and copy the path into it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RPathBuf;
+    ///
+    /// let mut path = RPathBuf::from("foo");
+    /// path.push("bar");
+    ///
+    /// assert_eq!(path, RPathBuf::from("foo/bar"));
+    ///
+    /// ```
+    pub fn push<P>(&mut self, path: P)
+    where
+        P: AsRef<Path>,
+    {
+        self.with_path_buf(|this| this.push(path));
+    }
+
+    /// Truncates the path to its parent,
+    /// returning false (and doing nothing) if there is no parent.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RPathBuf;
+    ///
+    /// let mut path = RPathBuf::from("foo/bar");
+    ///
+    /// assert!(path.pop());
+    /// assert_eq!(path, RPathBuf::from("foo"));
+    ///
+    /// ```
+    pub fn pop(&mut self) -> bool {
+        self.with_path_buf(|this| this.pop())
+    }
+
+    fn with_path_buf<F, R>(&mut self, f: F) -> R
+    where
+        F: FnOnce(&mut PathBuf) -> R,
+    {
+        let mut path = std::mem::take(self).into_path_buf();
+        let ret = f(&mut path);
+        *self = Self::from_path_buf(path);
+        ret
+    }
+
+    /// Converts this `RPathBuf` into a `PathBuf`.
+    ///
+    /// # Allocation
+    ///
+    /// If this is invoked outside of the dynamic library/binary that created it,
+    /// it will allocate a new `PathBuf` and copy the path into it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RPathBuf;
+    ///
+    /// use std::path::PathBuf;
+    ///
+    /// assert_eq!(RPathBuf::from("foo").into_path_buf(), PathBuf::from("foo"));
+    ///
+    /// ```
+    pub fn into_path_buf(self) -> PathBuf {
+        PathBuf::from(self.inner.into_os_string())
+    }
+
+    /// Converts this `RPathBuf` into an `ROsString`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{ROsString, RPathBuf};
+    ///
+    /// assert_eq!(
+    ///     RPathBuf::from("foo").into_ros_string(),
+    ///     ROsString::from("foo"),
+    /// );
+    ///
+    /// ```
+    #[inline]
+    pub fn into_ros_string(self) -> ROsString {
+        self.inner
+    }
+
+    #[cfg(windows)]
+    fn to_std_path_buf(&self) -> PathBuf {
+        PathBuf::from(self.inner.to_std_os_string())
+    }
+}
+
+#[cfg(not(windows))]
+impl Deref for RPathBuf {
+    type Target = Path;
+
+    #[inline]
+    fn deref(&self) -> &Path {
+        self.as_path()
+    }
+}
+
+#[cfg(not(windows))]
+impl AsRef<Path> for RPathBuf {
+    fn as_ref(&self) -> &Path {
+        self
+    }
+}
+
+#[cfg(not(windows))]
+impl AsRef<OsStr> for RPathBuf {
+    fn as_ref(&self) -> &OsStr {
+        self.inner.as_os_str()
+    }
+}
+
+#[cfg(not(windows))]
+impl Borrow<Path> for RPathBuf {
+    fn borrow(&self) -> &Path {
+        self
+    }
+}
+
+impl_from_rust_repr! {
+    impl[] From<PathBuf> for RPathBuf {
+        fn(this){
+            RPathBuf::from_path_buf(this)
+        }
+    }
+}
+
+impl_into_rust_repr! {
+    impl[] Into<PathBuf> for RPathBuf {
+        fn(this){
+            this.into_path_buf()
+        }
+    }
+}
+
+impl From<&Path> for RPathBuf {
+    fn from(this: &Path) -> Self {
+        Self {
+            inner: ROsString::from(this.as_os_str()),
+        }
+    }
+}
+
+impl From<RPath<'_>> for RPathBuf {
+    fn from(this: RPath<'_>) -> Self {
+        this.to_path_buf()
+    }
+}
+
+impl From<ROsString> for RPathBuf {
+    fn from(this: ROsString) -> Self {
+        Self { inner: this }
+    }
+}
+
+impl From<RPathBuf> for ROsString {
+    fn from(this: RPathBuf) -> Self {
+        this.inner
+    }
+}
+
+impl From<OsString> for RPathBuf {
+    fn from(this: OsString) -> Self {
+        Self {
+            inner: ROsString::from(this),
+        }
+    }
+}
+
+impl From<&OsStr> for RPathBuf {
+    fn from(this: &OsStr) -> Self {
+        Self {
+            inner: ROsString::from(this),
+        }
+    }
+}
+
+impl From<&str> for RPathBuf {
+    fn from(this: &str) -> Self {
+        Self {
+            inner: ROsString::from(this),
+        }
+    }
+}
+
+impl From<String> for RPathBuf {
+    fn from(this: String) -> Self {
+        Self {
+            inner: ROsString::from(this),
+        }
+    }
+}
+
+impl From<RString> for RPathBuf {
+    fn from(this: RString) -> Self {
+        Self {
+            inner: ROsString::from(this),
+        }
+    }
+}
+
+#[cfg(not(windows))]
+deref_coerced_impl_cmp_traits! {
+    RPathBuf;
+    coerce_to = Path,
+    [
+        PathBuf,
+        Path,
+        &Path,
+        RPath<'_>,
+    ]
+}
+
+#[cfg(not(windows))]
+shared_impls! {
+    mod = path_buf_impls
+    new_type = RPathBuf[][],
+    original_type = PathBuf,
+}
+
+#[cfg(windows)]
+converted_impl_cmp_traits! {
+    RPathBuf;
+    convert_with = to_std_path_buf,
+    coerce_to = Path,
+    std = [PathBuf, Path, &Path],
+    ffi = [RPath<'_>],
+}
+
+impl<'de> Deserialize<'de> for RPathBuf {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        PathBuf::deserialize(deserializer).map(Self::from)
+    }
+}
+
+impl Serialize for RPathBuf {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        #[cfg(not(windows))]
+        let path = self.as_path();
+        #[cfg(windows)]
+        let path = &self.to_std_path_buf();
+
+        path.serialize(serializer)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+impl<'a> RPath<'a> {
+    /// Converts a `&Path` to an `RPath<'_>`.
+    ///
+    /// This is only available on Unix,
+    /// read the [`os_str` module docs](crate::std_types::os_str) for why.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RPath;
+    ///
+    /// use std::path::Path;
+    ///
+    /// let path = RPath::new(Path::new("foo"));
+    ///
+    /// assert_eq!(path.as_path(), Path::new("foo"));
+    ///
+    /// ```
+    #[cfg(unix)]
+    pub fn new(this: &'a Path) -> Self {
+        Self {
+            inner: ROsStr::new(this.as_os_str()),
+        }
+    }
+
+    /// Constructs an `RPath<'a>` from a `&'a str`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RPath;
+    ///
+    /// const CONFIG: RPath<'static> = RPath::from_str("config.toml");
+    ///
+    /// assert_eq!(CONFIG.to_str(), Some("config.toml"));
+    ///
+    /// ```
+    pub const fn from_str(this: &'a str) -> Self {
+        Self {
+            inner: ROsStr::from_str(this),
+        }
+    }
+
+    /// Gets a `&Path` of the contents.
+    ///
+    /// This is not available on Windows,
+    /// read the [`os_str` module docs](crate::std_types::os_str) for why.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RPath;
+    ///
+    /// use std::path::Path;
+    ///
+    /// assert_eq!(RPath::from_str("foo").as_path(), Path::new("foo"));
+    ///
+    /// ```
+    #[cfg(not(windows))]
+    #[inline]
+    pub fn as_path(self) -> &'a Path {
+        Path::new(self.inner.as_os_str())
+    }
+
+    /// Gets the contents as an `ROsStr<'a>`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{ROsStr, RPath};
+    ///
+    /// assert_eq!(RPath::from_str("foo").as_ros_str(), ROsStr::from_str("foo"));
+    ///
+    /// ```
+    #[inline]
+    pub const fn as_ros_str(self) -> ROsStr<'a> {
+        self.inner
+    }
+
+    /// Converts the path to a `&str`,
+    /// returning `None` if it isn't valid UTF-8.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RPath;
+    ///
+    /// assert_eq!(RPath::from_str("foo").to_str(), Some("foo"));
+    ///
+    /// ```
+    pub fn to_str(self) -> Option<&'a str> {
+        self.inner.to_str()
+    }
+
+    /// Converts the path to a `Cow<'a, str>`,
+    /// replacing invalid UTF-8 with `U+FFFD REPLACEMENT CHARACTER`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RPath;
+    ///
+    /// assert_eq!(RPath::from_str("foo").to_string_lossy(), "foo");
+    ///
+    /// ```
+    pub fn to_string_lossy(self) -> Cow<'a, str> {
+        self.inner.to_string_lossy()
+    }
+
+    /// Returns an object that implements `Display`,
+    /// replacing invalid UTF-8 with `U+FFFD REPLACEMENT CHARACTER`.
+    ///
+    /// This is not available on Windows,
+    /// read the [`os_str` module docs](crate::std_types::os_str) for why.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RPath;
+    ///
+    /// assert_eq!(RPath::from_str("foo/bar").display().to_string(), "foo/bar");
+    ///
+    /// ```
+    #[cfg(not(windows))]
+    pub fn display(self) -> Display<'a> {
+        self.as_path().display()
+    }
+
+    /// Copies the path into an `RPathBuf`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RPath, RPathBuf};
+    ///
+    /// assert_eq!(RPath::from_str("foo").to_path_buf(), RPathBuf::from("foo"));
+    ///
+    /// ```
+    pub fn to_path_buf(self) -> RPathBuf {
+        RPathBuf {
+            inner: self.inner.to_os_string(),
+        }
+    }
+
+    #[cfg(windows)]
+    fn to_std_path_buf(self) -> PathBuf {
+        PathBuf::from(self.inner.to_std_os_string())
+    }
+}
+
+#[cfg(not(windows))]
+impl<'a> Deref for RPath<'a> {
+    type Target = Path;
+
+    #[inline]
+    fn deref(&self) -> &Path {
+        self.as_path()
+    }
+}
+
+#[cfg(not(windows))]
+impl AsRef<Path> for RPath<'_> {
+    fn as_ref(&self) -> &Path {
+        self
+    }
+}
+
+#[cfg(not(windows))]
+impl AsRef<OsStr> for RPath<'_> {
+    fn as_ref(&self) -> &OsStr {
+        self.inner.as_os_str()
+    }
+}
+
+#[cfg(unix)]
+impl_from_rust_repr! {
+    impl['a] From<&'a Path> for RPath<'a> {
+        fn(this){
+            RPath::new(this)
+        }
+    }
+}
+
+#[cfg(not(windows))]
+impl_into_rust_repr! {
+    impl['a] Into<&'a Path> for RPath<'a> {
+        fn(this){
+            this.as_path()
+        }
+    }
+}
+
+impl<'a> From<&'a str> for RPath<'a> {
+    fn from(this: &'a str) -> Self {
+        Self::from_str(this)
+    }
+}
+
+impl<'a> From<ROsStr<'a>> for RPath<'a> {
+    fn from(this: ROsStr<'a>) -> Self {
+        Self { inner: this }
+    }
+}
+
+impl<'a> From<&'a RPathBuf> for RPath<'a> {
+    fn from(this: &'a RPathBuf) -> Self {
+        this.as_rpath()
+    }
+}
+
+#[cfg(not(windows))]
+deref_coerced_impl_cmp_traits! {
+    RPath<'_>;
+    coerce_to = Path,
+    [
+        PathBuf,
+        Path,
+        &Path,
+    ]
+}
+
+#[cfg(not(windows))]
+shared_impls! {
+    mod = path_impls
+    new_type = RPath['a][],
+    original_type = Path,
+}
+
+#[cfg(windows)]
+converted_impl_cmp_traits! {
+    RPath<'_>;
+    convert_with = to_std_path_buf,
+    coerce_to = Path,
+    std = [PathBuf, Path, &Path],
+    ffi = [],
+}
+
+impl<'de: 'a, 'a> Deserialize<'de> for RPath<'a> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        <&'a str as Deserialize<'de>>::deserialize(deserializer).map(Self::from_str)
+    }
+}
+
+impl<'a> Serialize for RPath<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        #[cfg(not(windows))]
+        let path = self.as_path();
+        #[cfg(windows)]
+        let path = &self.to_std_path_buf();
+
+        path.serialize(serializer)
+    }
+}
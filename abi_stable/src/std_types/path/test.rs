@@ -0,0 +1,79 @@
+use super::*;
+
+#[cfg(unix)]
+use std::os::unix::ffi::OsStrExt;
+
+#[test]
+fn conversions() {
+    let std = PathBuf::from("foo/bar.txt");
+    let path = RPathBuf::from(std.clone());
+
+    assert_eq!(path, std);
+    #[cfg(not(windows))]
+    assert_eq!(path.as_path(), &*std);
+    assert_eq!(path.as_rpath(), RPath::from_str("foo/bar.txt"));
+    assert_eq!(RPathBuf::from(path.as_rpath()), path);
+    assert_eq!(RPathBuf::from(ROsString::from("foo/bar.txt")), path);
+    #[cfg(not(windows))]
+    assert_eq!(path.extension(), Some(OsStr::new("txt")));
+    assert_eq!(path.clone().into_path_buf(), std);
+    assert_eq!(path.into_ros_string(), ROsString::from("foo/bar.txt"));
+
+    #[cfg(unix)]
+    let borrowed = RPath::from(std.as_path());
+    #[cfg(not(unix))]
+    let borrowed = RPath::from_str("foo/bar.txt");
+    assert_eq!(borrowed, std);
+    assert_eq!(borrowed.to_str(), Some("foo/bar.txt"));
+    assert_eq!(borrowed.to_path_buf(), std);
+}
+
+#[test]
+fn push_pop() {
+    let mut path = RPathBuf::new();
+    let mut std = PathBuf::new();
+
+    for comp in ["foo", "bar", "baz.rs"] {
+        path.push(comp);
+        std.push(comp);
+        assert_eq!(path, std);
+    }
+
+    while std.pop() {
+        assert!(path.pop());
+        assert_eq!(path, std);
+    }
+    assert!(!path.pop());
+}
+
+#[cfg(unix)]
+#[test]
+fn non_utf8_round_trip() {
+    let std = Path::new(OsStr::from_bytes(b"dir/\xff.so"));
+
+    let path = RPathBuf::from(std);
+    assert_eq!(path.as_os_str().as_bytes(), std.as_os_str().as_bytes());
+    assert_eq!(path.to_str(), None);
+    assert_eq!(path.display().to_string(), "dir/\u{FFFD}.so");
+    assert_eq!(path.clone().into_path_buf(), std);
+
+    let borrowed = RPath::new(std);
+    assert_eq!(borrowed.as_path(), std);
+    assert_eq!(borrowed.to_path_buf(), path);
+}
+
+#[test]
+fn serde() {
+    let path = RPathBuf::from("foo/bar");
+    let serialized = serde_json::to_string(&path).unwrap();
+    assert_eq!(
+        serialized,
+        serde_json::to_string(Path::new("foo/bar")).unwrap()
+    );
+
+    let deserialized = serde_json::from_str::<RPathBuf>(&serialized).unwrap();
+    assert_eq!(deserialized, path);
+
+    let borrowed = serde_json::from_str::<RPath<'_>>(&serialized).unwrap();
+    assert_eq!(borrowed, path);
+}
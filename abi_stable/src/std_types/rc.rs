@@ -0,0 +1,769 @@
+//! Contains the ffi-safe equivalent of `std::rc::Rc`.
+
+use std::{
+    borrow::Borrow,
+    fmt::{self, Debug},
+    marker::PhantomData,
+    mem::ManuallyDrop,
+    rc::{Rc, Weak},
+};
+
+use core_extensions::SelfOps;
+
+use crate::{
+    abi_stability::StableAbi,
+    marker_type::ErasedPrefix,
+    pointer_trait::{
+        AsPtr, CallReferentDrop, CanTransmuteElement, GetPointerKind, PK_SmartPointer,
+    },
+    prefix_type::{PrefixRef, WithMetadata},
+    std_types::{
+        utypeid::{new_utypeid, UTypeId},
+        ROption, RResult,
+    },
+};
+
+#[cfg(all(test, not(feature = "only_new_tests")))]
+mod test;
+
+mod private {
+    use super::*;
+
+    /// Ffi-safe version of `std::rc::Rc`,
+    /// a single-threaded reference-counted pointer.
+    ///
+    /// Unlike [`RArc`](crate::std_types::RArc), the reference counts are not atomic,
+    /// so `RRc<T>` implements neither `Send` nor `Sync`.
+    ///
+    /// # Example
+    ///
+    /// Using an `RRc<RefCell<RVec<u32>>>` to share a list between two owners.
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RRc, RVec};
+    ///
+    /// use std::cell::RefCell;
+    ///
+    /// let rc = RRc::new(RefCell::new(RVec::new()));
+    ///
+    /// {
+    ///     let rc2 = RRc::clone(&rc);
+    ///     assert!(std::ptr::eq(&*rc, &*rc2));
+    ///
+    ///     rc2.borrow_mut().push(3);
+    /// }
+    ///
+    /// rc.borrow_mut().push(5);
+    ///
+    /// let vec = RRc::try_unwrap(rc)
+    ///     .ok()
+    ///     .expect("The clone was dropped, so this must be the only RRc")
+    ///     .into_inner();
+    ///
+    /// assert_eq!(vec, vec![3, 5]);
+    ///
+    /// ```
+    ///
+    #[derive(StableAbi)]
+    #[repr(C)]
+    pub struct RRc<T> {
+        data: *const T,
+        #[sabi(unsafe_change_type = RcVtable_Ref<T>)]
+        vtable: PrefixRef<ErasedPrefix>,
+        _marker: PhantomData<T>,
+    }
+
+    impl_from_rust_repr! {
+        impl[T] From<Rc<T>> for RRc<T> {
+            fn(this){
+                RRc {
+                    data: Rc::into_raw(this),
+                    vtable: unsafe{ VTableGetter::<T>::LIB_VTABLE.0.cast() },
+                    _marker: Default::default(),
+                }
+            }
+        }
+    }
+
+    unsafe impl<T> GetPointerKind for RRc<T> {
+        type Kind = PK_SmartPointer;
+
+        type PtrTarget = T;
+    }
+
+    unsafe impl<T> AsPtr for RRc<T> {
+        fn as_ptr(&self) -> *const T {
+            self.data
+        }
+    }
+
+    unsafe impl<T, O> CanTransmuteElement<O> for RRc<T> {
+        type TransmutedPtr = RRc<O>;
+
+        unsafe fn transmute_element_(self) -> Self::TransmutedPtr {
+            unsafe { core_extensions::utils::transmute_ignore_size(self) }
+        }
+    }
+
+    impl<T> RRc<T> {
+        #[inline(always)]
+        pub(super) const fn data(&self) -> *const T {
+            self.data
+        }
+
+        #[inline(always)]
+        pub(super) unsafe fn data_mut(&mut self) -> *mut T {
+            self.data as *mut T
+        }
+
+        #[inline]
+        pub(crate) fn into_raw(self) -> *const T {
+            let this = ManuallyDrop::new(self);
+            this.data
+        }
+
+        #[inline(always)]
+        pub(crate) const fn vtable(&self) -> RcVtable_Ref<T> {
+            unsafe { RcVtable_Ref::<T>(self.vtable.cast()) }
+        }
+
+        #[allow(dead_code)]
+        #[cfg(test)]
+        pub(super) fn set_vtable_for_testing(&mut self) {
+            self.vtable = unsafe { VTableGetter::<T>::LIB_VTABLE_FOR_TESTING.0.cast() };
+        }
+    }
+
+    /// Ffi-safe version of `std::rc::Weak`,
+    /// a non-owning reference to the value of an [`RRc`].
+    ///
+    /// An `RRcWeak` does not keep the value alive,
+    /// it has to be upgraded to an `RRc` to access the value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RRc, RNone, RSome, RRcWeak};
+    ///
+    /// let rc = RRc::new(100);
+    /// let weak: RRcWeak<u32> = RRc::downgrade(&rc);
+    ///
+    /// assert_eq!(weak.upgrade().map(|x| *x), RSome(100));
+    ///
+    /// drop(rc);
+    ///
+    /// assert_eq!(weak.upgrade(), RNone);
+    ///
+    /// ```
+    ///
+    #[derive(StableAbi)]
+    #[repr(C)]
+    pub struct RRcWeak<T> {
+        data: *const T,
+        #[sabi(unsafe_change_type = RcVtable_Ref<T>)]
+        vtable: PrefixRef<ErasedPrefix>,
+        _marker: PhantomData<T>,
+    }
+
+    impl_from_rust_repr! {
+        impl[T] From<Weak<T>> for RRcWeak<T> {
+            fn(this){
+                RRcWeak {
+                    data: Weak::into_raw(this),
+                    vtable: unsafe{ VTableGetter::<T>::LIB_VTABLE.0.cast() },
+                    _marker: Default::default(),
+                }
+            }
+        }
+    }
+
+    impl<T> RRcWeak<T> {
+        #[inline(always)]
+        pub(super) const fn data(&self) -> *const T {
+            self.data
+        }
+
+        #[inline(always)]
+        pub(crate) const fn vtable(&self) -> RcVtable_Ref<T> {
+            unsafe { RcVtable_Ref::<T>(self.vtable.cast()) }
+        }
+    }
+}
+
+pub use self::private::{RRc, RRcWeak};
+
+impl<T> RRc<T> {
+    /// Constructs an `RRc` from a value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RRc;
+    ///
+    /// let rc = RRc::new(100);
+    ///
+    /// ```
+    pub fn new(this: T) -> Self {
+        Rc::new(this).into()
+    }
+
+    /// Converts this `RRc<T>` into an `Rc<T>`
+    ///
+    /// # Allocators
+    ///
+    /// `RRc<T>` cannot always be converted to an `Rc<T>`,
+    /// because their allocators *might* be different.
+    ///
+    /// # When is T cloned
+    ///
+    /// `T` is cloned if the current dynamic_library/executable is
+    /// not the one that created the `RRc<T>`,
+    /// and the strong count is greater than 1.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RRc;
+    /// use std::rc::Rc;
+    ///
+    /// let rc = RRc::new(100);
+    ///
+    /// assert_eq!(RRc::into_rc(rc), Rc::new(100));
+    ///
+    /// ```
+    pub fn into_rc(this: Self) -> Rc<T>
+    where
+        T: Clone,
+    {
+        let this_vtable = this.vtable();
+        let other_vtable = VTableGetter::LIB_VTABLE;
+        if ::std::ptr::eq(this_vtable.0.to_raw_ptr(), other_vtable.0.to_raw_ptr())
+            || this_vtable.type_id()() == other_vtable.type_id()()
+        {
+            unsafe { Rc::from_raw(this.into_raw()) }
+        } else {
+            Self::try_unwrap(this)
+                .unwrap_or_else(|x| T::clone(&x))
+                .piped(Rc::new)
+        }
+    }
+
+    /// Attempts to unwrap this `RRc<T>` into a `T`,
+    /// returns `Err(self)` if the `RRc<T>`'s strong count is greater than 1.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RRc;
+    ///
+    /// let rc0 = RRc::new(100);
+    /// assert_eq!(RRc::try_unwrap(rc0), Ok(100));
+    ///
+    /// let rc1 = RRc::new(100);
+    /// let rc1_clone = RRc::clone(&rc1);
+    /// assert_eq!(RRc::try_unwrap(rc1), Err(rc1_clone.clone()));
+    ///
+    /// ```
+    #[inline]
+    pub fn try_unwrap(this: Self) -> Result<T, Self> {
+        let vtable = this.vtable();
+        unsafe { (vtable.try_unwrap())(this).into_result() }
+    }
+
+    /// Attempts to create a mutable reference to `T`,
+    /// failing if the `RRc<T>`'s strong count is greater than 1.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RRc;
+    ///
+    /// let mut rc0 = RRc::new(100);
+    /// *RRc::get_mut(&mut rc0).unwrap() += 400;
+    /// assert_eq!(*rc0, 500);
+    ///
+    /// let mut rc1 = RRc::new(100);
+    /// let _rc1_clone = RRc::clone(&rc1);
+    /// assert_eq!(RRc::get_mut(&mut rc1), None);
+    ///
+    /// ```
+    #[inline]
+    pub fn get_mut(this: &mut Self) -> Option<&mut T> {
+        let vtable = this.vtable();
+        unsafe { (vtable.get_mut())(this) }
+    }
+
+    /// Makes a mutable reference to `T`.
+    ///
+    /// If there are other `RRc<T>`s pointing to the same value,
+    /// then `T` is cloned into a new `RRc<T>` to ensure unique ownership of the value.
+    ///
+    ///
+    /// # Postconditions
+    ///
+    /// After this call, the strong count of `this` will be 1,
+    /// because either it was 1 before the call,
+    /// or because a new `RRc<T>` was created to ensure unique ownership of `T`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RRc;
+    ///
+    /// let mut rc0 = RRc::new(100);
+    /// *RRc::make_mut(&mut rc0) += 400;
+    /// assert_eq!(*rc0, 500);
+    ///
+    /// let mut rc1 = RRc::new(100);
+    /// let rc1_clone = RRc::clone(&rc1);
+    /// *RRc::make_mut(&mut rc1) += 400;
+    /// assert_eq!(*rc1, 500);
+    /// assert_eq!(*rc1_clone, 100);
+    ///
+    /// ```
+    #[inline]
+    pub fn make_mut(this: &mut Self) -> &mut T
+    where
+        T: Clone,
+    {
+        // Workaround for non-lexical lifetimes not being smart enough
+        // to figure out that this borrow doesn't continue in the None branch.
+        let unbounded_this = unsafe { &mut *(this as *mut Self) };
+        match Self::get_mut(unbounded_this) {
+            Some(x) => x,
+            None => {
+                let new_rc = RRc::new((**this).clone());
+                *this = new_rc;
+                // This is fine, since this is a freshly created rc with a clone of the data.
+                unsafe { &mut *this.data_mut() }
+            }
+        }
+    }
+
+    /// Gets the number of `RRc` that point to the value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RRc;
+    ///
+    /// let rc = RRc::new(0);
+    /// assert_eq!(RRc::strong_count(&rc), 1);
+    ///
+    /// let clone = RRc::clone(&rc);
+    /// assert_eq!(RRc::strong_count(&rc), 2);
+    ///
+    /// ```
+    pub fn strong_count(this: &Self) -> usize {
+        let vtable = this.vtable();
+        unsafe { vtable.strong_count()(this) }
+    }
+
+    /// Gets the number of `RRcWeak` and `std::rc::Weak` that point to the value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RRc;
+    ///
+    /// use std::rc::Rc;
+    ///
+    /// let rustrc = Rc::new(0);
+    /// let rc = RRc::from(rustrc.clone());
+    /// assert_eq!(RRc::weak_count(&rc), 0);
+    ///
+    /// let weak_0 = Rc::downgrade(&rustrc);
+    /// assert_eq!(RRc::weak_count(&rc), 1);
+    ///
+    /// let weak_1 = Rc::downgrade(&rustrc);
+    /// assert_eq!(RRc::weak_count(&rc), 2);
+    /// ```
+    pub fn weak_count(this: &Self) -> usize {
+        let vtable = this.vtable();
+        unsafe { vtable.weak_count()(this) }
+    }
+
+    /// Creates an [`RRcWeak`] pointing to the same value as this `RRc<T>`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RRc;
+    ///
+    /// let rc = RRc::new(0);
+    /// let weak = RRc::downgrade(&rc);
+    ///
+    /// assert_eq!(RRc::weak_count(&rc), 1);
+    /// assert!(RRc::ptr_eq(&rc, &weak.upgrade().unwrap()));
+    ///
+    /// ```
+    pub fn downgrade(this: &Self) -> RRcWeak<T> {
+        let vtable = this.vtable();
+        unsafe { vtable.downgrade()(this) }
+    }
+
+    /// Returns whether both `RRc`s point to the same allocation.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RRc;
+    ///
+    /// let rc = RRc::new(0);
+    ///
+    /// assert!(RRc::ptr_eq(&rc, &rc.clone()));
+    /// assert!(!RRc::ptr_eq(&rc, &RRc::new(0)));
+    ///
+    /// ```
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        std::ptr::eq(this.data(), other.data())
+    }
+}
+
+////////////////////////////////////////////////////////////////////
+
+impl<T> Borrow<T> for RRc<T> {
+    fn borrow(&self) -> &T {
+        self
+    }
+}
+
+impl<T> AsRef<T> for RRc<T> {
+    fn as_ref(&self) -> &T {
+        self
+    }
+}
+
+////////////////////////////////////////////////////////////////////
+
+impl<T> Default for RRc<T>
+where
+    T: Default,
+{
+    fn default() -> Self {
+        RRc::new(T::default())
+    }
+}
+
+impl<T> Clone for RRc<T> {
+    fn clone(&self) -> Self {
+        unsafe { (self.vtable().clone_())(self) }
+    }
+}
+
+impl_into_rust_repr! {
+    impl[T] Into<Rc<T>> for RRc<T>
+    where[
+        T: Clone+StableAbi,
+    ]{
+        fn(this){
+            RRc::into_rc(this)
+        }
+    }
+}
+
+impl<T> Drop for RRc<T> {
+    fn drop(&mut self) {
+        // The layout of the RRc<_> won't change since it doesn't
+        // actually support ?Sized types.
+        unsafe {
+            let vtable = self.vtable();
+            (vtable.destructor())(self.data(), CallReferentDrop::Yes);
+        }
+    }
+}
+
+shared_impls! {pointer
+    mod = rc_impls
+    new_type = RRc[][T],
+    original_type = Rc,
+}
+
+impl<T> Unpin for RRc<T> {}
+
+/////////////////////////////////////////////////////////
+
+impl<T> RRcWeak<T> {
+    /// Constructs an `RRcWeak<T>` that doesn't point to any value,
+    /// calling `upgrade` on it always returns `RNone`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RNone, RRcWeak};
+    ///
+    /// let weak = RRcWeak::<u32>::new();
+    ///
+    /// assert_eq!(weak.upgrade(), RNone);
+    ///
+    /// ```
+    pub fn new() -> Self {
+        Weak::new().into()
+    }
+
+    /// Attempts to get an `RRc<T>` to the value,
+    /// returning `RNone` if the value was already dropped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RRc, RNone};
+    ///
+    /// let rc = RRc::new("hello");
+    /// let weak = RRc::downgrade(&rc);
+    ///
+    /// assert_eq!(*weak.upgrade().unwrap(), "hello");
+    ///
+    /// drop(rc);
+    /// assert_eq!(weak.upgrade(), RNone);
+    ///
+    /// ```
+    pub fn upgrade(&self) -> ROption<RRc<T>> {
+        let vtable = self.vtable();
+        unsafe { vtable.upgrade()(self) }
+    }
+
+    /// Gets the number of `RRc` that point to the value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RRc;
+    ///
+    /// let rc = RRc::new(0);
+    /// let weak = RRc::downgrade(&rc);
+    /// assert_eq!(weak.strong_count(), 1);
+    ///
+    /// drop(rc);
+    /// assert_eq!(weak.strong_count(), 0);
+    ///
+    /// ```
+    pub fn strong_count(&self) -> usize {
+        let vtable = self.vtable();
+        unsafe { vtable.weak_strong_count()(self) }
+    }
+
+    /// Gets the number of weak pointers that point to the value,
+    /// returning 0 if there are no `RRc`s left.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RRc;
+    ///
+    /// let rc = RRc::new(0);
+    /// let weak = RRc::downgrade(&rc);
+    /// let _weak2 = weak.clone();
+    /// assert_eq!(weak.weak_count(), 2);
+    ///
+    /// drop(rc);
+    /// assert_eq!(weak.weak_count(), 0);
+    ///
+    /// ```
+    pub fn weak_count(&self) -> usize {
+        let vtable = self.vtable();
+        unsafe { vtable.weak_weak_count()(self) }
+    }
+
+    /// Returns whether both `RRcWeak`s point to the same allocation,
+    /// or were both created with `RRcWeak::new`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RRc;
+    ///
+    /// let rc = RRc::new(0);
+    /// let weak = RRc::downgrade(&rc);
+    ///
+    /// assert!(weak.ptr_eq(&RRc::downgrade(&rc)));
+    /// assert!(!weak.ptr_eq(&RRc::downgrade(&RRc::new(0))));
+    ///
+    /// ```
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self.data(), other.data())
+    }
+}
+
+impl<T> Default for RRcWeak<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for RRcWeak<T> {
+    fn clone(&self) -> Self {
+        unsafe { (self.vtable().clone_weak())(self) }
+    }
+}
+
+impl<T> Debug for RRcWeak<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("(RRcWeak)")
+    }
+}
+
+impl<T> Drop for RRcWeak<T> {
+    fn drop(&mut self) {
+        unsafe {
+            let vtable = self.vtable();
+            (vtable.destructor_weak())(self.data());
+        }
+    }
+}
+
+/////////////////////////////////////////////////////////
+
+mod vtable_mod {
+    use super::*;
+
+    pub(super) struct VTableGetter<'a, T>(PhantomData<&'a T>);
+
+    impl<'a, T: 'a> VTableGetter<'a, T> {
+        const DEFAULT_VTABLE: RcVtable<T> = RcVtable {
+            type_id: new_utypeid::<RRc<()>>,
+            destructor: destructor_rc::<T>,
+            clone_: clone_rc::<T>,
+            get_mut: get_mut_rc::<T>,
+            try_unwrap: try_unwrap_rc::<T>,
+            strong_count: strong_count_rc::<T>,
+            weak_count: weak_count_rc::<T>,
+            downgrade: downgrade_rc::<T>,
+            upgrade: upgrade_weak::<T>,
+            clone_weak: clone_weak::<T>,
+            destructor_weak: destructor_weak::<T>,
+            weak_strong_count: strong_count_weak::<T>,
+            weak_weak_count: weak_count_weak::<T>,
+        };
+
+        staticref! {
+            const WM_DEFAULT: WithMetadata<RcVtable<T>> =
+                WithMetadata::new(Self::DEFAULT_VTABLE)
+        }
+
+        // The VTABLE for this type in this executable/library
+        pub(super) const LIB_VTABLE: RcVtable_Ref<T> =
+            { RcVtable_Ref(Self::WM_DEFAULT.as_prefix()) };
+
+        #[cfg(test)]
+        staticref! {const WM_FOR_TESTING: WithMetadata<RcVtable<T>> =
+            WithMetadata::new(
+                RcVtable{
+                    type_id: new_utypeid::<RRc<i32>>,
+                    ..Self::DEFAULT_VTABLE
+                }
+            )
+        }
+
+        #[cfg(test)]
+        pub(super) const LIB_VTABLE_FOR_TESTING: RcVtable_Ref<T> =
+            { RcVtable_Ref(Self::WM_FOR_TESTING.as_prefix()) };
+    }
+
+    #[derive(StableAbi)]
+    #[repr(C)]
+    #[sabi(kind(Prefix))]
+    #[sabi(missing_field(panic))]
+    pub struct RcVtable<T> {
+        pub(super) type_id: extern "C" fn() -> UTypeId,
+        pub(super) destructor: unsafe extern "C" fn(*const T, CallReferentDrop),
+        pub(super) clone_: unsafe extern "C" fn(&RRc<T>) -> RRc<T>,
+        pub(super) get_mut: unsafe extern "C" fn(&mut RRc<T>) -> Option<&mut T>,
+        pub(super) try_unwrap: unsafe extern "C" fn(RRc<T>) -> RResult<T, RRc<T>>,
+        pub(super) strong_count: unsafe extern "C" fn(&RRc<T>) -> usize,
+        pub(super) weak_count: unsafe extern "C" fn(&RRc<T>) -> usize,
+        pub(super) downgrade: unsafe extern "C" fn(&RRc<T>) -> RRcWeak<T>,
+        pub(super) upgrade: unsafe extern "C" fn(&RRcWeak<T>) -> ROption<RRc<T>>,
+        pub(super) clone_weak: unsafe extern "C" fn(&RRcWeak<T>) -> RRcWeak<T>,
+        pub(super) destructor_weak: unsafe extern "C" fn(*const T),
+        pub(super) weak_strong_count: unsafe extern "C" fn(&RRcWeak<T>) -> usize,
+        #[sabi(last_prefix_field)]
+        pub(super) weak_weak_count: unsafe extern "C" fn(&RRcWeak<T>) -> usize,
+    }
+
+    unsafe extern "C" fn destructor_rc<T>(this: *const T, call_drop: CallReferentDrop) {
+        extern_fn_panic_handling! {no_early_return; unsafe {
+            if call_drop == CallReferentDrop::Yes {
+                drop(Rc::from_raw(this));
+            } else {
+                drop(Rc::from_raw(this as *const ManuallyDrop<T>));
+            }
+        }}
+    }
+
+    unsafe fn with_rc_ref<T, F, R>(this: &RRc<T>, f: F) -> R
+    where
+        F: FnOnce(&Rc<T>) -> R,
+    {
+        let x = this.data();
+        let x = unsafe { Rc::from_raw(x) };
+        let x = ManuallyDrop::new(x);
+        f(&x)
+    }
+
+    unsafe extern "C" fn clone_rc<T>(this: &RRc<T>) -> RRc<T> {
+        unsafe { with_rc_ref(this, |x| Rc::clone(x).into()) }
+    }
+
+    unsafe extern "C" fn get_mut_rc<'a, T>(this: &'a mut RRc<T>) -> Option<&'a mut T> {
+        let rc = unsafe { Rc::from_raw(this.data()) };
+        let mut rc = ManuallyDrop::new(rc);
+        // This is fine, since we are only touching the data afterwards,
+        // which is guaranteed to have the 'a lifetime.
+        let rc: &'a mut Rc<T> = unsafe { &mut *(&mut *rc as *mut Rc<T>) };
+        Rc::get_mut(rc)
+    }
+
+    unsafe extern "C" fn try_unwrap_rc<T>(this: RRc<T>) -> RResult<T, RRc<T>> {
+        this.into_raw()
+            .piped(|x| unsafe { Rc::from_raw(x) })
+            .piped(Rc::try_unwrap)
+            .map_err(RRc::from)
+            .into()
+    }
+
+    unsafe extern "C" fn strong_count_rc<T>(this: &RRc<T>) -> usize {
+        unsafe { with_rc_ref(this, |x| Rc::strong_count(x)) }
+    }
+
+    unsafe extern "C" fn weak_count_rc<T>(this: &RRc<T>) -> usize {
+        unsafe { with_rc_ref(this, |x| Rc::weak_count(x)) }
+    }
+
+    unsafe extern "C" fn downgrade_rc<T>(this: &RRc<T>) -> RRcWeak<T> {
+        unsafe { with_rc_ref(this, |x| Rc::downgrade(x).into()) }
+    }
+
+    unsafe fn with_weak_ref<T, F, R>(this: &RRcWeak<T>, f: F) -> R
+    where
+        F: FnOnce(&Weak<T>) -> R,
+    {
+        let x = this.data();
+        let x = unsafe { Weak::from_raw(x) };
+        let x = ManuallyDrop::new(x);
+        f(&x)
+    }
+
+    unsafe extern "C" fn upgrade_weak<T>(this: &RRcWeak<T>) -> ROption<RRc<T>> {
+        unsafe { with_weak_ref(this, |x| x.upgrade().map(RRc::from).into()) }
+    }
+
+    unsafe extern "C" fn clone_weak<T>(this: &RRcWeak<T>) -> RRcWeak<T> {
+        unsafe { with_weak_ref(this, |x| Weak::clone(x).into()) }
+    }
+
+    unsafe extern "C" fn destructor_weak<T>(this: *const T) {
+        extern_fn_panic_handling! {no_early_return; unsafe {
+            drop(Weak::from_raw(this));
+        }}
+    }
+
+    unsafe extern "C" fn strong_count_weak<T>(this: &RRcWeak<T>) -> usize {
+        unsafe { with_weak_ref(this, Weak::strong_count) }
+    }
+
+    unsafe extern "C" fn weak_count_weak<T>(this: &RRcWeak<T>) -> usize {
+        unsafe { with_weak_ref(this, Weak::weak_count) }
+    }
+}
+use self::vtable_mod::{RcVtable_Ref, VTableGetter};
@@ -0,0 +1,246 @@
+use super::*;
+
+use std::cell::Cell;
+
+use crate::{
+    sabi_trait::{examples::EmptyTrait_TO, TD_CanDowncast},
+    std_types::RNone,
+    DynTrait,
+};
+
+#[allow(clippy::redundant_allocation)]
+fn _covariant_rc<'a: 'b, 'b, T>(rc: Rc<&'a T>) -> Rc<&'b T> {
+    rc
+}
+
+fn _covariant_rrc<'a: 'b, 'b, T>(rc: RRc<&'a T>) -> RRc<&'b T> {
+    rc
+}
+
+#[test]
+fn test_covariance() {
+    struct F<T>(T);
+
+    fn eq<T>(left: &RRc<&T>, right: &RRc<&T>) -> bool
+    where
+        T: PartialEq,
+    {
+        left == right
+    }
+
+    let aaa = F(3);
+    let bbb = F(5);
+
+    let v0 = RRc::new(&aaa.0);
+    let v1 = RRc::new(&bbb.0);
+
+    assert!(!eq(&v0, &v1));
+}
+
+fn refaddr<T>(ref_: &T) -> usize {
+    ref_ as *const T as usize
+}
+
+#[test]
+fn to_from_rc() {
+    let orig_a = Rc::new(1000);
+    let a_addr = (&*orig_a) as *const _ as usize;
+    let mut reprc_a = orig_a.clone().piped(RRc::from);
+
+    assert_eq!(a_addr, refaddr(&*reprc_a));
+
+    assert_eq!(a_addr, reprc_a.clone().piped(|a| refaddr(&*a)));
+    assert_eq!(
+        a_addr,
+        reprc_a.clone().piped(RRc::into_rc).piped(|a| refaddr(&*a))
+    );
+
+    reprc_a.set_vtable_for_testing();
+
+    assert_eq!(a_addr, refaddr(&*reprc_a));
+    assert_eq!(Rc::strong_count(&orig_a), 2);
+
+    let back_to_a = reprc_a.piped(RRc::into_rc);
+    assert_eq!(Rc::strong_count(&orig_a), 1);
+    assert_ne!(a_addr, refaddr(&*back_to_a));
+    drop(back_to_a);
+
+    assert_eq!(Rc::strong_count(&orig_a), 1);
+}
+
+// testing that Rc<()> is valid
+#[allow(clippy::unit_cmp)]
+#[test]
+fn default() {
+    assert_eq!(*RRc::<String>::default(), "");
+    assert_eq!(*RRc::<()>::default(), ());
+    assert_eq!(*RRc::<u32>::default(), 0);
+    assert_eq!(*RRc::<bool>::default(), false);
+}
+
+#[test]
+fn new_test() {
+    for elem in 0..100 {
+        assert_eq!(*RRc::new(elem), elem);
+    }
+}
+
+#[test]
+fn into_raw() {
+    let orig_a = Rc::new(200);
+    let reprc_a = orig_a.clone().piped(RRc::from);
+    let raw_a = reprc_a.into_raw();
+    assert_eq!(Rc::strong_count(&orig_a), 2);
+    unsafe {
+        Rc::from_raw(raw_a);
+    }
+    assert_eq!(Rc::strong_count(&orig_a), 1);
+}
+
+#[test]
+fn get_mut() {
+    let mut conv = Rc::new(200).piped(RRc::from);
+
+    {
+        let _conv_clone = conv.clone();
+        assert_eq!(RRc::get_mut(&mut conv), None);
+    }
+    assert_eq!(RRc::get_mut(&mut conv), Some(&mut 200));
+}
+
+#[test]
+fn make_mut() {
+    let count = Cell::new(1);
+    let dod = DecrementOnDrop(&count);
+
+    let mut rc = Rc::new(ValueAndDod {
+        value: 'a',
+        _dod: dod.clone(),
+    })
+    .piped(RRc::from);
+
+    {
+        assert_eq!(dod.count(), 2);
+        let rc_clone = rc.clone();
+
+        let mutref = RRc::make_mut(&mut rc);
+        assert_eq!(dod.count(), 3);
+        mutref.value = 'c';
+
+        assert_eq!(rc_clone.value, 'a');
+    }
+    assert_eq!(dod.count(), 2);
+    assert_eq!(rc.value, 'c');
+}
+
+#[test]
+fn downgrade_upgrade() {
+    let count = Cell::new(1);
+    let dod = DecrementOnDrop(&count);
+
+    let rc = RRc::new(dod.clone());
+    let weak = RRc::downgrade(&rc);
+    assert_eq!(dod.count(), 2);
+    assert_eq!(RRc::weak_count(&rc), 1);
+    assert_eq!(weak.strong_count(), 1);
+
+    {
+        let upgraded = weak.upgrade().unwrap();
+        assert!(RRc::ptr_eq(&rc, &upgraded));
+        assert_eq!(RRc::strong_count(&rc), 2);
+    }
+
+    let weak_clone = weak.clone();
+    assert!(weak.ptr_eq(&weak_clone));
+    assert_eq!(weak.weak_count(), 2);
+
+    drop(rc);
+    assert_eq!(dod.count(), 1);
+    assert_eq!(weak.upgrade().map(drop), RNone);
+    assert_eq!(weak.strong_count(), 0);
+    assert_eq!(weak_clone.weak_count(), 0);
+}
+
+#[test]
+fn weak_with_other_vtable() {
+    let mut rc = RRc::new(1000);
+    rc.set_vtable_for_testing();
+
+    let weak = RRc::downgrade(&rc);
+    assert_eq!(*weak.upgrade().unwrap(), 1000);
+
+    let from_std = RRcWeak::from(Rc::downgrade(&RRc::into_rc(rc)));
+    assert_eq!(from_std.upgrade(), RNone);
+    assert_eq!(weak.upgrade(), RNone);
+}
+
+#[test]
+fn new_weak() {
+    let weak = RRcWeak::<u32>::new();
+    assert_eq!(weak.upgrade(), RNone);
+    assert_eq!(weak.strong_count(), 0);
+    assert_eq!(weak.weak_count(), 0);
+    assert!(weak.ptr_eq(&RRcWeak::default()));
+    drop(weak.clone());
+}
+
+#[repr(C)]
+#[derive(StableAbi)]
+#[sabi(impl_InterfaceType(Debug, Display))]
+struct UnsendDebugDisplayInterface;
+
+#[test]
+fn trait_objects() {
+    let rc = RRc::new(107_u32);
+
+    {
+        let object: DynTrait<'_, RRc<()>, UnsendDebugDisplayInterface> =
+            DynTrait::from_ptr(rc.clone());
+        let clone = object.shallow_clone();
+        assert_eq!(RRc::strong_count(&rc), 3);
+        assert_eq!(format!("{:?} {}", object, clone), "107 107");
+    }
+    assert_eq!(RRc::strong_count(&rc), 1);
+
+    {
+        let object = EmptyTrait_TO::from_ptr(rc.clone(), TD_CanDowncast);
+        let clone = EmptyTrait_TO::from_sabi(object.obj.shallow_clone());
+        assert_eq!(RRc::strong_count(&rc), 3);
+        drop(object);
+
+        let unerased = clone.obj.downcast_into::<u32>().unwrap();
+        assert!(RRc::ptr_eq(&unerased, &rc));
+    }
+    assert_eq!(RRc::strong_count(&rc), 1);
+}
+
+/////////////////////////////////////////
+
+#[derive(Clone)]
+struct ValueAndDod<'a, T> {
+    value: T,
+    _dod: DecrementOnDrop<'a>,
+}
+
+/////////////////////////////////////////
+
+struct DecrementOnDrop<'a>(&'a Cell<u32>);
+
+impl<'a> DecrementOnDrop<'a> {
+    fn count(&self) -> u32 {
+        self.0.get()
+    }
+}
+
+impl<'a> Clone for DecrementOnDrop<'a> {
+    fn clone(&self) -> Self {
+        self.0.set(self.0.get() + 1);
+        DecrementOnDrop(self.0)
+    }
+}
+
+impl<'a> Drop for DecrementOnDrop<'a> {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() - 1);
+    }
+}
@@ -0,0 +1,101 @@
+use super::*;
+
+use std::collections::hash_map::RandomState;
+
+use crate::std_types::{RSome, RString};
+
+fn sorted<'a, I>(iter: I) -> Vec<u32>
+where
+    I: IntoIterator<Item = &'a u32>,
+{
+    let mut list = iter.into_iter().copied().collect::<Vec<_>>();
+    list.sort_unstable();
+    list
+}
+
+fn set_of(list: &[u32]) -> RHashSet<u32> {
+    list.iter().copied().collect()
+}
+
+#[test]
+fn test_insert_remove() {
+    let mut set = RHashSet::<RString>::new();
+
+    assert!(set.insert("foo".into()));
+    assert!(set.insert("bar".into()));
+    assert!(!set.insert("foo".into()));
+    assert_eq!(set.len(), 2);
+
+    assert!(set.contains("foo"));
+    assert!(set.contains_p(&RString::from("bar")));
+    assert!(!set.contains("baz"));
+
+    assert!(set.remove("bar"));
+    assert!(!set.remove("bar"));
+    assert_eq!(set.take("foo"), RSome(RString::from("foo")));
+    assert!(set.is_empty());
+}
+
+#[test]
+fn test_iteration() {
+    let mut set = set_of(&[3, 5, 8]);
+
+    assert_eq!(sorted(&set), vec![3, 5, 8]);
+    assert_eq!(sorted(set.iter().clone()), vec![3, 5, 8]);
+
+    let mut drained = set.drain().collect::<Vec<_>>();
+    drained.sort_unstable();
+    assert_eq!(drained, vec![3, 5, 8]);
+    assert!(set.is_empty());
+
+    let mut owned = set_of(&[1, 2]).into_iter().collect::<Vec<_>>();
+    owned.sort_unstable();
+    assert_eq!(owned, vec![1, 2]);
+}
+
+#[test]
+fn test_set_operations() {
+    let a = set_of(&[1, 2, 3, 4]);
+    let b = set_of(&[3, 4, 5]);
+
+    assert_eq!(sorted(a.union(&b)), vec![1, 2, 3, 4, 5]);
+    assert_eq!(sorted(b.union(&a)), vec![1, 2, 3, 4, 5]);
+    assert_eq!(sorted(a.intersection(&b)), vec![3, 4]);
+    assert_eq!(sorted(b.intersection(&a)), vec![3, 4]);
+    assert_eq!(sorted(a.difference(&b)), vec![1, 2]);
+    assert_eq!(sorted(b.difference(&a)), vec![5]);
+    assert_eq!(sorted(a.symmetric_difference(&b)), vec![1, 2, 5]);
+
+    assert!(!a.is_disjoint(&b));
+    assert!(a.is_disjoint(&set_of(&[10, 11])));
+    assert!(set_of(&[1, 3]).is_subset(&a));
+    assert!(!b.is_subset(&a));
+    assert!(a.is_superset(&set_of(&[])));
+}
+
+#[test]
+fn test_conversions() {
+    let std = vec![1, 2, 3].into_iter().collect::<HashSet<u32>>();
+    let set = RHashSet::from(std.clone());
+
+    assert_eq!(HashSet::from(set.clone()), std);
+    assert_eq!(set.clone(), set);
+    assert_ne!(set, set_of(&[1, 2]));
+
+    let mut extended = set_of(&[1]);
+    extended.extend(&[2, 3]);
+    assert_eq!(extended, set);
+
+    let with_hasher = RHashSet::<u32, RandomState>::with_capacity_and_hasher(8, RandomState::new());
+    assert!(with_hasher.capacity() >= 8);
+}
+
+#[test]
+fn test_serde() {
+    let set = serde_json::from_str::<RHashSet<RString>>(r#"["a", "b", "a"]"#).unwrap();
+    assert_eq!(set.len(), 2);
+
+    let serialized = serde_json::to_string(&set).unwrap();
+    let redeserialized = serde_json::from_str::<RHashSet<RString>>(&serialized).unwrap();
+    assert_eq!(redeserialized, set);
+}
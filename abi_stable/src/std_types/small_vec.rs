@@ -0,0 +1,1005 @@
+//! Contains an ffi-safe vector which stores a few elements inline,
+//! spilling to the heap when it outgrows them, and related items.
+
+use std::{
+    borrow::{Borrow, BorrowMut},
+    cmp::Ordering,
+    fmt::{self, Debug},
+    hash::{Hash, Hasher},
+    iter::{FromIterator, FusedIterator},
+    mem::ManuallyDrop,
+    ops::{Deref, DerefMut},
+    ptr, slice,
+};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::std_types::{array_vec, vec, RArrayVec, RSlice, RSliceMut, RVec};
+
+#[cfg(all(test, not(feature = "only_new_tests")))]
+mod test;
+
+/// An ffi-safe vector which stores up to `N` elements inline,
+/// moving them into an [`RVec`] once it needs more capacity.
+///
+/// Whether the elements are currently on the heap is returned by
+/// [`spilled`](#method.spilled).
+///
+/// # Example
+///
+/// ```
+/// use abi_stable::std_types::{RSmallVec, RVec};
+///
+/// let mut vec = RSmallVec::<u32, 2>::new();
+///
+/// vec.push(3);
+/// vec.push(5);
+/// assert!(!vec.spilled());
+///
+/// vec.push(8);
+/// assert!(vec.spilled());
+///
+/// assert_eq!(vec.as_slice(), &[3, 5, 8]);
+///
+/// vec.pop();
+/// vec.shrink_to_fit();
+/// assert!(!vec.spilled());
+///
+/// assert_eq!(vec.into_rvec(), RVec::from(vec![3, 5]));
+///
+/// ```
+///
+#[repr(C)]
+#[derive(StableAbi)]
+pub struct RSmallVec<T, const N: usize> {
+    is_heap: bool,
+    data: SmallVecData<T, N>,
+}
+
+/// The storage of an `RSmallVec`, which field is initialized is determined by
+/// `RSmallVec::is_heap`.
+#[repr(C)]
+#[derive(StableAbi)]
+union SmallVecData<T, const N: usize> {
+    inline: ManuallyDrop<RArrayVec<T, N>>,
+    heap: ManuallyDrop<RVec<T>>,
+}
+
+enum InnerMut<'a, T, const N: usize> {
+    Inline(&'a mut RArrayVec<T, N>),
+    Heap(&'a mut RVec<T>),
+}
+
+/// Calls the same method on whichever vector stores the elements.
+macro_rules! with_inner {
+    ($this:expr, |$vec:ident| $expr:expr) => {
+        match $this.inner_mut() {
+            InnerMut::Inline($vec) => $expr,
+            InnerMut::Heap($vec) => $expr,
+        }
+    };
+}
+
+impl<T, const N: usize> RSmallVec<T, N> {
+    /// Creates an empty `RSmallVec`, which stores its elements inline.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RSmallVec;
+    ///
+    /// let vec = RSmallVec::<u32, 4>::new();
+    ///
+    /// assert!(vec.is_empty());
+    /// assert!(!vec.spilled());
+    /// assert_eq!(vec.capacity(), 4);
+    ///
+    /// ```
+    pub const fn new() -> Self {
+        Self {
+            is_heap: false,
+            data: SmallVecData {
+                inline: ManuallyDrop::new(RArrayVec::new()),
+            },
+        }
+    }
+
+    /// Creates an empty `RSmallVec` with at least `capacity` capacity,
+    /// allocating only if `capacity` is larger than `N`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RSmallVec;
+    ///
+    /// let vec = RSmallVec::<u32, 4>::with_capacity(3);
+    /// assert!(!vec.spilled());
+    ///
+    /// let vec = RSmallVec::<u32, 4>::with_capacity(10);
+    /// assert!(vec.spilled());
+    /// assert!(vec.capacity() >= 10);
+    ///
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        if capacity <= N {
+            Self::new()
+        } else {
+            Self::from_rvec(RVec::with_capacity(capacity))
+        }
+    }
+
+    /// Constructs an `RSmallVec` from an `RVec`, reusing its allocation.
+    ///
+    /// The returned `RSmallVec` is spilled even if the elements would fit inline,
+    /// call [`shrink_to_fit`](#method.shrink_to_fit) to move them inline.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RSmallVec, RVec};
+    ///
+    /// let vec = RSmallVec::<u32, 4>::from_rvec(RVec::from(vec![3, 5]));
+    ///
+    /// assert!(vec.spilled());
+    /// assert_eq!(vec.as_slice(), &[3, 5]);
+    ///
+    /// ```
+    pub const fn from_rvec(vec: RVec<T>) -> Self {
+        Self {
+            is_heap: true,
+            data: SmallVecData {
+                heap: ManuallyDrop::new(vec),
+            },
+        }
+    }
+
+    /// Constructs an `RSmallVec` that stores the elements of an `RArrayVec` inline.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RArrayVec, RSmallVec};
+    ///
+    /// let vec = RSmallVec::from_array_vec(RArrayVec::from([3, 5]));
+    ///
+    /// assert!(!vec.spilled());
+    /// assert_eq!(vec.as_slice(), &[3, 5]);
+    ///
+    /// ```
+    pub const fn from_array_vec(vec: RArrayVec<T, N>) -> Self {
+        Self {
+            is_heap: false,
+            data: SmallVecData {
+                inline: ManuallyDrop::new(vec),
+            },
+        }
+    }
+
+    #[inline]
+    fn inner_mut(&mut self) -> InnerMut<'_, T, N> {
+        unsafe {
+            if self.is_heap {
+                InnerMut::Heap(&mut self.data.heap)
+            } else {
+                InnerMut::Inline(&mut self.data.inline)
+            }
+        }
+    }
+
+    /// Returns whether the elements are stored on the heap.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RSmallVec;
+    ///
+    /// let mut vec = RSmallVec::<u32, 1>::new();
+    ///
+    /// vec.push(3);
+    /// assert!(!vec.spilled());
+    ///
+    /// vec.push(5);
+    /// assert!(vec.spilled());
+    ///
+    /// ```
+    #[inline]
+    pub const fn spilled(&self) -> bool {
+        self.is_heap
+    }
+
+    /// Returns the amount of elements in the vector.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RSmallVec;
+    ///
+    /// let vec = RSmallVec::<u32, 2>::from_iter([3, 5, 8]);
+    ///
+    /// assert_eq!(vec.len(), 3);
+    ///
+    /// ```
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.as_slice().len()
+    }
+
+    /// Returns whether the vector is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RSmallVec;
+    ///
+    /// let mut vec = RSmallVec::<u32, 2>::new();
+    /// assert!(vec.is_empty());
+    ///
+    /// vec.push(0);
+    /// assert!(!vec.is_empty());
+    ///
+    /// ```
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the amount of elements the vector can store without reallocating,
+    /// which is `N` while the elements are stored inline.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RSmallVec;
+    ///
+    /// let mut vec = RSmallVec::<u32, 2>::new();
+    /// assert_eq!(vec.capacity(), 2);
+    ///
+    /// vec.reserve(10);
+    /// assert!(vec.capacity() >= 10);
+    ///
+    /// ```
+    pub fn capacity(&self) -> usize {
+        unsafe {
+            if self.is_heap {
+                self.data.heap.capacity()
+            } else {
+                N
+            }
+        }
+    }
+
+    /// Creates a `&[T]` with access to all the elements of the vector.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RSmallVec;
+    ///
+    /// let vec = RSmallVec::<u32, 2>::from_iter([3, 5, 8]);
+    ///
+    /// assert_eq!(vec.as_slice(), &[3, 5, 8]);
+    ///
+    /// ```
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        unsafe {
+            if self.is_heap {
+                self.data.heap.as_slice()
+            } else {
+                self.data.inline.as_slice()
+            }
+        }
+    }
+
+    /// Creates a `&mut [T]` with access to all the elements of the vector.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RSmallVec;
+    ///
+    /// let mut vec = RSmallVec::<u32, 2>::from_iter([3, 5, 8]);
+    /// vec.as_mut_slice()[0] = 13;
+    ///
+    /// assert_eq!(vec.as_slice(), &[13, 5, 8]);
+    ///
+    /// ```
+    #[inline]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        with_inner!(self, |vec| vec.as_mut_slice())
+    }
+
+    /// Creates an `RSlice<'_, T>` with access to all the elements of the vector.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RSlice, RSmallVec};
+    ///
+    /// let vec = RSmallVec::<u32, 2>::from_iter([3, 5, 8]);
+    ///
+    /// assert_eq!(vec.as_rslice(), RSlice::from_slice(&[3, 5, 8]));
+    ///
+    /// ```
+    #[inline]
+    pub fn as_rslice(&self) -> RSlice<'_, T> {
+        self.as_slice().into()
+    }
+
+    /// Creates an `RSliceMut<'_, T>` with access to all the elements of the vector.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RSliceMut, RSmallVec};
+    ///
+    /// let mut vec = RSmallVec::<u32, 2>::from_iter([3, 5, 8]);
+    ///
+    /// assert_eq!(vec.as_mut_rslice(), RSliceMut::from_mut_slice(&mut [3, 5, 8]));
+    ///
+    /// ```
+    #[inline]
+    pub fn as_mut_rslice(&mut self) -> RSliceMut<'_, T> {
+        self.as_mut_slice().into()
+    }
+
+    /// Moves the inline elements to the heap, with capacity for `additional` more.
+    ///
+    /// Must only be called while the elements are stored inline.
+    fn spill(&mut self, additional: usize) {
+        debug_assert!(!self.is_heap);
+        // Allocating before taking the elements out,
+        // so that a panic while allocating doesn't cause a double drop.
+        let len = unsafe { self.data.inline.len() };
+        let mut vec = RVec::with_capacity(len.saturating_add(additional));
+        unsafe {
+            let inline = ManuallyDrop::take(&mut self.data.inline);
+            vec.extend(inline);
+        }
+        self.data = SmallVecData {
+            heap: ManuallyDrop::new(vec),
+        };
+        self.is_heap = true;
+    }
+
+    /// Reserves capacity for at least `additional` more elements,
+    /// moving the elements to the heap if they wouldn't fit inline.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RSmallVec;
+    ///
+    /// let mut vec = RSmallVec::<u32, 4>::from_iter([3, 5]);
+    ///
+    /// vec.reserve(2);
+    /// assert!(!vec.spilled());
+    ///
+    /// vec.reserve(3);
+    /// assert!(vec.spilled());
+    /// assert!(vec.capacity() >= 5);
+    ///
+    /// ```
+    pub fn reserve(&mut self, additional: usize) {
+        match self.inner_mut() {
+            InnerMut::Heap(vec) => vec.reserve(additional),
+            InnerMut::Inline(vec) if additional <= vec.remaining_capacity() => {}
+            InnerMut::Inline(_) => self.spill(additional),
+        }
+    }
+
+    /// Shrinks the capacity of the vector to match its length,
+    /// moving the elements back inline if they fit.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RSmallVec;
+    ///
+    /// let mut vec = RSmallVec::<u32, 2>::from_iter([3, 5, 8]);
+    /// assert!(vec.spilled());
+    ///
+    /// vec.truncate(2);
+    /// vec.shrink_to_fit();
+    /// assert!(!vec.spilled());
+    /// assert_eq!(vec.as_slice(), &[3, 5]);
+    ///
+    /// ```
+    pub fn shrink_to_fit(&mut self) {
+        if let InnerMut::Heap(vec) = self.inner_mut() {
+            if vec.len() > N {
+                vec.shrink_to_fit();
+                return;
+            }
+            unsafe {
+                let heap = ManuallyDrop::take(&mut self.data.heap);
+                // The vector has at most `N` elements, so this can't panic.
+                let inline = heap.into_iter().collect::<RArrayVec<T, N>>();
+                self.data = SmallVecData {
+                    inline: ManuallyDrop::new(inline),
+                };
+            }
+            self.is_heap = false;
+        }
+    }
+
+    /// Appends `value` at the end of the vector,
+    /// moving the elements to the heap if there's no space left inline.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RSmallVec;
+    ///
+    /// let mut vec = RSmallVec::<u32, 1>::new();
+    ///
+    /// vec.push(3);
+    /// vec.push(5);
+    ///
+    /// assert_eq!(vec.as_slice(), &[3, 5]);
+    ///
+    /// ```
+    pub fn push(&mut self, value: T) {
+        self.reserve(1);
+        with_inner!(self, |vec| vec.push(value))
+    }
+
+    /// Removes the last element of the vector, returning it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RSmallVec;
+    ///
+    /// let mut vec = RSmallVec::<u32, 1>::from_iter([3, 5]);
+    ///
+    /// assert_eq!(vec.pop(), Some(5));
+    /// assert_eq!(vec.pop(), Some(3));
+    /// assert_eq!(vec.pop(), None);
+    ///
+    /// ```
+    pub fn pop(&mut self) -> Option<T> {
+        with_inner!(self, |vec| vec.pop())
+    }
+
+    /// Inserts `value` at the `index` position,
+    /// shifting all the elements after it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > self.len()`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RSmallVec;
+    ///
+    /// let mut vec = RSmallVec::<u32, 2>::from_iter([3, 5]);
+    ///
+    /// vec.insert(1, 8);
+    /// vec.insert(0, 13);
+    ///
+    /// assert_eq!(vec.as_slice(), &[13, 3, 8, 5]);
+    ///
+    /// ```
+    pub fn insert(&mut self, index: usize, value: T) {
+        let len = self.len();
+        assert!(
+            index <= len,
+            "index out of bounds: the len is {} but the index is {}",
+            len,
+            index,
+        );
+        self.reserve(1);
+        with_inner!(self, |vec| vec.insert(index, value))
+    }
+
+    /// Removes the element at the `index` position,
+    /// shifting all the elements after it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index >= self.len()`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RSmallVec;
+    ///
+    /// let mut vec = RSmallVec::<u32, 2>::from_iter([3, 5, 8, 13]);
+    ///
+    /// assert_eq!(vec.remove(1), 5);
+    /// assert_eq!(vec.as_slice(), &[3, 8, 13]);
+    ///
+    /// ```
+    pub fn remove(&mut self, index: usize) -> T {
+        with_inner!(self, |vec| vec.remove(index))
+    }
+
+    /// Removes the element at the `index` position,
+    /// replacing it with the last element.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index >= self.len()`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RSmallVec;
+    ///
+    /// let mut vec = RSmallVec::<u32, 2>::from_iter([3, 5, 8, 13]);
+    ///
+    /// assert_eq!(vec.swap_remove(0), 3);
+    /// assert_eq!(vec.as_slice(), &[13, 5, 8]);
+    ///
+    /// ```
+    pub fn swap_remove(&mut self, index: usize) -> T {
+        with_inner!(self, |vec| vec.swap_remove(index))
+    }
+
+    /// Shortens the vector to `len` elements, dropping the rest.
+    ///
+    /// This doesn't move the elements back inline,
+    /// for that use [`shrink_to_fit`](#method.shrink_to_fit).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RSmallVec;
+    ///
+    /// let mut vec = RSmallVec::<u32, 2>::from_iter([3, 5, 8, 13]);
+    ///
+    /// vec.truncate(2);
+    /// assert_eq!(vec.as_slice(), &[3, 5]);
+    ///
+    /// ```
+    pub fn truncate(&mut self, len: usize) {
+        with_inner!(self, |vec| vec.truncate(len))
+    }
+
+    /// Removes all the elements of the vector.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RSmallVec;
+    ///
+    /// let mut vec = RSmallVec::<u32, 2>::from_iter([3, 5, 8, 13]);
+    ///
+    /// vec.clear();
+    /// assert!(vec.is_empty());
+    ///
+    /// ```
+    pub fn clear(&mut self) {
+        with_inner!(self, |vec| vec.clear())
+    }
+
+    /// Retains only the elements for which `pred` returns true.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RSmallVec;
+    ///
+    /// let mut vec = RSmallVec::<u32, 2>::from_iter([3, 5, 8, 13]);
+    ///
+    /// vec.retain(|x| x % 2 == 1);
+    /// assert_eq!(vec.as_slice(), &[3, 5, 13]);
+    ///
+    /// ```
+    pub fn retain<F>(&mut self, pred: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        with_inner!(self, |vec| vec.retain(pred))
+    }
+
+    /// Appends clones of all the elements of `slice` at the end of the vector.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RSmallVec;
+    ///
+    /// let mut vec = RSmallVec::<u32, 2>::new();
+    ///
+    /// vec.extend_from_slice(&[3, 5]);
+    /// vec.extend_from_slice(&[8]);
+    ///
+    /// assert_eq!(vec.as_slice(), &[3, 5, 8]);
+    ///
+    /// ```
+    pub fn extend_from_slice(&mut self, slice: &[T])
+    where
+        T: Clone,
+    {
+        self.reserve(slice.len());
+        with_inner!(self, |vec| vec.extend_from_slice(slice))
+    }
+
+    /// Converts this `RSmallVec` into an `RVec`,
+    /// reusing the allocation if the elements are on the heap.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RSmallVec, RVec};
+    ///
+    /// let vec = RSmallVec::<u32, 2>::from_iter([3, 5, 8]);
+    ///
+    /// assert_eq!(vec.into_rvec(), RVec::from(vec![3, 5, 8]));
+    ///
+    /// ```
+    pub fn into_rvec(self) -> RVec<T> {
+        let mut this = ManuallyDrop::new(self);
+        unsafe {
+            if this.is_heap {
+                ManuallyDrop::take(&mut this.data.heap)
+            } else {
+                ManuallyDrop::take(&mut this.data.inline).into_rvec()
+            }
+        }
+    }
+}
+
+impl<T, const N: usize> Drop for RSmallVec<T, N> {
+    fn drop(&mut self) {
+        unsafe {
+            if self.is_heap {
+                ManuallyDrop::drop(&mut self.data.heap)
+            } else {
+                ManuallyDrop::drop(&mut self.data.inline)
+            }
+        }
+    }
+}
+
+impl<T, const N: usize> Default for RSmallVec<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> Clone for RSmallVec<T, N>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T, const N: usize> Deref for RSmallVec<T, N> {
+    type Target = [T];
+
+    #[inline]
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T, const N: usize> DerefMut for RSmallVec<T, N> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<T, const N: usize> AsRef<[T]> for RSmallVec<T, N> {
+    fn as_ref(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T, const N: usize> AsMut<[T]> for RSmallVec<T, N> {
+    fn as_mut(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+impl<T, const N: usize> Borrow<[T]> for RSmallVec<T, N> {
+    fn borrow(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T, const N: usize> BorrowMut<[T]> for RSmallVec<T, N> {
+    fn borrow_mut(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+impl<T, const N: usize> Debug for RSmallVec<T, N>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt(self.as_slice(), f)
+    }
+}
+
+impl<T, const N: usize> Eq for RSmallVec<T, N> where T: Eq {}
+
+impl<T, const N: usize> PartialEq for RSmallVec<T, N>
+where
+    T: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<T, const N: usize> Ord for RSmallVec<T, N>
+where
+    T: Ord,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_slice().cmp(other.as_slice())
+    }
+}
+
+impl<T, const N: usize> PartialOrd for RSmallVec<T, N>
+where
+    T: PartialOrd,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.as_slice().partial_cmp(other.as_slice())
+    }
+}
+
+impl<T, const N: usize> Hash for RSmallVec<T, N>
+where
+    T: Hash,
+{
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        self.as_slice().hash(state)
+    }
+}
+
+slice_like_impl_cmp_traits! {
+    impl[const N: usize] RSmallVec<T, N>,
+    where[];
+    Vec<U>,
+    [U],
+    &[U],
+    &mut [U],
+    RVec<U>,
+    RSlice<'_, U>,
+    RSliceMut<'_, U>,
+}
+
+slice_like_impl_cmp_traits! {
+    impl[const N: usize, const M: usize] RSmallVec<T, N>,
+    where[];
+    [U; M],
+    RArrayVec<U, M>,
+}
+
+impl<T, const N: usize> From<RVec<T>> for RSmallVec<T, N> {
+    fn from(vec: RVec<T>) -> Self {
+        Self::from_rvec(vec)
+    }
+}
+
+impl<T, const N: usize> From<Vec<T>> for RSmallVec<T, N> {
+    fn from(vec: Vec<T>) -> Self {
+        Self::from_rvec(vec.into())
+    }
+}
+
+impl<T, const N: usize> From<RArrayVec<T, N>> for RSmallVec<T, N> {
+    fn from(vec: RArrayVec<T, N>) -> Self {
+        Self::from_array_vec(vec)
+    }
+}
+
+impl<T, const N: usize> From<[T; N]> for RSmallVec<T, N> {
+    fn from(array: [T; N]) -> Self {
+        Self::from_array_vec(array.into())
+    }
+}
+
+impl<T, const N: usize> From<&[T]> for RSmallVec<T, N>
+where
+    T: Clone,
+{
+    fn from(slice: &[T]) -> Self {
+        let mut this = Self::new();
+        this.extend_from_slice(slice);
+        this
+    }
+}
+
+impl<T, const N: usize> From<RSmallVec<T, N>> for RVec<T> {
+    fn from(this: RSmallVec<T, N>) -> RVec<T> {
+        this.into_rvec()
+    }
+}
+
+impl<T, const N: usize> From<RSmallVec<T, N>> for Vec<T> {
+    fn from(this: RSmallVec<T, N>) -> Vec<T> {
+        this.into_rvec().into_vec()
+    }
+}
+
+impl<T, const N: usize> FromIterator<T> for RSmallVec<T, N> {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut this = Self::new();
+        this.extend(iter);
+        this
+    }
+}
+
+impl<T, const N: usize> Extend<T> for RSmallVec<T, N> {
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        iter.for_each(|x| self.push(x));
+    }
+}
+
+impl<'a, T, const N: usize> Extend<&'a T> for RSmallVec<T, N>
+where
+    T: 'a + Copy,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = &'a T>,
+    {
+        self.extend(iter.into_iter().copied())
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a RSmallVec<T, N> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a mut RSmallVec<T, N> {
+    type Item = &'a mut T;
+    type IntoIter = slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T, const N: usize> IntoIterator for RSmallVec<T, N> {
+    type Item = T;
+    type IntoIter = IntoIter<T, N>;
+
+    fn into_iter(self) -> IntoIter<T, N> {
+        let mut this = ManuallyDrop::new(self);
+        let inner = unsafe {
+            if this.is_heap {
+                IntoIterInner::Heap(ManuallyDrop::take(&mut this.data.heap).into_iter())
+            } else {
+                IntoIterInner::Inline(ptr::read(&*this.data.inline).into_iter())
+            }
+        };
+        IntoIter { inner }
+    }
+}
+
+impl<T, const N: usize> Serialize for RSmallVec<T, N>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.as_slice().serialize(serializer)
+    }
+}
+
+impl<'de, T, const N: usize> Deserialize<'de> for RSmallVec<T, N>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let vec = Vec::<T>::deserialize(deserializer)?;
+        if vec.len() <= N {
+            Ok(vec.into_iter().collect())
+        } else {
+            Ok(Self::from(vec))
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// An iterator that yields the elements of an [`RSmallVec`] by value.
+pub struct IntoIter<T, const N: usize> {
+    inner: IntoIterInner<T, N>,
+}
+
+enum IntoIterInner<T, const N: usize> {
+    Inline(array_vec::IntoIter<T, N>),
+    Heap(vec::IntoIter<T>),
+}
+
+macro_rules! with_iter {
+    ($this:expr, |$iter:ident| $expr:expr) => {
+        match $this {
+            IntoIterInner::Inline($iter) => $expr,
+            IntoIterInner::Heap($iter) => $expr,
+        }
+    };
+}
+
+impl<T, const N: usize> IntoIter<T, N> {
+    /// Returns a slice over the elements that haven't been yielded yet.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RSmallVec;
+    ///
+    /// let mut iter = RSmallVec::<u32, 2>::from_iter([0, 1, 2, 3]).into_iter();
+    ///
+    /// assert_eq!(iter.next(), Some(0));
+    /// assert_eq!(iter.next_back(), Some(3));
+    /// assert_eq!(iter.as_slice(), &[1, 2]);
+    ///
+    /// ```
+    pub fn as_slice(&self) -> &[T] {
+        with_iter!(&self.inner, |iter| iter.as_slice())
+    }
+
+    /// Returns a mutable slice over the elements that haven't been yielded yet.
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        with_iter!(&mut self.inner, |iter| iter.as_mut_slice())
+    }
+}
+
+impl<T, const N: usize> Iterator for IntoIter<T, N> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        with_iter!(&mut self.inner, |iter| iter.next())
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        with_iter!(&self.inner, |iter| iter.size_hint())
+    }
+}
+
+impl<T, const N: usize> DoubleEndedIterator for IntoIter<T, N> {
+    #[inline]
+    fn next_back(&mut self) -> Option<T> {
+        with_iter!(&mut self.inner, |iter| iter.next_back())
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for IntoIter<T, N> {}
+
+impl<T, const N: usize> FusedIterator for IntoIter<T, N> {}
+
+impl<T, const N: usize> Debug for IntoIter<T, N>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("IntoIter").field(&self.as_slice()).finish()
+    }
+}
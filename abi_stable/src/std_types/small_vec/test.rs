@@ -0,0 +1,94 @@
+use super::*;
+
+use std::rc::Rc;
+
+use crate::std_types::RString;
+
+#[test]
+fn spills_and_shrinks() {
+    let mut vec = RSmallVec::<RString, 2>::new();
+    vec.push("foo".into());
+    vec.push("bar".into());
+    assert!(!vec.spilled());
+    assert_eq!(vec.capacity(), 2);
+
+    vec.insert(1, "baz".into());
+    assert!(vec.spilled());
+    assert_eq!(vec, ["foo", "baz", "bar"]);
+
+    assert_eq!(vec.remove(0), "foo");
+    vec.shrink_to_fit();
+    assert!(!vec.spilled());
+    assert_eq!(vec, ["baz", "bar"]);
+
+    vec.extend_from_slice(&["qux".into(), "quux".into()]);
+    assert!(vec.spilled());
+    vec.retain(|x| x.starts_with('q'));
+    assert_eq!(vec, ["qux", "quux"]);
+}
+
+#[test]
+fn rvec_conversions() {
+    let rvec = RVec::from(vec![3, 5, 8]);
+    let addr = rvec.as_ptr();
+
+    let vec = RSmallVec::<u32, 4>::from(rvec);
+    assert!(vec.spilled());
+    assert_eq!(vec.as_ptr(), addr);
+
+    let rvec = vec.into_rvec();
+    assert_eq!(rvec.as_ptr(), addr);
+
+    let vec = RSmallVec::from(RArrayVec::from([3, 5]));
+    assert!(!vec.spilled());
+    assert_eq!(vec.clone().into_rvec(), RVec::from(vec![3, 5]));
+    assert_eq!(Vec::from(vec), vec![3, 5]);
+}
+
+#[test]
+fn drops_elements() {
+    let count = Rc::new(());
+    for len in [1, 3, 5] {
+        let vec = (0..len).map(|_| count.clone()).collect::<RSmallVec<_, 3>>();
+        assert_eq!(vec.spilled(), len > 3);
+
+        let cloned = vec.clone();
+        assert_eq!(Rc::strong_count(&count), 2 * len + 1);
+        drop(cloned);
+
+        let mut iter = vec.into_iter();
+        iter.next();
+        assert_eq!(Rc::strong_count(&count), len);
+        drop(iter);
+        assert_eq!(Rc::strong_count(&count), 1);
+    }
+}
+
+#[test]
+fn serde_roundtrip() {
+    let vec = RSmallVec::<u32, 2>::from_iter([3, 5, 8]);
+    let json = serde_json::to_string(&vec).unwrap();
+    assert_eq!(json, "[3,5,8]");
+
+    let deserialized: RSmallVec<u32, 2> = serde_json::from_str(&json).unwrap();
+    assert!(deserialized.spilled());
+    assert_eq!(deserialized, [3, 5, 8]);
+
+    let deserialized: RSmallVec<u32, 4> = serde_json::from_str(&json).unwrap();
+    assert!(!deserialized.spilled());
+    assert_eq!(deserialized, vec.as_slice());
+}
+
+#[test]
+fn layout_depends_on_capacity() {
+    use crate::{abi_stability::abi_checking::check_layout_compatibility, StableAbi};
+
+    let small2 = <RSmallVec<u32, 2> as StableAbi>::LAYOUT;
+    let small3 = <RSmallVec<u32, 3> as StableAbi>::LAYOUT;
+    let array2 = <RArrayVec<u32, 2> as StableAbi>::LAYOUT;
+
+    assert!(check_layout_compatibility(small2, small2).is_ok());
+    assert!(check_layout_compatibility(small2, small3).is_err());
+    assert!(check_layout_compatibility(array2, array2).is_ok());
+    assert!(check_layout_compatibility(small2, array2).is_err());
+}
@@ -21,7 +21,7 @@ use crate::{
     sabi_types::RRef,
     std_types::{
         utypeid::{new_utypeid, UTypeId},
-        RBox, ROption, RResult, RStr, RString,
+        RBox, RNone, ROption, RResult, RSome, RStr, RString,
     },
     utils::transmute_reference,
 };
@@ -180,6 +180,43 @@ mod test;
 ///
 /// ```
 ///
+/// ### Source chains
+///
+/// The sources of the error (as returned by `std::error::Error::source`)
+/// are carried as `UnsyncRBoxError`s,
+/// each of which can be downcasted to the type of that source.
+///
+/// ```
+/// use std::{error::Error, fmt, num::ParseIntError};
+///
+/// use abi_stable::std_types::RBoxError;
+///
+/// #[derive(Debug)]
+/// struct ConfigError(ParseIntError);
+///
+/// impl fmt::Display for ConfigError {
+///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+///         f.write_str("invalid config")
+///     }
+/// }
+///
+/// impl Error for ConfigError {
+///     fn source(&self) -> Option<&(dyn Error + 'static)> {
+///         Some(&self.0)
+///     }
+/// }
+///
+/// let parse_err = "".parse::<u32>().unwrap_err();
+/// let err = RBoxError::new(ConfigError(parse_err.clone()));
+///
+/// assert_eq!(err.source().unwrap().to_string(), parse_err.to_string());
+///
+/// let sources = err.sources().collect::<Vec<_>>();
+/// assert_eq!(sources.len(), 1);
+/// assert_eq!(sources[0].downcast_ref::<ParseIntError>(), Some(&parse_err));
+///
+/// ```
+///
 ///
 ///
 #[repr(C)]
@@ -282,11 +319,7 @@ impl<M> RBoxError_<M> {
     where
         T: Display + Debug + ?Sized,
     {
-        DebugDisplay {
-            debug: format!("{:#?}", value),
-            display: format!("{:#}", value),
-        }
-        .piped(Self::from_debug_display)
+        Self::from_debug_display(DebugDisplay::new(value), ErrorContext::EMPTY)
     }
 
     /// Constructs an RBoxError from a type that only implements Debug,
@@ -309,35 +342,159 @@ impl<M> RBoxError_<M> {
     where
         T: Debug + ?Sized,
     {
-        DebugDisplay {
+        let value = DebugDisplay {
             debug: format!("{:#?}", value),
             display: format!("{:#?}", value),
-        }
-        .piped(Self::from_debug_display)
+        };
+        Self::from_debug_display(value, ErrorContext::EMPTY)
     }
 
-    fn from_debug_display(value: DebugDisplay) -> Self {
-        unsafe { Self::new_with_vtable(value, MakeRErrorVTable::LIB_VTABLE_DEBUG_DISPLAY) }
+    fn from_debug_display(value: DebugDisplay, context: ErrorContext) -> Self {
+        unsafe {
+            Self::new_with_vtable(value, MakeRErrorVTable::LIB_VTABLE_DEBUG_DISPLAY, |_| {
+                context
+            })
+        }
     }
 
     fn new_inner<T>(value: T) -> Self
     where
         T: ErrorTrait + 'static,
     {
-        unsafe { Self::new_with_vtable(value, MakeRErrorVTable::<T>::LIB_VTABLE) }
+        unsafe {
+            Self::new_with_vtable(value, MakeRErrorVTable::<T>::LIB_VTABLE, |value| {
+                ErrorContext::capture(value as *const (), get_error_root::<T>)
+            })
+        }
     }
 
-    unsafe fn new_with_vtable<T>(value: T, vtable: RErrorVTable_Ref) -> Self {
-        let value = value
-            .piped(RBox::new)
-            .piped(|x| unsafe { mem::transmute::<RBox<T>, RBox<ErasedObject>>(x) });
+    /// # Safety
+    ///
+    /// `vtable` must have been constructed for `T`,
+    /// with `context_impl::<T>` as its `context` function.
+    ///
+    /// `make_context` is passed a pointer to the boxed `T`.
+    unsafe fn new_with_vtable<T, F>(value: T, vtable: RErrorVTable_Ref, make_context: F) -> Self
+    where
+        F: FnOnce(*const T) -> ErrorContext,
+    {
+        let mut value = RBox::new(WithContext {
+            value,
+            context: ErrorContext::EMPTY,
+        });
+        // The value is boxed before getting its context,
+        // because the source chain points into the box.
+        //
+        // The pointer is copied from the `RBox` instead of being derived from a reference,
+        // so that mutable borrows of the value (eg: through `downcast_mut`)
+        // don't invalidate it.
+        value.context = make_context(value.data() as *const T);
 
         Self {
-            value,
+            value: unsafe { mem::transmute::<RBox<WithContext<T>>, RBox<ErasedObject>>(value) },
             vtable,
             _sync_send: PhantomData,
         }
     }
+
+    /// Unwraps the value this was constructed with.
+    ///
+    /// # Safety
+    ///
+    /// This must have been constructed with a `T` in the current dynamic library.
+    unsafe fn into_value<T>(self) -> T {
+        if self.vtable.context().is_some() {
+            let value =
+                unsafe { mem::transmute::<RBox<ErasedObject>, RBox<WithContext<T>>>(self.value) };
+            RBox::into_inner(value).value
+        } else {
+            let value = unsafe { mem::transmute::<RBox<ErasedObject>, RBox<T>>(self.value) };
+            RBox::into_inner(value)
+        }
+    }
+}
+
+impl<M> RBoxError_<M> {
+    /// Returns an iterator over the sources of this error,
+    /// starting with the one returned by `std::error::Error::source`.
+    ///
+    /// Each source can be downcasted to its original type
+    /// (if it was constructed in the current dynamic library).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::{error::Error, fmt};
+    ///
+    /// use abi_stable::std_types::RBoxError;
+    ///
+    /// #[derive(Debug)]
+    /// struct Chained(&'static str, Option<Box<Chained>>);
+    ///
+    /// impl fmt::Display for Chained {
+    ///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    ///         f.write_str(self.0)
+    ///     }
+    /// }
+    ///
+    /// impl Error for Chained {
+    ///     fn source(&self) -> Option<&(dyn Error + 'static)> {
+    ///         self.1.as_ref().map(|x| &**x as _)
+    ///     }
+    /// }
+    ///
+    /// let err = Chained("foo", Some(Box::new(Chained("bar", Some(Box::new(Chained("baz", None)))))));
+    ///
+    /// let err = RBoxError::new(err);
+    ///
+    /// let sources = err.sources().map(|x| x.to_string()).collect::<Vec<_>>();
+    /// assert_eq!(sources, ["bar", "baz"]);
+    ///
+    /// let last = err.sources().last().unwrap();
+    /// assert_eq!(last.downcast_ref::<Chained>().unwrap().0, "baz");
+    ///
+    /// ```
+    pub fn sources(&self) -> impl Iterator<Item = &UnsyncRBoxError> + '_ {
+        std::iter::successors(self.source_error(), |e| e.source_error())
+    }
+
+    /// Returns the backtrace captured when this error was constructed, if any.
+    ///
+    /// A backtrace is only captured by the `new` and `from_box` constructors,
+    /// when the "rust_1_65" feature is enabled,
+    /// and the `RUST_BACKTRACE`/`RUST_LIB_BACKTRACE` environment variables enable them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RBoxError;
+    ///
+    /// let err = RBoxError::from_fmt("hello");
+    ///
+    /// assert_eq!(err.backtrace(), None);
+    ///
+    /// ```
+    pub fn backtrace(&self) -> Option<RStr<'_>> {
+        self.context()?
+            .backtrace
+            .as_ref()
+            .map(|x| x.as_rstr())
+            .into_option()
+    }
+
+    fn source_error(&self) -> Option<&UnsyncRBoxError> {
+        let source = self.context()?.source.as_ref().into_option()?;
+        // The source chain is shorter if the root error was mutated through `downcast_mut`
+        match source.as_source_link() {
+            Some(link) if link.get().is_none() => None,
+            _ => Some(source),
+        }
+    }
+
+    fn context(&self) -> Option<&ErrorContext> {
+        let context = self.vtable.context()?;
+        unsafe { Some(context(self.value.as_rref())) }
+    }
 }
 
 impl<M> RBoxError_<M> {
@@ -349,15 +506,23 @@ impl<M> RBoxError_<M> {
     // This isn't strictly required anymore because abi_stable doesn't
     // unload libraries right now.
     ///
+    /// The source chain and backtrace of this error are preserved,
+    /// with each source converted to a formatted error.
+    ///
     pub fn to_formatted_error<N>(&self) -> RBoxError_<N> {
-        if let Some(dd) = self.as_debug_display() {
-            RBoxError_::from_debug_display(DebugDisplay {
+        let value = if let Some(dd) = self.as_debug_display() {
+            DebugDisplay {
                 debug: dd.debug.into(),
                 display: dd.display.into(),
-            })
+            }
         } else {
-            RBoxError_::from_fmt(self)
-        }
+            DebugDisplay::new(self)
+        };
+        let context = ErrorContext {
+            source: self.source_error().map(|e| e.to_formatted_error()).into(),
+            backtrace: self.backtrace().map(RString::from).into(),
+        };
+        RBoxError_::from_debug_display(value, context)
     }
 
     fn as_debug_display(&self) -> Option<DebugDisplayRef<'_>> {
@@ -463,7 +628,11 @@ impl RBoxError_<SyncSend> {
     }
 }
 
-impl<M> ErrorTrait for RBoxError_<M> {}
+impl<M> ErrorTrait for RBoxError_<M> {
+    fn source(&self) -> Option<&(dyn ErrorTrait + 'static)> {
+        self.source_error().map(|e| e as _)
+    }
+}
 
 impl<M> Display for RBoxError_<M> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -521,9 +690,15 @@ macro_rules! from_impls {
                 match this.downcast::<Self>() {
                     Ok(e) => *e,
                     Err(e) => unsafe {
-                        Self::new_with_vtable::<$boxdyn>(
+                        Self::new_with_vtable::<$boxdyn, _>(
                             e,
                             MakeBoxedRErrorVTable::<$boxdyn>::LIB_VTABLE,
+                            |e| {
+                                ErrorContext::capture(e as *const (), |e| {
+                                    let e: &(dyn ErrorTrait + 'static) = &**(e as *const $boxdyn);
+                                    e
+                                })
+                            },
                         )
                     },
                 }
@@ -547,10 +722,7 @@ macro_rules! from_impls {
             /// [here is the example](#from_to_conversion).
             pub fn into_box(self) -> $boxdyn {
                 if self.is_type::<$boxdyn>() {
-                    unsafe {
-                        let box_ = mem::transmute::<RBox<ErasedObject>, RBox<$boxdyn>>(self.value);
-                        RBox::into_inner(box_)
-                    }
+                    unsafe { self.into_value::<$boxdyn>() }
                 } else {
                     Box::new(self)
                 }
@@ -581,12 +753,9 @@ macro_rules! from_impls {
                 T: ErrorTrait + 'static,
             {
                 match (self.is_type::<T>(), self.is_type::<$boxdyn>()) {
-                    (true, _) => unsafe {
-                        Ok(mem::transmute::<RBox<ErasedObject>, RBox<T>>(self.value))
-                    },
+                    (true, _) => unsafe { Ok(RBox::new(self.into_value::<T>())) },
                     (false, true) if self.downcast_ref::<T>().is_some() => unsafe {
-                        let x = mem::transmute::<RBox<ErasedObject>, RBox<$boxdyn>>(self.value);
-                        let x = RBox::into_inner(x);
+                        let x = self.into_value::<$boxdyn>();
                         Ok(RBox::from_box(x.downcast::<T>().unwrap()))
                     },
                     (false, _) => Err(self),
@@ -600,6 +769,9 @@ macro_rules! from_impls {
             /// it'll first downcast to `&dyn Error + ... `,
             /// then it'll downcast the `&dyn Error + ... ` into `&T`.
             ///
+            /// If this is one of the [`sources`](#method.sources) of another `RBoxError_`,
+            /// this downcasts the source error.
+            ///
             /// # Errors
             ///
             /// This returns `None` in any of these cases:
@@ -623,7 +795,7 @@ macro_rules! from_impls {
                         let ref_box = &*(self.value.as_ptr() as *const $boxdyn);
                         (&**ref_box).downcast_ref::<T>()
                     },
-                    (false, false) => None,
+                    (false, false) => self.as_source_link()?.get()?.downcast_ref::<T>(),
                 }
             }
 
@@ -702,6 +874,9 @@ struct RErrorVTable {
 
     #[sabi(last_prefix_field)]
     type_id: extern "C" fn() -> UTypeId,
+
+    /// This is `None` for errors constructed by older versions of abi_stable.
+    context: unsafe extern "C" fn(RRef<'_, ErasedObject>) -> &'_ ErrorContext,
 }
 
 ///////////////////
@@ -717,6 +892,7 @@ where
         display: display_impl::<T>,
         as_debug_display: not_as_debug_display,
         type_id: new_utypeid::<T>,
+        context: context_impl::<T>,
     };
 
     const VALUE_MD: &'static WithMetadata<RErrorVTable> = &WithMetadata::new(Self::VALUE);
@@ -731,6 +907,7 @@ impl MakeRErrorVTable<DebugDisplay> {
             display: display_impl::<DebugDisplay>,
             as_debug_display,
             type_id: new_utypeid::<DebugDisplay>,
+            context: context_impl::<DebugDisplay>,
         })
     };
 
@@ -751,6 +928,7 @@ where
         display: display_impl::<Box<T>>,
         as_debug_display: not_as_debug_display,
         type_id: new_utypeid::<Box<T>>,
+        context: context_impl::<Box<T>>,
     };
 
     const WM_VTABLE: &'static WithMetadata<RErrorVTable> = &WithMetadata::new(Self::VALUE);
@@ -760,6 +938,159 @@ where
 
 ////////////////////////////////////////////////////////////////////////
 
+/// The value that `RBoxError_` erases.
+#[repr(C)]
+struct WithContext<T> {
+    // This must be the first field,
+    // so that a pointer to `WithContext<T>` can be used as a pointer to `T`.
+    value: T,
+    context: ErrorContext,
+}
+
+#[repr(C)]
+#[derive(StableAbi)]
+struct ErrorContext {
+    source: ROption<UnsyncRBoxError>,
+    backtrace: ROption<RString>,
+}
+
+impl ErrorContext {
+    const EMPTY: Self = Self {
+        source: RNone,
+        backtrace: RNone,
+    };
+
+    /// Gets the source chain of the error that `get_root(root)` points to,
+    /// and a backtrace if they're enabled.
+    ///
+    /// # Safety
+    ///
+    /// `root` must point to a value in the heap allocation that will own the returned value,
+    /// and `get_root` must be safe to call with it for as long as that allocation lives.
+    unsafe fn capture(root: *const (), get_root: GetErrorRoot) -> Self {
+        Self {
+            source: unsafe { SourceLink::chain(root, get_root) },
+            backtrace: capture_backtrace(),
+        }
+    }
+}
+
+/// Gets a pointer to the error that `root` points to (or owns).
+///
+/// This is called every time that a `SourceLink` is accessed,
+/// so that references to the error are only created while the source is borrowed.
+type GetErrorRoot = unsafe fn(root: *const ()) -> *const (dyn ErrorTrait + 'static);
+
+unsafe fn get_error_root<T>(root: *const ()) -> *const (dyn ErrorTrait + 'static)
+where
+    T: ErrorTrait + 'static,
+{
+    root as *const T
+}
+
+#[cfg(feature = "rust_1_65")]
+fn capture_backtrace() -> ROption<RString> {
+    use std::backtrace::{Backtrace, BacktraceStatus};
+
+    let backtrace = Backtrace::capture();
+    if backtrace.status() == BacktraceStatus::Captured {
+        RSome(backtrace.to_string().into())
+    } else {
+        RNone
+    }
+}
+
+#[cfg(not(feature = "rust_1_65"))]
+fn capture_backtrace() -> ROption<RString> {
+    RNone
+}
+
+unsafe extern "C" fn context_impl<T: 'static>(this: RRef<'_, ErasedObject>) -> &'_ ErrorContext {
+    let this = unsafe { this.transmute_into_ref::<WithContext<T>>() };
+    &this.context
+}
+
+////////////////////////////////////////////////////////////////////////
+
+/// A source in the source chain of an error, erased in an `UnsyncRBoxError`.
+///
+/// This gets the source by walking the chain from the root error on every access,
+/// so that mutating the root error (through `downcast_mut`) can't leave it dangling.
+struct SourceLink {
+    /// Points to the value stored in the `RBoxError_` that (transitively) owns this.
+    root: *const (),
+    get_root: GetErrorRoot,
+    depth: usize,
+}
+
+impl SourceLink {
+    /// # Safety
+    ///
+    /// This has the same requirements as `ErrorContext::capture`.
+    unsafe fn chain(root: *const (), get_root: GetErrorRoot) -> ROption<UnsyncRBoxError> {
+        let error = unsafe { &*get_root(root) };
+        let depth = std::iter::successors(error.source(), |&e| e.source()).count();
+
+        (1..=depth).rev().fold(RNone, |source, depth| {
+            let link = SourceLink {
+                root,
+                get_root,
+                depth,
+            };
+            let error = unsafe {
+                UnsyncRBoxError::new_with_vtable(
+                    link,
+                    MakeRErrorVTable::<SourceLink>::LIB_VTABLE,
+                    |_| ErrorContext {
+                        source,
+                        backtrace: RNone,
+                    },
+                )
+            };
+            RSome(error)
+        })
+    }
+
+    fn get(&self) -> Option<&(dyn ErrorTrait + 'static)> {
+        // SAFETY: the root error outlives this,
+        // and it can only be mutated when this isn't borrowed.
+        let root = unsafe { &*(self.get_root)(self.root) };
+        (0..self.depth).try_fold(root, |e, _| e.source())
+    }
+}
+
+impl Display for SourceLink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.get() {
+            Some(e) => Display::fmt(e, f),
+            None => f.write_str("<unavailable source error>"),
+        }
+    }
+}
+
+impl Debug for SourceLink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.get() {
+            Some(e) => Debug::fmt(e, f),
+            None => f.write_str("<unavailable source error>"),
+        }
+    }
+}
+
+impl ErrorTrait for SourceLink {}
+
+impl<M> RBoxError_<M> {
+    fn as_source_link(&self) -> Option<&SourceLink> {
+        if self.is_type::<SourceLink>() {
+            unsafe { Some(&*(self.value.as_ptr() as *const SourceLink)) }
+        } else {
+            None
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////
+
 #[repr(C)]
 #[derive(Clone)]
 struct DebugDisplay {
@@ -767,6 +1098,18 @@ struct DebugDisplay {
     display: String,
 }
 
+impl DebugDisplay {
+    fn new<T>(value: &T) -> Self
+    where
+        T: Display + Debug + ?Sized,
+    {
+        Self {
+            debug: format!("{:#?}", value),
+            display: format!("{:#}", value),
+        }
+    }
+}
+
 impl Display for DebugDisplay {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         Display::fmt(&self.display, f)
@@ -177,3 +177,109 @@ fn from_fmt_or_debug() {
         assert_eq!(format!("{:#?}", str_err), format!("{}", rerr));
     }
 }
+
+///////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug)]
+struct Chained {
+    msg: &'static str,
+    source: Option<Box<Chained>>,
+}
+
+impl Chained {
+    fn new(msgs: &[&'static str]) -> Self {
+        let (msg, rest) = msgs.split_first().unwrap();
+        Chained {
+            msg,
+            source: (!rest.is_empty()).then(|| Box::new(Chained::new(rest))),
+        }
+    }
+}
+
+impl Display for Chained {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.msg)
+    }
+}
+
+impl ErrorTrait for Chained {
+    fn source(&self) -> Option<&(dyn ErrorTrait + 'static)> {
+        self.source.as_ref().map(|x| &**x as _)
+    }
+}
+
+fn source_messages(err: &(dyn ErrorTrait + 'static)) -> Vec<String> {
+    std::iter::successors(err.source(), |&e| e.source())
+        .map(|e| e.to_string())
+        .collect()
+}
+
+#[test]
+fn source_chain() {
+    let chain = || Chained::new(&["foo", "bar", "baz"]);
+
+    let from_new = RBoxError::new(chain());
+    let from_box = RBoxError::from_box(Box::new(chain()));
+
+    for err in [&from_new, &from_box] {
+        assert_eq!(source_messages(err), ["bar", "baz"]);
+
+        let sources = err.sources().collect::<Vec<_>>();
+        assert_eq!(sources.len(), 2);
+        assert_eq!(sources[0].downcast_ref::<Chained>().unwrap().msg, "bar");
+        assert_eq!(sources[1].downcast_ref::<Chained>().unwrap().msg, "baz");
+        assert!(sources[1].downcast_ref::<Stringy>().is_none());
+        assert_eq!(
+            format!("{:?}", sources[1]),
+            format!("{:?}", Chained::new(&["baz"]))
+        );
+    }
+
+    let unchained = RBoxError::new(Stringy::new("hello"));
+    assert!(unchained.source().is_none());
+    assert_eq!(unchained.sources().count(), 0);
+}
+
+#[test]
+fn source_chain_after_downcast() {
+    let mut err = RBoxError::new(Chained::new(&["foo", "bar", "baz"]));
+
+    err.downcast_mut::<Chained>().unwrap().source = None;
+    assert_eq!(source_messages(&err), Vec::<String>::new());
+
+    let value = err.downcast::<Chained>().unwrap();
+    assert_eq!(value.msg, "foo");
+    assert!(value.source.is_none());
+
+    let err = RBoxError::from_box(Box::new(Chained::new(&["foo", "bar"])));
+    let boxed = err.into_box();
+    assert_eq!(source_messages(&*boxed), ["bar"]);
+    assert_eq!(source_messages(&RBoxError::from_box(boxed)), ["bar"]);
+}
+
+#[test]
+fn source_chain_after_boxed_downcast() {
+    let boxed: Box<dyn ErrorTrait + Send + Sync> = Box::new(Chained::new(&["foo", "bar", "baz"]));
+    let mut err = RBoxError::from_box(boxed);
+    assert_eq!(source_messages(&err), ["bar", "baz"]);
+
+    let chained = err.downcast_mut::<Chained>().unwrap();
+    chained.source.as_mut().unwrap().source = None;
+    assert_eq!(source_messages(&err), ["bar"]);
+
+    err.downcast_mut::<Chained>().unwrap().source = None;
+    assert_eq!(source_messages(&err), Vec::<String>::new());
+}
+
+#[test]
+fn formatted_source_chain() {
+    let err = RBoxError::new(Chained::new(&["foo", "bar", "baz"]));
+    let formatted: UnsyncRBoxError = err.to_formatted_error();
+    drop(err);
+
+    assert_eq!(formatted.to_string(), "foo");
+    assert_eq!(source_messages(&formatted), ["bar", "baz"]);
+    assert!(formatted
+        .sources()
+        .all(|e| e.downcast_ref::<Chained>().is_none()));
+}
@@ -0,0 +1,448 @@
+//! Contains ffi-safe equivalents of `std::task::{Poll, Waker}`.
+
+use std::{
+    fmt::{self, Debug},
+    mem::ManuallyDrop,
+    task::{Context, Poll, RawWaker, RawWakerVTable, Waker},
+};
+
+use crate::{marker_type::ErasedObject, prefix_type::WithMetadata};
+
+#[cfg(all(test, not(feature = "only_new_tests")))]
+mod tests;
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// Ffi-safe equivalent of `std::task::Poll`.
+///
+/// # Example
+///
+/// ```
+/// use abi_stable::std_types::RPoll;
+///
+/// use std::task::Poll;
+///
+/// assert_eq!(RPoll::from(Poll::Ready(3)), RPoll::Ready(3));
+/// assert_eq!(RPoll::<u32>::Pending.into_poll(), Poll::Pending);
+///
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Ord, PartialOrd, Hash)]
+#[repr(u8)]
+#[derive(StableAbi)]
+pub enum RPoll<T> {
+    /// The value is ready.
+    Ready(T),
+    /// The value is not ready yet.
+    Pending,
+}
+
+#[allow(clippy::missing_const_for_fn)]
+impl<T> RPoll<T> {
+    /// Whether this is an `RPoll::Ready`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RPoll;
+    ///
+    /// assert_eq!(RPoll::Ready(8).is_ready(), true);
+    /// assert_eq!(RPoll::<u32>::Pending.is_ready(), false);
+    ///
+    /// ```
+    #[inline]
+    pub const fn is_ready(&self) -> bool {
+        matches!(self, RPoll::Ready { .. })
+    }
+
+    /// Whether this is an `RPoll::Pending`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RPoll;
+    ///
+    /// assert_eq!(RPoll::Ready(8).is_pending(), false);
+    /// assert_eq!(RPoll::<u32>::Pending.is_pending(), true);
+    ///
+    /// ```
+    #[inline]
+    pub const fn is_pending(&self) -> bool {
+        matches!(self, RPoll::Pending)
+    }
+
+    /// Maps the ready value with `f`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RPoll;
+    ///
+    /// assert_eq!(RPoll::Ready(8).map(|x| x * 2), RPoll::Ready(16));
+    /// assert_eq!(RPoll::<u32>::Pending.map(|x| x * 2), RPoll::Pending);
+    ///
+    /// ```
+    #[inline]
+    pub fn map<F, U>(self, f: F) -> RPoll<U>
+    where
+        F: FnOnce(T) -> U,
+    {
+        match self {
+            RPoll::Ready(x) => RPoll::Ready(f(x)),
+            RPoll::Pending => RPoll::Pending,
+        }
+    }
+
+    /// Converts this into a `std::task::Poll`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RPoll;
+    ///
+    /// use std::task::Poll;
+    ///
+    /// assert_eq!(RPoll::Ready(8).into_poll(), Poll::Ready(8));
+    /// assert_eq!(RPoll::<u32>::Pending.into_poll(), Poll::Pending);
+    ///
+    /// ```
+    #[inline]
+    pub fn into_poll(self) -> Poll<T> {
+        self.into()
+    }
+}
+
+impl_from_rust_repr! {
+    impl[T] From<Poll<T>> for RPoll<T> {
+        fn(this){
+            match this {
+                Poll::Ready(v) => RPoll::Ready(v),
+                Poll::Pending => RPoll::Pending,
+            }
+        }
+    }
+}
+
+impl_into_rust_repr! {
+    impl[T] Into<Poll<T>> for RPoll<T> {
+        fn(this){
+            match this {
+                RPoll::Ready(v) => Poll::Ready(v),
+                RPoll::Pending => Poll::Pending,
+            }
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// Ffi-safe equivalent of `std::task::Waker`.
+///
+/// This is what ffi-safe futures and streams get passed when they are polled,
+/// it can be converted to and from a `std::task::Waker`.
+///
+/// # Example
+///
+/// ```
+/// use abi_stable::std_types::RWaker;
+///
+/// use std::{
+///     sync::{
+///         atomic::{AtomicUsize, Ordering},
+///         Arc,
+///     },
+///     task::{Wake, Waker},
+/// };
+///
+/// struct CountingWaker(AtomicUsize);
+///
+/// impl Wake for CountingWaker {
+///     fn wake(self: Arc<Self>) {
+///         self.0.fetch_add(1, Ordering::SeqCst);
+///     }
+/// }
+///
+/// let counter = Arc::new(CountingWaker(AtomicUsize::new(0)));
+/// let waker = Waker::from(counter.clone());
+///
+/// // Borrowing the `Waker` without cloning it
+/// RWaker::with_waker_ref(&waker, |rwaker| {
+///     rwaker.wake_by_ref();
+///
+///     let owned: RWaker = rwaker.clone();
+///     owned.wake();
+/// });
+///
+/// RWaker::from(waker.clone()).into_waker().wake();
+///
+/// assert_eq!(counter.0.load(Ordering::SeqCst), 3);
+///
+/// ```
+#[repr(C)]
+#[derive(StableAbi)]
+pub struct RWaker {
+    data: *const ErasedObject,
+    vtable: WakerVTable_Ref,
+}
+
+unsafe impl Send for RWaker {}
+unsafe impl Sync for RWaker {}
+
+impl RWaker {
+    /// Constructs an `RWaker` from a `std::task::Waker`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RWaker;
+    ///
+    /// # use std::{sync::Arc, task::{Wake, Waker}};
+    /// # struct Noop;
+    /// # impl Wake for Noop { fn wake(self: Arc<Self>) {} }
+    /// let waker: Waker = Arc::new(Noop).into();
+    ///
+    /// let rwaker = RWaker::new(waker);
+    /// rwaker.wake();
+    ///
+    /// ```
+    pub fn new(waker: Waker) -> Self {
+        Self {
+            data: Box::into_raw(Box::new(waker)) as *const ErasedObject,
+            vtable: WakerVTable::OWNED,
+        }
+    }
+
+    /// Calls `f` with an `RWaker` that borrows `waker`,
+    /// without cloning the `Waker`.
+    ///
+    /// Cloning the `&RWaker` clones the borrowed `Waker`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RWaker;
+    ///
+    /// # use std::{sync::Arc, task::{Wake, Waker}};
+    /// # struct Noop;
+    /// # impl Wake for Noop { fn wake(self: Arc<Self>) {} }
+    /// let waker: Waker = Arc::new(Noop).into();
+    ///
+    /// let rwaker: RWaker = RWaker::with_waker_ref(&waker, |rwaker| {
+    ///     rwaker.wake_by_ref();
+    ///     rwaker.clone()
+    /// });
+    ///
+    /// ```
+    pub fn with_waker_ref<F, R>(waker: &Waker, f: F) -> R
+    where
+        F: FnOnce(&RWaker) -> R,
+    {
+        let this = ManuallyDrop::new(Self {
+            data: waker as *const Waker as *const ErasedObject,
+            vtable: WakerVTable::BORROWED,
+        });
+        f(&this)
+    }
+
+    /// Calls `f` with a `std::task::Context` that borrows this `RWaker`,
+    /// for polling a Rust future or stream.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RWaker;
+    ///
+    /// use std::{future::Future, pin::Pin, task::Poll};
+    ///
+    /// # use std::{sync::Arc, task::{Wake, Waker}};
+    /// # struct Noop;
+    /// # impl Wake for Noop { fn wake(self: Arc<Self>) {} }
+    /// # let waker: Waker = Arc::new(Noop).into();
+    /// let rwaker = RWaker::new(waker);
+    ///
+    /// let mut fut = std::future::ready(100);
+    ///
+    /// let res = rwaker.with_context(|cx| Pin::new(&mut fut).poll(cx));
+    ///
+    /// assert_eq!(res, Poll::Ready(100));
+    ///
+    /// ```
+    pub fn with_context<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&mut Context<'_>) -> R,
+    {
+        let raw = RawWaker::new(self as *const RWaker as *const (), &BORROWED_RAW_VTABLE);
+        let waker = ManuallyDrop::new(unsafe { Waker::from_raw(raw) });
+        f(&mut Context::from_waker(&waker))
+    }
+
+    /// Converts this into a `std::task::Waker`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RWaker;
+    ///
+    /// # use std::{sync::Arc, task::{Wake, Waker}};
+    /// # struct Noop;
+    /// # impl Wake for Noop { fn wake(self: Arc<Self>) {} }
+    /// # let waker: Waker = Arc::new(Noop).into();
+    /// let rwaker = RWaker::new(waker);
+    ///
+    /// let waker: Waker = rwaker.into_waker();
+    /// waker.wake();
+    ///
+    /// ```
+    pub fn into_waker(self) -> Waker {
+        self.into()
+    }
+
+    /// Wakes up the task associated with this `RWaker`.
+    pub fn wake(self) {
+        let this = ManuallyDrop::new(self);
+        unsafe { this.vtable.wake()(this.data) }
+    }
+
+    /// Wakes up the task associated with this `RWaker`,without consuming it.
+    pub fn wake_by_ref(&self) {
+        unsafe { self.vtable.wake_by_ref()(self.data) }
+    }
+}
+
+impl Clone for RWaker {
+    fn clone(&self) -> Self {
+        unsafe { self.vtable.clone_()(self.data) }
+    }
+}
+
+impl Drop for RWaker {
+    fn drop(&mut self) {
+        unsafe { self.vtable.drop_()(self.data) }
+    }
+}
+
+impl Debug for RWaker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RWaker")
+            .field("data", &self.data)
+            .finish()
+    }
+}
+
+impl_from_rust_repr! {
+    impl From<Waker> for RWaker {
+        fn(this){
+            RWaker::new(this)
+        }
+    }
+}
+
+impl_into_rust_repr! {
+    impl Into<Waker> for RWaker {
+        fn(this){
+            let raw = RawWaker::new(
+                Box::into_raw(Box::new(this)) as *const (),
+                &OWNED_RAW_VTABLE,
+            );
+            unsafe{ Waker::from_raw(raw) }
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+#[repr(C)]
+#[derive(StableAbi)]
+#[sabi(kind(Prefix))]
+#[sabi(missing_field(panic))]
+struct WakerVTable {
+    clone_: unsafe extern "C" fn(*const ErasedObject) -> RWaker,
+    wake: unsafe extern "C" fn(*const ErasedObject),
+    wake_by_ref: unsafe extern "C" fn(*const ErasedObject),
+    #[sabi(last_prefix_field)]
+    drop_: unsafe extern "C" fn(*const ErasedObject),
+}
+
+impl WakerVTable {
+    const _OWNED: WithMetadata<WakerVTable> = WithMetadata::new(WakerVTable {
+        clone_: clone_waker,
+        wake: wake_owned,
+        wake_by_ref: wake_waker_by_ref,
+        drop_: drop_owned,
+    });
+
+    const _BORROWED: WithMetadata<WakerVTable> = WithMetadata::new(WakerVTable {
+        clone_: clone_waker,
+        wake: wake_waker_by_ref,
+        wake_by_ref: wake_waker_by_ref,
+        drop_: drop_borrowed,
+    });
+
+    // The vtable for `RWaker`s that own a `Waker`, in this executable/library
+    const OWNED: WakerVTable_Ref = { WakerVTable_Ref(Self::_OWNED.static_as_prefix()) };
+
+    // The vtable for `RWaker`s that borrow a `Waker`, in this executable/library
+    const BORROWED: WakerVTable_Ref = { WakerVTable_Ref(Self::_BORROWED.static_as_prefix()) };
+}
+
+unsafe extern "C" fn clone_waker(this: *const ErasedObject) -> RWaker {
+    extern_fn_panic_handling! {
+        let this = unsafe{ &*(this as *const Waker) };
+        RWaker::new(this.clone())
+    }
+}
+
+unsafe extern "C" fn wake_owned(this: *const ErasedObject) {
+    extern_fn_panic_handling! {
+        let this = unsafe{ Box::from_raw(this as *mut Waker) };
+        (*this).wake();
+    }
+}
+
+unsafe extern "C" fn wake_waker_by_ref(this: *const ErasedObject) {
+    extern_fn_panic_handling! {
+        let this = unsafe{ &*(this as *const Waker) };
+        this.wake_by_ref();
+    }
+}
+
+unsafe extern "C" fn drop_owned(this: *const ErasedObject) {
+    extern_fn_panic_handling! {
+        drop(unsafe{ Box::from_raw(this as *mut Waker) });
+    }
+}
+
+unsafe extern "C" fn drop_borrowed(_: *const ErasedObject) {}
+
+///////////////////////////////////////////////////////////////////////////////
+
+// The `RawWakerVTable` for `Waker`s that own an `RBox<RWaker>`
+static OWNED_RAW_VTABLE: RawWakerVTable =
+    RawWakerVTable::new(raw_clone, raw_wake_owned, raw_wake_by_ref, raw_drop_owned);
+
+// The `RawWakerVTable` for `Waker`s that borrow an `RWaker`
+static BORROWED_RAW_VTABLE: RawWakerVTable =
+    RawWakerVTable::new(raw_clone, raw_wake_by_ref, raw_wake_by_ref, raw_drop_borrowed);
+
+unsafe fn raw_clone(this: *const ()) -> RawWaker {
+    let this = unsafe { &*(this as *const RWaker) };
+    RawWaker::new(
+        Box::into_raw(Box::new(this.clone())) as *const (),
+        &OWNED_RAW_VTABLE,
+    )
+}
+
+unsafe fn raw_wake_owned(this: *const ()) {
+    let this = unsafe { Box::from_raw(this as *mut RWaker) };
+    (*this).wake();
+}
+
+unsafe fn raw_wake_by_ref(this: *const ()) {
+    let this = unsafe { &*(this as *const RWaker) };
+    this.wake_by_ref();
+}
+
+unsafe fn raw_drop_owned(this: *const ()) {
+    drop(unsafe { Box::from_raw(this as *mut RWaker) });
+}
+
+unsafe fn raw_drop_borrowed(_: *const ()) {}
@@ -0,0 +1,111 @@
+use super::*;
+
+use std::{future::Future, pin::Pin};
+
+use crate::{test_utils::WakeCounter, traits::IntoReprC};
+
+#[test]
+fn poll_conversions() {
+    assert_eq!(Poll::Ready(3).into_c(), RPoll::Ready(3));
+    assert_eq!(Poll::<u32>::Pending.into_c(), RPoll::Pending);
+
+    assert_eq!(RPoll::Ready(5).into_poll(), Poll::Ready(5));
+    assert_eq!(RPoll::<u32>::Pending.into_poll(), Poll::Pending);
+}
+
+#[test]
+fn owned_waker() {
+    let counter = WakeCounter::new();
+
+    let rwaker = RWaker::new(counter.waker());
+    rwaker.wake_by_ref();
+    assert_eq!(counter.count(), 1);
+
+    let cloned = rwaker.clone();
+    rwaker.wake();
+    assert_eq!(counter.count(), 2);
+
+    cloned.wake_by_ref();
+    assert_eq!(counter.count(), 3);
+    drop(cloned);
+
+    assert_eq!(std::sync::Arc::strong_count(&counter), 1);
+}
+
+#[test]
+fn borrowed_waker() {
+    let counter = WakeCounter::new();
+    let waker = counter.waker();
+
+    let cloned = RWaker::with_waker_ref(&waker, |rwaker| {
+        rwaker.wake_by_ref();
+        rwaker.clone()
+    });
+    assert_eq!(counter.count(), 1);
+    assert_eq!(std::sync::Arc::strong_count(&counter), 3);
+
+    cloned.wake();
+    assert_eq!(counter.count(), 2);
+
+    drop(waker);
+    assert_eq!(std::sync::Arc::strong_count(&counter), 1);
+}
+
+#[test]
+fn into_waker() {
+    let counter = WakeCounter::new();
+
+    let waker = RWaker::new(counter.waker()).into_waker();
+    waker.wake_by_ref();
+    assert_eq!(counter.count(), 1);
+
+    let cloned = waker.clone();
+    waker.wake();
+    assert_eq!(counter.count(), 2);
+
+    cloned.wake();
+    assert_eq!(counter.count(), 3);
+
+    assert_eq!(std::sync::Arc::strong_count(&counter), 1);
+}
+
+#[test]
+fn with_context() {
+    struct WakeOnce(bool);
+
+    impl Future for WakeOnce {
+        type Output = u32;
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<u32> {
+            if self.0 {
+                Poll::Ready(100)
+            } else {
+                self.0 = true;
+                cx.waker().wake_by_ref();
+                let waker = cx.waker().clone();
+                waker.wake();
+                Poll::Pending
+            }
+        }
+    }
+
+    let counter = WakeCounter::new();
+    let rwaker = RWaker::new(counter.waker());
+
+    let mut fut = WakeOnce(false);
+
+    assert_eq!(
+        rwaker.with_context(|cx| Pin::new(&mut fut).poll(cx)),
+        Poll::Pending
+    );
+    assert_eq!(counter.count(), 2);
+
+    assert_eq!(
+        rwaker.with_context(|cx| Pin::new(&mut fut).poll(cx)),
+        Poll::Ready(100)
+    );
+    assert_eq!(counter.count(), 2);
+
+    drop(rwaker);
+    assert_eq!(std::sync::Arc::strong_count(&counter), 1);
+}
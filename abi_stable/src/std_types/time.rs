@@ -1,6 +1,12 @@
-//! Contains ffi-safe equivalent of `std::time::Duration`.
+//! Contains ffi-safe equivalents of `std::time::{Duration, SystemTime, Instant}`.
 
-use std::time::Duration;
+use std::{
+    ops::{Add, AddAssign, Sub, SubAssign},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+#[cfg(all(test, not(feature = "only_new_tests")))]
+mod test;
 
 /// Ffi-safe equivalent of `std::time::Duration` .
 ///
@@ -231,3 +237,589 @@ impl_into_rust_repr! {
         }
     }
 }
+
+///////////////////////////////////////////////////////////////////////////////
+
+const NANOS_PER_SEC: i128 = 1_000_000_000;
+
+/// Ffi-safe equivalent of `std::time::SystemTime`,
+/// stored as the signed amount of time since the unix epoch.
+///
+/// # Example
+///
+/// ```
+/// use abi_stable::std_types::{RDuration, RSystemTime};
+///
+/// let time = RSystemTime::UNIX_EPOCH + RDuration::from_secs(86_400);
+///
+/// assert_eq!(
+///     time.duration_since(RSystemTime::UNIX_EPOCH),
+///     Ok(RDuration::from_secs(86_400)),
+/// );
+/// assert_eq!(
+///     RSystemTime::UNIX_EPOCH.duration_since(time),
+///     Err(RDuration::from_secs(86_400)),
+/// );
+///
+/// ```
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, Ord, PartialOrd, Hash, Deserialize, Serialize, StableAbi,
+)]
+#[repr(C)]
+pub struct RSystemTime {
+    seconds: i64,
+    subsec_nanos: u32,
+}
+
+impl RSystemTime {
+    /// An anchor in time, corresponding to "1970-01-01 00:00:00 UTC".
+    pub const UNIX_EPOCH: Self = Self {
+        seconds: 0,
+        subsec_nanos: 0,
+    };
+
+    /// Gets the current system time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RSystemTime;
+    ///
+    /// assert!(RSystemTime::now() > RSystemTime::UNIX_EPOCH);
+    ///
+    /// ```
+    pub fn now() -> Self {
+        SystemTime::now().into()
+    }
+
+    /// Constructs an `RSystemTime` from the amount of seconds since the unix epoch
+    /// (negative for times before it),
+    /// and the nanoseconds inside that second.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `subsec_nanos` is 1_000_000_000 or larger.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RDuration, RSystemTime};
+    ///
+    /// let time = RSystemTime::from_unix_parts(-1, 500_000_000);
+    ///
+    /// assert_eq!(
+    ///     RSystemTime::UNIX_EPOCH.duration_since(time),
+    ///     Ok(RDuration::from_millis(500)),
+    /// );
+    ///
+    /// ```
+    pub const fn from_unix_parts(seconds: i64, subsec_nanos: u32) -> Self {
+        assert!(
+            subsec_nanos < NANOS_PER_SEC as u32,
+            "subsec_nanos must be less than 1_000_000_000"
+        );
+        Self {
+            seconds,
+            subsec_nanos,
+        }
+    }
+
+    /// The amount of whole seconds since the unix epoch,
+    /// rounded towards negative infinity.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RDuration, RSystemTime};
+    ///
+    /// let time = RSystemTime::UNIX_EPOCH + RDuration::from_millis(1500);
+    /// assert_eq!(time.unix_seconds(), 1);
+    ///
+    /// let time = RSystemTime::UNIX_EPOCH - RDuration::from_millis(1500);
+    /// assert_eq!(time.unix_seconds(), -2);
+    ///
+    /// ```
+    pub const fn unix_seconds(&self) -> i64 {
+        self.seconds
+    }
+
+    /// The amount of nanoseconds after [`unix_seconds`](Self::unix_seconds).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RDuration, RSystemTime};
+    ///
+    /// let time = RSystemTime::UNIX_EPOCH - RDuration::from_millis(1500);
+    /// assert_eq!(time.subsec_nanos(), 500_000_000);
+    ///
+    /// ```
+    pub const fn subsec_nanos(&self) -> u32 {
+        self.subsec_nanos
+    }
+
+    /// Returns the amount of time elapsed from `earlier` to `self`.
+    ///
+    /// # Errors
+    ///
+    /// If `earlier` is later than `self`,
+    /// this returns the amount of time from `self` to `earlier` in the `Err` variant.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RDuration, RSystemTime};
+    ///
+    /// let earlier = RSystemTime::from_unix_parts(10, 0);
+    /// let later = RSystemTime::from_unix_parts(12, 500_000_000);
+    ///
+    /// assert_eq!(later.duration_since(earlier), Ok(RDuration::from_millis(2500)));
+    /// assert_eq!(earlier.duration_since(later), Err(RDuration::from_millis(2500)));
+    ///
+    /// ```
+    pub fn duration_since(&self, earlier: RSystemTime) -> Result<RDuration, RDuration> {
+        let diff = self.total_nanos() - earlier.total_nanos();
+        let dur = duration_from_nanos(diff.unsigned_abs());
+        if diff >= 0 {
+            Ok(dur)
+        } else {
+            Err(dur)
+        }
+    }
+
+    /// Returns the amount of time elapsed since this system time was created.
+    ///
+    /// # Errors
+    ///
+    /// If the system clock was adjusted to be earlier than `self`,
+    /// this returns the amount of time from now to `self` in the `Err` variant.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RDuration, RSystemTime};
+    ///
+    /// let time = RSystemTime::now() - RDuration::from_secs(60);
+    ///
+    /// assert!(time.elapsed().unwrap() >= RDuration::from_secs(60));
+    ///
+    /// ```
+    pub fn elapsed(&self) -> Result<RDuration, RDuration> {
+        Self::now().duration_since(*self)
+    }
+
+    /// Adds `dur` to this system time, returning `None` on overflow.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RDuration, RSystemTime};
+    ///
+    /// let time = RSystemTime::UNIX_EPOCH;
+    ///
+    /// assert_eq!(
+    ///     time.checked_add(RDuration::from_secs(3)),
+    ///     Some(RSystemTime::from_unix_parts(3, 0)),
+    /// );
+    /// assert_eq!(RSystemTime::from_unix_parts(i64::MAX, 0).checked_add(RDuration::from_secs(1)), None);
+    ///
+    /// ```
+    pub fn checked_add(&self, dur: RDuration) -> Option<RSystemTime> {
+        Self::from_total_nanos(self.total_nanos() + dur.as_nanos() as i128)
+    }
+
+    /// Subtracts `dur` from this system time, returning `None` on overflow.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RDuration, RSystemTime};
+    ///
+    /// let time = RSystemTime::UNIX_EPOCH;
+    ///
+    /// assert_eq!(
+    ///     time.checked_sub(RDuration::from_millis(250)),
+    ///     Some(RSystemTime::from_unix_parts(-1, 750_000_000)),
+    /// );
+    /// assert_eq!(RSystemTime::from_unix_parts(i64::MIN, 0).checked_sub(RDuration::from_secs(1)), None);
+    ///
+    /// ```
+    pub fn checked_sub(&self, dur: RDuration) -> Option<RSystemTime> {
+        Self::from_total_nanos(self.total_nanos() - dur.as_nanos() as i128)
+    }
+
+    const fn total_nanos(&self) -> i128 {
+        self.seconds as i128 * NANOS_PER_SEC + self.subsec_nanos as i128
+    }
+
+    fn from_total_nanos(nanos: i128) -> Option<Self> {
+        let seconds = i64::try_from(nanos.div_euclid(NANOS_PER_SEC)).ok()?;
+        Some(Self {
+            seconds,
+            subsec_nanos: nanos.rem_euclid(NANOS_PER_SEC) as u32,
+        })
+    }
+}
+
+fn duration_from_nanos(nanos: u128) -> RDuration {
+    RDuration::new(
+        (nanos / NANOS_PER_SEC as u128) as u64,
+        (nanos % NANOS_PER_SEC as u128) as u32,
+    )
+}
+
+impl Add<RDuration> for RSystemTime {
+    type Output = RSystemTime;
+
+    fn add(self, dur: RDuration) -> RSystemTime {
+        self.checked_add(dur)
+            .expect("overflow when adding duration to system time")
+    }
+}
+
+impl AddAssign<RDuration> for RSystemTime {
+    fn add_assign(&mut self, dur: RDuration) {
+        *self = *self + dur;
+    }
+}
+
+impl Sub<RDuration> for RSystemTime {
+    type Output = RSystemTime;
+
+    fn sub(self, dur: RDuration) -> RSystemTime {
+        self.checked_sub(dur)
+            .expect("overflow when subtracting duration from system time")
+    }
+}
+
+impl SubAssign<RDuration> for RSystemTime {
+    fn sub_assign(&mut self, dur: RDuration) {
+        *self = *self - dur;
+    }
+}
+
+impl_from_rust_repr! {
+    impl From<SystemTime> for RSystemTime {
+        fn(this){
+            match this.duration_since(UNIX_EPOCH) {
+                Ok(dur) => RSystemTime::UNIX_EPOCH + RDuration::from(dur),
+                Err(e) => RSystemTime::UNIX_EPOCH - RDuration::from(e.duration()),
+            }
+        }
+    }
+}
+
+impl_into_rust_repr! {
+    impl Into<SystemTime> for RSystemTime {
+        fn(this){
+            match this.duration_since(RSystemTime::UNIX_EPOCH) {
+                Ok(dur) => UNIX_EPOCH + Duration::from(dur),
+                Err(dur) => UNIX_EPOCH - Duration::from(dur),
+            }
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// Ffi-safe equivalent of `std::time::Instant`,
+/// a measurement of a monotonically nondecreasing clock.
+///
+/// Unlike `Instant`,
+/// `RInstant`s created by different dynamic libraries in the same process
+/// can be compared and subtracted,
+/// because they read the monotonic clock of the operating system directly
+/// (on Linux, Android, macOS, iOS, the BSDs, Solaris, illumos, and Windows).
+///
+/// # Platform support
+///
+/// On other platforms, `RInstant`s are measured from an origin
+/// that is specific to each copy of `abi_stable`
+/// (every dynamic library links its own copy),
+/// so `RInstant`s from different dynamic libraries can't be compared or subtracted,
+/// only the ones created by the same library are comparable.
+///
+/// # Example
+///
+/// ```
+/// use abi_stable::std_types::{RDuration, RInstant};
+///
+/// let start = RInstant::now();
+///
+/// std::thread::sleep(std::time::Duration::from_millis(1));
+///
+/// let end = RInstant::now();
+///
+/// assert!(start < end);
+/// assert!(end - start >= RDuration::from_millis(1));
+/// assert_eq!(start + (end - start), end);
+///
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Ord, PartialOrd, Hash, StableAbi)]
+#[repr(C)]
+pub struct RInstant {
+    since_origin: RDuration,
+}
+
+impl RInstant {
+    /// Gets the current instant.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RInstant;
+    ///
+    /// let first = RInstant::now();
+    /// let second = RInstant::now();
+    ///
+    /// assert!(first <= second);
+    ///
+    /// ```
+    pub fn now() -> Self {
+        Self {
+            since_origin: clock::now(),
+        }
+    }
+
+    /// Returns the amount of time elapsed from `earlier` to `self`,
+    /// or zero if `earlier` is later than `self`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RDuration, RInstant};
+    ///
+    /// let earlier = RInstant::now();
+    /// let later = earlier + RDuration::from_secs(2);
+    ///
+    /// assert_eq!(later.duration_since(earlier), RDuration::from_secs(2));
+    /// assert_eq!(earlier.duration_since(later), RDuration::from_secs(0));
+    ///
+    /// ```
+    pub fn duration_since(&self, earlier: RInstant) -> RDuration {
+        self.checked_duration_since(earlier)
+            .unwrap_or_else(|| RDuration::new(0, 0))
+    }
+
+    /// Returns the amount of time elapsed from `earlier` to `self`,
+    /// or `None` if `earlier` is later than `self`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RDuration, RInstant};
+    ///
+    /// let earlier = RInstant::now();
+    /// let later = earlier + RDuration::from_secs(2);
+    ///
+    /// assert_eq!(later.checked_duration_since(earlier), Some(RDuration::from_secs(2)));
+    /// assert_eq!(earlier.checked_duration_since(later), None);
+    ///
+    /// ```
+    pub fn checked_duration_since(&self, earlier: RInstant) -> Option<RDuration> {
+        Duration::from(self.since_origin)
+            .checked_sub(earlier.since_origin.into())
+            .map(RDuration::from)
+    }
+
+    /// Returns the amount of time elapsed since this instant was created.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RDuration, RInstant};
+    ///
+    /// let start = RInstant::now();
+    ///
+    /// std::thread::sleep(std::time::Duration::from_millis(1));
+    ///
+    /// assert!(start.elapsed() >= RDuration::from_millis(1));
+    ///
+    /// ```
+    pub fn elapsed(&self) -> RDuration {
+        Self::now().duration_since(*self)
+    }
+
+    /// Adds `dur` to this instant, returning `None` on overflow.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RDuration, RInstant};
+    ///
+    /// let now = RInstant::now();
+    ///
+    /// assert!(now.checked_add(RDuration::from_secs(1)).unwrap() > now);
+    /// assert_eq!(now.checked_add(RDuration::new(u64::MAX, 0)), None);
+    ///
+    /// ```
+    pub fn checked_add(&self, dur: RDuration) -> Option<RInstant> {
+        Duration::from(self.since_origin)
+            .checked_add(dur.into())
+            .map(|since_origin| Self {
+                since_origin: since_origin.into(),
+            })
+    }
+
+    /// Subtracts `dur` from this instant,
+    /// returning `None` if the result would be before the origin of the clock.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RDuration, RInstant};
+    ///
+    /// let now = RInstant::now();
+    /// let later = now + RDuration::from_secs(1);
+    ///
+    /// assert_eq!(later.checked_sub(RDuration::from_secs(1)), Some(now));
+    /// assert_eq!(now.checked_sub(RDuration::new(u64::MAX, 0)), None);
+    ///
+    /// ```
+    pub fn checked_sub(&self, dur: RDuration) -> Option<RInstant> {
+        Duration::from(self.since_origin)
+            .checked_sub(dur.into())
+            .map(|since_origin| Self {
+                since_origin: since_origin.into(),
+            })
+    }
+}
+
+impl Add<RDuration> for RInstant {
+    type Output = RInstant;
+
+    fn add(self, dur: RDuration) -> RInstant {
+        self.checked_add(dur)
+            .expect("overflow when adding duration to instant")
+    }
+}
+
+impl AddAssign<RDuration> for RInstant {
+    fn add_assign(&mut self, dur: RDuration) {
+        *self = *self + dur;
+    }
+}
+
+impl Sub<RDuration> for RInstant {
+    type Output = RInstant;
+
+    fn sub(self, dur: RDuration) -> RInstant {
+        self.checked_sub(dur)
+            .expect("overflow when subtracting duration from instant")
+    }
+}
+
+impl SubAssign<RDuration> for RInstant {
+    fn sub_assign(&mut self, dur: RDuration) {
+        *self = *self - dur;
+    }
+}
+
+impl Sub<RInstant> for RInstant {
+    type Output = RDuration;
+
+    fn sub(self, other: RInstant) -> RDuration {
+        self.duration_since(other)
+    }
+}
+
+/// Converts an `Instant` by measuring its distance from the current time,
+/// so the result can be off by the time it takes to read both clocks.
+impl From<Instant> for RInstant {
+    fn from(this: Instant) -> Self {
+        let (now, rnow) = (Instant::now(), RInstant::now());
+        match now.checked_duration_since(this) {
+            Some(dur) => rnow - RDuration::from(dur),
+            None => rnow + RDuration::from(this - now),
+        }
+    }
+}
+
+/// Converts an `RInstant` by measuring its distance from the current time,
+/// so the result can be off by the time it takes to read both clocks.
+impl From<RInstant> for Instant {
+    fn from(this: RInstant) -> Self {
+        let (now, rnow) = (Instant::now(), RInstant::now());
+        match rnow.checked_duration_since(this) {
+            Some(dur) => now - Duration::from(dur),
+            None => now + Duration::from(this - rnow),
+        }
+    }
+}
+
+#[cfg(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "freebsd",
+    target_os = "dragonfly",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    target_os = "solaris",
+    target_os = "illumos",
+))]
+mod clock {
+    use super::RDuration;
+
+    pub(super) fn now() -> RDuration {
+        // `timespec` has private padding fields on some targets,
+        // so it can't be constructed with a struct literal.
+        let mut ts: libc::timespec = unsafe { std::mem::zeroed() };
+        let ret = unsafe { libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut ts) };
+        assert_eq!(ret, 0, "clock_gettime failed");
+        RDuration::new(ts.tv_sec as u64, ts.tv_nsec as u32)
+    }
+}
+
+#[cfg(windows)]
+mod clock {
+    use super::{duration_from_nanos, RDuration, NANOS_PER_SEC};
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn QueryPerformanceCounter(count: *mut i64) -> i32;
+        fn QueryPerformanceFrequency(frequency: *mut i64) -> i32;
+    }
+
+    pub(super) fn now() -> RDuration {
+        let mut count = 0i64;
+        let mut frequency = 0i64;
+        unsafe {
+            assert_ne!(QueryPerformanceCounter(&mut count), 0);
+            assert_ne!(QueryPerformanceFrequency(&mut frequency), 0);
+        }
+        duration_from_nanos(count as u128 * NANOS_PER_SEC as u128 / frequency as u128)
+    }
+}
+
+// The origin of this clock is lazily initialized by each copy of abi_stable,
+// so `RInstant`s from different dynamic libraries aren't comparable,
+// this is documented in `RInstant`.
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "freebsd",
+    target_os = "dragonfly",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    target_os = "solaris",
+    target_os = "illumos",
+    windows,
+)))]
+mod clock {
+    use super::RDuration;
+
+    use crate::sabi_types::LateStaticRef;
+
+    use std::time::Instant;
+
+    static ORIGIN: LateStaticRef<&'static Instant> = LateStaticRef::new();
+
+    pub(super) fn now() -> RDuration {
+        let origin = ORIGIN.init(|| Box::leak(Box::new(Instant::now())));
+        origin.elapsed().into()
+    }
+}
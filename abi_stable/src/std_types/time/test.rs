@@ -0,0 +1,118 @@
+use super::*;
+
+#[test]
+fn system_time_conversions() {
+    let times = [
+        UNIX_EPOCH,
+        UNIX_EPOCH + Duration::new(1_600_000_000, 123_456_789),
+        UNIX_EPOCH - Duration::new(0, 1),
+        UNIX_EPOCH - Duration::new(86_400, 250_000_000),
+        SystemTime::now(),
+    ];
+
+    for time in times {
+        let rtime = RSystemTime::from(time);
+        assert_eq!(SystemTime::from(rtime), time);
+        assert_eq!(
+            rtime.duration_since(RSystemTime::UNIX_EPOCH).ok(),
+            time.duration_since(UNIX_EPOCH).ok().map(RDuration::from),
+        );
+        assert!(rtime.subsec_nanos() < 1_000_000_000);
+    }
+
+    let before = RSystemTime::from(UNIX_EPOCH - Duration::new(1, 250_000_000));
+    assert_eq!(before.unix_seconds(), -2);
+    assert_eq!(before.subsec_nanos(), 750_000_000);
+}
+
+#[test]
+fn system_time_arithmetic() {
+    let epoch = RSystemTime::UNIX_EPOCH;
+    let dur = RDuration::new(5, 600_000_000);
+
+    let later = epoch + dur;
+    let earlier = epoch - dur;
+    assert!(earlier < epoch && epoch < later);
+    assert_eq!(later - dur, epoch);
+    assert_eq!(earlier + dur, epoch);
+    assert_eq!(
+        later.duration_since(earlier),
+        Ok(RDuration::new(11, 200_000_000))
+    );
+    assert_eq!(
+        earlier.duration_since(later),
+        Err(RDuration::new(11, 200_000_000))
+    );
+
+    let mut time = epoch;
+    time += dur;
+    time -= RDuration::from_secs(10);
+    assert_eq!(time, RSystemTime::from_unix_parts(-5, 600_000_000));
+
+    let max = RSystemTime::from_unix_parts(i64::MAX, 999_999_999);
+    assert_eq!(max.checked_add(RDuration::from_nanos(1)), None);
+    let min = RSystemTime::from_unix_parts(i64::MIN, 0);
+    assert_eq!(min.checked_sub(RDuration::from_nanos(1)), None);
+}
+
+#[test]
+fn system_time_serde() {
+    let time = RSystemTime::from_unix_parts(-3, 5);
+    let serialized = serde_json::to_string(&time).unwrap();
+    assert_eq!(
+        serde_json::from_str::<RSystemTime>(&serialized).unwrap(),
+        time
+    );
+}
+
+#[test]
+fn instant_arithmetic() {
+    let start = RInstant::now();
+    std::thread::sleep(Duration::from_millis(2));
+    let end = RInstant::now();
+
+    assert!(start < end);
+    assert!(end - start >= RDuration::from_millis(2));
+    assert_eq!(start - end, RDuration::from_secs(0));
+    assert_eq!(start.checked_duration_since(end), None);
+    assert_eq!(start + (end - start), end);
+    assert_eq!(end - (end - start), start);
+    assert!(start.elapsed() >= end - start);
+
+    let mut moved = start;
+    moved += RDuration::from_secs(3);
+    moved -= RDuration::from_secs(1);
+    assert_eq!(moved - start, RDuration::from_secs(2));
+}
+
+#[test]
+fn instant_matches_std_clock() {
+    let std_start = Instant::now();
+    let start = RInstant::now();
+    std::thread::sleep(Duration::from_millis(5));
+    let end = RInstant::now();
+    let std_end = Instant::now();
+
+    // The std clock was read before and after this clock,
+    // so its measurement has to be at least as long.
+    assert!(RDuration::from(std_end - std_start) >= end - start);
+}
+
+#[test]
+fn instant_conversions() {
+    let tolerance = Duration::from_millis(100);
+
+    let std = Instant::now() - Duration::from_secs(1);
+    let rinstant = RInstant::from(std);
+    let elapsed: Duration = rinstant.elapsed().into();
+    assert!(elapsed >= Duration::from_secs(1));
+    assert!(elapsed < Duration::from_secs(1) + tolerance);
+
+    let back = Instant::from(rinstant);
+    let diff = if back > std { back - std } else { std - back };
+    assert!(diff < tolerance);
+
+    let future = RInstant::now() + RDuration::from_secs(10);
+    let std_future = Instant::from(future);
+    assert!(std_future > Instant::now() + Duration::from_secs(9));
+}
@@ -1446,7 +1446,7 @@ impl io::Write for RVec<u8> {
 
 #[repr(C)]
 #[derive(Debug, Copy, Clone, PartialEq, StableAbi)]
-enum Exactness {
+pub(super) enum Exactness {
     Exact,
     Above,
 }
@@ -0,0 +1,1227 @@
+//! Contains an ffi-safe equivalent of `VecDeque<T>`.
+
+use std::{
+    cmp::Ordering,
+    collections::VecDeque,
+    fmt::{self, Debug},
+    hash::{Hash, Hasher},
+    iter::FromIterator,
+    marker::PhantomData,
+    mem::{self, ManuallyDrop},
+    ops::{Bound, Index, IndexMut, RangeBounds},
+    ptr::{self, NonNull},
+    slice,
+};
+
+use serde::{ser::SerializeSeq, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{
+    pointer_trait::CanTransmuteElement,
+    prefix_type::WithMetadata,
+    sabi_types::RMut,
+    std_types::{
+        utypeid::{new_utypeid, UTypeId},
+        vec::Exactness,
+        RSlice, RSliceMut, RVec, Tuple2,
+    },
+};
+
+#[cfg(test)]
+mod tests;
+
+mod iters;
+
+pub use self::iters::{Drain, IntoIter, Iter, IterMut};
+
+/// Ffi-safe equivalent of `std::collections::VecDeque`,
+/// a double-ended queue implemented as a growable ring buffer.
+///
+/// Like [`RVec`], the buffer is allocated and freed through a vtable,
+/// so an `RVecDeque` can be grown and dropped on either side of the ffi boundary.
+///
+/// # Example
+///
+/// ```
+/// use abi_stable::std_types::{RSlice, RVecDeque, Tuple2};
+///
+/// let mut queue = RVecDeque::new();
+///
+/// queue.push_back(3);
+/// queue.push_back(5);
+/// queue.push_front(1);
+///
+/// assert_eq!(queue.pop_front(), Some(1));
+/// assert_eq!(queue.pop_back(), Some(5));
+///
+/// queue.extend(vec![8, 13]);
+///
+/// assert_eq!(queue.iter().copied().collect::<Vec<_>>(), [3, 8, 13]);
+///
+/// let Tuple2(front, back) = queue.as_slices();
+/// assert_eq!(front.len() + back.len(), 3);
+///
+/// ```
+///
+#[repr(C)]
+#[derive(StableAbi)]
+pub struct RVecDeque<T> {
+    buffer: NonNull<T>,
+    // The index in `buffer` of the first element.
+    head: usize,
+    length: usize,
+    capacity: usize,
+    vtable: VecDequeVTable_Ref,
+    _marker: PhantomData<T>,
+}
+
+impl<T> RVecDeque<T> {
+    /// Creates a new, empty `RVecDeque<T>`.
+    ///
+    /// This function does not allocate.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RVecDeque;
+    ///
+    /// let queue = RVecDeque::<u32>::new();
+    ///
+    /// ```
+    pub const fn new() -> Self {
+        Self::NEW
+    }
+
+    const NEW: Self = RVecDeque {
+        vtable: VTableGetter::<T>::LIB_VTABLE,
+        buffer: NonNull::dangling(),
+        head: 0,
+        length: 0,
+        capacity: 0_usize.wrapping_sub((mem::size_of::<T>() == 0) as usize),
+        _marker: PhantomData,
+    };
+
+    /// Creates a new, empty `RVecDeque<T>`, with space for `capacity` elements.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RVecDeque;
+    ///
+    /// let mut queue = RVecDeque::with_capacity(7);
+    ///
+    /// assert_eq!(queue.len(), 0);
+    /// assert!(queue.capacity() >= 7);
+    ///
+    /// queue.extend(0..7);
+    /// assert!(queue.capacity() >= 7);
+    ///
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        Vec::with_capacity(capacity).into()
+    }
+
+    #[allow(dead_code)]
+    // Used to test functions that change behavior when the vtable changes
+    fn set_vtable_for_testing(mut self) -> Self {
+        self.vtable = VTableGetter::<T>::LIB_VTABLE_FOR_TESTING;
+        self
+    }
+
+    /// Returns the amount of elements this `RVecDeque<T>` can store without reallocating.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RVecDeque;
+    ///
+    /// let mut queue = RVecDeque::new();
+    ///
+    /// assert_eq!(queue.capacity(), 0);
+    ///
+    /// queue.push_back(0);
+    /// assert_ne!(queue.capacity(), 0);
+    ///
+    /// ```
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the amount of elements in the `RVecDeque<T>`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RVecDeque;
+    ///
+    /// let mut queue = RVecDeque::new();
+    ///
+    /// assert_eq!(queue.len(), 0);
+    ///
+    /// queue.push_back(0);
+    /// assert_eq!(queue.len(), 1);
+    ///
+    /// queue.push_front(1);
+    /// assert_eq!(queue.len(), 2);
+    ///
+    /// ```
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Returns whether the `RVecDeque<T>` is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RVecDeque;
+    ///
+    /// let mut queue = RVecDeque::new();
+    ///
+    /// assert!(queue.is_empty());
+    ///
+    /// queue.push_back(0);
+    /// assert!(!queue.is_empty());
+    ///
+    /// ```
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Converts a logical index into an index into the buffer.
+    ///
+    /// `index` must be less than or equal to the capacity.
+    #[inline]
+    fn physical_index(&self, index: usize) -> usize {
+        let until_end = self.capacity - self.head;
+        if index >= until_end {
+            index - until_end
+        } else {
+            self.head + index
+        }
+    }
+
+    /// Returns the (start, length) pairs of the two contiguous
+    /// sections of the buffer that contain elements.
+    #[inline]
+    fn slice_ranges(&self) -> ((usize, usize), (usize, usize)) {
+        let until_end = self.capacity - self.head;
+        if self.length <= until_end {
+            ((self.head, self.length), (0, 0))
+        } else {
+            ((self.head, until_end), (0, self.length - until_end))
+        }
+    }
+
+    fn raw_slices(&self) -> (*mut [T], *mut [T]) {
+        let ((a_start, a_len), (b_start, b_len)) = self.slice_ranges();
+        let buffer = self.buffer.as_ptr();
+        unsafe {
+            (
+                ptr::slice_from_raw_parts_mut(buffer.add(a_start), a_len),
+                ptr::slice_from_raw_parts_mut(buffer.add(b_start), b_len),
+            )
+        }
+    }
+
+    /// Returns the contents of the ring buffer as two slices,
+    /// the elements of the first one come before the second one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RSlice, RVecDeque, Tuple2};
+    ///
+    /// let mut queue = RVecDeque::with_capacity(4);
+    ///
+    /// queue.extend(vec![3, 5]);
+    /// queue.push_front(1);
+    ///
+    /// let Tuple2(front, back) = queue.as_slices();
+    ///
+    /// let joined = front.iter().chain(back.iter()).copied().collect::<Vec<_>>();
+    /// assert_eq!(joined, [1, 3, 5]);
+    ///
+    /// ```
+    pub fn as_slices(&self) -> Tuple2<RSlice<'_, T>, RSlice<'_, T>> {
+        let (a, b) = self.raw_slices();
+        unsafe { Tuple2(RSlice::from_slice(&*a), RSlice::from_slice(&*b)) }
+    }
+
+    /// Returns the contents of the ring buffer as two mutable slices,
+    /// the elements of the first one come before the second one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RVecDeque, Tuple2};
+    ///
+    /// let mut queue = RVecDeque::with_capacity(4);
+    ///
+    /// queue.extend(vec![3, 5]);
+    /// queue.push_front(1);
+    ///
+    /// let Tuple2(mut front, mut back) = queue.as_mut_slices();
+    /// for x in front.iter_mut().chain(back.iter_mut()) {
+    ///     *x *= 10;
+    /// }
+    ///
+    /// assert_eq!(queue.iter().copied().collect::<Vec<_>>(), [10, 30, 50]);
+    ///
+    /// ```
+    pub fn as_mut_slices(&mut self) -> Tuple2<RSliceMut<'_, T>, RSliceMut<'_, T>> {
+        let (a, b) = self.raw_slices();
+        unsafe {
+            Tuple2(
+                RSliceMut::from_mut_slice(&mut *a),
+                RSliceMut::from_mut_slice(&mut *b),
+            )
+        }
+    }
+
+    /// Moves the elements to the start of the buffer,
+    /// so that they are all stored in a single slice,
+    /// returning that slice.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RVecDeque, Tuple2};
+    ///
+    /// let mut queue = RVecDeque::with_capacity(4);
+    ///
+    /// queue.extend(vec![3, 5]);
+    /// queue.push_front(1);
+    ///
+    /// assert_eq!(queue.make_contiguous(), &mut [1, 3, 5][..]);
+    ///
+    /// let Tuple2(front, back) = queue.as_slices();
+    /// assert_eq!(front.as_slice(), &[1, 3, 5]);
+    /// assert!(back.is_empty());
+    ///
+    /// ```
+    pub fn make_contiguous(&mut self) -> &mut [T] {
+        let buffer = self.buffer.as_ptr();
+        let ((a_start, a_len), (_, b_len)) = self.slice_ranges();
+        unsafe {
+            if b_len == 0 {
+                if a_start != 0 {
+                    ptr::copy(buffer.add(a_start), buffer, a_len);
+                }
+            } else {
+                // Only allocates space, the elements are moved back into
+                // the buffer before `temp` is dropped.
+                let mut temp = Vec::<T>::with_capacity(b_len);
+                ptr::copy_nonoverlapping(buffer, temp.as_mut_ptr(), b_len);
+                ptr::copy(buffer.add(a_start), buffer, a_len);
+                ptr::copy_nonoverlapping(temp.as_ptr(), buffer.add(a_len), b_len);
+            }
+            self.head = 0;
+            slice::from_raw_parts_mut(buffer, self.length)
+        }
+    }
+
+    /// Returns a reference to the element at the `index` position.
+    ///
+    /// Returns `None` if `index` is out of bounds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RVecDeque;
+    ///
+    /// let mut queue = RVecDeque::new();
+    /// queue.extend(vec![3, 5]);
+    /// queue.push_front(1);
+    ///
+    /// assert_eq!(queue.get(0), Some(&1));
+    /// assert_eq!(queue.get(2), Some(&5));
+    /// assert_eq!(queue.get(3), None);
+    ///
+    /// ```
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index < self.length {
+            unsafe { Some(&*self.buffer.as_ptr().add(self.physical_index(index))) }
+        } else {
+            None
+        }
+    }
+
+    /// Returns a mutable reference to the element at the `index` position.
+    ///
+    /// Returns `None` if `index` is out of bounds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RVecDeque;
+    ///
+    /// let mut queue = RVecDeque::new();
+    /// queue.extend(vec![3, 5]);
+    ///
+    /// *queue.get_mut(1).unwrap() += 100;
+    /// assert_eq!(queue.get(1), Some(&105));
+    /// assert_eq!(queue.get_mut(2), None);
+    ///
+    /// ```
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index < self.length {
+            unsafe { Some(&mut *self.buffer.as_ptr().add(self.physical_index(index))) }
+        } else {
+            None
+        }
+    }
+
+    /// Returns a reference to the first element, if there is one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RVecDeque;
+    ///
+    /// let mut queue = RVecDeque::new();
+    /// assert_eq!(queue.front(), None);
+    ///
+    /// queue.extend(vec![3, 5]);
+    /// assert_eq!(queue.front(), Some(&3));
+    ///
+    /// ```
+    pub fn front(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    /// Returns a mutable reference to the first element, if there is one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RVecDeque;
+    ///
+    /// let mut queue = RVecDeque::new();
+    /// assert_eq!(queue.front_mut(), None);
+    ///
+    /// queue.extend(vec![3, 5]);
+    /// assert_eq!(queue.front_mut(), Some(&mut 3));
+    ///
+    /// ```
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        self.get_mut(0)
+    }
+
+    /// Returns a reference to the last element, if there is one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RVecDeque;
+    ///
+    /// let mut queue = RVecDeque::new();
+    /// assert_eq!(queue.back(), None);
+    ///
+    /// queue.extend(vec![3, 5]);
+    /// assert_eq!(queue.back(), Some(&5));
+    ///
+    /// ```
+    pub fn back(&self) -> Option<&T> {
+        self.get(self.length.wrapping_sub(1))
+    }
+
+    /// Returns a mutable reference to the last element, if there is one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RVecDeque;
+    ///
+    /// let mut queue = RVecDeque::new();
+    /// assert_eq!(queue.back_mut(), None);
+    ///
+    /// queue.extend(vec![3, 5]);
+    /// assert_eq!(queue.back_mut(), Some(&mut 5));
+    ///
+    /// ```
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        self.get_mut(self.length.wrapping_sub(1))
+    }
+
+    /// Appends an element to the back of the `RVecDeque<T>`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RVecDeque;
+    ///
+    /// let mut queue = RVecDeque::new();
+    ///
+    /// queue.push_back(11);
+    /// queue.push_back(22);
+    ///
+    /// assert_eq!(queue.iter().copied().collect::<Vec<_>>(), [11, 22]);
+    ///
+    /// ```
+    pub fn push_back(&mut self, value: T) {
+        if self.length == self.capacity {
+            self.reserve(1);
+        }
+        unsafe {
+            let index = self.physical_index(self.length);
+            ptr::write(self.buffer.as_ptr().add(index), value);
+        }
+        self.length += 1;
+    }
+
+    /// Prepends an element to the front of the `RVecDeque<T>`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RVecDeque;
+    ///
+    /// let mut queue = RVecDeque::new();
+    ///
+    /// queue.push_front(11);
+    /// queue.push_front(22);
+    ///
+    /// assert_eq!(queue.iter().copied().collect::<Vec<_>>(), [22, 11]);
+    ///
+    /// ```
+    pub fn push_front(&mut self, value: T) {
+        if self.length == self.capacity {
+            self.reserve(1);
+        }
+        self.head = match self.head {
+            0 => self.capacity - 1,
+            head => head - 1,
+        };
+        unsafe {
+            ptr::write(self.buffer.as_ptr().add(self.head), value);
+        }
+        self.length += 1;
+    }
+
+    /// Removes the first element and returns it,
+    /// returns `None` if the `RVecDeque<T>` is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RVecDeque;
+    ///
+    /// let mut queue = RVecDeque::new();
+    /// queue.extend(vec![11, 22]);
+    ///
+    /// assert_eq!(queue.pop_front(), Some(11));
+    /// assert_eq!(queue.pop_front(), Some(22));
+    /// assert_eq!(queue.pop_front(), None);
+    ///
+    /// ```
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.length == 0 {
+            return None;
+        }
+        unsafe {
+            let value = ptr::read(self.buffer.as_ptr().add(self.head));
+            self.head = self.physical_index(1);
+            self.length -= 1;
+            Some(value)
+        }
+    }
+
+    /// Removes the last element and returns it,
+    /// returns `None` if the `RVecDeque<T>` is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RVecDeque;
+    ///
+    /// let mut queue = RVecDeque::new();
+    /// queue.extend(vec![11, 22]);
+    ///
+    /// assert_eq!(queue.pop_back(), Some(22));
+    /// assert_eq!(queue.pop_back(), Some(11));
+    /// assert_eq!(queue.pop_back(), None);
+    ///
+    /// ```
+    pub fn pop_back(&mut self) -> Option<T> {
+        if self.length == 0 {
+            return None;
+        }
+        self.length -= 1;
+        unsafe {
+            let index = self.physical_index(self.length);
+            Some(ptr::read(self.buffer.as_ptr().add(index)))
+        }
+    }
+
+    /// Swaps the elements at the `i` and `j` positions.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either index is out of bounds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RVecDeque;
+    ///
+    /// let mut queue = RVecDeque::new();
+    /// queue.extend(vec![3, 5, 8]);
+    ///
+    /// queue.swap(0, 2);
+    /// assert_eq!(queue.iter().copied().collect::<Vec<_>>(), [8, 5, 3]);
+    ///
+    /// ```
+    pub fn swap(&mut self, i: usize, j: usize) {
+        assert!(i < self.length, "index out of bounds in RVecDeque::swap");
+        assert!(j < self.length, "index out of bounds in RVecDeque::swap");
+        let buffer = self.buffer.as_ptr();
+        unsafe {
+            ptr::swap(
+                buffer.add(self.physical_index(i)),
+                buffer.add(self.physical_index(j)),
+            );
+        }
+    }
+
+    /// Shortens the `RVecDeque<T>` to `to` elements,
+    /// dropping the elements at the back.
+    ///
+    /// This has no effect if `to` is greater than or equal to the length.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RVecDeque;
+    ///
+    /// let mut queue = RVecDeque::new();
+    /// queue.extend(vec![3, 5, 8]);
+    ///
+    /// queue.truncate(1);
+    /// assert_eq!(queue.iter().copied().collect::<Vec<_>>(), [3]);
+    ///
+    /// ```
+    pub fn truncate(&mut self, to: usize) {
+        if to >= self.length {
+            return;
+        }
+        let ((a_start, a_len), (b_start, b_len)) = self.slice_ranges();
+        let buffer = self.buffer.as_ptr();
+        self.length = to;
+        unsafe {
+            if to <= a_len {
+                ptr::drop_in_place(ptr::slice_from_raw_parts_mut(
+                    buffer.add(a_start + to),
+                    a_len - to,
+                ));
+                ptr::drop_in_place(ptr::slice_from_raw_parts_mut(buffer.add(b_start), b_len));
+            } else {
+                let to = to - a_len;
+                ptr::drop_in_place(ptr::slice_from_raw_parts_mut(
+                    buffer.add(b_start + to),
+                    b_len - to,
+                ));
+            }
+        }
+    }
+
+    /// Removes all the elements from the `RVecDeque<T>`.
+    ///
+    /// This has no effect on the capacity of the `RVecDeque<T>`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RVecDeque;
+    ///
+    /// let mut queue = RVecDeque::new();
+    /// queue.extend(vec![3, 5, 8]);
+    ///
+    /// queue.clear();
+    /// assert!(queue.is_empty());
+    /// assert_ne!(queue.capacity(), 0);
+    ///
+    /// ```
+    pub fn clear(&mut self) {
+        self.truncate(0);
+        self.head = 0;
+    }
+
+    /// Returns whether the `RVecDeque<T>` contains an element equal to `value`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RVecDeque;
+    ///
+    /// let mut queue = RVecDeque::new();
+    /// queue.extend(vec![3, 5, 8]);
+    ///
+    /// assert!(queue.contains(&5));
+    /// assert!(!queue.contains(&13));
+    ///
+    /// ```
+    pub fn contains(&self, value: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        self.iter().any(|x| x == value)
+    }
+
+    /// Reserves `additional` additional capacity for extra elements.
+    /// This may reserve more than necessary for the additional capacity.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RVecDeque;
+    ///
+    /// let mut queue = RVecDeque::<u32>::new();
+    ///
+    /// queue.reserve(10);
+    /// assert!(queue.capacity() >= 10);
+    ///
+    /// ```
+    pub fn reserve(&mut self, additional: usize) {
+        self.resize_capacity(self.length.saturating_add(additional), Exactness::Above)
+    }
+
+    /// Reserves `additional` additional capacity for extra elements.
+    ///
+    /// Prefer using `reserve` for incremental growth,
+    /// because this would reallocate every time `additional` elements are added.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RVecDeque;
+    ///
+    /// let mut queue = RVecDeque::<u32>::new();
+    ///
+    /// queue.reserve_exact(17);
+    /// assert!(queue.capacity() >= 17);
+    ///
+    /// ```
+    pub fn reserve_exact(&mut self, additional: usize) {
+        self.resize_capacity(self.length.saturating_add(additional), Exactness::Exact)
+    }
+
+    #[inline]
+    fn resize_capacity(&mut self, to: usize, exactness: Exactness) {
+        let vtable = self.vtable;
+        if self.capacity < to {
+            unsafe {
+                vtable.grow_capacity_to()(RMut::new(self).transmute_element_(), to, exactness);
+            }
+        }
+    }
+
+    /// Shrinks the capacity of the `RVecDeque<T>` to match its length.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RVecDeque;
+    ///
+    /// let mut queue = RVecDeque::<u32>::with_capacity(7);
+    ///
+    /// queue.push_back(3);
+    /// queue.push_back(5);
+    ///
+    /// queue.shrink_to_fit();
+    /// assert_eq!(queue.capacity(), 2);
+    ///
+    /// ```
+    pub fn shrink_to_fit(&mut self) {
+        let vtable = self.vtable;
+        unsafe {
+            vtable.shrink_to_fit()(RMut::new(self).transmute_element_());
+        }
+    }
+
+    /// Returns an iterator over references to the elements,
+    /// from front to back.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RVecDeque;
+    ///
+    /// let mut queue = RVecDeque::new();
+    /// queue.extend(vec![3, 5]);
+    /// queue.push_front(1);
+    ///
+    /// assert_eq!(queue.iter().collect::<Vec<_>>(), [&1, &3, &5]);
+    /// assert_eq!(queue.iter().rev().collect::<Vec<_>>(), [&5, &3, &1]);
+    ///
+    /// ```
+    pub fn iter(&self) -> Iter<'_, T> {
+        let (a, b) = self.raw_slices();
+        unsafe { Iter::new(&*a, &*b) }
+    }
+
+    /// Returns an iterator over mutable references to the elements,
+    /// from front to back.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RVecDeque;
+    ///
+    /// let mut queue = RVecDeque::new();
+    /// queue.extend(vec![3, 5]);
+    /// queue.push_front(1);
+    ///
+    /// queue.iter_mut().for_each(|x| *x += 1);
+    /// assert_eq!(queue.iter().collect::<Vec<_>>(), [&2, &4, &6]);
+    ///
+    /// ```
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        let (a, b) = self.raw_slices();
+        unsafe { IterMut::new(&mut *a, &mut *b) }
+    }
+
+    /// Creates a draining iterator that removes the specified range in
+    /// the `RVecDeque<T>` and yields the removed items.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the index is out of bounds or if the start of the range is
+    /// greater than the end of the range.
+    ///
+    /// # Consumption
+    ///
+    /// The elements in the range will be removed even if the iterator
+    /// was dropped before yielding them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RVecDeque;
+    ///
+    /// let mut queue = RVecDeque::new();
+    /// queue.extend(vec![2, 3, 4, 5]);
+    /// queue.push_front(1);
+    /// queue.push_front(0);
+    ///
+    /// assert_eq!(queue.drain(1..3).collect::<Vec<_>>(), [1, 2]);
+    /// assert_eq!(queue.iter().copied().collect::<Vec<_>>(), [0, 3, 4, 5]);
+    ///
+    /// assert_eq!(queue.drain(..).rev().collect::<Vec<_>>(), [5, 4, 3, 0]);
+    /// assert!(queue.is_empty());
+    ///
+    /// ```
+    pub fn drain<R>(&mut self, range: R) -> Drain<'_, T>
+    where
+        R: RangeBounds<usize>,
+    {
+        let start = match range.start_bound() {
+            Bound::Unbounded => 0,
+            Bound::Included(&n) => n,
+            Bound::Excluded(&n) => n.saturating_add(1),
+        };
+        let end = match range.end_bound() {
+            Bound::Unbounded => self.length,
+            Bound::Included(&n) => n.saturating_add(1),
+            Bound::Excluded(&n) => n,
+        };
+        assert!(
+            start <= end,
+            "drain start ({}) is greater than the end ({}) in RVecDeque",
+            start,
+            end,
+        );
+        assert!(
+            end <= self.length,
+            "drain end ({}) is out of bounds for an RVecDeque of length {}",
+            end,
+            self.length,
+        );
+
+        self.make_contiguous();
+        unsafe { Drain::new(self.buffer.as_ptr(), &mut self.length, start, end) }
+    }
+
+    /// Converts this `RVecDeque<T>` into a `VecDeque<T>`.
+    ///
+    /// # Allocation
+    ///
+    /// If this is invoked outside of the dynamic library/binary that created it,
+    /// it will allocate a new `VecDeque<T>` and move the data into it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::RVecDeque;
+    ///
+    /// use std::collections::VecDeque;
+    ///
+    /// let mut queue = RVecDeque::new();
+    /// queue.extend(vec![3, 5]);
+    /// queue.push_front(1);
+    ///
+    /// assert_eq!(queue.into_vec_deque(), VecDeque::from(vec![1, 3, 5]));
+    ///
+    /// ```
+    pub fn into_vec_deque(self) -> VecDeque<T> {
+        let mut this = ManuallyDrop::new(self);
+
+        unsafe {
+            let this_vtable = this.vtable;
+            let other_vtable = VTableGetter::<T>::LIB_VTABLE;
+            if ptr::eq(this_vtable.0.to_raw_ptr(), other_vtable.0.to_raw_ptr())
+                || this_vtable.type_id()() == other_vtable.type_id()()
+            {
+                this.make_contiguous();
+                Vec::from_raw_parts(this.buffer.as_ptr(), this.length, this.capacity).into()
+            } else {
+                let mut ret = VecDeque::with_capacity(this.length);
+                while let Some(x) = this.pop_front() {
+                    ret.push_back(x);
+                }
+                ManuallyDrop::drop(&mut this);
+                ret
+            }
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+impl<T> Default for RVecDeque<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for RVecDeque<T>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T> Debug for RVecDeque<T>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T> Eq for RVecDeque<T> where T: Eq {}
+
+impl<T> PartialEq for RVecDeque<T>
+where
+    T: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.length == other.length && self.iter().eq(other.iter())
+    }
+}
+
+impl<T> PartialOrd for RVecDeque<T>
+where
+    T: PartialOrd,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T> Ord for RVecDeque<T>
+where
+    T: Ord,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T> Hash for RVecDeque<T>
+where
+    T: Hash,
+{
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        state.write_usize(self.length);
+        for elem in self.iter() {
+            elem.hash(state);
+        }
+    }
+}
+
+impl<T> Index<usize> for RVecDeque<T> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, index: usize) -> &T {
+        self.get(index).expect("index out of bounds in RVecDeque")
+    }
+}
+
+impl<T> IndexMut<usize> for RVecDeque<T> {
+    #[inline]
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        self.get_mut(index)
+            .expect("index out of bounds in RVecDeque")
+    }
+}
+
+impl<T> From<Vec<T>> for RVecDeque<T> {
+    fn from(this: Vec<T>) -> Self {
+        let mut this = ManuallyDrop::new(this);
+        RVecDeque {
+            vtable: VTableGetter::<T>::LIB_VTABLE,
+            buffer: unsafe { NonNull::new_unchecked(this.as_mut_ptr()) },
+            head: 0,
+            length: this.len(),
+            capacity: this.capacity(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> From<RVec<T>> for RVecDeque<T> {
+    fn from(this: RVec<T>) -> Self {
+        this.into_vec().into()
+    }
+}
+
+impl<T> From<RVecDeque<T>> for RVec<T> {
+    fn from(this: RVecDeque<T>) -> Self {
+        Vec::from(this.into_vec_deque()).into()
+    }
+}
+
+impl_from_rust_repr! {
+    impl[T] From<VecDeque<T>> for RVecDeque<T> {
+        fn(this){
+            Vec::from(this).into()
+        }
+    }
+}
+
+impl_into_rust_repr! {
+    impl[T] Into<VecDeque<T>> for RVecDeque<T> {
+        fn(this){
+            this.into_vec_deque()
+        }
+    }
+}
+
+impl<T> FromIterator<T> for RVecDeque<T> {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        Vec::from_iter(iter).into()
+    }
+}
+
+impl<T> Extend<T> for RVecDeque<T> {
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let iter = iter.into_iter();
+        let (lower, _) = iter.size_hint();
+        self.reserve(lower);
+        for elem in iter {
+            self.push_back(elem);
+        }
+    }
+}
+
+impl<'a, T> Extend<&'a T> for RVecDeque<T>
+where
+    T: 'a + Copy,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = &'a T>,
+    {
+        self.extend(iter.into_iter().copied())
+    }
+}
+
+impl<T> IntoIterator for RVecDeque<T> {
+    type Item = T;
+
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter { deque: self }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a RVecDeque<T> {
+    type Item = &'a T;
+
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut RVecDeque<T> {
+    type Item = &'a mut T;
+
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+unsafe impl<T> Send for RVecDeque<T> where T: Send {}
+unsafe impl<T> Sync for RVecDeque<T> where T: Sync {}
+
+impl<T> Drop for RVecDeque<T> {
+    fn drop(&mut self) {
+        let vtable = self.vtable;
+        unsafe { vtable.destructor()(RMut::new(self).transmute_element_()) }
+    }
+}
+
+impl<'de, T> Deserialize<'de> for RVecDeque<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        <Vec<T>>::deserialize(deserializer).map(Self::from)
+    }
+}
+
+impl<T> Serialize for RVecDeque<T>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut seq = serializer.serialize_seq(Some(self.length))?;
+        for elem in self.iter() {
+            seq.serialize_element(elem)?;
+        }
+        seq.end()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////////////////////////////////////////////////
+
+/// Dummy type used to create a statically allocated constant that can only be
+/// accessed of the lifetime of T.
+struct VTableGetter<'a, T>(PhantomData<&'a T>);
+
+impl<'a, T: 'a> VTableGetter<'a, T> {
+    const DEFAULT_VTABLE: VecDequeVTable = VecDequeVTable {
+        type_id: new_utypeid::<RVecDeque<()>>,
+        destructor: destructor_deque::<T>,
+        grow_capacity_to: grow_capacity_to_deque::<T>,
+        shrink_to_fit: shrink_to_fit_deque::<T>,
+    };
+
+    staticref! {
+        const WM_DEFAULT: WithMetadata<VecDequeVTable> = WithMetadata::new(Self::DEFAULT_VTABLE);
+    }
+
+    // The VTABLE for this type in this executable/library
+    const LIB_VTABLE: VecDequeVTable_Ref = VecDequeVTable_Ref(Self::WM_DEFAULT.as_prefix());
+
+    staticref! {
+        const WM_FOR_TESTING: WithMetadata<VecDequeVTable> =
+            WithMetadata::new(
+                VecDequeVTable {
+                    type_id: new_utypeid::<RVecDeque<i32>>,
+                    ..Self::DEFAULT_VTABLE
+                }
+            )
+    }
+
+    // Used to test functions that change behavior based on the vtable being used
+    const LIB_VTABLE_FOR_TESTING: VecDequeVTable_Ref =
+        VecDequeVTable_Ref(Self::WM_FOR_TESTING.as_prefix());
+}
+
+#[repr(C)]
+#[derive(StableAbi)]
+#[sabi(kind(Prefix))]
+#[sabi(missing_field(panic))]
+struct VecDequeVTable {
+    type_id: extern "C" fn() -> UTypeId,
+    destructor: unsafe extern "C" fn(RMut<'_, ()>),
+    grow_capacity_to: unsafe extern "C" fn(RMut<'_, ()>, usize, Exactness),
+    #[sabi(last_prefix_field)]
+    shrink_to_fit: unsafe extern "C" fn(RMut<'_, ()>),
+}
+
+/// Moves the elements of `this` into a new buffer with (at least) `capacity` capacity,
+/// deallocating the old buffer.
+///
+/// # Safety
+///
+/// This must only be called from the functions stored in the vtable,
+/// since the buffer must have been allocated by this library/executable.
+unsafe fn reallocate<T>(this: &mut RVecDeque<T>, capacity: usize) {
+    let ((a_start, a_len), (b_start, b_len)) = this.slice_ranges();
+    let mut new_buffer = ManuallyDrop::new(Vec::<T>::with_capacity(capacity));
+    let old_buffer = this.buffer.as_ptr();
+    let new_ptr = new_buffer.as_mut_ptr();
+    unsafe {
+        ptr::copy_nonoverlapping(old_buffer.add(a_start), new_ptr, a_len);
+        ptr::copy_nonoverlapping(old_buffer.add(b_start), new_ptr.add(a_len), b_len);
+        drop(Vec::from_raw_parts(old_buffer, 0, this.capacity));
+        this.buffer = NonNull::new_unchecked(new_ptr);
+    }
+    this.head = 0;
+    this.capacity = new_buffer.capacity();
+}
+
+unsafe extern "C" fn destructor_deque<T>(this: RMut<'_, ()>) {
+    extern_fn_panic_handling! {no_early_return; unsafe {
+        let this = this.transmute_into_mut::<RVecDeque<T>>();
+        let (a, b) = this.raw_slices();
+        ptr::drop_in_place(a);
+        ptr::drop_in_place(b);
+        drop(Vec::from_raw_parts(this.buffer.as_ptr(), 0, this.capacity));
+    }}
+}
+
+unsafe extern "C" fn grow_capacity_to_deque<T>(
+    this: RMut<'_, ()>,
+    to: usize,
+    exactness: Exactness,
+) {
+    extern_fn_panic_handling! {no_early_return; unsafe {
+        let this = this.transmute_into_mut::<RVecDeque<T>>();
+        let to = match exactness {
+            Exactness::Above => to.max(this.capacity.saturating_mul(2)).max(4),
+            Exactness::Exact => to,
+        };
+        reallocate(this, to);
+    }}
+}
+
+unsafe extern "C" fn shrink_to_fit_deque<T>(this: RMut<'_, ()>) {
+    extern_fn_panic_handling! {no_early_return; unsafe {
+        let this = this.transmute_into_mut::<RVecDeque<T>>();
+        if this.length < this.capacity {
+            let len = this.length;
+            reallocate(this, len);
+        }
+    }}
+}
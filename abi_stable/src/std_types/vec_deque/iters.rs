@@ -0,0 +1,318 @@
+use super::*;
+
+use std::iter::FusedIterator;
+
+/// An iterator over references to the elements of an `RVecDeque<T>`,
+/// from front to back.
+///
+/// This is returned by `RVecDeque::iter`.
+pub struct Iter<'a, T> {
+    front: slice::Iter<'a, T>,
+    back: slice::Iter<'a, T>,
+}
+
+impl<'a, T> Iter<'a, T> {
+    pub(super) fn new(front: &'a [T], back: &'a [T]) -> Self {
+        Self {
+            front: front.iter(),
+            back: back.iter(),
+        }
+    }
+}
+
+impl<'a, T> Clone for Iter<'a, T> {
+    fn clone(&self) -> Self {
+        Self {
+            front: self.front.clone(),
+            back: self.back.clone(),
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.front.next().or_else(|| self.back.next())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<&'a T> {
+        self.back.next_back().or_else(|| self.front.next_back())
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {
+    fn len(&self) -> usize {
+        self.front.len() + self.back.len()
+    }
+}
+
+impl<'a, T> FusedIterator for Iter<'a, T> {}
+
+impl<'a, T> Debug for Iter<'a, T>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// An iterator over mutable references to the elements of an `RVecDeque<T>`,
+/// from front to back.
+///
+/// This is returned by `RVecDeque::iter_mut`.
+pub struct IterMut<'a, T> {
+    front: slice::IterMut<'a, T>,
+    back: slice::IterMut<'a, T>,
+}
+
+impl<'a, T> IterMut<'a, T> {
+    pub(super) fn new(front: &'a mut [T], back: &'a mut [T]) -> Self {
+        Self {
+            front: front.iter_mut(),
+            back: back.iter_mut(),
+        }
+    }
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<&'a mut T> {
+        match self.front.next() {
+            Some(x) => Some(x),
+            None => self.back.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    fn next_back(&mut self) -> Option<&'a mut T> {
+        match self.back.next_back() {
+            Some(x) => Some(x),
+            None => self.front.next_back(),
+        }
+    }
+}
+
+impl<'a, T> ExactSizeIterator for IterMut<'a, T> {
+    fn len(&self) -> usize {
+        self.front.len() + self.back.len()
+    }
+}
+
+impl<'a, T> FusedIterator for IterMut<'a, T> {}
+
+impl<'a, T> Debug for IterMut<'a, T>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.front.as_slice())
+            .entries(self.back.as_slice())
+            .finish()
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// An iterator which moves the elements out of an `RVecDeque<T>`,
+/// from front to back.
+///
+/// This is returned by `<RVecDeque<T> as IntoIterator>::into_iter`.
+pub struct IntoIter<T> {
+    pub(super) deque: RVecDeque<T>,
+}
+
+impl<T> IntoIter<T> {
+    /// Returns a slice pair over the remaining elements of the iterator.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use abi_stable::std_types::{RVecDeque, Tuple2};
+    ///
+    /// let queue = (0..4).collect::<RVecDeque<u32>>();
+    ///
+    /// let mut iter = queue.into_iter();
+    /// assert_eq!(iter.next(), Some(0));
+    ///
+    /// let Tuple2(front, back) = iter.as_slices();
+    /// assert_eq!(front.as_slice(), &[1, 2, 3]);
+    /// assert!(back.is_empty());
+    ///
+    /// ```
+    pub fn as_slices(&self) -> Tuple2<RSlice<'_, T>, RSlice<'_, T>> {
+        self.deque.as_slices()
+    }
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.deque.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.deque.len();
+        (len, Some(len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<T> {
+        self.deque.pop_back()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> FusedIterator for IntoIter<T> {}
+
+impl<T> Debug for IntoIter<T>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.deque.iter()).finish()
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// An iterator that removes a range of elements from an `RVecDeque<T>`,
+/// yielding them by value.
+///
+/// The remaining elements are dropped when this iterator is dropped.
+///
+/// This is returned by `RVecDeque::drain`.
+pub struct Drain<'a, T> {
+    buffer: *mut T,
+    // The length of the deque,set to the start of the drained range
+    // until this iterator is dropped.
+    deque_len: &'a mut usize,
+    front: usize,
+    back: usize,
+    // The end of the drained range.
+    end: usize,
+    // The length of the deque before draining.
+    orig_len: usize,
+    _marker: PhantomData<&'a mut [T]>,
+}
+
+impl<'a, T> Drain<'a, T> {
+    /// # Safety
+    ///
+    /// `buffer` must contain `*deque_len` contiguous initialized elements
+    /// starting from its first position, and `start <= end <= *deque_len` must hold.
+    pub(super) unsafe fn new(
+        buffer: *mut T,
+        deque_len: &'a mut usize,
+        start: usize,
+        end: usize,
+    ) -> Self {
+        let orig_len = *deque_len;
+        *deque_len = start;
+        Self {
+            buffer,
+            deque_len,
+            front: start,
+            back: end,
+            end,
+            orig_len,
+            _marker: PhantomData,
+        }
+    }
+
+    fn remaining(&self) -> *mut [T] {
+        unsafe {
+            ptr::slice_from_raw_parts_mut(self.buffer.add(self.front), self.back - self.front)
+        }
+    }
+}
+
+impl<'a, T> Iterator for Drain<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.front == self.back {
+            return None;
+        }
+        let value = unsafe { ptr::read(self.buffer.add(self.front)) };
+        self.front += 1;
+        Some(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Drain<'a, T> {
+    fn next_back(&mut self) -> Option<T> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        unsafe { Some(ptr::read(self.buffer.add(self.back))) }
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Drain<'a, T> {}
+
+impl<'a, T> FusedIterator for Drain<'a, T> {}
+
+impl<'a, T> Debug for Drain<'a, T>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let remaining = unsafe { &*self.remaining() };
+        f.debug_list().entries(remaining).finish()
+    }
+}
+
+unsafe impl<'a, T: Send> Send for Drain<'a, T> {}
+unsafe impl<'a, T: Sync> Sync for Drain<'a, T> {}
+
+impl<'a, T> Drop for Drain<'a, T> {
+    fn drop(&mut self) {
+        let remaining = self.remaining();
+        // Makes sure that the tail is moved back even if a destructor panics.
+        self.front = self.back;
+        struct MoveTail<'b, 'a, T>(&'b mut Drain<'a, T>);
+        impl<'b, 'a, T> Drop for MoveTail<'b, 'a, T> {
+            fn drop(&mut self) {
+                let this = &mut *self.0;
+                let start = *this.deque_len;
+                let tail_len = this.orig_len - this.end;
+                unsafe {
+                    ptr::copy(this.buffer.add(this.end), this.buffer.add(start), tail_len);
+                }
+                *this.deque_len = start + tail_len;
+            }
+        }
+        let _guard = MoveTail(self);
+        unsafe {
+            ptr::drop_in_place(remaining);
+        }
+    }
+}
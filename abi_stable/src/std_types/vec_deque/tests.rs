@@ -0,0 +1,251 @@
+use super::*;
+
+use std::{cell::Cell, rc::Rc};
+
+fn _covariant_rvecdeque<'a: 'b, 'b, T>(foo: RVecDeque<&'a T>) -> RVecDeque<&'b T> {
+    foo
+}
+
+fn to_vec<T: Clone>(queue: &RVecDeque<T>) -> Vec<T> {
+    queue.iter().cloned().collect()
+}
+
+/// Creates an `RVecDeque<u32>` whose elements wrap around the end of the buffer.
+fn wrapped_deque() -> RVecDeque<u32> {
+    let mut queue = RVecDeque::with_capacity(8);
+    let cap = queue.capacity();
+    for x in 0..cap as u32 - 2 {
+        queue.push_back(x);
+    }
+    for _ in 0..cap - 4 {
+        queue.pop_front();
+    }
+    for x in 100..104 {
+        queue.push_back(x);
+    }
+    let Tuple2(front, back) = queue.as_slices();
+    assert!(!front.is_empty());
+    assert!(!back.is_empty());
+    queue
+}
+
+#[test]
+fn push_pop_both_ends() {
+    let mut queue = RVecDeque::new();
+    let mut std = VecDeque::new();
+
+    for x in 0..100u32 {
+        if x % 3 == 0 {
+            queue.push_front(x);
+            std.push_front(x);
+        } else {
+            queue.push_back(x);
+            std.push_back(x);
+        }
+        if x % 7 == 0 {
+            assert_eq!(queue.pop_front(), std.pop_front());
+        }
+        if x % 11 == 0 {
+            assert_eq!(queue.pop_back(), std.pop_back());
+        }
+        assert_eq!(queue.len(), std.len());
+        assert_eq!(queue.front(), std.front());
+        assert_eq!(queue.back(), std.back());
+    }
+
+    assert_eq!(to_vec(&queue), std.iter().copied().collect::<Vec<_>>());
+    assert_eq!(
+        queue.iter().rev().copied().collect::<Vec<_>>(),
+        std.iter().rev().copied().collect::<Vec<_>>(),
+    );
+
+    for i in 0..queue.len() {
+        assert_eq!(queue[i], std[i]);
+    }
+}
+
+#[test]
+fn wrapped_slices() {
+    let mut queue = wrapped_deque();
+    let expected = to_vec(&queue);
+
+    let Tuple2(front, back) = queue.as_slices();
+    let joined = front.iter().chain(back.iter()).copied().collect::<Vec<_>>();
+    assert_eq!(joined, expected);
+
+    assert_eq!(queue.make_contiguous(), &expected[..]);
+    let Tuple2(front, back) = queue.as_slices();
+    assert_eq!(front.as_slice(), &expected[..]);
+    assert!(back.is_empty());
+}
+
+#[test]
+fn growth_preserves_order() {
+    let mut queue = wrapped_deque();
+    let mut expected = to_vec(&queue);
+
+    queue.reserve_exact(100);
+    assert!(queue.capacity() >= expected.len() + 100);
+    assert_eq!(to_vec(&queue), expected);
+
+    queue.push_front(7);
+    expected.insert(0, 7);
+    queue.shrink_to_fit();
+    assert_eq!(queue.capacity(), expected.len());
+    assert_eq!(to_vec(&queue), expected);
+}
+
+#[test]
+fn truncate_and_clear() {
+    let mut queue = wrapped_deque();
+    let expected = to_vec(&queue);
+
+    queue.truncate(expected.len() - 1);
+    assert_eq!(to_vec(&queue), &expected[..expected.len() - 1]);
+
+    queue.truncate(1);
+    assert_eq!(to_vec(&queue), &expected[..1]);
+
+    queue.clear();
+    assert!(queue.is_empty());
+    assert_eq!(queue.front(), None);
+}
+
+#[test]
+fn drain() {
+    let expected = to_vec(&wrapped_deque());
+    let len = expected.len();
+
+    for start in 0..=len {
+        for end in start..=len {
+            let mut queue = wrapped_deque();
+            let drained = queue.drain(start..end).collect::<Vec<_>>();
+            assert_eq!(drained, &expected[start..end]);
+
+            let mut remaining = expected.clone();
+            remaining.drain(start..end);
+            assert_eq!(to_vec(&queue), remaining);
+        }
+    }
+
+    {
+        let mut queue = wrapped_deque();
+        let mut drain = queue.drain(1..4);
+        assert_eq!(drain.next_back(), Some(expected[3]));
+        drop(drain);
+        assert_eq!(queue.len(), len - 3);
+        assert_eq!(queue[1], expected[4]);
+    }
+}
+
+#[test]
+#[should_panic]
+fn drain_out_of_bounds() {
+    let mut queue = wrapped_deque();
+    let len = queue.len();
+    let _ = queue.drain(..len + 1);
+}
+
+#[test]
+fn drops_elements() {
+    let counter = Rc::new(Cell::new(0));
+
+    struct OnDrop(Rc<Cell<u32>>);
+
+    impl Drop for OnDrop {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    let new_deque = || {
+        let mut queue = RVecDeque::with_capacity(4);
+        for _ in 0..3 {
+            queue.push_back(OnDrop(counter.clone()));
+        }
+        queue.pop_front();
+        queue.push_back(OnDrop(counter.clone()));
+        queue.push_back(OnDrop(counter.clone()));
+        queue
+    };
+
+    drop(new_deque());
+    assert_eq!(counter.get(), 5);
+
+    counter.set(0);
+    drop(new_deque().drain(1..2));
+    assert_eq!(counter.get(), 5);
+
+    counter.set(0);
+    let mut iter = new_deque().into_iter();
+    iter.next();
+    drop(iter);
+    assert_eq!(counter.get(), 5);
+}
+
+#[test]
+fn zero_sized() {
+    let mut queue = RVecDeque::new();
+    for _ in 0..10 {
+        queue.push_front(());
+        queue.push_back(());
+    }
+    assert_eq!(queue.len(), 20);
+    assert_eq!(queue.drain(5..10).count(), 5);
+    assert_eq!(queue.into_iter().count(), 15);
+}
+
+#[test]
+fn into_vec_deque() {
+    let expected = to_vec(&wrapped_deque());
+
+    let queue = wrapped_deque();
+    assert_eq!(queue.into_vec_deque(), expected);
+
+    let queue = wrapped_deque().set_vtable_for_testing();
+    assert_eq!(queue.into_vec_deque(), expected);
+
+    let queue = wrapped_deque().set_vtable_for_testing();
+    assert_eq!(RVec::from(queue).as_slice(), &expected[..]);
+}
+
+#[test]
+fn conversions_and_cmp() {
+    let std = (0..10).collect::<VecDeque<u32>>();
+    let queue = RVecDeque::from(std.clone());
+
+    assert_eq!(VecDeque::from(queue.clone()), std);
+    assert_eq!(
+        RVecDeque::from(RVec::from(vec![0, 1, 2])),
+        RVecDeque::from(vec![0, 1, 2])
+    );
+    assert_eq!(wrapped_deque(), wrapped_deque().into_vec_deque().into());
+
+    let mut bigger = queue.clone();
+    bigger.push_back(0);
+    assert!(queue < bigger);
+    assert_ne!(queue, bigger);
+
+    assert_eq!(format!("{:?}", queue), format!("{:?}", std));
+}
+
+#[test]
+fn grow_with_other_vtable() {
+    let mut queue = wrapped_deque().set_vtable_for_testing();
+    let mut expected = to_vec(&queue);
+    for x in 0..20 {
+        queue.push_front(x);
+        expected.insert(0, x);
+    }
+    assert_eq!(to_vec(&queue), expected);
+}
+
+#[test]
+fn serde() {
+    let queue = wrapped_deque();
+    let serialized = serde_json::to_string(&queue).unwrap();
+    assert_eq!(serialized, serde_json::to_string(&to_vec(&queue)).unwrap());
+
+    let deserialized = serde_json::from_str::<RVecDeque<u32>>(&serialized).unwrap();
+    assert_eq!(deserialized, queue);
+}
@@ -3,6 +3,14 @@
 use std::{
     error::Error as ErrorTrait,
     fmt::{self, Debug, Display},
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    task::{Context, Poll, Wake, Waker},
+    thread::{self, Thread},
 };
 
 #[allow(unused_imports)]
@@ -131,3 +139,56 @@ impl Display for Stringy {
 }
 
 impl ErrorTrait for Stringy {}
+
+//////////////////////////////////////////////////////////////////
+
+/// A waker which counts how many times it was woken up.
+pub struct WakeCounter {
+    count: AtomicUsize,
+}
+
+impl WakeCounter {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self {
+            count: AtomicUsize::new(0),
+        })
+    }
+
+    pub fn count(&self) -> usize {
+        self.count.load(Ordering::SeqCst)
+    }
+
+    pub fn waker(self: &Arc<Self>) -> Waker {
+        Waker::from(self.clone())
+    }
+}
+
+impl Wake for WakeCounter {
+    fn wake(self: Arc<Self>) {
+        self.count.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Polls `fut` to completion, parking the current thread while it's pending.
+pub fn block_on<F>(fut: F) -> F::Output
+where
+    F: Future,
+{
+    let mut fut = Box::pin(fut);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        match Pin::new(&mut fut).poll(&mut cx) {
+            Poll::Ready(x) => return x,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
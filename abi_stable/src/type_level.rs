@@ -86,6 +86,9 @@ pub mod trait_marker {
     /// Represents the [`std::marker::Unpin`] trait.
     pub struct Unpin;
 
+    /// Represents the `futures_core::Stream` trait.
+    pub struct Stream;
+
     #[doc(hidden)]
     #[allow(non_camel_case_types)]
     pub struct define_this_in_the_impl_InterfaceType_macro;
@@ -215,6 +215,7 @@ usable_traits! {
     io_buf_read=(IoBufRead,"::std::io::BufRead",false,UB::DYN_TRAIT),
     error=(Error,"::std::error::Error",false,UB::ROBJECT_AND_DYN_TRAIT),
     unpin=(Unpin,"::std::marker::Unpin",false,UB::ROBJECT_AND_DYN_TRAIT),
+    stream=(Stream,"::futures_core::Stream",false,UB::DYN_TRAIT),
 }
 
 pub(crate) fn private_associated_type() -> syn::Ident {
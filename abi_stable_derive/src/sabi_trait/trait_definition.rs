@@ -799,6 +799,12 @@ where
                                     "Serialize is not currently supported."
                                 ));
                             }
+                            WhichTrait::Stream => {
+                                errors.push_err(spanned_err!(
+                                    trait_bound.path,
+                                    "Stream is not currently supported."
+                                ));
+                            }
                             WhichTrait::Eq | WhichTrait::PartialOrd => {
                                 set_impld(&mut trait_struct.partial_eq, span);
                             }
@@ -59,6 +59,7 @@ mod kw {
     syn::custom_keyword! {Error}
     syn::custom_keyword! {extra_checks}
     syn::custom_keyword! {Hash}
+    syn::custom_keyword! {heap_fallback}
     syn::custom_keyword! {ident}
     syn::custom_keyword! {interface}
     syn::custom_keyword! {impl_InterfaceType}
@@ -1076,6 +1077,8 @@ fn parse_non_exhaustive_list<'a>(
         } else if input.check_parse(kw::size)? {
             input.parse::<Token!(=)>()?;
             this.size = Some(parse_expr_or_type(input, arenas)?);
+        } else if input.check_parse(kw::heap_fallback)? {
+            this.heap_fallback = true;
         } else if input.check_parse(kw::assert_nonexhaustive)? {
             if input.peek(syn::token::Paren) {
                 input
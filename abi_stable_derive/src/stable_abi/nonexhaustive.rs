@@ -37,6 +37,7 @@ pub(crate) struct UncheckedNonExhaustive<'a> {
     pub(crate) size: Option<ExprOrType<'a>>,
     pub(crate) enum_interface: Option<EnumInterface<'a>>,
     pub(crate) assert_nonexh: Vec<&'a syn::Type>,
+    pub(crate) heap_fallback: bool,
 }
 
 /// The configuration for code generation related to nonexhaustive enums.
@@ -51,6 +52,9 @@ pub(crate) struct NonExhaustive<'a> {
     pub(crate) alignment: ExprOrType<'a>,
     /// The size of `#enum_storage`
     pub(crate) size: ExprOrType<'a>,
+    /// Whether the enum is stored in the heap when it doesn't fit in `#enum_storage`,
+    /// using `HeapFallback<#enum_storage>` as the storage of `NonExhaustive<>`.
+    pub(crate) heap_fallback: bool,
     /// The InterfaceType-implementing marker struct this will generate,
     /// if this is:
     ///     - `Some(EnumInterface::New{..})`:it will use a new struct as the InterfaceType.
@@ -260,6 +264,7 @@ impl<'a> NonExhaustive<'a> {
             enum_storage: parse_ident(&format!("{}_Storage", name), None),
             alignment,
             size,
+            heap_fallback: unchecked.heap_fallback,
             enum_interface: unchecked.enum_interface,
             default_interface,
             new_interface,
@@ -268,6 +273,16 @@ impl<'a> NonExhaustive<'a> {
             ne_variants,
         })
     }
+
+    /// The storage type parameter of `NonExhaustive<>`.
+    pub(crate) fn storage_type(&self) -> TokenStream2 {
+        let enum_storage = self.enum_storage;
+        if self.heap_fallback {
+            quote!(::abi_stable::pmr::HeapFallback<#enum_storage>)
+        } else {
+            enum_storage.into_token_stream()
+        }
+    }
 }
 
 #[derive(Copy, Clone)]
@@ -388,6 +403,7 @@ pub(crate) fn tokenize_nonexhaustive_items<'a>(
         }
 
         let default_interface = &this.default_interface;
+        let storage_type = this.storage_type();
 
         quote!(
             #[doc=#storage_docs]
@@ -404,7 +420,7 @@ pub(crate) fn tokenize_nonexhaustive_items<'a>(
             #vis type #nonexhaustive_alias<#type_generics_decl>=
                 ::abi_stable::pmr::NonExhaustive<
                     #name<#type_generics_use>,
-                    #enum_storage,
+                    #storage_type,
                     #default_interface,
                 >;
 
@@ -460,6 +476,12 @@ pub(crate) fn tokenize_nonexhaustive_items<'a>(
         }
 
         if this.ne_variants.iter().any(|x| x.constructor.is_some()) {
+            let constructor_fn = if this.heap_fallback {
+                quote!(new_or_boxed)
+            } else {
+                quote!(new)
+            };
+
             let constructors = this
                 .ne_variants
                 .iter()
@@ -495,7 +517,7 @@ pub(crate) fn tokenize_nonexhaustive_items<'a>(
                                     let x=#name::#variant_ident{
                                         #( #field_names_c:#field_names_b, )*
                                     };
-                                    #nonexhaustive_alias::new(x)
+                                    #nonexhaustive_alias::#constructor_fn(x)
                                 }
                             }
                         }
@@ -518,7 +540,7 @@ pub(crate) fn tokenize_nonexhaustive_items<'a>(
                                     let x=#name::#variant_ident{
                                         #ptr_field_ident:x,
                                     };
-                                    #nonexhaustive_alias::new(x)
+                                    #nonexhaustive_alias::#constructor_fn(x)
                                 }
                             }
                         }
@@ -532,7 +554,7 @@ pub(crate) fn tokenize_nonexhaustive_items<'a>(
                 Some(BoundsTrait { ident, .. }) => quote!(#ident),
                 None => quote!(
                     ::abi_stable::pmr::NonExhaustiveMarkerVTable<
-                        #enum_storage,
+                        #storage_type,
                         #default_interface,
                     >
                 ),
@@ -626,6 +648,7 @@ pub(crate) fn tokenize_enum_info<'a>(
         let generics_use = GenParamsIn::new(ds.generics, InWhat::ImplHeader);
 
         let default_interface = &this.default_interface;
+        let storage_type = this.storage_type();
 
         let (impl_generics, ty_generics, where_clause) = ds.generics.split_for_impl();
 
@@ -649,7 +672,7 @@ pub(crate) fn tokenize_enum_info<'a>(
             {
                 type Discriminant=#discriminant_type;
 
-                type DefaultStorage=#enum_storage;
+                type DefaultStorage=#storage_type;
 
                 type DefaultInterface=#default_interface;
 
@@ -691,7 +714,11 @@ pub(crate) fn tokenize_enum_info<'a>(
 
         let self_type: syn::Type;
         let self_type_buf: Vec<&syn::Type>;
-        let assert_nonexh = if this.assert_nonexh.is_empty() && ds.generics.params.is_empty() {
+        // Enums with a heap fallback don't need to fit in their storage.
+        let assert_nonexh = if this.assert_nonexh.is_empty()
+            && ds.generics.params.is_empty()
+            && !this.heap_fallback
+        {
             let name = ds.name;
             self_type = syn::parse_quote!(#name);
             self_type_buf = vec![&self_type];
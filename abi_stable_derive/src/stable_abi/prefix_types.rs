@@ -577,9 +577,18 @@ accessible through [`{prefix_name}`](struct@{prefix_name}), with `.0.prefix()`.
                         val_var.to_token_stream()
                     };
 
+                    // Only the accessors that return an `Option` are `const`,
+                    // because the other ones can call arbitrary functions
+                    // when the field is missing.
+                    let const_ = if is_optional {
+                        Some(quote_spanned!(field_span=> const ))
+                    } else {
+                        None
+                    };
+
                     conditional_accessors.push(quote_spanned! {field_span=>
                         #[allow(clippy::missing_const_for_fn)]
-                        #vis fn #getter_name(&self)->#return_ty
+                        #vis #const_ fn #getter_name(&self)->#return_ty
                         #field_where_clause #( #accessor_bounds+ )*
                         {
                             let acc_bits=self.0.field_accessibility().bits();